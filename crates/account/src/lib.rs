@@ -24,6 +24,8 @@ mod storage;
 mod storage_key;
 mod types;
 
+use std::collections::BTreeMap;
+
 pub use auth::AccountPublicKeysMap;
 use borsh::{BorshDeserialize, BorshSerialize};
 pub use namada_core::address::Address;
@@ -53,6 +55,9 @@ pub struct Account {
     pub public_keys_map: AccountPublicKeysMap,
     /// The account signature threshold
     pub threshold: u8,
+    /// The signature thresholds overriding the default one for specific
+    /// classes of actions
+    pub action_thresholds: BTreeMap<ActionClass, u8>,
     /// The address corresponding to the account owner
     pub address: Address,
 }
@@ -74,6 +79,14 @@ impl Account {
         self.public_keys_map.get_index_from_public_key(public_key)
     }
 
+    /// Get the signature threshold required for the given class of actions
+    pub fn threshold_for(&self, class: ActionClass) -> u8 {
+        self.action_thresholds
+            .get(&class)
+            .copied()
+            .unwrap_or(self.threshold)
+    }
+
     /// Get all public keys of the account
     pub fn get_all_public_keys(&self) -> Vec<common::PublicKey> {
         self.public_keys_map.pk_to_idx.keys().cloned().collect()
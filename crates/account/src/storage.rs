@@ -1,5 +1,7 @@
 //! Cryptographic signature keys storage API
 
+use std::collections::BTreeMap;
+
 use namada_core::storage;
 use namada_storage::{Result, ResultExt, StorageRead, StorageWrite};
 
@@ -75,8 +77,118 @@ where
     S: StorageRead,
 {
     let public_keys = public_keys(storage, owner)?;
+    let weights = weights(storage, owner)?;
+
+    Ok(AccountPublicKeysMap::from_iter(public_keys).with_weights(weights))
+}
+
+/// Get the weights of the public keys associated with an account, by key
+/// index. Keys without an entry have a weight of 1.
+pub fn weights<S>(storage: &S, owner: &Address) -> Result<BTreeMap<u8, u8>>
+where
+    S: StorageRead,
+{
+    weights_handle(owner).iter(storage)?.collect()
+}
+
+/// Set the weight of the public key at specific index
+pub fn set_public_key_weight<S>(
+    storage: &mut S,
+    owner: &Address,
+    index: u8,
+    weight: u8,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    weights_handle(owner).insert(storage, index, weight)?;
+    Ok(())
+}
+
+/// Clear the public key weights account subtorage space
+pub fn clear_weights<S>(storage: &mut S, owner: &Address) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let indices = weights(storage, owner)?.into_keys().collect::<Vec<_>>();
+    for index in indices {
+        weights_handle(owner).remove(storage, &index)?;
+    }
+    Ok(())
+}
+
+/// Get the signature thresholds associated with specific classes of actions
+/// of an account
+pub fn action_thresholds<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<BTreeMap<ActionClass, u8>>
+where
+    S: StorageRead,
+{
+    let action_thresholds_key = action_thresholds_key(owner);
+    Ok(storage.read(&action_thresholds_key)?.unwrap_or_default())
+}
+
+/// Write the signature thresholds associated with specific classes of actions
+/// of an account
+pub fn write_action_thresholds<S>(
+    storage: &mut S,
+    owner: &Address,
+    action_thresholds: &BTreeMap<ActionClass, u8>,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let action_thresholds_key = action_thresholds_key(owner);
+    if action_thresholds.is_empty() {
+        storage.delete(&action_thresholds_key)
+    } else {
+        storage.write(&action_thresholds_key, action_thresholds)
+    }
+}
 
-    Ok(AccountPublicKeysMap::from_iter(public_keys))
+/// Get the threshold required to authorize the given class of actions of an
+/// account, falling back to the account's default threshold
+pub fn threshold_for_action<S>(
+    storage: &S,
+    owner: &Address,
+    class: ActionClass,
+) -> Result<Option<u8>>
+where
+    S: StorageRead,
+{
+    match action_thresholds(storage, owner)?.get(&class) {
+        Some(threshold) => Ok(Some(*threshold)),
+        None => threshold(storage, owner),
+    }
+}
+
+/// Check that an account's public keys, weights and thresholds can still
+/// authorize its actions. If any weights are set, there must be exactly one per
+/// public key and none of them can be zero. The total weight of the keys must
+/// reach the default threshold and the threshold of every class of actions.
+pub fn is_auth_valid<S>(storage: &S, owner: &Address) -> Result<bool>
+where
+    S: StorageRead,
+{
+    let pks_map = public_keys_index_map(storage, owner)?;
+    let weights = weights(storage, owner)?;
+    if !weights.is_empty() {
+        let one_per_key = weights.len() == pks_map.idx_to_pk.len()
+            && weights
+                .keys()
+                .all(|idx| pks_map.idx_to_pk.contains_key(idx));
+        if !one_per_key || weights.values().any(|weight| *weight == 0) {
+            return Ok(false);
+        }
+    }
+    let total_weight = pks_map.total_weight_of_all();
+    let default_threshold = threshold(storage, owner)?.unwrap_or(1);
+    let action_thresholds = action_thresholds(storage, owner)?;
+    Ok(std::iter::once(default_threshold)
+        .chain(action_thresholds.into_values())
+        .all(|threshold| total_weight >= u32::from(threshold)))
 }
 
 /// Check if a user account exists in storage
@@ -11,6 +11,8 @@ struct Keys {
     public_keys: &'static str,
     threshold: &'static str,
     protocol_public_keys: &'static str,
+    weights: &'static str,
+    action_thresholds: &'static str,
 }
 
 /// Obtain a storage key for user's public key.
@@ -68,6 +70,72 @@ pub fn threshold_key(owner: &Address) -> storage::Key {
     }
 }
 
+/// Obtain a storage key prefix for the weights of user's public keys.
+pub fn weights_key_prefix(owner: &Address) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.weights.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the weights of user's public keys, by key index
+pub fn weights_handle(owner: &Address) -> LazyMap<u8, u8> {
+    LazyMap::open(weights_key_prefix(owner))
+}
+
+/// Check if the given storage key is a public key weight. If it is, returns
+/// the owner.
+pub fn is_weights_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::StringSeg(index),
+        ] if prefix.as_str() == Keys::VALUES.weights
+            && data.as_str() == lazy_map::DATA_SUBKEY
+            && <u8 as storage::KeySeg>::parse(index.clone()).is_ok() =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the per action class thresholds of a user
+pub fn action_thresholds_key(owner: &Address) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.action_thresholds.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is an action thresholds key. If it is,
+/// returns the owner.
+pub fn is_action_thresholds_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.action_thresholds =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key holds any of a user's authorization data:
+/// public keys, weights or thresholds. If it is, returns the owner.
+pub fn is_account_auth_key(key: &storage::Key) -> Option<&Address> {
+    is_pks_key(key)
+        .or_else(|| is_threshold_key(key))
+        .or_else(|| is_weights_key(key))
+        .or_else(|| is_action_thresholds_key(key))
+}
+
 /// Obtain a storage key for user's protocol public key.
 pub fn protocol_pk_key(owner: &Address) -> storage::Key {
     storage::Key {
@@ -1,3 +1,7 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
 use namada_core::address::Address;
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::hash::Hash;
@@ -7,6 +11,87 @@ use namada_macros::BorshDeserializer;
 use namada_migrations::*;
 use serde::{Deserialize, Serialize};
 
+/// A class of actions that can be given a signature threshold different from
+/// the account's default one
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum ActionClass {
+    /// Debiting tokens from the account, including shielding and unshielding
+    Transfer,
+    /// Updating the account's validity predicate, public keys, weights or
+    /// thresholds
+    UpdateAccount,
+    /// Bonding, unbonding, withdrawing and redelegating
+    Staking,
+    /// Claiming PoS rewards
+    ClaimRewards,
+    /// Validator management actions
+    Validator,
+    /// Submitting and voting on governance proposals and PGF steward actions
+    Governance,
+}
+
+impl ActionClass {
+    /// All the action classes
+    pub const ALL: [ActionClass; 6] = [
+        ActionClass::Transfer,
+        ActionClass::UpdateAccount,
+        ActionClass::Staking,
+        ActionClass::ClaimRewards,
+        ActionClass::Validator,
+        ActionClass::Governance,
+    ];
+
+    /// The name of the action class as used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionClass::Transfer => "transfer",
+            ActionClass::UpdateAccount => "update-account",
+            ActionClass::Staking => "staking",
+            ActionClass::ClaimRewards => "claim-rewards",
+            ActionClass::Validator => "validator",
+            ActionClass::Governance => "governance",
+        }
+    }
+}
+
+impl Display for ActionClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ActionClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|class| class.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown action class {s}, expected one of: {}",
+                    Self::ALL.map(|class| class.as_str()).join(", ")
+                )
+            })
+    }
+}
+
 /// A tx data type to initialize a new established account
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(
@@ -55,6 +140,13 @@ pub struct UpdateAccount {
     pub public_keys: Vec<common::PublicKey>,
     /// The account signature threshold
     pub threshold: Option<u8>,
+    /// The weights of the account's public keys, by index. If empty, the
+    /// weights are left untouched, unless the public keys are replaced in
+    /// which case every key is reset to a weight of 1.
+    pub weights: Vec<u8>,
+    /// Signature thresholds for specific classes of actions, overriding the
+    /// account's default threshold. A threshold of 0 removes the override.
+    pub action_thresholds: BTreeMap<ActionClass, u8>,
 }
 
 #[allow(clippy::cast_possible_truncation)]
@@ -64,11 +156,23 @@ pub mod tests {
     use namada_core::address::testing::arb_non_internal_address;
     use namada_core::hash::testing::arb_hash;
     use namada_core::key::testing::arb_common_pk;
-    use proptest::prelude::Just;
-    use proptest::{collection, option, prop_compose};
+    use proptest::prelude::{Just, Strategy};
+    use proptest::{collection, option, prop_compose, prop_oneof};
 
     use super::*;
 
+    /// Generate an arbitrary action class
+    pub fn arb_action_class() -> impl Strategy<Value = ActionClass> {
+        prop_oneof![
+            Just(ActionClass::Transfer),
+            Just(ActionClass::UpdateAccount),
+            Just(ActionClass::Staking),
+            Just(ActionClass::ClaimRewards),
+            Just(ActionClass::Validator),
+            Just(ActionClass::Governance),
+        ]
+    }
+
     prop_compose! {
         /// Generate an account initialization
         pub fn arb_init_account()(
@@ -94,6 +198,12 @@ pub mod tests {
             addr in arb_non_internal_address(),
             vp_code_hash in option::of(arb_hash()),
             threshold in option::of(0..=public_keys.len() as u8),
+            weights in collection::vec(1..=u8::MAX, public_keys.len()),
+            action_thresholds in collection::btree_map(
+                arb_action_class(),
+                0..=public_keys.len() as u8,
+                0..3,
+            ),
             public_keys in Just(public_keys),
         ) -> UpdateAccount {
             UpdateAccount {
@@ -101,6 +211,8 @@ pub mod tests {
                 vp_code_hash,
                 public_keys,
                 threshold,
+                weights,
+                action_thresholds,
             }
         }
     }
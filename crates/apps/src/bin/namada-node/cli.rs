@@ -34,7 +34,8 @@ pub fn main() -> Result<()> {
                     chain_ctx.config.ledger,
                     wasm_dir,
                     scheduled_migration,
-                );
+                )
+                .wrap_err("Failed to run the Namada node")?;
             }
             cmds::Ledger::RunUntil(cmds::LedgerRunUntil(args)) => {
                 let mut chain_ctx = ctx.take_chain_or_exit();
@@ -42,7 +43,8 @@ pub fn main() -> Result<()> {
                 sleep_until(args.time);
                 chain_ctx.config.ledger.shell.action_at_height =
                     Some(args.action_at_height);
-                node::run(chain_ctx.config.ledger, wasm_dir, None);
+                node::run(chain_ctx.config.ledger, wasm_dir, None)
+                    .wrap_err("Failed to run the Namada node")?;
             }
             cmds::Ledger::Reset(_) => {
                 let chain_ctx = ctx.take_chain_or_exit();
@@ -51,7 +53,8 @@ pub fn main() -> Result<()> {
             }
             cmds::Ledger::DumpDb(cmds::LedgerDumpDb(args)) => {
                 let chain_ctx = ctx.take_chain_or_exit();
-                node::dump_db(chain_ctx.config.ledger, args);
+                node::dump_db(chain_ctx.config.ledger, args)
+                    .wrap_err("Failed to dump the DB")?;
             }
             cmds::Ledger::RollBack(_) => {
                 let chain_ctx = ctx.take_chain_or_exit();
@@ -72,7 +75,8 @@ pub fn main() -> Result<()> {
                     chain_ctx.config.ledger.clone(),
                     args.updates,
                     args.dry_run,
-                );
+                )
+                .wrap_err("Failed to update the DB")?;
                 if !args.dry_run {
                     let wasm_dir = chain_ctx.wasm_dir();
                     chain_ctx.config.ledger.shell.action_at_height =
@@ -107,7 +111,8 @@ pub fn main() -> Result<()> {
                     &args.key,
                     &args.hash,
                     &args.cf,
-                );
+                )
+                .wrap_err("Failed to query the DB")?;
             }
         },
         cli::NamadaNode::Config(cmd, mut ctx) => match cmd {
@@ -166,6 +171,18 @@ pub fn main() -> Result<()> {
                 std::fs::write(config_path, updated_config).unwrap();
             }
         },
+        cli::NamadaNode::Snapshot(cmd, ctx) => match cmd {
+            cmds::Snapshot::Export(cmds::SnapshotExport(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                node::export_snapshot(chain_ctx.config.ledger, args)
+                    .wrap_err("Failed to export the snapshot")?;
+            }
+            cmds::Snapshot::Import(cmds::SnapshotImport(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                node::import_snapshot(chain_ctx.config.ledger, args)
+                    .wrap_err("Failed to import the snapshot")?;
+            }
+        },
         cli::NamadaNode::Utils(sub, global_args) => match sub {
             cmds::NodeUtils::TestGenesis(TestGenesis(args)) => {
                 node::utils::test_genesis(args, global_args)
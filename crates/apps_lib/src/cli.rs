@@ -139,6 +139,7 @@ pub mod cmds {
     pub enum NamadaNode {
         Ledger(Ledger),
         Config(Config),
+        Snapshot(Snapshot),
         Utils(NodeUtils),
     }
 
@@ -146,14 +147,16 @@ pub mod cmds {
         fn add_sub(app: App) -> App {
             app.subcommand(Ledger::def())
                 .subcommand(Config::def())
+                .subcommand(Snapshot::def())
                 .subcommand(NodeUtils::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let ledger = SubCmd::parse(matches).map(Self::Ledger);
             let config = SubCmd::parse(matches).map(Self::Config);
+            let snapshot = SubCmd::parse(matches).map(Self::Snapshot);
             let utils = SubCmd::parse(matches).map(Self::Utils);
-            ledger.or(config).or(utils)
+            ledger.or(config).or(snapshot).or(utils)
         }
     }
     impl SubCmd for NamadaNode {
@@ -285,6 +288,7 @@ pub mod cmds {
                 .subcommand(QueryProposalResult::def().display_order(5))
                 .subcommand(QueryProtocolParameters::def().display_order(5))
                 .subcommand(QueryPgf::def().display_order(5))
+                .subcommand(QueryUpgradePlan::def().display_order(5))
                 .subcommand(QueryValidatorState::def().display_order(5))
                 .subcommand(QueryCommissionRate::def().display_order(5))
                 .subcommand(QueryRewards::def().display_order(5))
@@ -381,6 +385,8 @@ pub mod cmds {
             let query_protocol_parameters =
                 Self::parse_with_ctx(matches, QueryProtocolParameters);
             let query_pgf = Self::parse_with_ctx(matches, QueryPgf);
+            let query_upgrade_plan =
+                Self::parse_with_ctx(matches, QueryUpgradePlan);
             let query_validator_state =
                 Self::parse_with_ctx(matches, QueryValidatorState);
             let query_commission =
@@ -440,6 +446,7 @@ pub mod cmds {
                 .or(query_proposal_result)
                 .or(query_protocol_parameters)
                 .or(query_pgf)
+                .or(query_upgrade_plan)
                 .or(query_validator_state)
                 .or(query_commission)
                 .or(query_metadata)
@@ -539,6 +546,7 @@ pub mod cmds {
         QueryProposalResult(QueryProposalResult),
         QueryProtocolParameters(QueryProtocolParameters),
         QueryPgf(QueryPgf),
+        QueryUpgradePlan(QueryUpgradePlan),
         QueryValidatorState(QueryValidatorState),
         QueryRewards(QueryRewards),
         SignTx(SignTx),
@@ -1082,6 +1090,85 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub enum Snapshot {
+        Export(SnapshotExport),
+        Import(SnapshotImport),
+    }
+
+    impl SubCmd for Snapshot {
+        const CMD: &'static str = "snapshot";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let export = SubCmd::parse(matches).map(Self::Export);
+                let import = SubCmd::parse(matches).map(Self::Import);
+                export.or(import)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .about(wrap!(
+                    "Offline state sync snapshot sub-commands. The ledger \
+                     must not be running while these are used."
+                ))
+                .subcommand(SnapshotExport::def())
+                .subcommand(SnapshotImport::def())
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SnapshotExport(pub args::SnapshotExport);
+
+    impl SubCmd for SnapshotExport {
+        const CMD: &'static str = "export";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::SnapshotExport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(wrap!(
+                    "Export a state sync snapshot of the DB into a directory. \
+                     The snapshot is either one that was stored by the node \
+                     at the given height or a new one of the last committed \
+                     block."
+                ))
+                .add_args::<args::SnapshotExport>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SnapshotImport(pub args::SnapshotImport);
+
+    impl SubCmd for SnapshotImport {
+        const CMD: &'static str = "import";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::SnapshotImport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(wrap!(
+                    "Verify an exported state sync snapshot and restore the \
+                     DB from it, without fetching it from the network. This \
+                     replaces the current DB and resets CometBFT's state, \
+                     which is bootstrapped at the height of the snapshot on \
+                     the next start of the node."
+                ))
+                .add_args::<args::SnapshotImport>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Config {
         Gen(ConfigGen),
@@ -1282,6 +1369,31 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryUpgradePlan(pub args::QueryUpgradePlan<args::CliTypes>);
+
+    impl SubCmd for QueryUpgradePlan {
+        const CMD: &'static str = "query-upgrade-plan";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryUpgradePlan(args::QueryUpgradePlan::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(wrap!(
+                    "Query the upgrade plan scheduled by governance and its \
+                     status."
+                ))
+                .add_args::<args::QueryUpgradePlan<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryPgf(pub args::QueryPgf<args::CliTypes>);
 
@@ -2792,6 +2904,31 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryBridgePoolFeeQuote(
+        pub args::BridgePoolFeeQuote<args::CliTypes>,
+    );
+
+    impl SubCmd for QueryBridgePoolFeeQuote {
+        const CMD: &'static str = "query-fee-quote";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::BridgePoolFeeQuote::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(wrap!(
+                    "Get the minimum gas fee of transfers added to the \
+                     Ethereum Bridge pool, paid in the given token."
+                ))
+                .arg_required_else_help(true)
+                .add_args::<args::BridgePoolFeeQuote<args::CliTypes>>()
+        }
+    }
+
     /// Used as sub-commands (`SubCmd` instance) in `namadar` binary.
     #[derive(Clone, Debug)]
     pub enum EthBridgePool {
@@ -2828,6 +2965,8 @@ pub mod cmds {
         /// Check the confirmation status of `TransferToEthereum`
         /// events.
         QueryRelays(QueryRelayProgress),
+        /// Query the minimum gas fee of transfers in the pool.
+        QueryFeeQuote(QueryBridgePoolFeeQuote),
     }
 
     impl Cmd for EthBridgePool {
@@ -2838,6 +2977,7 @@ pub mod cmds {
                 .subcommand(QueryEthBridgePool::def().display_order(1))
                 .subcommand(QuerySignedBridgePool::def().display_order(1))
                 .subcommand(QueryRelayProgress::def().display_order(1))
+                .subcommand(QueryBridgePoolFeeQuote::def().display_order(1))
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
@@ -2851,6 +2991,8 @@ pub mod cmds {
             let query_pool = Self::parse_without_ctx(matches, QueryPool);
             let query_signed = Self::parse_without_ctx(matches, QuerySigned);
             let query_relays = Self::parse_without_ctx(matches, QueryRelays);
+            let query_fee_quote =
+                Self::parse_without_ctx(matches, QueryFeeQuote);
 
             construct_proof
                 .or(recommend)
@@ -2858,6 +3000,7 @@ pub mod cmds {
                 .or(query_pool)
                 .or(query_signed)
                 .or(query_relays)
+                .or(query_fee_quote)
         }
     }
 
@@ -2900,6 +3043,7 @@ pub mod cmds {
                 .subcommand(QueryEthBridgePool::def().display_order(1))
                 .subcommand(QuerySignedBridgePool::def().display_order(1))
                 .subcommand(QueryRelayProgress::def().display_order(1))
+                .subcommand(QueryBridgePoolFeeQuote::def().display_order(1))
         }
     }
 
@@ -3274,6 +3418,7 @@ pub mod args {
 
     use data_encoding::HEXUPPER;
     use namada_core::masp::{MaspEpoch, PaymentAddress};
+    use namada_sdk::account::ActionClass;
     use namada_sdk::address::{Address, EstablishedAddress};
     pub use namada_sdk::args::*;
     use namada_sdk::chain::{ChainId, ChainIdPrefix};
@@ -3312,6 +3457,8 @@ pub mod args {
     use crate::tendermint_rpc::Url;
     use crate::wrap;
 
+    pub const ACTION_THRESHOLDS: ArgMulti<String, GlobStar> =
+        arg_multi("action-thresholds");
     pub const ADDRESS: Arg<WalletAddress> = arg("address");
     pub const ADD_PERSISTENT_PEERS: ArgFlag = flag("add-persistent-peers");
     pub const ALIAS_OPT: ArgOpt<String> = ALIAS.opt();
@@ -3330,6 +3477,7 @@ pub mod args {
             Err(_) => config::get_default_namada_folder(),
         }),
     );
+    pub const BINARY_PATH_OPT: ArgOpt<PathBuf> = arg_opt("binary");
     pub const BIRTHDAY: ArgOpt<BlockHeight> = arg_opt("birthday");
     pub const BLOCK_HEIGHT: Arg<BlockHeight> = arg("block-height");
     pub const BLOCK_HEIGHT_OPT: ArgOpt<BlockHeight> = arg_opt("height");
@@ -3352,6 +3500,7 @@ pub mod args {
         "pool-gas-token",
         DefaultFn(|| "".parse().unwrap()),
     );
+    pub const BRIDGE_POOL_RAW_GAS_TOKEN: Arg<Address> = arg("pool-gas-token");
     pub const BRIDGE_POOL_TARGET: Arg<EthAddress> = arg("target");
     pub const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
     pub const CHAIN_ID: Arg<ChainId> = arg("chain-id");
@@ -3411,6 +3560,7 @@ pub mod args {
     pub const FEE_AMOUNT_OPT: ArgOpt<token::DenominatedAmount> =
         arg_opt("gas-price");
     pub const FEE_PAYER_OPT: ArgOpt<WalletPublicKey> = arg_opt("gas-payer");
+    pub const FEE_SPONSOR_OPT: ArgOpt<WalletAddress> = arg_opt("gas-sponsor");
     pub const FILE_PATH: Arg<String> = arg("file");
     pub const FORCE: ArgFlag = flag("force");
     pub const GAS_LIMIT: ArgDefault<GasLimit> = arg_default(
@@ -3502,6 +3652,7 @@ pub mod args {
         arg_multi("secret-keys");
     pub const PROPOSAL_PGF_STEWARD: ArgFlag = flag("pgf-stewards");
     pub const PROPOSAL_PGF_FUNDING: ArgFlag = flag("pgf-funding");
+    pub const PROPOSAL_PARAMETER_CHANGE: ArgFlag = flag("parameter-change");
     pub const PROTOCOL_KEY: ArgOpt<WalletPublicKey> = arg_opt("protocol-key");
     pub const PRE_GENESIS_PATH: ArgOpt<PathBuf> = arg_opt("pre-genesis-path");
     pub const PUBLIC_KEY: Arg<WalletPublicKey> = arg("public-key");
@@ -3529,7 +3680,20 @@ pub mod args {
     pub const REFUND_TARGET: ArgOpt<WalletTransferTarget> =
         arg_opt("refund-target");
     pub const RELAYER: Arg<Address> = arg("relayer");
+    pub const RELAYER_OPT: ArgOpt<Address> = RELAYER.opt();
+    pub const RELAYER_ACK_FEE: ArgOpt<token::DenominatedAmount> =
+        arg_opt("relayer-ack-fee");
+    pub const RELAYER_FEE: ArgOpt<token::DenominatedAmount> =
+        arg_opt("relayer-fee");
+    pub const RELAYER_FEE_TOKEN: ArgDefaultFromCtx<WalletAddrOrNativeToken> =
+        arg_default_from_ctx(
+            "relayer-fee-token",
+            DefaultFn(|| "".parse().unwrap()),
+        );
+    pub const RELAYER_TIMEOUT_FEE: ArgOpt<token::DenominatedAmount> =
+        arg_opt("relayer-timeout-fee");
     pub const RETRIES: ArgOpt<u64> = arg_opt("retries");
+    pub const ROOT_HASH: Arg<Hash> = arg("root-hash");
     pub const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
     pub const SHELL: Arg<Shell> = arg("shell");
@@ -3595,6 +3759,7 @@ pub mod args {
     pub const WASM_CHECKSUMS_PATH: Arg<PathBuf> = arg("wasm-checksums-path");
     pub const WASM_DIR: ArgOpt<PathBuf> = arg_opt("wasm-dir");
     pub const WEBSITE_OPT: ArgOpt<String> = arg_opt("website");
+    pub const WEIGHTS: ArgMulti<u8, GlobStar> = arg_multi("weights");
     pub const WITH_INDEXER: ArgOpt<String> = arg_opt("with-indexer");
     pub const TX_PATH: Arg<PathBuf> = arg("tx-path");
     pub const TX_PATH_OPT: ArgOpt<PathBuf> = TX_PATH.opt();
@@ -3816,6 +3981,67 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct SnapshotExport {
+        pub block_height: Option<BlockHeight>,
+        pub out_dir: Option<PathBuf>,
+    }
+
+    impl Args for SnapshotExport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let block_height = BLOCK_HEIGHT_OPT.parse(matches);
+            let out_dir = OUTPUT_FOLDER_PATH.parse(matches);
+
+            Self {
+                block_height,
+                out_dir,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(BLOCK_HEIGHT_OPT.def().help(wrap!(
+                "The block height of the snapshot to export. Defaults to the \
+                 latest committed block."
+            )))
+            .arg(OUTPUT_FOLDER_PATH.def().help(wrap!(
+                "Path of the directory to export the snapshot to. Defaults to \
+                 \"snapshot-{block_height}\" (or \"snapshot-latest\") in the \
+                 current working directory."
+            )))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SnapshotImport {
+        pub snapshot_dir: PathBuf,
+        pub root_hash: Hash,
+    }
+
+    impl Args for SnapshotImport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let snapshot_dir = PATH.parse(matches);
+            let root_hash = ROOT_HASH.parse(matches);
+
+            Self {
+                snapshot_dir,
+                root_hash,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                PATH.def().help(wrap!(
+                    "Path of the directory of an exported snapshot."
+                )),
+            )
+            .arg(ROOT_HASH.def().help(wrap!(
+                "The trusted root hash of the snapshot, as printed by the \
+                 export. The snapshot is rejected when its root hash doesn't \
+                 match."
+            )))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct LedgerUpdateDb {
         pub updates: PathBuf,
@@ -4129,6 +4355,7 @@ pub mod args {
                         })
                         .collect()
                 },
+                relayer: self.relayer,
             })
         }
     }
@@ -4139,11 +4366,13 @@ pub mod args {
             let max_gas = MAX_ETH_GAS.parse(matches);
             let gas = ETH_GAS.parse(matches);
             let conversion_table = CONVERSION_TABLE.parse(matches);
+            let relayer = RELAYER_OPT.parse(matches);
             Self {
                 query,
                 max_gas,
                 gas,
                 conversion_table,
+                relayer,
             }
         }
 
@@ -4164,6 +4393,12 @@ pub mod args {
                     "Path to a JSON object containing a mapping between token \
                      aliases (or addresses) and their conversion rates in gwei"
                 )))
+                .arg(RELAYER_OPT.def().help(wrap!(
+                    "The rewards address for relaying the recommended \
+                     transfers. If set, the batch maximising the net profit \
+                     under the gas budget is recommended, along with the \
+                     proof to relay it to Ethereum."
+                )))
         }
     }
 
@@ -4177,6 +4412,35 @@ pub mod args {
         }
     }
 
+    impl CliToSdkCtxless<BridgePoolFeeQuote<SdkTypes>>
+        for BridgePoolFeeQuote<CliTypes>
+    {
+        fn to_sdk_ctxless(self) -> BridgePoolFeeQuote<SdkTypes> {
+            BridgePoolFeeQuote::<SdkTypes> {
+                ledger_address: self.ledger_address,
+                token: self.token,
+            }
+        }
+    }
+
+    impl Args for BridgePoolFeeQuote<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let ledger_address = LEDGER_ADDRESS.parse(matches);
+            let token = BRIDGE_POOL_RAW_GAS_TOKEN.parse(matches);
+            Self {
+                ledger_address,
+                token,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(LEDGER_ADDRESS.def().help(LEDGER_ADDRESS_ABOUT))
+                .arg(BRIDGE_POOL_RAW_GAS_TOKEN.def().help(wrap!(
+                    "The token for paying the Bridge pool gas fees."
+                )))
+        }
+    }
+
     impl Args for BridgePoolProof<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
             let ledger_address = LEDGER_ADDRESS.parse(matches);
@@ -4932,6 +5196,12 @@ pub mod args {
                 refund_target: chain_ctx.get_opt(&self.refund_target),
                 ibc_shielding_data: self.ibc_shielding_data,
                 ibc_memo: self.ibc_memo,
+                relayer_fee: self.relayer_fee.map(|fee| IbcRelayerFee {
+                    token: chain_ctx.get(&fee.token).into(),
+                    recv_fee: fee.recv_fee,
+                    ack_fee: fee.ack_fee,
+                    timeout_fee: fee.timeout_fee,
+                }),
                 gas_spending_keys,
                 disposable_signing_key: self.disposable_signing_key,
                 tx_code_path: self.tx_code_path.to_path_buf(),
@@ -4959,6 +5229,20 @@ pub mod args {
                         .expect("Failed to decode IBC shielding data")
                 });
             let ibc_memo = IBC_MEMO.parse(matches);
+            let relayer_fee = RELAYER_FEE.parse(matches).map(|recv_fee| {
+                let zero =
+                    || token::DenominatedAmount::native(token::Amount::zero());
+                IbcRelayerFee {
+                    token: RELAYER_FEE_TOKEN.parse(matches),
+                    recv_fee: InputAmount::Unvalidated(recv_fee),
+                    ack_fee: InputAmount::Unvalidated(
+                        RELAYER_ACK_FEE.parse(matches).unwrap_or_else(zero),
+                    ),
+                    timeout_fee: InputAmount::Unvalidated(
+                        RELAYER_TIMEOUT_FEE.parse(matches).unwrap_or_else(zero),
+                    ),
+                }
+            });
             let mut gas_spending_keys = vec![];
             if let Some(key) = GAS_SPENDING_KEY.parse(matches) {
                 gas_spending_keys.push(key);
@@ -4978,6 +5262,7 @@ pub mod args {
                 refund_target,
                 ibc_shielding_data,
                 ibc_memo,
+                relayer_fee,
                 gas_spending_keys,
                 disposable_signing_key,
                 tx_code_path,
@@ -5025,6 +5310,38 @@ pub mod args {
                         .def()
                         .help(wrap!("The memo for IBC transfer packet.")),
                 )
+                .arg(RELAYER_FEE.def().help(wrap!(
+                    "The ICS-29 fee paid to the relayer relaying the packet \
+                     to the destination chain. The channel should be \
+                     fee-enabled."
+                )))
+                .arg(
+                    RELAYER_ACK_FEE
+                        .def()
+                        .help(wrap!(
+                            "The ICS-29 fee paid to the relayer relaying the \
+                             acknowledgement. Defaults to zero."
+                        ))
+                        .requires(RELAYER_FEE.name),
+                )
+                .arg(
+                    RELAYER_TIMEOUT_FEE
+                        .def()
+                        .help(wrap!(
+                            "The ICS-29 fee paid to the relayer relaying the \
+                             timeout. Defaults to zero."
+                        ))
+                        .requires(RELAYER_FEE.name),
+                )
+                .arg(
+                    RELAYER_FEE_TOKEN
+                        .def()
+                        .help(wrap!(
+                            "The token for paying the relayer fees. Defaults \
+                             to the native token."
+                        ))
+                        .requires(RELAYER_FEE.name),
+                )
                 .arg(GAS_SPENDING_KEY.def().help(wrap!(
                     "The optional spending key that will be used in addition \
                      to the source for gas payment (if this is a shielded \
@@ -5435,6 +5752,8 @@ pub mod args {
                     .map(|pk| chain_ctx.get(pk))
                     .collect(),
                 threshold: self.threshold,
+                weights: self.weights,
+                action_thresholds: self.action_thresholds,
             })
         }
     }
@@ -5447,6 +5766,32 @@ pub mod args {
             let tx_code_path = PathBuf::from(TX_UPDATE_ACCOUNT_WASM);
             let public_keys = PUBLIC_KEYS.parse(matches);
             let threshold = THRESHOLD.parse(matches);
+            let weights = WEIGHTS.parse(matches);
+            let action_thresholds = ACTION_THRESHOLDS
+                .parse(matches)
+                .into_iter()
+                .map(|raw| {
+                    raw.split_once('=')
+                        .ok_or_else(|| {
+                            "expected <class>=<threshold>".to_string()
+                        })
+                        .and_then(|(class, threshold)| {
+                            let class = ActionClass::from_str(class)?;
+                            let threshold = threshold
+                                .parse::<u8>()
+                                .map_err(|err| err.to_string())?;
+                            Ok((class, threshold))
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!(
+                                "Failed to parse the {} argument. Raw value: \
+                                 {}, error: {}",
+                                ACTION_THRESHOLDS.name, raw, err
+                            );
+                            safe_exit(1)
+                        })
+                })
+                .collect();
             Self {
                 tx,
                 vp_code_path,
@@ -5454,6 +5799,8 @@ pub mod args {
                 tx_code_path,
                 public_keys,
                 threshold,
+                weights,
+                action_thresholds,
             }
         }
 
@@ -5475,6 +5822,19 @@ pub mod args {
                      authorization. Must be less then the maximum number of \
                      public keys provided."
                 )))
+                .arg(WEIGHTS.def().help(wrap!(
+                    "The weights of the public keys, in the same order as the \
+                     keys. When weights are set, the threshold is the minimum \
+                     total weight of the signing keys. Every key has a weight \
+                     of 1 by default."
+                )))
+                .arg(ACTION_THRESHOLDS.def().help(wrap!(
+                    "Thresholds overriding the default one for specific \
+                     classes of actions, formatted as <class>=<threshold>. \
+                     The classes are transfer, update-account, staking, \
+                     claim-rewards, validator and governance. A threshold of \
+                     0 removes the override."
+                )))
         }
     }
 
@@ -5758,6 +6118,7 @@ pub mod args {
                 proposal_data,
                 is_pgf_stewards: self.is_pgf_stewards,
                 is_pgf_funding: self.is_pgf_funding,
+                is_parameter_change: self.is_parameter_change,
                 tx_code_path: self.tx_code_path,
             })
         }
@@ -5769,6 +6130,7 @@ pub mod args {
             let proposal_data = DATA_PATH.parse(matches);
             let is_pgf_stewards = PROPOSAL_PGF_STEWARD.parse(matches);
             let is_pgf_funding = PROPOSAL_PGF_FUNDING.parse(matches);
+            let is_parameter_change = PROPOSAL_PARAMETER_CHANGE.parse(matches);
             let tx_code_path = PathBuf::from(TX_INIT_PROPOSAL);
 
             Self {
@@ -5777,6 +6139,7 @@ pub mod args {
                 tx_code_path,
                 is_pgf_stewards,
                 is_pgf_funding,
+                is_parameter_change,
             }
         }
 
@@ -5792,7 +6155,10 @@ pub mod args {
                             "Flag if the proposal is of type pgf-stewards. \
                              Used to elect/remove stewards."
                         ))
-                        .conflicts_with(PROPOSAL_PGF_FUNDING.name),
+                        .conflicts_with_all([
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                        ]),
                 )
                 .arg(
                     PROPOSAL_PGF_FUNDING
@@ -5801,7 +6167,23 @@ pub mod args {
                             "Flag if the proposal is of type pgf-funding. \
                              Used to control continuous/retro PGF fundings."
                         ))
-                        .conflicts_with(PROPOSAL_PGF_STEWARD.name),
+                        .conflicts_with_all([
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_PARAMETER_CHANGE
+                        .def()
+                        .help(wrap!(
+                            "Flag if the proposal is of type \
+                             parameter-change. Used to change protocol, PoS, \
+                             governance, PGF and IBC parameters."
+                        ))
+                        .conflicts_with_all([
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                        ]),
                 )
         }
     }
@@ -6057,6 +6439,37 @@ pub mod args {
         }
     }
 
+    impl Args for QueryUpgradePlan<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let binary = BINARY_PATH_OPT.parse(matches);
+
+            Self { query, binary }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(BINARY_PATH_OPT.def().help(wrap!(
+                    "Path of a node binary to check against the hash of the \
+                     upgrade plan."
+                )))
+        }
+    }
+
+    impl CliToSdk<QueryUpgradePlan<SdkTypes>> for QueryUpgradePlan<CliTypes> {
+        type Error = std::convert::Infallible;
+
+        fn to_sdk(
+            self,
+            ctx: &mut Context,
+        ) -> Result<QueryUpgradePlan<SdkTypes>, Self::Error> {
+            Ok(QueryUpgradePlan::<SdkTypes> {
+                query: self.query.to_sdk(ctx)?,
+                binary: self.binary,
+            })
+        }
+    }
+
     impl CliToSdk<QueryPgf<SdkTypes>> for QueryPgf<CliTypes> {
         type Error = std::convert::Infallible;
 
@@ -7354,6 +7767,7 @@ pub mod args {
                     .chain_id
                     .or_else(|| Some(ctx.config.ledger.chain_id.clone())),
                 wrapper_fee_payer: self.wrapper_fee_payer.map(|x| ctx.get(&x)),
+                fee_sponsor: self.fee_sponsor.map(|x| ctx.get(&x)),
                 memo: self.memo,
                 use_device: self.use_device,
                 device_transport: self.device_transport,
@@ -7467,6 +7881,10 @@ pub mod args {
                  --disposable-signing-key, then this one will overwrite this \
                  argument."
             )))
+            .arg(FEE_SPONSOR_OPT.def().help(wrap!(
+                "The address of a sponsor paying the gas fees from its \
+                 pre-authorized budget instead of the gas payer."
+            )))
             .arg(
                 USE_DEVICE
                     .def()
@@ -7514,6 +7932,7 @@ pub mod args {
             let password = None;
             let memo = MEMO_OPT.parse(matches).map(String::into_bytes);
             let wrapper_fee_payer = FEE_PAYER_OPT.parse(matches);
+            let fee_sponsor = FEE_SPONSOR_OPT.parse(matches);
             let output_folder = OUTPUT_FOLDER_PATH.parse(matches);
             let use_device = USE_DEVICE.parse(matches);
             let no_expiration = NO_EXPIRATION.parse(matches);
@@ -7545,6 +7964,7 @@ pub mod args {
                 password,
                 chain_id,
                 wrapper_fee_payer,
+                fee_sponsor,
                 output_folder,
                 memo,
                 use_device,
@@ -8701,6 +9121,7 @@ pub fn namada_cli() -> (cmds::Namada, String) {
 pub enum NamadaNode {
     Ledger(cmds::Ledger, Context),
     Config(cmds::Config, Context),
+    Snapshot(cmds::Snapshot, Context),
     Utils(cmds::NodeUtils, args::Global),
 }
 
@@ -8719,6 +9140,10 @@ pub fn namada_node_cli() -> Result<NamadaNode> {
                     let context = Context::new::<CliIo>(global_args)?;
                     Ok(NamadaNode::Config(sub_cmd, context))
                 }
+                cmds::NamadaNode::Snapshot(sub_cmd) => {
+                    let context = Context::new::<CliIo>(global_args)?;
+                    Ok(NamadaNode::Snapshot(sub_cmd, context))
+                }
                 cmds::NamadaNode::Utils(sub_cmd) => {
                     Ok(NamadaNode::Utils(sub_cmd, global_args))
                 }
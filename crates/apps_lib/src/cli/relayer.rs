@@ -106,6 +106,21 @@ impl CliApi {
                     client.wait_until_node_is_synced(&io).await?;
                     bridge_pool::query_relay_progress(&client, &io).await?;
                 }
+                EthBridgePoolWithoutCtx::QueryFeeQuote(
+                    QueryBridgePoolFeeQuote(args),
+                ) => {
+                    let client = client.unwrap_or_else(|| {
+                        C::from_tendermint_address(&args.ledger_address)
+                    });
+                    client.wait_until_node_is_synced(&io).await?;
+                    let args = args.to_sdk_ctxless();
+                    bridge_pool::query_bridge_pool_fee_quote(
+                        &client,
+                        &io,
+                        &args.token,
+                    )
+                    .await?;
+                }
             },
             cli::NamadaRelayer::ValidatorSet(sub) => match sub {
                 ValidatorSet::BridgeValidatorSet(BridgeValidatorSet(args)) => {
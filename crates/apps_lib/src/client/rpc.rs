@@ -22,7 +22,7 @@ use namada_sdk::governance::pgf::parameters::PgfParameters;
 use namada_sdk::governance::pgf::storage::steward::StewardDetail;
 use namada_sdk::governance::storage::keys as governance_storage;
 use namada_sdk::governance::storage::proposal::{
-    StoragePgfFunding, StorageProposal,
+    StoragePgfFunding, StorageProposal, UpgradePlanStatus,
 };
 use namada_sdk::governance::utils::{ProposalVotes, VotePower};
 use namada_sdk::governance::ProposalVote;
@@ -551,15 +551,90 @@ pub async fn query_account(context: &impl Namada, args: args::QueryAccount) {
     if let Some(account) = account {
         display_line!(context.io(), "Address: {}", account.address);
         display_line!(context.io(), "Threshold: {}", account.threshold);
+        for (class, threshold) in &account.action_thresholds {
+            display_line!(context.io(), "{} threshold: {}", class, threshold);
+        }
         display_line!(context.io(), "Public keys:");
-        for (public_key, _) in account.public_keys_map.pk_to_idx {
-            display_line!(context.io(), "- {}", public_key);
+        for (public_key, index) in &account.public_keys_map.pk_to_idx {
+            let weight = account.public_keys_map.get_weight_from_index(*index);
+            if weight == 1 {
+                display_line!(context.io(), "- {}", public_key);
+            } else {
+                display_line!(
+                    context.io(),
+                    "- {} (weight {})",
+                    public_key,
+                    weight
+                );
+            }
         }
     } else {
         display_line!(context.io(), "No account exists for {}", args.owner);
     }
 }
 
+/// Query the upgrade plan scheduled by governance and check a node binary
+/// against it
+pub async fn query_upgrade_plan(
+    context: &impl Namada,
+    args: args::QueryUpgradePlan,
+) {
+    let plan = match rpc::query_upgrade_plan(context.client()).await {
+        Ok(plan) => plan,
+        Err(err) => {
+            edisplay_line!(
+                context.io(),
+                "Failed to query the upgrade plan: {err}"
+            );
+            cli::safe_exit(1)
+        }
+    };
+    let Some(UpgradePlanStatus { plan, status }) = plan else {
+        display_line!(context.io(), "No upgrade plan is scheduled.");
+        return;
+    };
+
+    display_line!(context.io(), "Upgrade plan: {}", plan.name);
+    display_line!(context.io(), "{:4}Height: {}", "", plan.height);
+    display_line!(context.io(), "{:4}Binary hash: {}", "", plan.binary_hash);
+    match &plan.migration_hash {
+        Some(hash) => {
+            display_line!(context.io(), "{:4}Migration hash: {}", "", hash)
+        }
+        None => display_line!(context.io(), "{:4}Migration: none", ""),
+    }
+    display_line!(context.io(), "{:4}Status: {}", "", status);
+
+    if let Some(path) = args.binary {
+        let hash = match std::fs::read(&path) {
+            Ok(bytes) => Hash::sha256(bytes),
+            Err(err) => {
+                edisplay_line!(
+                    context.io(),
+                    "Failed to read the binary at {}: {err}",
+                    path.to_string_lossy()
+                );
+                cli::safe_exit(1)
+            }
+        };
+        if hash == plan.binary_hash {
+            display_line!(
+                context.io(),
+                "The binary at {} matches the upgrade plan.",
+                path.to_string_lossy()
+            );
+        } else {
+            edisplay_line!(
+                context.io(),
+                "The hash {hash} of the binary at {} doesn't match the \
+                 upgrade plan.",
+                path.to_string_lossy()
+            );
+            cli::safe_exit(1)
+        }
+    }
+}
+
 pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
     let stewards = query_pgf_stewards(context.client()).await;
     let fundings = query_pgf_fundings(context.client()).await;
@@ -2269,5 +2344,6 @@ pub async fn compute_proposal_votes<C: Client + Sync>(
         validator_voting_power,
         delegators_vote,
         delegator_voting_power,
+        ..ProposalVotes::default()
     }
 }
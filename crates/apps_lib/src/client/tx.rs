@@ -8,7 +8,8 @@ use namada_sdk::address::{Address, ImplicitAddress};
 use namada_sdk::args::TxBecomeValidator;
 use namada_sdk::collections::HashSet;
 use namada_sdk::governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    DefaultProposal, ParameterChangeProposal, PgfFundingProposal,
+    PgfStewardProposal,
 };
 use namada_sdk::ibc::convert_masp_tx_to_ibc_memo;
 use namada_sdk::io::{display_line, edisplay_line, Io};
@@ -989,6 +990,39 @@ where
             tx::build_pgf_stewards_proposal(namada, &args, proposal).await?,
             proposal_author,
         )
+    } else if args.is_parameter_change {
+        let proposal =
+            ParameterChangeProposal::try_from(args.proposal_data.as_ref())
+                .map_err(|e| {
+                    error::TxSubmitError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+        let author_balance = namada_sdk::rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+            None,
+        )
+        .await
+        .unwrap();
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| {
+                error::TxSubmitError::InvalidProposal(e.to_string())
+            })?;
+        let proposal_author = proposal.proposal.author.clone();
+
+        (
+            tx::build_parameter_change_proposal(namada, &args, proposal)
+                .await?,
+            proposal_author,
+        )
     } else {
         let proposal = DefaultProposal::try_from(args.proposal_data.as_ref())
             .map_err(|e| {
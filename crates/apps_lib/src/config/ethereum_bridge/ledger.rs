@@ -1,4 +1,6 @@
 //! Runtime configuration for a validator node.
+use std::path::PathBuf;
+
 #[allow(unused_imports)]
 use namada_sdk::ethereum_events::EthereumEvent;
 use serde::{Deserialize, Serialize};
@@ -21,6 +23,17 @@ pub enum Mode {
     /// itself for submission of Borsh-serialized [`EthereumEvent`]
     /// instances. Mostly useful for testing purposes.
     SelfHostedEndpoint,
+    /// The oracle will replay the Ethereum event logs recorded at `source`,
+    /// instead of listening to an Ethereum JSON-RPC endpoint. The source is
+    /// a JSON or CBOR file, or a directory of block dumps. Useful for
+    /// reproducing bridge incidents and running bridge tests offline.
+    Replay {
+        /// Path to the recorded Ethereum event logs
+        source: PathBuf,
+        /// The number of confirmations the Ethereum chain is reported to
+        /// have past the last recorded block
+        confirmations: u64,
+    },
     /// Do not run any components of the Ethereum bridge.
     Off,
 }
@@ -337,6 +337,7 @@ impl Finalized {
             minimum_gas_price,
             max_tx_bytes,
             is_native_token_transferable,
+            enforce_fee_priority_ordering,
             ..
         } = self.parameters.parameters.clone();
 
@@ -386,6 +387,7 @@ impl Finalized {
                 })
                 .collect(),
             is_native_token_transferable,
+            enforce_fee_priority_ordering,
         }
     }
 
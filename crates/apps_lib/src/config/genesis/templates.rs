@@ -16,6 +16,7 @@ use namada_sdk::eth_bridge::storage::parameters::{
 use namada_sdk::parameters::ProposalBytes;
 use namada_sdk::token::{
     Amount, DenominatedAmount, Denomination, NATIVE_MAX_DECIMAL_PLACES,
+    VestingSchedule,
 };
 use namada_sdk::{ethereum_structs, token};
 use serde::{Deserialize, Serialize};
@@ -74,6 +75,9 @@ pub fn read_transactions(
 )]
 pub struct UndenominatedBalances {
     pub token: BTreeMap<Alias, RawTokenBalances>,
+    /// Vesting schedules that lock some of the genesis balances
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vesting: BTreeMap<Alias, VestingSchedules>,
 }
 
 impl UndenominatedBalances {
@@ -85,6 +89,7 @@ impl UndenominatedBalances {
     ) -> eyre::Result<DenominatedBalances> {
         let mut balances = DenominatedBalances {
             token: BTreeMap::new(),
+            vesting: BTreeMap::new(),
         };
         for (alias, bals) in self.token {
             let denom = tokens
@@ -107,6 +112,28 @@ impl UndenominatedBalances {
                 .token
                 .insert(alias, TokenBalances(denominated_bals));
         }
+        for (alias, schedules) in self.vesting {
+            let denom = tokens
+                .token
+                .get(&alias)
+                .ok_or_else(|| {
+                    eyre::eyre!(
+                        "A vesting schedule of token {} was found, but this \
+                         token was not found in the `tokens.toml` file",
+                        alias
+                    )
+                })?
+                .denom;
+            let mut denominated_schedules = BTreeMap::new();
+            for (addr, schedule) in schedules.0.into_iter() {
+                let denominated = schedule
+                    .try_map(|amount| amount.increase_precision(denom))?;
+                denominated_schedules.insert(addr, denominated);
+            }
+            balances
+                .vesting
+                .insert(alias, VestingSchedules(denominated_schedules));
+        }
         Ok(balances)
     }
 }
@@ -125,6 +152,9 @@ impl UndenominatedBalances {
 )]
 pub struct DenominatedBalances {
     pub token: BTreeMap<Alias, TokenBalances>,
+    /// Vesting schedules that lock some of the genesis balances
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vesting: BTreeMap<Alias, VestingSchedules>,
 }
 
 /// Genesis balances for a given token
@@ -155,6 +185,23 @@ pub struct RawTokenBalances(pub BTreeMap<Address, token::DenominatedAmount>);
 )]
 pub struct TokenBalances(pub BTreeMap<Address, token::DenominatedAmount>);
 
+/// Genesis vesting schedules for a given token
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSerialize,
+    PartialEq,
+    Eq,
+)]
+pub struct VestingSchedules(
+    pub BTreeMap<Address, VestingSchedule<token::DenominatedAmount>>,
+);
+
 /// Genesis validity predicates
 #[derive(
     Clone,
@@ -256,6 +303,10 @@ pub struct ChainParams<T: TemplateValidation> {
     pub native_token: Alias,
     /// Enable the native token transfer if it is true
     pub is_native_token_transferable: bool,
+    /// Reject the block proposals whose wrapper txs aren't ordered by
+    /// decreasing fee priority
+    #[serde(default)]
+    pub enforce_fee_priority_ordering: bool,
     /// Minimum number of blocks per epoch.
     // NB: u64 only works with values up to i64::MAX with toml-rs!
     pub min_num_of_blocks: u64,
@@ -305,6 +356,7 @@ impl ChainParams<Unvalidated> {
             max_tx_bytes,
             native_token,
             is_native_token_transferable,
+            enforce_fee_priority_ordering,
             min_num_of_blocks,
             max_proposal_bytes,
             vp_allowlist,
@@ -350,6 +402,7 @@ impl ChainParams<Unvalidated> {
             max_tx_bytes,
             native_token,
             is_native_token_transferable,
+            enforce_fee_priority_ordering,
             min_num_of_blocks,
             max_proposal_bytes,
             vp_allowlist,
@@ -976,6 +1029,33 @@ pub fn validate_balances(
             }
         }
     });
+
+    balances.vesting.iter().for_each(|(token, schedules)| {
+        for (owner, schedule) in &schedules.0 {
+            let schedule = schedule.clone().map(|amount| amount.amount());
+            if !schedule.is_valid() {
+                is_valid = false;
+                eprintln!(
+                    "The vesting schedule of {owner} for token {token} is \
+                     invalid."
+                );
+                continue;
+            }
+            // A vesting schedule cannot lock more than the genesis balance
+            let balance = balances
+                .token
+                .get(token)
+                .and_then(|balances| balances.get(owner))
+                .unwrap_or_default();
+            if !matches!(schedule.total(), Some(total) if total <= balance) {
+                is_valid = false;
+                eprintln!(
+                    "The vesting schedule of {owner} for token {token} locks \
+                     more than its genesis balance."
+                );
+            }
+        }
+    });
     is_valid
 }
 
@@ -1057,4 +1137,46 @@ mod tests {
         let example_balance = balances.token.get(&token_alias).unwrap();
         assert_eq!(balance, example_balance.0.get(&address).unwrap().amount());
     }
+
+    #[test]
+    fn test_read_vesting_balances() {
+        let test_dir = tempdir().unwrap();
+        let path = test_dir.path().join(BALANCES_FILE_NAME);
+        let sk = key::testing::keypair_1();
+        let pk = sk.ref_to();
+        let address: Address = (&pk).into();
+        let token_alias = Alias::from("Some_token".to_string());
+        let contents = format!(
+            r#"
+		[token.{token_alias}]
+		{address} = "100"
+
+		[vesting.{token_alias}.{address}]
+		kind = "linear"
+		amount = "80"
+		start = 0
+		end = 10
+	    "#
+        );
+        fs::write(&path, contents).unwrap();
+
+        let balances = read_balances(&path).unwrap();
+        let schedule = balances
+            .vesting
+            .get(&token_alias)
+            .unwrap()
+            .0
+            .get(&address)
+            .unwrap()
+            .clone()
+            .map(|amount| amount.amount());
+        assert_eq!(
+            schedule,
+            VestingSchedule::Linear {
+                amount: token::Amount::from(80),
+                start: 0.into(),
+                end: 10.into(),
+            }
+        );
+    }
 }
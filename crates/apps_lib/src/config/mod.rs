@@ -68,6 +68,31 @@ impl TendermintMode {
     }
 }
 
+/// The storage backend of the ledger's DB. A node can only use the backend
+/// that it's built with, which is selected with cargo features.
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// RocksDB, the default backend
+    #[default]
+    RocksDb,
+    /// redb, enabled with the `redb` feature
+    #[cfg(feature = "redb")]
+    Redb,
+}
+
+impl std::fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageBackend::RocksDb => write!(f, "rocksdb"),
+            #[cfg(feature = "redb")]
+            StorageBackend::Redb => write!(f, "redb"),
+        }
+    }
+}
+
 /// An action to be performed at a
 /// certain block height.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,7 +127,7 @@ pub struct Ledger {
 pub struct Shell {
     pub base_dir: PathBuf,
     // pub ledger_address: SocketAddr,
-    /// RocksDB block cache maximum size in bytes.
+    /// DB block cache maximum size in bytes.
     /// When not set, defaults to 1/3 of the available memory.
     pub block_cache_bytes: Option<u64>,
     /// VP WASM compilation cache maximum size in bytes.
@@ -127,6 +152,20 @@ pub struct Shell {
     pub blocks_between_snapshots: Option<NonZeroU64>,
     /// Number of snapshots to keep
     pub snapshots_to_keep: Option<NonZeroU64>,
+    /// When set, the node keeps the full history of the storage from the
+    /// next committed block on, such that past values of any key can be
+    /// queried. Archive mode cannot be turned off once enabled on a DB.
+    #[serde(default)]
+    pub archive_mode: bool,
+    /// The storage backend of the DB. It must match the backend that the node
+    /// is built with. Configs without it are using RocksDB.
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+    /// When set, the inner transactions of a block are executed one after
+    /// the other instead of being speculatively executed in parallel. The
+    /// results are the same in both cases.
+    #[serde(default)]
+    pub sequential_tx_execution: bool,
 }
 
 impl Ledger {
@@ -157,6 +196,12 @@ impl Ledger {
                 tendermint_mode: mode,
                 blocks_between_snapshots: None,
                 snapshots_to_keep: None,
+                archive_mode: false,
+                #[cfg(not(feature = "redb"))]
+                storage_backend: StorageBackend::RocksDb,
+                #[cfg(feature = "redb")]
+                storage_backend: StorageBackend::Redb,
+                sequential_tx_execution: false,
             },
             cometbft: tendermint_config,
             ethereum_bridge: ethereum_bridge::ledger::Config::default(),
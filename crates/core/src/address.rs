@@ -8,7 +8,7 @@ use std::hash::Hash;
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use data_encoding::HEXUPPER;
+use data_encoding::{HEXLOWER, HEXUPPER};
 use ibc::primitives::Signer;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
@@ -99,6 +99,94 @@ pub enum Address {
     Internal(InternalAddress),
 }
 
+/// Hash of a validator address that identifies its liquid staking share
+/// token.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[repr(transparent)]
+pub struct LiquidShareHash(pub [u8; HASH_LEN]);
+
+impl Display for LiquidShareHash {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+impl From<&Address> for LiquidShareHash {
+    fn from(validator: &Address) -> Self {
+        let hash = Sha256::digest(validator.encode().as_bytes());
+        let mut output = [0; HASH_LEN];
+        output.copy_from_slice(&hash[..HASH_LEN]);
+        Self(output)
+    }
+}
+
+/// Obtain the address of the liquid staking share token of the given
+/// validator.
+pub fn liquid_share_token(validator: &Address) -> Address {
+    Address::Internal(InternalAddress::LiquidShare(validator.into()))
+}
+
+/// Hash of an IBC connection ID and a controller port ID that identifies an
+/// ICS-27 interchain account hosted on this chain.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[repr(transparent)]
+pub struct InterchainAccountHash(pub [u8; HASH_LEN]);
+
+impl Display for InterchainAccountHash {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+/// Obtain the address of the interchain account controlled by the given
+/// controller port over the given host connection.
+pub fn interchain_account(
+    connection_id: impl AsRef<str>,
+    controller_port_id: impl AsRef<str>,
+) -> Address {
+    let hash = Sha256::digest(
+        format!("{}/{}", connection_id.as_ref(), controller_port_id.as_ref())
+            .as_bytes(),
+    );
+    let mut output = [0; HASH_LEN];
+    output.copy_from_slice(&hash[..HASH_LEN]);
+    Address::Internal(InternalAddress::InterchainAccount(
+        InterchainAccountHash(output),
+    ))
+}
+
 impl From<raw::Address<'_, raw::Validated>> for Address {
     fn from(raw_addr: raw::Address<'_, raw::Validated>) -> Self {
         match raw_addr.discriminant() {
@@ -147,6 +235,14 @@ impl From<raw::Address<'_, raw::Validated>> for Address {
             raw::Discriminant::ReplayProtection => {
                 Address::Internal(InternalAddress::ReplayProtection)
             }
+            raw::Discriminant::LiquidShare => Address::Internal(
+                InternalAddress::LiquidShare(LiquidShareHash(*raw_addr.data())),
+            ),
+            raw::Discriminant::InterchainAccount => {
+                Address::Internal(InternalAddress::InterchainAccount(
+                    InterchainAccountHash(*raw_addr.data()),
+                ))
+            }
         }
     }
 }
@@ -253,6 +349,22 @@ impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
                 .validate()
                 .expect("This raw address is valid")
             }
+            Address::Internal(InternalAddress::LiquidShare(
+                LiquidShareHash(hash),
+            )) => {
+                raw::Address::from_discriminant(raw::Discriminant::LiquidShare)
+                    .with_data_array_ref(hash)
+                    .validate()
+                    .expect("This raw address is valid")
+            }
+            Address::Internal(InternalAddress::InterchainAccount(
+                InterchainAccountHash(hash),
+            )) => raw::Address::from_discriminant(
+                raw::Discriminant::InterchainAccount,
+            )
+            .with_data_array_ref(hash)
+            .validate()
+            .expect("This raw address is valid"),
         }
     }
 }
@@ -594,6 +706,10 @@ pub enum InternalAddress {
     /// Address with temporary storage is used to pass data from txs to VPs
     /// which is never committed to DB
     TempStorage,
+    /// Liquid staking share token of a validator
+    LiquidShare(LiquidShareHash),
+    /// ICS-27 interchain account controlled by a counterparty chain
+    InterchainAccount(InterchainAccountHash),
 }
 
 impl Display for InternalAddress {
@@ -617,6 +733,10 @@ impl Display for InternalAddress {
                 Self::Masp => "MASP".to_string(),
                 Self::ReplayProtection => "ReplayProtection".to_string(),
                 Self::TempStorage => "TempStorage".to_string(),
+                Self::LiquidShare(hash) => format!("LiquidShare: {hash}"),
+                Self::InterchainAccount(hash) => {
+                    format!("InterchainAccount: {hash}")
+                }
             }
         )
     }
@@ -847,8 +967,12 @@ pub mod testing {
             InternalAddress::Masp => {}
             InternalAddress::Multitoken => {}
             InternalAddress::ReplayProtection => {}
-            InternalAddress::TempStorage => {} /* Add new addresses in the
-                                                * `prop_oneof` below. */
+            InternalAddress::TempStorage => {}
+            InternalAddress::LiquidShare(_) => {}
+            InternalAddress::InterchainAccount(_) => {} /* Add new addresses
+                                                         * in
+                                                         * the `prop_oneof`
+                                                         * below. */
         };
         prop_oneof![
             Just(InternalAddress::PoS),
@@ -866,9 +990,23 @@ pub mod testing {
             Just(InternalAddress::Masp),
             Just(InternalAddress::ReplayProtection),
             Just(InternalAddress::TempStorage),
+            arb_liquid_share(),
+            arb_interchain_account(),
         ]
     }
 
+    fn arb_interchain_account() -> impl Strategy<Value = InternalAddress> {
+        proptest::array::uniform20(proptest::num::u8::ANY).prop_map(|hash| {
+            InternalAddress::InterchainAccount(InterchainAccountHash(hash))
+        })
+    }
+
+    fn arb_liquid_share() -> impl Strategy<Value = InternalAddress> {
+        proptest::array::uniform20(proptest::num::u8::ANY).prop_map(|hash| {
+            InternalAddress::LiquidShare(LiquidShareHash(hash))
+        })
+    }
+
     fn arb_ibc_token() -> impl Strategy<Value = InternalAddress> {
         ("[a-zA-Z0-9_]{2,128}", any::<u64>()).prop_map(|(id, counter)| {
             let mut hasher = sha2::Sha256::new();
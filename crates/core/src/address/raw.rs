@@ -69,6 +69,10 @@ pub enum Discriminant {
     TempStorage = 15,
     /// Replay protection
     ReplayProtection = 16,
+    /// Liquid staking share token raw address.
+    LiquidShare = 17,
+    /// Interchain account raw address.
+    InterchainAccount = 18,
 }
 
 /// Raw address representation.
@@ -118,7 +122,9 @@ impl<S> Address<'_, S> {
                 | Discriminant::Established
                 | Discriminant::Erc20
                 | Discriminant::Nut
-                | Discriminant::IbcToken,
+                | Discriminant::IbcToken
+                | Discriminant::LiquidShare
+                | Discriminant::InterchainAccount,
         )
     }
 }
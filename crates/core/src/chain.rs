@@ -120,6 +120,7 @@ impl ChainId {
 
 /// Height of a block, i.e. the level. The `default` is the
 /// [`BlockHeight::sentinel`] value, which doesn't correspond to any block.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(
     Clone,
     Copy,
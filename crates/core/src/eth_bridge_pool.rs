@@ -35,6 +35,8 @@ pub struct Segments {
     pub signed_root: &'static str,
     /// Bridge pool nonce storage key
     pub bridge_pool_nonce: &'static str,
+    /// Relay costs of transfers, per gas fee token
+    pub relay_costs: &'static str,
 }
 
 /// Check if a key is for a pending transfer
@@ -367,6 +369,39 @@ pub struct GasFee {
     pub token: Address,
 }
 
+/// A quote of the gas fees required to add a transfer to the
+/// Ethereum bridge pool, in some fungible token.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+)]
+pub struct GasFeeQuote {
+    /// The address of the fungible token to draw
+    /// gas fees from.
+    pub token: Address,
+    /// The moving average of the lowest fees of the transfers
+    /// relayed to Ethereum in each batch.
+    pub relay_cost: Amount,
+    /// The minimum fee accepted by the Bridge pool.
+    pub min_fee: Amount,
+}
+
+impl GasFeeQuote {
+    /// Check if the given gas fee pays at least the minimum fee.
+    #[inline]
+    pub fn is_covered_by(&self, gas_fee: &GasFee) -> bool {
+        gas_fee.token == self.token && gas_fee.amount >= self.min_fee
+    }
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Testing helpers and strategies for the Ethereum bridge pool
 pub mod testing {
@@ -58,6 +58,9 @@ pub struct Parameters {
     pub minimum_gas_price: BTreeMap<Address, token::Amount>,
     /// Enable the native token transfer if it is true
     pub is_native_token_transferable: bool,
+    /// Reject the block proposals whose wrapper txs aren't ordered by
+    /// decreasing fee priority
+    pub enforce_fee_priority_ordering: bool,
 }
 
 /// Epoch duration. A new epoch begins as soon as both the `min_num_of_blocks`
@@ -101,6 +104,7 @@ impl Default for Parameters {
             gas_scale: 100_000_000,
             minimum_gas_price: Default::default(),
             is_native_token_transferable: true,
+            enforce_fee_priority_ordering: false,
         }
     }
 }
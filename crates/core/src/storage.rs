@@ -92,6 +92,8 @@ pub enum DbColFam {
     ROLLBACK,
     /// Replay protection
     REPLAYPROT,
+    /// Height-indexed history of subspace values (archive mode only)
+    HISTORY,
 }
 
 /// Subspace column family name
@@ -106,10 +108,13 @@ pub const STATE_CF: &str = "state";
 pub const BLOCK_CF: &str = "block";
 /// Replay protection column family name
 pub const REPLAY_PROTECTION_CF: &str = "replay_protection";
+/// Height-indexed history of subspace values (archive mode only) column
+/// family name
+pub const HISTORY_CF: &str = "history";
 
 impl DbColFam {
     /// Get the name of the column family
-    pub fn to_str(&self) -> &str {
+    pub fn to_str(&self) -> &'static str {
         match self {
             DbColFam::SUBSPACE => SUBSPACE_CF,
             DbColFam::BLOCK => BLOCK_CF,
@@ -117,11 +122,12 @@ impl DbColFam {
             DbColFam::DIFFS => DIFFS_CF,
             DbColFam::ROLLBACK => ROLLBACK_CF,
             DbColFam::REPLAYPROT => REPLAY_PROTECTION_CF,
+            DbColFam::HISTORY => HISTORY_CF,
         }
     }
 
     /// Return an array of all column families
-    pub fn all() -> [&'static str; 6] {
+    pub fn all() -> [&'static str; 7] {
         [
             SUBSPACE_CF,
             BLOCK_CF,
@@ -129,6 +135,7 @@ impl DbColFam {
             DIFFS_CF,
             ROLLBACK_CF,
             REPLAY_PROTECTION_CF,
+            HISTORY_CF,
         ]
     }
 }
@@ -144,6 +151,7 @@ impl FromStr for DbColFam {
             STATE_CF => Ok(Self::STATE),
             REPLAY_PROTECTION_CF => Ok(Self::REPLAYPROT),
             BLOCK_CF => Ok(Self::BLOCK),
+            HISTORY_CF => Ok(Self::HISTORY),
             _ => Err(Error::DbColFamily(s.to_string())),
         }
     }
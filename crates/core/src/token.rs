@@ -1,6 +1,7 @@
 //! A basic fungible token
 
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -14,8 +15,11 @@ use namada_migrations::*;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::address::Address;
 use crate::arith::{self, checked, CheckedAdd, CheckedSub};
+use crate::chain::Epoch;
 use crate::dec::{Dec, POS_DECIMAL_PRECISION};
+use crate::hash::Hash;
 use crate::storage;
 use crate::storage::{DbKeySeg, KeySeg};
 use crate::uint::{self, Uint, I256};
@@ -952,6 +956,318 @@ impl From<DenominatedAmount> for IbcAmount {
     }
 }
 
+/// A schedule that releases a locked token balance over epochs. Until an
+/// amount is vested it may not be debited from the owner's balance, with the
+/// exception of bonding it with PoS.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VestingSchedule<A = Amount> {
+    /// The whole amount vests at once at the `cliff` epoch
+    Cliff {
+        /// The locked amount
+        amount: A,
+        /// The epoch from which the amount is vested
+        cliff: Epoch,
+    },
+    /// The amount vests linearly in every epoch in `(start, end]`
+    Linear {
+        /// The locked amount
+        amount: A,
+        /// The last epoch in which nothing is vested yet
+        start: Epoch,
+        /// The epoch from which the whole amount is vested
+        end: Epoch,
+    },
+    /// Every tranche vests at its own epoch
+    Custom {
+        /// The tranches of the schedule
+        tranches: Vec<VestingTranche<A>>,
+    },
+}
+
+#[cfg(feature = "migrations")]
+namada_macros::derive_borshdeserializer!(VestingSchedule::<Amount>);
+
+/// A single tranche of a custom vesting schedule
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct VestingTranche<A = Amount> {
+    /// The epoch from which the amount is vested
+    pub epoch: Epoch,
+    /// The amount that vests
+    pub amount: A,
+}
+
+impl<A> VestingSchedule<A> {
+    /// Convert the amounts of the schedule with the given function
+    pub fn map<B>(self, mut f: impl FnMut(A) -> B) -> VestingSchedule<B> {
+        match self
+            .try_map(|amount| Ok::<_, std::convert::Infallible>(f(amount)))
+        {
+            Ok(schedule) => schedule,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Convert the amounts of the schedule with the given fallible function
+    pub fn try_map<B, E>(
+        self,
+        mut f: impl FnMut(A) -> Result<B, E>,
+    ) -> Result<VestingSchedule<B>, E> {
+        Ok(match self {
+            Self::Cliff { amount, cliff } => VestingSchedule::Cliff {
+                amount: f(amount)?,
+                cliff,
+            },
+            Self::Linear { amount, start, end } => VestingSchedule::Linear {
+                amount: f(amount)?,
+                start,
+                end,
+            },
+            Self::Custom { tranches } => VestingSchedule::Custom {
+                tranches: tranches
+                    .into_iter()
+                    .map(|VestingTranche { epoch, amount }| {
+                        Ok(VestingTranche {
+                            epoch,
+                            amount: f(amount)?,
+                        })
+                    })
+                    .collect::<Result<_, E>>()?,
+            },
+        })
+    }
+}
+
+impl VestingSchedule {
+    /// Check that the schedule is well-formed - the epoch range of a linear
+    /// schedule must not be empty, a custom schedule must have at least one
+    /// tranche and the total amount must not overflow.
+    pub fn is_valid(&self) -> bool {
+        let well_formed = match self {
+            Self::Cliff { .. } => true,
+            Self::Linear { start, end, .. } => start < end,
+            Self::Custom { tranches } => !tranches.is_empty(),
+        };
+        well_formed && self.total().is_some()
+    }
+
+    /// The total amount that is subject to the schedule
+    pub fn total(&self) -> Option<Amount> {
+        match self {
+            Self::Cliff { amount, .. } | Self::Linear { amount, .. } => {
+                Some(*amount)
+            }
+            Self::Custom { tranches } => {
+                Amount::sum(tranches.iter().map(|tranche| tranche.amount))
+            }
+        }
+    }
+
+    /// The amount that has vested by the given epoch
+    pub fn vested_at(&self, epoch: Epoch) -> Option<Amount> {
+        match self {
+            Self::Cliff { amount, cliff } => Some(if epoch >= *cliff {
+                *amount
+            } else {
+                Amount::zero()
+            }),
+            Self::Linear { amount, start, end } => {
+                if epoch <= *start {
+                    Some(Amount::zero())
+                } else if epoch >= *end {
+                    Some(*amount)
+                } else {
+                    let elapsed = epoch.checked_sub(*start)?.0;
+                    let duration = end.checked_sub(*start)?.0;
+                    amount.checked_mul(elapsed)?.checked_div_u64(duration)
+                }
+            }
+            Self::Custom { tranches } => Amount::sum(
+                tranches
+                    .iter()
+                    .filter(|tranche| tranche.epoch <= epoch)
+                    .map(|tranche| tranche.amount),
+            ),
+        }
+    }
+
+    /// The amount that is still locked at the given epoch
+    pub fn locked_at(&self, epoch: Epoch) -> Option<Amount> {
+        self.total()?.checked_sub(self.vested_at(epoch)?)
+    }
+}
+
+/// A fee budget pre-authorized by a sponsor account. The sponsor pays the
+/// fees of the wrapper txs referencing it, as long as their inner txs run
+/// whitelisted codes, they are signed by one of the targets, if any, and the
+/// fees fit in the remaining budget and in the cap of the current epoch.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct FeeSponsorship {
+    /// The token in which the fees are paid
+    pub token: Address,
+    /// The remaining budget
+    pub budget: Amount,
+    /// The maximum amount of fees paid in a single epoch
+    pub epoch_cap: Amount,
+    /// The hashes of the tx codes whose fees are paid
+    pub code_hashes: BTreeSet<Hash>,
+    /// The fee payers whose fees are paid. Any fee payer if empty.
+    pub targets: BTreeSet<Address>,
+    /// The last epoch in which fees were paid
+    pub epoch: Epoch,
+    /// The amount of fees paid in `epoch`
+    pub epoch_spent: Amount,
+}
+
+impl FeeSponsorship {
+    /// Check that the sponsorship is well-formed - it must whitelist at least
+    /// one tx code and the epoch cap must not be zero.
+    pub fn is_valid(&self) -> bool {
+        !self.code_hashes.is_empty() && !self.epoch_cap.is_zero()
+    }
+
+    // The amount of fees paid in the given epoch
+    fn spent_at(&self, epoch: Epoch) -> Amount {
+        if epoch > self.epoch {
+            Amount::zero()
+        } else {
+            self.epoch_spent
+        }
+    }
+
+    /// The amount of fees that can still be paid in the given epoch
+    pub fn available_at(&self, epoch: Epoch) -> Amount {
+        let remaining_cap = self
+            .epoch_cap
+            .checked_sub(self.spent_at(epoch))
+            .unwrap_or_default();
+        std::cmp::min(self.budget, remaining_cap)
+    }
+
+    /// Pay the fees of a wrapper tx in the given epoch. Returns the
+    /// sponsorship with the fees debited from its allowance, or an error if
+    /// the wrapper tx is not sponsored or the allowance is insufficient.
+    pub fn spend<'a>(
+        &self,
+        epoch: Epoch,
+        token: &Address,
+        fee_payer: &Address,
+        mut code_hashes: impl Iterator<Item = &'a Hash>,
+        fees: Amount,
+    ) -> Result<Self, String> {
+        if &self.token != token {
+            return Err(format!(
+                "The fees must be paid in the sponsored token {}",
+                self.token
+            ));
+        }
+        if !self.targets.is_empty() && !self.targets.contains(fee_payer) {
+            return Err(format!("The fee payer {fee_payer} is not sponsored"));
+        }
+        if let Some(code_hash) =
+            code_hashes.find(|hash| !self.code_hashes.contains(hash))
+        {
+            return Err(format!("The tx code {code_hash} is not sponsored"));
+        }
+        let available = self.available_at(epoch);
+        if available < fees {
+            return Err(format!(
+                "The fees {fees} exceed the available sponsored amount \
+                 {available}"
+            ));
+        }
+
+        let spent = self.spent_at(epoch);
+        Ok(Self {
+            budget: checked!(self.budget - fees)
+                .map_err(|err| err.to_string())?,
+            epoch: std::cmp::max(epoch, self.epoch),
+            epoch_spent: checked!(spent + fees)
+                .map_err(|err| err.to_string())?,
+            ..self.clone()
+        })
+    }
+}
+
+/// A transfer executed by the protocol from a source account at a regular
+/// interval of epochs, until its count of executions runs out.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct ScheduledTransfer {
+    /// The transferred token
+    pub token: Address,
+    /// The amount transferred on each execution
+    pub amount: Amount,
+    /// The target of the transfer
+    pub target: Address,
+    /// The epoch of the next execution
+    pub next_epoch: Epoch,
+    /// The number of epochs between two executions
+    pub interval: u64,
+    /// The number of remaining executions
+    pub remaining: u64,
+}
+
+impl ScheduledTransfer {
+    /// Check that the scheduled transfer is well-formed - the amount, the
+    /// interval and the number of remaining executions must not be zero.
+    pub fn is_valid(&self) -> bool {
+        !self.amount.is_zero() && self.interval != 0 && self.remaining != 0
+    }
+
+    /// Get the scheduled transfer following an execution. Returns `None`
+    /// after the last execution.
+    pub fn next(&self) -> Option<Self> {
+        let remaining = self.remaining.checked_sub(1).filter(|n| *n != 0)?;
+        Some(Self {
+            next_epoch: self.next_epoch.checked_add(self.interval)?,
+            remaining,
+            ..self.clone()
+        })
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum AmountError {
@@ -1356,4 +1672,160 @@ mod tests {
             Ordering::Less
         );
     }
+
+    #[test]
+    fn test_vesting_schedule() {
+        let amount = Amount::from(1000);
+
+        let cliff = VestingSchedule::Cliff {
+            amount,
+            cliff: Epoch(5),
+        };
+        assert!(cliff.is_valid());
+        assert_eq!(cliff.vested_at(Epoch(4)), Some(Amount::zero()));
+        assert_eq!(cliff.vested_at(Epoch(5)), Some(amount));
+        assert_eq!(cliff.locked_at(Epoch(4)), Some(amount));
+
+        let linear = VestingSchedule::Linear {
+            amount,
+            start: Epoch(10),
+            end: Epoch(20),
+        };
+        assert!(linear.is_valid());
+        assert_eq!(linear.vested_at(Epoch(10)), Some(Amount::zero()));
+        assert_eq!(linear.vested_at(Epoch(13)), Some(Amount::from(300)));
+        assert_eq!(linear.locked_at(Epoch(13)), Some(Amount::from(700)));
+        assert_eq!(linear.vested_at(Epoch(25)), Some(amount));
+        let empty_range = VestingSchedule::Linear {
+            amount,
+            start: Epoch(10),
+            end: Epoch(10),
+        };
+        assert!(!empty_range.is_valid());
+
+        let custom = VestingSchedule::Custom {
+            tranches: vec![
+                VestingTranche {
+                    epoch: Epoch(2),
+                    amount: Amount::from(100),
+                },
+                VestingTranche {
+                    epoch: Epoch(7),
+                    amount: Amount::from(400),
+                },
+            ],
+        };
+        assert!(custom.is_valid());
+        assert_eq!(custom.total(), Some(Amount::from(500)));
+        assert_eq!(custom.vested_at(Epoch(1)), Some(Amount::zero()));
+        assert_eq!(custom.vested_at(Epoch(2)), Some(Amount::from(100)));
+        assert_eq!(custom.locked_at(Epoch(6)), Some(Amount::from(400)));
+        assert_eq!(custom.locked_at(Epoch(7)), Some(Amount::zero()));
+        assert!(!VestingSchedule::Custom { tranches: vec![] }.is_valid());
+    }
+
+    #[test]
+    fn test_fee_sponsorship() {
+        use crate::address::testing::{
+            established_address_1, established_address_2, nam,
+        };
+
+        let code = Hash::sha256(b"tx_transfer");
+        let other_code = Hash::sha256(b"tx_bond");
+        let target = established_address_1();
+        let sponsorship = FeeSponsorship {
+            token: nam(),
+            budget: Amount::from(250),
+            epoch_cap: Amount::from(100),
+            code_hashes: [code].into_iter().collect(),
+            targets: [target.clone()].into_iter().collect(),
+            epoch: Epoch(0),
+            epoch_spent: Amount::zero(),
+        };
+        assert!(sponsorship.is_valid());
+        assert_eq!(sponsorship.available_at(Epoch(1)), Amount::from(100));
+
+        let spent = sponsorship
+            .spend(Epoch(1), &nam(), &target, [code].iter(), Amount::from(60))
+            .unwrap();
+        assert_eq!(spent.budget, Amount::from(190));
+        assert_eq!(spent.epoch, Epoch(1));
+        assert_eq!(spent.available_at(Epoch(1)), Amount::from(40));
+        // the epoch cap is exceeded
+        assert!(
+            spent
+                .spend(Epoch(1), &nam(), &target, [code].iter(), 50.into())
+                .is_err()
+        );
+        // the cap is restored in the next epoch
+        let spent = spent
+            .spend(Epoch(2), &nam(), &target, [code].iter(), 100.into())
+            .unwrap();
+        assert_eq!(spent.available_at(Epoch(3)), Amount::from(90));
+
+        // not sponsored
+        let other_target = established_address_2();
+        assert!(
+            sponsorship
+                .spend(Epoch(1), &nam(), &other_target, [code].iter(), 1.into())
+                .is_err()
+        );
+        assert!(
+            sponsorship
+                .spend(
+                    Epoch(1),
+                    &nam(),
+                    &target,
+                    [code, other_code].iter(),
+                    1.into()
+                )
+                .is_err()
+        );
+        assert!(
+            sponsorship
+                .spend(Epoch(1), &target, &target, [code].iter(), 1.into())
+                .is_err()
+        );
+        assert!(
+            !FeeSponsorship {
+                code_hashes: BTreeSet::new(),
+                ..sponsorship
+            }
+            .is_valid()
+        );
+    }
+
+    #[test]
+    fn test_scheduled_transfer() {
+        let transfer = ScheduledTransfer {
+            token: nam(),
+            amount: Amount::from(10),
+            target: established_address_2(),
+            next_epoch: Epoch(3),
+            interval: 2,
+            remaining: 2,
+        };
+        assert!(transfer.is_valid());
+
+        let next = transfer.next().unwrap();
+        assert_eq!(next.next_epoch, Epoch(5));
+        assert_eq!(next.remaining, 1);
+        // the last execution
+        assert!(next.next().is_none());
+
+        assert!(
+            !ScheduledTransfer {
+                interval: 0,
+                ..transfer.clone()
+            }
+            .is_valid()
+        );
+        assert!(
+            !ScheduledTransfer {
+                amount: Amount::zero(),
+                ..transfer
+            }
+            .is_valid()
+        );
+    }
 }
@@ -1,6 +1,6 @@
 //! Logic for acting on events
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
 use borsh::BorshDeserialize;
@@ -29,7 +29,8 @@ use token::{burn_tokens, decrement_total_supply, increment_total_supply};
 
 use crate::event::EthBridgeEvent;
 use crate::storage::bridge_pool::{
-    get_nonce_key, is_pending_transfer_key, BRIDGE_POOL_ADDRESS,
+    get_nonce_key, get_relay_cost_key, is_pending_transfer_key,
+    read_gas_fee_quote, update_relay_cost, BRIDGE_POOL_ADDRESS,
 };
 use crate::storage::eth_bridge_queries::{EthAssetMint, EthBridgeQueries};
 use crate::storage::parameters::read_native_erc20_address;
@@ -267,6 +268,8 @@ where
         })
         .filter(is_pending_transfer_key)
         .collect();
+    // the lowest gas fee relayed in this batch, per gas fee token
+    let mut relayed_fees: BTreeMap<Address, token::Amount> = BTreeMap::new();
     // Remove the completed transfers from the bridge pool
     for event in transfers {
         let (pending_transfer, key) = if let Some((pending, key)) =
@@ -300,6 +303,13 @@ where
             pending_transfer.gas_fee.amount,
         )?;
 
+        relayed_fees
+            .entry(pending_transfer.gas_fee.token.clone())
+            .and_modify(|fee| {
+                *fee = (*fee).min(pending_transfer.gas_fee.amount)
+            })
+            .or_insert(pending_transfer.gas_fee.amount);
+
         state.delete(&key)?;
         _ = pending_keys.swap_remove(&key);
         _ = changed_keys.insert(key);
@@ -310,6 +320,13 @@ where
         ));
     }
 
+    // the relayed fees are an estimate of the cost of relaying
+    // transfers, from which the minimum gas fees are derived
+    for (token, fee) in relayed_fees {
+        update_relay_cost(state, &token, fee)?;
+        _ = changed_keys.insert(get_relay_cost_key(&token));
+    }
+
     if pending_keys.is_empty() {
         return Ok((changed_keys, tx_events));
     }
@@ -320,29 +337,44 @@ where
     // and refunded.
     let epoch_duration = read_epoch_duration_parameter(state)?;
     let timeout_offset = epoch_duration.min_num_of_blocks;
+    // transfers paying less than the minimum gas fee are unlikely to
+    // be relayed, so they are refunded after half the timeout
+    let underpaid_timeout_offset = timeout_offset / 2;
 
     // Check time out and refund
-    if state.in_mem().block.height.0 > timeout_offset {
-        let timeout_height = BlockHeight(
-            state
-                .in_mem()
-                .block
-                .height
-                .0
-                .checked_sub(timeout_offset)
-                .expect("Cannot underflow - checked above"),
-        );
-        for key in pending_keys {
-            let inserted_height = BlockHeight::try_from_slice(
-                &state.in_mem().block.tree.get(&key)?,
-            )
-            .expect("BlockHeight should be decoded");
-            if inserted_height <= timeout_height {
-                let (mut keys, mut new_tx_events) =
-                    refund_transfer(state, key)?;
-                changed_keys.append(&mut keys);
-                tx_events.append(&mut new_tx_events);
-            }
+    let current_height = state.in_mem().block.height.0;
+    let timeout_height = |offset: u64| {
+        current_height
+            .checked_sub(offset)
+            .filter(|height| *height > 0)
+            .map(BlockHeight)
+    };
+    let Some(underpaid_timeout_height) =
+        timeout_height(underpaid_timeout_offset)
+    else {
+        return Ok((changed_keys, tx_events));
+    };
+    let timeout_height = timeout_height(timeout_offset);
+    for key in pending_keys {
+        let inserted_height =
+            BlockHeight::try_from_slice(&state.in_mem().block.tree.get(&key)?)
+                .expect("BlockHeight should be decoded");
+        let timed_out = if timeout_height
+            .is_some_and(|timeout_height| inserted_height <= timeout_height)
+        {
+            true
+        } else if inserted_height <= underpaid_timeout_height {
+            let transfer: PendingTransfer =
+                state.read(&key)?.expect("No PendingTransfer");
+            !read_gas_fee_quote(state, &transfer.gas_fee.token)?
+                .is_covered_by(&transfer.gas_fee)
+        } else {
+            false
+        };
+        if timed_out {
+            let (mut keys, mut new_tx_events) = refund_transfer(state, key)?;
+            changed_keys.append(&mut keys);
+            tx_events.append(&mut new_tx_events);
         }
     }
 
@@ -994,13 +1026,18 @@ mod tests {
         assert!(changed_keys.remove(&pool_nam_balance_key));
         assert!(changed_keys.remove(&pool_erc_balance_key));
         assert!(changed_keys.remove(&get_nonce_key()));
+        assert!(changed_keys.remove(&get_relay_cost_key(&nam())));
+        assert!(changed_keys.remove(&get_relay_cost_key(
+            &wrapped_erc20s::token(&erc20_gas_addr)
+        )));
         assert!(changed_keys.iter().all(|k| pending_keys.contains(k)));
 
         let prefix = BRIDGE_POOL_ADDRESS.to_db_key().into();
         assert_eq!(
             state.iter_prefix(&prefix).expect("Test failed").count(),
-            // NOTE: we should have one write -- the bridge pool nonce update
-            1
+            // NOTE: we should have three writes -- the bridge pool nonce
+            // update and the relay costs of the two gas fee tokens
+            3
         );
         let relayer_nam_balance: Amount = state
             .read(&payer_nam_balance_key)
@@ -1133,6 +1170,92 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that the relay cost of a gas fee token is updated with the
+    /// lowest fee of the relayed transfers paying in that token
+    fn test_act_on_updates_relay_costs() {
+        let mut state = TestState::default();
+        test_utils::bootstrap_ethereum_bridge(&mut state);
+        state.commit_block().expect("Test failed");
+        init_storage(&mut state);
+        update_relay_cost(&mut state, &nam(), Amount::from(9))
+            .expect("Test failed");
+        let pending_transfers = init_bridge_pool(&mut state);
+        init_balance(&mut state, &pending_transfers);
+        let event = EthereumEvent::TransfersToEthereum {
+            nonce: arbitrary_nonce(),
+            transfers: pending_transfers
+                .iter()
+                .map(TransferToEthereum::from)
+                .collect(),
+            relayer: gen_established_address("random"),
+        };
+        let (changed_keys, _) = act_on(&mut state, event).unwrap();
+        assert!(changed_keys.contains(&get_relay_cost_key(&nam())));
+
+        // the relayed fees of 1 move the average down by an eighth
+        let quote = read_gas_fee_quote(&state, &nam()).expect("Test failed");
+        assert_eq!(quote.relay_cost, Amount::from(8));
+        assert_eq!(quote.min_fee, Amount::from(4));
+    }
+
+    #[test]
+    /// Test that transfers paying less than the minimum gas fee are
+    /// refunded after half the timeout
+    fn test_act_on_refunds_underpaid_transfers_to_eth() {
+        let mut state = TestState::default();
+        test_utils::bootstrap_ethereum_bridge(&mut state);
+        state.commit_block().expect("Test failed");
+        init_storage(&mut state);
+        // the minimum gas fee in NAM is 2
+        update_relay_cost(&mut state, &nam(), Amount::from(4))
+            .expect("Test failed");
+        let underpaid_transfers = init_bridge_pool(&mut state);
+        init_balance(&mut state, &underpaid_transfers);
+        let transfer = PendingTransfer {
+            transfer: eth_bridge_pool::TransferToEthereum {
+                asset: EthAddress([4; 20]),
+                sender: address::testing::established_address_1(),
+                recipient: EthAddress([5; 20]),
+                amount: Amount::from(10),
+                kind: eth_bridge_pool::TransferToEthereumKind::Erc20,
+            },
+            gas_fee: GasFee {
+                token: nam(),
+                amount: Amount::from(2),
+                payer: address::testing::established_address_1(),
+            },
+        };
+        let key = get_pending_key(&transfer);
+        state.write(&key, transfer).expect("Test failed");
+        state.commit_block().expect("Test failed");
+        // only the underpaid transfers time out
+        state.in_mem_mut().block.height += 5 + 1;
+
+        let event = EthereumEvent::TransfersToEthereum {
+            nonce: arbitrary_nonce(),
+            transfers: vec![],
+            relayer: gen_implicit_address(),
+        };
+        let (changed_keys, _) = act_on(&mut state, event).unwrap();
+
+        for transfer in &underpaid_transfers {
+            let key = get_pending_key(transfer);
+            assert!(changed_keys.contains(&key));
+            assert!(!state.has_key(&key).expect("Test failed"));
+        }
+        assert!(!changed_keys.contains(&key));
+        assert!(state.has_key(&key).expect("Test failed"));
+
+        // the gas fees are refunded
+        let payer = address::testing::established_address_2();
+        let payer_balance: Amount = state
+            .read(&balance_key(&nam(), &payer))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(payer_balance, Amount::from(2));
+    }
+
     #[test]
     fn test_redeem_native_token() -> Result<()> {
         let mut state = TestState::default();
@@ -1,13 +1,25 @@
 //! Tools for accessing the storage subspaces of the Ethereum
 //! bridge pool
 
-use namada_core::eth_bridge_pool::Segments;
+use namada_core::address::Address;
 pub use namada_core::eth_bridge_pool::{
     get_key_from_hash, get_pending_key, is_pending_transfer_key,
     BRIDGE_POOL_ADDRESS,
 };
-use namada_core::storage::{DbKeySeg, Key};
+use namada_core::eth_bridge_pool::{GasFeeQuote, Segments};
+use namada_core::storage::{DbKeySeg, Key, KeySeg};
+use namada_core::token::Amount;
 pub use namada_state::merkle_tree::eth_bridge_pool::BridgePoolTree;
+use namada_storage::{Result, StorageRead, StorageWrite};
+
+/// The weight of a new observation in the moving average of the
+/// relay costs is the inverse of this value.
+const RELAY_COST_SMOOTHING: u64 = 8;
+
+/// The minimum gas fee of a token is its relay cost divided by this
+/// value. Accepting fees lower than the relay cost lets the minimum
+/// decay, when relayers settle for cheaper transfers.
+const MIN_FEE_DIVISOR: u64 = 2;
 
 /// Get the storage key for the root of the Merkle tree
 /// containing the transfers in the pool
@@ -31,7 +43,159 @@ pub fn get_nonce_key() -> Key {
     }
 }
 
+/// Get the storage key for the relay cost of transfers paying
+/// gas fees in the given token
+pub fn get_relay_cost_key(token: &Address) -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(BRIDGE_POOL_ADDRESS),
+            DbKeySeg::StringSeg(Segments::VALUES.relay_costs.into()),
+            token.to_db_key(),
+        ],
+    }
+}
+
+/// Read the relay cost of transfers paying gas fees in the given
+/// token. Defaults to zero, if no transfers have been relayed yet.
+pub fn read_relay_cost<S>(storage: &S, token: &Address) -> Result<Amount>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&get_relay_cost_key(token))?
+        .unwrap_or_default())
+}
+
+/// Read the gas fee quote of the given token.
+pub fn read_gas_fee_quote<S>(
+    storage: &S,
+    token: &Address,
+) -> Result<GasFeeQuote>
+where
+    S: StorageRead,
+{
+    let relay_cost = read_relay_cost(storage, token)?;
+    Ok(GasFeeQuote {
+        token: token.clone(),
+        relay_cost,
+        min_fee: min_gas_fee(relay_cost),
+    })
+}
+
+/// Compute the minimum gas fee from the relay cost of a token.
+#[inline]
+pub fn min_gas_fee(relay_cost: Amount) -> Amount {
+    relay_cost
+        .checked_div_u64(MIN_FEE_DIVISOR)
+        .expect("The divisor is not zero")
+}
+
+/// Compute the next moving average of the relay cost of a token,
+/// given the lowest gas fee observed in a relayed batch.
+pub fn next_relay_cost(prev: Option<Amount>, observed: Amount) -> Amount {
+    let Some(prev) = prev else {
+        return observed;
+    };
+    let step = |delta: Amount| {
+        delta
+            .checked_div_u64(RELAY_COST_SMOOTHING)
+            .expect("The smoothing factor is not zero")
+    };
+    if observed >= prev {
+        let delta = observed.checked_sub(prev).expect("Cannot underflow");
+        prev.checked_add(step(delta)).unwrap_or(observed)
+    } else {
+        let delta = prev.checked_sub(observed).expect("Cannot underflow");
+        prev.checked_sub(step(delta)).unwrap_or(observed)
+    }
+}
+
+/// Update the relay cost of a token, given the lowest gas fee
+/// observed in a relayed batch.
+pub fn update_relay_cost<S>(
+    storage: &mut S,
+    token: &Address,
+    observed: Amount,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = get_relay_cost_key(token);
+    let prev = storage.read(&key)?;
+    storage.write(&key, next_relay_cost(prev, observed))
+}
+
 /// Check if a key belongs to the bridge pools sub-storage
 pub fn is_bridge_pool_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &BRIDGE_POOL_ADDRESS)
 }
+
+#[cfg(test)]
+mod test_bridge_pool_storage {
+    use namada_core::address::testing::nam;
+    use namada_core::eth_bridge_pool::GasFee;
+    use namada_state::testing::TestState;
+
+    use super::*;
+
+    /// Test that the relay costs are not mistaken for pending transfers.
+    #[test]
+    fn test_relay_cost_key_is_not_pending() {
+        let key = get_relay_cost_key(&nam());
+        assert!(is_bridge_pool_key(&key));
+        assert!(!is_pending_transfer_key(&key));
+    }
+
+    /// Test the moving average of the relay costs.
+    #[test]
+    fn test_next_relay_cost() {
+        // the first observation is taken as is
+        assert_eq!(
+            next_relay_cost(None, Amount::from(1000)),
+            Amount::from(1000)
+        );
+        // later observations move the average by an eighth of the
+        // difference, up or down
+        assert_eq!(
+            next_relay_cost(Some(Amount::from(1000)), Amount::from(1800)),
+            Amount::from(1100)
+        );
+        assert_eq!(
+            next_relay_cost(Some(Amount::from(1000)), Amount::from(200)),
+            Amount::from(900)
+        );
+        assert_eq!(
+            next_relay_cost(Some(Amount::from(1000)), Amount::from(1000)),
+            Amount::from(1000)
+        );
+    }
+
+    /// Test reading the gas fee quote of a token.
+    #[test]
+    fn test_gas_fee_quote() {
+        let mut state = TestState::default();
+        let quote = read_gas_fee_quote(&state, &nam()).unwrap();
+        assert_eq!(quote.relay_cost, Amount::zero());
+        assert_eq!(quote.min_fee, Amount::zero());
+
+        update_relay_cost(&mut state, &nam(), Amount::from(1000)).unwrap();
+        update_relay_cost(&mut state, &nam(), Amount::from(200)).unwrap();
+        let quote = read_gas_fee_quote(&state, &nam()).unwrap();
+        assert_eq!(
+            quote,
+            GasFeeQuote {
+                token: nam(),
+                relay_cost: Amount::from(900),
+                min_fee: Amount::from(450),
+            }
+        );
+
+        let fee = |amount: u64| GasFee {
+            amount: Amount::from(amount),
+            payer: nam(),
+            token: nam(),
+        };
+        assert!(quote.is_covered_by(&fee(450)));
+        assert!(!quote.is_covered_by(&fee(449)));
+    }
+}
@@ -32,7 +32,8 @@ use namada_tx::BatchedTxRef;
 use namada_vp_env::{Error, Result, StorageRead, VpEnv};
 
 use crate::storage::bridge_pool::{
-    get_pending_key, is_bridge_pool_key, BRIDGE_POOL_ADDRESS,
+    get_pending_key, is_bridge_pool_key, read_gas_fee_quote,
+    BRIDGE_POOL_ADDRESS,
 };
 use crate::storage::eth_bridge_queries::is_bridge_active_at;
 use crate::storage::parameters::read_native_erc20_address;
@@ -136,6 +137,17 @@ where
             tracing::debug!("{error}");
             return Err(error);
         }
+        // check that the transfer pays at least the minimum gas fee
+        let quote = read_gas_fee_quote(&ctx.pre(), &transfer.gas_fee.token)?;
+        if !quote.is_covered_by(&transfer.gas_fee) {
+            let error = Error::new_alloc(format!(
+                "Rejecting transaction as the gas fee of {} is lower than the \
+                 minimum gas fee of {} in {}",
+                transfer.gas_fee.amount, quote.min_fee, quote.token,
+            ));
+            tracing::debug!("{error}");
+            return Err(error);
+        }
         // The deltas in the escrowed amounts we must check.
         let wnam_address = read_native_erc20_address(&ctx.pre())?;
         let escrow_checks =
@@ -646,7 +658,7 @@ mod test_bridge_pool_vp {
     use namada_vp::native_vp;
 
     use super::*;
-    use crate::storage::bridge_pool::get_signed_root_key;
+    use crate::storage::bridge_pool::{get_signed_root_key, update_relay_cost};
     use crate::storage::parameters::{
         Contracts, EthereumBridgeParams, UpgradeableContract,
     };
@@ -945,8 +957,29 @@ mod test_bridge_pool_vp {
     ) where
         F: FnOnce(&mut PendingTransfer, &mut WriteLog) -> BTreeSet<Key>,
     {
-        // setup
-        let mut state = setup_storage();
+        assert_bridge_pool_with_state(
+            setup_storage(),
+            payer_gas_delta,
+            gas_escrow_delta,
+            payer_delta,
+            escrow_delta,
+            insert_transfer,
+            expect,
+        )
+    }
+
+    /// Same as [`assert_bridge_pool`], starting from the given state
+    fn assert_bridge_pool_with_state<F>(
+        mut state: TestState,
+        payer_gas_delta: I320,
+        gas_escrow_delta: I320,
+        payer_delta: I320,
+        escrow_delta: I320,
+        insert_transfer: F,
+        expect: Expect,
+    ) where
+        F: FnOnce(&mut PendingTransfer, &mut WriteLog) -> BTreeSet<Key>,
+    {
         let mut tx = Tx::from_type(TxType::Raw);
         tx.push_default_inner_tx();
 
@@ -1043,6 +1076,38 @@ mod test_bridge_pool_vp {
         );
     }
 
+    /// Test that a transfer paying less than the minimum gas fee
+    /// is rejected, and one paying exactly the minimum is accepted
+    #[test]
+    fn test_min_gas_fee() {
+        for (relay_cost, expect) in [
+            (2 * GAS_FEE + 2, Expect::Rejected),
+            (2 * GAS_FEE, Expect::Accepted),
+        ] {
+            let mut state = setup_storage();
+            update_relay_cost(&mut state, &nam(), relay_cost.into())
+                .expect("Test failed");
+            state.commit_block().expect("Test failed");
+            assert_bridge_pool_with_state(
+                state,
+                -I320::from(GAS_FEE),
+                I320::from(GAS_FEE),
+                -I320::from(TOKENS),
+                I320::from(TOKENS),
+                |transfer, log| {
+                    let _ = log
+                        .write(
+                            &get_pending_key(transfer),
+                            transfer.serialize_to_vec(),
+                        )
+                        .unwrap();
+                    BTreeSet::from([get_pending_key(transfer)])
+                },
+                expect,
+            );
+        }
+    }
+
     /// Test that if the balance for the gas payer
     /// was not correctly adjusted, reject
     #[test]
@@ -149,6 +149,18 @@ impl Display for EventLevel {
     }
 }
 
+impl FromStr for EventLevel {
+    type Err = EventError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(EventLevel::Block),
+            "tx" => Ok(EventLevel::Tx),
+            _ => Err(EventError::InvalidEventLevel(s.to_owned())),
+        }
+    }
+}
+
 /// ABCI event type.
 ///
 /// It is comprised of an event domain and sub-domain, plus any other
@@ -319,6 +331,9 @@ pub enum EventError {
     /// Error when parsing an event type
     #[error("Invalid event type")]
     InvalidEventType,
+    /// Error when parsing an event level
+    #[error("Invalid event level: {0}")]
+    InvalidEventLevel(String),
     /// Error when parsing attributes from an event JSON.
     #[error("Json missing `attributes` field")]
     MissingAttributes,
@@ -529,3 +544,145 @@ impl From<Event> for namada_core::tendermint::abci::Event {
         }
     }
 }
+
+impl TryFrom<namada_core::tendermint::abci::Event> for Event {
+    type Error = EventError;
+
+    fn try_from(
+        event: namada_core::tendermint::abci::Event,
+    ) -> Result<Self, Self::Error> {
+        let mut level = EventLevel::Tx;
+        let mut attributes = BTreeMap::new();
+
+        for attr in event.attributes {
+            let (Ok(key), Ok(value)) = (attr.key_str(), attr.value_str())
+            else {
+                return Err(EventError::AttributeEncoding(
+                    "Event attribute is malformed UTF-8".to_string(),
+                ));
+            };
+            if key == "event-level" {
+                level = value.parse()?;
+            } else {
+                attributes.insert(key.to_owned(), value.to_owned());
+            }
+        }
+
+        let event_type = {
+            use extend::{Domain, RawReadFromEventAttributes};
+
+            match Domain::<Event>::raw_read_opt_from_event_attributes(
+                &attributes,
+            ) {
+                Some("") => return Err(EventError::MissingDomain),
+                // NB: the domain of the event was encoded in its attributes,
+                // and only its sub-domain was kept as the event kind
+                Some(domain) => EventTypeBuilder::new_with_type(domain)
+                    .with_segment(&event.kind)
+                    .build(),
+                None => event.kind.parse()?,
+            }
+        };
+
+        Ok(Self {
+            level,
+            event_type,
+            attributes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::chain::BlockHeight;
+    use namada_core::tendermint::abci;
+
+    use super::extend::{ComposeEvent, Height, Log, event_domain_of};
+    use super::*;
+
+    struct DummyEvent;
+
+    impl EventToEmit for DummyEvent {
+        const DOMAIN: &'static str = "dummy";
+    }
+
+    fn dummy_event(level: EventLevel) -> Event {
+        Event::new(
+            EventTypeBuilder::new_of::<DummyEvent>()
+                .with_segment("event")
+                .build(),
+            level,
+        )
+        .with(Height(BlockHeight(300)))
+        .with(Log("this is sparta!".to_string()))
+        .into()
+    }
+
+    fn abci_event(kind: &str, attributes: &[(&str, &str)]) -> abci::Event {
+        abci::Event {
+            kind: kind.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|&(key, value)| (key, value, true).into())
+                .collect(),
+        }
+    }
+
+    /// Test that events are preserved when converted to ABCI events and
+    /// back, at both event levels.
+    #[test]
+    fn test_abci_event_roundtrip() {
+        for level in [EventLevel::Tx, EventLevel::Block] {
+            let event = dummy_event(level);
+            let abci_event = abci::Event::from(event.clone());
+            assert_eq!(Event::try_from(abci_event).unwrap(), event);
+        }
+    }
+
+    /// Test that events whose domain is encoded in their attributes, such
+    /// as IBC events, are preserved when converted to ABCI events and back.
+    #[test]
+    fn test_abci_event_roundtrip_with_domain_attribute() {
+        let event: Event = dummy_event(EventLevel::Tx)
+            .with(event_domain_of::<DummyEvent>())
+            .into();
+
+        let abci_event = abci::Event::from(event.clone());
+        assert_eq!(abci_event.kind, "event");
+        assert_eq!(Event::try_from(abci_event).unwrap(), event);
+    }
+
+    /// Test that ABCI events without a level are parsed as tx events.
+    #[test]
+    fn test_abci_event_default_level() {
+        let event =
+            Event::try_from(abci_event("dummy/event", &[("log", "hi")]))
+                .unwrap();
+        assert_eq!(event.level, EventLevel::Tx);
+        assert_eq!(event.event_type.to_string(), "dummy/event");
+        assert_eq!(event.attributes.get("log").unwrap(), "hi");
+    }
+
+    /// Test that ABCI events with malformed attributes or types are
+    /// rejected.
+    #[test]
+    fn test_abci_event_malformed() {
+        let result = Event::try_from(abci_event(
+            "dummy/event",
+            &[("event-level", "epoch")],
+        ));
+        assert!(matches!(
+            result,
+            Err(EventError::InvalidEventLevel(level)) if level == "epoch"
+        ));
+
+        let result = Event::try_from(abci_event("dummy", &[]));
+        assert!(matches!(result, Err(EventError::MissingDomain)));
+
+        let result = Event::try_from(abci_event(
+            "event",
+            &[("event-domain", ""), ("event-level", "tx")],
+        ));
+        assert!(matches!(result, Err(EventError::MissingDomain)));
+    }
+}
@@ -212,6 +212,169 @@ pub const MASP_FINAL_CHECK_GAS: u64 =
 /// Gas module result for functions that may fail
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The schedule of the gas costs charged to transactions and validity
+/// predicates. It's stored in the parameters subspace and can be updated by
+/// governance, the default schedule has the costs defined in this module.
+///
+/// The cost of a wasm memory page is not part of the schedule because it's
+/// injected in the wasm code when it's compiled, and the compiled code is
+/// cached.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSerialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct GasCostSchedule {
+    /// The cost of compiling wasm code, per byte
+    pub compile_per_byte: u64,
+    /// The cost of validating untrusted wasm code, per byte
+    pub wasm_code_validation_per_byte: u64,
+    /// The cost of validating a wrapper transaction
+    pub wrapper_tx_validation: u64,
+    /// The cost of the space occupied by a transaction in a block, per byte
+    pub storage_occupation_per_byte: u64,
+    /// The cost of downloading a transaction over the network, per byte
+    pub network_transmission_per_byte: u64,
+    /// The cost of accessing data from memory (both read and write mode), per
+    /// byte
+    pub memory_access_per_byte: u64,
+    /// The cost of accessing data from storage, per byte
+    pub storage_access_per_byte: u64,
+    /// The cost of writing data to storage, per byte
+    pub storage_write_per_byte: u64,
+    /// The cost of removing data from storage, per byte
+    pub storage_delete_per_byte: u64,
+    /// The cost of verifying a single signature of a transaction
+    pub verify_tx_sig: u64,
+    /// The cost to validate an Ibc action
+    pub ibc_action_validate: u64,
+    /// The cost to execute an Ibc action
+    pub ibc_action_execute: u64,
+    /// The cost of masp sig verification
+    pub masp_verify_sig: u64,
+    /// The fixed cost of spend note verification
+    pub masp_fixed_spend: u64,
+    /// The variable cost of spend note verification
+    pub masp_variable_spend: u64,
+    /// The fixed cost of convert note verification
+    pub masp_fixed_convert: u64,
+    /// The variable cost of convert note verification
+    pub masp_variable_convert: u64,
+    /// The fixed cost of output note verification
+    pub masp_fixed_output: u64,
+    /// The variable cost of output note verification
+    pub masp_variable_output: u64,
+    /// The cost to process a masp spend note in the bundle
+    pub masp_spend_check: u64,
+    /// The cost to process a masp convert note in the bundle
+    pub masp_convert_check: u64,
+    /// The cost to process a masp output note in the bundle
+    pub masp_output_check: u64,
+    /// The cost to run the final masp check in the bundle
+    pub masp_final_check: u64,
+}
+
+impl Default for GasCostSchedule {
+    fn default() -> Self {
+        Self {
+            compile_per_byte: COMPILE_GAS_PER_BYTE,
+            wasm_code_validation_per_byte: WASM_CODE_VALIDATION_GAS_PER_BYTE,
+            wrapper_tx_validation: WRAPPER_TX_VALIDATION_GAS,
+            storage_occupation_per_byte: STORAGE_OCCUPATION_GAS_PER_BYTE,
+            network_transmission_per_byte: NETWORK_TRANSMISSION_GAS_PER_BYTE,
+            memory_access_per_byte: MEMORY_ACCESS_GAS_PER_BYTE,
+            storage_access_per_byte: STORAGE_ACCESS_GAS_PER_BYTE,
+            storage_write_per_byte: STORAGE_WRITE_GAS_PER_BYTE,
+            storage_delete_per_byte: STORAGE_DELETE_GAS_PER_BYTE,
+            verify_tx_sig: VERIFY_TX_SIG_GAS,
+            ibc_action_validate: IBC_ACTION_VALIDATE_GAS,
+            ibc_action_execute: IBC_ACTION_EXECUTE_GAS,
+            masp_verify_sig: MASP_VERIFY_SIG_GAS,
+            masp_fixed_spend: MASP_FIXED_SPEND_GAS,
+            masp_variable_spend: MASP_VARIABLE_SPEND_GAS,
+            masp_fixed_convert: MASP_FIXED_CONVERT_GAS,
+            masp_variable_convert: MASP_VARIABLE_CONVERT_GAS,
+            masp_fixed_output: MASP_FIXED_OUTPUT_GAS,
+            masp_variable_output: MASP_VARIABLE_OUTPUT_GAS,
+            masp_spend_check: MASP_SPEND_CHECK_GAS,
+            masp_convert_check: MASP_CONVERT_CHECK_GAS,
+            masp_output_check: MASP_OUTPUT_CHECK_GAS,
+            masp_final_check: MASP_FINAL_CHECK_GAS,
+        }
+    }
+}
+
+impl GasCostSchedule {
+    /// Check that none of the costs is zero, otherwise the resource would be
+    /// free
+    pub fn is_valid(&self) -> bool {
+        let Self {
+            compile_per_byte,
+            wasm_code_validation_per_byte,
+            wrapper_tx_validation,
+            storage_occupation_per_byte,
+            network_transmission_per_byte,
+            memory_access_per_byte,
+            storage_access_per_byte,
+            storage_write_per_byte,
+            storage_delete_per_byte,
+            verify_tx_sig,
+            ibc_action_validate,
+            ibc_action_execute,
+            masp_verify_sig,
+            masp_fixed_spend,
+            masp_variable_spend,
+            masp_fixed_convert,
+            masp_variable_convert,
+            masp_fixed_output,
+            masp_variable_output,
+            masp_spend_check,
+            masp_convert_check,
+            masp_output_check,
+            masp_final_check,
+        } = self;
+        [
+            compile_per_byte,
+            wasm_code_validation_per_byte,
+            wrapper_tx_validation,
+            storage_occupation_per_byte,
+            network_transmission_per_byte,
+            memory_access_per_byte,
+            storage_access_per_byte,
+            storage_write_per_byte,
+            storage_delete_per_byte,
+            verify_tx_sig,
+            ibc_action_validate,
+            ibc_action_execute,
+            masp_verify_sig,
+            masp_fixed_spend,
+            masp_variable_spend,
+            masp_fixed_convert,
+            masp_variable_convert,
+            masp_fixed_output,
+            masp_variable_output,
+            masp_spend_check,
+            masp_convert_check,
+            masp_output_check,
+            masp_final_check,
+        ]
+        .into_iter()
+        .all(|cost| *cost > 0)
+    }
+}
+
 /// Representation of tracking gas in sub-units.
 ///
 /// This effectively decouples gas metering from fee payment, allowing higher
@@ -343,11 +506,14 @@ pub trait GasMetering {
     /// will still be updated
     fn consume(&mut self, gas: Gas) -> Result<()>;
 
+    /// Get the gas cost schedule
+    fn gas_costs(&self) -> &GasCostSchedule;
+
     /// Add the compiling cost proportionate to the code length
     fn add_compiling_gas(&mut self, bytes_len: u64) -> Result<()> {
         self.consume(
             bytes_len
-                .checked_mul(COMPILE_GAS_PER_BYTE)
+                .checked_mul(self.gas_costs().compile_per_byte)
                 .ok_or(Error::GasOverflow)?
                 .into(),
         )
@@ -357,7 +523,7 @@ pub trait GasMetering {
     fn add_wasm_load_from_storage_gas(&mut self, bytes_len: u64) -> Result<()> {
         self.consume(
             bytes_len
-                .checked_mul(STORAGE_ACCESS_GAS_PER_BYTE)
+                .checked_mul(self.gas_costs().storage_access_per_byte)
                 .ok_or(Error::GasOverflow)?
                 .into(),
         )
@@ -367,7 +533,7 @@ pub trait GasMetering {
     fn add_wasm_validation_gas(&mut self, bytes_len: u64) -> Result<()> {
         self.consume(
             bytes_len
-                .checked_mul(WASM_CODE_VALIDATION_GAS_PER_BYTE)
+                .checked_mul(self.gas_costs().wasm_code_validation_per_byte)
                 .ok_or(Error::GasOverflow)?
                 .into(),
         )
@@ -395,13 +561,15 @@ pub trait GasMetering {
 }
 
 /// Gas metering in a transaction
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TxGasMeter {
     /// Track gas overflow
     gas_overflow: bool,
     /// The gas limit for a transaction
     pub tx_gas_limit: Gas,
     transaction_gas: Gas,
+    /// The gas cost schedule
+    gas_costs: GasCostSchedule,
 }
 
 /// Gas metering in a validity predicate
@@ -415,6 +583,8 @@ pub struct VpGasMeter {
     initial_gas: Gas,
     /// The current gas usage in the VP
     current_gas: Gas,
+    /// The gas cost schedule
+    gas_costs: GasCostSchedule,
 }
 
 impl GasMetering for TxGasMeter {
@@ -451,16 +621,31 @@ impl GasMetering for TxGasMeter {
     fn get_gas_limit(&self) -> Gas {
         self.tx_gas_limit.clone()
     }
+
+    fn gas_costs(&self) -> &GasCostSchedule {
+        &self.gas_costs
+    }
 }
 
 impl TxGasMeter {
-    /// Initialize a new Tx gas meter. Requires a gas limit for the specific
-    /// wrapper transaction
+    /// Initialize a new Tx gas meter with the default gas cost schedule.
+    /// Requires a gas limit for the specific wrapper transaction
     pub fn new(tx_gas_limit: impl Into<Gas>) -> Self {
+        Self::new_with_costs(tx_gas_limit, GasCostSchedule::default())
+    }
+
+    /// Initialize a new Tx gas meter with the gas cost schedule of the
+    /// current block. Requires a gas limit for the specific wrapper
+    /// transaction
+    pub fn new_with_costs(
+        tx_gas_limit: impl Into<Gas>,
+        gas_costs: GasCostSchedule,
+    ) -> Self {
         Self {
             gas_overflow: false,
             tx_gas_limit: tx_gas_limit.into(),
             transaction_gas: Gas::default(),
+            gas_costs,
         }
     }
 
@@ -470,15 +655,14 @@ impl TxGasMeter {
     ///  - cost of downloading (as part of the block) the transaction bytes over
     ///    the network
     pub fn add_wrapper_gas(&mut self, tx_bytes: &[u8]) -> Result<()> {
-        self.consume(WRAPPER_TX_VALIDATION_GAS.into())?;
+        self.consume(self.gas_costs.wrapper_tx_validation.into())?;
 
         let bytes_len = tx_bytes.len() as u64;
         self.consume(
-            bytes_len
-                .checked_mul(
-                    STORAGE_OCCUPATION_GAS_PER_BYTE
-                        + NETWORK_TRANSMISSION_GAS_PER_BYTE,
-                )
+            self.gas_costs
+                .storage_occupation_per_byte
+                .checked_add(self.gas_costs.network_transmission_per_byte)
+                .and_then(|cost| bytes_len.checked_mul(cost))
                 .ok_or(Error::GasOverflow)?
                 .into(),
         )
@@ -531,6 +715,10 @@ impl GasMetering for VpGasMeter {
     fn get_gas_limit(&self) -> Gas {
         self.tx_gas_limit.clone()
     }
+
+    fn gas_costs(&self) -> &GasCostSchedule {
+        &self.gas_costs
+    }
 }
 
 impl VpGasMeter {
@@ -541,6 +729,7 @@ impl VpGasMeter {
             tx_gas_limit: tx_gas_meter.tx_gas_limit.clone(),
             initial_gas: tx_gas_meter.transaction_gas.clone(),
             current_gas: Gas::default(),
+            gas_costs: tx_gas_meter.gas_costs,
         }
     }
 
@@ -566,6 +755,7 @@ mod tests {
                 gas_overflow: false,
                 tx_gas_limit: BLOCK_GAS_LIMIT.into(),
                 transaction_gas: Gas::default(),
+                gas_costs: GasCostSchedule::default(),
             };
             let mut meter = VpGasMeter::new_from_tx_meter(&tx_gas_meter);
             meter.consume(gas.into()).expect("cannot add the gas");
@@ -579,6 +769,7 @@ mod tests {
             gas_overflow: false,
             tx_gas_limit: BLOCK_GAS_LIMIT.into(),
             transaction_gas: (TX_GAS_LIMIT - 1).into(),
+            gas_costs: GasCostSchedule::default(),
         };
         let mut meter = VpGasMeter::new_from_tx_meter(&tx_gas_meter);
         assert_matches!(
@@ -595,6 +786,7 @@ mod tests {
             gas_overflow: false,
             tx_gas_limit: TX_GAS_LIMIT.into(),
             transaction_gas: (TX_GAS_LIMIT - 1).into(),
+            gas_costs: GasCostSchedule::default(),
         };
         let mut meter = VpGasMeter::new_from_tx_meter(&tx_gas_meter);
         assert_matches!(
@@ -617,6 +809,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gas_cost_schedule() {
+        assert!(GasCostSchedule::default().is_valid());
+        let free_sigs = GasCostSchedule {
+            verify_tx_sig: 0,
+            ..Default::default()
+        };
+        assert!(!free_sigs.is_valid());
+
+        // The meters charge the costs of their schedule
+        let costs = GasCostSchedule {
+            wrapper_tx_validation: 10,
+            storage_occupation_per_byte: 1,
+            network_transmission_per_byte: 2,
+            ..Default::default()
+        };
+        let mut meter = TxGasMeter::new_with_costs(TX_GAS_LIMIT, costs);
+        meter.add_wrapper_gas(&[0; 5]).expect("cannot add the gas");
+        assert_eq!(meter.get_tx_consumed_gas(), 25.into());
+        let vp_meter = VpGasMeter::new_from_tx_meter(&meter);
+        assert_eq!(vp_meter.gas_costs(), &costs);
+    }
+
     #[test]
     fn test_tx_gas_limit() {
         let mut meter = TxGasMeter::new(TX_GAS_LIMIT);
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -13,11 +13,12 @@ use serde::{Deserialize, Serialize};
 use super::validation::{
     is_valid_activation_epoch, is_valid_author_balance, is_valid_content,
     is_valid_default_proposal_data, is_valid_end_epoch,
-    is_valid_pgf_funding_data, is_valid_pgf_stewards_data,
-    is_valid_proposal_period, is_valid_start_epoch, ProposalValidation,
+    is_valid_parameter_change_data, is_valid_pgf_funding_data,
+    is_valid_pgf_stewards_data, is_valid_proposal_period, is_valid_start_epoch,
+    is_valid_upgrade_data, ProposalValidation,
 };
 use crate::parameters::GovernanceParameters;
-use crate::storage::proposal::PGFTarget;
+use crate::storage::proposal::{PGFTarget, ParameterChange, UpgradePlan};
 
 #[derive(
     Debug,
@@ -248,6 +249,128 @@ impl TryFrom<&[u8]> for PgfFundingProposal {
     }
 }
 
+/// Parameter change proposal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterChangeProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The parameter changes to apply when the proposal passes
+    pub data: BTreeSet<ParameterChange>,
+}
+
+impl ParameterChangeProposal {
+    /// Validate a parameter change proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        is_valid_start_epoch(self.proposal.voting_start_epoch, current_epoch)?;
+        is_valid_end_epoch(
+            self.proposal.voting_start_epoch,
+            self.proposal.voting_end_epoch,
+            current_epoch,
+            governance_parameters.min_proposal_voting_period,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_activation_epoch(
+            self.proposal.activation_epoch,
+            self.proposal.voting_end_epoch,
+            governance_parameters.min_proposal_grace_epochs,
+        )?;
+        is_valid_proposal_period(
+            self.proposal.voting_start_epoch,
+            self.proposal.activation_epoch,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_author_balance(
+            balance,
+            governance_parameters.min_proposal_fund,
+        )?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+        is_valid_parameter_change_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for ParameterChangeProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// Upgrade proposal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The upgrade plan scheduled when the proposal passes
+    pub data: UpgradePlan,
+}
+
+impl UpgradeProposal {
+    /// Validate an upgrade proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        is_valid_start_epoch(self.proposal.voting_start_epoch, current_epoch)?;
+        is_valid_end_epoch(
+            self.proposal.voting_start_epoch,
+            self.proposal.voting_end_epoch,
+            current_epoch,
+            governance_parameters.min_proposal_voting_period,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_activation_epoch(
+            self.proposal.activation_epoch,
+            self.proposal.voting_end_epoch,
+            governance_parameters.min_proposal_grace_epochs,
+        )?;
+        is_valid_proposal_period(
+            self.proposal.voting_start_epoch,
+            self.proposal.activation_epoch,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_author_balance(
+            balance,
+            governance_parameters.min_proposal_fund,
+        )?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+        is_valid_upgrade_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for UpgradeProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
 /// PGF stewards
 #[derive(
     Debug,
@@ -1,5 +1,6 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
+use itertools::Itertools;
 use namada_core::address::Address;
 use namada_core::arith::{self, checked};
 use namada_core::chain::Epoch;
@@ -7,6 +8,8 @@ use namada_core::token;
 use thiserror::Error;
 
 use super::onchain::{PgfFunding, StewardsUpdate};
+use crate::storage::proposal::{ParameterChange, UpgradePlan};
+use crate::vp::{MAX_PARAMETER_CHANGES, MAX_UPGRADE_NAME_LEN};
 
 /// This enum represents proposal data
 #[derive(Debug, Error)]
@@ -63,6 +66,12 @@ pub enum ProposalValidation {
     /// The PGF funding data is not valid
     #[error("invalid proposal extra data: cannot be empty.")]
     InvalidPgfFundingExtraData,
+    /// The parameter change data is not valid
+    #[error("Invalid proposal extra data: {0}")]
+    InvalidParameterChangeExtraData(String),
+    /// The upgrade data is not valid
+    #[error("Invalid proposal extra data: {0}")]
+    InvalidUpgradeExtraData(String),
     #[error("Arithmetic {0}.")]
     Arith(#[from] arith::Error),
 }
@@ -229,3 +238,53 @@ pub fn is_valid_pgf_funding_data(
         Err(ProposalValidation::InvalidPgfFundingExtraData)
     }
 }
+
+pub fn is_valid_parameter_change_data(
+    data: &BTreeSet<ParameterChange>,
+) -> Result<(), ProposalValidation> {
+    if data.is_empty() {
+        return Err(ProposalValidation::InvalidParameterChangeExtraData(
+            "cannot be empty.".to_string(),
+        ));
+    }
+    if data.len() > MAX_PARAMETER_CHANGES {
+        return Err(ProposalValidation::InvalidParameterChangeExtraData(
+            format!(
+                "cannot contain more than {MAX_PARAMETER_CHANGES} changes."
+            ),
+        ));
+    }
+    if let Some(change) = data.iter().find(|change| !change.is_valid()) {
+        return Err(ProposalValidation::InvalidParameterChangeExtraData(
+            format!("value out of range in \"{change}\"."),
+        ));
+    }
+    if let Some((change, _)) = data
+        .iter()
+        .tuple_combinations()
+        .find(|(a, b)| a.is_same_parameter(b))
+    {
+        return Err(ProposalValidation::InvalidParameterChangeExtraData(
+            format!("parameter changed more than once in \"{change}\"."),
+        ));
+    }
+    Ok(())
+}
+
+pub fn is_valid_upgrade_data(
+    data: &UpgradePlan,
+) -> Result<(), ProposalValidation> {
+    if !data.is_valid_name() {
+        return Err(ProposalValidation::InvalidUpgradeExtraData(format!(
+            "the name \"{}\" must be a non-empty file name of at most \
+             {MAX_UPGRADE_NAME_LEN} alphanumeric, '-', '_' or '.' characters.",
+            data.name
+        )));
+    }
+    if data.height.0 == 0 {
+        return Err(ProposalValidation::InvalidUpgradeExtraData(
+            "the upgrade height must be set.".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -10,8 +10,10 @@ use namada_core::encode;
 use namada_core::ibc::PGFIbcTarget;
 use namada_events::extend::{ComposeEvent, Height};
 use namada_events::{EmitEvents, EventLevel};
-use namada_state::{Key, Result, State, StateRead, StorageRead, StorageWrite};
-use namada_systems::{proof_of_stake, trans_token as token};
+use namada_state::{
+    Error, Key, Result, State, StateRead, StorageRead, StorageWrite,
+};
+use namada_systems::{ibc, parameters, proof_of_stake, trans_token as token};
 use namada_tx::data::TxType;
 use namada_tx::{Code, Data, Tx};
 
@@ -20,7 +22,8 @@ use crate::pgf::storage::keys as pgf_keys;
 use crate::pgf::storage::steward::StewardDetail;
 use crate::pgf::{storage as pgf_storage, ADDRESS as PGF_ADDRESS};
 use crate::storage::proposal::{
-    AddRemove, PGFAction, PGFTarget, ProposalType, StoragePgfFunding,
+    AddRemove, PGFAction, PGFTarget, ParameterChange, ProposalType,
+    StoragePgfFunding, UpgradePlan,
 };
 use crate::storage::{keys, load_proposals};
 use crate::utils::{
@@ -31,7 +34,7 @@ use crate::{storage, ProposalVote, ADDRESS as GOV_ADDRESS};
 /// Apply governance updates for a block. On a new epoch, this will look for
 /// proposals to tally completed proposals and execute accepted proposals.
 #[allow(clippy::too_many_arguments)]
-pub fn finalize_block<S, Token, PoS, FnTx, FnIbcTransfer>(
+pub fn finalize_block<S, Token, PoS, Params, Ibc, FnTx, FnIbcTransfer>(
     state: &mut S,
     events: &mut impl EmitEvents,
     current_epoch: Epoch,
@@ -42,7 +45,9 @@ pub fn finalize_block<S, Token, PoS, FnTx, FnIbcTransfer>(
 where
     S: StateRead + State,
     Token: token::Read<S> + token::Write<S> + token::Events<S>,
-    PoS: proof_of_stake::Read<S>,
+    PoS: proof_of_stake::Write<S>,
+    Params: parameters::Write<S>,
+    Ibc: ibc::Write<S>,
     FnTx: FnMut(&Tx, &mut S) -> Result<bool>,
     FnIbcTransfer: Fn(&mut S, &Address, &Address, &PGFIbcTarget) -> Result<()>,
 {
@@ -51,6 +56,8 @@ where
             S,
             Token,
             PoS,
+            Params,
+            Ibc,
             FnTx,
             FnIbcTransfer,
         >(state, events, current_epoch, dispatch_tx, transfer_over_ibc)?;
@@ -58,7 +65,15 @@ where
     Ok(())
 }
 
-fn load_and_execute_governance_proposals<S, Token, PoS, FnTx, FnIbcTransfer>(
+fn load_and_execute_governance_proposals<
+    S,
+    Token,
+    PoS,
+    Params,
+    Ibc,
+    FnTx,
+    FnIbcTransfer,
+>(
     state: &mut S,
     events: &mut impl EmitEvents,
     current_epoch: Epoch,
@@ -68,22 +83,34 @@ fn load_and_execute_governance_proposals<S, Token, PoS, FnTx, FnIbcTransfer>(
 where
     S: StateRead + State,
     Token: token::Read<S> + token::Write<S> + token::Events<S>,
-    PoS: proof_of_stake::Read<S>,
+    PoS: proof_of_stake::Write<S>,
+    Params: parameters::Write<S>,
+    Ibc: ibc::Write<S>,
     FnTx: FnMut(&Tx, &mut S) -> Result<bool>,
     FnIbcTransfer: Fn(&mut S, &Address, &Address, &PGFIbcTarget) -> Result<()>,
 {
     let proposal_ids = load_proposals(state, current_epoch)?;
 
-    execute_governance_proposals::<S, Token, PoS, FnTx, FnIbcTransfer>(
-        state,
-        events,
-        proposal_ids,
-        dispatch_tx,
-        transfer_over_ibc,
-    )
+    execute_governance_proposals::<
+        S,
+        Token,
+        PoS,
+        Params,
+        Ibc,
+        FnTx,
+        FnIbcTransfer,
+    >(state, events, proposal_ids, dispatch_tx, transfer_over_ibc)
 }
 
-fn execute_governance_proposals<S, Token, PoS, FnTx, FnIbcTransfer>(
+fn execute_governance_proposals<
+    S,
+    Token,
+    PoS,
+    Params,
+    Ibc,
+    FnTx,
+    FnIbcTransfer,
+>(
     state: &mut S,
     events: &mut impl EmitEvents,
     proposal_ids: BTreeSet<u64>,
@@ -93,17 +120,22 @@ fn execute_governance_proposals<S, Token, PoS, FnTx, FnIbcTransfer>(
 where
     S: StateRead + State,
     Token: token::Read<S> + token::Write<S> + token::Events<S>,
-    PoS: proof_of_stake::Read<S>,
+    PoS: proof_of_stake::Write<S>,
+    Params: parameters::Write<S>,
+    Ibc: ibc::Write<S>,
     FnTx: FnMut(&Tx, &mut S) -> Result<bool>,
     FnIbcTransfer: Fn(&mut S, &Address, &Address, &PGFIbcTarget) -> Result<()>,
 {
     for id in proposal_ids {
         let proposal_funds_key = keys::get_funds_key(id);
+        let proposal_start_epoch_key = keys::get_voting_start_epoch_key(id);
         let proposal_end_epoch_key = keys::get_voting_end_epoch_key(id);
         let proposal_type_key = keys::get_proposal_type_key(id);
         let proposal_author_key = keys::get_author_key(id);
 
         let funds: token::Amount = force_read(state, &proposal_funds_key)?;
+        let proposal_start_epoch: Epoch =
+            force_read(state, &proposal_start_epoch_key)?;
         let proposal_end_epoch: Epoch =
             force_read(state, &proposal_end_epoch_key)?;
         let proposal_type: ProposalType =
@@ -115,16 +147,29 @@ where
         let total_active_voting_power = PoS::total_active_stake::<
             crate::Store<_>,
         >(state, proposal_end_epoch)?;
+        let lock_voting_power = compute_lock_voting_power(
+            state,
+            proposal_start_epoch,
+            proposal_end_epoch,
+        )?;
+        let total_voting_power =
+            token::Amount::sum(lock_voting_power.values().copied())
+                .and_then(|total_lock_voting_power| {
+                    total_active_voting_power
+                        .checked_add(total_lock_voting_power)
+                })
+                .expect("The total voting power must not overflow");
 
         let tally_type = TallyType::from(proposal_type.clone(), is_steward);
-        let votes =
-            compute_proposal_votes::<S, PoS>(state, id, proposal_end_epoch)?;
-        let proposal_result = compute_proposal_result(
-            votes,
-            total_active_voting_power,
-            tally_type,
-        )
-        .expect("Proposal result calculation must not over/underflow");
+        let votes = compute_proposal_votes::<S, PoS>(
+            state,
+            id,
+            proposal_end_epoch,
+            lock_voting_power,
+        )?;
+        let proposal_result =
+            compute_proposal_result(votes, total_voting_power, tally_type)
+                .expect("Proposal result calculation must not over/underflow");
         storage::write_proposal_result(state, id, proposal_result)?;
 
         let transfer_address = match proposal_result.result {
@@ -174,6 +219,45 @@ where
 
                         GovernanceEvent::passed_proposal(id, false, false)
                     }
+                    ProposalType::ParameterChange(changes) => {
+                        let result = execute_parameter_change_proposal::<
+                            S,
+                            PoS,
+                            Params,
+                            Ibc,
+                        >(state, changes)?;
+                        tracing::info!(
+                            "Governance proposal #{} for parameter changes \
+                             has been executed. {}.",
+                            id,
+                            if result {
+                                "State changes have been applied successfully"
+                            } else {
+                                "FAILURE trying to apply the state changes - \
+                                 no state change occurred"
+                            }
+                        );
+
+                        GovernanceEvent::passed_proposal(id, false, false)
+                    }
+                    ProposalType::Upgrade(plan) => {
+                        let plan_info = plan.to_string();
+                        let result = execute_upgrade_proposal(state, plan)?;
+                        tracing::info!(
+                            "Governance proposal #{} for upgrade {} has been \
+                             executed. {}.",
+                            id,
+                            plan_info,
+                            if result {
+                                "The upgrade plan has been scheduled"
+                            } else {
+                                "FAILURE trying to schedule the upgrade plan - \
+                                 the upgrade height has already been reached"
+                            }
+                        );
+
+                        GovernanceEvent::passed_proposal(id, false, false)
+                    }
                     ProposalType::PGFPayment(payments) => {
                         let native_token = state.get_native_token()?;
                         execute_pgf_funding_proposal::<S, Token, FnIbcTransfer>(
@@ -277,10 +361,39 @@ where
     Ok(())
 }
 
+/// Get the lock-weighted voting power of the locked tokens eligible for the
+/// tally of a proposal, keyed by their owner. Empty if lock-weighted voting
+/// is not enabled.
+fn compute_lock_voting_power<S>(
+    storage: &S,
+    voting_start_epoch: Epoch,
+    voting_end_epoch: Epoch,
+) -> Result<HashMap<Address, VotePower>>
+where
+    S: StorageRead,
+{
+    let mut lock_voting_power: HashMap<Address, VotePower> = HashMap::default();
+    let Some(params) = storage::get_vote_lock_params(storage)? else {
+        return Ok(lock_voting_power);
+    };
+    for (owner, lock) in storage::get_vote_locks(storage)? {
+        if !lock.is_eligible(voting_start_epoch, voting_end_epoch) {
+            continue;
+        }
+        let voting_power =
+            params.voting_power(lock.amount, lock.lock_epochs()).ok_or(
+                Error::new_const("The lock-weighted voting power overflowed"),
+            )?;
+        lock_voting_power.insert(owner, voting_power);
+    }
+    Ok(lock_voting_power)
+}
+
 fn compute_proposal_votes<S, PoS>(
     storage: &S,
     proposal_id: u64,
     epoch: Epoch,
+    lock_voting_power: HashMap<Address, VotePower>,
 ) -> Result<ProposalVotes>
 where
     S: StorageRead,
@@ -352,11 +465,74 @@ where
         }
     }
 
+    let account_votes =
+        storage::get_proposal_account_votes(storage, proposal_id)?;
+    let vote_delegates = storage::get_vote_delegates(storage)?;
+
+    // Apply the votes of the delegates to the bonds of the delegators that
+    // didn't vote. A validator's own stake cannot be delegated.
+    for (delegator, delegate) in &vote_delegates {
+        if account_votes.contains_key(delegator)
+            || delegators_vote.contains_key(delegator)
+            || PoS::is_validator(storage, delegator)?
+        {
+            continue;
+        }
+        let Some(vote) = account_votes.get(delegate) else {
+            continue;
+        };
+        for validator in PoS::delegation_validators(storage, delegator, epoch)?
+        {
+            let is_active_validator = if let Some(is_active_validator) =
+                validator_cache.get(&validator)
+            {
+                *is_active_validator
+            } else {
+                let is_active_validator =
+                    PoS::is_active_validator::<crate::Store<_>>(
+                        storage, &validator, epoch,
+                    )?;
+                validator_cache.insert(validator.clone(), is_active_validator);
+                is_active_validator
+            };
+            if !is_active_validator {
+                continue;
+            }
+
+            let delegator_stake = PoS::bond_amount::<crate::Store<_>>(
+                storage, &validator, delegator, epoch,
+            );
+            if let Ok(stake) = delegator_stake {
+                delegators_vote.insert(delegator.clone(), vote.clone());
+                delegator_voting_power
+                    .entry(delegator.clone())
+                    .or_default()
+                    .insert(validator, stake);
+            }
+        }
+    }
+
+    // The locked tokens vote with their owner's vote or, if the owner didn't
+    // vote, with the vote of its delegate
+    let mut lock_votes: HashMap<Address, ProposalVote> = HashMap::default();
+    for owner in lock_voting_power.keys() {
+        let vote = account_votes.get(owner).or_else(|| {
+            vote_delegates
+                .get(owner)
+                .and_then(|delegate| account_votes.get(delegate))
+        });
+        if let Some(vote) = vote {
+            lock_votes.insert(owner.clone(), vote.clone());
+        }
+    }
+
     Ok(ProposalVotes {
         validators_vote,
         validator_voting_power,
         delegators_vote,
         delegator_voting_power,
+        lock_votes,
+        lock_voting_power,
     })
 }
 
@@ -433,6 +609,99 @@ where
     Ok(true)
 }
 
+fn execute_parameter_change_proposal<S, PoS, Params, Ibc>(
+    state: &mut S,
+    changes: BTreeSet<ParameterChange>,
+) -> Result<bool>
+where
+    S: StateRead + State,
+    PoS: proof_of_stake::Write<S>,
+    Params: parameters::Write<S>,
+    Ibc: ibc::Write<S>,
+{
+    // The changes are validated when the proposal is submitted, but check them
+    // again before writing anything
+    if !changes.iter().all(ParameterChange::is_valid) {
+        return Ok(false);
+    }
+
+    // Move the writes done so far to the batch, such that the changes are
+    // written to a new tx log that can be dropped if any of them fails. The
+    // events of the current tx are not carried over to the batch, so they're
+    // set aside in the meantime.
+    let events = state.write_log_mut().take_events();
+    state.write_log_mut().commit_tx_to_batch();
+
+    let result = apply_parameter_changes::<S, PoS, Params, Ibc>(state, changes);
+
+    let write_log = state.write_log_mut();
+    match &result {
+        Ok(()) => write_log.commit_tx_to_batch(),
+        Err(err) => {
+            tracing::warn!(
+                "Error applying governance parameter changes: {err}"
+            );
+            write_log.drop_tx();
+        }
+    }
+    write_log.commit_batch_only();
+    for event in events {
+        write_log.emit_event(event);
+    }
+
+    Ok(result.is_ok())
+}
+
+/// Write all the given parameter changes, stopping at the first one that
+/// fails.
+fn apply_parameter_changes<S, PoS, Params, Ibc>(
+    storage: &mut S,
+    changes: BTreeSet<ParameterChange>,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    PoS: proof_of_stake::Write<S>,
+    Params: parameters::Write<S>,
+    Ibc: ibc::Write<S>,
+{
+    for change in changes {
+        match change {
+            ParameterChange::Protocol(change) => {
+                Params::apply_change(storage, &change)?
+            }
+            ParameterChange::Pos(change) => {
+                PoS::apply_param_change(storage, &change)?
+            }
+            ParameterChange::Governance(change) => change.apply(storage)?,
+            ParameterChange::Pgf(change) => change.apply(storage)?,
+            ParameterChange::Ibc(change) => {
+                Ibc::apply_param_change(storage, &change)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_upgrade_proposal<S>(
+    storage: &mut S,
+    plan: UpgradePlan,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+{
+    // The upgrade height is validated when the proposal is submitted, but the
+    // chain may have reached it since then
+    let current_height = storage.get_block_height()?;
+    if plan.height <= current_height {
+        return Ok(false);
+    }
+
+    storage::write_upgrade_plan(storage, plan)?;
+
+    Ok(true)
+}
+
 fn execute_pgf_funding_proposal<S, Token, FnIbcTransfer>(
     storage: &mut S,
     token: &Address,
@@ -20,6 +20,7 @@
 use std::marker::PhantomData;
 
 use namada_core::address::{self, Address};
+use namada_core::storage::Key;
 
 /// governance CLI structures
 pub mod cli;
@@ -40,8 +41,13 @@ use namada_state::{StorageRead, StorageWrite};
 pub use namada_systems::governance::*;
 use parameters::GovernanceParameters;
 pub use storage::proposal::{InitProposalData, ProposalType, VoteProposalData};
-pub use storage::vote::ProposalVote;
-pub use storage::{init_proposal, is_proposal_accepted, vote_proposal};
+pub use storage::vote::{
+    DelegateVoteData, LockVotingTokensData, ProposalVote, VoteLock,
+};
+pub use storage::{
+    delegate_vote, init_proposal, is_proposal_accepted, lock_voting_tokens,
+    unlock_voting_tokens, vote_proposal,
+};
 
 /// The governance internal address
 pub const ADDRESS: Address = address::GOV;
@@ -50,6 +56,28 @@ pub const ADDRESS: Address = address::GOV;
 #[derive(Debug)]
 pub struct Store<S>(PhantomData<S>);
 
+impl<S> Keys for Store<S> {
+    fn account_vote_key(proposal_id: u64, voter: &Address) -> Key {
+        storage::keys::get_account_vote_key(proposal_id, voter)
+    }
+
+    fn delegation_vote_key(
+        proposal_id: u64,
+        voter: &Address,
+        validator: &Address,
+    ) -> Key {
+        storage::keys::get_vote_proposal_key(
+            proposal_id,
+            voter.clone(),
+            validator.clone(),
+        )
+    }
+
+    fn is_any_vote_key(key: &Key) -> Option<(u64, &Address)> {
+        storage::keys::is_any_vote_key(key)
+    }
+}
+
 impl<S> Read<S> for Store<S>
 where
     S: StorageRead,
@@ -1,10 +1,12 @@
-use namada_core::borsh::{BorshDeserialize, BorshSerialize};
+use std::fmt::Display;
+
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::token;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
 use namada_migrations::*;
 use namada_state::{Result, StorageRead, StorageWrite};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::storage::keys as goverance_storage;
 
@@ -107,3 +109,181 @@ impl GovernanceParameters {
         storage.write(&counter_key, u64::MIN)
     }
 }
+
+/// The parameters of the optional lock-weighted voting. Tokens locked in the
+/// governance account for some number of epochs vote with their amount
+/// multiplied by `1 + lock_epochs / epochs_per_multiplier`, capped at
+/// `max_multiplier`. Lock-weighted voting is disabled until these parameters
+/// are set by a governance proposal.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct VoteLockParams {
+    /// The number of lock epochs that earn one more multiple of the locked
+    /// amount
+    pub epochs_per_multiplier: u64,
+    /// The maximum multiplier of the locked amount
+    pub max_multiplier: u64,
+}
+
+impl VoteLockParams {
+    /// Check that the parameters are valid - both of them must not be zero.
+    pub fn is_valid(&self) -> bool {
+        self.epochs_per_multiplier > 0 && self.max_multiplier > 0
+    }
+
+    /// Get the multiplier of tokens locked for the given number of epochs
+    pub fn multiplier(&self, lock_epochs: u64) -> u64 {
+        lock_epochs
+            .checked_div(self.epochs_per_multiplier)
+            .unwrap_or_default()
+            .saturating_add(1)
+            .min(self.max_multiplier)
+    }
+
+    /// Get the voting power of the amount locked for the given number of
+    /// epochs. Returns `None` on overflow.
+    pub fn voting_power(
+        &self,
+        amount: token::Amount,
+        lock_epochs: u64,
+    ) -> Option<token::Amount> {
+        amount.checked_mul(self.multiplier(lock_epochs))
+    }
+}
+
+impl Display for VoteLockParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ epochs_per_multiplier = {}, max_multiplier = {} }}",
+            self.epochs_per_multiplier, self.max_multiplier
+        )
+    }
+}
+
+/// A change of a single governance parameter that can be applied by a
+/// governance proposal. The maximum proposal period is not included, because
+/// it's also used by PoS to determine how long its data must be kept.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum GovernanceParamChange {
+    /// Set the minimum amount of locked funds
+    MinProposalFund(token::Amount),
+    /// Set the maximum length for proposal code in bytes
+    MaxProposalCodeSize(u64),
+    /// Set the minimum number of epochs between the proposal end epoch and
+    /// start epoch
+    MinProposalVotingPeriod(u64),
+    /// Set the maximum number of characters for proposal content
+    MaxProposalContentSize(u64),
+    /// Set the minimum number of epochs between the end and activation epochs
+    MinProposalGraceEpochs(u64),
+    /// Set the maximum number of epochs between current epoch and start epoch
+    MaxProposalLatency(u64),
+    /// Enable lock-weighted voting or update its parameters
+    VoteLock(VoteLockParams),
+}
+
+impl GovernanceParamChange {
+    /// Check that the new value is in its valid range. The minimum voting
+    /// period and the maximum content size must not be zero and the
+    /// lock-weighted voting parameters must be valid.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::MinProposalVotingPeriod(value)
+            | Self::MaxProposalContentSize(value) => *value > 0,
+            Self::VoteLock(params) => params.is_valid(),
+            Self::MinProposalFund(_)
+            | Self::MaxProposalCodeSize(_)
+            | Self::MinProposalGraceEpochs(_)
+            | Self::MaxProposalLatency(_) => true,
+        }
+    }
+
+    /// Write the new parameter value into storage
+    pub fn apply<S>(&self, storage: &mut S) -> Result<()>
+    where
+        S: StorageRead + StorageWrite,
+    {
+        match self {
+            Self::MinProposalFund(value) => storage
+                .write(&goverance_storage::get_min_proposal_fund_key(), value),
+            Self::MaxProposalCodeSize(value) => storage.write(
+                &goverance_storage::get_max_proposal_code_size_key(),
+                value,
+            ),
+            Self::MinProposalVotingPeriod(value) => storage.write(
+                &goverance_storage::get_min_proposal_voting_period_key(),
+                value,
+            ),
+            Self::MaxProposalContentSize(value) => storage.write(
+                &goverance_storage::get_max_proposal_content_key(),
+                value,
+            ),
+            Self::MinProposalGraceEpochs(value) => storage.write(
+                &goverance_storage::get_min_proposal_grace_epochs_key(),
+                value,
+            ),
+            Self::MaxProposalLatency(value) => storage.write(
+                &goverance_storage::get_max_proposal_latency_key(),
+                value,
+            ),
+            Self::VoteLock(params) => storage
+                .write(&goverance_storage::get_vote_lock_params_key(), params),
+        }
+    }
+}
+
+impl Display for GovernanceParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MinProposalFund(value) => {
+                write!(f, "min_proposal_fund = {}", value.to_string_native())
+            }
+            Self::MaxProposalCodeSize(value) => {
+                write!(f, "max_proposal_code_size = {value}")
+            }
+            Self::MinProposalVotingPeriod(value) => {
+                write!(f, "min_proposal_voting_period = {value}")
+            }
+            Self::MaxProposalContentSize(value) => {
+                write!(f, "max_proposal_content_size = {value}")
+            }
+            Self::MinProposalGraceEpochs(value) => {
+                write!(f, "min_proposal_grace_epochs = {value}")
+            }
+            Self::MaxProposalLatency(value) => {
+                write!(f, "max_proposal_latency = {value}")
+            }
+            Self::VoteLock(params) => write!(f, "vote_lock = {params}"),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::BTreeSet;
+use std::fmt::Display;
 
 use namada_core::address::Address;
-use namada_core::borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::dec::Dec;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
@@ -83,3 +84,77 @@ impl PgfParameters {
             .write(&maximum_number_of_stewards_key, maximum_number_of_stewards)
     }
 }
+
+/// A change of a single PGF parameter that can be applied by a governance
+/// proposal. The stewards are updated with PGF steward proposals instead.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum PgfParamChange {
+    /// Set the pgf funding inflation rate
+    PgfInflationRate(Dec),
+    /// Set the pgf stewards inflation rate
+    StewardsInflationRate(Dec),
+    /// Set the maximum number of pgf stewards at once
+    MaximumNumberOfStewards(u64),
+}
+
+impl PgfParamChange {
+    /// Check that the new value is in its valid range. The inflation rates
+    /// must be between `0` and `1`.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::PgfInflationRate(rate)
+            | Self::StewardsInflationRate(rate) => {
+                !rate.is_negative() && *rate <= Dec::one()
+            }
+            Self::MaximumNumberOfStewards(_) => true,
+        }
+    }
+
+    /// Write the new parameter value into storage
+    pub fn apply<S>(&self, storage: &mut S) -> Result<()>
+    where
+        S: StorageRead + StorageWrite,
+    {
+        match self {
+            Self::PgfInflationRate(value) => {
+                storage.write(&pgf_storage::get_pgf_inflation_rate_key(), value)
+            }
+            Self::StewardsInflationRate(value) => storage
+                .write(&pgf_storage::get_steward_inflation_rate_key(), value),
+            Self::MaximumNumberOfStewards(value) => storage.write(
+                &pgf_storage::get_maximum_number_of_pgf_steward_key(),
+                value,
+            ),
+        }
+    }
+}
+
+impl Display for PgfParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PgfInflationRate(value) => {
+                write!(f, "pgf_inflation_rate = {value}")
+            }
+            Self::StewardsInflationRate(value) => {
+                write!(f, "stewards_inflation_rate = {value}")
+            }
+            Self::MaximumNumberOfStewards(value) => {
+                write!(f, "maximum_number_of_stewards = {value}")
+            }
+        }
+    }
+}
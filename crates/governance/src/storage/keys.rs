@@ -28,6 +28,11 @@ struct Keys {
     counter: &'static str,
     pending: &'static str,
     result: &'static str,
+    upgrade_plan: &'static str,
+    account_vote: &'static str,
+    vote_delegate: &'static str,
+    vote_lock: &'static str,
+    vote_lock_params: &'static str,
 }
 
 /// Check if key is inside governance address space
@@ -55,6 +60,86 @@ pub fn is_vote_key(key: &Key) -> bool {
     }
 }
 
+/// Check if a key is an account vote key
+pub fn is_account_vote_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(account_vote),
+            DbKeySeg::AddressSeg(_voter),
+        ] if addr == &ADDRESS
+            && prefix == Keys::VALUES.proposal
+            && account_vote == Keys::VALUES.account_vote =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Check if a key is either a vote key or an account vote key and return the
+/// proposal ID and the voter address
+pub fn is_any_vote_key(key: &Key) -> Option<(u64, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(vote),
+            DbKeySeg::AddressSeg(_validator_address),
+            DbKeySeg::AddressSeg(voter),
+        ] if addr == &ADDRESS
+            && prefix == Keys::VALUES.proposal
+            && vote == Keys::VALUES.vote =>
+        {
+            id.parse::<u64>().ok().map(|id| (id, voter))
+        }
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(account_vote),
+            DbKeySeg::AddressSeg(voter),
+        ] if addr == &ADDRESS
+            && prefix == Keys::VALUES.proposal
+            && account_vote == Keys::VALUES.account_vote =>
+        {
+            id.parse::<u64>().ok().map(|id| (id, voter))
+        }
+        _ => None,
+    }
+}
+
+/// Check if a key is a vote delegate key and return the delegator address
+pub fn is_vote_delegate_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(delegator),
+        ] if addr == &ADDRESS && prefix == Keys::VALUES.vote_delegate => {
+            Some(delegator)
+        }
+        _ => None,
+    }
+}
+
+/// Check if a key is a vote lock key and return the owner address
+pub fn is_vote_lock_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(owner),
+        ] if addr == &ADDRESS && prefix == Keys::VALUES.vote_lock => {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
 /// Check if key is author key
 pub fn is_author_key(key: &Key) -> bool {
     match &key.segments[..] {
@@ -271,6 +356,15 @@ pub fn is_min_grace_epochs_key(key: &Key) -> bool {
                     && min_grace_epochs_param == Keys::VALUES.min_grace_epochs)
 }
 
+/// Check if key is a vote lock parameters key
+pub fn is_vote_lock_params_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(vote_lock_params),
+                ] if addr == &ADDRESS
+                    && vote_lock_params == Keys::VALUES.vote_lock_params)
+}
+
 /// Check if key is parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
     is_min_proposal_fund_key(key)
@@ -279,6 +373,7 @@ pub fn is_parameter_key(key: &Key) -> bool {
         || is_min_proposal_voting_period_key(key)
         || is_max_proposal_period_key(key)
         || is_min_grace_epochs_key(key)
+        || is_vote_lock_params_key(key)
 }
 
 /// Check if key is start epoch or end epoch key
@@ -349,6 +444,48 @@ pub fn get_counter_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get key of the upgrade plan
+pub fn get_upgrade_plan_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.upgrade_plan.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get key of the lock-weighted voting parameters
+pub fn get_vote_lock_params_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.vote_lock_params.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the vote delegates prefix key
+pub fn get_vote_delegate_prefix_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.vote_delegate.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get key of the vote delegate of a delegator
+pub fn get_vote_delegate_key(delegator: &Address) -> Key {
+    get_vote_delegate_prefix_key()
+        .push(delegator)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the vote locks prefix key
+pub fn get_vote_lock_prefix_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.vote_lock.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get key of the vote lock of an owner
+pub fn get_vote_lock_key(owner: &Address) -> Key {
+    get_vote_lock_prefix_key()
+        .push(owner)
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get key of proposal content
 pub fn get_content_key(id: u64) -> Key {
     proposal_prefix()
@@ -459,6 +596,23 @@ pub fn get_vote_proposal_key(
         .expect("Cannot obtain a storage key")
 }
 
+/// Get proposal account vote prefix key
+pub fn get_account_vote_prefix_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.account_vote.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the vote cast by an account on a proposal, independent of
+/// its delegations
+pub fn get_account_vote_key(id: u64, voter: &Address) -> Key {
+    get_account_vote_prefix_key(id)
+        .push(voter)
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get the proposal execution key
 pub fn get_proposal_execution_key(id: u64) -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -530,3 +684,14 @@ pub fn get_voter_address(key: &Key) -> Option<&Address> {
         None => None,
     }
 }
+
+/// Get voter address from an account vote key
+pub fn get_account_voter_address(key: &Key) -> Option<&Address> {
+    match key.get_at(4) {
+        Some(addr) => match addr {
+            DbKeySeg::AddressSeg(res) => Some(res),
+            DbKeySeg::StringSeg(_) => None,
+        },
+        None => None,
+    }
+}
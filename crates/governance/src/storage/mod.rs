@@ -12,18 +12,19 @@ use std::collections::{BTreeMap, BTreeSet};
 use namada_core::address::Address;
 use namada_core::borsh::BorshDeserialize;
 use namada_core::chain::Epoch;
-use namada_core::collections::HashSet;
+use namada_core::collections::{HashMap, HashSet};
 use namada_core::hash::Hash;
 use namada_core::token;
 use namada_state::{iter_prefix, Error, Result, StorageRead, StorageWrite};
 use namada_systems::trans_token;
 
-use crate::parameters::GovernanceParameters;
+use crate::parameters::{GovernanceParameters, VoteLockParams};
 use crate::storage::keys as governance_keys;
 use crate::storage::proposal::{
-    InitProposalData, ProposalType, StorageProposal, VoteProposalData,
+    InitProposalData, ProposalType, StorageProposal, UpgradePlan,
+    VoteProposalData,
 };
-use crate::storage::vote::ProposalVote;
+use crate::storage::vote::{ProposalVote, VoteLock};
 use crate::utils::{ProposalResult, Vote};
 use crate::ADDRESS as governance_address;
 
@@ -110,7 +111,9 @@ where
     Ok(proposal_id)
 }
 
-/// A proposal vote transaction.
+/// A proposal vote transaction. Besides the votes on the voter's delegations,
+/// the vote of the account itself is recorded to be applied to its locked
+/// tokens and to the accounts that delegated their vote to it.
 pub fn vote_proposal<S>(
     storage: &mut S,
     data: VoteProposalData,
@@ -119,6 +122,9 @@ pub fn vote_proposal<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    let account_vote_key =
+        governance_keys::get_account_vote_key(data.id, &data.voter);
+    storage.write(&account_vote_key, data.vote.clone())?;
     for validator in delegation_targets {
         let vote_key = governance_keys::get_vote_proposal_key(
             data.id,
@@ -130,6 +136,107 @@ where
     Ok(())
 }
 
+/// A vote delegation transaction. The delegate votes on behalf of the
+/// delegator on the proposals the delegator doesn't vote on. Passing `None`
+/// revokes the delegation.
+pub fn delegate_vote<S>(
+    storage: &mut S,
+    delegator: &Address,
+    delegate: Option<Address>,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = governance_keys::get_vote_delegate_key(delegator);
+    match delegate {
+        Some(delegate) if &delegate == delegator => Err(Error::new_alloc(
+            format!("{delegator} cannot delegate its vote to itself"),
+        )),
+        Some(delegate) => storage.write(&key, delegate),
+        None => storage.delete(&key),
+    }
+}
+
+/// A transaction to lock native tokens of the owner in the governance account
+/// for lock-weighted voting. An owner can only hold a single lock at a time.
+pub fn lock_voting_tokens<S, TransToken>(
+    storage: &mut S,
+    owner: &Address,
+    amount: token::Amount,
+    lock_epochs: u64,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    TransToken: trans_token::Write<S>,
+{
+    if get_vote_lock_params(storage)?.is_none() {
+        return Err(Error::new_const("Lock-weighted voting is not enabled"));
+    }
+    if amount.is_zero() || lock_epochs == 0 {
+        return Err(Error::new_const(
+            "The locked amount and the lock duration must not be zero",
+        ));
+    }
+    if read_vote_lock(storage, owner)?.is_some() {
+        return Err(Error::new_alloc(format!(
+            "{owner} already holds locked voting tokens"
+        )));
+    }
+    let start_epoch = storage.get_block_epoch()?;
+    let end_epoch = start_epoch
+        .checked_add(lock_epochs)
+        .ok_or(Error::new_const("The lock end epoch overflowed"))?;
+    let key = governance_keys::get_vote_lock_key(owner);
+    storage.write(
+        &key,
+        VoteLock {
+            amount,
+            start_epoch,
+            end_epoch,
+        },
+    )?;
+
+    TransToken::transfer(
+        storage,
+        &storage.get_native_token()?,
+        owner,
+        &governance_address,
+        amount,
+    )
+}
+
+/// A transaction to return the expired locked voting tokens to their owner.
+/// Returns the unlocked amount.
+pub fn unlock_voting_tokens<S, TransToken>(
+    storage: &mut S,
+    owner: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+    TransToken: trans_token::Write<S>,
+{
+    let lock = read_vote_lock(storage, owner)?.ok_or_else(|| {
+        Error::new_alloc(format!("{owner} has no locked voting tokens"))
+    })?;
+    let current_epoch = storage.get_block_epoch()?;
+    if !lock.is_unlocked(current_epoch) {
+        return Err(Error::new_alloc(format!(
+            "The voting tokens of {owner} are locked until epoch {}",
+            lock.end_epoch
+        )));
+    }
+    storage.delete(&governance_keys::get_vote_lock_key(owner))?;
+
+    TransToken::transfer(
+        storage,
+        &storage.get_native_token()?,
+        &governance_address,
+        owner,
+        lock.amount,
+    )?;
+    Ok(lock.amount)
+}
+
 /// Write the proposal result to storage.
 pub fn write_proposal_result<S>(
     storage: &mut S,
@@ -228,6 +335,92 @@ where
     Ok(votes)
 }
 
+/// Query the votes cast by accounts on a proposal, independent of their
+/// delegations
+pub fn get_proposal_account_votes<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> Result<HashMap<Address, ProposalVote>>
+where
+    S: StorageRead,
+{
+    let prefix = governance_keys::get_account_vote_prefix_key(proposal_id);
+    let mut votes = HashMap::default();
+    for entry in iter_prefix::<ProposalVote>(storage, &prefix)? {
+        let (key, vote) = entry?;
+        if let Some(voter) = governance_keys::get_account_voter_address(&key) {
+            votes.insert(voter.clone(), vote);
+        }
+    }
+    Ok(votes)
+}
+
+/// Read the address the delegator delegated its vote to, if any
+pub fn read_vote_delegate<S>(
+    storage: &S,
+    delegator: &Address,
+) -> Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    let key = governance_keys::get_vote_delegate_key(delegator);
+    storage.read(&key)
+}
+
+/// Read all the vote delegations, keyed by the delegator
+pub fn get_vote_delegates<S>(storage: &S) -> Result<HashMap<Address, Address>>
+where
+    S: StorageRead,
+{
+    let prefix = governance_keys::get_vote_delegate_prefix_key();
+    let mut delegates = HashMap::default();
+    for entry in iter_prefix::<Address>(storage, &prefix)? {
+        let (key, delegate) = entry?;
+        if let Some(delegator) = governance_keys::is_vote_delegate_key(&key) {
+            delegates.insert(delegator.clone(), delegate);
+        }
+    }
+    Ok(delegates)
+}
+
+/// Read the locked voting tokens of the owner, if any
+pub fn read_vote_lock<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<VoteLock>>
+where
+    S: StorageRead,
+{
+    let key = governance_keys::get_vote_lock_key(owner);
+    storage.read(&key)
+}
+
+/// Read all the locked voting tokens, keyed by their owner
+pub fn get_vote_locks<S>(storage: &S) -> Result<HashMap<Address, VoteLock>>
+where
+    S: StorageRead,
+{
+    let prefix = governance_keys::get_vote_lock_prefix_key();
+    let mut locks = HashMap::default();
+    for entry in iter_prefix::<VoteLock>(storage, &prefix)? {
+        let (key, lock) = entry?;
+        if let Some(owner) = governance_keys::is_vote_lock_key(&key) {
+            locks.insert(owner.clone(), lock);
+        }
+    }
+    Ok(locks)
+}
+
+/// Get the lock-weighted voting parameters. Returns `None` if lock-weighted
+/// voting is not enabled.
+pub fn get_vote_lock_params<S>(storage: &S) -> Result<Option<VoteLockParams>>
+where
+    S: StorageRead,
+{
+    let key = governance_keys::get_vote_lock_params_key();
+    storage.read(&key)
+}
+
 /// Check if an accepted proposal is being executed
 pub fn is_proposal_accepted<S>(storage: &S, tx_data: &[u8]) -> Result<bool>
 where
@@ -333,6 +526,25 @@ where
     Ok(proposal_result)
 }
 
+/// Get the upgrade plan scheduled by the last passed upgrade proposal, if any
+pub fn get_upgrade_plan<S>(storage: &S) -> Result<Option<UpgradePlan>>
+where
+    S: StorageRead,
+{
+    let key = governance_keys::get_upgrade_plan_key();
+    storage.read(&key)
+}
+
+/// Write the upgrade plan of a passed upgrade proposal, replacing the previous
+/// one
+pub fn write_upgrade_plan<S>(storage: &mut S, plan: UpgradePlan) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = governance_keys::get_upgrade_plan_key();
+    storage.write(&key, plan)
+}
+
 /// Load proposals for execution in the current epoch.
 pub fn load_proposals<S>(
     storage: &S,
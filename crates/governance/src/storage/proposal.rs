@@ -4,22 +4,27 @@ use std::fmt::Display;
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use itertools::Itertools;
 use namada_core::address::Address;
-use namada_core::chain::Epoch;
+use namada_core::chain::{BlockHeight, Epoch};
 use namada_core::hash::Hash;
 pub use namada_core::ibc::PGFIbcTarget;
 use namada_core::token;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
 use namada_migrations::*;
+use namada_systems::{ibc, parameters, proof_of_stake};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::vote::ProposalVote;
 use crate::cli::onchain::{
-    DefaultProposal, PgfAction, PgfContinuous, PgfFundingProposal, PgfRetro,
-    PgfSteward, PgfStewardProposal, StewardsUpdate,
+    DefaultProposal, ParameterChangeProposal, PgfAction, PgfContinuous,
+    PgfFundingProposal, PgfRetro, PgfSteward, PgfStewardProposal,
+    StewardsUpdate, UpgradeProposal,
 };
+use crate::parameters::GovernanceParamChange;
+use crate::pgf::parameters::PgfParamChange;
 use crate::utils::{ProposalStatus, TallyType};
+use crate::vp::MAX_UPGRADE_NAME_LEN;
 
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
@@ -169,6 +174,36 @@ impl TryFrom<PgfFundingProposal> for InitProposalData {
     }
 }
 
+impl TryFrom<ParameterChangeProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: ParameterChangeProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::ParameterChange(value.data),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            activation_epoch: value.proposal.activation_epoch,
+        })
+    }
+}
+
+impl TryFrom<UpgradeProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: UpgradeProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::Upgrade(value.data),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            activation_epoch: value.proposal.activation_epoch,
+        })
+    }
+}
+
 /// Storage struture for pgf fundings
 #[derive(
     Debug,
@@ -222,6 +257,221 @@ pub enum ProposalType {
     PGFSteward(BTreeSet<AddRemove<Address>>),
     /// PGF funding proposal
     PGFPayment(BTreeSet<PGFAction>),
+    /// Parameter change proposal
+    ParameterChange(BTreeSet<ParameterChange>),
+    /// Upgrade proposal
+    Upgrade(UpgradePlan),
+}
+
+/// A typed change of a single parameter, applied when a parameter change
+/// proposal passes
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub enum ParameterChange {
+    /// Change of a protocol parameter
+    Protocol(parameters::ParamChange),
+    /// Change of a PoS parameter
+    Pos(proof_of_stake::ParamChange),
+    /// Change of a governance parameter
+    Governance(GovernanceParamChange),
+    /// Change of a PGF parameter
+    Pgf(PgfParamChange),
+    /// Change of an IBC parameter
+    Ibc(ibc::ParamChange),
+}
+
+impl ParameterChange {
+    /// Check that the new value of the parameter is in its valid range
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ParameterChange::Protocol(change) => change.is_valid(),
+            ParameterChange::Pos(change) => change.is_valid(),
+            ParameterChange::Governance(change) => change.is_valid(),
+            ParameterChange::Pgf(change) => change.is_valid(),
+            ParameterChange::Ibc(change) => change.is_valid(),
+        }
+    }
+
+    /// Check if both of the changes apply to the same parameter
+    pub fn is_same_parameter(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match (self, other) {
+            (ParameterChange::Protocol(a), ParameterChange::Protocol(b)) => {
+                discriminant(a) == discriminant(b)
+            }
+            (ParameterChange::Pos(a), ParameterChange::Pos(b)) => {
+                discriminant(a) == discriminant(b)
+            }
+            (
+                ParameterChange::Governance(a),
+                ParameterChange::Governance(b),
+            ) => discriminant(a) == discriminant(b),
+            (ParameterChange::Pgf(a), ParameterChange::Pgf(b)) => {
+                discriminant(a) == discriminant(b)
+            }
+            (ParameterChange::Ibc(a), ParameterChange::Ibc(b)) => {
+                a.is_same_parameter(b)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for ParameterChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParameterChange::Protocol(change) => {
+                write!(f, "Protocol: {}", change)
+            }
+            ParameterChange::Pos(change) => write!(f, "PoS: {}", change),
+            ParameterChange::Governance(change) => {
+                write!(f, "Governance: {}", change)
+            }
+            ParameterChange::Pgf(change) => write!(f, "PGF: {}", change),
+            ParameterChange::Ibc(change) => write!(f, "IBC: {}", change),
+        }
+    }
+}
+
+/// A coordinated upgrade of the node binary, scheduled when an upgrade
+/// proposal passes
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub struct UpgradePlan {
+    /// The name of the upgrade
+    pub name: String,
+    /// The height of the first block that must be processed by the new
+    /// binary
+    pub height: BlockHeight,
+    /// The SHA-256 hash of the new node binary
+    pub binary_hash: Hash,
+    /// The SHA-256 hash of the DB migration applied by the new binary at the
+    /// upgrade height, if any
+    pub migration_hash: Option<Hash>,
+}
+
+impl UpgradePlan {
+    /// Check that the name of the upgrade is not empty and can be used as a
+    /// file name
+    pub fn is_valid_name(&self) -> bool {
+        !self.name.is_empty()
+            && self.name.len() <= MAX_UPGRADE_NAME_LEN
+            && !self.name.starts_with('.')
+            && self.name.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+            })
+    }
+
+    /// Get the status of the upgrade given the height of the last committed
+    /// block
+    pub fn status(&self, last_committed_height: BlockHeight) -> UpgradeStatus {
+        match self.height.0.checked_sub(last_committed_height.0) {
+            Some(remaining) if remaining > 0 => {
+                UpgradeStatus::Pending(remaining)
+            }
+            _ => UpgradeStatus::Reached,
+        }
+    }
+}
+
+impl Display for UpgradePlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at height {}, binary hash: {}",
+            self.name, self.height, self.binary_hash
+        )?;
+        match &self.migration_hash {
+            Some(hash) => write!(f, ", migration hash: {hash}"),
+            None => write!(f, ", no migration"),
+        }
+    }
+}
+
+/// The status of an upgrade plan
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum UpgradeStatus {
+    /// The upgrade height hasn't been reached. Holds the number of blocks
+    /// left to commit until the new binary is required.
+    Pending(u64),
+    /// The upgrade height has been reached and the new binary is required
+    Reached,
+}
+
+impl Display for UpgradeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpgradeStatus::Pending(1) => {
+                write!(f, "pending, the next block requires the new binary")
+            }
+            UpgradeStatus::Pending(remaining) => write!(
+                f,
+                "pending, the new binary is required in {remaining} blocks"
+            ),
+            UpgradeStatus::Reached => {
+                write!(f, "reached, the new binary is required")
+            }
+        }
+    }
+}
+
+/// An upgrade plan with its status
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpgradePlanStatus {
+    /// The upgrade plan
+    pub plan: UpgradePlan,
+    /// The status of the plan
+    pub status: UpgradeStatus,
 }
 
 /// An add or remove action for PGF
@@ -373,6 +623,16 @@ impl ProposalType {
         matches!(self, ProposalType::DefaultWithWasm(_))
     }
 
+    /// Check if the proposal type is parameter change
+    pub fn is_parameter_change(&self) -> bool {
+        matches!(self, ProposalType::ParameterChange(_))
+    }
+
+    /// Check if the proposal type is upgrade
+    pub fn is_upgrade(&self) -> bool {
+        matches!(self, ProposalType::Upgrade(_))
+    }
+
     fn format_data(&self) -> String {
         match self {
             ProposalType::DefaultWithWasm(hash) => format!("Hash: {}", &hash),
@@ -391,6 +651,14 @@ impl ProposalType {
                     .map(|action| format!("\n  {}", &action))
                     .join("")
             ),
+            ProposalType::ParameterChange(changes) => format!(
+                "Changes:{}",
+                changes
+                    .iter()
+                    .map(|change| format!("\n  {}", &change))
+                    .join("")
+            ),
+            ProposalType::Upgrade(plan) => format!("Upgrade: {}", plan),
         }
     }
 }
@@ -402,6 +670,8 @@ impl Display for ProposalType {
             ProposalType::DefaultWithWasm(_) => write!(f, "Default with Wasm"),
             ProposalType::PGFSteward(_) => write!(f, "PGF steward"),
             ProposalType::PGFPayment(_) => write!(f, "PGF funding"),
+            ProposalType::ParameterChange(_) => write!(f, "Parameter change"),
+            ProposalType::Upgrade(_) => write!(f, "Upgrade"),
         }
     }
 }
@@ -583,11 +853,12 @@ impl Display for StorageProposal {
 pub mod testing {
     use namada_core::address::testing::arb_non_internal_address;
     use namada_core::chain::testing::arb_epoch;
+    use namada_core::dec::testing::arb_non_negative_dec;
     use namada_core::hash::testing::arb_hash;
     use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
     use namada_core::token::testing::arb_amount;
     use proptest::prelude::*;
-    use proptest::{collection, prop_compose};
+    use proptest::{collection, option, prop_compose};
 
     use super::*;
     use crate::storage::vote::testing::arb_proposal_vote;
@@ -668,6 +939,76 @@ pub mod testing {
         ]
     }
 
+    /// Generate an arbitrary parameter change
+    pub fn arb_parameter_change() -> impl Strategy<Value = ParameterChange> {
+        prop_oneof![
+            any::<u64>().prop_map(|value| ParameterChange::Protocol(
+                parameters::ParamChange::MaxBlockGas(value)
+            )),
+            any::<bool>().prop_map(|value| ParameterChange::Protocol(
+                parameters::ParamChange::IsNativeTokenTransferable(value)
+            )),
+            any::<bool>().prop_map(|value| ParameterChange::Protocol(
+                parameters::ParamChange::EnforceFeePriorityOrdering(value)
+            )),
+            arb_non_negative_dec().prop_map(|value| ParameterChange::Pos(
+                proof_of_stake::ParamChange::MaxInflationRate(value)
+            )),
+            arb_amount().prop_map(|value| ParameterChange::Pos(
+                proof_of_stake::ParamChange::ValidatorStakeThreshold(value)
+            )),
+            arb_amount().prop_map(|value| ParameterChange::Governance(
+                GovernanceParamChange::MinProposalFund(value)
+            )),
+            any::<u64>().prop_map(|value| ParameterChange::Governance(
+                GovernanceParamChange::MaxProposalLatency(value)
+            )),
+            arb_non_negative_dec().prop_map(|value| ParameterChange::Pgf(
+                PgfParamChange::PgfInflationRate(value)
+            )),
+            arb_amount().prop_map(|value| ParameterChange::Ibc(
+                ibc::ParamChange::DefaultMintLimit(value)
+            )),
+            (
+                arb_non_internal_address(),
+                proptest::option::of(arb_ibc_channel_id()),
+                proptest::option::of(arb_amount()),
+                proptest::option::of(arb_amount()),
+            )
+                .prop_map(|(token, channel, inbound, outbound)| {
+                    ParameterChange::Ibc(ibc::ParamChange::RateLimit(
+                        ibc::RateLimitChange {
+                            token,
+                            channel_id: channel.map(|c| c.to_string()),
+                            limits: ibc::RateLimits {
+                                hourly_inbound: inbound,
+                                hourly_outbound: outbound,
+                                daily_inbound: inbound,
+                                daily_outbound: outbound,
+                            },
+                        },
+                    ))
+                }),
+        ]
+    }
+
+    prop_compose! {
+        /// Generate an arbitrary upgrade plan
+        pub fn arb_upgrade_plan()(
+            name in "[a-z0-9][a-z0-9-]{0,20}",
+            height in any::<u64>(),
+            binary_hash in arb_hash(),
+            migration_hash in option::of(arb_hash()),
+        ) -> UpgradePlan {
+            UpgradePlan {
+                name,
+                height: BlockHeight(height),
+                binary_hash,
+                migration_hash,
+            }
+        }
+    }
+
     /// Generate an arbitrary proposal type
     pub fn arb_proposal_type() -> impl Strategy<Value = ProposalType> {
         prop_oneof![
@@ -680,6 +1021,9 @@ pub mod testing {
             .prop_map(ProposalType::PGFSteward),
             collection::btree_set(arb_pgf_action(), 0..10)
                 .prop_map(ProposalType::PGFPayment),
+            collection::btree_set(arb_parameter_change(), 0..10)
+                .prop_map(ProposalType::ParameterChange),
+            arb_upgrade_plan().prop_map(ProposalType::Upgrade),
         ]
     }
 
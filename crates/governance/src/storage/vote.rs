@@ -1,6 +1,9 @@
 use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::address::Address;
+use namada_core::chain::Epoch;
+use namada_core::token;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
 use namada_migrations::*;
@@ -81,6 +84,96 @@ impl TryFrom<String> for ProposalVote {
     }
 }
 
+/// Tokens locked in the governance account to vote with a weight multiplied
+/// by the lock duration
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct VoteLock {
+    /// The locked amount of the native token
+    pub amount: token::Amount,
+    /// The epoch in which the tokens were locked
+    pub start_epoch: Epoch,
+    /// The epoch from which the tokens can be unlocked
+    pub end_epoch: Epoch,
+}
+
+impl VoteLock {
+    /// The number of epochs the tokens are locked for
+    pub fn lock_epochs(&self) -> u64 {
+        self.end_epoch.0.saturating_sub(self.start_epoch.0)
+    }
+
+    /// Check if the locked tokens count in the tally of a proposal. The tokens
+    /// must have been locked by the start of the voting period and stay locked
+    /// past its end.
+    pub fn is_eligible(
+        &self,
+        voting_start_epoch: Epoch,
+        voting_end_epoch: Epoch,
+    ) -> bool {
+        self.start_epoch <= voting_start_epoch
+            && self.end_epoch > voting_end_epoch
+    }
+
+    /// Check if the tokens can be unlocked in the given epoch
+    pub fn is_unlocked(&self, epoch: Epoch) -> bool {
+        epoch >= self.end_epoch
+    }
+}
+
+/// A tx data type to lock tokens for lock-weighted voting
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    Serialize,
+    Deserialize,
+)]
+pub struct LockVotingTokensData {
+    /// The owner of the locked tokens
+    pub owner: Address,
+    /// The amount of the native token to lock
+    pub amount: token::Amount,
+    /// The number of epochs to lock the tokens for
+    pub lock_epochs: u64,
+}
+
+/// A tx data type to delegate the vote of an account to another address
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSchema,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    Serialize,
+    Deserialize,
+)]
+pub struct DelegateVoteData {
+    /// The address delegating its vote
+    pub delegator: Address,
+    /// The address to vote on behalf of the delegator, or `None` to revoke
+    /// the delegation
+    pub delegate: Option<Address>,
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Testing helpers and and strategies for governance proposals
 pub mod testing {
@@ -87,6 +87,8 @@ impl TallyType {
         match (proposal_type, is_steward) {
             (ProposalType::Default, _) => TallyType::TwoFifths,
             (ProposalType::DefaultWithWasm(_), _) => TallyType::TwoFifths,
+            (ProposalType::ParameterChange(_), _) => TallyType::TwoFifths,
+            (ProposalType::Upgrade(_), _) => TallyType::TwoFifths,
             (ProposalType::PGFSteward(_), _) => TallyType::OneHalfOverOneThird,
             (ProposalType::PGFPayment(_), true) => {
                 TallyType::LessOneHalfOverOneThirdNay
@@ -289,6 +291,11 @@ pub struct ProposalVotes {
     pub delegators_vote: HashMap<Address, ProposalVote>,
     /// Map from delegator address to the corresponding validator voting power
     pub delegator_voting_power: HashMap<Address, HashMap<Address, VotePower>>,
+    /// Map from the owner of locked tokens to the vote applied to them, cast
+    /// either by the owner or by its vote delegate
+    pub lock_votes: HashMap<Address, ProposalVote>,
+    /// Map from the owner of locked tokens to their lock-weighted voting power
+    pub lock_voting_power: HashMap<Address, VotePower>,
 }
 
 impl ProposalVotes {
@@ -326,6 +333,17 @@ impl ProposalVotes {
             .insert(validator_address.clone(), voting_power);
         self.delegators_vote.insert(address.clone(), vote);
     }
+
+    /// Add vote corresponding to locked tokens
+    pub fn add_lock(
+        &mut self,
+        owner: &Address,
+        voting_power: VotePower,
+        vote: ProposalVote,
+    ) {
+        self.lock_voting_power.insert(owner.clone(), voting_power);
+        self.lock_votes.insert(owner.clone(), vote);
+    }
 }
 
 /// Compute the result of a proposal
@@ -405,6 +423,19 @@ pub fn compute_proposal_result(
         }
     }
 
+    for (owner, vote_power) in votes.lock_voting_power {
+        let vote_type = votes.lock_votes.get(&owner);
+        if let Some(vote) = vote_type {
+            if vote.is_yay() {
+                checked!(yay_voting_power += vote_power)?;
+            } else if vote.is_nay() {
+                checked!(nay_voting_power += vote_power)?;
+            } else if vote.is_abstain() {
+                checked!(abstain_voting_power += vote_power)?;
+            }
+        }
+    }
+
     let tally_result = TallyResult::new(
         &tally_type,
         yay_voting_power,
@@ -1460,6 +1491,58 @@ mod test {
         assert!(!proposal_result.two_thirds_nay_over_two_thirds_total())
     }
 
+    #[test]
+    fn test_proposal_with_locked_tokens() {
+        let mut proposal_votes = ProposalVotes::default();
+
+        let validator_address = address::testing::established_address_1();
+        let validator_voting_power = token::Amount::from_u64(100);
+        proposal_votes.add_validator(
+            &validator_address,
+            validator_voting_power,
+            ProposalVote::Nay,
+        );
+
+        let lock_owner = address::testing::established_address_2();
+        let lock_voting_power = token::Amount::from_u64(210);
+        proposal_votes.add_lock(
+            &lock_owner,
+            lock_voting_power,
+            ProposalVote::Yay,
+        );
+
+        let lock_owner_two = address::testing::established_address_3();
+        proposal_votes.add_lock(
+            &lock_owner_two,
+            token::Amount::from_u64(40),
+            ProposalVote::Abstain,
+        );
+
+        let proposal_result = compute_proposal_result(
+            proposal_votes,
+            token::Amount::from(400),
+            TallyType::TwoFifths,
+        )
+        .unwrap();
+
+        assert!(matches!(proposal_result.result, TallyResult::Passed));
+        assert_eq!(
+            proposal_result.total_yay_power,
+            token::Amount::from(210),
+            "yay"
+        );
+        assert_eq!(
+            proposal_result.total_nay_power,
+            token::Amount::from(100),
+            "nay"
+        );
+        assert_eq!(
+            proposal_result.total_abstain_power,
+            token::Amount::from(40),
+            "abstain"
+        );
+    }
+
     #[test]
     fn test_validator_voting_period() {
         // Voting period of 2 epochs
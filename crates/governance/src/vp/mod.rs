@@ -7,6 +7,7 @@ use std::collections::BTreeSet;
 use std::marker::PhantomData;
 
 use borsh::BorshDeserialize;
+use itertools::Itertools;
 use namada_core::arith::checked;
 use namada_core::booleans::{BoolResultUnitExt, ResultBoolExt};
 use namada_core::chain::Epoch;
@@ -19,7 +20,9 @@ use thiserror::Error;
 
 use self::utils::ReadType;
 use crate::address::{Address, InternalAddress};
+use crate::parameters::VoteLockParams;
 use crate::storage::proposal::{AddRemove, PGFAction, ProposalType};
+use crate::storage::vote::VoteLock;
 use crate::storage::{is_proposal_accepted, keys as gov_storage};
 use crate::utils::is_valid_validator_voting_period;
 use crate::ProposalVote;
@@ -30,6 +33,12 @@ pub const ADDRESS: Address = Address::Internal(InternalAddress::Governance);
 /// The maximum number of item in a pgf proposal
 pub const MAX_PGF_ACTIONS: usize = 20;
 
+/// The maximum number of changes in a parameter change proposal
+pub const MAX_PARAMETER_CHANGES: usize = 20;
+
+/// The maximum length of the name of an upgrade plan
+pub const MAX_UPGRADE_NAME_LEN: usize = 64;
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum VpError {
@@ -124,6 +133,42 @@ where
                             .into());
                         }
                     }
+                    GovAction::DelegateVote { delegator } => {
+                        if !verifiers.contains(&delegator) {
+                            tracing::info!(
+                                "Unauthorized GovAction::DelegateVote"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "DelegateVote",
+                                delegator,
+                            )
+                            .into());
+                        }
+                    }
+                    GovAction::LockVotingTokens { owner } => {
+                        if !verifiers.contains(&owner) {
+                            tracing::info!(
+                                "Unauthorized GovAction::LockVotingTokens"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "LockVotingTokens",
+                                owner,
+                            )
+                            .into());
+                        }
+                    }
+                    GovAction::UnlockVotingTokens { owner } => {
+                        if !verifiers.contains(&owner) {
+                            tracing::info!(
+                                "Unauthorized GovAction::UnlockVotingTokens"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "UnlockVotingTokens",
+                                owner,
+                            )
+                            .into());
+                        }
+                    }
                 },
                 _ => {
                     // Other actions are not relevant to Governance VP
@@ -132,6 +177,22 @@ where
             }
         }
 
+        // The locked voting tokens must be held by the governance account,
+        // even if the tx doesn't change its balance
+        let is_lock_changed = if let Some((pre_locked, post_locked)) =
+            Self::read_locked_voting_tokens(ctx, keys_changed)?
+        {
+            Self::is_valid_locked_balance(
+                ctx,
+                &native_token,
+                pre_locked,
+                post_locked,
+            )?;
+            true
+        } else {
+            false
+        };
+
         for key in keys_changed.iter() {
             let proposal_id = gov_storage::get_proposal_id(key);
             let key_type = KeyType::from_key::<TokenKeys>(key, &native_token);
@@ -140,6 +201,20 @@ where
                 (KeyType::VOTE, Some(proposal_id)) => {
                     Self::is_valid_vote_key(ctx, proposal_id, key, verifiers)
                 }
+                (KeyType::ACCOUNT_VOTE, Some(proposal_id)) => {
+                    Self::is_valid_account_vote_key(
+                        ctx,
+                        proposal_id,
+                        key,
+                        verifiers,
+                    )
+                }
+                (KeyType::VOTE_DELEGATE, _) => {
+                    Self::is_valid_vote_delegate_key(ctx, key, verifiers)
+                }
+                (KeyType::VOTE_LOCK, _) => {
+                    Self::is_valid_vote_lock_key(ctx, key, verifiers)
+                }
                 (KeyType::CONTENT, Some(proposal_id)) => {
                     Self::is_valid_content_key(ctx, proposal_id)
                 }
@@ -172,7 +247,7 @@ where
                     Self::is_valid_parameter(ctx, tx_data)
                 }
                 (KeyType::BALANCE, _) => {
-                    Self::is_valid_balance(ctx, &native_token)
+                    Self::is_valid_balance(ctx, &native_token, is_lock_changed)
                 }
                 (KeyType::UNKNOWN_GOVERNANCE, _) => Err(Error::new_alloc(
                     format!("Unkown governance key change: {key}"),
@@ -344,6 +419,151 @@ where
         Ok(())
     }
 
+    /// Validate a vote cast by an account, independent of its delegations
+    fn is_valid_account_vote_key(
+        ctx: &'ctx CTX,
+        proposal_id: u64,
+        key: &storage::Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<()> {
+        let counter_key = gov_storage::get_counter_key();
+        let voting_start_epoch_key =
+            gov_storage::get_voting_start_epoch_key(proposal_id);
+        let voting_end_epoch_key =
+            gov_storage::get_voting_end_epoch_key(proposal_id);
+
+        let current_epoch = ctx.get_block_epoch()?;
+
+        let pre_counter: u64 =
+            Self::force_read(ctx, &counter_key, ReadType::Pre)?;
+        // Invalid proposal id
+        if pre_counter <= proposal_id {
+            return Err(Error::new_alloc(format!(
+                "Invalid proposal ID. Expected {pre_counter} or lower, got \
+                 {proposal_id}"
+            )));
+        }
+
+        let voter = gov_storage::get_account_voter_address(key).ok_or(
+            Error::new_alloc(format!(
+                "Failed to parse a voter from the account vote key {key}",
+            )),
+        )?;
+        if !verifiers.contains(voter) {
+            return Err(
+                VpError::Unauthorized("VoteProposal", voter.clone()).into()
+            );
+        }
+
+        Self::force_read::<ProposalVote>(ctx, key, ReadType::Post)?;
+
+        let pre_voting_start_epoch: Epoch =
+            Self::force_read(ctx, &voting_start_epoch_key, ReadType::Pre)?;
+        let pre_voting_end_epoch: Epoch =
+            Self::force_read(ctx, &voting_end_epoch_key, ReadType::Pre)?;
+        Self::is_valid_voting_window(
+            current_epoch,
+            pre_voting_start_epoch,
+            pre_voting_end_epoch,
+            false,
+        )
+        .ok_or_else(|| {
+            Error::new_alloc(format!(
+                "Voted outside voting window. Current epoch: {current_epoch}, \
+                 start: {pre_voting_start_epoch}, end: {pre_voting_end_epoch}."
+            ))
+        })
+    }
+
+    /// Validate a vote delegation
+    fn is_valid_vote_delegate_key(
+        ctx: &'ctx CTX,
+        key: &storage::Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<()> {
+        let delegator = gov_storage::is_vote_delegate_key(key).ok_or(
+            Error::new_alloc(format!(
+                "Failed to parse a delegator from the vote delegate key {key}",
+            )),
+        )?;
+        if !verifiers.contains(delegator) {
+            return Err(VpError::Unauthorized(
+                "DelegateVote",
+                delegator.clone(),
+            )
+            .into());
+        }
+
+        let delegate: Option<Address> = ctx.post().read(key)?;
+        (delegate.as_ref() != Some(delegator)).ok_or_else(|| {
+            Error::new_alloc(format!(
+                "{delegator} cannot delegate its vote to itself"
+            ))
+        })
+    }
+
+    /// Validate a lock of voting tokens. A lock can only be created with the
+    /// lock-weighted voting enabled, starting in the current epoch, and it
+    /// can only be removed once it has expired. The locked tokens themselves
+    /// are checked against the balance of the governance account.
+    fn is_valid_vote_lock_key(
+        ctx: &'ctx CTX,
+        key: &storage::Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<()> {
+        let owner = gov_storage::is_vote_lock_key(key).ok_or(
+            Error::new_alloc(format!(
+                "Failed to parse an owner from the vote lock key {key}",
+            )),
+        )?;
+        if !verifiers.contains(owner) {
+            return Err(VpError::Unauthorized(
+                "LockVotingTokens",
+                owner.clone(),
+            )
+            .into());
+        }
+
+        let current_epoch = ctx.get_block_epoch()?;
+        let pre: Option<VoteLock> = ctx.pre().read(key)?;
+        let post: Option<VoteLock> = ctx.post().read(key)?;
+        match (pre, post) {
+            (None, Some(post)) => {
+                let params_key = gov_storage::get_vote_lock_params_key();
+                let params: Option<VoteLockParams> =
+                    ctx.pre().read(&params_key)?;
+                if params.is_none() {
+                    return Err(Error::new_const(
+                        "Lock-weighted voting is not enabled",
+                    ));
+                }
+                let is_valid_lock = !post.amount.is_zero()
+                    && post.start_epoch == current_epoch
+                    && post.end_epoch > post.start_epoch;
+                is_valid_lock.ok_or_else(|| {
+                    Error::new_alloc(format!(
+                        "Invalid lock of voting tokens of {owner}: {post:?}"
+                    ))
+                })
+            }
+            (Some(pre), None) => {
+                pre.is_unlocked(current_epoch).ok_or_else(|| {
+                    Error::new_alloc(format!(
+                        "The voting tokens of {owner} are locked until epoch \
+                         {}",
+                        pre.end_epoch
+                    ))
+                })
+            }
+            (Some(pre), Some(post)) => (pre == post).ok_or_else(|| {
+                Error::new_alloc(format!(
+                    "The lock of voting tokens of {owner} cannot be modified"
+                ))
+            }),
+            (None, None) => Ok(()),
+        }
+    }
+
     /// Validate a content key
     pub fn is_valid_content_key(
         ctx: &'ctx CTX,
@@ -539,6 +759,61 @@ where
                     )
                 })
             }
+            ProposalType::ParameterChange(changes) => {
+                if changes.is_empty() {
+                    return Err(Error::new_const(
+                        "Parameter change proposal without any changes",
+                    ));
+                }
+
+                if changes.len() > MAX_PARAMETER_CHANGES {
+                    return Err(Error::new_alloc(format!(
+                        "Maximum number of parameter changes \
+                         ({MAX_PARAMETER_CHANGES}) exceeded ({})",
+                        changes.len()
+                    )));
+                }
+
+                if let Some(change) =
+                    changes.iter().find(|change| !change.is_valid())
+                {
+                    return Err(Error::new_alloc(format!(
+                        "Invalid parameter change: {change}"
+                    )));
+                }
+
+                // every parameter can only be changed once per proposal
+                let is_changed_more_than_once = changes
+                    .iter()
+                    .tuple_combinations()
+                    .any(|(a, b)| a.is_same_parameter(b));
+
+                (!is_changed_more_than_once).ok_or_else(|| {
+                    Error::new_const(
+                        "One or more parameters were changed more than once \
+                         in the same proposal",
+                    )
+                })
+            }
+            ProposalType::Upgrade(plan) => {
+                if !plan.is_valid_name() {
+                    return Err(Error::new_alloc(format!(
+                        "Invalid upgrade name \"{}\": it must be a non-empty \
+                         file name of at most {MAX_UPGRADE_NAME_LEN} \
+                         alphanumeric, '-', '_' or '.' characters",
+                        plan.name
+                    )));
+                }
+
+                let current_height = ctx.get_block_height()?;
+                (plan.height > current_height).ok_or_else(|| {
+                    Error::new_alloc(format!(
+                        "The upgrade height {} must be after the current \
+                         block height {current_height}",
+                        plan.height
+                    ))
+                })
+            }
             // Default proposal condition are checked already for all other
             // proposals.
             // default_with_wasm proposal needs to check only for valid code
@@ -879,18 +1154,23 @@ where
         )
     }
 
-    /// Validate a balance key
+    /// Validate a balance key. When voting tokens are locked or unlocked, the
+    /// balance is validated against the changed locks instead.
     fn is_valid_balance(
         ctx: &'ctx CTX,
         native_token_address: &Address,
+        is_lock_changed: bool,
     ) -> Result<()> {
+        if is_lock_changed {
+            return Ok(());
+        }
+
         let balance_key =
             TokenKeys::balance_key(native_token_address, &ADDRESS);
         let min_funds_parameter_key = gov_storage::get_min_proposal_fund_key();
 
         let pre_balance: Option<token::Amount> =
             ctx.pre().read(&balance_key)?;
-
         let min_funds_parameter: token::Amount =
             Self::force_read(ctx, &min_funds_parameter_key, ReadType::Pre)?;
         let post_balance: token::Amount =
@@ -911,6 +1191,56 @@ where
         })
     }
 
+    /// Validate that the balance of the governance account changes by exactly
+    /// the difference of the locked voting tokens
+    fn is_valid_locked_balance(
+        ctx: &'ctx CTX,
+        native_token_address: &Address,
+        pre_locked: token::Amount,
+        post_locked: token::Amount,
+    ) -> Result<()> {
+        let balance_key =
+            TokenKeys::balance_key(native_token_address, &ADDRESS);
+        let pre_balance: token::Amount =
+            ctx.pre().read(&balance_key)?.unwrap_or_default();
+        let post_balance: token::Amount =
+            ctx.post().read(&balance_key)?.unwrap_or_default();
+        // post_balance - pre_balance == post_locked - pre_locked
+        let is_valid_balance = checked!(post_balance + pre_locked)?
+            == checked!(pre_balance + post_locked)?;
+        is_valid_balance.ok_or_else(|| {
+            Error::new_alloc(format!(
+                "The balance {} of the governance account doesn't match the \
+                 locked voting tokens",
+                post_balance.native_denominated()
+            ))
+        })
+    }
+
+    /// Sum the amounts of the changed locks of voting tokens before and after
+    /// the tx. Returns `None` if no lock has changed.
+    fn read_locked_voting_tokens(
+        ctx: &'ctx CTX,
+        keys_changed: &BTreeSet<storage::Key>,
+    ) -> Result<Option<(token::Amount, token::Amount)>> {
+        let mut is_changed = false;
+        let mut pre_locked = token::Amount::zero();
+        let mut post_locked = token::Amount::zero();
+        for key in keys_changed {
+            if gov_storage::is_vote_lock_key(key).is_none() {
+                continue;
+            }
+            is_changed = true;
+            if let Some(VoteLock { amount, .. }) = ctx.pre().read(key)? {
+                checked!(pre_locked += amount)?;
+            }
+            if let Some(VoteLock { amount, .. }) = ctx.post().read(key)? {
+                checked!(post_locked += amount)?;
+            }
+        }
+        Ok(is_changed.then_some((pre_locked, post_locked)))
+    }
+
     /// Validate a author key
     pub fn is_valid_author(
         ctx: &'ctx CTX,
@@ -1088,6 +1418,12 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     VOTE,
     #[allow(non_camel_case_types)]
+    ACCOUNT_VOTE,
+    #[allow(non_camel_case_types)]
+    VOTE_DELEGATE,
+    #[allow(non_camel_case_types)]
+    VOTE_LOCK,
+    #[allow(non_camel_case_types)]
     CONTENT,
     #[allow(non_camel_case_types)]
     PROPOSAL_CODE,
@@ -1122,6 +1458,12 @@ impl KeyType {
     {
         if gov_storage::is_vote_key(key) {
             Self::VOTE
+        } else if gov_storage::is_account_vote_key(key) {
+            Self::ACCOUNT_VOTE
+        } else if gov_storage::is_vote_delegate_key(key).is_some() {
+            Self::VOTE_DELEGATE
+        } else if gov_storage::is_vote_lock_key(key).is_some() {
+            Self::VOTE_LOCK
         } else if gov_storage::is_content_key(key) {
             KeyType::CONTENT
         } else if gov_storage::is_proposal_type_key(key) {
@@ -1167,6 +1509,7 @@ mod test {
     use namada_core::address::Address;
     use namada_core::borsh::BorshSerializeExt;
     use namada_core::chain::testing::get_dummy_header;
+    use namada_core::hash::Hash;
     use namada_core::key::testing::keypair_1;
     use namada_core::key::RefTo;
     use namada_core::parameters::Parameters;
@@ -1190,13 +1533,15 @@ mod test {
     use namada_vm::{wasm, WasmCacheRwAccess};
     use namada_vp::native_vp::{self, CtxPreStorageRead};
 
+    use crate::parameters::VoteLockParams;
     use crate::storage::keys::{
         get_activation_epoch_key, get_author_key, get_committing_proposals_key,
         get_content_key, get_counter_key, get_funds_key, get_proposal_type_key,
-        get_vote_proposal_key, get_voting_end_epoch_key,
-        get_voting_start_epoch_key,
+        get_vote_lock_key, get_vote_lock_params_key, get_vote_proposal_key,
+        get_voting_end_epoch_key, get_voting_start_epoch_key,
     };
-    use crate::{ProposalType, ProposalVote, ADDRESS};
+    use crate::storage::proposal::UpgradePlan;
+    use crate::{ProposalType, ProposalVote, VoteLock, ADDRESS};
 
     type CA = WasmCacheRwAccess;
     type Eval<S> = VpEvalWasm<<S as StateRead>::D, <S as StateRead>::H, CA>;
@@ -1571,6 +1916,116 @@ mod test {
         assert_eq!(counter, 1);
     }
 
+    #[test]
+    fn test_governance_upgrade_proposal() {
+        let mut state = init_storage();
+
+        let proposal_id = 0;
+        let activation_epoch = 19;
+
+        let keys_changed = get_proposal_keys(proposal_id, activation_epoch);
+
+        let signer = keypair_1();
+        let signer_address = Address::from(&signer.clone().ref_to());
+        let verifiers = BTreeSet::from([signer_address.clone()]);
+
+        initialize_account_balance(
+            &mut state,
+            &signer_address.clone(),
+            token::Amount::native_whole(510),
+        );
+        initialize_account_balance(
+            &mut state,
+            &ADDRESS,
+            token::Amount::native_whole(0),
+        );
+        state.commit_block().unwrap();
+
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = state.in_mem().chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        tx.add_section(Section::Authorization(Authorization::new(
+            vec![tx.header_hash()],
+            [(0, keypair_1())].into_iter().collect(),
+            None,
+        )));
+
+        init_proposal(
+            &mut state,
+            proposal_id,
+            500,
+            3,
+            9,
+            19,
+            &signer_address,
+            false,
+        );
+
+        let valid_plan = UpgradePlan {
+            name: "v2.0.0".to_string(),
+            height: BlockHeight(100),
+            binary_hash: Hash::sha256(b"namada-node"),
+            migration_hash: Some(Hash::sha256(b"migration")),
+        };
+        let invalid_plans = [
+            // the name isn't a valid file name
+            UpgradePlan {
+                name: "../v2".to_string(),
+                ..valid_plan.clone()
+            },
+            UpgradePlan {
+                name: String::new(),
+                ..valid_plan.clone()
+            },
+            // the height has already been reached
+            UpgradePlan {
+                height: state.in_mem().get_block_height().0,
+                ..valid_plan.clone()
+            },
+        ];
+
+        let mut validate = |plan: UpgradePlan| {
+            let _ = state
+                .write_log_mut()
+                .write(
+                    &get_proposal_type_key(proposal_id),
+                    ProposalType::Upgrade(plan).serialize_to_vec(),
+                )
+                .unwrap();
+
+            let gas_meter = RefCell::new(VpGasMeter::new_from_tx_meter(
+                &TxGasMeter::new(u64::MAX),
+            ));
+            let (vp_wasm_cache, _vp_cache_dir) =
+                wasm::compilation_cache::common::testing::vp_cache();
+            let tx_index = TxIndex::default();
+            let batched_tx = tx.batch_ref_first_tx().unwrap();
+            let ctx = Ctx::new(
+                &ADDRESS,
+                &state,
+                batched_tx.tx,
+                batched_tx.cmt,
+                &tx_index,
+                &gas_meter,
+                &keys_changed,
+                &verifiers,
+                vp_wasm_cache,
+            );
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers,
+            )
+        };
+
+        for plan in invalid_plans {
+            assert_matches!(validate(plan), Err(_));
+        }
+        assert_matches!(validate(valid_plan), Ok(_));
+    }
+
     #[test]
     fn test_governance_proposal_not_enough_funds_failed() {
         let mut state = init_storage();
@@ -3082,4 +3537,266 @@ mod test {
             Err(_)
         );
     }
+
+    /// Write the lock of the owner's voting tokens together with the
+    /// transfer of the locked amount to the governance account
+    fn write_vote_lock<S>(
+        state: &mut S,
+        owner: &Address,
+        lock: Option<VoteLock>,
+        owner_balance: token::Amount,
+        gov_balance: token::Amount,
+    ) -> BTreeSet<Key>
+    where
+        S: State,
+    {
+        let lock_key = get_vote_lock_key(owner);
+        match lock {
+            Some(lock) => state
+                .write_log_mut()
+                .write(&lock_key, lock.serialize_to_vec())
+                .unwrap(),
+            None => state.write_log_mut().delete(&lock_key).unwrap(),
+        };
+        let owner_balance_key = balance_key(&nam(), owner);
+        let _ = state
+            .write_log_mut()
+            .write(&owner_balance_key, owner_balance.serialize_to_vec())
+            .unwrap();
+        let gov_balance_key = balance_key(&nam(), &ADDRESS);
+        let _ = state
+            .write_log_mut()
+            .write(&gov_balance_key, gov_balance.serialize_to_vec())
+            .unwrap();
+        BTreeSet::from([lock_key, owner_balance_key, gov_balance_key])
+    }
+
+    #[test]
+    fn test_governance_lock_voting_tokens() {
+        let mut state = init_storage();
+
+        let gas_meter = RefCell::new(VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new(u64::MAX),
+        ));
+        let (vp_wasm_cache, _vp_cache_dir) =
+            wasm::compilation_cache::common::testing::vp_cache();
+
+        let tx_index = TxIndex::default();
+
+        let owner = established_address_3();
+        let verifiers = BTreeSet::from([owner.clone()]);
+
+        initialize_account_balance(
+            &mut state,
+            &owner,
+            token::Amount::native_whole(1000),
+        );
+        initialize_account_balance(
+            &mut state,
+            &ADDRESS,
+            token::Amount::native_whole(0),
+        );
+        state.commit_block().unwrap();
+
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = state.in_mem().chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        let batched_tx = tx.batch_ref_first_tx().unwrap();
+
+        let epoch = state.in_mem().block.epoch;
+        let lock = VoteLock {
+            amount: token::Amount::native_whole(100),
+            start_epoch: epoch,
+            end_epoch: epoch.checked_add(10).unwrap(),
+        };
+
+        // Lock-weighted voting is not enabled yet
+        state
+            .push_action(Action::Gov(GovAction::LockVotingTokens {
+                owner: owner.clone(),
+            }))
+            .unwrap();
+        let keys_changed = write_vote_lock(
+            &mut state,
+            &owner,
+            Some(lock.clone()),
+            token::Amount::native_whole(900),
+            token::Amount::native_whole(100),
+        );
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            batched_tx.tx,
+            batched_tx.cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache.clone(),
+        );
+        assert_matches!(
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers
+            ),
+            Err(_)
+        );
+        state.write_log_mut().drop_tx();
+
+        let _ = state
+            .write_log_mut()
+            .write(
+                &get_vote_lock_params_key(),
+                VoteLockParams {
+                    epochs_per_multiplier: 5,
+                    max_multiplier: 4,
+                }
+                .serialize_to_vec(),
+            )
+            .unwrap();
+        state.write_log_mut().commit_batch_and_current_tx();
+
+        // The lock is written without transferring the tokens to the
+        // governance account
+        state
+            .push_action(Action::Gov(GovAction::LockVotingTokens {
+                owner: owner.clone(),
+            }))
+            .unwrap();
+        let lock_key = get_vote_lock_key(&owner);
+        let _ = state
+            .write_log_mut()
+            .write(&lock_key, lock.serialize_to_vec())
+            .unwrap();
+        let keys_changed = BTreeSet::from([lock_key]);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            batched_tx.tx,
+            batched_tx.cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache.clone(),
+        );
+        assert_matches!(
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers
+            ),
+            Err(_)
+        );
+        state.write_log_mut().drop_tx();
+
+        // The locked amount doesn't match the balance of the governance
+        // account
+        state
+            .push_action(Action::Gov(GovAction::LockVotingTokens {
+                owner: owner.clone(),
+            }))
+            .unwrap();
+        let keys_changed = write_vote_lock(
+            &mut state,
+            &owner,
+            Some(lock.clone()),
+            token::Amount::native_whole(950),
+            token::Amount::native_whole(50),
+        );
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            batched_tx.tx,
+            batched_tx.cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache.clone(),
+        );
+        assert_matches!(
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers
+            ),
+            Err(_)
+        );
+        state.write_log_mut().drop_tx();
+
+        // A valid lock
+        state
+            .push_action(Action::Gov(GovAction::LockVotingTokens {
+                owner: owner.clone(),
+            }))
+            .unwrap();
+        let keys_changed = write_vote_lock(
+            &mut state,
+            &owner,
+            Some(lock),
+            token::Amount::native_whole(900),
+            token::Amount::native_whole(100),
+        );
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            batched_tx.tx,
+            batched_tx.cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache.clone(),
+        );
+        assert_matches!(
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers
+            ),
+            Ok(_)
+        );
+        state.write_log_mut().commit_batch_and_current_tx();
+
+        // The tokens cannot be unlocked before the end of the lock
+        state
+            .push_action(Action::Gov(GovAction::UnlockVotingTokens {
+                owner: owner.clone(),
+            }))
+            .unwrap();
+        let keys_changed = write_vote_lock(
+            &mut state,
+            &owner,
+            None,
+            token::Amount::native_whole(1000),
+            token::Amount::native_whole(0),
+        );
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            batched_tx.tx,
+            batched_tx.cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        assert_matches!(
+            GovernanceVp::validate_tx(
+                &ctx,
+                &batched_tx,
+                &keys_changed,
+                &verifiers
+            ),
+            Err(_)
+        );
+    }
 }
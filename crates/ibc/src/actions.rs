@@ -19,12 +19,14 @@ use namada_core::tendermint::Time as TmTime;
 use namada_core::token::Amount;
 use namada_events::EmitEvents;
 use namada_state::{
-    BlockHeader, BlockHeight, Epoch, Epochs, Key, Result, ResultExt, State,
-    StorageRead, StorageWrite, TxIndex,
+    BlockHeader, BlockHeight, Epoch, Epochs, Error as StorageError, Key,
+    Result, ResultExt, State, StorageRead, StorageWrite, TxIndex,
 };
 use namada_systems::{parameters, trans_token};
+use namada_tx::action::IbcHook;
 
 use crate::event::IbcEvent;
+use crate::ica::HostMsg;
 use crate::{
     storage as ibc_storage, IbcActions, IbcCommonContext, IbcStorageContext,
     MsgTransfer,
@@ -168,6 +170,22 @@ where
         ibc_storage::burn_tokens::<_, Token>(self.state, target, token, amount)
     }
 
+    fn execute_host_msgs(
+        &mut self,
+        _account: &Address,
+        _msgs: &[HostMsg],
+    ) -> Result<()> {
+        Err(StorageError::new_const(
+            "Interchain accounts aren't supported in the IBC protocol context",
+        ))
+    }
+
+    fn schedule_hook(&mut self, _hook: IbcHook) -> Result<()> {
+        Err(StorageError::new_const(
+            "IBC hooks aren't supported in the IBC protocol context",
+        ))
+    }
+
     fn insert_verifier(&mut self, _verifier: &Address) -> Result<()> {
         Ok(())
     }
@@ -16,7 +16,13 @@ use namada_systems::parameters;
 use prost::Message;
 
 use super::common::IbcCommonContext;
+use super::wasm_client::{WasmClientState, WasmConsensusState};
 use super::IbcContext;
+use crate::wasm_client::{
+    ClientState as WasmClientStateType,
+    ConsensusState as WasmConsensusStateType, PendingLightClientCalls,
+    WASM_CLIENT_STATE_TYPE_URL, WASM_CONSENSUS_STATE_TYPE_URL,
+};
 
 /// ClientState for light clients
 #[derive(Clone, Debug, IbcClientState)]
@@ -26,11 +32,28 @@ pub enum AnyClientState {
     /// Tendermint client state
     Tendermint(TmClientState),
 
+    /// ICS-08 wasm client state
+    Wasm(WasmClientState),
+
     #[cfg(any(test, feature = "testing"))]
     /// Mock client state for testing
     Mock(MockClientState),
 }
 
+impl AnyClientState {
+    /// Attach the pending calls to the wasm light client code. The other
+    /// client states are returned as they are.
+    pub fn with_light_client_calls(
+        self,
+        calls: &PendingLightClientCalls,
+    ) -> Self {
+        match self {
+            Self::Wasm(cs) => Self::Wasm(cs.with_calls(calls.clone())),
+            _ => self,
+        }
+    }
+}
+
 impl From<TmClientState> for AnyClientState {
     fn from(cs: TmClientState) -> Self {
         Self::Tendermint(cs)
@@ -49,6 +72,11 @@ impl TryFrom<AnyClientState> for TmClientState {
     fn try_from(any: AnyClientState) -> Result<Self, Self::Error> {
         match any {
             AnyClientState::Tendermint(cs) => Ok(cs),
+            AnyClientState::Wasm(_) => {
+                Err(ClientError::UnknownConsensusStateType {
+                    consensus_state_type: "wasm".to_string(),
+                })
+            }
             #[cfg(any(test, feature = "testing"))]
             AnyClientState::Mock(_) => {
                 Err(ClientError::UnknownConsensusStateType {
@@ -59,6 +87,18 @@ impl TryFrom<AnyClientState> for TmClientState {
     }
 }
 
+impl From<WasmClientState> for AnyClientState {
+    fn from(cs: WasmClientState) -> Self {
+        Self::Wasm(cs)
+    }
+}
+
+impl From<WasmClientStateType> for AnyClientState {
+    fn from(cs: WasmClientStateType) -> Self {
+        Self::Wasm(cs.into())
+    }
+}
+
 #[cfg(any(test, feature = "testing"))]
 impl From<MockClientState> for AnyClientState {
     fn from(cs: MockClientState) -> Self {
@@ -77,6 +117,11 @@ impl TryFrom<AnyClientState> for MockClientState {
                     consensus_state_type: "tendermint".to_string(),
                 })
             }
+            AnyClientState::Wasm(_) => {
+                Err(ClientError::UnknownConsensusStateType {
+                    consensus_state_type: "wasm".to_string(),
+                })
+            }
             AnyClientState::Mock(cs) => Ok(cs),
         }
     }
@@ -86,6 +131,7 @@ impl From<AnyClientState> for Any {
     fn from(client_state: AnyClientState) -> Self {
         match client_state {
             AnyClientState::Tendermint(cs) => cs.into(),
+            AnyClientState::Wasm(cs) => cs.into(),
             #[cfg(any(test, feature = "testing"))]
             AnyClientState::Mock(cs) => cs.into(),
         }
@@ -96,6 +142,10 @@ impl TryFrom<Any> for AnyClientState {
     type Error = ClientError;
 
     fn try_from(client_state: Any) -> Result<Self, Self::Error> {
+        if client_state.type_url == WASM_CLIENT_STATE_TYPE_URL {
+            return Ok(Self::Wasm(WasmClientState::try_from(client_state)?));
+        }
+
         #[cfg(any(test, feature = "testing"))]
         if let Ok(cs) = MockClientState::try_from(client_state.clone()) {
             return Ok(cs.into());
@@ -116,6 +166,9 @@ pub enum AnyConsensusState {
     /// Tendermint consensus state
     Tendermint(TmConsensusState),
 
+    /// ICS-08 wasm consensus state
+    Wasm(WasmConsensusState),
+
     #[cfg(any(test, feature = "testing"))]
     /// Mock consensus state for testing
     Mock(MockConsensusState),
@@ -139,6 +192,11 @@ impl TryFrom<AnyConsensusState> for TmConsensusStateType {
     fn try_from(any: AnyConsensusState) -> Result<Self, Self::Error> {
         match any {
             AnyConsensusState::Tendermint(c) => Ok(c.inner().clone()),
+            AnyConsensusState::Wasm(_) => {
+                Err(ClientError::UnknownConsensusStateType {
+                    consensus_state_type: "wasm".to_string(),
+                })
+            }
             #[cfg(any(test, feature = "testing"))]
             AnyConsensusState::Mock(_) => {
                 Err(ClientError::UnknownConsensusStateType {
@@ -149,6 +207,18 @@ impl TryFrom<AnyConsensusState> for TmConsensusStateType {
     }
 }
 
+impl From<WasmConsensusState> for AnyConsensusState {
+    fn from(cs: WasmConsensusState) -> Self {
+        Self::Wasm(cs)
+    }
+}
+
+impl From<WasmConsensusStateType> for AnyConsensusState {
+    fn from(cs: WasmConsensusStateType) -> Self {
+        Self::Wasm(cs.into())
+    }
+}
+
 #[cfg(any(test, feature = "testing"))]
 impl From<MockConsensusState> for AnyConsensusState {
     fn from(cs: MockConsensusState) -> Self {
@@ -162,7 +232,6 @@ impl TryFrom<AnyConsensusState> for TmConsensusState {
     fn try_from(any: AnyConsensusState) -> Result<Self, Self::Error> {
         match any {
             AnyConsensusState::Tendermint(cs) => Ok(cs),
-            #[cfg(any(test, feature = "testing"))]
             _ => Err(ClientError::UnknownConsensusStateType {
                 consensus_state_type: "Only Tendermint client state type is \
                                        supported"
@@ -191,6 +260,7 @@ impl From<AnyConsensusState> for Any {
     fn from(consensus_state: AnyConsensusState) -> Self {
         match consensus_state {
             AnyConsensusState::Tendermint(cs) => cs.into(),
+            AnyConsensusState::Wasm(cs) => cs.into(),
             #[cfg(any(test, feature = "testing"))]
             AnyConsensusState::Mock(cs) => cs.into(),
         }
@@ -201,6 +271,12 @@ impl TryFrom<Any> for AnyConsensusState {
     type Error = ClientError;
 
     fn try_from(consensus_state: Any) -> Result<Self, Self::Error> {
+        if consensus_state.type_url == WASM_CONSENSUS_STATE_TYPE_URL {
+            return Ok(Self::Wasm(WasmConsensusState::try_from(
+                consensus_state,
+            )?));
+        }
+
         #[cfg(any(test, feature = "testing"))]
         if let Ok(cs) = MockConsensusState::try_from(consensus_state.clone()) {
             return Ok(cs.into());
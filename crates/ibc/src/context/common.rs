@@ -30,6 +30,7 @@ use prost::Message;
 
 use super::client::{AnyClientState, AnyConsensusState};
 use super::storage::IbcStorageContext;
+use crate::rate_limit::RateLimitFlow;
 use crate::{storage, trace, NftClass, NftMetadata};
 
 /// Result of IBC common function call
@@ -762,6 +763,31 @@ pub trait IbcCommonContext: IbcStorageContext {
             .write(&key, amount)
             .map_err(ContextError::from)
     }
+
+    /// Read the rolling-window flow of the given token over the channel, or
+    /// over all the channels if no channel is given
+    fn rate_limit_flow(
+        &self,
+        token: &Address,
+        channel_id: Option<&ChannelId>,
+    ) -> Result<RateLimitFlow> {
+        let key = storage::rate_limit_flow_key(token, channel_id);
+        Ok(self.storage().read(&key)?.unwrap_or_default())
+    }
+
+    /// Write the rolling-window flow of the given token over the channel, or
+    /// over all the channels if no channel is given
+    fn store_rate_limit_flow(
+        &mut self,
+        token: &Address,
+        channel_id: Option<&ChannelId>,
+        flow: RateLimitFlow,
+    ) -> Result<()> {
+        let key = storage::rate_limit_flow_key(token, channel_id);
+        self.storage_mut()
+            .write(&key, flow)
+            .map_err(ContextError::from)
+    }
 }
 
 /// Read and decode the IBC sequence
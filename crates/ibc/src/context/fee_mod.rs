@@ -0,0 +1,595 @@
+//! ICS-29 fee middleware on the token transfer module
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use ibc::apps::transfer::types::MODULE_ID_STR;
+use ibc::core::channel::types::acknowledgement::{
+    Acknowledgement, AcknowledgementStatus, StatusValue,
+};
+use ibc::core::channel::types::channel::{Counterparty, Order};
+use ibc::core::channel::types::error::{ChannelError, PacketError};
+use ibc::core::channel::types::packet::Packet;
+use ibc::core::channel::types::Version;
+use ibc::core::host::types::identifiers::{ChannelId, ConnectionId, PortId};
+use ibc::core::router::module::Module;
+use ibc::core::router::types::module::{ModuleExtras, ModuleId};
+use ibc::primitives::Signer;
+use namada_core::address::Address;
+use namada_core::token::Amount;
+use namada_state::{StorageRead, StorageWrite};
+
+use super::common::IbcCommonContext;
+use super::packet_forward_mod::PacketForwardModule;
+use super::transfer_mod::{ModuleWrapper, TransferModule};
+use crate::event::{IbcEvent, IbcEventType};
+use crate::fee::{
+    DISTRIBUTE_FEE_EVENT, IncentivizedAcknowledgement, Metadata, PacketFee,
+    RelayerFee,
+};
+use crate::{storage, IBC_ESCROW_ADDRESS};
+
+/// ICS-29 fee middleware wrapping the packet-forward middleware. It negotiates
+/// the fee-enabled channels, wraps the acknowledgements written by this chain
+/// and pays out the escrowed relayer fees.
+#[derive(Debug)]
+pub struct FeeModule<C, M = TransferModule<C>>
+where
+    C: IbcCommonContext,
+{
+    pub(crate) app: PacketForwardModule<C, M>,
+    ctx: Rc<RefCell<C>>,
+}
+
+impl<C, M> FeeModule<C, M>
+where
+    C: IbcCommonContext,
+    M: ModuleWrapper,
+{
+    /// Make a new middleware on the packet-forward middleware
+    pub fn new(ctx: Rc<RefCell<C>>, app: PacketForwardModule<C, M>) -> Self {
+        Self { app, ctx }
+    }
+
+    fn is_fee_enabled(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<bool, String> {
+        storage::is_fee_enabled(
+            self.ctx.borrow().storage(),
+            port_id,
+            channel_id,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn enable_fee(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        let key = storage::fee_enabled_key(port_id, channel_id);
+        self.ctx
+            .borrow_mut()
+            .storage_mut()
+            .write(&key, true)
+            .map_err(|e| into_channel_error(e.to_string()))
+    }
+
+    // Get the application version from the counterparty version of the
+    // fee-enabled channel
+    fn app_counterparty_version(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<Version, ChannelError> {
+        if !self
+            .is_fee_enabled(port_id, channel_id)
+            .map_err(into_channel_error)?
+        {
+            return Ok(counterparty_version.clone());
+        }
+        let metadata = Metadata::parse(counterparty_version.as_str())
+            .ok_or_else(|| {
+                into_channel_error(format!(
+                    "The counterparty version {counterparty_version} of the \
+                     fee-enabled channel isn't the ICS-29 metadata"
+                ))
+            })?
+            .map_err(into_channel_error)?;
+        Ok(Version::new(metadata.app_version))
+    }
+
+    // Unwrap the acknowledgement on the fee-enabled channel
+    fn unwrap_ack(
+        &self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+    ) -> Result<Option<(IncentivizedAcknowledgement, Acknowledgement)>, String>
+    {
+        if !self.is_fee_enabled(&packet.port_id_on_a, &packet.chan_id_on_a)? {
+            return Ok(None);
+        }
+        let ack = IncentivizedAcknowledgement::decode(acknowledgement)?;
+        let app_ack =
+            Acknowledgement::try_from(ack.app_acknowledgement.clone())
+                .map_err(|e| e.to_string())?;
+        Ok(Some((ack, app_ack)))
+    }
+
+    // Get the payee of the relayer. The relayer is paid if it hasn't
+    // registered a payee.
+    fn payee(
+        &self,
+        channel_id: &ChannelId,
+        relayer: &Signer,
+    ) -> Result<Option<Address>, String> {
+        let Ok(relayer) = Address::decode(relayer.as_ref()) else {
+            return Ok(None);
+        };
+        let payee = self
+            .ctx
+            .borrow()
+            .storage()
+            .read(&storage::payee_key(channel_id, &relayer))
+            .map_err(|e| e.to_string())?;
+        Ok(Some(payee.unwrap_or(relayer)))
+    }
+
+    // Pay out the escrowed fees of the packet to the given receivers of the
+    // receive, acknowledgement and timeout fees. The fee without a receiver is
+    // refunded to the payer.
+    fn distribute_fees(
+        &self,
+        packet: &Packet,
+        receivers: impl FnOnce(&RelayerFee) -> [(Amount, Option<Address>); 3],
+    ) -> Result<(), String> {
+        let key = storage::packet_fee_key(
+            &packet.port_id_on_a,
+            &packet.chan_id_on_a,
+            packet.seq_on_a,
+        );
+        let Some(PacketFee { fee, payer }) = self
+            .ctx
+            .borrow()
+            .storage()
+            .read::<PacketFee>(&key)
+            .map_err(|e| e.to_string())?
+        else {
+            // The packet isn't incentivized
+            return Ok(());
+        };
+        self.ctx
+            .borrow_mut()
+            .storage_mut()
+            .delete(&key)
+            .map_err(|e| e.to_string())?;
+
+        for (amount, receiver) in receivers(&fee) {
+            if amount.is_zero() {
+                continue;
+            }
+            let receiver = receiver.unwrap_or_else(|| payer.clone());
+            let mut ctx = self.ctx.borrow_mut();
+            ctx.insert_verifier(&receiver).map_err(|e| e.to_string())?;
+            ctx.transfer_token(
+                &IBC_ESCROW_ADDRESS,
+                &receiver,
+                &fee.token,
+                amount,
+            )
+            .map_err(|e| e.to_string())?;
+            let event = IbcEvent {
+                event_type: IbcEventType(DISTRIBUTE_FEE_EVENT.to_string()),
+                attributes: HashMap::from([
+                    ("receiver".to_string(), receiver.to_string()),
+                    ("token".to_string(), fee.token.to_string()),
+                    ("fee".to_string(), amount.to_string()),
+                ]),
+            };
+            ctx.emit_ibc_event(event).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+impl<C, M> ModuleWrapper for FeeModule<C, M>
+where
+    C: IbcCommonContext + Debug,
+    M: ModuleWrapper,
+{
+    fn as_module(&self) -> &dyn Module {
+        self
+    }
+
+    fn as_module_mut(&mut self) -> &mut dyn Module {
+        self
+    }
+
+    fn module_id(&self) -> ModuleId {
+        ModuleId::new(MODULE_ID_STR.to_string())
+    }
+
+    fn port_id(&self) -> PortId {
+        self.app.port_id()
+    }
+}
+
+impl<C, M> Module for FeeModule<C, M>
+where
+    C: IbcCommonContext + Debug,
+    M: ModuleWrapper,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<Version, ChannelError> {
+        let Some(metadata) = Metadata::parse(version.as_str()) else {
+            return self.app.on_chan_open_init_validate(
+                order,
+                connection_hops,
+                port_id,
+                channel_id,
+                counterparty,
+                version,
+            );
+        };
+        let metadata = metadata.map_err(into_channel_error)?;
+        let app_version = self.app.on_chan_open_init_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            &Version::new(metadata.app_version),
+        )?;
+        Ok(Version::new(
+            Metadata::new(app_version.as_str()).to_version(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        let Some(metadata) = Metadata::parse(version.as_str()) else {
+            return self.app.on_chan_open_init_execute(
+                order,
+                connection_hops,
+                port_id,
+                channel_id,
+                counterparty,
+                version,
+            );
+        };
+        let metadata = metadata.map_err(into_channel_error)?;
+        let (extras, app_version) = self.app.on_chan_open_init_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            &Version::new(metadata.app_version),
+        )?;
+        self.enable_fee(port_id, channel_id)?;
+        let version =
+            Version::new(Metadata::new(app_version.as_str()).to_version());
+        Ok((extras, version))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<Version, ChannelError> {
+        let Some(metadata) = Metadata::parse(counterparty_version.as_str())
+        else {
+            return self.app.on_chan_open_try_validate(
+                order,
+                connection_hops,
+                port_id,
+                channel_id,
+                counterparty,
+                counterparty_version,
+            );
+        };
+        let metadata = metadata.map_err(into_channel_error)?;
+        let app_version = self.app.on_chan_open_try_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            &Version::new(metadata.app_version),
+        )?;
+        Ok(Version::new(
+            Metadata::new(app_version.as_str()).to_version(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        let Some(metadata) = Metadata::parse(counterparty_version.as_str())
+        else {
+            return self.app.on_chan_open_try_execute(
+                order,
+                connection_hops,
+                port_id,
+                channel_id,
+                counterparty,
+                counterparty_version,
+            );
+        };
+        let metadata = metadata.map_err(into_channel_error)?;
+        let (extras, app_version) = self.app.on_chan_open_try_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            &Version::new(metadata.app_version),
+        )?;
+        self.enable_fee(port_id, channel_id)?;
+        let version =
+            Version::new(Metadata::new(app_version.as_str()).to_version());
+        Ok((extras, version))
+    }
+
+    fn on_chan_open_ack_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<(), ChannelError> {
+        let app_version = self.app_counterparty_version(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )?;
+        self.app
+            .on_chan_open_ack_validate(port_id, channel_id, &app_version)
+    }
+
+    fn on_chan_open_ack_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<ModuleExtras, ChannelError> {
+        let app_version = self.app_counterparty_version(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )?;
+        self.app
+            .on_chan_open_ack_execute(port_id, channel_id, &app_version)
+    }
+
+    fn on_chan_open_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.app.on_chan_open_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_open_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.app.on_chan_open_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.app.on_chan_close_init_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.app.on_chan_close_init_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.app.on_chan_close_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.app.on_chan_close_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_recv_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Acknowledgement) {
+        let forward_relayer = match self
+            .is_fee_enabled(&packet.port_id_on_b, &packet.chan_id_on_b)
+            .and_then(|is_enabled| {
+                is_enabled
+                    .then(|| {
+                        storage::counterparty_payee(
+                            self.ctx.borrow().storage(),
+                            &packet.chan_id_on_b,
+                            relayer.as_ref(),
+                        )
+                        .map_err(|e| e.to_string())
+                    })
+                    .transpose()
+            }) {
+            Ok(None) => {
+                return self.app.on_recv_packet_execute(packet, relayer);
+            }
+            Ok(Some(forward_relayer)) => forward_relayer,
+            Err(e) => {
+                let ack = AcknowledgementStatus::error(
+                    StatusValue::new(e)
+                        .expect("The error message shouldn't be empty"),
+                );
+                return (ModuleExtras::empty(), ack.into());
+            }
+        };
+
+        let (extras, ack) = self.app.on_recv_packet_execute(packet, relayer);
+        let underlying_app_success =
+            serde_json::from_slice::<AcknowledgementStatus>(ack.as_ref())
+                .map(|status| status.is_successful())
+                .unwrap_or(false);
+        let ack = IncentivizedAcknowledgement {
+            app_acknowledgement: ack.as_ref().to_vec(),
+            forward_relayer_address: forward_relayer,
+            underlying_app_success,
+        };
+        let ack = Acknowledgement::try_from(ack.encode())
+            .expect("The incentivized acknowledgement shouldn't be empty");
+        (extras, ack)
+    }
+
+    fn on_acknowledgement_packet_validate(
+        &self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        match self
+            .unwrap_ack(packet, acknowledgement)
+            .map_err(into_packet_error)?
+        {
+            Some((_, app_ack)) => self
+                .app
+                .on_acknowledgement_packet_validate(packet, &app_ack, relayer),
+            None => self.app.on_acknowledgement_packet_validate(
+                packet,
+                acknowledgement,
+                relayer,
+            ),
+        }
+    }
+
+    fn on_acknowledgement_packet_execute(
+        &mut self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        let (ack, app_ack) = match self.unwrap_ack(packet, acknowledgement) {
+            Ok(Some(unwrapped)) => unwrapped,
+            Ok(None) => {
+                return self.app.on_acknowledgement_packet_execute(
+                    packet,
+                    acknowledgement,
+                    relayer,
+                );
+            }
+            Err(e) => {
+                return (ModuleExtras::empty(), Err(into_packet_error(e)));
+            }
+        };
+        let (extras, result) = self
+            .app
+            .on_acknowledgement_packet_execute(packet, &app_ack, relayer);
+        if result.is_err() {
+            return (extras, result);
+        }
+        let result = self
+            .payee(&packet.chan_id_on_a, relayer)
+            .and_then(|payee| {
+                let forward_relayer =
+                    Address::decode(&ack.forward_relayer_address).ok();
+                self.distribute_fees(packet, |fee| {
+                    [
+                        (fee.recv_fee, forward_relayer),
+                        (fee.ack_fee, payee),
+                        (fee.timeout_fee, None),
+                    ]
+                })
+            })
+            .map_err(into_packet_error);
+        (extras, result)
+    }
+
+    fn on_timeout_packet_validate(
+        &self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        self.app.on_timeout_packet_validate(packet, relayer)
+    }
+
+    fn on_timeout_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        let (extras, result) =
+            self.app.on_timeout_packet_execute(packet, relayer);
+        if result.is_err() {
+            return (extras, result);
+        }
+        let result = self
+            .payee(&packet.chan_id_on_a, relayer)
+            .and_then(|payee| {
+                self.distribute_fees(packet, |fee| {
+                    [
+                        (fee.recv_fee, None),
+                        (fee.ack_fee, None),
+                        (fee.timeout_fee, payee),
+                    ]
+                })
+            })
+            .map_err(into_packet_error);
+        (extras, result)
+    }
+}
+
+fn into_channel_error(description: String) -> ChannelError {
+    ChannelError::AppModule { description }
+}
+
+fn into_packet_error(description: String) -> PacketError {
+    PacketError::AppModule { description }
+}
@@ -0,0 +1,361 @@
+//! IBC hooks middleware on the token transfer module
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use ibc::apps::transfer::types::packet::PacketData;
+use ibc::apps::transfer::types::MODULE_ID_STR;
+use ibc::core::channel::types::acknowledgement::{
+    Acknowledgement, AcknowledgementStatus, StatusValue,
+};
+use ibc::core::channel::types::channel::{Counterparty, Order};
+use ibc::core::channel::types::error::{ChannelError, PacketError};
+use ibc::core::channel::types::packet::Packet;
+use ibc::core::channel::types::Version;
+use ibc::core::host::types::identifiers::{ChannelId, ConnectionId, PortId};
+use ibc::core::router::module::Module;
+use ibc::core::router::types::module::{ModuleExtras, ModuleId};
+use ibc::primitives::Signer;
+use namada_core::address::Address;
+use namada_tx::action::IbcHook;
+
+use super::common::IbcCommonContext;
+use super::transfer_mod::{ModuleWrapper, TransferModule};
+use crate::hooks::IbcHookMemo;
+use crate::storage;
+
+/// IBC hooks middleware wrapping the token transfer module. A received packet
+/// with a hook memo schedules the hook to be executed by the protocol.
+#[derive(Debug)]
+pub struct IbcHooksModule<C>
+where
+    C: IbcCommonContext,
+{
+    transfer: TransferModule<C>,
+    ctx: Rc<RefCell<C>>,
+    /// The failure of the hook in the previous execution of the tx
+    hook_failure: Option<String>,
+}
+
+impl<C> IbcHooksModule<C>
+where
+    C: IbcCommonContext,
+{
+    /// Make a new middleware on the token transfer module. The packet is
+    /// acknowledged with the hook failure if it's given.
+    pub fn new(
+        ctx: Rc<RefCell<C>>,
+        transfer: TransferModule<C>,
+        hook_failure: Option<String>,
+    ) -> Self {
+        Self {
+            transfer,
+            ctx,
+            hook_failure,
+        }
+    }
+
+    // Make the hook from the incoming packet. Returns `None` if the packet
+    // doesn't trigger a hook.
+    fn hook(
+        &self,
+        packet_data: &PacketData,
+    ) -> Option<Result<IbcHook, String>> {
+        let memo = IbcHookMemo::parse(packet_data.memo.as_ref())?;
+        Some(memo.and_then(|memo| {
+            if let Some(failure) = &self.hook_failure {
+                return Err(format!("IBC hook failed: {failure}"));
+            }
+            let receiver = Address::from_str(packet_data.receiver.as_ref())
+                .map_err(|_| {
+                    format!(
+                        "The receiver {} of the hook is invalid",
+                        packet_data.receiver
+                    )
+                })?;
+            let hook = memo.hook.to_hook(receiver)?;
+            let is_allowed = storage::is_hook_allowed(
+                self.ctx.borrow().storage(),
+                &hook.code_hash,
+            )
+            .map_err(|e| e.to_string())?;
+            if !is_allowed {
+                return Err(format!(
+                    "The hook code {} isn't allowed",
+                    hook.code_hash
+                ));
+            }
+            Ok(hook)
+        }))
+    }
+}
+
+impl<C> ModuleWrapper for IbcHooksModule<C>
+where
+    C: IbcCommonContext + Debug,
+{
+    fn as_module(&self) -> &dyn Module {
+        self
+    }
+
+    fn as_module_mut(&mut self) -> &mut dyn Module {
+        self
+    }
+
+    fn module_id(&self) -> ModuleId {
+        ModuleId::new(MODULE_ID_STR.to_string())
+    }
+
+    fn port_id(&self) -> PortId {
+        self.transfer.port_id()
+    }
+}
+
+impl<C> Module for IbcHooksModule<C>
+where
+    C: IbcCommonContext + Debug,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<Version, ChannelError> {
+        self.transfer.on_chan_open_init_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        self.transfer.on_chan_open_init_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<Version, ChannelError> {
+        self.transfer.on_chan_open_try_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            counterparty_version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        self.transfer.on_chan_open_try_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_ack_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<(), ChannelError> {
+        self.transfer.on_chan_open_ack_validate(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_ack_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer.on_chan_open_ack_execute(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_open_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_open_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_open_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_close_init_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_close_init_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_close_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_close_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_recv_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Acknowledgement) {
+        let Ok(packet_data) =
+            serde_json::from_slice::<PacketData>(&packet.data)
+        else {
+            return self.transfer.on_recv_packet_execute(packet, relayer);
+        };
+        let hook = match self.hook(&packet_data) {
+            None => {
+                return self.transfer.on_recv_packet_execute(packet, relayer);
+            }
+            Some(Ok(hook)) => hook,
+            Some(Err(e)) => return (ModuleExtras::empty(), error_ack(e)),
+        };
+
+        let (extras, ack) =
+            self.transfer.on_recv_packet_execute(packet, relayer);
+        let is_success =
+            serde_json::from_slice::<AcknowledgementStatus>(ack.as_ref())
+                .map(|status| status.is_successful())
+                .unwrap_or(false);
+        if is_success {
+            if let Err(e) = self.ctx.borrow_mut().schedule_hook(hook) {
+                return (extras, error_ack(e.to_string()));
+            }
+        }
+        (extras, ack)
+    }
+
+    fn on_acknowledgement_packet_validate(
+        &self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        self.transfer.on_acknowledgement_packet_validate(
+            packet,
+            acknowledgement,
+            relayer,
+        )
+    }
+
+    fn on_acknowledgement_packet_execute(
+        &mut self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        self.transfer.on_acknowledgement_packet_execute(
+            packet,
+            acknowledgement,
+            relayer,
+        )
+    }
+
+    fn on_timeout_packet_validate(
+        &self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        self.transfer.on_timeout_packet_validate(packet, relayer)
+    }
+
+    fn on_timeout_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        self.transfer.on_timeout_packet_execute(packet, relayer)
+    }
+}
+
+fn error_ack(description: String) -> Acknowledgement {
+    AcknowledgementStatus::error(
+        StatusValue::new(description)
+            .expect("The error message shouldn't be empty"),
+    )
+    .into()
+}
@@ -0,0 +1,599 @@
+//! IBC module for interchain accounts
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use ibc::apps::transfer::types::ack_success_b64;
+use ibc::core::channel::types::acknowledgement::{
+    Acknowledgement, AcknowledgementStatus, StatusValue,
+};
+use ibc::core::channel::types::channel::{Counterparty, Order, State};
+use ibc::core::channel::types::error::{ChannelError, PacketError};
+use ibc::core::channel::types::packet::Packet;
+use ibc::core::channel::types::Version;
+use ibc::core::host::types::identifiers::{ChannelId, ConnectionId, PortId};
+use ibc::core::router::module::Module;
+use ibc::core::router::types::module::{ModuleExtras, ModuleId};
+use ibc::primitives::Signer;
+use namada_core::address::{interchain_account, Address};
+use namada_core::storage::Key;
+use namada_state::{StorageRead, StorageWrite};
+
+use super::common::IbcCommonContext;
+use super::transfer_mod::ModuleWrapper;
+use crate::ica::{
+    controller_owner, is_host_port, InterchainAccountPacketData, Metadata,
+    CONTROLLER_PORT_ID_PREFIX, HOST_MSGS_FAILURE, HOST_PORT_ID_STR,
+    MODULE_ID_STR,
+};
+use crate::storage;
+
+/// IBC module for ICS-27 interchain accounts. It handles both the host port
+/// and the controller ports.
+#[derive(Debug)]
+pub struct IcaModule<C>
+where
+    C: IbcCommonContext,
+{
+    ctx: Rc<RefCell<C>>,
+}
+
+impl<C> IcaModule<C>
+where
+    C: IbcCommonContext,
+{
+    /// Make a new module
+    pub fn new(ctx: Rc<RefCell<C>>) -> Self {
+        Self { ctx }
+    }
+
+    fn single_connection_hop(
+        connection_hops: &[ConnectionId],
+    ) -> Result<&ConnectionId, String> {
+        match connection_hops {
+            [connection_id] => Ok(connection_id),
+            _ => Err(format!(
+                "Interchain accounts require a single connection hop: \
+                 {connection_hops:?}"
+            )),
+        }
+    }
+
+    fn read_channel_id(&self, key: &Key) -> Result<Option<ChannelId>, String> {
+        self.ctx
+            .borrow()
+            .storage()
+            .read::<String>(key)
+            .map_err(|e| e.to_string())?
+            .map(|channel_id| {
+                ChannelId::from_str(&channel_id).map_err(|e| e.to_string())
+            })
+            .transpose()
+    }
+
+    fn write_value(&self, key: &Key, value: String) -> Result<(), String> {
+        self.ctx
+            .borrow_mut()
+            .storage_mut()
+            .write(key, value)
+            .map_err(|e| e.to_string())
+    }
+
+    // Check that the active channel doesn't exist or it has been closed
+    fn check_no_active_channel(
+        &self,
+        port_id: &PortId,
+        active_channel: Option<ChannelId>,
+    ) -> Result<(), String> {
+        if let Some(channel_id) = active_channel {
+            let channel_end = self
+                .ctx
+                .borrow()
+                .channel_end(port_id, &channel_id)
+                .map_err(|e| e.to_string())?;
+            if !channel_end.state_matches(&State::Closed) {
+                return Err(format!(
+                    "The active channel {channel_id} already exists on the \
+                     port {port_id}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn chan_open_init(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<Version, String> {
+        if controller_owner(port_id).is_none() {
+            return Err(format!(
+                "The channel can't be initialized on the port {port_id}"
+            ));
+        }
+        if order != Order::Ordered {
+            return Err("The channel should be ordered".to_string());
+        }
+        if !is_host_port(&counterparty.port_id) {
+            return Err(format!(
+                "The counterparty port should be {HOST_PORT_ID_STR}"
+            ));
+        }
+        let connection_id = Self::single_connection_hop(connection_hops)?;
+        let metadata = if version.as_str().is_empty() {
+            let connection_end = self
+                .ctx
+                .borrow()
+                .connection_end(connection_id)
+                .map_err(|e| e.to_string())?;
+            let host_connection_id = connection_end
+                .counterparty()
+                .connection_id()
+                .ok_or_else(|| {
+                    format!(
+                        "No counterparty connection of the connection \
+                         {connection_id}"
+                    )
+                })?;
+            Metadata::new(connection_id, host_connection_id)
+        } else {
+            Metadata::from_str(version.as_str())?
+        };
+        metadata.validate()?;
+        if metadata.controller_connection_id != connection_id.as_str() {
+            return Err(format!(
+                "The controller connection ID mismatched: expected \
+                 {connection_id}, metadata {}",
+                metadata.controller_connection_id
+            ));
+        }
+
+        let key = storage::ica_controller_channel_key(connection_id, port_id);
+        let active_channel = self.read_channel_id(&key)?;
+        self.check_no_active_channel(port_id, active_channel)?;
+
+        Ok(Version::new(metadata.to_string()))
+    }
+
+    fn chan_open_try(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<(Address, Version), String> {
+        if !is_host_port(port_id) {
+            return Err(format!(
+                "The channel can't be opened on the port {port_id}"
+            ));
+        }
+        if order != Order::Ordered {
+            return Err("The channel should be ordered".to_string());
+        }
+        let controller_port_id = &counterparty.port_id;
+        if controller_owner(controller_port_id).is_none() {
+            return Err(format!(
+                "The counterparty port should start with \
+                 {CONTROLLER_PORT_ID_PREFIX}"
+            ));
+        }
+        let connection_id = Self::single_connection_hop(connection_hops)?;
+        let metadata = Metadata::from_str(counterparty_version.as_str())?;
+        metadata.validate()?;
+        if metadata.host_connection_id != connection_id.as_str() {
+            return Err(format!(
+                "The host connection ID mismatched: expected {connection_id}, \
+                 metadata {}",
+                metadata.host_connection_id
+            ));
+        }
+
+        let key =
+            storage::ica_host_channel_key(connection_id, controller_port_id);
+        let active_channel = self.read_channel_id(&key)?;
+        self.check_no_active_channel(port_id, active_channel)?;
+
+        let address = interchain_account(
+            connection_id.as_str(),
+            controller_port_id.as_str(),
+        );
+        if !metadata.address.is_empty()
+            && metadata.address != address.to_string()
+        {
+            return Err(format!(
+                "The interchain account address mismatched: expected \
+                 {address}, metadata {}",
+                metadata.address
+            ));
+        }
+        let version = Version::new(
+            Metadata {
+                address: address.to_string(),
+                ..metadata
+            }
+            .to_string(),
+        );
+        Ok((address, version))
+    }
+
+    fn chan_open_ack(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<(ConnectionId, Metadata), String> {
+        if controller_owner(port_id).is_none() {
+            return Err(format!(
+                "The channel can't be acknowledged on the port {port_id}"
+            ));
+        }
+        let channel_end = self
+            .ctx
+            .borrow()
+            .channel_end(port_id, channel_id)
+            .map_err(|e| e.to_string())?;
+        let connection_id =
+            Self::single_connection_hop(channel_end.connection_hops())?.clone();
+        let metadata = Metadata::from_str(counterparty_version.as_str())?;
+        metadata.validate()?;
+        if metadata.controller_connection_id != connection_id.as_str() {
+            return Err(format!(
+                "The controller connection ID mismatched: expected \
+                 {connection_id}, metadata {}",
+                metadata.controller_connection_id
+            ));
+        }
+        if metadata.address.is_empty() {
+            return Err("No interchain account address".to_string());
+        }
+        Ok((connection_id, metadata))
+    }
+
+    fn chan_open_confirm(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(ConnectionId, PortId), String> {
+        if !is_host_port(port_id) {
+            return Err(format!(
+                "The channel can't be confirmed on the port {port_id}"
+            ));
+        }
+        let channel_end = self
+            .ctx
+            .borrow()
+            .channel_end(port_id, channel_id)
+            .map_err(|e| e.to_string())?;
+        let connection_id =
+            Self::single_connection_hop(channel_end.connection_hops())?.clone();
+        Ok((connection_id, channel_end.counterparty().port_id.clone()))
+    }
+
+    fn receive_packet(&self, packet: &Packet) -> Result<(), String> {
+        if !is_host_port(&packet.port_id_on_b) {
+            return Err(format!(
+                "Packets can't be received on the port {}",
+                packet.port_id_on_b
+            ));
+        }
+        let channel_end = self
+            .ctx
+            .borrow()
+            .channel_end(&packet.port_id_on_b, &packet.chan_id_on_b)
+            .map_err(|e| e.to_string())?;
+        let connection_id =
+            Self::single_connection_hop(channel_end.connection_hops())?;
+
+        let key =
+            storage::ica_host_channel_key(connection_id, &packet.port_id_on_a);
+        if self.read_channel_id(&key)?.as_ref() != Some(&packet.chan_id_on_b) {
+            return Err(format!(
+                "The channel {} isn't active for the controller port {}",
+                packet.chan_id_on_b, packet.port_id_on_a
+            ));
+        }
+        let key =
+            storage::ica_host_account_key(connection_id, &packet.port_id_on_a);
+        let account = self
+            .ctx
+            .borrow()
+            .storage()
+            .read::<Address>(&key)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| {
+                format!(
+                    "No interchain account for the controller port {}",
+                    packet.port_id_on_a
+                )
+            })?;
+
+        let packet_data =
+            serde_json::from_slice::<InterchainAccountPacketData>(&packet.data)
+                .map_err(|e| format!("Decoding the packet data failed: {e}"))?;
+        let msgs = packet_data.decode_host_msgs()?;
+        let result = self.ctx.borrow_mut().execute_host_msgs(&account, &msgs);
+        // The error acknowledgement should be deterministic, so the details
+        // are only logged
+        result.map_err(|e| {
+            self.ctx.borrow().log_string(format!(
+                "Executing the host messages of the interchain account \
+                 {account} failed: {e}"
+            ));
+            HOST_MSGS_FAILURE.to_string()
+        })
+    }
+}
+
+impl<C> ModuleWrapper for IcaModule<C>
+where
+    C: IbcCommonContext + Debug,
+{
+    fn as_module(&self) -> &dyn Module {
+        self
+    }
+
+    fn as_module_mut(&mut self) -> &mut dyn Module {
+        self
+    }
+
+    fn module_id(&self) -> ModuleId {
+        ModuleId::new(MODULE_ID_STR.to_string())
+    }
+
+    fn port_id(&self) -> PortId {
+        PortId::from_str(HOST_PORT_ID_STR).expect("The port ID should be valid")
+    }
+
+    fn port_prefix(&self) -> Option<&'static str> {
+        Some(CONTROLLER_PORT_ID_PREFIX)
+    }
+}
+
+impl<C> Module for IcaModule<C>
+where
+    C: IbcCommonContext + Debug,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<Version, ChannelError> {
+        self.chan_open_init(
+            order,
+            connection_hops,
+            port_id,
+            counterparty,
+            version,
+        )
+        .map_err(into_channel_error)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        let version = self
+            .chan_open_init(
+                order,
+                connection_hops,
+                port_id,
+                counterparty,
+                version,
+            )
+            .map_err(into_channel_error)?;
+        Ok((ModuleExtras::empty(), version))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<Version, ChannelError> {
+        let (_, version) = self
+            .chan_open_try(
+                order,
+                connection_hops,
+                port_id,
+                counterparty,
+                counterparty_version,
+            )
+            .map_err(into_channel_error)?;
+        Ok(version)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        let (address, version) = self
+            .chan_open_try(
+                order,
+                connection_hops,
+                port_id,
+                counterparty,
+                counterparty_version,
+            )
+            .map_err(into_channel_error)?;
+        let connection_id = Self::single_connection_hop(connection_hops)
+            .map_err(into_channel_error)?;
+        let key =
+            storage::ica_host_account_key(connection_id, &counterparty.port_id);
+        self.ctx
+            .borrow_mut()
+            .storage_mut()
+            .write(&key, address)
+            .map_err(|e| into_channel_error(e.to_string()))?;
+        Ok((ModuleExtras::empty(), version))
+    }
+
+    fn on_chan_open_ack_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<(), ChannelError> {
+        self.chan_open_ack(port_id, channel_id, counterparty_version)
+            .map_err(into_channel_error)?;
+        Ok(())
+    }
+
+    fn on_chan_open_ack_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<ModuleExtras, ChannelError> {
+        let (connection_id, metadata) = self
+            .chan_open_ack(port_id, channel_id, counterparty_version)
+            .map_err(into_channel_error)?;
+        let key = storage::ica_controller_account_key(&connection_id, port_id);
+        self.write_value(&key, metadata.address)
+            .map_err(into_channel_error)?;
+        let key = storage::ica_controller_channel_key(&connection_id, port_id);
+        self.write_value(&key, channel_id.to_string())
+            .map_err(into_channel_error)?;
+        Ok(ModuleExtras::empty())
+    }
+
+    fn on_chan_open_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.chan_open_confirm(port_id, channel_id)
+            .map_err(into_channel_error)?;
+        Ok(())
+    }
+
+    fn on_chan_open_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        let (connection_id, controller_port_id) = self
+            .chan_open_confirm(port_id, channel_id)
+            .map_err(into_channel_error)?;
+        let key =
+            storage::ica_host_channel_key(&connection_id, &controller_port_id);
+        self.write_value(&key, channel_id.to_string())
+            .map_err(into_channel_error)?;
+        Ok(ModuleExtras::empty())
+    }
+
+    fn on_chan_close_init_validate(
+        &self,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        Err(into_channel_error(
+            "Interchain account channels can't be closed by users".to_string(),
+        ))
+    }
+
+    fn on_chan_close_init_execute(
+        &mut self,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        Err(into_channel_error(
+            "Interchain account channels can't be closed by users".to_string(),
+        ))
+    }
+
+    fn on_chan_close_confirm_validate(
+        &self,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        Ok(())
+    }
+
+    fn on_chan_close_confirm_execute(
+        &mut self,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        Ok(ModuleExtras::empty())
+    }
+
+    fn on_recv_packet_execute(
+        &mut self,
+        packet: &Packet,
+        _relayer: &Signer,
+    ) -> (ModuleExtras, Acknowledgement) {
+        let ack = match self.receive_packet(packet) {
+            Ok(()) => AcknowledgementStatus::success(ack_success_b64()),
+            Err(e) => AcknowledgementStatus::error(
+                StatusValue::new(e)
+                    .expect("The error message shouldn't be empty"),
+            ),
+        };
+        (ModuleExtras::empty(), ack.into())
+    }
+
+    fn on_acknowledgement_packet_validate(
+        &self,
+        _packet: &Packet,
+        _acknowledgement: &Acknowledgement,
+        _relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        Ok(())
+    }
+
+    fn on_acknowledgement_packet_execute(
+        &mut self,
+        _packet: &Packet,
+        _acknowledgement: &Acknowledgement,
+        _relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        (ModuleExtras::empty(), Ok(()))
+    }
+
+    fn on_timeout_packet_validate(
+        &self,
+        _packet: &Packet,
+        _relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        Ok(())
+    }
+
+    fn on_timeout_packet_execute(
+        &mut self,
+        _packet: &Packet,
+        _relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        (ModuleExtras::empty(), Ok(()))
+    }
+}
+
+fn into_channel_error(description: String) -> ChannelError {
+    ChannelError::AppModule { description }
+}
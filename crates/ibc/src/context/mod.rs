@@ -3,13 +3,18 @@
 pub mod client;
 pub mod common;
 pub mod execution;
+pub mod fee_mod;
+pub mod hooks_mod;
+pub mod ica_mod;
 pub mod nft_transfer;
 pub mod nft_transfer_mod;
+pub mod packet_forward_mod;
 pub mod router;
 pub mod storage;
 pub mod token_transfer;
 pub mod transfer_mod;
 pub mod validation;
+pub mod wasm_client;
 
 use std::cell::RefCell;
 use std::fmt::Debug;
@@ -22,6 +27,8 @@ use ibc::core::host::types::identifiers::ChainId as IbcChainId;
 use namada_core::hash::Sha256Hasher;
 use namada_state::merkle_tree::ics23_specs::proof_specs;
 
+use crate::wasm_client::PendingLightClientCalls;
+
 /// IBC context to handle IBC-related data
 #[derive(Debug)]
 pub struct IbcContext<C, Params>
@@ -32,6 +39,8 @@ where
     pub inner: Rc<RefCell<C>>,
     /// Validation parameters for IBC VP
     pub validation_params: ValidationParams,
+    /// Calls to the wasm light client code requested in the validation
+    pub light_client_calls: PendingLightClientCalls,
     /// Marker for DI types
     pub _marker: PhantomData<Params>,
 }
@@ -45,6 +54,7 @@ where
         Self {
             inner,
             validation_params: ValidationParams::default(),
+            light_client_calls: Rc::new(RefCell::new(Vec::new())),
             _marker: PhantomData,
         }
     }
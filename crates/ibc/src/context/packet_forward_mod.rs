@@ -0,0 +1,457 @@
+//! IBC packet-forward middleware on the token transfer module
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use ibc::apps::transfer::types::packet::PacketData;
+use ibc::apps::transfer::types::{
+    is_receiver_chain_source, TracePrefix, MODULE_ID_STR,
+};
+use ibc::core::channel::types::acknowledgement::{
+    Acknowledgement, AcknowledgementStatus, StatusValue,
+};
+use ibc::core::channel::types::channel::{Counterparty, Order};
+use ibc::core::channel::types::error::{ChannelError, PacketError};
+use ibc::core::channel::types::packet::Packet;
+use ibc::core::channel::types::Version;
+use ibc::core::host::types::identifiers::{ChannelId, ConnectionId, PortId};
+use ibc::core::router::module::Module;
+use ibc::core::router::types::module::{ModuleExtras, ModuleId};
+use ibc::primitives::Signer;
+use namada_state::{StorageRead, StorageWrite};
+
+use super::common::IbcCommonContext;
+use super::transfer_mod::{ModuleWrapper, TransferModule};
+use crate::packet_forward::{
+    ForwardRefund, ForwardTransfer, PacketForwardMemo, DEFAULT_FORWARD_RETRIES,
+    DEFAULT_FORWARD_TIMEOUT,
+};
+use crate::{storage, IBC_ESCROW_ADDRESS};
+
+/// Transfers to be sent by the packet-forward middleware
+pub type PendingForwards = Rc<RefCell<Vec<ForwardTransfer>>>;
+
+/// Packet-forward middleware wrapping the token transfer module or another
+/// middleware on it. The forwarded tokens are held by the IBC account between
+/// the hops.
+#[derive(Debug)]
+pub struct PacketForwardModule<C, M = TransferModule<C>>
+where
+    C: IbcCommonContext,
+{
+    transfer: M,
+    ctx: Rc<RefCell<C>>,
+    /// Transfers to be sent. They are sent by `IbcActions` after the packet
+    /// handling.
+    pub(crate) forwards: PendingForwards,
+}
+
+impl<C, M> PacketForwardModule<C, M>
+where
+    C: IbcCommonContext,
+    M: ModuleWrapper,
+{
+    /// Make a new middleware on the token transfer module
+    pub fn new(ctx: Rc<RefCell<C>>, transfer: M) -> Self {
+        Self {
+            transfer,
+            ctx,
+            forwards: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    // Make the transfer to the next hop from the incoming packet. Returns
+    // `None` if the packet isn't forwarded.
+    fn forward_transfer(
+        &self,
+        packet: &Packet,
+        packet_data: &PacketData,
+    ) -> Option<Result<ForwardTransfer, String>> {
+        let memo = PacketForwardMemo::parse(packet_data.memo.as_ref())?;
+        Some(memo.and_then(|memo| {
+            let forward = memo.forward;
+            if forward.receiver.is_empty() {
+                return Err("No receiver to forward the packet".to_string());
+            }
+            let port_id =
+                PortId::from_str(&forward.port).map_err(|e| e.to_string())?;
+            let channel_id = ChannelId::from_str(&forward.channel)
+                .map_err(|e| e.to_string())?;
+            if port_id == packet.port_id_on_b
+                && channel_id == packet.chan_id_on_b
+            {
+                return Err("The packet can't be forwarded back to the same \
+                            channel"
+                    .to_string());
+            }
+
+            // The denom held by the IBC account after receiving
+            let mut denom = packet_data.token.denom.clone();
+            if is_receiver_chain_source(
+                packet.port_id_on_a.clone(),
+                packet.chan_id_on_a.clone(),
+                &denom,
+            ) {
+                denom.remove_trace_prefix(&TracePrefix::new(
+                    packet.port_id_on_a.clone(),
+                    packet.chan_id_on_a.clone(),
+                ));
+            } else {
+                denom.add_trace_prefix(TracePrefix::new(
+                    packet.port_id_on_b.clone(),
+                    packet.chan_id_on_b.clone(),
+                ));
+            }
+
+            Ok(ForwardTransfer {
+                port_id: port_id.to_string(),
+                channel_id: channel_id.to_string(),
+                receiver: forward.receiver.clone(),
+                denom: denom.to_string(),
+                amount: packet_data.token.amount.to_string(),
+                memo: forward.next_memo(),
+                timeout: forward.timeout.unwrap_or(DEFAULT_FORWARD_TIMEOUT),
+                retries: forward.retries.unwrap_or(DEFAULT_FORWARD_RETRIES),
+                refund: Some(ForwardRefund {
+                    port_id: packet.port_id_on_b.to_string(),
+                    channel_id: packet.chan_id_on_b.to_string(),
+                    receiver: packet_data.sender.to_string(),
+                }),
+            })
+        }))
+    }
+
+    // Re-send or refund the forwarded packet if it failed
+    fn on_forward_result(
+        &self,
+        packet: &Packet,
+        is_success: bool,
+    ) -> Result<(), String> {
+        let key = storage::forward_key(
+            &packet.port_id_on_a,
+            &packet.chan_id_on_a,
+            packet.seq_on_a,
+        );
+        let Some(forward) = self
+            .ctx
+            .borrow()
+            .storage()
+            .read::<ForwardTransfer>(&key)
+            .map_err(|e| e.to_string())?
+        else {
+            // The packet wasn't sent by this middleware
+            return Ok(());
+        };
+        self.ctx
+            .borrow_mut()
+            .storage_mut()
+            .delete(&key)
+            .map_err(|e| e.to_string())?;
+
+        if !is_success {
+            let next = forward.retry().unwrap_or_else(|| forward.refund());
+            self.forwards.borrow_mut().push(next);
+        }
+        Ok(())
+    }
+}
+
+impl<C, M> ModuleWrapper for PacketForwardModule<C, M>
+where
+    C: IbcCommonContext + Debug,
+    M: ModuleWrapper,
+{
+    fn as_module(&self) -> &dyn Module {
+        self
+    }
+
+    fn as_module_mut(&mut self) -> &mut dyn Module {
+        self
+    }
+
+    fn module_id(&self) -> ModuleId {
+        ModuleId::new(MODULE_ID_STR.to_string())
+    }
+
+    fn port_id(&self) -> PortId {
+        self.transfer.port_id()
+    }
+}
+
+impl<C, M> Module for PacketForwardModule<C, M>
+where
+    C: IbcCommonContext + Debug,
+    M: ModuleWrapper,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<Version, ChannelError> {
+        self.transfer.on_chan_open_init_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        self.transfer.on_chan_open_init_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_validate(
+        &self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<Version, ChannelError> {
+        self.transfer.on_chan_open_try_validate(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            counterparty_version,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try_execute(
+        &mut self,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        counterparty_version: &Version,
+    ) -> Result<(ModuleExtras, Version), ChannelError> {
+        self.transfer.on_chan_open_try_execute(
+            order,
+            connection_hops,
+            port_id,
+            channel_id,
+            counterparty,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_ack_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<(), ChannelError> {
+        self.transfer.on_chan_open_ack_validate(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_ack_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &Version,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer.on_chan_open_ack_execute(
+            port_id,
+            channel_id,
+            counterparty_version,
+        )
+    }
+
+    fn on_chan_open_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_open_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_open_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_open_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_close_init_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_init_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_close_init_execute(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_validate(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<(), ChannelError> {
+        self.transfer
+            .on_chan_close_confirm_validate(port_id, channel_id)
+    }
+
+    fn on_chan_close_confirm_execute(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<ModuleExtras, ChannelError> {
+        self.transfer
+            .on_chan_close_confirm_execute(port_id, channel_id)
+    }
+
+    fn on_recv_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Acknowledgement) {
+        let Ok(mut packet_data) =
+            serde_json::from_slice::<PacketData>(&packet.data)
+        else {
+            return self.transfer.on_recv_packet_execute(packet, relayer);
+        };
+        let forward = match self.forward_transfer(packet, &packet_data) {
+            None => {
+                return self.transfer.on_recv_packet_execute(packet, relayer);
+            }
+            Some(Ok(forward)) => forward,
+            Some(Err(e)) => {
+                let ack = AcknowledgementStatus::error(
+                    StatusValue::new(e)
+                        .expect("The error message shouldn't be empty"),
+                );
+                return (ModuleExtras::empty(), ack.into());
+            }
+        };
+
+        // Receive the tokens to the IBC account instead of the receiver
+        packet_data.receiver = IBC_ESCROW_ADDRESS.to_string().into();
+        packet_data.memo = String::new().into();
+        let mut received_packet = packet.clone();
+        received_packet.data = serde_json::to_vec(&packet_data)
+            .expect("Encoding the packet data shouldn't fail");
+        let (extras, ack) = self
+            .transfer
+            .on_recv_packet_execute(&received_packet, relayer);
+
+        let is_success =
+            serde_json::from_slice::<AcknowledgementStatus>(ack.as_ref())
+                .map(|status| status.is_successful())
+                .unwrap_or(false);
+        if is_success {
+            self.forwards.borrow_mut().push(forward);
+        }
+        (extras, ack)
+    }
+
+    fn on_acknowledgement_packet_validate(
+        &self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        self.transfer.on_acknowledgement_packet_validate(
+            packet,
+            acknowledgement,
+            relayer,
+        )
+    }
+
+    fn on_acknowledgement_packet_execute(
+        &mut self,
+        packet: &Packet,
+        acknowledgement: &Acknowledgement,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        let (extras, result) = self.transfer.on_acknowledgement_packet_execute(
+            packet,
+            acknowledgement,
+            relayer,
+        );
+        if result.is_err() {
+            return (extras, result);
+        }
+        let is_success = serde_json::from_slice::<AcknowledgementStatus>(
+            acknowledgement.as_ref(),
+        )
+        .map(|status| status.is_successful())
+        .unwrap_or(false);
+        let result = self
+            .on_forward_result(packet, is_success)
+            .map_err(into_packet_error);
+        (extras, result)
+    }
+
+    fn on_timeout_packet_validate(
+        &self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> Result<(), PacketError> {
+        self.transfer.on_timeout_packet_validate(packet, relayer)
+    }
+
+    fn on_timeout_packet_execute(
+        &mut self,
+        packet: &Packet,
+        relayer: &Signer,
+    ) -> (ModuleExtras, Result<(), PacketError>) {
+        let (extras, result) =
+            self.transfer.on_timeout_packet_execute(packet, relayer);
+        if result.is_err() {
+            return (extras, result);
+        }
+        let result = self
+            .on_forward_result(packet, false)
+            .map_err(into_packet_error);
+        (extras, result)
+    }
+}
+
+fn into_packet_error(description: String) -> PacketError {
+    PacketError::AppModule { description }
+}
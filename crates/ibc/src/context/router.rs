@@ -15,6 +15,7 @@ use super::super::ModuleWrapper;
 pub struct IbcRouter<'a> {
     modules: HashMap<ModuleId, Rc<dyn ModuleWrapper + 'a>>,
     ports: HashMap<PortId, ModuleId>,
+    port_prefixes: HashMap<&'static str, ModuleId>,
 }
 
 impl<'a> IbcRouter<'a> {
@@ -23,13 +24,22 @@ impl<'a> IbcRouter<'a> {
         Self {
             modules: HashMap::new(),
             ports: HashMap::new(),
+            port_prefixes: HashMap::new(),
         }
     }
 
     /// Add TokenTransfer route
     pub fn add_transfer_module(&mut self, module: impl ModuleWrapper + 'a) {
+        self.add_module(module)
+    }
+
+    /// Add a module route with its port and the port prefix
+    pub fn add_module(&mut self, module: impl ModuleWrapper + 'a) {
         let module_id = module.module_id();
         let port_id = module.port_id();
+        if let Some(prefix) = module.port_prefix() {
+            self.port_prefixes.insert(prefix, module_id.clone());
+        }
         self.modules.insert(module_id.clone(), Rc::new(module));
         self.ports.insert(port_id, module_id);
     }
@@ -51,6 +61,11 @@ impl<'a> Router for IbcRouter<'a> {
     }
 
     fn lookup_module(&self, port_id: &PortId) -> Option<ModuleId> {
-        self.ports.get(port_id).cloned()
+        self.ports.get(port_id).cloned().or_else(|| {
+            self.port_prefixes
+                .iter()
+                .find(|(prefix, _)| port_id.as_str().starts_with(*prefix))
+                .map(|(_, module_id)| module_id.clone())
+        })
     }
 }
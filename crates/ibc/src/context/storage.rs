@@ -4,8 +4,10 @@ pub use ics23::ProofSpec;
 use namada_core::address::Address;
 use namada_core::token::Amount;
 use namada_state::{Result, StorageRead, StorageWrite};
+use namada_tx::action::IbcHook;
 
 use crate::event::IbcEvent;
+use crate::ica::HostMsg;
 
 /// IBC context trait to be implemented in integration that can read and write
 pub trait IbcStorageContext {
@@ -46,6 +48,17 @@ pub trait IbcStorageContext {
         amount: Amount,
     ) -> Result<()>;
 
+    /// Execute the host messages received by the interchain account. The
+    /// execution is atomic: nothing is written when any message fails.
+    fn execute_host_msgs(
+        &mut self,
+        account: &Address,
+        msgs: &[HostMsg],
+    ) -> Result<()>;
+
+    /// Schedule the hook triggered by a received packet
+    fn schedule_hook(&mut self, hook: IbcHook) -> Result<()>;
+
     /// Insert the verifier
     fn insert_verifier(&mut self, verifier: &Address) -> Result<()>;
 
@@ -1,8 +1,9 @@
 //! IBC token transfer context
 
 use std::cell::RefCell;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
+use std::str::FromStr;
 
 use ibc::apps::transfer::context::{
     TokenTransferExecutionContext, TokenTransferValidationContext,
@@ -17,7 +18,9 @@ use namada_core::token::Amount;
 use namada_core::uint::Uint;
 
 use super::common::IbcCommonContext;
-use crate::{trace, IBC_ESCROW_ADDRESS};
+use crate::event::{IbcEvent, IbcEventType, RATE_LIMIT_EVENT};
+use crate::rate_limit::FlowDirection;
+use crate::{storage, trace, IBC_ESCROW_ADDRESS};
 
 /// Token transfer context to handle tokens
 #[derive(Debug)]
@@ -146,6 +149,92 @@ where
             .map_err(TokenTransferError::from)
     }
 
+    /// Add the amount to the rolling-window flows of the token over all the
+    /// channels and over the channel, and emit the remaining quotas
+    fn add_flow(
+        &self,
+        token: &Address,
+        channel_id: &ChannelId,
+        direction: FlowDirection,
+        amount: Amount,
+    ) -> Result<(), TokenTransferError> {
+        let now =
+            self.inner.borrow().host_timestamp()?.nanoseconds() / 1_000_000_000;
+        for channel_id in [None, Some(channel_id)] {
+            let flow = self
+                .inner
+                .borrow()
+                .rate_limit_flow(token, channel_id)?
+                .add(now, direction, amount)
+                .ok_or_else(|| {
+                    TokenTransferError::Other(
+                        "The rate limit flow overflowed".to_string(),
+                    )
+                })?;
+            let limits = storage::get_rate_limits(
+                self.inner.borrow().storage(),
+                token,
+                channel_id,
+            )
+            .map_err(ContextError::from)?;
+            let remaining = flow.remaining(&limits, now);
+            self.inner
+                .borrow_mut()
+                .store_rate_limit_flow(token, channel_id, flow)?;
+
+            let (hourly, daily) = match direction {
+                FlowDirection::Inbound => {
+                    (remaining.hourly_inbound, remaining.daily_inbound)
+                }
+                FlowDirection::Outbound => {
+                    (remaining.hourly_outbound, remaining.daily_outbound)
+                }
+            };
+            let quota = |remaining: Option<Amount>| {
+                remaining.map_or("unlimited".to_string(), |amount| {
+                    amount.to_string()
+                })
+            };
+            let mut attributes = HashMap::from([
+                ("token".to_string(), token.to_string()),
+                ("direction".to_string(), direction.as_str().to_string()),
+                ("amount".to_string(), amount.to_string()),
+                ("remaining_hourly_quota".to_string(), quota(hourly)),
+                ("remaining_daily_quota".to_string(), quota(daily)),
+            ]);
+            if let Some(channel_id) = channel_id {
+                attributes
+                    .insert("channel_id".to_string(), channel_id.to_string());
+            }
+            self.inner
+                .borrow_mut()
+                .emit_ibc_event(IbcEvent {
+                    event_type: IbcEventType(RATE_LIMIT_EVENT.to_string()),
+                    attributes,
+                })
+                .map_err(ContextError::from)?;
+        }
+        Ok(())
+    }
+
+    /// Get the channel on this chain from the trace path of the denom
+    fn trace_channel_id(
+        coin: &PrefixedCoin,
+    ) -> Result<ChannelId, TokenTransferError> {
+        coin.denom
+            .trace_path
+            .to_string()
+            .split('/')
+            .nth(1)
+            .and_then(|channel_id| ChannelId::from_str(channel_id).ok())
+            .ok_or_else(|| {
+                TokenTransferError::Other(format!(
+                    "No channel in the trace path: Denom {}",
+                    coin.denom
+                ))
+            })
+    }
+
     fn maybe_store_ibc_denom(
         &self,
         owner: &Address,
@@ -246,13 +335,14 @@ where
         &mut self,
         from_account: &Self::AccountId,
         _port_id: &PortId,
-        _channel_id: &ChannelId,
+        channel_id: &ChannelId,
         coin: &PrefixedCoin,
         _memo: &Memo,
     ) -> Result<(), TokenTransferError> {
         let (ibc_token, amount) = self.get_token_amount(coin)?;
 
         self.add_withdraw(&ibc_token, amount)?;
+        self.add_flow(&ibc_token, channel_id, FlowDirection::Outbound, amount)?;
 
         // A transfer of NUT tokens must be verified by their VP
         if ibc_token.is_internal()
@@ -282,12 +372,13 @@ where
         &mut self,
         to_account: &Self::AccountId,
         _port_id: &PortId,
-        _channel_id: &ChannelId,
+        channel_id: &ChannelId,
         coin: &PrefixedCoin,
     ) -> Result<(), TokenTransferError> {
         let (ibc_token, amount) = self.get_token_amount(coin)?;
 
         self.add_deposit(&ibc_token, amount)?;
+        self.add_flow(&ibc_token, channel_id, FlowDirection::Inbound, amount)?;
 
         self.inner
             .borrow_mut()
@@ -305,6 +396,8 @@ where
 
         self.update_mint_amount(&ibc_token, amount, true)?;
         self.add_deposit(&ibc_token, amount)?;
+        let channel_id = Self::trace_channel_id(coin)?;
+        self.add_flow(&ibc_token, &channel_id, FlowDirection::Inbound, amount)?;
 
         // A transfer of NUT tokens must be verified by their VP
         if ibc_token.is_internal()
@@ -333,6 +426,14 @@ where
 
         self.update_mint_amount(&ibc_token, amount, false)?;
         self.add_withdraw(&ibc_token, amount)?;
+        // The burned token is going back through the channel of the trace
+        let channel_id = Self::trace_channel_id(coin)?;
+        self.add_flow(
+            &ibc_token,
+            &channel_id,
+            FlowDirection::Outbound,
+            amount,
+        )?;
 
         // A transfer of NUT tokens must be verified by their VP
         if ibc_token.is_internal()
@@ -46,6 +46,11 @@ pub trait ModuleWrapper: Module {
 
     /// Get the port ID
     fn port_id(&self) -> PortId;
+
+    /// Get the prefix of the port IDs dynamically bound to the module, if any
+    fn port_prefix(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 /// IBC module for token transfer
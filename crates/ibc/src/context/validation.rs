@@ -100,7 +100,8 @@ where
         &self,
         client_id: &ClientId,
     ) -> Result<Self::ClientStateRef, ContextError> {
-        self.inner.borrow().client_state(client_id)
+        let client_state = self.inner.borrow().client_state(client_id)?;
+        Ok(client_state.with_light_client_calls(&self.light_client_calls))
     }
 
     fn consensus_state(
@@ -0,0 +1,474 @@
+//! ICS-08 wasm light client states for IBC context
+
+use std::str::FromStr;
+
+use ibc::core::client::context::client_state::{
+    ClientStateCommon, ClientStateExecution, ClientStateValidation,
+};
+use ibc::core::client::context::consensus_state::ConsensusState;
+use ibc::core::client::context::{
+    ClientExecutionContext, ClientValidationContext,
+    ExtClientValidationContext,
+};
+use ibc::core::client::types::error::ClientError;
+use ibc::core::client::types::{Height, Status};
+use ibc::core::commitment_types::commitment::{
+    CommitmentPrefix, CommitmentProofBytes, CommitmentRoot,
+};
+use ibc::core::handler::types::error::ContextError;
+use ibc::core::host::types::identifiers::{ClientId, ClientType};
+use ibc::core::host::types::path::{
+    ClientConsensusStatePath, ClientStatePath, Path,
+};
+use ibc::primitives::proto::Any;
+use ibc::primitives::Timestamp;
+use namada_systems::parameters;
+use prost::Message;
+
+use super::common::IbcCommonContext;
+use super::IbcContext;
+use crate::storage::is_wasm_client_allowed;
+use crate::wasm_client::{
+    ClientMessage, ClientState as WasmClientStateType,
+    ConsensusState as WasmConsensusStateType, PendingLightClientCalls,
+    WasmLightClientCall, WasmLightClientRequest, WASM_CLIENT_TYPE,
+};
+
+/// Wasm client state. The verification is requested to the light client
+/// code through the calls attached by the IBC context.
+#[derive(Clone, Debug)]
+pub struct WasmClientState {
+    inner: WasmClientStateType,
+    calls: Option<PendingLightClientCalls>,
+}
+
+impl WasmClientState {
+    /// Get the inner client state
+    pub fn inner(&self) -> &WasmClientStateType {
+        &self.inner
+    }
+
+    /// Attach the pending calls to request the verification
+    pub fn with_calls(self, calls: PendingLightClientCalls) -> Self {
+        Self {
+            calls: Some(calls),
+            ..self
+        }
+    }
+
+    fn with_inner(&self, inner: WasmClientStateType) -> Self {
+        Self {
+            inner,
+            calls: self.calls.clone(),
+        }
+    }
+
+    fn encoded(&self) -> Vec<u8> {
+        Any::from(self.inner.clone()).encode_to_vec()
+    }
+
+    // Request the verification to the light client code
+    fn request(
+        &self,
+        request: WasmLightClientRequest,
+    ) -> Result<(), ClientError> {
+        let calls = self.calls.as_ref().ok_or_else(|| ClientError::Other {
+            description: "The wasm light client can't be verified in this \
+                          context"
+                .to_string(),
+        })?;
+        calls.borrow_mut().push(WasmLightClientCall {
+            checksum: self.inner.checksum,
+            request,
+        });
+        Ok(())
+    }
+}
+
+impl From<WasmClientStateType> for WasmClientState {
+    fn from(inner: WasmClientStateType) -> Self {
+        Self { inner, calls: None }
+    }
+}
+
+impl From<WasmClientState> for Any {
+    fn from(cs: WasmClientState) -> Self {
+        cs.inner.into()
+    }
+}
+
+impl TryFrom<Any> for WasmClientState {
+    type Error = ClientError;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        WasmClientStateType::try_from(any).map(Into::into)
+    }
+}
+
+/// Wasm consensus state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmConsensusState(WasmConsensusStateType);
+
+impl WasmConsensusState {
+    /// Get the inner consensus state
+    pub fn inner(&self) -> &WasmConsensusStateType {
+        &self.0
+    }
+}
+
+impl From<WasmConsensusStateType> for WasmConsensusState {
+    fn from(cs: WasmConsensusStateType) -> Self {
+        Self(cs)
+    }
+}
+
+impl From<WasmConsensusState> for Any {
+    fn from(cs: WasmConsensusState) -> Self {
+        cs.0.into()
+    }
+}
+
+impl TryFrom<Any> for WasmConsensusState {
+    type Error = ClientError;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        WasmConsensusStateType::try_from(any).map(Self)
+    }
+}
+
+impl ConsensusState for WasmConsensusState {
+    fn root(&self) -> &CommitmentRoot {
+        &self.0.root
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        self.0.timestamp
+    }
+}
+
+impl ClientStateCommon for WasmClientState {
+    fn verify_consensus_state(
+        &self,
+        consensus_state: Any,
+    ) -> Result<(), ClientError> {
+        WasmConsensusState::try_from(consensus_state).map(|_| ())
+    }
+
+    fn client_type(&self) -> ClientType {
+        ClientType::from_str(WASM_CLIENT_TYPE)
+            .expect("The wasm client type should be valid")
+    }
+
+    fn latest_height(&self) -> Height {
+        self.inner.latest_height
+    }
+
+    fn validate_proof_height(
+        &self,
+        proof_height: Height,
+    ) -> Result<(), ClientError> {
+        if self.inner.latest_height < proof_height {
+            return Err(ClientError::Other {
+                description: format!(
+                    "The proof height {proof_height} is higher than the \
+                     latest height {} of the wasm client",
+                    self.inner.latest_height
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn verify_upgrade_client(
+        &self,
+        upgraded_client_state: Any,
+        upgraded_consensus_state: Any,
+        proof_upgrade_client: CommitmentProofBytes,
+        proof_upgrade_consensus_state: CommitmentProofBytes,
+        root: &CommitmentRoot,
+    ) -> Result<(), ClientError> {
+        self.request(WasmLightClientRequest::VerifyUpgrade {
+            client_state: self.encoded(),
+            upgraded_client_state: upgraded_client_state.encode_to_vec(),
+            upgraded_consensus_state: upgraded_consensus_state.encode_to_vec(),
+            proof_upgrade_client: proof_upgrade_client.into(),
+            proof_upgrade_consensus_state: proof_upgrade_consensus_state.into(),
+            root: root.as_bytes().to_vec(),
+        })
+    }
+
+    fn verify_membership(
+        &self,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        path: Path,
+        value: Vec<u8>,
+    ) -> Result<(), ClientError> {
+        self.request(WasmLightClientRequest::VerifyMembership {
+            client_state: self.encoded(),
+            prefix: prefix.as_bytes().to_vec(),
+            proof: proof.clone().into(),
+            root: root.as_bytes().to_vec(),
+            path: path.to_string(),
+            value,
+        })
+    }
+
+    fn verify_non_membership(
+        &self,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        path: Path,
+    ) -> Result<(), ClientError> {
+        self.request(WasmLightClientRequest::VerifyNonMembership {
+            client_state: self.encoded(),
+            prefix: prefix.as_bytes().to_vec(),
+            proof: proof.clone().into(),
+            root: root.as_bytes().to_vec(),
+            path: path.to_string(),
+        })
+    }
+}
+
+impl<C, Params> ClientStateValidation<IbcContext<C, Params>> for WasmClientState
+where
+    C: IbcCommonContext,
+    Params: parameters::Read<C::Storage>,
+{
+    fn verify_client_message(
+        &self,
+        ctx: &IbcContext<C, Params>,
+        client_id: &ClientId,
+        client_message: Any,
+    ) -> Result<(), ClientError> {
+        // Check the format before requesting the verification
+        ClientMessage::try_from(client_message.clone())?;
+        let height = self.inner.latest_height;
+        let consensus_state = ctx
+            .consensus_state(&ClientConsensusStatePath::new(
+                client_id.clone(),
+                height.revision_number(),
+                height.revision_height(),
+            ))
+            .map_err(into_client_error)?;
+        self.request(WasmLightClientRequest::VerifyClientMessage {
+            client_state: self.encoded(),
+            consensus_state: Any::from(consensus_state).encode_to_vec(),
+            client_message: client_message.encode_to_vec(),
+        })
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        _ctx: &IbcContext<C, Params>,
+        _client_id: &ClientId,
+        client_message: Any,
+    ) -> Result<bool, ClientError> {
+        // The misbehaviour has been verified by the light client code
+        Ok(ClientMessage::try_from(client_message)?.is_misbehaviour())
+    }
+
+    fn status(
+        &self,
+        ctx: &IbcContext<C, Params>,
+        _client_id: &ClientId,
+    ) -> Result<Status, ClientError> {
+        if self.inner.frozen_height.is_some() {
+            return Ok(Status::Frozen);
+        }
+        let is_allowed = {
+            let inner = ctx.inner.borrow();
+            is_wasm_client_allowed(inner.storage(), &self.inner.checksum)
+                .map_err(|e| ClientError::Other {
+                    description: e.to_string(),
+                })?
+        };
+        if is_allowed {
+            Ok(Status::Active)
+        } else {
+            Ok(Status::Unauthorized)
+        }
+    }
+
+    fn check_substitute(
+        &self,
+        _ctx: &IbcContext<C, Params>,
+        substitute_client_state: Any,
+    ) -> Result<(), ClientError> {
+        self.request(WasmLightClientRequest::CheckSubstitute {
+            client_state: self.encoded(),
+            substitute_client_state: substitute_client_state.encode_to_vec(),
+        })
+    }
+}
+
+impl<C, Params> ClientStateExecution<IbcContext<C, Params>> for WasmClientState
+where
+    C: IbcCommonContext,
+    Params: parameters::Read<C::Storage>,
+{
+    fn initialise(
+        &self,
+        ctx: &mut IbcContext<C, Params>,
+        client_id: &ClientId,
+        consensus_state: Any,
+    ) -> Result<(), ClientError> {
+        let is_allowed = {
+            let inner = ctx.inner.borrow();
+            is_wasm_client_allowed(inner.storage(), &self.inner.checksum)
+                .map_err(|e| ClientError::Other {
+                    description: e.to_string(),
+                })?
+        };
+        if !is_allowed {
+            return Err(ClientError::Other {
+                description: format!(
+                    "The wasm light client code {} isn't allowed",
+                    self.inner.checksum
+                ),
+            });
+        }
+        let consensus_state = WasmConsensusState::try_from(consensus_state)?;
+        store_states(
+            ctx,
+            client_id,
+            self.clone(),
+            self.inner.latest_height,
+            consensus_state,
+        )
+    }
+
+    fn update_state(
+        &self,
+        ctx: &mut IbcContext<C, Params>,
+        client_id: &ClientId,
+        header: Any,
+    ) -> Result<Vec<Height>, ClientError> {
+        let Some((height, consensus_state)) =
+            ClientMessage::try_from(header)?.update
+        else {
+            return Err(ClientError::Other {
+                description: "The wasm client message isn't a header"
+                    .to_string(),
+            });
+        };
+        let client_state = if self.inner.latest_height < height {
+            self.with_inner(WasmClientStateType {
+                latest_height: height,
+                ..self.inner.clone()
+            })
+        } else {
+            self.clone()
+        };
+        store_states(
+            ctx,
+            client_id,
+            client_state,
+            height,
+            consensus_state.into(),
+        )?;
+        Ok(vec![height])
+    }
+
+    fn update_state_on_misbehaviour(
+        &self,
+        ctx: &mut IbcContext<C, Params>,
+        client_id: &ClientId,
+        _client_message: Any,
+    ) -> Result<(), ClientError> {
+        let client_state = self.with_inner(WasmClientStateType {
+            frozen_height: Some(self.inner.latest_height),
+            ..self.inner.clone()
+        });
+        ctx.store_client_state(
+            ClientStatePath::new(client_id.clone()),
+            client_state.into(),
+        )
+        .map_err(into_client_error)
+    }
+
+    fn update_state_on_upgrade(
+        &self,
+        ctx: &mut IbcContext<C, Params>,
+        client_id: &ClientId,
+        upgraded_client_state: Any,
+        upgraded_consensus_state: Any,
+    ) -> Result<Height, ClientError> {
+        let client_state = WasmClientState::try_from(upgraded_client_state)?;
+        let consensus_state =
+            WasmConsensusState::try_from(upgraded_consensus_state)?;
+        let height = client_state.inner.latest_height;
+        store_states(ctx, client_id, client_state, height, consensus_state)?;
+        Ok(height)
+    }
+
+    fn update_on_recovery(
+        &self,
+        ctx: &mut IbcContext<C, Params>,
+        subject_client_id: &ClientId,
+        substitute_client_state: Any,
+        substitute_consensus_state: Any,
+    ) -> Result<(), ClientError> {
+        let substitute =
+            WasmClientStateType::try_from(substitute_client_state)?;
+        let client_state = self.with_inner(WasmClientStateType {
+            frozen_height: None,
+            ..substitute
+        });
+        let consensus_state =
+            WasmConsensusState::try_from(substitute_consensus_state)?;
+        let height = client_state.inner.latest_height;
+        store_states(
+            ctx,
+            subject_client_id,
+            client_state,
+            height,
+            consensus_state,
+        )
+    }
+}
+
+// Store the client state and the consensus state at the height
+fn store_states<C, Params>(
+    ctx: &mut IbcContext<C, Params>,
+    client_id: &ClientId,
+    client_state: WasmClientState,
+    height: Height,
+    consensus_state: WasmConsensusState,
+) -> Result<(), ClientError>
+where
+    C: IbcCommonContext,
+    Params: parameters::Read<C::Storage>,
+{
+    ctx.store_client_state(
+        ClientStatePath::new(client_id.clone()),
+        client_state.into(),
+    )
+    .map_err(into_client_error)?;
+    ctx.store_consensus_state(
+        ClientConsensusStatePath::new(
+            client_id.clone(),
+            height.revision_number(),
+            height.revision_height(),
+        ),
+        consensus_state.into(),
+    )
+    .map_err(into_client_error)?;
+    let host_timestamp = ctx.host_timestamp().map_err(into_client_error)?;
+    let host_height = ctx.host_height().map_err(into_client_error)?;
+    ctx.store_update_meta(
+        client_id.clone(),
+        height,
+        host_timestamp,
+        host_height,
+    )
+    .map_err(into_client_error)
+}
+
+fn into_client_error(e: ContextError) -> ClientError {
+    ClientError::Other {
+        description: e.to_string(),
+    }
+}
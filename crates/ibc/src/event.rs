@@ -35,6 +35,10 @@ use serde::{Deserialize, Serialize};
 /// Describes a token event within IBC.
 pub const TOKEN_EVENT_DESCRIPTOR: &str = IbcEvent::DOMAIN;
 
+/// The type of the event reporting the remaining rate-limit quotas of a token
+/// after a transfer
+pub const RATE_LIMIT_EVENT: &str = "rate_limit";
+
 pub mod types {
     //! IBC event types.
 
@@ -0,0 +1,232 @@
+//! ICS-29 relayer incentivization
+//!
+//! A channel is fee-enabled when its version is the ICS-29 metadata wrapping
+//! the application version. The sender of a transfer over a fee-enabled
+//! channel can attach relayer fees to the packet. They are escrowed in the IBC
+//! account when the packet is sent and paid out when the packet is
+//! acknowledged or timed out:
+//!
+//! - The receive fee goes to the forward relayer reported by the counterparty
+//!   in the incentivized acknowledgement
+//! - The acknowledgement fee goes to the payee of the relayer submitting the
+//!   acknowledgement
+//! - The timeout fee goes to the payee of the relayer submitting the timeout
+//!
+//! The fees which aren't paid out are refunded to the payer. A relayer can
+//! register its payee on this chain, and the counterparty payee reported in
+//! the acknowledgements written by this chain. The relayer itself is paid when
+//! it hasn't registered a payee.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::address::Address;
+use namada_core::token::Amount;
+use serde::{Deserialize, Serialize};
+
+/// The supported ICS-29 version
+pub const FEE_VERSION: &str = "ics29-1";
+/// The type of the event emitted when the fees of a packet are escrowed
+pub const INCENTIVIZED_PACKET_EVENT: &str = "incentivized_ibc_packet";
+/// The type of the event emitted when an escrowed fee is paid out or refunded
+pub const DISTRIBUTE_FEE_EVENT: &str = "distribute_fee";
+
+/// ICS-29 channel version metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// ICS-29 version
+    pub fee_version: String,
+    /// The version of the wrapped application
+    pub app_version: String,
+}
+
+impl Metadata {
+    /// Make the metadata wrapping the application version
+    pub fn new(app_version: impl Into<String>) -> Self {
+        Self {
+            fee_version: FEE_VERSION.to_string(),
+            app_version: app_version.into(),
+        }
+    }
+
+    /// Parse the channel version. Returns `None` if the version isn't the
+    /// ICS-29 metadata.
+    pub fn parse(version: impl AsRef<str>) -> Option<Result<Self, String>> {
+        let metadata: Self = serde_json::from_str(version.as_ref()).ok()?;
+        if metadata.fee_version != FEE_VERSION {
+            return Some(Err(format!(
+                "Unsupported ICS-29 version: expected {FEE_VERSION}, actual {}",
+                metadata.fee_version
+            )));
+        }
+        Some(Ok(metadata))
+    }
+
+    /// Get the channel version
+    pub fn to_version(&self) -> String {
+        serde_json::to_string(self)
+            .expect("Encoding the ICS-29 metadata shouldn't fail")
+    }
+}
+
+/// Relayer fees of a packet paid in a token
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RelayerFee {
+    /// The fee token
+    pub token: Address,
+    /// The fee for relaying the packet to the counterparty
+    pub recv_fee: Amount,
+    /// The fee for relaying the acknowledgement back to this chain
+    pub ack_fee: Amount,
+    /// The fee for relaying the timeout to this chain
+    pub timeout_fee: Amount,
+}
+
+impl RelayerFee {
+    /// Get the total amount to be escrowed. Returns `None` on overflow.
+    pub fn total(&self) -> Option<Amount> {
+        self.recv_fee
+            .checked_add(self.ack_fee)?
+            .checked_add(self.timeout_fee)
+    }
+}
+
+/// The escrowed relayer fees of a packet
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct PacketFee {
+    /// The fees
+    pub fee: RelayerFee,
+    /// The payer refunded with the fees which aren't paid out
+    pub payer: Address,
+}
+
+/// ICS-29 acknowledgement wrapping the application acknowledgement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncentivizedAcknowledgement {
+    /// The acknowledgement of the wrapped application
+    #[serde(with = "crate::ica::base64_bytes")]
+    pub app_acknowledgement: Vec<u8>,
+    /// The counterparty payee of the relayer which relayed the packet
+    pub forward_relayer_address: String,
+    /// Whether the wrapped application succeeded
+    pub underlying_app_success: bool,
+}
+
+impl IncentivizedAcknowledgement {
+    /// Decode the acknowledgement
+    pub fn decode(ack: impl AsRef<[u8]>) -> Result<Self, String> {
+        serde_json::from_slice(ack.as_ref()).map_err(|e| {
+            format!("Decoding the incentivized acknowledgement failed: {e}")
+        })
+    }
+
+    /// Encode the acknowledgement
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self)
+            .expect("Encoding the incentivized acknowledgement shouldn't fail")
+    }
+}
+
+/// Register the payee on this chain of the relayer for a channel
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgRegisterPayee {
+    /// Port ID on this chain
+    pub port_id: String,
+    /// Channel ID on this chain
+    pub channel_id: String,
+    /// The relayer address on this chain
+    pub relayer: Address,
+    /// The payee receiving the fees instead of the relayer
+    pub payee: Address,
+}
+
+/// Register the payee on the counterparty chain of the relayer for a channel
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgRegisterCounterpartyPayee {
+    /// Port ID on this chain
+    pub port_id: String,
+    /// Channel ID on this chain
+    pub channel_id: String,
+    /// The relayer address on this chain
+    pub relayer: Address,
+    /// The payee on the counterparty chain receiving the receive fees
+    pub counterparty_payee: String,
+}
+
+/// ICS-29 relayer message
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub enum MsgRelayerFee {
+    /// Register the payee on this chain
+    RegisterPayee(MsgRegisterPayee),
+    /// Register the payee on the counterparty chain
+    RegisterCounterpartyPayee(MsgRegisterCounterpartyPayee),
+}
+
+impl MsgRelayerFee {
+    /// Get the relayer
+    pub fn relayer(&self) -> &Address {
+        match self {
+            Self::RegisterPayee(msg) => &msg.relayer,
+            Self::RegisterCounterpartyPayee(msg) => &msg.relayer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::address::testing::nam;
+
+    use super::*;
+
+    #[test]
+    fn test_fee_metadata() {
+        let version = r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#;
+        let metadata = Metadata::parse(version).unwrap().unwrap();
+        assert_eq!(metadata, Metadata::new("ics20-1"));
+        assert_eq!(
+            Metadata::parse(metadata.to_version()).unwrap().unwrap(),
+            metadata
+        );
+
+        // Not fee-enabled
+        assert!(Metadata::parse("ics20-1").is_none());
+        // Unsupported version
+        let version = r#"{"fee_version":"ics29-2","app_version":"ics20-1"}"#;
+        assert!(Metadata::parse(version).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_incentivized_ack() {
+        let ack = r#"{"app_acknowledgement":"eyJyZXN1bHQiOiJBUT09In0=","forward_relayer_address":"relayer","underlying_app_success":true}"#;
+        let ack = IncentivizedAcknowledgement::decode(ack).unwrap();
+        assert_eq!(ack.app_acknowledgement, br#"{"result":"AQ=="}"#.to_vec());
+        assert_eq!(ack.forward_relayer_address, "relayer");
+        assert!(ack.underlying_app_success);
+        assert_eq!(IncentivizedAcknowledgement::decode(ack.encode()), Ok(ack));
+
+        let fee = RelayerFee {
+            token: nam(),
+            recv_fee: Amount::from_u64(1),
+            ack_fee: Amount::from_u64(2),
+            timeout_fee: Amount::from_u64(3),
+        };
+        assert_eq!(fee.total(), Some(Amount::from_u64(6)));
+    }
+}
@@ -0,0 +1,111 @@
+//! IBC hooks triggering allow-listed txs on receiving ICS-20 packets
+//!
+//! An incoming ICS-20 packet whose memo has a `hook` object triggers the tx
+//! after the tokens are received by the receiver:
+//!
+//! ```json
+//! {"hook": {"code_hash": "...", "data": "...", "gas_limit": 50000}}
+//! ```
+//!
+//! The code hash has to be in the hook allow-list of the IBC storage and the
+//! data is the hex-encoded tx data. The hook is executed by the protocol right
+//! after the IBC tx, within the gas limit in the memo, and it's validated by
+//! the VP of the receiver. When the hook fails, the IBC tx is executed again
+//! to acknowledge the packet with the failure.
+
+use std::str::FromStr;
+
+use data_encoding::HEXLOWER_PERMISSIVE;
+use namada_core::address::Address;
+use namada_core::hash::Hash;
+use namada_tx::action::IbcHook;
+use serde::Deserialize;
+
+/// The memo of an incoming packet triggering a hook
+#[derive(Debug, Clone, Deserialize)]
+pub struct IbcHookMemo {
+    /// Hook metadata
+    pub hook: HookMetadata,
+}
+
+/// The hook metadata in the memo
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookMetadata {
+    /// The hash of the allow-listed tx code
+    pub code_hash: String,
+    /// The hex-encoded tx data
+    #[serde(default)]
+    pub data: String,
+    /// The gas limit of the hook in whole gas units
+    pub gas_limit: u64,
+}
+
+impl IbcHookMemo {
+    /// Parse the memo of an incoming packet. Returns `None` if the memo
+    /// doesn't have the hook metadata.
+    pub fn parse(memo: impl AsRef<str>) -> Option<Result<Self, String>> {
+        let value: serde_json::Value =
+            serde_json::from_str(memo.as_ref()).ok()?;
+        value.get("hook")?;
+        Some(
+            serde_json::from_value(value)
+                .map_err(|e| format!("Invalid hook metadata: {e}")),
+        )
+    }
+}
+
+impl HookMetadata {
+    /// Make the hook executed for the receiver
+    pub fn to_hook(&self, receiver: Address) -> Result<IbcHook, String> {
+        let code_hash = Hash::from_str(&self.code_hash)
+            .map_err(|e| format!("Invalid hook code hash: {e}"))?;
+        let data = HEXLOWER_PERMISSIVE
+            .decode(self.data.as_bytes())
+            .map_err(|e| format!("Invalid hook data: {e}"))?;
+        if self.gas_limit == 0 {
+            return Err("No gas for the hook".to_string());
+        }
+        Ok(IbcHook {
+            receiver,
+            code_hash,
+            data,
+            gas_limit: self.gas_limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::address::testing::established_address_1;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_hook_memo() {
+        let code_hash = Hash::sha256(b"tx_bond.wasm");
+        let memo = format!(
+            r#"{{"hook":{{"code_hash":"{code_hash}","data":"0a0b","gas_limit":50000}}}}"#
+        );
+        let memo = IbcHookMemo::parse(memo).unwrap().unwrap();
+        let receiver = established_address_1();
+        let hook = memo.hook.to_hook(receiver.clone()).unwrap();
+        assert_eq!(hook.receiver, receiver);
+        assert_eq!(hook.code_hash, code_hash);
+        assert_eq!(hook.data, vec![0x0a, 0x0b]);
+        assert_eq!(hook.gas_limit, 50000);
+
+        // Not a hook memo
+        assert!(IbcHookMemo::parse("").is_none());
+        assert!(IbcHookMemo::parse(r#"{"forward":{}}"#).is_none());
+        // Invalid hook metadata
+        assert!(
+            IbcHookMemo::parse(r#"{"hook":{"code_hash":"00"}}"#)
+                .unwrap()
+                .is_err()
+        );
+        let memo =
+            r#"{"hook":{"code_hash":"00","data":"0a0b","gas_limit":50000}}"#;
+        let memo = IbcHookMemo::parse(memo).unwrap().unwrap();
+        assert!(memo.hook.to_hook(established_address_1()).is_err());
+    }
+}
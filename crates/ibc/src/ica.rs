@@ -0,0 +1,502 @@
+//! ICS-27 interchain accounts
+//!
+//! A controller port on this chain is bound per owner address as
+//! `icacontroller-<owner>`, and the owner sends transactions to its account
+//! on the host chain. Counterparty controllers can register accounts hosted
+//! on this chain through the `icahost` port. The hosted accounts can execute
+//! only the allow-listed messages defined in this module.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use ibc::core::host::types::identifiers::{ConnectionId, PortId};
+use ibc::primitives::proto::Any;
+use namada_core::address::Address;
+use namada_core::borsh::BorshSerializeExt;
+use namada_core::token::Amount;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// The port ID of the host module
+pub const HOST_PORT_ID_STR: &str = "icahost";
+/// The prefix of the controller port IDs
+pub const CONTROLLER_PORT_ID_PREFIX: &str = "icacontroller-";
+/// The module ID of the interchain accounts module
+pub const MODULE_ID_STR: &str = "interchainaccounts";
+/// The supported ICS-27 version
+pub const VERSION: &str = "ics27-1";
+/// The supported encoding of the host messages
+pub const ENCODING_PROTO3: &str = "proto3";
+/// The supported transaction type
+pub const TX_TYPE_SDK_MULTI_MSG: &str = "sdk_multi_msg";
+
+/// The error acknowledgement when executing the host messages failed
+pub const HOST_MSGS_FAILURE: &str = "Executing the host messages failed";
+
+/// Type URL of the allow-listed transfer message
+pub const MSG_TRANSFER_TYPE_URL: &str = "/namada.ica.v1.MsgTransfer";
+/// Type URL of the allow-listed bond message
+pub const MSG_BOND_TYPE_URL: &str = "/namada.ica.v1.MsgBond";
+/// Type URL of the allow-listed vote message
+pub const MSG_VOTE_TYPE_URL: &str = "/namada.ica.v1.MsgVote";
+
+/// Get the controller port ID of the owner
+pub fn controller_port_id(owner: &Address) -> PortId {
+    PortId::from_str(&format!("{CONTROLLER_PORT_ID_PREFIX}{owner}"))
+        .expect("The controller port ID should be valid")
+}
+
+/// Get the owner of the controller port. Returns `None` if the given port
+/// isn't a controller port.
+pub fn controller_owner(port_id: &PortId) -> Option<&str> {
+    port_id
+        .as_str()
+        .strip_prefix(CONTROLLER_PORT_ID_PREFIX)
+        .filter(|owner| !owner.is_empty())
+}
+
+/// Returns true if the given port is the host port
+pub fn is_host_port(port_id: &PortId) -> bool {
+    port_id.as_str() == HOST_PORT_ID_STR
+}
+
+/// ICS-27 channel version metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// ICS-27 version
+    pub version: String,
+    /// Connection ID on the controller chain
+    pub controller_connection_id: String,
+    /// Connection ID on the host chain
+    pub host_connection_id: String,
+    /// Interchain account address on the host chain. This is empty until
+    /// the host chain fills it in the channel handshake.
+    #[serde(default)]
+    pub address: String,
+    /// Encoding of the messages in the packet data
+    pub encoding: String,
+    /// Transaction type
+    pub tx_type: String,
+}
+
+impl Metadata {
+    /// Make the default metadata for the given connections
+    pub fn new(
+        controller_connection_id: &ConnectionId,
+        host_connection_id: &ConnectionId,
+    ) -> Self {
+        Self {
+            version: VERSION.to_string(),
+            controller_connection_id: controller_connection_id.to_string(),
+            host_connection_id: host_connection_id.to_string(),
+            address: String::default(),
+            encoding: ENCODING_PROTO3.to_string(),
+            tx_type: TX_TYPE_SDK_MULTI_MSG.to_string(),
+        }
+    }
+
+    /// Check the version, the encoding and the transaction type
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != VERSION {
+            return Err(format!(
+                "Unsupported ICS-27 version: {}",
+                self.version
+            ));
+        }
+        if self.encoding != ENCODING_PROTO3 {
+            return Err(format!("Unsupported encoding: {}", self.encoding));
+        }
+        if self.tx_type != TX_TYPE_SDK_MULTI_MSG {
+            return Err(format!(
+                "Unsupported transaction type: {}",
+                self.tx_type
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Metadata {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+            .map_err(|e| format!("Decoding the ICS-27 metadata failed: {e}"))
+    }
+}
+
+impl Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{json}")
+    }
+}
+
+/// Type of the interchain account packet
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub enum PacketType {
+    /// Unspecified type which is always rejected
+    #[serde(rename = "TYPE_UNSPECIFIED")]
+    Unspecified,
+    /// Execute the messages with the interchain account
+    #[serde(rename = "TYPE_EXECUTE_TX")]
+    ExecuteTx,
+}
+
+/// ICS-27 packet data
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct InterchainAccountPacketData {
+    /// Packet type
+    #[serde(rename = "type")]
+    pub packet_type: PacketType,
+    /// Proto3-encoded `CosmosTx`
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+    /// Memo
+    #[serde(default)]
+    pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+    /// Make packet data to execute the given messages on the host chain
+    pub fn execute_tx(messages: Vec<Any>, memo: String) -> Self {
+        Self {
+            packet_type: PacketType::ExecuteTx,
+            data: CosmosTx { messages }.encode_to_vec(),
+            memo,
+        }
+    }
+
+    /// Decode the allow-listed messages to be executed on this chain
+    pub fn decode_host_msgs(&self) -> Result<Vec<HostMsg>, String> {
+        if self.packet_type != PacketType::ExecuteTx {
+            return Err("Unsupported ICS-27 packet type".to_string());
+        }
+        let tx = CosmosTx::decode(&self.data[..])
+            .map_err(|e| format!("Decoding the messages failed: {e}"))?;
+        if tx.messages.is_empty() {
+            return Err("No message to be executed".to_string());
+        }
+        tx.messages.iter().map(HostMsg::try_from).collect()
+    }
+}
+
+/// The messages in the ICS-27 packet data
+#[derive(Clone, PartialEq, Message)]
+pub struct CosmosTx {
+    /// Messages
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+}
+
+/// Transfer the token from the interchain account
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgHostTransfer {
+    /// Target address
+    pub target: Address,
+    /// Token address
+    pub token: Address,
+    /// Amount
+    pub amount: Amount,
+}
+
+/// Bond the native token from the interchain account
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgHostBond {
+    /// Validator address
+    pub validator: Address,
+    /// Amount
+    pub amount: Amount,
+}
+
+/// Vote on a governance proposal with the interchain account
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgHostVote {
+    /// Proposal ID
+    pub proposal_id: u64,
+    /// Vote
+    pub vote: VoteOption,
+}
+
+/// Vote option of [`MsgHostVote`]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub enum VoteOption {
+    /// Yes
+    Yay,
+    /// No
+    Nay,
+    /// Abstain
+    Abstain,
+}
+
+/// Allow-listed message that an interchain account hosted on this chain can
+/// execute. The message value in `Any` is Borsh-encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostMsg {
+    /// Transfer
+    Transfer(MsgHostTransfer),
+    /// Bond
+    Bond(MsgHostBond),
+    /// Vote
+    Vote(MsgHostVote),
+}
+
+impl TryFrom<&Any> for HostMsg {
+    type Error = String;
+
+    fn try_from(any: &Any) -> Result<Self, Self::Error> {
+        let decoding_err = |e: std::io::Error| {
+            format!("Decoding {} failed: {e}", any.type_url)
+        };
+        match any.type_url.as_str() {
+            MSG_TRANSFER_TYPE_URL => {
+                MsgHostTransfer::try_from_slice(&any.value)
+                    .map(Self::Transfer)
+                    .map_err(decoding_err)
+            }
+            MSG_BOND_TYPE_URL => MsgHostBond::try_from_slice(&any.value)
+                .map(Self::Bond)
+                .map_err(decoding_err),
+            MSG_VOTE_TYPE_URL => MsgHostVote::try_from_slice(&any.value)
+                .map(Self::Vote)
+                .map_err(decoding_err),
+            type_url => Err(format!("The message {type_url} isn't allowed")),
+        }
+    }
+}
+
+impl From<HostMsg> for Any {
+    fn from(msg: HostMsg) -> Self {
+        let (type_url, value) = match msg {
+            HostMsg::Transfer(msg) => {
+                (MSG_TRANSFER_TYPE_URL, msg.serialize_to_vec())
+            }
+            HostMsg::Bond(msg) => (MSG_BOND_TYPE_URL, msg.serialize_to_vec()),
+            HostMsg::Vote(msg) => (MSG_VOTE_TYPE_URL, msg.serialize_to_vec()),
+        };
+        Any {
+            type_url: type_url.to_string(),
+            value,
+        }
+    }
+}
+
+/// Register an interchain account on the host chain by opening a channel
+/// from the controller port of the owner
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgRegisterInterchainAccount {
+    /// Owner of the interchain account
+    pub owner: Address,
+    /// Connection ID on this chain
+    pub connection_id: String,
+    /// ICS-27 version metadata. The default metadata is used if it's empty.
+    pub version: String,
+}
+
+/// Send messages to be executed by the interchain account on the host chain
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct MsgSendTx {
+    /// Owner of the interchain account
+    pub owner: Address,
+    /// Connection ID on this chain
+    pub connection_id: String,
+    /// Packet data
+    pub packet_data: InterchainAccountPacketData,
+    /// Timeout in nanoseconds relative to the block time
+    pub relative_timeout: u64,
+}
+
+/// Interchain accounts controller message
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub enum MsgInterchainAccount {
+    /// Register an interchain account
+    Register(MsgRegisterInterchainAccount),
+    /// Send a transaction to the interchain account
+    SendTx(MsgSendTx),
+}
+
+impl MsgInterchainAccount {
+    /// Get the owner of the interchain account
+    pub fn owner(&self) -> &Address {
+        match self {
+            Self::Register(msg) => &msg.owner,
+            Self::SendTx(msg) => &msg.owner,
+        }
+    }
+}
+
+pub(crate) mod base64_bytes {
+    use data_encoding::BASE64;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        BASE64
+            .decode(s.as_bytes())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_encoding::BASE64;
+    use namada_core::address::testing::{established_address_1, nam};
+
+    use super::*;
+
+    #[test]
+    fn test_packet_data_json() {
+        let msg = HostMsg::Transfer(MsgHostTransfer {
+            target: established_address_1(),
+            token: nam(),
+            amount: Amount::from_u64(100),
+        });
+        let packet_data = InterchainAccountPacketData::execute_tx(
+            vec![msg.clone().into()],
+            "memo".to_string(),
+        );
+        let json = serde_json::to_string(&packet_data).unwrap();
+        assert!(json.contains("\"type\":\"TYPE_EXECUTE_TX\""));
+        assert!(json.contains(&BASE64.encode(&packet_data.data)));
+
+        let decoded: InterchainAccountPacketData =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, packet_data);
+        assert_eq!(decoded.decode_host_msgs().unwrap(), vec![msg]);
+    }
+
+    #[test]
+    fn test_host_msgs_allow_list() {
+        let disallowed = Any {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: vec![],
+        };
+        let packet_data = InterchainAccountPacketData::execute_tx(
+            vec![disallowed],
+            "".into(),
+        );
+        assert!(packet_data.decode_host_msgs().is_err());
+
+        let empty = InterchainAccountPacketData::execute_tx(vec![], "".into());
+        assert!(empty.decode_host_msgs().is_err());
+
+        let vote = HostMsg::Vote(MsgHostVote {
+            proposal_id: 0,
+            vote: VoteOption::Abstain,
+        });
+        let unspecified = InterchainAccountPacketData {
+            packet_type: PacketType::Unspecified,
+            ..InterchainAccountPacketData::execute_tx(
+                vec![vote.into()],
+                "".into(),
+            )
+        };
+        assert!(unspecified.decode_host_msgs().is_err());
+    }
+
+    #[test]
+    fn test_vote_option_encoding() {
+        use namada_core::storage::Key;
+        use namada_governance::{Keys, ProposalVote, Store};
+
+        // The pseudo-execution of the IBC VP writes the vote option in place
+        // of the governance proposal vote
+        for (option, vote) in [
+            (VoteOption::Yay, ProposalVote::Yay),
+            (VoteOption::Nay, ProposalVote::Nay),
+            (VoteOption::Abstain, ProposalVote::Abstain),
+        ] {
+            assert_eq!(option.serialize_to_vec(), vote.serialize_to_vec());
+        }
+
+        let voter = established_address_1();
+        let validator = nam();
+        let key = Store::<()>::account_vote_key(3, &voter);
+        assert_eq!(Store::<()>::is_any_vote_key(&key), Some((3, &voter)));
+        let key = Store::<()>::delegation_vote_key(3, &voter, &validator);
+        assert_eq!(Store::<()>::is_any_vote_key(&key), Some((3, &voter)));
+        let key = Key::parse("key").expect("cannot parse the key string");
+        assert!(Store::<()>::is_any_vote_key(&key).is_none());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let controller = ConnectionId::new(0);
+        let host = ConnectionId::new(1);
+        let metadata = Metadata::new(&controller, &host);
+        assert!(metadata.validate().is_ok());
+        let decoded = Metadata::from_str(&metadata.to_string()).unwrap();
+        assert_eq!(decoded, metadata);
+
+        let metadata = Metadata {
+            encoding: "proto3json".to_string(),
+            ..metadata
+        };
+        assert!(metadata.validate().is_err());
+    }
+
+    #[test]
+    fn test_controller_port() {
+        let owner = established_address_1();
+        let port_id = controller_port_id(&owner);
+        assert_eq!(
+            controller_owner(&port_id),
+            Some(owner.to_string().as_str())
+        );
+        assert_eq!(controller_owner(&PortId::transfer()), None);
+        assert!(!is_host_port(&port_id));
+    }
+}
@@ -20,15 +20,21 @@
 mod actions;
 pub mod context;
 pub mod event;
+pub mod fee;
+pub mod hooks;
+pub mod ica;
 mod msg;
 mod nft;
+pub mod packet_forward;
 pub mod parameters;
+pub mod rate_limit;
 pub mod storage;
 pub mod trace;
 pub mod vp;
+pub mod wasm_client;
 
 use std::cell::RefCell;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::rc::Rc;
@@ -39,8 +45,13 @@ use apps::transfer::types::packet::PacketData;
 use apps::transfer::types::PORT_ID_STR;
 use borsh::BorshDeserialize;
 pub use context::common::IbcCommonContext;
+pub use context::fee_mod::FeeModule;
+pub use context::hooks_mod::IbcHooksModule;
+pub use context::ica_mod::IcaModule;
 pub use context::nft_transfer::NftTransferContext;
 pub use context::nft_transfer_mod::NftTransferModule;
+pub use context::packet_forward_mod::PacketForwardModule;
+use context::packet_forward_mod::PendingForwards;
 use context::router::IbcRouter;
 pub use context::storage::{IbcStorageContext, ProofSpec};
 pub use context::token_transfer::TokenTransferContext;
@@ -63,22 +74,33 @@ use ibc::apps::transfer::handler::{
 use ibc::apps::transfer::types::error::TokenTransferError;
 use ibc::apps::transfer::types::msgs::transfer::MsgTransfer as IbcMsgTransfer;
 use ibc::apps::transfer::types::{
-    is_receiver_chain_source, TracePrefix, PORT_ID_STR as FT_PORT_ID_STR,
+    is_receiver_chain_source, PrefixedCoin, TracePrefix,
+    PORT_ID_STR as FT_PORT_ID_STR,
 };
-use ibc::core::channel::types::acknowledgement::AcknowledgementStatus;
+use ibc::core::channel::handler::{send_packet_execute, send_packet_validate};
+use ibc::core::channel::types::acknowledgement::{
+    Acknowledgement, AcknowledgementStatus,
+};
+use ibc::core::channel::types::channel::Order;
 use ibc::core::channel::types::commitment::compute_ack_commitment;
 use ibc::core::channel::types::msgs::{
-    MsgRecvPacket as IbcMsgRecvPacket, PacketMsg,
+    ChannelMsg, MsgChannelOpenInit, MsgRecvPacket as IbcMsgRecvPacket,
+    PacketMsg,
 };
+use ibc::core::channel::types::packet::Packet;
 use ibc::core::channel::types::timeout::{TimeoutHeight, TimeoutTimestamp};
+use ibc::core::channel::types::Version;
 use ibc::core::entrypoint::{execute, validate};
 use ibc::core::handler::types::error::ContextError;
 use ibc::core::handler::types::events::Error as RawIbcEventError;
 use ibc::core::handler::types::msgs::MsgEnvelope;
 use ibc::core::host::types::error::IdentifierError;
-use ibc::core::host::types::identifiers::{ChannelId, PortId, Sequence};
+use ibc::core::host::types::identifiers::{
+    ChannelId, ConnectionId, PortId, Sequence,
+};
 use ibc::core::router::types::error::RouterError;
 use ibc::primitives::proto::Any;
+use ibc::primitives::Timestamp;
 pub use ibc::*;
 use masp_primitives::transaction::Transaction as MaspTransaction;
 pub use msg::*;
@@ -108,10 +130,21 @@ use trace::{
     is_sender_chain_source,
 };
 
+use crate::event::{IbcEvent, IbcEventType};
+use crate::fee::{
+    INCENTIVIZED_PACKET_EVENT, IncentivizedAcknowledgement, MsgRelayerFee,
+    PacketFee, RelayerFee,
+};
+use crate::ica::{
+    controller_owner, controller_port_id, MsgInterchainAccount,
+    MsgRegisterInterchainAccount, MsgSendTx,
+};
+use crate::packet_forward::{ForwardTransfer, PacketForwardMemo};
 use crate::storage::{
     channel_counter_key, client_counter_key, connection_counter_key,
     deposit_prefix, withdraw_prefix,
 };
+use crate::wasm_client::WasmLightClientCall;
 
 /// The event type defined in ibc-rs for receiving a token
 pub const EVENT_TYPE_PACKET: &str = "fungible_token_packet";
@@ -137,6 +170,12 @@ pub enum Error {
     TokenTransfer(TokenTransferError),
     #[error("IBC NFT transfer error: {0}")]
     NftTransfer(NftTransferError),
+    #[error("IBC interchain account error: {0}")]
+    InterchainAccount(String),
+    #[error("IBC packet forward error: {0}")]
+    PacketForward(String),
+    #[error("IBC relayer fee error: {0}")]
+    RelayerFee(String),
     #[error("Trace error: {0}")]
     Trace(String),
     #[error("Invalid chain ID: {0}")]
@@ -288,6 +327,10 @@ where
                     keys_changed,
                 )?;
             }
+            // Interchain account and relayer messages don't transfer tokens
+            // over IBC
+            Some(IbcMessage::InterchainAccount(_))
+            | Some(IbcMessage::RelayerFee(_)) => {}
             // This event is emitted on the receiver
             Some(IbcMessage::Envelope(envelope)) => {
                 if let MsgEnvelope::Packet(PacketMsg::Recv(msg)) = *envelope {
@@ -313,7 +356,9 @@ where
                             amount,
                             keys_changed,
                         )?;
-                    } else {
+                    } else if msg.packet.port_id_on_b.as_str()
+                        == NFT_PORT_ID_STR
+                    {
                         let packet_data =
                             serde_json::from_slice::<NftPacketData>(
                                 &msg.packet.data,
@@ -349,6 +394,25 @@ where
     }
 }
 
+impl<S> namada_systems::ibc::Write<S> for Store<S>
+where
+    S: StorageRead + StorageWrite,
+{
+    fn apply_param_change(
+        storage: &mut S,
+        change: &namada_systems::ibc::ParamChange,
+    ) -> StorageResult<()> {
+        if let namada_systems::ibc::ParamChange::RateLimit(change) = change {
+            return storage::write_rate_limits(storage, change);
+        }
+        let key = storage::params_key();
+        let mut params: parameters::IbcParameters =
+            storage.read(&key)?.expect("Parameters should be stored");
+        params.apply_change(change);
+        storage.write(&key, params)
+    }
+}
+
 fn check_ibc_transfer<S>(
     storage: &S,
     ibc_transfer: &IbcTransferInfo,
@@ -480,19 +544,19 @@ where
 // Check if IBC message was received successfully in this state transition
 fn is_receiving_success<S>(
     storage: &S,
-    dst_port_id: &PortId,
-    dst_channel_id: &ChannelId,
-    sequence: Sequence,
+    msg: &IbcMsgRecvPacket,
 ) -> StorageResult<bool>
 where
     S: StorageRead,
 {
     // Ensure that the event corresponds to the current changes to storage
-    let ack_key = storage::ack_key(dst_port_id, dst_channel_id, sequence);
-    // If the receive is a success, then the commitment is unique
-    let succ_ack_commitment = compute_ack_commitment(
-        &AcknowledgementStatus::success(ack_success_b64()).into(),
+    let ack_key = storage::ack_key(
+        &msg.packet.port_id_on_b,
+        &msg.packet.chan_id_on_b,
+        msg.packet.seq_on_a,
     );
+    // If the receive is a success, then the commitment is unique
+    let succ_ack_commitment = success_ack_commitment(storage, msg)?;
     Ok(match storage.read_bytes(&ack_key)? {
         // Success happens only if commitment equals the above
         Some(value) => {
@@ -503,6 +567,40 @@ where
     })
 }
 
+// The acknowledgement commitment of the successful receiving. The
+// acknowledgement is wrapped with the forward relayer on the fee-enabled
+// channel.
+fn success_ack_commitment<S>(
+    storage: &S,
+    msg: &IbcMsgRecvPacket,
+) -> StorageResult<AcknowledgementCommitment>
+where
+    S: StorageRead,
+{
+    let ack: Acknowledgement =
+        AcknowledgementStatus::success(ack_success_b64()).into();
+    if !storage::is_fee_enabled(
+        storage,
+        &msg.packet.port_id_on_b,
+        &msg.packet.chan_id_on_b,
+    )? {
+        return Ok(compute_ack_commitment(&ack));
+    }
+    let forward_relayer_address = storage::counterparty_payee(
+        storage,
+        &msg.packet.chan_id_on_b,
+        msg.signer.as_ref(),
+    )?;
+    let ack = IncentivizedAcknowledgement {
+        app_acknowledgement: ack.as_ref().to_vec(),
+        forward_relayer_address,
+        underlying_app_success: true,
+    };
+    let ack = Acknowledgement::try_from(ack.encode())
+        .expect("The incentivized acknowledgement shouldn't be empty");
+    Ok(compute_ack_commitment(&ack))
+}
+
 // Apply the given write acknowledge to the changed balances structure
 fn apply_recv_msg<S>(
     storage: &S,
@@ -519,12 +617,7 @@ where
     check_packet_receiving(msg, keys_changed)?;
     // If the transfer was a failure, then enable funds to
     // be withdrawn from the IBC internal address
-    if is_receiving_success(
-        storage,
-        &msg.packet.port_id_on_b,
-        &msg.packet.chan_id_on_b,
-        msg.packet.seq_on_a,
-    )? {
+    if is_receiving_success(storage, msg)? {
         for ibc_trace in ibc_traces {
             // Only artificially increase the IBC internal address pre-balance
             // if receiving involves minting. We do not do this in the unescrow
@@ -574,6 +667,7 @@ where
     ctx: IbcContext<C, Params>,
     router: IbcRouter<'a>,
     verifiers: Rc<RefCell<BTreeSet<Address>>>,
+    forwards: PendingForwards,
     _marker: PhantomData<Token>,
 }
 
@@ -592,6 +686,7 @@ where
             ctx: IbcContext::new(ctx),
             router: IbcRouter::new(),
             verifiers,
+            forwards: Rc::new(RefCell::new(Vec::new())),
             _marker: PhantomData,
         }
     }
@@ -601,11 +696,54 @@ where
         self.router.add_transfer_module(module)
     }
 
+    /// Add an IBC module to the router
+    pub fn add_module(&mut self, module: impl ModuleWrapper + 'a) {
+        self.router.add_module(module)
+    }
+
+    /// Add the interchain accounts module to the router
+    pub fn add_ica_module(&mut self, module: IcaModule<C>)
+    where
+        C: Debug + 'a,
+    {
+        self.router.add_module(module)
+    }
+
+    /// Add the packet-forward middleware wrapping the transfer module to the
+    /// router. The transfers to the next hop are sent in this IBC actions.
+    pub fn add_packet_forward_module<M>(
+        &mut self,
+        mut module: PacketForwardModule<C, M>,
+    ) where
+        C: Debug + 'a,
+        M: ModuleWrapper + 'a,
+    {
+        module.forwards = self.forwards.clone();
+        self.router.add_transfer_module(module)
+    }
+
+    /// Add the fee middleware wrapping the packet-forward middleware to the
+    /// router. The transfers to the next hop are sent in this IBC actions.
+    pub fn add_fee_module<M>(&mut self, mut module: FeeModule<C, M>)
+    where
+        C: Debug + 'a,
+        M: ModuleWrapper + 'a,
+    {
+        module.app.forwards = self.forwards.clone();
+        self.router.add_transfer_module(module)
+    }
+
     /// Set the validation parameters
     pub fn set_validation_params(&mut self, params: ValidationParams) {
         self.ctx.validation_params = params;
     }
 
+    /// Take the calls to the wasm light client code requested in the
+    /// validation. They have to be evaluated to complete the validation.
+    pub fn take_light_client_calls(&self) -> Vec<WasmLightClientCall> {
+        self.ctx.light_client_calls.take()
+    }
+
     /// Execute according to the message in an IBC transaction or VP
     pub fn execute<Transfer: BorshDeserialize>(
         &mut self,
@@ -634,12 +772,43 @@ where
                 if msg.transfer.is_some() {
                     token_transfer_ctx.enable_shielded_transfer();
                 }
+                let port_id = msg.message.port_id_on_a.clone();
+                let channel_id = msg.message.chan_id_on_a.clone();
+                let sequence = self
+                    .ctx
+                    .inner
+                    .borrow()
+                    .get_next_sequence_send(&port_id, &channel_id)
+                    .map_err(|e| Error::Context(Box::new(e)))?;
+                let payer = msg.message.packet_data.sender.clone();
                 send_transfer_execute(
                     &mut self.ctx,
                     &mut token_transfer_ctx,
                     msg.message,
                 )
                 .map_err(Error::TokenTransfer)?;
+                if let Some(fee) = msg.fee {
+                    if msg.transfer.is_some() {
+                        return Err(Error::RelayerFee(
+                            "The relayer fees can't be paid for a shielded \
+                             transfer"
+                                .to_string(),
+                        ));
+                    }
+                    let payer =
+                        Address::from_str(payer.as_ref()).map_err(|_| {
+                            Error::RelayerFee(format!(
+                                "Cannot convert the payer address {payer}"
+                            ))
+                        })?;
+                    self.escrow_packet_fee(
+                        &port_id,
+                        &channel_id,
+                        sequence,
+                        payer,
+                        fee,
+                    )?;
+                }
                 Ok((msg.transfer, None))
             }
             IbcMessage::NftTransfer(msg) => {
@@ -684,6 +853,7 @@ where
                 }
                 execute(&mut self.ctx, &mut self.router, *envelope.clone())
                     .map_err(|e| Error::Context(Box::new(e)))?;
+                self.execute_forwards()?;
 
                 // Extract MASP tx from the memo in the packet if needed
                 let masp_tx = match &*envelope {
@@ -703,9 +873,287 @@ where
                 };
                 Ok((None, masp_tx))
             }
+            IbcMessage::InterchainAccount(msg) => {
+                // Add the owner to the set of verifiers
+                self.verifiers.borrow_mut().insert(msg.owner().clone());
+                self.insert_verifiers()?;
+                match msg {
+                    MsgInterchainAccount::Register(msg) => {
+                        let envelope = ica_channel_open_init(msg)?;
+                        execute(&mut self.ctx, &mut self.router, envelope)
+                            .map_err(|e| Error::Context(Box::new(e)))?;
+                    }
+                    MsgInterchainAccount::SendTx(msg) => {
+                        let packet = self.ica_packet(msg)?;
+                        send_packet_execute(&mut self.ctx, packet)
+                            .map_err(|e| Error::Context(Box::new(e)))?;
+                    }
+                }
+                Ok((None, None))
+            }
+            IbcMessage::RelayerFee(msg) => {
+                // Add the relayer to the set of verifiers
+                self.verifiers.borrow_mut().insert(msg.relayer().clone());
+                self.insert_verifiers()?;
+                self.register_payee(msg)?;
+                Ok((None, None))
+            }
         }
     }
 
+    // Escrow the relayer fees of the packet sent over the fee-enabled channel
+    fn escrow_packet_fee(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+        payer: Address,
+        fee: RelayerFee,
+    ) -> Result<(), Error> {
+        let mut ctx = self.ctx.inner.borrow_mut();
+        let is_fee_enabled =
+            storage::is_fee_enabled(ctx.storage(), port_id, channel_id)
+                .map_err(|e| Error::RelayerFee(e.to_string()))?;
+        if !is_fee_enabled {
+            return Err(Error::RelayerFee(format!(
+                "The channel {channel_id} isn't fee-enabled"
+            )));
+        }
+        let total = fee.total().ok_or_else(|| {
+            Error::RelayerFee("The total relayer fee overflowed".to_string())
+        })?;
+        ctx.transfer_token(&payer, &IBC_ESCROW_ADDRESS, &fee.token, total)
+            .map_err(|e| Error::RelayerFee(e.to_string()))?;
+
+        let event = IbcEvent {
+            event_type: IbcEventType(INCENTIVIZED_PACKET_EVENT.to_string()),
+            attributes: HashMap::from([
+                ("port_id".to_string(), port_id.to_string()),
+                ("channel_id".to_string(), channel_id.to_string()),
+                ("packet_sequence".to_string(), sequence.to_string()),
+                ("token".to_string(), fee.token.to_string()),
+                ("recv_fee".to_string(), fee.recv_fee.to_string()),
+                ("ack_fee".to_string(), fee.ack_fee.to_string()),
+                ("timeout_fee".to_string(), fee.timeout_fee.to_string()),
+                ("payer".to_string(), payer.to_string()),
+            ]),
+        };
+        ctx.emit_ibc_event(event)
+            .map_err(|e| Error::RelayerFee(e.to_string()))?;
+
+        let key = storage::packet_fee_key(port_id, channel_id, sequence);
+        ctx.storage_mut()
+            .write(&key, PacketFee { fee, payer })
+            .map_err(|e| Error::RelayerFee(e.to_string()))
+    }
+
+    // Register the payee of the relayer for the fee-enabled channel
+    fn register_payee(&mut self, msg: MsgRelayerFee) -> Result<(), Error> {
+        let key =
+            self.relayer_fee_channel(&msg)
+                .map(|channel_id| match &msg {
+                    MsgRelayerFee::RegisterPayee(msg) => {
+                        storage::payee_key(&channel_id, &msg.relayer)
+                    }
+                    MsgRelayerFee::RegisterCounterpartyPayee(msg) => {
+                        storage::counterparty_payee_key(
+                            &channel_id,
+                            &msg.relayer,
+                        )
+                    }
+                })?;
+        let mut ctx = self.ctx.inner.borrow_mut();
+        match msg {
+            MsgRelayerFee::RegisterPayee(msg) => {
+                ctx.storage_mut().write(&key, msg.payee)
+            }
+            MsgRelayerFee::RegisterCounterpartyPayee(msg) => {
+                ctx.storage_mut().write(&key, msg.counterparty_payee)
+            }
+        }
+        .map_err(|e| Error::RelayerFee(e.to_string()))
+    }
+
+    // Get the fee-enabled channel of the relayer message
+    fn relayer_fee_channel(
+        &self,
+        msg: &MsgRelayerFee,
+    ) -> Result<ChannelId, Error> {
+        let (port_id, channel_id) = match msg {
+            MsgRelayerFee::RegisterPayee(msg) => {
+                (&msg.port_id, &msg.channel_id)
+            }
+            MsgRelayerFee::RegisterCounterpartyPayee(msg) => {
+                if msg.counterparty_payee.trim().is_empty() {
+                    return Err(Error::RelayerFee(
+                        "The counterparty payee is empty".to_string(),
+                    ));
+                }
+                (&msg.port_id, &msg.channel_id)
+            }
+        };
+        let port_id = PortId::from_str(port_id)
+            .map_err(|e| Error::RelayerFee(e.to_string()))?;
+        let channel_id = ChannelId::from_str(channel_id)
+            .map_err(|e| Error::RelayerFee(e.to_string()))?;
+        let is_fee_enabled = storage::is_fee_enabled(
+            self.ctx.inner.borrow().storage(),
+            &port_id,
+            &channel_id,
+        )
+        .map_err(|e| Error::RelayerFee(e.to_string()))?;
+        if !is_fee_enabled {
+            return Err(Error::RelayerFee(format!(
+                "The channel {channel_id} isn't fee-enabled"
+            )));
+        }
+        Ok(channel_id)
+    }
+
+    // Send the transfers forwarded by the packet-forward middleware
+    fn execute_forwards(&mut self) -> Result<(), Error> {
+        let forwards = std::mem::take(&mut *self.forwards.borrow_mut());
+        for forward in forwards {
+            let token_transfer_ctx = TokenTransferContext::new(
+                self.ctx.inner.clone(),
+                self.verifiers.clone(),
+            );
+            let msg = self.forward_msg(&forward)?;
+            // Refund immediately when the next hop isn't available
+            let (forward, msg) = match send_transfer_validate(
+                &self.ctx,
+                &token_transfer_ctx,
+                msg.clone(),
+            ) {
+                Ok(()) => (forward, msg),
+                Err(e) if forward.refund.is_none() => {
+                    return Err(Error::TokenTransfer(e));
+                }
+                Err(e) => {
+                    tracing::debug!("Refunding the forwarded transfer: {e}");
+                    let refund = forward.refund();
+                    let msg = self.forward_msg(&refund)?;
+                    (refund, msg)
+                }
+            };
+
+            let sequence = self
+                .ctx
+                .inner
+                .borrow()
+                .get_next_sequence_send(&msg.port_id_on_a, &msg.chan_id_on_a)
+                .map_err(|e| Error::Context(Box::new(e)))?;
+            let key = storage::forward_key(
+                &msg.port_id_on_a,
+                &msg.chan_id_on_a,
+                sequence,
+            );
+            let mut token_transfer_ctx = TokenTransferContext::new(
+                self.ctx.inner.clone(),
+                self.verifiers.clone(),
+            );
+            send_transfer_execute(&mut self.ctx, &mut token_transfer_ctx, msg)
+                .map_err(Error::TokenTransfer)?;
+            self.ctx
+                .inner
+                .borrow_mut()
+                .storage_mut()
+                .write(&key, forward)
+                .map_err(|e| Error::PacketForward(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    // Make a transfer message from the IBC escrow to the next hop
+    fn forward_msg(
+        &self,
+        forward: &ForwardTransfer,
+    ) -> Result<IbcMsgTransfer, Error> {
+        let port_id_on_a = PortId::from_str(&forward.port_id)
+            .map_err(|e| Error::PacketForward(e.to_string()))?;
+        let chan_id_on_a = ChannelId::from_str(&forward.channel_id)
+            .map_err(|e| Error::PacketForward(e.to_string()))?;
+        let token = PrefixedCoin {
+            denom: forward.denom.parse().map_err(Error::TokenTransfer)?,
+            amount: forward.amount.parse().map_err(Error::TokenTransfer)?,
+        };
+        let host_timestamp = self
+            .ctx
+            .inner
+            .borrow()
+            .host_timestamp()
+            .map_err(|e| Error::Context(Box::new(e)))?;
+        let timeout = checked!(host_timestamp.nanoseconds() + forward.timeout)
+            .map_err(|e| Error::PacketForward(e.to_string()))?;
+        Ok(IbcMsgTransfer {
+            port_id_on_a,
+            chan_id_on_a,
+            packet_data: PacketData {
+                token,
+                sender: IBC_ESCROW_ADDRESS.to_string().into(),
+                receiver: forward.receiver.clone().into(),
+                memo: forward.memo.clone().into(),
+            },
+            timeout_height_on_b: TimeoutHeight::Never,
+            timeout_timestamp_on_b: TimeoutTimestamp::At(
+                Timestamp::from_nanoseconds(timeout),
+            ),
+        })
+    }
+
+    // Make a packet to send the messages to the interchain account
+    fn ica_packet(&self, msg: MsgSendTx) -> Result<Packet, Error> {
+        let connection_id = ConnectionId::from_str(&msg.connection_id)
+            .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+        let port_id = controller_port_id(&msg.owner);
+        let ctx = self.ctx.inner.borrow();
+        let key = storage::ica_controller_channel_key(&connection_id, &port_id);
+        let channel_id = ctx
+            .storage()
+            .read::<String>(&key)
+            .map_err(|e| Error::InterchainAccount(e.to_string()))?
+            .ok_or_else(|| {
+                Error::InterchainAccount(format!(
+                    "No active channel for the controller port {port_id}"
+                ))
+            })?;
+        let channel_id = ChannelId::from_str(&channel_id)
+            .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+        let channel_end = ctx
+            .channel_end(&port_id, &channel_id)
+            .map_err(|e| Error::Context(Box::new(e)))?;
+        let counterparty = channel_end.counterparty();
+        let chan_id_on_b =
+            counterparty.channel_id().cloned().ok_or_else(|| {
+                Error::InterchainAccount(format!(
+                    "No counterparty channel of the channel {channel_id}"
+                ))
+            })?;
+        let seq_on_a = ctx
+            .get_next_sequence_send(&port_id, &channel_id)
+            .map_err(|e| Error::Context(Box::new(e)))?;
+        let host_timestamp = ctx
+            .host_timestamp()
+            .map_err(|e| Error::Context(Box::new(e)))?;
+        let timeout =
+            checked!(host_timestamp.nanoseconds() + msg.relative_timeout)
+                .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+        let data = serde_json::to_vec(&msg.packet_data)
+            .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+        Ok(Packet {
+            seq_on_a,
+            port_id_on_a: port_id,
+            chan_id_on_a: channel_id,
+            port_id_on_b: counterparty.port_id().clone(),
+            chan_id_on_b,
+            data,
+            timeout_height_on_b: TimeoutHeight::Never,
+            timeout_timestamp_on_b: TimeoutTimestamp::At(
+                Timestamp::from_nanoseconds(timeout),
+            ),
+        })
+    }
+
     /// Check the result of receiving the packet by checking the packet
     /// acknowledgement
     pub fn is_receiving_success(
@@ -722,9 +1170,9 @@ where
                 msg.packet.seq_on_a,
             )
             .map_err(|e| Error::Context(Box::new(e)))?;
-        let success_ack_commitment = compute_ack_commitment(
-            &AcknowledgementStatus::success(ack_success_b64()).into(),
-        );
+        let success_ack_commitment =
+            success_ack_commitment(self.ctx.inner.borrow().storage(), msg)
+                .map_err(|e| Error::Other(e.to_string()))?;
         Ok(packet_ack == success_ack_commitment)
     }
 
@@ -766,6 +1214,21 @@ where
                 validate(&self.ctx, &self.router, *envelope)
                     .map_err(|e| Error::Context(Box::new(e)))
             }
+            IbcMessage::InterchainAccount(msg) => match msg {
+                MsgInterchainAccount::Register(msg) => {
+                    let envelope = ica_channel_open_init(msg)?;
+                    validate(&self.ctx, &self.router, envelope)
+                        .map_err(|e| Error::Context(Box::new(e)))
+                }
+                MsgInterchainAccount::SendTx(msg) => {
+                    let packet = self.ica_packet(msg)?;
+                    send_packet_validate(&self.ctx, &packet)
+                        .map_err(|e| Error::Context(Box::new(e)))
+                }
+            },
+            IbcMessage::RelayerFee(msg) => {
+                self.relayer_fee_channel(&msg).map(|_| ())
+            }
         }
     }
 
@@ -778,6 +1241,25 @@ where
     }
 }
 
+// Make the message to open a channel from the controller port of the owner
+fn ica_channel_open_init(
+    msg: MsgRegisterInterchainAccount,
+) -> Result<MsgEnvelope, Error> {
+    let connection_id = ConnectionId::from_str(&msg.connection_id)
+        .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+    let port_id_on_b = PortId::from_str(ica::HOST_PORT_ID_STR)
+        .map_err(|e| Error::InterchainAccount(e.to_string()))?;
+    let msg = MsgChannelOpenInit {
+        port_id_on_a: controller_port_id(&msg.owner),
+        connection_hops_on_a: vec![connection_id],
+        port_id_on_b,
+        ordering: Order::Ordered,
+        signer: msg.owner.to_string().into(),
+        version_proposal: Version::new(msg.version),
+    };
+    Ok(MsgEnvelope::Channel(ChannelMsg::OpenInit(msg)))
+}
+
 // Extract the involved namada address from the packet (either sender or
 // receiver) to trigger its vp. Returns None if an address could not be found
 fn get_envelope_verifier(
@@ -789,6 +1271,12 @@ fn get_envelope_verifier(
                 FT_PORT_ID_STR => {
                     serde_json::from_slice::<PacketData>(&msg.packet.data)
                         .ok()
+                        // The receiver of a forwarded packet is on the next
+                        // hop
+                        .filter(|packet_data| {
+                            PacketForwardMemo::parse(packet_data.memo.as_ref())
+                                .is_none()
+                        })
                         .map(|packet_data| packet_data.receiver)
                 }
                 NFT_PORT_ID_STR => {
@@ -823,6 +1311,11 @@ fn get_envelope_verifier(
                 }
             }
         }),
+        // Opening a channel from the controller port requires the owner
+        MsgEnvelope::Channel(ChannelMsg::OpenInit(msg)) => {
+            controller_owner(&msg.port_id_on_a)
+                .map(|owner| owner.to_string().into())
+        }
         MsgEnvelope::Packet(PacketMsg::Timeout(msg)) => {
             match msg.packet.port_id_on_a.as_str() {
                 FT_PORT_ID_STR => {
@@ -855,6 +1348,7 @@ pub fn decode_message<Transfer: BorshDeserialize>(
             let msg = MsgTransfer {
                 message,
                 transfer: None,
+                fee: None,
             };
             return Ok(IbcMessage::Transfer(Box::new(msg)));
         }
@@ -877,6 +1371,16 @@ pub fn decode_message<Transfer: BorshDeserialize>(
         return Ok(IbcMessage::NftTransfer(msg));
     }
 
+    // Interchain accounts controller message
+    if let Ok(msg) = MsgInterchainAccount::try_from_slice(tx_data) {
+        return Ok(IbcMessage::InterchainAccount(msg));
+    }
+
+    // ICS-29 relayer message
+    if let Ok(msg) = MsgRelayerFee::try_from_slice(tx_data) {
+        return Ok(IbcMessage::RelayerFee(msg));
+    }
+
     Err(Error::DecodingData)
 }
 
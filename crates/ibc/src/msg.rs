@@ -17,6 +17,11 @@ use ibc::primitives::proto::Protobuf;
 use masp_primitives::transaction::Transaction as MaspTransaction;
 use namada_core::borsh::BorshSerializeExt;
 
+use crate::fee::{MsgRelayerFee, RelayerFee};
+use crate::ica::{
+    MsgInterchainAccount, HOST_PORT_ID_STR as ICA_HOST_PORT_ID_STR,
+};
+
 /// The different variants of an Ibc message
 #[derive(Debug, Clone)]
 pub enum IbcMessage<Transfer> {
@@ -26,6 +31,10 @@ pub enum IbcMessage<Transfer> {
     Transfer(Box<MsgTransfer<Transfer>>),
     /// NFT transfer
     NftTransfer(MsgNftTransfer<Transfer>),
+    /// Interchain accounts controller message
+    InterchainAccount(MsgInterchainAccount),
+    /// ICS-29 relayer message
+    RelayerFee(MsgRelayerFee),
 }
 
 /// IBC transfer message with `Transfer`
@@ -36,6 +45,8 @@ pub struct MsgTransfer<Transfer> {
     pub message: IbcMsgTransfer,
     /// Shieleded transfer for MASP transaction
     pub transfer: Option<Transfer>,
+    /// ICS-29 relayer fees escrowed from the sender
+    pub fee: Option<RelayerFee>,
 }
 
 impl<Transfer: BorshSerialize> BorshSerialize for MsgTransfer<Transfer> {
@@ -44,7 +55,7 @@ impl<Transfer: BorshSerialize> BorshSerialize for MsgTransfer<Transfer> {
         writer: &mut W,
     ) -> std::io::Result<()> {
         let encoded_msg = self.message.clone().encode_vec();
-        let members = (encoded_msg, &self.transfer);
+        let members = (encoded_msg, &self.transfer, &self.fee);
         BorshSerialize::serialize(&members, writer)
     }
 }
@@ -54,11 +65,18 @@ impl<Transfer: BorshDeserialize> BorshDeserialize for MsgTransfer<Transfer> {
         reader: &mut R,
     ) -> std::io::Result<Self> {
         use std::io::{Error, ErrorKind};
-        let (msg, transfer): (Vec<u8>, Option<Transfer>) =
-            BorshDeserialize::deserialize_reader(reader)?;
+        let (msg, transfer, fee): (
+            Vec<u8>,
+            Option<Transfer>,
+            Option<RelayerFee>,
+        ) = BorshDeserialize::deserialize_reader(reader)?;
         let message = IbcMsgTransfer::decode_vec(&msg)
             .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
-        Ok(Self { message, transfer })
+        Ok(Self {
+            message,
+            transfer,
+            fee,
+        })
     }
 }
 
@@ -66,10 +84,12 @@ impl<Transfer: BorshSchema> BorshSchema for MsgTransfer<Transfer> {
     fn add_definitions_recursively(
         definitions: &mut BTreeMap<Declaration, Definition>,
     ) {
-        <(Vec<u8>, Option<Transfer>)>::add_definitions_recursively(definitions);
-        let fields = Fields::UnnamedFields(vec![
-            <(Vec<u8>, Option<Transfer>)>::declaration(),
-        ]);
+        <(Vec<u8>, Option<Transfer>, Option<RelayerFee>)>::add_definitions_recursively(definitions);
+        let fields = Fields::UnnamedFields(vec![<(
+            Vec<u8>,
+            Option<Transfer>,
+            Option<RelayerFee>,
+        )>::declaration()]);
         definitions.insert(Self::declaration(), Definition::Struct { fields });
     }
 
@@ -183,6 +203,8 @@ fn extract_memo_from_packet(
                 serde_json::from_slice::<NftPacketData>(&packet.data).ok()?;
             Some(packet_data.memo?.as_ref().to_string())
         }
+        // Interchain account packets have no shielding data
+        ICA_HOST_PORT_ID_STR => None,
         _ => {
             tracing::warn!(
                 "Memo couldn't be extracted from the unsupported IBC packet \
@@ -0,0 +1,283 @@
+//! Packet-forward middleware for multi-hop ICS-20 transfers
+//!
+//! An incoming ICS-20 packet whose memo has a `forward` object is received
+//! by the IBC account on this chain, and the tokens are sent to the next hop
+//! in the same transaction. The memo follows the format of the
+//! packet-forward middleware of ibc-go:
+//!
+//! ```json
+//! {"forward": {"receiver": "...", "port": "transfer", "channel": "channel-1",
+//!  "timeout": "10m", "retries": 2, "next": {"forward": {...}}}}
+//! ```
+//!
+//! The incoming packet is acknowledged when the tokens are forwarded. When
+//! the forwarded packet fails, it is re-sent while retries remain, and then
+//! the tokens are refunded to the original sender over the incoming channel.
+
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Deserializer};
+
+/// The default timeout of a forwarded packet in nanoseconds (5 minutes)
+pub const DEFAULT_FORWARD_TIMEOUT: u64 = 5 * 60 * 1_000_000_000;
+/// The default number of retries of a forwarded packet
+pub const DEFAULT_FORWARD_RETRIES: u8 = 1;
+
+/// The memo of an incoming packet to be forwarded
+#[derive(Debug, Clone, Deserialize)]
+pub struct PacketForwardMemo {
+    /// Forward metadata
+    pub forward: ForwardMetadata,
+}
+
+/// The forward metadata in the memo
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForwardMetadata {
+    /// Receiver on the next hop
+    pub receiver: String,
+    /// Port ID on this chain to send the packet
+    pub port: String,
+    /// Channel ID on this chain to send the packet
+    pub channel: String,
+    /// Timeout relative to the block time in nanoseconds
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub timeout: Option<u64>,
+    /// Number of retries when the forwarded packet fails
+    #[serde(default)]
+    pub retries: Option<u8>,
+    /// Memo for the next hop
+    #[serde(default)]
+    pub next: Option<serde_json::Value>,
+}
+
+impl ForwardMetadata {
+    /// Get the memo for the next hop
+    pub fn next_memo(&self) -> String {
+        match &self.next {
+            None => String::new(),
+            Some(serde_json::Value::String(memo)) => memo.clone(),
+            Some(next) => next.to_string(),
+        }
+    }
+}
+
+impl PacketForwardMemo {
+    /// Parse the memo of an incoming packet. Returns `None` if the memo
+    /// doesn't have the forward metadata.
+    pub fn parse(memo: impl AsRef<str>) -> Option<Result<Self, String>> {
+        let value: serde_json::Value =
+            serde_json::from_str(memo.as_ref()).ok()?;
+        value.get("forward")?;
+        Some(
+            serde_json::from_value(value)
+                .map_err(|e| format!("Invalid forward metadata: {e}")),
+        )
+    }
+}
+
+/// The destination of the refund when the forwarding fails
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ForwardRefund {
+    /// Port ID on this chain where the original packet was received
+    pub port_id: String,
+    /// Channel ID on this chain where the original packet was received
+    pub channel_id: String,
+    /// The original sender on the counterparty chain
+    pub receiver: String,
+}
+
+/// A transfer sent by the packet-forward middleware. It's stored while the
+/// packet is in flight.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ForwardTransfer {
+    /// Port ID on this chain to send the packet
+    pub port_id: String,
+    /// Channel ID on this chain to send the packet
+    pub channel_id: String,
+    /// Receiver on the next hop
+    pub receiver: String,
+    /// Denom of the forwarded token on this chain
+    pub denom: String,
+    /// Amount of the forwarded token
+    pub amount: String,
+    /// Memo for the next hop
+    pub memo: String,
+    /// Timeout relative to the block time in nanoseconds
+    pub timeout: u64,
+    /// Remaining retries
+    pub retries: u8,
+    /// The refund destination. `None` if this transfer is a refund.
+    pub refund: Option<ForwardRefund>,
+}
+
+impl ForwardTransfer {
+    /// Make the retry of this transfer if any retry remains
+    pub fn retry(&self) -> Option<Self> {
+        let retries = self.retries.checked_sub(1)?;
+        Some(Self {
+            retries,
+            ..self.clone()
+        })
+    }
+
+    /// Make the refund transfer to the original sender. A refund is retried
+    /// until it succeeds.
+    pub fn refund(&self) -> Self {
+        match &self.refund {
+            Some(refund) => Self {
+                port_id: refund.port_id.clone(),
+                channel_id: refund.channel_id.clone(),
+                receiver: refund.receiver.clone(),
+                denom: self.denom.clone(),
+                amount: self.amount.clone(),
+                memo: String::new(),
+                timeout: self.timeout,
+                retries: 0,
+                refund: None,
+            },
+            None => self.clone(),
+        }
+    }
+}
+
+fn deserialize_duration<'de, D>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Duration {
+        Nanos(u64),
+        Text(String),
+    }
+
+    match Option::<Duration>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Duration::Nanos(nanos)) => Ok(Some(nanos)),
+        Some(Duration::Text(text)) => parse_duration(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// Parse a duration string like `1h30m` or `90s` into nanoseconds. A string
+/// of only digits is parsed as nanoseconds.
+pub fn parse_duration(text: &str) -> Result<u64, String> {
+    if let Ok(nanos) = u64::from_str(text) {
+        return Ok(nanos);
+    }
+    let mut total: u64 = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("No unit in the duration {text}"))?;
+        if digits_len == 0 {
+            return Err(format!("Invalid duration {text}"));
+        }
+        let value = u64::from_str(&rest[..digits_len])
+            .map_err(|e| format!("Invalid duration {text}: {e}"))?;
+        rest = &rest[digits_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit: u64 = match &rest[..unit_len] {
+            "ns" => 1,
+            "us" | "µs" => 1_000,
+            "ms" => 1_000_000,
+            "s" => 1_000_000_000,
+            "m" => 60 * 1_000_000_000,
+            "h" => 60 * 60 * 1_000_000_000,
+            unit => {
+                return Err(format!(
+                    "Invalid unit {unit} in the duration {text}"
+                ));
+            }
+        };
+        rest = &rest[unit_len..];
+        total = value
+            .checked_mul(unit)
+            .and_then(|nanos| total.checked_add(nanos))
+            .ok_or_else(|| format!("The duration {text} overflowed"))?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_forward_memo() {
+        let memo = r#"{"forward":{"receiver":"cosmos1receiver","port":"transfer","channel":"channel-1","timeout":"10m","retries":2,"next":{"forward":{"receiver":"osmo1receiver","port":"transfer","channel":"channel-7"}}}}"#;
+        let memo = PacketForwardMemo::parse(memo).unwrap().unwrap();
+        assert_eq!(memo.forward.receiver, "cosmos1receiver");
+        assert_eq!(memo.forward.channel, "channel-1");
+        assert_eq!(memo.forward.timeout, Some(600_000_000_000));
+        assert_eq!(memo.forward.retries, Some(2));
+        let next = PacketForwardMemo::parse(memo.forward.next_memo())
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.forward.receiver, "osmo1receiver");
+        assert_eq!(next.forward.timeout, None);
+
+        // Not a forward memo
+        assert!(PacketForwardMemo::parse("").is_none());
+        assert!(PacketForwardMemo::parse("shielding data").is_none());
+        assert!(PacketForwardMemo::parse(r#"{"wasm":{}}"#).is_none());
+        // Invalid forward metadata
+        assert!(
+            PacketForwardMemo::parse(r#"{"forward":{"receiver":"a"}}"#)
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("100").unwrap(), 100);
+        assert_eq!(parse_duration("90s").unwrap(), 90_000_000_000);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5_400_000_000_000);
+        assert_eq!(parse_duration("1500ms").unwrap(), 1_500_000_000);
+        assert!(parse_duration("10").is_ok());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("10m5").is_err());
+    }
+
+    #[test]
+    fn test_forward_retry_and_refund() {
+        let transfer = ForwardTransfer {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-1".to_string(),
+            receiver: "cosmos1receiver".to_string(),
+            denom: "transfer/channel-0/uatom".to_string(),
+            amount: "100".to_string(),
+            memo: String::new(),
+            timeout: DEFAULT_FORWARD_TIMEOUT,
+            retries: 1,
+            refund: Some(ForwardRefund {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-0".to_string(),
+                receiver: "cosmos1sender".to_string(),
+            }),
+        };
+        let retry = transfer.retry().unwrap();
+        assert_eq!(retry.retries, 0);
+        assert!(retry.retry().is_none());
+
+        let refund = retry.refund();
+        assert_eq!(refund.channel_id, "channel-0");
+        assert_eq!(refund.receiver, "cosmos1sender");
+        assert_eq!(refund.refund, None);
+        // The refund is re-sent as it is
+        assert_eq!(refund.refund(), refund);
+    }
+}
@@ -3,6 +3,7 @@
 use namada_core::borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::token::Amount;
 use namada_state::{Result, StorageWrite};
+use namada_systems::ibc::ParamChange;
 
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
 /// Governance parameter structure
@@ -31,4 +32,18 @@ impl IbcParameters {
         let key = crate::storage::params_key();
         storage.write(&key, self)
     }
+
+    /// Apply a governance change of a single parameter
+    pub fn apply_change(&mut self, change: &ParamChange) {
+        match change {
+            ParamChange::DefaultMintLimit(value) => {
+                self.default_mint_limit = *value
+            }
+            ParamChange::DefaultPerEpochThroughputLimit(value) => {
+                self.default_per_epoch_throughput_limit = *value
+            }
+            // The rate limits are stored apart from the parameters
+            ParamChange::RateLimit(_) => {}
+        }
+    }
 }
@@ -0,0 +1,353 @@
+//! Rolling-window rate limits of IBC transfers
+//!
+//! The inbound and the outbound flows of each token are tracked over all the
+//! channels and over each channel. The quotas over all the channels are set
+//! for a token, and a channel can have its own quotas to limit the damage of
+//! a compromised counterparty.
+//!
+//! A rolling window is estimated from the amounts of the current fixed window
+//! and the previous one. The previous amount is weighted by the remaining
+//! part of the rolling window overlapping the previous fixed window.
+
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::token::Amount;
+pub use namada_systems::ibc::{RateLimitChange, RateLimits};
+use serde::{Deserialize, Serialize};
+
+/// The length of the hourly window in seconds
+pub const HOUR_SECS: u64 = 60 * 60;
+/// The length of the daily window in seconds
+pub const DAY_SECS: u64 = 24 * HOUR_SECS;
+
+/// The direction of a flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowDirection {
+    /// Tokens received from the counterparty
+    Inbound,
+    /// Tokens sent to the counterparty
+    Outbound,
+}
+
+impl FlowDirection {
+    /// Get the direction as a string for events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+        }
+    }
+}
+
+/// The flows in a fixed window and the previous one
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct WindowFlow {
+    /// Index of the current fixed window since the UNIX epoch
+    pub index: u64,
+    /// Inbound amount in the current window
+    pub inbound: Amount,
+    /// Outbound amount in the current window
+    pub outbound: Amount,
+    /// Inbound amount in the previous window
+    pub prev_inbound: Amount,
+    /// Outbound amount in the previous window
+    pub prev_outbound: Amount,
+}
+
+impl WindowFlow {
+    // Move to the fixed window of the index
+    fn advance(&self, index: u64) -> Self {
+        if index <= self.index {
+            return self.clone();
+        }
+        let (prev_inbound, prev_outbound) =
+            if self.index.checked_add(1) == Some(index) {
+                (self.inbound, self.outbound)
+            } else {
+                (Amount::zero(), Amount::zero())
+            };
+        Self {
+            index,
+            inbound: Amount::zero(),
+            outbound: Amount::zero(),
+            prev_inbound,
+            prev_outbound,
+        }
+    }
+
+    /// Estimate the amount of the direction in the rolling window of the
+    /// length ending at the time in seconds
+    pub fn rolling_amount(
+        &self,
+        direction: FlowDirection,
+        window_secs: u64,
+        now: u64,
+    ) -> Option<Amount> {
+        let flow = self.advance(now.checked_div(window_secs)?);
+        let (current, prev) = match direction {
+            FlowDirection::Inbound => (flow.inbound, flow.prev_inbound),
+            FlowDirection::Outbound => (flow.outbound, flow.prev_outbound),
+        };
+        let elapsed = now.checked_rem(window_secs)?;
+        let remaining = window_secs.checked_sub(elapsed)?;
+        prev.checked_mul(Amount::from_u64(remaining))?
+            .checked_div_u64(window_secs)?
+            .checked_add(current)
+    }
+}
+
+/// The hourly and daily flows of a token
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RateLimitFlow {
+    /// The block time in seconds of the last update
+    pub updated_at: u64,
+    /// The hourly flow
+    pub hourly: WindowFlow,
+    /// The daily flow
+    pub daily: WindowFlow,
+}
+
+impl RateLimitFlow {
+    /// Add the amount of the direction at the time in seconds
+    pub fn add(
+        &self,
+        now: u64,
+        direction: FlowDirection,
+        amount: Amount,
+    ) -> Option<Self> {
+        let mut hourly = self.hourly.advance(now.checked_div(HOUR_SECS)?);
+        let mut daily = self.daily.advance(now.checked_div(DAY_SECS)?);
+        for flow in [&mut hourly, &mut daily] {
+            match direction {
+                FlowDirection::Inbound => {
+                    flow.inbound = flow.inbound.checked_add(amount)?
+                }
+                FlowDirection::Outbound => {
+                    flow.outbound = flow.outbound.checked_add(amount)?
+                }
+            }
+        }
+        Some(Self {
+            updated_at: now.max(self.updated_at),
+            hourly,
+            daily,
+        })
+    }
+
+    /// Get the remaining quotas at the time in seconds. `None` means no
+    /// limit.
+    pub fn remaining(&self, limits: &RateLimits, now: u64) -> RateLimits {
+        let remaining =
+            |limit: &Option<Amount>, flow: &WindowFlow, window_secs, dir| {
+                limit.map(|limit| {
+                    flow.rolling_amount(dir, window_secs, now)
+                        .and_then(|amount| limit.checked_sub(amount))
+                        .unwrap_or_default()
+                })
+            };
+        RateLimits {
+            hourly_inbound: remaining(
+                &limits.hourly_inbound,
+                &self.hourly,
+                HOUR_SECS,
+                FlowDirection::Inbound,
+            ),
+            hourly_outbound: remaining(
+                &limits.hourly_outbound,
+                &self.hourly,
+                HOUR_SECS,
+                FlowDirection::Outbound,
+            ),
+            daily_inbound: remaining(
+                &limits.daily_inbound,
+                &self.daily,
+                DAY_SECS,
+                FlowDirection::Inbound,
+            ),
+            daily_outbound: remaining(
+                &limits.daily_outbound,
+                &self.daily,
+                DAY_SECS,
+                FlowDirection::Outbound,
+            ),
+        }
+    }
+
+    /// Check the flow of the direction at the last update against the limits
+    pub fn check(
+        &self,
+        limits: &RateLimits,
+        direction: FlowDirection,
+    ) -> Result<(), String> {
+        let (hourly_limit, daily_limit) = match direction {
+            FlowDirection::Inbound => {
+                (limits.hourly_inbound, limits.daily_inbound)
+            }
+            FlowDirection::Outbound => {
+                (limits.hourly_outbound, limits.daily_outbound)
+            }
+        };
+        for (limit, flow, window_secs, window) in [
+            (hourly_limit, &self.hourly, HOUR_SECS, "hourly"),
+            (daily_limit, &self.daily, DAY_SECS, "daily"),
+        ] {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let amount = flow
+                .rolling_amount(direction, window_secs, self.updated_at)
+                .ok_or_else(|| "The rolling amount overflowed".to_string())?;
+            if limit < amount {
+                return Err(format!(
+                    "The {window} {} quota {limit} is exceeded: actual amount \
+                     {amount}",
+                    direction.as_str()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the directions whose flows have been increased from the previous
+    /// flow
+    pub fn increased_directions(&self, prev: &Self) -> Vec<FlowDirection> {
+        let index = self.hourly.index.max(prev.hourly.index);
+        let current = self.hourly.advance(index);
+        let prev = prev.hourly.advance(index);
+        let mut directions = Vec::new();
+        if prev.inbound < current.inbound {
+            directions.push(FlowDirection::Inbound);
+        }
+        if prev.outbound < current.outbound {
+            directions.push(FlowDirection::Outbound);
+        }
+        directions
+    }
+}
+
+/// The limits and the remaining quotas of a token
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RateLimitQuota {
+    /// The limits
+    pub limits: RateLimits,
+    /// The remaining quotas
+    pub remaining: RateLimits,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_window() {
+        let start = 10 * HOUR_SECS;
+        let flow = RateLimitFlow::default()
+            .add(start, FlowDirection::Inbound, Amount::from_u64(100))
+            .unwrap();
+        assert_eq!(
+            flow.hourly
+                .rolling_amount(FlowDirection::Inbound, HOUR_SECS, start)
+                .unwrap(),
+            Amount::from_u64(100)
+        );
+
+        // Half of the previous window is still in the rolling window
+        let now = start + HOUR_SECS + HOUR_SECS / 2;
+        let flow = flow
+            .add(now, FlowDirection::Inbound, Amount::from_u64(10))
+            .unwrap();
+        assert_eq!(
+            flow.hourly
+                .rolling_amount(FlowDirection::Inbound, HOUR_SECS, now)
+                .unwrap(),
+            Amount::from_u64(60)
+        );
+        // The daily window has both
+        assert_eq!(
+            flow.daily
+                .rolling_amount(FlowDirection::Inbound, DAY_SECS, now)
+                .unwrap(),
+            Amount::from_u64(110)
+        );
+        // The outbound flow is separated
+        assert!(
+            flow.hourly
+                .rolling_amount(FlowDirection::Outbound, HOUR_SECS, now)
+                .unwrap()
+                .is_zero()
+        );
+
+        // The old windows are dropped
+        let later = now + 2 * HOUR_SECS;
+        assert!(
+            flow.hourly
+                .rolling_amount(FlowDirection::Inbound, HOUR_SECS, later)
+                .unwrap()
+                .is_zero()
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_check() {
+        let limits = RateLimits {
+            hourly_inbound: Some(Amount::from_u64(100)),
+            hourly_outbound: None,
+            daily_inbound: None,
+            daily_outbound: Some(Amount::from_u64(150)),
+        };
+        let now = 5 * DAY_SECS;
+        let flow = RateLimitFlow::default()
+            .add(now, FlowDirection::Inbound, Amount::from_u64(100))
+            .unwrap()
+            .add(now, FlowDirection::Outbound, Amount::from_u64(120))
+            .unwrap();
+        flow.check(&limits, FlowDirection::Inbound).unwrap();
+        flow.check(&limits, FlowDirection::Outbound).unwrap();
+
+        let remaining = flow.remaining(&limits, now);
+        assert_eq!(remaining.hourly_inbound, Some(Amount::zero()));
+        assert_eq!(remaining.hourly_outbound, None);
+        assert_eq!(remaining.daily_outbound, Some(Amount::from_u64(30)));
+
+        let exceeded = flow
+            .add(now + 1, FlowDirection::Outbound, Amount::from_u64(31))
+            .unwrap();
+        assert!(exceeded.check(&limits, FlowDirection::Outbound).is_err());
+        assert_eq!(
+            exceeded.increased_directions(&flow),
+            vec![FlowDirection::Outbound]
+        );
+    }
+}
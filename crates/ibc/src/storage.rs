@@ -13,16 +13,18 @@ use ibc::core::host::types::path::{
     ReceiptPath, SeqAckPath, SeqRecvPath, SeqSendPath, UpgradeClientStatePath,
     UpgradeConsensusStatePath,
 };
-use namada_core::address::{Address, InternalAddress};
+use namada_core::address::{self, Address, InternalAddress};
+use namada_core::hash::Hash;
 use namada_core::storage::{DbKeySeg, Key, KeySeg};
 use namada_core::token::Amount;
 use namada_events::EmitEvents;
 pub use namada_state::{Error, Result};
 use namada_state::{StorageRead, StorageWrite};
-use namada_systems::trans_token;
+use namada_systems::{governance, trans_token};
 
 use crate::event::TOKEN_EVENT_DESCRIPTOR;
 use crate::parameters::IbcParameters;
+use crate::rate_limit::{RateLimitChange, RateLimits};
 use crate::trace::{ibc_token, ibc_token_for_nft};
 
 const CLIENTS_COUNTER_PREFIX: &str = "clients";
@@ -38,6 +40,23 @@ const MINT: &str = "mint";
 const THROUGHPUT_LIMIT: &str = "throughput_limit";
 const DEPOSIT: &str = "deposit";
 const WITHDRAW: &str = "withdraw";
+const ICA: &str = "ica";
+const ICA_CONTROLLER: &str = "controller";
+const ICA_HOST: &str = "host";
+const ICA_ACCOUNT: &str = "account";
+const ICA_ACTIVE_CHANNEL: &str = "active_channel";
+const FORWARD: &str = "forward";
+const HOOK_ALLOWLIST: &str = "hook_allowlist";
+const HOOK_FAILURE: &str = "ibc_hook_failure";
+const WASM_CLIENT_ALLOWLIST: &str = "wasm_client_allowlist";
+const RATE_LIMIT: &str = "rate_limit";
+const CHANNEL_RATE_LIMIT: &str = "channel_rate_limit";
+const RATE_LIMIT_FLOW: &str = "rate_limit_flow";
+const CHANNEL_RATE_LIMIT_FLOW: &str = "channel_rate_limit_flow";
+const FEE_ENABLED: &str = "fee_enabled";
+const PACKET_FEE: &str = "packet_fee";
+const PAYEE: &str = "payee";
+const COUNTERPARTY_PAYEE: &str = "counterparty_payee";
 
 /// Mint IBC tokens. This function doesn't emit event (see
 /// `mint_tokens_and_emit_event` below)
@@ -647,3 +666,390 @@ pub fn withdraw_key(token: &Address) -> Key {
         .push(&token.to_string().to_db_key())
         .expect("Cannot obtain a storage key")
 }
+
+/// Returns a key of the rolling-window rate limits of the token over all the
+/// channels
+pub fn rate_limit_key(token: &Address) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&RATE_LIMIT.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        // Set as String to avoid checking the token address
+        .push(&token.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a key of the rolling-window rate limits of the token over the
+/// channel
+pub fn channel_rate_limit_key(channel_id: &ChannelId, token: &Address) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&CHANNEL_RATE_LIMIT.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        // Set as String to avoid checking the token address
+        .push(&token.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the rolling-window rate limits of the token over the channel, or over
+/// all the channels if no channel is given. No limit is set by default.
+pub fn get_rate_limits<S: StorageRead>(
+    storage: &S,
+    token: &Address,
+    channel_id: Option<&ChannelId>,
+) -> Result<RateLimits> {
+    let key = match channel_id {
+        Some(channel_id) => channel_rate_limit_key(channel_id, token),
+        None => rate_limit_key(token),
+    };
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Write the rolling-window rate limits of the change
+pub fn write_rate_limits<S: StorageWrite>(
+    storage: &mut S,
+    change: &RateLimitChange,
+) -> Result<()> {
+    let key = match &change.channel_id {
+        Some(channel_id) => {
+            let channel_id = ChannelId::from_str(channel_id)
+                .map_err(|e| Error::new_alloc(e.to_string()))?;
+            channel_rate_limit_key(&channel_id, &change.token)
+        }
+        None => rate_limit_key(&change.token),
+    };
+    storage.write(&key, change.limits.clone())
+}
+
+/// Returns a key of the rolling-window flow of the token over the channel,
+/// or over all the channels if no channel is given
+pub fn rate_limit_flow_key(
+    token: &Address,
+    channel_id: Option<&ChannelId>,
+) -> Key {
+    let key = Key::from(Address::Internal(InternalAddress::Ibc).to_db_key());
+    match channel_id {
+        Some(channel_id) => key
+            .push(&CHANNEL_RATE_LIMIT_FLOW.to_string().to_db_key())
+            .expect("Cannot obtain a storage key")
+            .push(&channel_id.to_string().to_db_key())
+            .expect("Cannot obtain a storage key"),
+        None => key
+            .push(&RATE_LIMIT_FLOW.to_string().to_db_key())
+            .expect("Cannot obtain a storage key"),
+    }
+    // Set as String to avoid checking the token address
+    .push(&token.to_string().to_db_key())
+    .expect("Cannot obtain a storage key")
+}
+
+/// Returns the token and the channel if the key is of a rolling-window flow.
+/// The channel is `None` for the flow over all the channels.
+pub fn is_rate_limit_flow_key(
+    key: &Key,
+) -> Option<(Address, Option<ChannelId>)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(token),
+        ] if addr == &Address::Internal(InternalAddress::Ibc)
+            && prefix == RATE_LIMIT_FLOW =>
+        {
+            Some((Address::decode(token).ok()?, None))
+        }
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(channel_id),
+            DbKeySeg::StringSeg(token),
+        ] if addr == &Address::Internal(InternalAddress::Ibc)
+            && prefix == CHANNEL_RATE_LIMIT_FLOW =>
+        {
+            let channel_id = ChannelId::from_str(channel_id).ok()?;
+            Some((Address::decode(token).ok()?, Some(channel_id)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the token address if the key is of the minted amount, the deposit
+/// or the withdraw of the token
+pub fn limit_accounting_token(key: &Key) -> Option<Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(token),
+        ] if addr == &Address::Internal(InternalAddress::Ibc)
+            && (prefix == MINT || prefix == DEPOSIT || prefix == WITHDRAW) =>
+        {
+            Address::decode(token).ok()
+        }
+        _ => None,
+    }
+}
+
+fn ica_key(
+    side: &str,
+    kind: &str,
+    connection_id: &ConnectionId,
+    controller_port_id: &PortId,
+) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&ICA.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&side.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&kind.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&connection_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&controller_port_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a key of the address of the interchain account on the host chain
+/// registered by the controller port of this chain
+pub fn ica_controller_account_key(
+    connection_id: &ConnectionId,
+    controller_port_id: &PortId,
+) -> Key {
+    ica_key(
+        ICA_CONTROLLER,
+        ICA_ACCOUNT,
+        connection_id,
+        controller_port_id,
+    )
+}
+
+/// Returns a key of the active channel of the controller port of this chain
+pub fn ica_controller_channel_key(
+    connection_id: &ConnectionId,
+    controller_port_id: &PortId,
+) -> Key {
+    ica_key(
+        ICA_CONTROLLER,
+        ICA_ACTIVE_CHANNEL,
+        connection_id,
+        controller_port_id,
+    )
+}
+
+/// Returns a key of the address of the interchain account hosted on this
+/// chain for the counterparty controller port
+pub fn ica_host_account_key(
+    connection_id: &ConnectionId,
+    controller_port_id: &PortId,
+) -> Key {
+    ica_key(ICA_HOST, ICA_ACCOUNT, connection_id, controller_port_id)
+}
+
+/// Returns a key of the active host channel for the counterparty controller
+/// port
+pub fn ica_host_channel_key(
+    connection_id: &ConnectionId,
+    controller_port_id: &PortId,
+) -> Key {
+    ica_key(
+        ICA_HOST,
+        ICA_ACTIVE_CHANNEL,
+        connection_id,
+        controller_port_id,
+    )
+}
+
+/// Returns a key of the in-flight packet forwarded by the packet-forward
+/// middleware
+pub fn forward_key(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: Sequence,
+) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&FORWARD.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&port_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&sequence.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a key of the flag of the ICS-29 fee-enabled channel
+pub fn fee_enabled_key(port_id: &PortId, channel_id: &ChannelId) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&FEE_ENABLED.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&port_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the channel is fee-enabled
+pub fn is_fee_enabled<S: StorageRead>(
+    storage: &S,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<bool> {
+    storage.has_key(&fee_enabled_key(port_id, channel_id))
+}
+
+/// Returns a key of the escrowed relayer fees of the packet
+pub fn packet_fee_key(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: Sequence,
+) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&PACKET_FEE.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&port_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&sequence.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a key of the payee of the relayer for the channel
+pub fn payee_key(channel_id: &ChannelId, relayer: &Address) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&PAYEE.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&relayer.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a key of the counterparty payee of the relayer for the channel
+pub fn counterparty_payee_key(
+    channel_id: &ChannelId,
+    relayer: &Address,
+) -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&COUNTERPARTY_PAYEE.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&relayer.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the counterparty payee of the relayer for the channel. It's empty if
+/// the relayer hasn't registered it.
+pub fn counterparty_payee<S: StorageRead>(
+    storage: &S,
+    channel_id: &ChannelId,
+    relayer: &str,
+) -> Result<String> {
+    let Ok(relayer) = Address::decode(relayer) else {
+        return Ok(String::new());
+    };
+    Ok(storage
+        .read(&counterparty_payee_key(channel_id, &relayer))?
+        .unwrap_or_default())
+}
+
+/// Returns a key of the allow-list of the tx code hashes which can be
+/// triggered by IBC hooks
+pub fn hook_allowlist_key() -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&HOOK_ALLOWLIST.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns a temporary key of the failure of the IBC hook. The protocol
+/// writes the failure to acknowledge the packet with it.
+pub fn hook_failure_key() -> Key {
+    Key::from(address::TEMP_STORAGE.to_db_key())
+        .push(&HOOK_FAILURE.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the tx code can be triggered by an IBC hook. No code is allowed
+/// when the allow-list is empty.
+pub fn is_hook_allowed<S>(storage: &S, code_hash: &Hash) -> Result<bool>
+where
+    S: StorageRead,
+{
+    let allowlist: Vec<String> =
+        storage.read(&hook_allowlist_key())?.unwrap_or_default();
+    let code_hash = code_hash.to_string().to_lowercase();
+    Ok(allowlist
+        .iter()
+        .any(|allowed| allowed.to_lowercase() == code_hash))
+}
+
+/// Returns a key of the allow-list of the wasm light client code checksums
+pub fn wasm_client_allowlist_key() -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&WASM_CLIENT_ALLOWLIST.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the wasm light client code can be used by clients. No code is
+/// allowed when the allow-list is empty.
+pub fn is_wasm_client_allowed<S>(storage: &S, checksum: &Hash) -> Result<bool>
+where
+    S: StorageRead,
+{
+    let allowlist: Vec<String> = storage
+        .read(&wasm_client_allowlist_key())?
+        .unwrap_or_default();
+    let checksum = checksum.to_string().to_lowercase();
+    Ok(allowlist
+        .iter()
+        .any(|allowed| allowed.to_lowercase() == checksum))
+}
+
+/// Store the wasm light client code and allow it. This should be called by a
+/// governance proposal. Returns the checksum of the code.
+pub fn store_wasm_client_code<S>(storage: &mut S, code: &[u8]) -> Result<Hash>
+where
+    S: StorageRead + StorageWrite,
+{
+    let checksum = Hash::sha256(code);
+    let code_len = u64::try_from(code.len()).map_err(Error::new)?;
+    storage.write(&Key::wasm_code(&checksum), code.to_vec())?;
+    storage.write(&Key::wasm_code_len(&checksum), code_len)?;
+
+    let key = wasm_client_allowlist_key();
+    let mut allowlist: Vec<String> = storage.read(&key)?.unwrap_or_default();
+    let checksum_str = checksum.to_string();
+    if !allowlist
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(&checksum_str))
+    {
+        allowlist.push(checksum_str);
+        storage.write(&key, allowlist)?;
+    }
+    Ok(checksum)
+}
+
+/// Returns true if the given key is a token balance key of an interchain
+/// account hosted on this chain
+pub fn is_ica_balance_key<Token>(key: &Key) -> bool
+where
+    Token: trans_token::Keys,
+{
+    matches!(
+        Token::is_any_token_balance_key(key),
+        Some([_, Address::Internal(InternalAddress::InterchainAccount(_))])
+    )
+}
+
+/// Returns true if the given key is a governance vote key of an interchain
+/// account hosted on this chain
+pub fn is_ica_vote_key<Gov>(key: &Key) -> bool
+where
+    Gov: governance::Keys,
+{
+    matches!(
+        Gov::is_any_vote_key(key),
+        Some((_, Address::Internal(InternalAddress::InterchainAccount(_))))
+    )
+}
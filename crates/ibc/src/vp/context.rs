@@ -3,41 +3,45 @@
 use std::collections::BTreeSet;
 use std::marker::PhantomData;
 
-use namada_core::address::Address;
+use namada_core::address::{Address, POS};
 use namada_core::arith::checked;
 use namada_core::chain::{BlockHeader, BlockHeight, ChainId, Epoch, Epochs};
 use namada_core::collections::{HashMap, HashSet};
 use namada_core::storage::{Key, TxIndex};
 use namada_events::Event;
-use namada_gas::MEMORY_ACCESS_GAS_PER_BYTE;
 use namada_state::write_log::StorageModification;
 pub use namada_state::Result;
 use namada_state::{Error, PrefixIter, StateRead, StorageRead, StorageWrite};
 use namada_systems::trans_token::{self as token, Amount};
+use namada_systems::{governance, proof_of_stake};
+use namada_tx::action::IbcHook;
 use namada_vp::native_vp::{CtxPreStorageRead, VpEvaluator};
 use namada_vp::VpEnv;
 
 use crate::event::IbcEvent;
-use crate::storage::{self, is_ibc_key};
+use crate::ica::{HostMsg, VoteOption};
+use crate::storage::{self, is_ibc_key, is_ica_balance_key, is_ica_vote_key};
 use crate::{IbcCommonContext, IbcStorageContext};
 
 /// Pseudo execution environment context for ibc native vp
 #[derive(Debug)]
-pub struct PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token>
+pub struct PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
 where
-    S: 'static + StateRead,
+    S: StateRead,
 {
     /// Execution context and storage
     pub storage: PseudoExecutionStorage<'view, 'a, S, CA, EVAL>,
     /// Token type
     pub token: PhantomData<Token>,
+    /// Governance and PoS types
+    pub _marker: PhantomData<(Gov, PoS)>,
 }
 
 /// Pseudo execution environment context storage for ibc native vp
 #[derive(Debug)]
 pub struct PseudoExecutionStorage<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
 {
     /// Temporary store for pseudo execution
     store: HashMap<Key, StorageModification>,
@@ -47,11 +51,13 @@ where
     pub event: BTreeSet<Event>,
 }
 
-impl<'view, 'a, S, CA, EVAL, Token>
-    PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token>
+impl<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
+    PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: VpEvaluator<'a, S, CA, EVAL>,
+    Token: token::Keys,
+    Gov: governance::Keys,
 {
     /// Generate new pseudo execution context
     pub fn new(ctx: CtxPreStorageRead<'view, 'a, S, CA, EVAL>) -> Self {
@@ -62,6 +68,7 @@ where
                 event: BTreeSet::new(),
             },
             token: PhantomData,
+            _marker: PhantomData,
         }
     }
 
@@ -70,7 +77,11 @@ where
         self.storage
             .store
             .keys()
-            .filter(|k| is_ibc_key(k))
+            .filter(|k| {
+                is_ibc_key(k)
+                    || is_ica_balance_key::<Token>(k)
+                    || is_ica_vote_key::<Gov>(k)
+            })
             .collect()
     }
 
@@ -83,10 +94,27 @@ where
     }
 }
 
+impl<'view, 'a, S, CA, EVAL> PseudoExecutionStorage<'view, 'a, S, CA, EVAL>
+where
+    S: StateRead,
+    CA: 'static + Clone,
+    EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
+{
+    // The gas cost of accessing the temporary store per byte
+    fn memory_access_per_byte(&self) -> u64 {
+        self.ctx
+            .ctx
+            .state
+            .in_mem()
+            .gas_cost_schedule
+            .memory_access_per_byte
+    }
+}
+
 impl<'view, 'a, S, CA, EVAL> StorageRead
     for PseudoExecutionStorage<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -94,19 +122,16 @@ where
 Self: 'iter;
 
     fn read_bytes(&self, key: &Key) -> Result<Option<Vec<u8>>> {
+        let cost = self.memory_access_per_byte();
         match self.store.get(key) {
             Some(StorageModification::Write { ref value }) => {
                 let gas = checked!(key.len() + value.len())? as u64;
-                self.ctx.ctx.charge_gas(
-                    checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
-                )?;
+                self.ctx.ctx.charge_gas(checked!(gas * cost)?.into())?;
                 Ok(Some(value.clone()))
             }
             Some(StorageModification::Delete) => {
                 let len = key.len() as u64;
-                self.ctx.ctx.charge_gas(
-                    checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
-                )?;
+                self.ctx.ctx.charge_gas(checked!(len * cost)?.into())?;
                 Ok(None)
             }
             Some(StorageModification::InitAccount { .. }) => {
@@ -114,9 +139,7 @@ Self: 'iter;
             }
             None => {
                 let len = key.len() as u64;
-                self.ctx.ctx.charge_gas(
-                    checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
-                )?;
+                self.ctx.ctx.charge_gas(checked!(len * cost)?.into())?;
                 self.ctx.read_bytes(key)
             }
         }
@@ -177,7 +200,7 @@ Self: 'iter;
 impl<'view, 'a, S, CA, EVAL> StorageWrite
     for PseudoExecutionStorage<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -188,30 +211,107 @@ where
     ) -> Result<()> {
         let value = value.as_ref().to_vec();
         let gas = checked!(key.len() + value.len())? as u64;
+        let cost = self.memory_access_per_byte();
         self.store
             .insert(key.clone(), StorageModification::Write { value });
-        self.ctx
-            .ctx
-            .charge_gas(checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into())
+        self.ctx.ctx.charge_gas(checked!(gas * cost)?.into())
     }
 
     fn delete(&mut self, key: &Key) -> Result<()> {
         self.store.insert(key.clone(), StorageModification::Delete);
         let len = key.len() as u64;
-        self.ctx
-            .ctx
-            .charge_gas(checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?.into())
+        let cost = self.memory_access_per_byte();
+        self.ctx.ctx.charge_gas(checked!(len * cost)?.into())
     }
 }
 
-impl<'view, 'a, S, CA, EVAL, Token> IbcStorageContext
-    for PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token>
+impl<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
+    PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
     CA: 'static + Clone,
     Token: token::Keys
         + token::Write<PseudoExecutionStorage<'view, 'a, S, CA, EVAL>>,
+    Gov: governance::Keys,
+    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'a, S, CA, EVAL>>,
+{
+    fn execute_host_msg(
+        &mut self,
+        account: &Address,
+        msg: &HostMsg,
+    ) -> Result<()> {
+        match msg {
+            HostMsg::Transfer(msg) => self.transfer_token(
+                account,
+                &msg.target,
+                &msg.token,
+                msg.amount,
+            ),
+            HostMsg::Bond(msg) => {
+                self.bond_tokens(account, &msg.validator, msg.amount)
+            }
+            HostMsg::Vote(msg) => {
+                self.vote_proposal(account, msg.proposal_id, msg.vote)
+            }
+        }
+    }
+
+    fn bond_tokens(
+        &mut self,
+        source: &Address,
+        validator: &Address,
+        amount: Amount,
+    ) -> Result<()> {
+        // Only the balance change of the source is reproduced here to be
+        // compared. The bond itself is validated by PoS VP, but it should fail
+        // for a non-validator target as the execution does.
+        if !PoS::is_validator(&self.storage.ctx, validator)? {
+            return Err(Error::new_alloc(format!(
+                "The bond target {validator} isn't a validator"
+            )));
+        }
+        let storage = self.storage_mut();
+        let native_token = storage.get_native_token()?;
+        Token::transfer(storage, &native_token, source, &POS, amount)
+    }
+
+    fn vote_proposal(
+        &mut self,
+        voter: &Address,
+        proposal_id: u64,
+        vote: VoteOption,
+    ) -> Result<()> {
+        // Only the vote records are reproduced here to be compared. The
+        // voting window is validated by governance VP. The vote option is
+        // encoded in the same way as the governance proposal vote.
+        let pre = &self.storage.ctx;
+        let current_epoch = pre.get_block_epoch()?;
+        let delegation_targets = if PoS::is_validator(pre, voter)? {
+            [voter.clone()].into()
+        } else {
+            PoS::delegation_validators(pre, voter, current_epoch)?
+        };
+        let storage = self.storage_mut();
+        storage.write(&Gov::account_vote_key(proposal_id, voter), vote)?;
+        for validator in delegation_targets {
+            let key = Gov::delegation_vote_key(proposal_id, voter, &validator);
+            storage.write(&key, vote)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'view, 'a, S, CA, EVAL, Token, Gov, PoS> IbcStorageContext
+    for PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
+where
+    S: StateRead,
+    EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
+    CA: 'static + Clone,
+    Token: token::Keys
+        + token::Write<PseudoExecutionStorage<'view, 'a, S, CA, EVAL>>,
+    Gov: governance::Keys,
+    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'a, S, CA, EVAL>>,
 {
     type Storage = PseudoExecutionStorage<'view, 'a, S, CA, EVAL>;
 
@@ -259,6 +359,28 @@ where
         Token::burn_tokens(storage, token, target, amount)
     }
 
+    fn execute_host_msgs(
+        &mut self,
+        account: &Address,
+        msgs: &[HostMsg],
+    ) -> Result<()> {
+        // Restore the temporary store when any message fails
+        let store = self.storage.store.clone();
+        let result = msgs
+            .iter()
+            .try_for_each(|msg| self.execute_host_msg(account, msg));
+        if result.is_err() {
+            self.storage.store = store;
+        }
+        result
+    }
+
+    fn schedule_hook(&mut self, _hook: IbcHook) -> Result<()> {
+        // The hook is executed by the protocol and validated by the VP of the
+        // receiver
+        Ok(())
+    }
+
     fn insert_verifier(&mut self, _verifier: &Address) -> Result<()> {
         Ok(())
     }
@@ -268,14 +390,16 @@ where
     }
 }
 
-impl<'view, 'a, S, CA, EVAL, Token> IbcCommonContext
-    for PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token>
+impl<'view, 'a, S, CA, EVAL, Token, Gov, PoS> IbcCommonContext
+    for PseudoExecutionContext<'view, 'a, S, CA, EVAL, Token, Gov, PoS>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
     Token: token::Keys
         + token::Write<PseudoExecutionStorage<'view, 'a, S, CA, EVAL>>,
+    Gov: governance::Keys,
+    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'a, S, CA, EVAL>>,
 {
 }
 
@@ -283,7 +407,7 @@ where
 #[derive(Debug)]
 pub struct VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: VpEvaluator<'a, S, CA, EVAL>,
 {
     /// Context to read the post value
@@ -292,7 +416,7 @@ where
 
 impl<'view, 'a, S, CA, EVAL> VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: VpEvaluator<'a, S, CA, EVAL>,
 {
     /// Generate a new ibc vp validation context
@@ -304,7 +428,7 @@ where
 impl<'view, 'a, S, CA, EVAL> StorageRead
     for VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -367,7 +491,7 @@ where
 impl<'view, 'a, S, CA, EVAL> StorageWrite
     for VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -387,7 +511,7 @@ where
 impl<'view, 'a, S, CA, EVAL> IbcStorageContext
     for VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -433,6 +557,18 @@ where
         unimplemented!("Validation doesn't burn")
     }
 
+    fn execute_host_msgs(
+        &mut self,
+        _account: &Address,
+        _msgs: &[HostMsg],
+    ) -> Result<()> {
+        unimplemented!("Validation doesn't execute host messages")
+    }
+
+    fn schedule_hook(&mut self, _hook: IbcHook) -> Result<()> {
+        unimplemented!("Validation doesn't schedule a hook")
+    }
+
     fn insert_verifier(&mut self, _verifier: &Address) -> Result<()> {
         Ok(())
     }
@@ -446,7 +582,7 @@ where
 impl<'view, 'a, S, CA, EVAL> IbcCommonContext
     for VpValidationContext<'view, 'a, S, CA, EVAL>
 where
-    S: 'static + StateRead,
+    S: StateRead,
     CA: 'static + Clone,
     EVAL: 'static + VpEvaluator<'a, S, CA, EVAL>,
 {
@@ -0,0 +1,253 @@
+//! Validity predicate for interchain accounts hosted on this chain
+
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+
+use namada_core::address::{interchain_account, Address, InternalAddress};
+use namada_core::storage::Key;
+use namada_state::{Error, Result};
+use namada_systems::governance;
+use namada_systems::trans_token::{self as token, Amount};
+use namada_tx::BatchedTxRef;
+use namada_vp::VpEnv;
+use prost::Message;
+
+use crate::core::channel::types::channel::ChannelEnd;
+use crate::core::channel::types::msgs::PacketMsg;
+use crate::core::channel::types::packet::Packet;
+use crate::core::handler::types::msgs::MsgEnvelope;
+use crate::ica::{
+    is_host_port, HostMsg, InterchainAccountPacketData, VoteOption,
+};
+use crate::primitives::proto::{Any, Protobuf};
+use crate::storage;
+
+/// Validity predicate for interchain accounts.
+///
+/// An interchain account can be debited and can vote only as requested by the
+/// host messages in a packet received on the active host channel of the
+/// account. The IBC VP validates that the state changes reproduce the
+/// messages. Otherwise, the account can only receive tokens.
+pub struct IcaVp<'ctx, CTX, TokenKeys, GovKeys> {
+    /// Generic types for DI
+    pub _marker: PhantomData<(&'ctx CTX, TokenKeys, GovKeys)>,
+}
+
+impl<'ctx, CTX, TokenKeys, GovKeys> IcaVp<'ctx, CTX, TokenKeys, GovKeys>
+where
+    CTX: VpEnv<'ctx>,
+    TokenKeys: token::Keys,
+    GovKeys: governance::Keys,
+{
+    /// Run the validity predicate
+    pub fn validate_tx(
+        ctx: &'ctx CTX,
+        account: &Address,
+        batched_tx: &BatchedTxRef<'_>,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<()> {
+        let host_msgs =
+            match Self::host_packet(ctx, account, batched_tx, keys_changed)? {
+                Some(packet) => {
+                    if !verifiers
+                        .contains(&Address::Internal(InternalAddress::Ibc))
+                    {
+                        return Err(Error::new_const(
+                            "The IBC VP should validate the interchain \
+                             account tx",
+                        ));
+                    }
+                    // The messages aren't executed when the packet data is
+                    // invalid, the packet is acknowledged with an error instead
+                    serde_json::from_slice::<InterchainAccountPacketData>(
+                        &packet.data,
+                    )
+                    .ok()
+                    .and_then(|data| data.decode_host_msgs().ok())
+                    .unwrap_or_default()
+                }
+                None => vec![],
+            };
+
+        for key in keys_changed
+            .iter()
+            .filter(|key| key.find_addresses().contains(account))
+        {
+            Self::validate_change(ctx, account, key, &host_msgs)?;
+        }
+
+        Ok(())
+    }
+
+    // Check a change of the interchain account against the host messages
+    // executed by the tx
+    fn validate_change(
+        ctx: &'ctx CTX,
+        account: &Address,
+        key: &Key,
+        host_msgs: &[HostMsg],
+    ) -> Result<()> {
+        if let Some([token, owner]) = TokenKeys::is_any_token_balance_key(key) {
+            if owner == account {
+                return Self::validate_balance_change(
+                    ctx, account, token, key, host_msgs,
+                );
+            }
+        }
+        if let Some((proposal_id, voter)) = GovKeys::is_any_vote_key(key) {
+            if voter == account {
+                return Self::validate_vote(
+                    ctx,
+                    account,
+                    proposal_id,
+                    key,
+                    host_msgs,
+                );
+            }
+        }
+        // The bond itself is validated by PoS VP
+        if host_msgs.iter().any(|msg| matches!(msg, HostMsg::Bond(_))) {
+            return Ok(());
+        }
+        Err(Error::new_alloc(format!(
+            "The interchain account {account} can't be changed without a host \
+             message: {key}"
+        )))
+    }
+
+    // The balance can only be decreased by the amounts of the transfers and
+    // the bonds of the host messages, any other change has to be a credit
+    fn validate_balance_change(
+        ctx: &'ctx CTX,
+        account: &Address,
+        token: &Address,
+        key: &Key,
+        host_msgs: &[HostMsg],
+    ) -> Result<()> {
+        let native_token = ctx.get_native_token()?;
+        let debit = host_msgs.iter().try_fold(Amount::zero(), |acc, msg| {
+            let amount = match msg {
+                HostMsg::Transfer(msg)
+                    if msg.token == *token && msg.target != *account =>
+                {
+                    msg.amount
+                }
+                HostMsg::Bond(msg) if *token == native_token => msg.amount,
+                _ => Amount::zero(),
+            };
+            acc.checked_add(amount).ok_or_else(|| {
+                Error::new_const("The debited amount overflowed")
+            })
+        })?;
+
+        let pre: Amount = ctx.read_pre(key)?.unwrap_or_default();
+        let post: Amount = ctx.read_post(key)?.unwrap_or_default();
+        let is_valid = if debit.is_zero() {
+            post >= pre
+        } else {
+            post.checked_add(debit) == Some(pre)
+        };
+        if !is_valid {
+            tracing::debug!(
+                %key,
+                pre_amount = ?pre,
+                post_amount = ?post,
+                debit_amount = ?debit,
+                "Interchain account balance change mismatched"
+            );
+            return Err(Error::new_alloc(format!(
+                "The balance of the interchain account {account} can only be \
+                 decreased by the amount {debit} of the host messages: {key}"
+            )));
+        }
+        Ok(())
+    }
+
+    // The vote has to be requested by a host message
+    fn validate_vote(
+        ctx: &'ctx CTX,
+        account: &Address,
+        proposal_id: u64,
+        key: &Key,
+        host_msgs: &[HostMsg],
+    ) -> Result<()> {
+        // The vote option is encoded in the same way as the governance
+        // proposal vote
+        let vote: Option<VoteOption> = ctx.read_post(key)?;
+        let is_requested = host_msgs.iter().any(|msg| {
+            matches!(
+                msg,
+                HostMsg::Vote(msg)
+                    if msg.proposal_id == proposal_id
+                        && Some(msg.vote) == vote
+            )
+        });
+        if !is_requested {
+            return Err(Error::new_alloc(format!(
+                "The vote of the interchain account {account} on the proposal \
+                 {proposal_id} isn't requested by a host message: {key}"
+            )));
+        }
+        Ok(())
+    }
+
+    // Get the packet if the tx receives a packet for the account on its
+    // active host channel
+    fn host_packet(
+        ctx: &'ctx CTX,
+        account: &Address,
+        batched_tx: &BatchedTxRef<'_>,
+        keys_changed: &BTreeSet<Key>,
+    ) -> Result<Option<Packet>> {
+        let Some(tx_data) = batched_tx.tx.data(batched_tx.cmt) else {
+            return Ok(None);
+        };
+        let Ok(any_msg) = Any::decode(&tx_data[..]) else {
+            return Ok(None);
+        };
+        let Ok(MsgEnvelope::Packet(PacketMsg::Recv(msg))) =
+            MsgEnvelope::try_from(any_msg)
+        else {
+            return Ok(None);
+        };
+        let packet = msg.packet;
+        if !is_host_port(&packet.port_id_on_b) {
+            return Ok(None);
+        }
+
+        let channel_key =
+            storage::channel_key(&packet.port_id_on_b, &packet.chan_id_on_b);
+        let Some(bytes) = ctx.read_bytes_pre(&channel_key)? else {
+            return Ok(None);
+        };
+        let channel_end = ChannelEnd::decode_vec(&bytes).map_err(|e| {
+            Error::new_alloc(format!("Decoding the channel end failed: {e}"))
+        })?;
+        let [connection_id] = channel_end.connection_hops() else {
+            return Ok(None);
+        };
+        if interchain_account(
+            connection_id.as_str(),
+            packet.port_id_on_a.as_str(),
+        ) != *account
+        {
+            return Ok(None);
+        }
+
+        let active_channel_key =
+            storage::ica_host_channel_key(connection_id, &packet.port_id_on_a);
+        let active_channel: Option<String> =
+            ctx.read_pre(&active_channel_key)?;
+        if active_channel.as_deref() != Some(packet.chan_id_on_b.as_str()) {
+            return Ok(None);
+        }
+
+        // Interchain account channels are ordered
+        let next_seq_recv_key = storage::next_sequence_recv_key(
+            &packet.port_id_on_b,
+            &packet.chan_id_on_b,
+        );
+        Ok(keys_changed.contains(&next_seq_recv_key).then_some(packet))
+    }
+}
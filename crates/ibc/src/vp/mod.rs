@@ -1,6 +1,7 @@
 //! IBC integration as a native validity predicate
 
 pub mod context;
+pub mod ica;
 
 use std::cell::RefCell;
 use std::collections::BTreeSet;
@@ -17,12 +18,12 @@ use namada_core::address::Address;
 use namada_core::arith::checked;
 use namada_core::collections::HashSet;
 use namada_core::storage::Key;
-use namada_gas::{IBC_ACTION_EXECUTE_GAS, IBC_ACTION_VALIDATE_GAS};
 use namada_state::write_log::StorageModification;
 use namada_state::{Error, Result, StateRead};
 use namada_systems::trans_token::{self as token, Amount};
 use namada_systems::{governance, parameters, proof_of_stake};
-use namada_tx::BatchedTxRef;
+use namada_tx::data::TxType;
+use namada_tx::{BatchedTxRef, Tx};
 use namada_vp::native_vp::{Ctx, CtxPreStorageRead, NativeVp, VpEvaluator};
 use namada_vp::VpEnv;
 use thiserror::Error;
@@ -30,14 +31,19 @@ use thiserror::Error;
 use crate::core::host::types::identifiers::ChainId as IbcChainId;
 use crate::core::host::types::path::UPGRADED_IBC_STATE;
 use crate::event::IbcEvent;
+use crate::rate_limit::RateLimitFlow;
 use crate::storage::{
-    deposit_key, get_limits, is_ibc_key, is_ibc_trace_key, mint_amount_key,
-    withdraw_key,
+    deposit_key, get_limits, get_rate_limits, hook_failure_key, is_ibc_key,
+    is_ibc_trace_key, is_ica_balance_key, is_ica_vote_key,
+    is_rate_limit_flow_key, is_wasm_client_allowed, limit_accounting_token,
+    mint_amount_key, rate_limit_flow_key, withdraw_key,
 };
 use crate::trace::calc_hash;
+use crate::wasm_client::WasmLightClientCall;
 use crate::{
-    Error as ActionError, IbcActions, NftTransferModule, TransferModule,
-    ValidationParams, COMMITMENT_PREFIX,
+    Error as ActionError, FeeModule, IbcActions, IbcHooksModule, IcaModule,
+    NftTransferModule, PacketForwardModule, TransferModule, ValidationParams,
+    COMMITMENT_PREFIX,
 };
 
 #[allow(missing_docs)]
@@ -55,6 +61,8 @@ pub enum VpError {
     IbcEvent(String),
     #[error("IBC rate limit: {0}")]
     RateLimit(String),
+    #[error("IBC VP error: wasm light client error: {0}")]
+    WasmClient(String),
 }
 
 /// IBC functions result
@@ -80,7 +88,7 @@ pub struct Ibc<
     PoS,
     Transfer,
 > where
-    S: 'static + StateRead,
+    S: StateRead,
 {
     /// Context to interact with the host structures.
     pub ctx: Ctx<'ctx, S, CA, EVAL>,
@@ -124,10 +132,12 @@ impl<
         Transfer,
     >
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: 'static + VpEvaluator<'ctx, S, CA, EVAL> + Debug,
     CA: 'static + Clone + Debug,
-    Gov: governance::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>,
+    Gov: governance::Keys
+        + governance::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>
+        + Debug,
     Params: parameters::Read<VpValidationContext<'view, 'ctx, S, CA, EVAL>>,
     ParamsPre: parameters::Keys
         + parameters::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>,
@@ -136,7 +146,8 @@ where
     Token: token::Keys
         + token::Write<PseudoExecutionStorage<'view, 'ctx, S, CA, EVAL>>
         + Debug,
-    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>,
+    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>
+        + Debug,
     Transfer: BorshDeserialize,
 {
     fn validate_tx(
@@ -206,7 +217,7 @@ impl<
         Transfer,
     >
 where
-    S: 'static + StateRead,
+    S: StateRead,
     EVAL: 'static + VpEvaluator<'ctx, S, CA, EVAL> + Debug,
     CA: 'static + Clone + Debug,
     Params: parameters::Read<VpValidationContext<'view, 'ctx, S, CA, EVAL>>,
@@ -217,7 +228,9 @@ where
     Token: token::Keys
         + token::Write<PseudoExecutionStorage<'view, 'ctx, S, CA, EVAL>>
         + Debug,
-    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>,
+    Gov: governance::Keys + Debug,
+    PoS: proof_of_stake::Read<CtxPreStorageRead<'view, 'ctx, S, CA, EVAL>>
+        + Debug,
     Transfer: BorshDeserialize,
 {
     /// Instantiate IBC VP
@@ -233,10 +246,16 @@ where
         tx_data: &[u8],
         keys_changed: &BTreeSet<Key>,
     ) -> Result<()> {
-        let exec_ctx =
-            PseudoExecutionContext::<'_, '_, S, CA, EVAL, Token>::new(
-                self.ctx.pre(),
-            );
+        let exec_ctx = PseudoExecutionContext::<
+            '_,
+            '_,
+            S,
+            CA,
+            EVAL,
+            Token,
+            Gov,
+            PoS,
+        >::new(self.ctx.pre());
         let ctx = Rc::new(RefCell::new(exec_ctx));
         // Use an empty verifiers set placeholder for validation, this is only
         // needed in actual txs to addresses whose VPs should be triggered
@@ -246,16 +265,39 @@ where
             ctx.clone(),
             verifiers.clone(),
         );
-        let module = TransferModule::new(ctx.clone(), verifiers);
-        actions.add_transfer_module(module);
+        // The protocol writes the failure of the IBC hook when it executes
+        // the tx again
+        let hook_failure = self.ctx.read_temp(&hook_failure_key())?;
+        let module = FeeModule::new(
+            ctx.clone(),
+            PacketForwardModule::new(
+                ctx.clone(),
+                IbcHooksModule::new(
+                    ctx.clone(),
+                    TransferModule::new(ctx.clone(), verifiers),
+                    hook_failure,
+                ),
+            ),
+        );
+        actions.add_fee_module(module);
         let module = NftTransferModule::<_, Token>::new(ctx.clone());
         actions.add_transfer_module(module);
+        actions.add_ica_module(IcaModule::new(ctx.clone()));
         // Charge gas for the expensive execution
-        self.ctx.charge_gas(IBC_ACTION_EXECUTE_GAS.into())?;
+        let gas_costs = &self.ctx.state.in_mem().gas_cost_schedule;
+        self.ctx.charge_gas(gas_costs.ibc_action_execute.into())?;
         actions.execute::<Transfer>(tx_data)?;
 
-        let changed_ibc_keys: HashSet<&Key> =
-            keys_changed.iter().filter(|k| is_ibc_key(k)).collect();
+        // The balances and the votes of the interchain accounts are changed
+        // only by the host messages received over IBC
+        let changed_ibc_keys: HashSet<&Key> = keys_changed
+            .iter()
+            .filter(|k| {
+                is_ibc_key(k)
+                    || is_ica_balance_key::<Token>(k)
+                    || is_ica_vote_key::<Gov>(k)
+            })
+            .collect();
         if changed_ibc_keys.len() != ctx.borrow().get_changed_keys().len() {
             return Err(VpError::StateChange(format!(
                 "The changed keys mismatched: Actual {:?}, Expected {:?}",
@@ -301,13 +343,56 @@ where
             IbcActions::<_, Params, Token>::new(ctx.clone(), verifiers.clone());
         actions.set_validation_params(self.validation_params()?);
 
-        let module = TransferModule::new(ctx.clone(), verifiers);
-        actions.add_transfer_module(module);
-        let module = NftTransferModule::<_, Token>::new(ctx);
+        // The hook isn't scheduled in validation
+        let module = FeeModule::new(
+            ctx.clone(),
+            PacketForwardModule::new(
+                ctx.clone(),
+                IbcHooksModule::new(
+                    ctx.clone(),
+                    TransferModule::new(ctx.clone(), verifiers),
+                    None,
+                ),
+            ),
+        );
+        actions.add_fee_module(module);
+        let module = NftTransferModule::<_, Token>::new(ctx.clone());
         actions.add_transfer_module(module);
+        actions.add_ica_module(IcaModule::new(ctx));
         // Charge gas for the expensive validation
-        self.ctx.charge_gas(IBC_ACTION_VALIDATE_GAS.into())?;
-        Ok(actions.validate::<Transfer>(tx_data)?)
+        let gas_costs = &self.ctx.state.in_mem().gas_cost_schedule;
+        self.ctx.charge_gas(gas_costs.ibc_action_validate.into())?;
+        actions.validate::<Transfer>(tx_data)?;
+
+        // Verify with the wasm light client code
+        for call in actions.take_light_client_calls() {
+            self.eval_light_client(call)?;
+        }
+        Ok(())
+    }
+
+    // Evaluate the wasm light client code with the request as the tx data.
+    // The gas is charged to this VP.
+    fn eval_light_client(&self, call: WasmLightClientCall) -> Result<()> {
+        if !is_wasm_client_allowed(&self.ctx.pre(), &call.checksum)? {
+            return Err(VpError::WasmClient(format!(
+                "The light client code {} isn't allowed",
+                call.checksum
+            ))
+            .into());
+        }
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.add_data(call.request);
+        let batched_tx = tx.batch_ref_first_tx().ok_or_else(|| {
+            VpError::WasmClient("No light client request".to_string())
+        })?;
+        self.ctx.eval(call.checksum, batched_tx).map_err(|e| {
+            VpError::WasmClient(format!(
+                "The light client code {} rejected the request: {e}",
+                call.checksum
+            ))
+            .into()
+        })
     }
 
     /// Retrieve the validation params
@@ -367,13 +452,17 @@ where
     }
 
     fn check_limits(&self, keys_changed: &BTreeSet<Key>) -> Result<bool> {
-        let tokens: BTreeSet<&Address> = keys_changed
+        // Forwarded tokens might not change any balance, e.g. moving the
+        // escrowed tokens to another channel
+        let tokens: BTreeSet<Address> = keys_changed
             .iter()
             .filter_map(|k| {
-                Token::is_any_token_balance_key(k).map(|[key, _]| key)
+                Token::is_any_token_balance_key(k)
+                    .map(|[key, _]| key.clone())
+                    .or_else(|| limit_accounting_token(k))
             })
             .collect();
-        for token in tokens {
+        for token in &tokens {
             let (mint_limit, throughput_limit) =
                 get_limits(&self.ctx.pre(), token)?;
 
@@ -400,6 +489,30 @@ where
                 .into());
             }
         }
+
+        // Check the rolling-window quotas of the increased flows
+        for (token, channel_id) in
+            keys_changed.iter().filter_map(is_rate_limit_flow_key)
+        {
+            let key = rate_limit_flow_key(&token, channel_id.as_ref());
+            let pre: RateLimitFlow =
+                self.ctx.read_pre(&key)?.unwrap_or_default();
+            let post: RateLimitFlow =
+                self.ctx.read_post(&key)?.unwrap_or_default();
+            let limits =
+                get_rate_limits(&self.ctx.pre(), &token, channel_id.as_ref())?;
+            for direction in post.increased_directions(&pre) {
+                post.check(&limits, direction).map_err(|e| {
+                    let channel = channel_id
+                        .as_ref()
+                        .map_or("all".to_string(), |c| c.to_string());
+                    VpError::RateLimit(format!(
+                        "Transfer exceeding the rolling-window quota is not \
+                         allowed: Token {token}, channel {channel}, {e}"
+                    ))
+                })?;
+            }
+        }
         Ok(true)
     }
 
@@ -0,0 +1,417 @@
+//! ICS-08 wasm light clients
+//!
+//! A wasm light client is a client whose verification is done by a wasm code
+//! on this chain. The code is uploaded and allow-listed by a governance
+//! proposal and it's identified by the checksum, the hash of the code, in the
+//! client state.
+//!
+//! The states follow the protobuf of `ibc.lightclients.wasm.v1` with a few
+//! additional fields. The client and the consensus states have opaque `data`
+//! only interpreted by the light client code. A consensus state has also the
+//! timestamp and the commitment root for the IBC handlers, and a client
+//! message has the new height and the new consensus state for updating the
+//! client. A client message without the new consensus state is misbehaviour.
+//!
+//! The IBC VP verifies the client messages and the proofs by evaluating the
+//! light client code with [`WasmLightClientRequest`] as the tx data. The code
+//! accepts the tx only when the request is valid.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::hash::Hash;
+use prost::Message;
+
+use crate::core::client::types::error::ClientError;
+use crate::core::client::types::Height;
+use crate::core::commitment_types::commitment::CommitmentRoot;
+use crate::primitives::proto::Any;
+use crate::primitives::Timestamp;
+
+/// The client type of wasm light clients
+pub const WASM_CLIENT_TYPE: &str = "08-wasm";
+/// Type URL of the wasm client state
+pub const WASM_CLIENT_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.wasm.v1.ClientState";
+/// Type URL of the wasm consensus state
+pub const WASM_CONSENSUS_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.wasm.v1.ConsensusState";
+/// Type URL of the wasm client message
+pub const WASM_CLIENT_MESSAGE_TYPE_URL: &str =
+    "/ibc.lightclients.wasm.v1.ClientMessage";
+
+/// Raw height of the wasm states
+#[derive(Clone, PartialEq, Message)]
+pub struct RawHeight {
+    /// Revision number
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    /// Revision height
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+/// Raw wasm client state
+#[derive(Clone, PartialEq, Message)]
+pub struct RawClientState {
+    /// Opaque data for the light client code
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+    /// Checksum of the light client code
+    #[prost(bytes = "vec", tag = "2")]
+    pub checksum: Vec<u8>,
+    /// Latest height of the client
+    #[prost(message, optional, tag = "3")]
+    pub latest_height: Option<RawHeight>,
+    /// Height of the misbehaviour if the client is frozen
+    #[prost(message, optional, tag = "4")]
+    pub frozen_height: Option<RawHeight>,
+}
+
+/// Raw wasm consensus state
+#[derive(Clone, PartialEq, Message)]
+pub struct RawConsensusState {
+    /// Opaque data for the light client code
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+    /// Timestamp in nanoseconds
+    #[prost(uint64, tag = "2")]
+    pub timestamp: u64,
+    /// Commitment root
+    #[prost(bytes = "vec", tag = "3")]
+    pub root: Vec<u8>,
+}
+
+/// Raw wasm client message
+#[derive(Clone, PartialEq, Message)]
+pub struct RawClientMessage {
+    /// Opaque header or misbehaviour for the light client code
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+    /// The new height of the header
+    #[prost(message, optional, tag = "2")]
+    pub height: Option<RawHeight>,
+    /// The new consensus state of the header
+    #[prost(message, optional, tag = "3")]
+    pub consensus_state: Option<RawConsensusState>,
+}
+
+/// Wasm client state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientState {
+    /// Opaque data for the light client code
+    pub data: Vec<u8>,
+    /// Checksum of the light client code
+    pub checksum: Hash,
+    /// Latest height of the client
+    pub latest_height: Height,
+    /// Height of the misbehaviour if the client is frozen
+    pub frozen_height: Option<Height>,
+}
+
+/// Wasm consensus state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsensusState {
+    /// Opaque data for the light client code
+    pub data: Vec<u8>,
+    /// Timestamp of the consensus state
+    pub timestamp: Timestamp,
+    /// Commitment root
+    pub root: CommitmentRoot,
+}
+
+/// Wasm client message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientMessage {
+    /// Opaque header or misbehaviour for the light client code
+    pub data: Vec<u8>,
+    /// The new height and consensus state of a header. `None` for
+    /// misbehaviour.
+    pub update: Option<(Height, ConsensusState)>,
+}
+
+impl ClientMessage {
+    /// Check if the message is misbehaviour
+    pub fn is_misbehaviour(&self) -> bool {
+        self.update.is_none()
+    }
+}
+
+fn height_from_raw(raw: Option<RawHeight>) -> Result<Height, ClientError> {
+    let raw = raw.ok_or_else(|| ClientError::Other {
+        description: "No height in the wasm client".to_string(),
+    })?;
+    Height::new(raw.revision_number, raw.revision_height)
+}
+
+impl From<Height> for RawHeight {
+    fn from(height: Height) -> Self {
+        Self {
+            revision_number: height.revision_number(),
+            revision_height: height.revision_height(),
+        }
+    }
+}
+
+impl TryFrom<RawClientState> for ClientState {
+    type Error = ClientError;
+
+    fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+        let checksum = Hash::try_from(&raw.checksum[..]).map_err(|e| {
+            ClientError::Other {
+                description: format!("Invalid wasm client checksum: {e}"),
+            }
+        })?;
+        let frozen_height = raw
+            .frozen_height
+            .map(|height| height_from_raw(Some(height)))
+            .transpose()?;
+        Ok(Self {
+            data: raw.data,
+            checksum,
+            latest_height: height_from_raw(raw.latest_height)?,
+            frozen_height,
+        })
+    }
+}
+
+impl From<ClientState> for RawClientState {
+    fn from(cs: ClientState) -> Self {
+        Self {
+            data: cs.data,
+            checksum: cs.checksum.0.to_vec(),
+            latest_height: Some(cs.latest_height.into()),
+            frozen_height: cs.frozen_height.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+    type Error = ClientError;
+
+    fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+        let timestamp =
+            Timestamp::from_nanoseconds(raw.timestamp).map_err(|e| {
+                ClientError::Other {
+                    description: format!("Invalid wasm timestamp: {e}"),
+                }
+            })?;
+        Ok(Self {
+            data: raw.data,
+            timestamp,
+            root: CommitmentRoot::from_bytes(&raw.root),
+        })
+    }
+}
+
+impl From<ConsensusState> for RawConsensusState {
+    fn from(cs: ConsensusState) -> Self {
+        Self {
+            data: cs.data,
+            timestamp: cs.timestamp.nanoseconds(),
+            root: cs.root.into_vec(),
+        }
+    }
+}
+
+impl TryFrom<RawClientMessage> for ClientMessage {
+    type Error = ClientError;
+
+    fn try_from(raw: RawClientMessage) -> Result<Self, Self::Error> {
+        let update = match raw.consensus_state {
+            Some(cs) => Some((height_from_raw(raw.height)?, cs.try_into()?)),
+            None => None,
+        };
+        Ok(Self {
+            data: raw.data,
+            update,
+        })
+    }
+}
+
+impl From<ClientMessage> for RawClientMessage {
+    fn from(msg: ClientMessage) -> Self {
+        let (height, consensus_state) = match msg.update {
+            Some((height, cs)) => (Some(height.into()), Some(cs.into())),
+            None => (None, None),
+        };
+        Self {
+            data: msg.data,
+            height,
+            consensus_state,
+        }
+    }
+}
+
+macro_rules! impl_any_conversion {
+    ($domain:ty, $raw:ty, $type_url:expr) => {
+        impl TryFrom<Any> for $domain {
+            type Error = ClientError;
+
+            fn try_from(any: Any) -> Result<Self, Self::Error> {
+                if any.type_url != $type_url {
+                    return Err(ClientError::Other {
+                        description: format!(
+                            "Unexpected type URL {} for {}",
+                            any.type_url, $type_url
+                        ),
+                    });
+                }
+                <$raw>::decode(&any.value[..])
+                    .map_err(|e| ClientError::Other {
+                        description: e.to_string(),
+                    })?
+                    .try_into()
+            }
+        }
+
+        impl From<$domain> for Any {
+            fn from(domain: $domain) -> Self {
+                Any {
+                    type_url: $type_url.to_string(),
+                    value: <$raw>::from(domain).encode_to_vec(),
+                }
+            }
+        }
+    };
+}
+
+impl_any_conversion!(ClientState, RawClientState, WASM_CLIENT_STATE_TYPE_URL);
+impl_any_conversion!(
+    ConsensusState,
+    RawConsensusState,
+    WASM_CONSENSUS_STATE_TYPE_URL
+);
+impl_any_conversion!(
+    ClientMessage,
+    RawClientMessage,
+    WASM_CLIENT_MESSAGE_TYPE_URL
+);
+
+/// A request to the light client code. It's given to the code as the tx data
+/// and the code accepts the tx only when the request is valid. The states and
+/// the messages are the protobuf-encoded `Any`.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub enum WasmLightClientRequest {
+    /// Verify a header or misbehaviour
+    VerifyClientMessage {
+        /// The client state
+        client_state: Vec<u8>,
+        /// The consensus state at the latest height of the client
+        consensus_state: Vec<u8>,
+        /// The client message
+        client_message: Vec<u8>,
+    },
+    /// Verify the membership of the value at the path
+    VerifyMembership {
+        /// The client state
+        client_state: Vec<u8>,
+        /// The commitment prefix
+        prefix: Vec<u8>,
+        /// The proof
+        proof: Vec<u8>,
+        /// The commitment root
+        root: Vec<u8>,
+        /// The path
+        path: String,
+        /// The value
+        value: Vec<u8>,
+    },
+    /// Verify the non-membership at the path
+    VerifyNonMembership {
+        /// The client state
+        client_state: Vec<u8>,
+        /// The commitment prefix
+        prefix: Vec<u8>,
+        /// The proof
+        proof: Vec<u8>,
+        /// The commitment root
+        root: Vec<u8>,
+        /// The path
+        path: String,
+    },
+    /// Verify the upgraded client and consensus states
+    VerifyUpgrade {
+        /// The client state
+        client_state: Vec<u8>,
+        /// The upgraded client state
+        upgraded_client_state: Vec<u8>,
+        /// The upgraded consensus state
+        upgraded_consensus_state: Vec<u8>,
+        /// The proof of the upgraded client state
+        proof_upgrade_client: Vec<u8>,
+        /// The proof of the upgraded consensus state
+        proof_upgrade_consensus_state: Vec<u8>,
+        /// The commitment root
+        root: Vec<u8>,
+    },
+    /// Check if the substitute client can replace the client
+    CheckSubstitute {
+        /// The client state
+        client_state: Vec<u8>,
+        /// The substitute client state
+        substitute_client_state: Vec<u8>,
+    },
+}
+
+/// A request to be evaluated by the light client code of the checksum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmLightClientCall {
+    /// Checksum of the light client code
+    pub checksum: Hash,
+    /// The request
+    pub request: WasmLightClientRequest,
+}
+
+/// The light client calls requested while validating an IBC message. They're
+/// evaluated by the IBC VP after the validation.
+pub type PendingLightClientCalls = Rc<RefCell<Vec<WasmLightClientCall>>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_states_conversion() {
+        let client_state = ClientState {
+            data: vec![1, 2, 3],
+            checksum: Hash::sha256(b"light_client.wasm"),
+            latest_height: Height::new(1, 10).unwrap(),
+            frozen_height: None,
+        };
+        let any = Any::from(client_state.clone());
+        assert_eq!(any.type_url, WASM_CLIENT_STATE_TYPE_URL);
+        assert_eq!(ClientState::try_from(any).unwrap(), client_state);
+
+        let consensus_state = ConsensusState {
+            data: vec![4, 5],
+            timestamp: Timestamp::from_nanoseconds(1_000).unwrap(),
+            root: CommitmentRoot::from_bytes(&[6; 32]),
+        };
+        let any = Any::from(consensus_state.clone());
+        assert_eq!(ConsensusState::try_from(any).unwrap(), consensus_state);
+
+        let header = ClientMessage {
+            data: vec![7],
+            update: Some((Height::new(1, 11).unwrap(), consensus_state)),
+        };
+        let any = Any::from(header.clone());
+        let decoded = ClientMessage::try_from(any).unwrap();
+        assert_eq!(decoded, header);
+        assert!(!decoded.is_misbehaviour());
+
+        let misbehaviour = ClientMessage {
+            data: vec![8],
+            update: None,
+        };
+        let decoded = ClientMessage::try_from(Any::from(misbehaviour)).unwrap();
+        assert!(decoded.is_misbehaviour());
+
+        // A client state can't be decoded as a consensus state
+        let any = Any::from(client_state);
+        assert!(ConsensusState::try_from(any).is_err());
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use namada_sdk::account::ActionClass;
 use namada_sdk::address::Address;
 use namada_sdk::hash::Hash;
 use namada_sdk::key::common;
@@ -175,6 +178,8 @@ impl UpdateAccount {
         vp_code_hash: Option<Hash>,
         public_keys: Vec<common::PublicKey>,
         threshold: Option<u8>,
+        weights: Vec<u8>,
+        action_thresholds: BTreeMap<ActionClass, u8>,
         args: GlobalArgs,
     ) -> Self {
         let update_account = namada_sdk::account::UpdateAccount {
@@ -182,6 +187,8 @@ impl UpdateAccount {
             vp_code_hash,
             public_keys,
             threshold,
+            weights,
+            action_thresholds,
         };
 
         Self(transaction::build_tx(
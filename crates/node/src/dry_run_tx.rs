@@ -7,7 +7,7 @@ use namada_sdk::gas::{GasMetering, TxGasMeter};
 use namada_sdk::parameters;
 use namada_sdk::queries::{EncodedResponseQuery, RequestQuery};
 use namada_sdk::state::{
-    DBIter, Result, ResultExt, StorageHasher, TxIndex, DB,
+    DBIter, Result, ResultExt, StateRead, StorageHasher, TxIndex, DB,
 };
 use namada_sdk::tx::data::{DryRunResult, GasLimit, TxResult, TxType};
 use namada_sdk::tx::Tx;
@@ -42,7 +42,10 @@ where
                     .gas_limit
                     .as_scaled_gas(gas_scale)
                     .into_storage_result()?;
-                let tx_gas_meter = RefCell::new(TxGasMeter::new(gas_limit));
+                let tx_gas_meter = RefCell::new(TxGasMeter::new_with_costs(
+                    gas_limit,
+                    state.in_mem().gas_cost_schedule,
+                ));
                 let mut shell_params = ShellParams::new(
                     &tx_gas_meter,
                     &mut state,
@@ -73,7 +76,10 @@ where
                 (
                     None,
                     TxResult::default().to_extended_result(None),
-                    RefCell::new(TxGasMeter::new(gas_limit)),
+                    RefCell::new(TxGasMeter::new_with_costs(
+                        gas_limit,
+                        state.in_mem().gas_cost_schedule,
+                    )),
                 )
             }
         };
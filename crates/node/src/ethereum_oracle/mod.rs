@@ -1,5 +1,6 @@
 pub mod control;
 pub mod events;
+pub mod replay;
 pub mod test_tools;
 
 use std::ops::ControlFlow;
@@ -277,7 +278,7 @@ async fn await_initial_configuration(
 
 /// Set up an Oracle and run the process where the Oracle
 /// processes and forwards Ethereum events to the ledger
-pub fn run_oracle<C: RpcClient>(
+pub fn run_oracle<C: RpcClient + Send + 'static>(
     url: impl AsRef<str>,
     sender: BoundedSender<EthereumEvent>,
     control: control::Receiver,
@@ -285,19 +286,59 @@ pub fn run_oracle<C: RpcClient>(
     spawner: &mut AbortableSpawner,
 ) {
     let url = url.as_ref().to_owned();
+    spawn_oracle::<C>(
+        Either::Right(url),
+        sender,
+        control,
+        last_processed_block,
+        spawner,
+    )
+}
+
+/// Set up an Oracle replaying recorded Ethereum event logs and run
+/// the process where the Oracle forwards them to the ledger
+pub fn run_replay_oracle(
+    client: replay::ReplayClient,
+    sender: BoundedSender<EthereumEvent>,
+    control: control::Receiver,
+    last_processed_block: last_processed_block::Sender,
+    spawner: &mut AbortableSpawner,
+) {
+    spawn_oracle(
+        Either::Left(client),
+        sender,
+        control,
+        last_processed_block,
+        spawner,
+    )
+}
+
+/// Spawn the Oracle process with the given client, or with a new
+/// client pointing to the given url
+fn spawn_oracle<C: RpcClient + Send + 'static>(
+    client_or_url: Either<C, String>,
+    sender: BoundedSender<EthereumEvent>,
+    control: control::Receiver,
+    last_processed_block: last_processed_block::Sender,
+    spawner: &mut AbortableSpawner,
+) {
     spawner
         .abortable("Ethereum Oracle", move |aborter| {
             let rt = tokio::runtime::Handle::current();
             rt.block_on(async move {
                 LocalSet::new()
                     .run_until(async move {
+                        let url = client_or_url.as_ref().right().cloned();
                         tracing::info!(
                             ?url,
                             "Ethereum event oracle is starting"
                         );
 
+                        let client = client_or_url
+                            .map_right(|url| C::new_client(&url))
+                            .into_inner();
                         let oracle = Oracle::<C>::new(
-                            Either::Right(&url),
+                            Either::Left(client),
                             sender,
                             last_processed_block,
                             DEFAULT_BACKOFF,
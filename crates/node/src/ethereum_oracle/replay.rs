@@ -0,0 +1,363 @@
+//! Replay of recorded Ethereum event logs.
+//!
+//! The [`ReplayClient`] serves the logs recorded from an Ethereum
+//! full node to the oracle, instead of querying a live JSON-RPC
+//! endpoint. This allows reproducing bridge incidents and running
+//! end-to-end bridge tests offline.
+//!
+//! A recording is either a single file or a directory of files, which
+//! are read in lexicographical order of their names. Files with a
+//! `.cbor` extension are decoded as CBOR, and any other file as JSON.
+//! Each file holds either a list of logs, as returned by `eth_getLogs`,
+//! or a block dump with the number of the block and its logs.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use ethabi::Address;
+use namada_sdk::control_flow::time::{Duration, Instant};
+use namada_sdk::eth_bridge::ethers::types::{Log, H256, U64};
+use namada_sdk::eth_bridge::ethers::utils::keccak256;
+use namada_sdk::eth_bridge::SyncStatus;
+use namada_sdk::ethereum_structs;
+use num256::Uint256;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{Error, RpcClient};
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("Couldn't read the recorded Ethereum logs at {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Couldn't decode the recorded Ethereum logs at {0}: {1}")]
+    Decode(PathBuf, String),
+    #[error("A recorded Ethereum log at {0} has no block number")]
+    MissingBlockNumber(PathBuf),
+}
+
+/// The contents of a recorded file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Recording {
+    /// Logs spanning any number of blocks.
+    Logs(Vec<Log>),
+    /// The logs of a single block.
+    Block(BlockDump),
+}
+
+/// A dump of the logs emitted in an Ethereum block.
+#[derive(Deserialize)]
+struct BlockDump {
+    /// The number of the block.
+    number: U64,
+    /// The logs emitted in the block.
+    #[serde(default)]
+    logs: Vec<Log>,
+}
+
+/// An [`RpcClient`] replaying recorded Ethereum logs.
+#[derive(Debug, Clone)]
+pub struct ReplayClient {
+    /// The recorded logs, indexed by block number and ordered by
+    /// their index in the block.
+    logs: BTreeMap<u64, Vec<Log>>,
+    /// The height of the Ethereum chain reported to the oracle.
+    latest_block: u64,
+}
+
+impl ReplayClient {
+    /// Load the logs recorded at the given path. The Ethereum chain is
+    /// reported to be `confirmations` blocks past the last recorded
+    /// block, such that replays are deterministic.
+    pub fn load(
+        path: impl AsRef<Path>,
+        confirmations: u64,
+    ) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            let mut files = std::fs::read_dir(path)
+                .and_then(|entries| {
+                    entries
+                        .map(|entry| entry.map(|entry| entry.path()))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .map_err(|error| ReplayError::Read(path.to_owned(), error))?;
+            files.retain(|file| file.is_file());
+            files.sort();
+            files
+        } else {
+            vec![path.to_owned()]
+        };
+
+        let mut logs: BTreeMap<u64, Vec<Log>> = BTreeMap::new();
+        let mut last_block = 0;
+        for file in files {
+            let (number, recorded) = match read_recording(&file)? {
+                Recording::Logs(logs) => (None, logs),
+                Recording::Block(BlockDump { number, logs }) => {
+                    (Some(number), logs)
+                }
+            };
+            if let Some(number) = number {
+                last_block = last_block.max(number.as_u64());
+            }
+            // logs removed by a chain reorganization are dropped
+            for log in
+                recorded.into_iter().filter(|log| log.removed != Some(true))
+            {
+                let block = log
+                    .block_number
+                    .or(number)
+                    .ok_or_else(|| {
+                        ReplayError::MissingBlockNumber(file.clone())
+                    })?
+                    .as_u64();
+                last_block = last_block.max(block);
+                logs.entry(block).or_default().push(log);
+            }
+        }
+        for block_logs in logs.values_mut() {
+            block_logs.sort_by_key(|log| log.log_index);
+        }
+
+        Ok(Self {
+            logs,
+            latest_block: last_block.saturating_add(confirmations),
+        })
+    }
+
+    /// Get the height of the Ethereum chain reported to the oracle.
+    pub fn latest_block(&self) -> u64 {
+        self.latest_block
+    }
+}
+
+/// Decode a recorded file.
+fn read_recording(file: &Path) -> Result<Recording, ReplayError> {
+    let bytes = std::fs::read(file)
+        .map_err(|error| ReplayError::Read(file.to_owned(), error))?;
+    let is_cbor = file.extension().is_some_and(|ext| ext == "cbor");
+    if is_cbor {
+        ciborium::from_reader(bytes.as_slice()).map_err(|error| {
+            ReplayError::Decode(file.to_owned(), error.to_string())
+        })
+    } else {
+        serde_json::from_slice(&bytes).map_err(|error| {
+            ReplayError::Decode(file.to_owned(), error.to_string())
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RpcClient for ReplayClient {
+    type Log = Log;
+
+    #[inline]
+    fn new_client(path: &str) -> Self
+    where
+        Self: Sized,
+    {
+        Self::load(path, 0).expect("Invalid recorded Ethereum logs")
+    }
+
+    async fn check_events_in_block(
+        &self,
+        block: ethereum_structs::BlockHeight,
+        address: Address,
+        abi_signature: &str,
+    ) -> Result<Vec<Self::Log>, Error> {
+        let height = {
+            let n: Uint256 = block.into();
+            let n: u64 =
+                n.0.try_into().expect("Ethereum block number overflow");
+            n
+        };
+        let signature = H256::from(keccak256(abi_signature.as_bytes()));
+        Ok(self
+            .logs
+            .get(&height)
+            .map(|logs| {
+                logs.iter()
+                    .filter(|log| {
+                        log.address == address
+                            && log.topics.first() == Some(&signature)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn syncing(
+        &self,
+        _: Option<&ethereum_structs::BlockHeight>,
+        _: Duration,
+        _: Instant,
+    ) -> Result<SyncStatus, Error> {
+        Ok(SyncStatus::AtHeight(Uint256::from(self.latest_block)))
+    }
+
+    #[inline(always)]
+    fn may_recover(&self, error: &Error) -> bool {
+        !matches!(error, Error::Channel(_, _))
+    }
+}
+
+#[cfg(test)]
+mod test_replay {
+    use namada_sdk::eth_bridge::ethers::types::U256;
+
+    use super::*;
+
+    /// Make a log of the given event in a block.
+    fn log(
+        address: Address,
+        abi_signature: &str,
+        block: Option<u64>,
+        index: u64,
+    ) -> Log {
+        Log {
+            address,
+            topics: vec![H256::from(keccak256(abi_signature.as_bytes()))],
+            block_number: block.map(U64::from),
+            log_index: Some(U256::from(index)),
+            ..Default::default()
+        }
+    }
+
+    /// Check the logs of a block returned by the client.
+    async fn events_in_block(
+        client: &ReplayClient,
+        block: u64,
+        address: Address,
+        abi_signature: &str,
+    ) -> Vec<Log> {
+        client
+            .check_events_in_block(block.into(), address, abi_signature)
+            .await
+            .unwrap()
+    }
+
+    /// Test replaying logs recorded in a JSON file
+    #[tokio::test]
+    async fn test_replay_json_file() {
+        let bridge = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        let first = log(bridge, "TransferToChain()", Some(3), 1);
+        let second = log(bridge, "TransferToChain()", Some(3), 0);
+        let reorged = Log {
+            removed: Some(true),
+            ..log(bridge, "TransferToChain()", Some(3), 2)
+        };
+        let logs = vec![
+            first.clone(),
+            second.clone(),
+            reorged,
+            log(other, "TransferToChain()", Some(5), 0),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.json");
+        std::fs::write(&path, serde_json::to_vec(&logs).unwrap()).unwrap();
+
+        let client = ReplayClient::load(&path, 10).unwrap();
+        assert_eq!(client.latest_block(), 15);
+        let status = client
+            .syncing(None, Duration::from_secs(1), Instant::now())
+            .await
+            .unwrap();
+        assert!(
+            matches!(status, SyncStatus::AtHeight(h) if h == Uint256::from(15u64))
+        );
+
+        // the logs are ordered by their index in the block
+        assert_eq!(
+            events_in_block(&client, 3, bridge, "TransferToChain()").await,
+            vec![second, first]
+        );
+        assert!(
+            events_in_block(&client, 3, bridge, "TransferToErc()")
+                .await
+                .is_empty()
+        );
+        assert!(
+            events_in_block(&client, 5, bridge, "TransferToChain()")
+                .await
+                .is_empty()
+        );
+        assert!(
+            events_in_block(&client, 4, bridge, "TransferToChain()")
+                .await
+                .is_empty()
+        );
+    }
+
+    /// Test replaying a directory of JSON and CBOR block dumps
+    #[tokio::test]
+    async fn test_replay_block_dumps() {
+        #[derive(serde::Serialize)]
+        struct Dump {
+            number: U64,
+            logs: Vec<Log>,
+        }
+
+        let bridge = Address::repeat_byte(1);
+        let dir = tempfile::tempdir().unwrap();
+        let first = log(bridge, "TransferToChain()", None, 0);
+        std::fs::write(
+            dir.path().join("000001.json"),
+            serde_json::to_vec(&Dump {
+                number: 1.into(),
+                logs: vec![first.clone()],
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let second = log(bridge, "TransferToChain()", Some(2), 0);
+        let mut cbor = vec![];
+        ciborium::into_writer(
+            &Dump {
+                number: 2.into(),
+                logs: vec![second.clone()],
+            },
+            &mut cbor,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("000002.cbor"), cbor).unwrap();
+        // a block without any logs
+        std::fs::write(
+            dir.path().join("000003.json"),
+            r#"{"number":"0x4","logs":[]}"#,
+        )
+        .unwrap();
+
+        let client = ReplayClient::load(dir.path(), 0).unwrap();
+        assert_eq!(client.latest_block(), 4);
+        assert_eq!(
+            events_in_block(&client, 1, bridge, "TransferToChain()").await,
+            vec![first]
+        );
+        assert_eq!(
+            events_in_block(&client, 2, bridge, "TransferToChain()").await,
+            vec![second]
+        );
+
+        // logs must be attributed to a block
+        std::fs::write(
+            dir.path().join("000004.json"),
+            serde_json::to_vec(&vec![log(
+                bridge,
+                "TransferToChain()",
+                None,
+                0,
+            )])
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            ReplayClient::load(dir.path(), 0),
+            Err(ReplayError::MissingBlockNumber(_))
+        ));
+    }
+}
@@ -28,7 +28,7 @@ pub mod utils;
 
 use std::convert::TryInto;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use byte_unit::Byte;
 use data_encoding::HEXUPPER;
@@ -261,6 +261,38 @@ pub fn migrating_state() -> Option<BlockHeight> {
     height.parse::<u64>().ok().map(BlockHeight)
 }
 
+/// The file in the chain dir that holds the initial height of CometBFT's
+/// chain, while its state is bootstrapped from a DB restored from a snapshot.
+const BOOTSTRAP_HEIGHT_FILE: &str = "cometbft-bootstrap-height";
+
+/// Determine if CometBFT's state is being bootstrapped from a DB restored from
+/// a snapshot. Returns the initial height of CometBFT's chain.
+pub fn bootstrapping_state(chain_dir: &Path) -> Option<BlockHeight> {
+    let height =
+        std::fs::read_to_string(chain_dir.join(BOOTSTRAP_HEIGHT_FILE)).ok()?;
+    height.parse::<u64>().ok().map(BlockHeight)
+}
+
+/// Bootstrap CometBFT's state from the DB on the next start of the ledger,
+/// such that its chain resumes at the given initial height.
+fn start_bootstrapping_state(
+    chain_dir: &Path,
+    initial_height: BlockHeight,
+) -> std::io::Result<()> {
+    std::fs::write(
+        chain_dir.join(BOOTSTRAP_HEIGHT_FILE),
+        initial_height.0.to_string(),
+    )
+}
+
+/// Mark CometBFT's state as bootstrapped, once it was initialized from the DB.
+fn finish_bootstrapping_state(chain_dir: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(chain_dir.join(BOOTSTRAP_HEIGHT_FILE)) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
 /// Emit a header of warning log msgs if the host does not have
 /// a 64-bit CPU.
 fn emit_warning_on_non_64bit_cpu() {
@@ -287,8 +319,9 @@ pub fn run(
     config: config::Ledger,
     wasm_dir: PathBuf,
     scheduled_migration: Option<ScheduledMigration>,
-) {
+) -> Result<(), shell::Error> {
     emit_warning_on_non_64bit_cpu();
+    storage::check_storage_backend(config.shell.storage_backend)?;
 
     let logical_cores = num_cpus::get();
     tracing::info!("Available logical cores: {}", logical_cores);
@@ -324,6 +357,7 @@ pub fn run(
         .build()
         .unwrap()
         .block_on(run_aux(config, wasm_dir, scheduled_migration));
+    Ok(())
 }
 
 /// Resets the tendermint_node state and removes database files
@@ -339,12 +373,57 @@ pub fn dump_db(
         out_file_path,
         historic,
     }: args::LedgerDumpDb,
-) {
+) -> Result<(), shell::Error> {
     let chain_id = config.chain_id;
     let db_path = config.shell.db_dir(&chain_id);
 
+    storage::check_storage_backend(config.shell.storage_backend)?;
     let db = storage::PersistentDB::open(db_path, None);
     db.dump_block(out_file_path, historic, block_height);
+    Ok(())
+}
+
+/// Export a state sync snapshot of Namada ledger node's DB into a directory
+pub fn export_snapshot(
+    config: config::Ledger,
+    args::SnapshotExport {
+        block_height,
+        out_dir,
+    }: args::SnapshotExport,
+) -> Result<(), shell::Error> {
+    let out_dir = out_dir.unwrap_or_else(|| {
+        let height = block_height
+            .map(|height| height.to_string())
+            .unwrap_or_else(|| "latest".to_string());
+        PathBuf::from(format!("snapshot-{height}"))
+    });
+    let meta = shell::export_snapshot(config, block_height, &out_dir)?;
+    tracing::info!(
+        "Exported the snapshot at height {} to {}. Its root hash is {}.",
+        meta.height,
+        out_dir.to_string_lossy(),
+        meta.root_hash
+    );
+    Ok(())
+}
+
+/// Restore Namada ledger node's DB from an exported state sync snapshot
+pub fn import_snapshot(
+    config: config::Ledger,
+    args::SnapshotImport {
+        snapshot_dir,
+        root_hash,
+    }: args::SnapshotImport,
+) -> Result<(), shell::Error> {
+    let meta = shell::import_snapshot(config, &snapshot_dir, root_hash)?;
+    tracing::info!(
+        "Restored the DB from the snapshot at height {} with root hash {}. \
+         CometBFT's state will be bootstrapped at this height on the next \
+         start of the ledger.",
+        meta.height,
+        meta.root_hash
+    );
+    Ok(())
 }
 
 #[cfg(feature = "migrations")]
@@ -353,13 +432,14 @@ pub fn query_db(
     key: &namada_sdk::storage::Key,
     type_hash: &[u8; 32],
     cf: &DbColFam,
-) {
+) -> Result<(), shell::Error> {
     use namada_sdk::migrations::DBUpdateVisitor;
     let chain_id = config.chain_id;
     let db_path = config.shell.db_dir(&chain_id);
 
+    storage::check_storage_backend(config.shell.storage_backend)?;
     let db = storage::PersistentDB::open(db_path, None);
-    let db_visitor = storage::RocksDBUpdateVisitor::new(&db);
+    let db_visitor = storage::PersistentDBUpdateVisitor::new(&db);
     let bytes = db_visitor.read(key, cf).unwrap();
 
     let deserializer = namada_migrations::get_deserializer(type_hash)
@@ -380,15 +460,22 @@ pub fn query_db(
         value,
         hex_bytes
     );
+    Ok(())
 }
 
 /// Change the funds of an account in-place. Use with
 /// caution, as this modifies state in storage without
 /// going through the consensus protocol.
 #[cfg(feature = "migrations")]
-pub fn update_db_keys(config: config::Ledger, updates: PathBuf, dry_run: bool) {
+pub fn update_db_keys(
+    config: config::Ledger,
+    updates: PathBuf,
+    dry_run: bool,
+) -> Result<(), shell::Error> {
     use std::io::Read;
 
+    storage::check_storage_backend(config.shell.storage_backend)?;
+
     let mut update_json = String::new();
     let mut file = std::fs::File::open(updates)
         .expect("Could not fine updates file at the specified path.");
@@ -413,6 +500,7 @@ pub fn update_db_keys(config: config::Ledger, updates: PathBuf, dry_run: bool) {
         tendermint_node::reset_state(cometbft_path)
             .expect("Failed to reset CometBFT state");
     }
+    Ok(())
 }
 
 /// Roll Namada state back to the previous height
@@ -546,7 +634,7 @@ async fn run_aux_setup(
             .get_appropriate_unit(true)
     );
 
-    // Find the RocksDB block cache size
+    // Find the DB block cache size
     let db_block_cache_size_bytes = match config.shell.block_cache_bytes {
         Some(block_cache_bytes) => {
             tracing::info!("Block cache set from the configuration.");
@@ -561,7 +649,7 @@ async fn run_aux_setup(
         }
     };
     tracing::info!(
-        "RocksDB block cache size: {}",
+        "DB block cache size: {}",
         Byte::from_bytes(u128::from(db_block_cache_size_bytes))
             .get_appropriate_unit(true)
     );
@@ -626,7 +714,7 @@ fn start_abci_broadcaster_shell(
     }
 
     // Setup DB cache, it must outlive the DB instance that's in the shell
-    let db_cache = rocksdb::Cache::new_lru_cache(
+    let db_cache = storage::DbCache::new_lru_cache(
         usize::try_from(db_block_cache_size_bytes)
             .expect("`db_block_cache_size_bytes` must not exceed `usize::MAX`"),
     );
@@ -822,7 +910,7 @@ async fn maybe_start_ethereum_oracle(
         last_processed_block::channel();
     let (control_sender, control_receiver) = oracle::control::channel();
 
-    match config.ethereum_bridge.mode {
+    match &config.ethereum_bridge.mode {
         ethereum_bridge::ledger::Mode::RemoteEndpoint => {
             oracle::run_oracle::<Provider<Http>>(
                 ethereum_url,
@@ -892,6 +980,39 @@ async fn maybe_start_ethereum_oracle(
                 ),
             }
         }
+        ethereum_bridge::ledger::Mode::Replay {
+            source,
+            confirmations,
+        } => {
+            let client =
+                oracle::replay::ReplayClient::load(source, *confirmations)
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "Failed to load the recorded Ethereum event logs: \
+                             {error}"
+                        )
+                    });
+            tracing::info!(
+                ?source,
+                latest_block = client.latest_block(),
+                "Replaying the recorded Ethereum event logs"
+            );
+            oracle::run_replay_oracle(
+                client,
+                eth_sender,
+                control_receiver,
+                last_processed_block_sender,
+                spawner,
+            );
+
+            EthereumOracleTask::Enabled {
+                channels: EthereumOracleChannels::new(
+                    eth_receiver,
+                    control_sender,
+                    last_processed_block_receiver,
+                ),
+            }
+        }
         ethereum_bridge::ledger::Mode::Off => EthereumOracleTask::NotEnabled,
     }
 }
@@ -7,6 +7,7 @@ use either::Either;
 use eyre::{eyre, WrapErr};
 use namada_sdk::address::{Address, InternalAddress};
 use namada_sdk::booleans::BoolResultUnitExt;
+use namada_sdk::borsh::BorshSerializeExt;
 use namada_sdk::events::extend::{
     ComposeEvent, Height as HeightAttr, MaspTxRef, MaspTxRefs,
     TxHash as TxHashAttr, UserAccount,
@@ -15,6 +16,7 @@ use namada_sdk::events::EventLevel;
 use namada_sdk::gas::{self, Gas, GasMetering, TxGasMeter, VpGasMeter};
 use namada_sdk::hash::Hash;
 use namada_sdk::parameters::get_gas_scale;
+use namada_sdk::state::write_log::{BatchWriteLog, ReadSet};
 use namada_sdk::state::{
     DBIter, State, StorageHasher, StorageRead, TxWrites, WlState, DB,
 };
@@ -25,15 +27,15 @@ use namada_sdk::token::Amount;
 use namada_sdk::tx::action::{self, Read};
 use namada_sdk::tx::data::protocol::{ProtocolTx, ProtocolTxType};
 use namada_sdk::tx::data::{
-    BatchedTxResult, ExtendedTxResult, TxResult, VpStatusFlags, VpsResult,
-    WrapperTx,
+    BatchedTxResult, ExtendedTxResult, TxResult, TxType, VpStatusFlags,
+    VpsResult, WrapperTx,
 };
-use namada_sdk::tx::{BatchedTxRef, Tx, TxCommitments};
+use namada_sdk::tx::{BatchedTxRef, Section, Tx, TxCommitments};
 use namada_sdk::validation::{
-    EthBridgeNutVp, EthBridgePoolVp, EthBridgeVp, GovernanceVp, IbcVp, MaspVp,
-    MultitokenVp, NativeVpCtx, ParametersVp, PgfVp, PosVp,
+    EthBridgeNutVp, EthBridgePoolVp, EthBridgeVp, GovernanceVp, IbcVp, IcaVp,
+    MaspVp, MultitokenVp, NativeVpCtx, ParametersVp, PgfVp, PosVp,
 };
-use namada_sdk::{governance, parameters, state, storage, token};
+use namada_sdk::{governance, ibc, parameters, state, storage, token};
 use namada_vm::wasm::{TxCache, VpCache};
 use namada_vm::{self, wasm, WasmCacheAccess};
 use namada_vote_ext::EthereumTxData;
@@ -341,8 +343,7 @@ pub(crate) fn dispatch_inner_txs<'a, S, D, H, CA>(
     tx_wasm_cache: &'a mut TxCache<CA>,
 ) -> std::result::Result<ExtendedTxResult<Error>, DispatchError>
 where
-    S: 'static
-        + State<D = D, H = H>
+    S: State<D = D, H = H>
         + Read<Err = state::Error>
         + ReadConversionState
         + Sync,
@@ -421,6 +422,67 @@ where
     Ok(extended_tx_result)
 }
 
+/// The outcome of the speculative execution of a batch of inner transactions
+pub(crate) struct SpeculativeBatch {
+    /// The result of the execution, not including the entries of the wrapper
+    pub result: std::result::Result<ExtendedTxResult<Error>, DispatchError>,
+    /// The gas meter of the wrapper after the execution of the batch
+    pub tx_gas_meter: TxGasMeter,
+    /// The storage modifications of the batch
+    pub batch_write_log: BatchWriteLog,
+    /// The storage keys and prefixes read by the batch, including the reads
+    /// of the validity predicates
+    pub read_set: ReadSet,
+}
+
+/// Execute the inner transactions of a batch against a speculative copy of the
+/// block write log. The execution doesn't observe the batches that precede
+/// this one in the block, so the outcome can only be applied if none of the
+/// keys in the returned read set has been modified by those batches in the
+/// meantime.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn speculate_inner_txs<D, H, CA>(
+    tx: &Tx,
+    wrapper_hash: &Hash,
+    masp_tx_refs: MaspTxRefs,
+    tx_index: TxIndex,
+    tx_gas_meter: TxGasMeter,
+    state: &WlState<D, H>,
+    mut vp_wasm_cache: VpCache<CA>,
+    mut tx_wasm_cache: TxCache<CA>,
+) -> SpeculativeBatch
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    CA: 'static + WasmCacheAccess + Sync,
+{
+    let mut temp_state = state.with_speculative_write_log();
+    let tx_gas_meter = RefCell::new(tx_gas_meter);
+    let result = dispatch_inner_txs(
+        tx,
+        Some(wrapper_hash),
+        ExtendedTxResult {
+            tx_result: TxResult::new(),
+            masp_tx_refs,
+        },
+        tx_index,
+        &tx_gas_meter,
+        &mut temp_state,
+        &mut vp_wasm_cache,
+        &mut tx_wasm_cache,
+    );
+
+    let write_log = temp_state.write_log_mut();
+    SpeculativeBatch {
+        result,
+        tx_gas_meter: tx_gas_meter.into_inner(),
+        batch_write_log: write_log.take_batch(),
+        read_set: write_log
+            .take_read_set()
+            .expect("Speculative write log must track the reads"),
+    }
+}
+
 /// Transaction result for masp transfer
 pub struct MaspTxResult {
     tx_result: BatchedTxResult,
@@ -533,11 +595,27 @@ where
             )
             .map_err(Error::Error)?;
 
+            let sponsorship = wrapper
+                .fee_sponsor
+                .as_ref()
+                .map(|sponsor| {
+                    check_fee_sponsorship(
+                        shell_params.state,
+                        tx,
+                        wrapper,
+                        sponsor,
+                        fees,
+                    )
+                    .map(|sponsorship| (sponsor, sponsorship))
+                })
+                .transpose()?;
+            let fee_source = wrapper.fee_source();
+
             #[cfg(not(fuzzing))]
             let balance = token::read_balance(
                 shell_params.state,
                 &wrapper.fee.token,
-                &wrapper.fee_payer(),
+                &fee_source,
             )
             .map_err(Error::Error)?;
 
@@ -552,12 +630,33 @@ where
                 fee_token_transfer(
                     shell_params.state,
                     &wrapper.fee.token,
-                    &wrapper.fee_payer(),
+                    &fee_source,
                     block_proposer,
                     fees,
                 )?;
+                // Debit the fees from the sponsor's allowance in the same
+                // write log, such that the txs in a block can't spend it twice
+                if let Some((sponsor, sponsorship)) = sponsorship {
+                    write_fee_sponsorship(
+                        shell_params.state,
+                        sponsor,
+                        sponsorship,
+                    )?;
+                }
 
                 (post_bal, None)
+            } else if wrapper.fee_sponsor.is_some() {
+                // Sponsored fees can only be paid by the sponsor. This
+                // shouldn't happen as it should be prevented by
+                // process_proposal.
+                tracing::error!(
+                    "Transfer of tx fee cannot be applied to due to \
+                     insufficient funds of the fee sponsor. This shouldn't \
+                     happen."
+                );
+                return Err(Error::FeeError(
+                    "Insufficient sponsor funds for fee payment".to_string(),
+                ));
             } else {
                 // See if the first inner transaction of the batch pays the fees
                 // with a masp unshield
@@ -640,7 +739,7 @@ where
                     descriptor: FEE_PAYMENT_DESCRIPTOR,
                     level: EventLevel::Tx,
                     operation: TokenOperation::transfer(
-                        UserAccount::Internal(wrapper.fee_payer()),
+                        UserAccount::Internal(fee_source),
                         UserAccount::Internal(block_proposer.clone()),
                         wrapper.fee.token.clone(),
                         fees.into(),
@@ -704,10 +803,11 @@ where
         .min(tx_gas_meter.borrow().get_available_gas().into());
     let gas_scale = get_gas_scale(&**state).map_err(Error::Error)?;
 
-    let masp_gas_meter = RefCell::new(TxGasMeter::new(
+    let masp_gas_meter = RefCell::new(TxGasMeter::new_with_costs(
         Gas::from_whole_units(max_gas_limit.into(), gas_scale).ok_or_else(
             || Error::GasError("Overflow in gas expansion".to_string()),
         )?,
+        *tx_gas_meter.borrow().gas_costs(),
     ));
 
     let valid_batched_tx_result = {
@@ -848,7 +948,71 @@ where
         })
 }
 
-/// Check if the fee payer has enough transparent balance to pay fees
+// Check that the sponsor of the wrapper tx pays its fees. Returns the
+// sponsorship with the fees debited from its allowance
+fn check_fee_sponsorship<S>(
+    state: &S,
+    tx: &Tx,
+    wrapper: &WrapperTx,
+    sponsor: &Address,
+    fees: Amount,
+) -> Result<token::FeeSponsorship>
+where
+    S: StorageRead,
+{
+    let sponsorship = token::read_fee_sponsorship(state, sponsor)
+        .map_err(Error::Error)?
+        .ok_or_else(|| {
+            Error::FeeError(format!("{sponsor} doesn't sponsor any fees"))
+        })?;
+    let code_hashes = tx
+        .commitments()
+        .iter()
+        .map(|cmt| {
+            tx.get_section(cmt.code_sechash())
+                .and_then(|section| Section::code_sec(&section))
+                .map(|code_sec| code_sec.code.hash())
+                .ok_or_else(|| {
+                    Error::MissingSection(cmt.code_sechash().to_string())
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let epoch = state.get_block_epoch().map_err(Error::Error)?;
+    sponsorship
+        .spend(
+            epoch,
+            &wrapper.fee.token,
+            &wrapper.fee_payer(),
+            code_hashes.iter(),
+            fees,
+        )
+        .map_err(Error::FeeError)
+}
+
+// Write the sponsorship debited for the fee payment. If an error is detected
+// the write log is dropped to prevent committing the fee transfer without it
+fn write_fee_sponsorship<WLS>(
+    state: &mut WLS,
+    sponsor: &Address,
+    sponsorship: token::FeeSponsorship,
+) -> Result<()>
+where
+    WLS: State + StorageRead + TxWrites,
+{
+    token::write_fee_sponsorship(
+        &mut state.with_tx_writes(),
+        sponsor,
+        sponsorship,
+    )
+    .map_err(|err| {
+        state.write_log_mut().drop_tx();
+
+        Error::Error(err)
+    })
+}
+
+/// Check if the fee payer, or the fee sponsor of the wrapper tx, has enough
+/// transparent balance to pay fees
 pub fn check_fees<S, D, H, CA>(
     shell_params: &mut ShellParams<'_, S, D, H, CA>,
     tx: &Tx,
@@ -874,6 +1038,33 @@ where
             )
             .map_err(Error::Error)?;
 
+            if let Some(sponsor) = &wrapper.fee_sponsor {
+                check_fee_sponsorship(
+                    shell_params.state,
+                    tx,
+                    wrapper,
+                    sponsor,
+                    fees,
+                )?;
+                let balance = token::read_balance(
+                    shell_params.state,
+                    &wrapper.fee.token,
+                    sponsor,
+                )
+                .map_err(Error::Error)?;
+
+                // Sponsored fees can't be paid via the MASP
+                return checked!(balance - fees).map_or_else(
+                    |_| {
+                        Err(Error::FeeError(
+                            "Insufficient sponsor funds for fee payment"
+                                .to_string(),
+                        ))
+                    },
+                    |_| Ok(None),
+                );
+            }
+
             let balance = token::read_balance(
                 shell_params.state,
                 &wrapper.fee.token,
@@ -930,7 +1121,10 @@ fn apply_wasm_tx<S, D, H, CA>(
     shell_params: ShellParams<'_, S, D, H, CA>,
 ) -> Result<BatchedTxResult>
 where
-    S: 'static + State<D = D, H = H> + ReadConversionState + Sync,
+    S: State<D = D, H = H>
+        + Read<Err = state::Error>
+        + ReadConversionState
+        + Sync,
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
@@ -942,7 +1136,7 @@ where
         tx_wasm_cache,
     } = shell_params;
 
-    let verifiers = execute_tx(
+    let mut verifiers = execute_tx(
         batched_tx,
         tx_index,
         state,
@@ -951,14 +1145,69 @@ where
         tx_wasm_cache,
     )?;
 
-    let vps_result = check_vps(CheckVps {
-        batched_tx,
-        tx_index,
-        state,
-        tx_gas_meter: &mut tx_gas_meter.borrow_mut(),
-        verifiers_from_tx: &verifiers,
-        vp_wasm_cache,
-    })?;
+    // Execute the hook triggered by a received IBC packet
+    let hook =
+        action::get_ibc_hook(&state.read_actions().map_err(Error::StateError)?);
+    let mut hook_failure = None;
+    if let Some(hook) = &hook {
+        match execute_ibc_hook(
+            hook,
+            tx_index,
+            state,
+            tx_gas_meter,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        )? {
+            Ok(hook_verifiers) => verifiers.extend(hook_verifiers),
+            Err(failure) => hook_failure = Some(failure),
+        }
+    }
+
+    let mut vps_result = VpsResult::default();
+    if hook_failure.is_none() {
+        vps_result = check_vps(CheckVps {
+            batched_tx,
+            tx_index,
+            state,
+            tx_gas_meter: &mut tx_gas_meter.borrow_mut(),
+            verifiers_from_tx: &verifiers,
+            vp_wasm_cache,
+        })?;
+        if hook.is_some() && !vps_result.rejected_vps.is_empty() {
+            hook_failure = Some(format!(
+                "The hook was rejected by the VPs of {:?}",
+                vps_result.rejected_vps
+            ));
+        }
+    }
+    if let Some(failure) = hook_failure {
+        // Execute the IBC tx again without the hook to acknowledge the packet
+        // with the failure
+        state.write_log_mut().drop_tx();
+        state
+            .write_log_mut()
+            .write_temp(
+                &ibc::storage::hook_failure_key(),
+                failure.serialize_to_vec(),
+            )
+            .map_err(|e| Error::StateError(state::Error::new(e)))?;
+        let verifiers = execute_tx(
+            batched_tx,
+            tx_index,
+            state,
+            tx_gas_meter,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        )?;
+        vps_result = check_vps(CheckVps {
+            batched_tx,
+            tx_index,
+            state,
+            tx_gas_meter: &mut tx_gas_meter.borrow_mut(),
+            verifiers_from_tx: &verifiers,
+            vp_wasm_cache,
+        })?;
+    }
 
     let initialized_accounts = state.write_log().get_initialized_accounts();
     let changed_keys = state.write_log().get_keys();
@@ -972,6 +1221,70 @@ where
     })
 }
 
+// Execute the hook triggered by a received IBC packet within the gas limit
+// of the hook. The gas is charged to the tx. Returns the verifiers of the hook
+// including the receiver, or the failure of the hook.
+fn execute_ibc_hook<S, D, H, CA>(
+    hook: &action::IbcHook,
+    tx_index: &TxIndex,
+    state: &mut S,
+    tx_gas_meter: &RefCell<TxGasMeter>,
+    vp_wasm_cache: &mut VpCache<CA>,
+    tx_wasm_cache: &mut TxCache<CA>,
+) -> Result<std::result::Result<BTreeSet<Address>, String>>
+where
+    S: State<D = D, H = H>,
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    CA: 'static + WasmCacheAccess + Sync,
+{
+    let gas_scale = get_gas_scale(&*state).map_err(Error::Error)?;
+    let Some(gas_limit) =
+        Gas::from_whole_units(hook.gas_limit.into(), gas_scale)
+    else {
+        return Ok(Err("Overflow in the gas limit of the hook".to_string()));
+    };
+
+    let mut hook_tx = Tx::from_type(TxType::Raw);
+    hook_tx
+        .add_code_from_hash(hook.code_hash, None)
+        .add_serialized_data(hook.data.clone());
+    let cmt = hook_tx
+        .first_commitments()
+        .ok_or(Error::MissingInnerTxs)?
+        .to_owned();
+    let hook_gas_meter = RefCell::new(TxGasMeter::new_with_costs(
+        gas_limit.clone(),
+        *tx_gas_meter.borrow().gas_costs(),
+    ));
+    let result = execute_tx(
+        &hook_tx.batch_ref_tx(&cmt),
+        tx_index,
+        state,
+        &hook_gas_meter,
+        vp_wasm_cache,
+        tx_wasm_cache,
+    );
+
+    let consumed_gas = hook_gas_meter.borrow().get_tx_consumed_gas();
+    let consumed_gas = if consumed_gas > gas_limit {
+        gas_limit
+    } else {
+        consumed_gas
+    };
+    tx_gas_meter
+        .borrow_mut()
+        .consume(consumed_gas)
+        .map_err(|e| Error::GasError(e.to_string()))?;
+
+    Ok(result
+        .map(|mut verifiers| {
+            verifiers.insert(hook.receiver.clone());
+            verifiers
+        })
+        .map_err(|e| e.to_string()))
+}
+
 /// Apply a derived transaction to storage based on some protocol transaction.
 /// The logic here must be completely deterministic and will be executed by all
 /// full nodes every time a protocol transaction is included in a block. Storage
@@ -1114,7 +1427,7 @@ fn check_vps<S, CA>(
     }: CheckVps<'_, S, CA>,
 ) -> Result<VpsResult>
 where
-    S: 'static + ReadConversionState + State + Sync,
+    S: ReadConversionState + State + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
     let (verifiers, keys_changed) = state
@@ -1151,7 +1464,7 @@ fn execute_vps<S, CA>(
     vp_wasm_cache: &mut VpCache<CA>,
 ) -> Result<(VpsResult, namada_sdk::gas::Gas)>
 where
-    S: 'static + ReadConversionState + State + Sync,
+    S: ReadConversionState + State + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
     let vps_result = verifiers
@@ -1298,8 +1611,21 @@ where
                                 )
                                 .map_err(Error::NativeVpError)
                             }
+                            InternalAddress::InterchainAccount(_) => {
+                                IcaVp::validate_tx(
+                                    &ctx,
+                                    addr,
+                                    batched_tx,
+                                    &keys_changed,
+                                    &verifiers,
+                                )
+                                .map_err(Error::NativeVpError)
+                            }
                             internal_addr @ (InternalAddress::IbcToken(_)
-                            | InternalAddress::Erc20(_)) => {
+                            | InternalAddress::Erc20(_)
+                            | InternalAddress::LiquidShare(
+                                _,
+                            )) => {
                                 // The address should be a part of a multitoken
                                 // key
                                 verifiers
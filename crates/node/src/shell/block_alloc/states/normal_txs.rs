@@ -15,8 +15,16 @@ impl TryAlloc for BlockAllocator<BuildingNormalTxBatch> {
         &mut self,
         resource_required: Self::Resources<'_>,
     ) -> Result<(), AllocFailure> {
+        let space = self.normal_txs.space;
         self.normal_txs.space.try_dump(resource_required.tx)?;
-        self.normal_txs.gas.try_dump(resource_required.gas)
+        self.normal_txs
+            .gas
+            .try_dump(resource_required.gas)
+            .inspect_err(|_| {
+                // Give back the space taken by this tx, since it may be
+                // skipped in favor of some other tx that still fits
+                self.normal_txs.space = space;
+            })
     }
 }
 
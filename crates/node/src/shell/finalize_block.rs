@@ -27,6 +27,7 @@ use namada_sdk::tx::new_tx_event;
 use namada_sdk::{ibc, proof_of_stake};
 use namada_vote_ext::ethereum_events::MultiSignedEthEvent;
 use namada_vote_ext::ethereum_tx_data_variants;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use tendermint::abci::types::Misbehavior;
 
 use super::*;
@@ -51,6 +52,11 @@ where
     ) -> ShellResult<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
 
+        // Halt at the height of an upgrade plan unless running its binary
+        self.check_upgrade_plan(
+            self.state.in_mem().get_last_block_height().next_height(),
+        )?;
+
         // Begin the new block and check if a new epoch has begun
         let (height, new_epoch) = self.update_state(req.header);
         let masp_epoch_multiplier =
@@ -199,6 +205,17 @@ where
             native_block_proposer_address,
         )?;
 
+        // Activate the gas cost schedule approved by governance, it's loaded
+        // on commit and applies from the next block
+        if let Some(version) =
+            parameters::activate_pending_gas_cost_schedule(&mut self.state)?
+        {
+            tracing::info!(
+                "Activated the gas cost schedule version {version} at height \
+                 {height}"
+            );
+        }
+
         self.event_log_mut().emit_many(response.events.clone());
         tracing::debug!("End finalize_block {height} of epoch {current_epoch}");
 
@@ -691,7 +708,10 @@ where
                                 continue;
                             }
                         };
-                    let tx_gas_meter = TxGasMeter::new(gas_limit);
+                    let tx_gas_meter = TxGasMeter::new_with_costs(
+                        gas_limit,
+                        self.state.in_mem().gas_cost_schedule,
+                    );
                     for cmt in tx.commitments() {
                         if let Some(code_sec) = tx
                             .get_section(cmt.code_sechash())
@@ -768,7 +788,13 @@ where
                             }
                         }
                     }
-                    (DispatchArgs::Protocol(protocol_tx), TxGasMeter::new(0))
+                    (
+                        DispatchArgs::Protocol(protocol_tx),
+                        TxGasMeter::new_with_costs(
+                            0,
+                            self.state.in_mem().gas_cost_schedule,
+                        ),
+                    )
                 }
             };
             let tx_event = new_tx_event(&tx, height.0);
@@ -814,7 +840,59 @@ where
         successful_wrappers
     }
 
-    // Execute the transaction batches for successful wrapper transactions
+    // Speculatively execute in parallel the transaction batches for
+    // successful wrapper transactions, each one against the state left by the
+    // wrappers. Return an empty vector if parallel execution is disabled.
+    fn speculate_tx_batches(
+        &self,
+        successful_wrappers: &[WrapperCache],
+    ) -> Vec<protocol::SpeculativeBatch> {
+        if self.sequential_tx_execution || successful_wrappers.len() < 2 {
+            return vec![];
+        }
+
+        let inputs: Vec<_> = successful_wrappers
+            .iter()
+            .map(|wrapper| {
+                let mut tx = wrapper.tx.clone();
+                let tx_hash = tx.header_hash();
+                tx.update_header(TxType::Raw);
+                (
+                    tx,
+                    tx_hash,
+                    TxIndex::must_from_usize(wrapper.tx_index),
+                    wrapper.extended_tx_result.masp_tx_refs.clone(),
+                    wrapper.gas_meter.clone(),
+                )
+            })
+            .collect();
+        let state = self.state.read_only();
+        let vp_wasm_cache = &self.vp_wasm_cache;
+        let tx_wasm_cache = &self.tx_wasm_cache;
+
+        inputs
+            .into_par_iter()
+            .map(|(tx, tx_hash, tx_index, masp_tx_refs, tx_gas_meter)| {
+                protocol::speculate_inner_txs(
+                    &tx,
+                    &tx_hash,
+                    masp_tx_refs,
+                    tx_index,
+                    tx_gas_meter,
+                    state,
+                    vp_wasm_cache.clone(),
+                    tx_wasm_cache.clone(),
+                )
+            })
+            .collect()
+    }
+
+    // Execute the transaction batches for successful wrapper transactions.
+    // The batches are first executed speculatively in parallel, then their
+    // outcomes are applied in block order. A speculative outcome is discarded
+    // and the batch executed again if any of the keys it read has been
+    // modified by a preceding batch, so that the result is the same as the
+    // one of a sequential execution.
     fn execute_tx_batches(
         &mut self,
         successful_wrappers: Vec<WrapperCache>,
@@ -825,6 +903,11 @@ where
             height,
         }: ExecutionArgs<'_>,
     ) {
+        let mut speculative_batches =
+            self.speculate_tx_batches(&successful_wrappers).into_iter();
+        // The keys modified by the batches executed so far in this block
+        let mut written_keys = BTreeSet::new();
+
         for WrapperCache {
             mut tx,
             tx_index,
@@ -843,25 +926,64 @@ where
 
             // change tx type to raw for execution
             tx.update_header(TxType::Raw);
-            let tx_gas_meter = RefCell::new(tx_gas_meter);
-            let dispatch_result = protocol::dispatch_tx(
-                &tx,
-                DispatchArgs::Raw {
-                    wrapper_hash: Some(&tx_hash),
-                    tx_index: TxIndex::must_from_usize(tx_index),
-                    wrapper_tx_result: Some(wrapper_tx_result),
-                    vp_wasm_cache: &mut self.vp_wasm_cache,
-                    tx_wasm_cache: &mut self.tx_wasm_cache,
-                },
-                &tx_gas_meter,
-                &mut self.state,
-            );
-            let tx_gas_meter = tx_gas_meter.into_inner();
+            let (dispatch_result, tx_gas_meter) = match speculative_batches
+                .next()
+            {
+                Some(batch)
+                    if !batch.read_set.conflicts_with(&written_keys)
+                        && !self
+                            .state
+                            .write_log()
+                            .has_replay_protection_entry(
+                                &tx.raw_header_hash(),
+                            ) =>
+                {
+                    self.state
+                        .write_log_mut()
+                        .replace_batch(batch.batch_write_log);
+                    let dispatch_result = match batch.result {
+                        Ok(result) => {
+                            Ok(merge_batch_result(wrapper_tx_result, result))
+                        }
+                        Err(DispatchError { error, tx_result }) => {
+                            Err(DispatchError {
+                                error,
+                                tx_result: tx_result.map(|result| {
+                                    merge_batch_result(
+                                        wrapper_tx_result,
+                                        result,
+                                    )
+                                }),
+                            })
+                        }
+                    };
+                    (dispatch_result, batch.tx_gas_meter)
+                }
+                _ => {
+                    // No speculative outcome or a stale one, execute the
+                    // batch against the current state
+                    let tx_gas_meter = RefCell::new(tx_gas_meter);
+                    let dispatch_result = protocol::dispatch_tx(
+                        &tx,
+                        DispatchArgs::Raw {
+                            wrapper_hash: Some(&tx_hash),
+                            tx_index: TxIndex::must_from_usize(tx_index),
+                            wrapper_tx_result: Some(wrapper_tx_result),
+                            vp_wasm_cache: &mut self.vp_wasm_cache,
+                            tx_wasm_cache: &mut self.tx_wasm_cache,
+                        },
+                        &tx_gas_meter,
+                        &mut self.state,
+                    );
+                    (dispatch_result, tx_gas_meter.into_inner())
+                }
+            };
             let consumed_gas = tx_gas_meter.get_tx_consumed_gas();
 
             // update the gas cost of the corresponding wrapper
             self.update_tx_gas(tx_hash, consumed_gas);
 
+            written_keys.extend(self.state.write_log().get_batch_keys());
             self.evaluate_tx_result(
                 response,
                 dispatch_result,
@@ -884,6 +1006,23 @@ where
     }
 }
 
+// Merge the result of a speculatively executed batch into the result of the
+// corresponding wrapper transaction
+fn merge_batch_result(
+    mut wrapper_tx_result: namada_sdk::tx::data::ExtendedTxResult<
+        protocol::Error,
+    >,
+    batch_result: namada_sdk::tx::data::ExtendedTxResult<protocol::Error>,
+) -> namada_sdk::tx::data::ExtendedTxResult<protocol::Error> {
+    wrapper_tx_result
+        .tx_result
+        .0
+        .extend(batch_result.tx_result.0);
+    // The batch execution started from the masp refs of the wrapper
+    wrapper_tx_result.masp_tx_refs = batch_result.masp_tx_refs;
+    wrapper_tx_result
+}
+
 struct ExecutionArgs<'finalize> {
     response: &'finalize mut shim::response::FinalizeBlock,
     changed_keys: &'finalize mut BTreeSet<Key>,
@@ -1136,6 +1275,8 @@ where
         _,
         token::Store<_>,
         proof_of_stake::Store<_>,
+        parameters::Store<_>,
+        ibc::Store<_>,
         _,
         _,
     >(
@@ -1290,15 +1431,24 @@ mod test_finalize_block {
     use namada_sdk::events::extend::Log;
     use namada_sdk::events::Event;
     use namada_sdk::gas::VpGasMeter;
-    use namada_sdk::governance::storage::keys::get_proposal_execution_key;
-    use namada_sdk::governance::storage::proposal::ProposalType;
+    use namada_sdk::governance::parameters::GovernanceParamChange;
+    use namada_sdk::governance::storage::keys::{
+        get_max_proposal_latency_key, get_min_proposal_grace_epochs_key,
+        get_proposal_execution_key,
+    };
+    use namada_sdk::governance::storage::proposal::{
+        ParameterChange, ProposalType,
+    };
+    use namada_sdk::governance::utils::TallyResult;
     use namada_sdk::governance::{
         InitProposalData, ProposalVote, VoteProposalData,
     };
     use namada_sdk::hash::Hash;
     use namada_sdk::keccak::KeccakHash;
     use namada_sdk::key::testing::common_sk_from_simple_seed;
-    use namada_sdk::parameters::EpochDuration;
+    use namada_sdk::parameters::{
+        EpochDuration, ParamChange as ProtocolParamChange,
+    };
     use namada_sdk::proof_of_stake::storage::{
         enqueued_slashes_handle, get_num_consensus_validators,
         liveness_missed_votes_handle, liveness_sum_missed_votes_handle,
@@ -1470,6 +1620,39 @@ mod test_finalize_block {
         )
     }
 
+    /// Wrap a single inner tx running the given test wasm with the given data.
+    fn mk_wrapped_tx(
+        shell: &TestShell,
+        sk: &common::SecretKey,
+        wasm: TestWasms,
+        data: Vec<u8>,
+    ) -> ProcessedTx {
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(1.into()),
+                    token: shell.state.in_mem().native_token.clone(),
+                },
+                sk.ref_to(),
+                WRAPPER_GAS_LIMIT.into(),
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_data(Data::new(data));
+        wrapper.set_code(Code::new(wasm.read_bytes(), None));
+        wrapper.add_section(Section::Authorization(Authorization::new(
+            wrapper.sechashes(),
+            [(0, sk.clone())].into_iter().collect(),
+            None,
+        )));
+        ProcessedTx {
+            tx: wrapper.to_bytes().into(),
+            result: TxResult {
+                code: ResultCode::Ok.into(),
+                info: "".into(),
+            },
+        }
+    }
+
     /// Check that if a wrapper tx was rejected by [`process_proposal`], the
     /// correct event is returned.
     #[test]
@@ -2065,6 +2248,136 @@ mod test_finalize_block {
         }
     }
 
+    /// Test that the changes of a passed parameter change proposal are
+    /// applied, that the ones of a rejected proposal are not, and that a
+    /// proposal with a failing change doesn't apply any of its changes nor
+    /// halt the chain.
+    #[test]
+    fn test_parameter_change_proposals() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let mut add_proposal = |proposal_id, changes, vote| {
+            let validator = shell.mode.get_validator_address().unwrap().clone();
+
+            let proposal = InitProposalData {
+                content: Hash::default(),
+                author: validator.clone(),
+                voting_start_epoch: Epoch::default(),
+                voting_end_epoch: Epoch::default().next(),
+                activation_epoch: Epoch::default().next(),
+                r#type: ProposalType::ParameterChange(changes),
+            };
+
+            namada_sdk::governance::init_proposal::<_, token::Store<_>>(
+                &mut shell.state,
+                &proposal,
+                vec![],
+                None,
+            )
+            .unwrap();
+
+            let vote = VoteProposalData {
+                id: proposal_id,
+                vote,
+                voter: validator,
+            };
+            namada_sdk::governance::vote_proposal(
+                &mut shell.state,
+                vote,
+                HashSet::new(),
+            )
+            .unwrap();
+        };
+
+        // A proposal to be accepted
+        add_proposal(
+            0,
+            BTreeSet::from([
+                ParameterChange::Protocol(ProtocolParamChange::MaxBlockGas(
+                    123_456,
+                )),
+                ParameterChange::Governance(
+                    GovernanceParamChange::MaxProposalLatency(42),
+                ),
+            ]),
+            ProposalVote::Yay,
+        );
+        // A proposal to be rejected
+        add_proposal(
+            1,
+            BTreeSet::from([ParameterChange::Governance(
+                GovernanceParamChange::MinProposalGraceEpochs(77),
+            )]),
+            ProposalVote::Nay,
+        );
+        // A proposal to be accepted, whose PoS change fails after its
+        // protocol change has been written
+        add_proposal(
+            2,
+            BTreeSet::from([
+                ParameterChange::Protocol(ProtocolParamChange::MaxTxBytes(
+                    4321,
+                )),
+                ParameterChange::Pos(
+                    proof_of_stake::ParamChange::RewardsGainP(Dec::one()),
+                ),
+            ]),
+            ProposalVote::Yay,
+        );
+
+        // Store PoS params that fail validation, such that any PoS parameter
+        // change is rejected
+        let mut pos_params = read_pos_params(&shell.state).unwrap().owned;
+        pos_params.tm_votes_per_token = Dec::two();
+        write_pos_params(&mut shell.state, &pos_params).unwrap();
+
+        shell.state.commit_block().unwrap();
+        shell.commit();
+
+        let params_pre = namada_sdk::parameters::read(&shell.state).unwrap();
+        let min_grace_epochs_pre: u64 = shell
+            .state
+            .read(&get_min_proposal_grace_epochs_key())
+            .unwrap()
+            .unwrap();
+
+        // The proposals are executed at the start of the activation epoch
+        shell.start_new_epoch(None);
+
+        for (id, passed) in [(0, true), (1, false), (2, true)] {
+            let result = namada_sdk::governance::storage::get_proposal_result(
+                &shell.state,
+                id,
+            )
+            .unwrap()
+            .unwrap();
+            assert_eq!(matches!(result.result, TallyResult::Passed), passed);
+        }
+
+        // The changes of the passed proposal have been applied
+        let params_post = namada_sdk::parameters::read(&shell.state).unwrap();
+        assert_eq!(params_post.max_block_gas, 123_456);
+        let max_latency: u64 = shell
+            .state
+            .read(&get_max_proposal_latency_key())
+            .unwrap()
+            .unwrap();
+        assert_eq!(max_latency, 42);
+
+        // The change of the rejected proposal hasn't been applied
+        let min_grace_epochs_post: u64 = shell
+            .state
+            .read(&get_min_proposal_grace_epochs_key())
+            .unwrap()
+            .unwrap();
+        assert_eq!(min_grace_epochs_post, min_grace_epochs_pre);
+
+        // None of the changes of the failing proposal have been applied
+        assert_eq!(params_post.max_tx_bytes, params_pre.max_tx_bytes);
+        let pos_params_post = read_pos_params(&shell.state).unwrap().owned;
+        assert_eq!(pos_params_post.rewards_gain_p, pos_params.rewards_gain_p);
+    }
+
     /// A unit test for PoS inflationary rewards
     #[test]
     fn test_inflation_accounting() {
@@ -4079,6 +4392,85 @@ mod test_finalize_block {
         )
     }
 
+    // Test that the fees of a sponsored wrapper are withdrew from the sponsor
+    // and debited from its allowance
+    #[test]
+    fn test_sponsored_fee_payment() {
+        let (mut shell, _, _, _) = setup();
+        let native_token = shell.state.in_mem().native_token.clone();
+        let sponsor = address::testing::established_address_1();
+        let keypair = gen_keypair();
+        let code = TestWasms::TxNoOp.read_bytes();
+
+        namada_sdk::token::credit_tokens(
+            &mut shell.state,
+            &native_token,
+            &sponsor,
+            10_000_000.into(),
+        )
+        .unwrap();
+        namada_sdk::token::write_fee_sponsorship(
+            &mut shell.state,
+            &sponsor,
+            namada_sdk::token::FeeSponsorship {
+                token: native_token.clone(),
+                budget: 8_000_000.into(),
+                epoch_cap: 6_000_000.into(),
+                code_hashes: [Hash::sha256(&code)].into_iter().collect(),
+                targets: [Address::from(&keypair.ref_to())]
+                    .into_iter()
+                    .collect(),
+                epoch: Epoch::default(),
+                epoch_spent: Amount::zero(),
+            },
+        )
+        .unwrap();
+        shell.commit();
+
+        let mut wrapper = Tx::from_type(TxType::Wrapper(Box::new(
+            WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(1.into()),
+                    token: native_token.clone(),
+                },
+                keypair.ref_to(),
+                5_000_000.into(),
+            )
+            .with_fee_sponsor(sponsor.clone()),
+        )));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.add_code(code, None).add_data("Transaction data");
+        wrapper.sign_wrapper(keypair);
+
+        let processed_tx = ProcessedTx {
+            tx: wrapper.to_bytes().into(),
+            result: TxResult {
+                code: ResultCode::Ok.into(),
+                info: "".into(),
+            },
+        };
+        let event = &shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![processed_tx],
+                ..Default::default()
+            })
+            .expect("Test failed")[0];
+        assert_eq!(*event.kind(), APPLIED_TX);
+        let code = event.read_attribute::<CodeAttr>().expect("Test failed");
+        assert_eq!(code, ResultCode::Ok);
+
+        // The fees are paid by the sponsor
+        let sponsor_balance =
+            read_balance(&shell.state, &native_token, &sponsor).unwrap();
+        assert_eq!(sponsor_balance, 5_000_000.into());
+        let sponsorship =
+            namada_sdk::token::read_fee_sponsorship(&shell.state, &sponsor)
+                .unwrap()
+                .unwrap();
+        assert_eq!(sponsorship.budget, 3_000_000.into());
+        assert_eq!(sponsorship.epoch_spent, 5_000_000.into());
+    }
+
     #[test]
     fn test_ledger_slashing() -> namada_sdk::state::Result<()> {
         let num_validators = 7_u64;
@@ -5814,6 +6206,91 @@ mod test_finalize_block {
         }
     }
 
+    // Test that the speculative parallel execution of the batches of a block
+    // yields exactly the same outcome of their sequential execution, also in
+    // presence of batches depending on the changes of the preceding ones
+    #[test]
+    fn test_parallel_execution_matches_sequential() {
+        let (mut sequential, _broadcaster, _, _) = setup();
+        sequential.sequential_tx_execution = true;
+        let (mut parallel, _broadcaster, _, _) = setup();
+        assert!(!parallel.sequential_tx_execution);
+
+        let key_1: Key = "random_key_1".parse().unwrap();
+        let key_2: Key = "random_key_2".parse().unwrap();
+        let write_data = |key: &Key, value: &str| {
+            TxWriteData {
+                key: key.clone(),
+                value: value.serialize_to_vec(),
+            }
+            .serialize_to_vec()
+        };
+        let txs = vec![
+            // Write a key read by the following txs
+            mk_wrapped_tx(
+                &sequential,
+                &wallet::defaults::albert_keypair(),
+                TestWasms::TxWriteStorageKey,
+                write_data(&key_1, "first"),
+            ),
+            // Only succeeds if the previous write is visible
+            mk_wrapped_tx(
+                &sequential,
+                &wallet::defaults::bertha_keypair(),
+                TestWasms::TxReadStorageKey,
+                key_1.serialize_to_vec(),
+            ),
+            // Independent from the other txs
+            mk_wrapped_tx(
+                &sequential,
+                &wallet::defaults::christel_keypair(),
+                TestWasms::TxWriteStorageKey,
+                write_data(&key_2, "independent"),
+            ),
+            // Overwrite the first write
+            mk_wrapped_tx(
+                &sequential,
+                &wallet::defaults::albert_keypair(),
+                TestWasms::TxWriteStorageKey,
+                write_data(&key_1, "last"),
+            ),
+        ];
+        let req = FinalizeBlock {
+            txs,
+            ..Default::default()
+        };
+
+        let sequential_events =
+            sequential.finalize_block(req.clone()).expect("Test failed");
+        let parallel_events =
+            parallel.finalize_block(req).expect("Test failed");
+
+        assert_eq!(parallel_events, sequential_events);
+        for event in parallel_events
+            .iter()
+            .filter(|event| *event.kind() == APPLIED_TX)
+        {
+            let code = event.read_attribute::<CodeAttr>().unwrap();
+            assert_eq!(code, ResultCode::Ok);
+            let inner_results = event.read_attribute::<Batch<'_>>().unwrap();
+            assert!(
+                inner_results
+                    .0
+                    .values()
+                    .all(|res| res.as_ref().is_ok_and(|res| res.is_accepted()))
+            );
+        }
+        assert_eq!(parallel.state.write_log(), sequential.state.write_log());
+        assert_eq!(
+            parallel.state.read::<String>(&key_1).unwrap().unwrap(),
+            "last"
+        );
+        assert_eq!(
+            parallel.state.read::<String>(&key_2).unwrap().unwrap(),
+            "independent"
+        );
+    }
+
     // Test a failing atomic batch with two successful txs and a failing one.
     // Verify that also the changes applied by the valid txs are dropped and
     // that the last transaction is never executed (batch short-circuit)
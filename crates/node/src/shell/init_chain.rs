@@ -14,7 +14,7 @@ use namada_sdk::proof_of_stake::{self, BecomeValidator, PosParams};
 use namada_sdk::state::StorageWrite;
 use namada_sdk::time::{TimeZone, Utc};
 use namada_sdk::token::storage_key::masp_token_map_key;
-use namada_sdk::token::{credit_tokens, write_denom};
+use namada_sdk::token::{credit_tokens, write_denom, write_vesting_schedule};
 use namada_sdk::{eth_bridge, ibc};
 use namada_vm::validate_untrusted_wasm;
 
@@ -23,7 +23,9 @@ use crate::config::genesis::chain::{
     FinalizedEstablishedAccountTx, FinalizedTokenConfig,
     FinalizedValidatorAccountTx,
 };
-use crate::config::genesis::templates::{TokenBalances, TokenConfig};
+use crate::config::genesis::templates::{
+    TokenBalances, TokenConfig, VestingSchedules,
+};
 use crate::config::genesis::transactions::{
     BondTx, EstablishedAccountTx, Signed as SignedTx, ValidatorAccountTx,
 };
@@ -98,7 +100,7 @@ where
                 chain_id, init.chain_id
             )));
         }
-        if crate::migrating_state().is_some() {
+        if self.cometbft_initial_height().is_some() {
             let rsp = response::InitChain {
                 validators: self
                     .get_abci_validator_updates(true, |pk, power| {
@@ -125,6 +127,8 @@ where
                     .iter()
                     .all(|&b| b == 0)
             );
+            crate::finish_bootstrapping_state(&self.base_dir.join(chain_id))
+                .map_err(Error::Snapshot)?;
             return Ok(rsp);
         }
 
@@ -221,6 +225,11 @@ where
         let parameters = genesis.get_chain_parameters(&self.wasm_dir);
         self.store_wasms(&parameters)?;
         parameters::init_storage(&parameters, &mut self.state).unwrap();
+        parameters::init_gas_cost_schedule(
+            &mut self.state,
+            &parameters::GasCostSchedule::default(),
+        )
+        .unwrap();
 
         // Initialize governance parameters
         let gov_params = genesis.get_gov_params();
@@ -520,6 +529,42 @@ where
                 .expect("Couldn't credit initial balance");
             }
         }
+        for (token_alias, VestingSchedules(schedules)) in
+            &genesis.balances.vesting
+        {
+            tracing::debug!("Initializing vesting schedules {token_alias}");
+
+            let Some(token_address) = self
+                .validate(
+                    genesis
+                        .tokens
+                        .token
+                        .get(token_alias)
+                        .ok_or_else(|| {
+                            Panic::MissingTokenConfig(token_alias.to_string())
+                        })
+                        .map(|conf| &conf.address),
+                )
+                .or_placeholder(None)?
+            else {
+                continue;
+            };
+
+            for (owner, schedule) in schedules {
+                tracing::info!(
+                    "Locking {} tokens of {} with a vesting schedule",
+                    token_alias,
+                    owner,
+                );
+                write_vesting_schedule(
+                    &mut self.state,
+                    token_address,
+                    owner,
+                    schedule.clone().map(|amount| amount.amount()),
+                )
+                .expect("Couldn't write vesting schedule");
+            }
+        }
         self.proceed_with(())
     }
 
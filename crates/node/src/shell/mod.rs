@@ -18,10 +18,12 @@ use namada_sdk::state::State;
 pub mod process_proposal;
 pub(super) mod queries;
 mod snapshots;
+pub use snapshots::{export_snapshot, import_snapshot};
 mod stats;
 #[cfg(any(test, feature = "testing"))]
 #[allow(dead_code)]
 pub mod testing;
+mod upgrade;
 mod vote_extensions;
 
 use std::cell::RefCell;
@@ -133,6 +135,14 @@ pub enum Error {
     RejectedBlockProposal,
     #[error("Received an invalid block proposal")]
     InvalidBlockProposal,
+    #[error("{0}")]
+    Upgrade(String),
+    #[error(
+        "The DB storage backend is set to {0} in the config, but the node is \
+         built with {1}. The backend can only be changed by rebuilding the \
+         node with the matching cargo feature."
+    )]
+    StorageBackend(config::StorageBackend, config::StorageBackend),
 }
 
 impl From<Error> for TxResult {
@@ -159,6 +169,8 @@ pub fn reset(config: config::Ledger) -> ShellResult<()> {
 }
 
 pub fn rollback(config: config::Ledger) -> ShellResult<()> {
+    storage::check_storage_backend(config.shell.storage_backend)?;
+
     // Rollback Tendermint state
     tracing::info!("Rollback Tendermint state");
     let tendermint_block_height =
@@ -379,12 +391,18 @@ where
     event_log: EventLog,
     /// A migration that can be scheduled at a given block height
     pub scheduled_migration: Option<ScheduledMigration<D::Migrator>>,
+    /// The hash of the running binary, computed when the height of an
+    /// upgrade plan is reached
+    pub(crate) binary_hash: Option<Hash>,
     /// When set, indicates after how many blocks a new snapshot
     /// will be taken (counting from the first block)
     pub blocks_between_snapshots: Option<NonZeroU64>,
     /// Data for a node downloading and apply snapshots as part of
     /// the fast sync protocol.
     pub syncing: Option<SnapshotSync>,
+    /// Taken from config `sequential_tx_execution`. When set, disables the
+    /// speculative parallel execution of the inner transactions.
+    pub sequential_tx_execution: bool,
 }
 
 /// Storage key filter to store the diffs into the storage. Return `false` for
@@ -447,7 +465,7 @@ impl Shell<crate::storage::PersistentDB, Sha256Hasher> {
             })
         };
 
-        let db_cache = rocksdb::Cache::new_lru_cache(
+        let db_cache = crate::storage::DbCache::new_lru_cache(
             usize::try_from(db_block_cache_size_bytes).expect(
                 "`db_block_cache_size_bytes` must not exceed `usize::MAX`",
             ),
@@ -460,6 +478,7 @@ impl Shell<crate::storage::PersistentDB, Sha256Hasher> {
 
         // rebuild the in-memory state
         self.state.load_last_state();
+        self.load_gas_cost_schedule();
     }
 }
 
@@ -516,7 +535,7 @@ where
         };
 
         // load last state from storage
-        let state = FullAccessState::open(
+        let mut state = FullAccessState::open(
             db_path,
             db_cache,
             chain_id.clone(),
@@ -524,6 +543,12 @@ where
             config.shell.storage_read_past_height_limit,
             is_key_diff_storable,
         );
+        if config.shell.archive_mode {
+            state
+                .db_mut()
+                .enable_archive_mode()
+                .expect("Enabling archive mode should not fail");
+        }
         let vp_wasm_cache_dir =
             base_dir.join(chain_id.as_str()).join("vp_wasm_cache");
         let tx_wasm_cache_dir =
@@ -666,10 +691,13 @@ where
             // TODO(namada#3237): config event log params
             event_log: EventLog::default(),
             scheduled_migration,
+            binary_hash: None,
             blocks_between_snapshots: config.shell.blocks_between_snapshots,
             syncing: None,
+            sequential_tx_execution: config.shell.sequential_tx_execution,
         };
         shell.update_eth_oracle(&Default::default());
+        shell.load_gas_cost_schedule();
         shell
     }
 
@@ -685,13 +713,24 @@ where
         &mut self.event_log
     }
 
+    /// The initial height of CometBFT's chain, if it is initialized from the
+    /// state in the DB, when migrating state or when bootstrapping CometBFT's
+    /// state from a DB restored from a snapshot.
+    pub(crate) fn cometbft_initial_height(&self) -> Option<BlockHeight> {
+        crate::migrating_state().or_else(|| {
+            crate::bootstrapping_state(
+                &self.base_dir.join(self.chain_id.as_str()),
+            )
+        })
+    }
+
     /// Load the Merkle root hash and the height of the last committed block, if
     /// any. This is returned when ABCI sends an `info` request.
     pub fn last_state(&self) -> response::Info {
-        if crate::migrating_state().is_some() {
-            // When migrating state, return a height of 0, such
-            // that CometBFT calls InitChain and subsequently
-            // updates the apphash in its state.
+        if self.cometbft_initial_height().is_some() {
+            // When migrating state or bootstrapping CometBFT's state, return
+            // a height of 0, such that CometBFT calls InitChain and
+            // subsequently updates the apphash in its state.
             return response::Info {
                 last_block_height: 0u32.into(),
                 ..response::Info::default()
@@ -791,6 +830,7 @@ where
             committed_height,
             &mut self.scheduled_migration,
         );
+        self.load_gas_cost_schedule();
         let merkle_root = self.state.in_mem().merkle_root();
 
         tracing::info!(
@@ -812,6 +852,14 @@ where
         )
     }
 
+    /// Load the active gas cost schedule from storage. A schedule activated
+    /// by governance applies from the block after its activation.
+    fn load_gas_cost_schedule(&mut self) {
+        let schedule = parameters::read_gas_cost_schedule(&self.state)
+            .expect("Failed to read the gas cost schedule");
+        self.state.set_gas_cost_schedule(schedule);
+    }
+
     /// Check if we have reached a block height at which we should take a
     /// snapshot
     fn check_snapshot_required(&self) -> TakeSnapshot {
@@ -1240,7 +1288,10 @@ where
                         return response;
                     }
                 };
-                let mut gas_meter = TxGasMeter::new(gas_limit);
+                let mut gas_meter = TxGasMeter::new_with_costs(
+                    gas_limit,
+                    self.state.in_mem().gas_cost_schedule,
+                );
                 if gas_meter.add_wrapper_gas(tx_bytes).is_err() {
                     response.code = ResultCode::TxGasLimit.into();
                     response.log = "{INVALID_MSG}: Wrapper transaction \
@@ -1510,6 +1561,90 @@ where
     Ok(())
 }
 
+/// The priority of a wrapper tx in a block proposal.
+///
+/// This is the price paid per unit of gas, normalised by the minimum gas
+/// price agreed upon by consensus for the fee token, such that wrappers
+/// paying fees in different tokens can be compared with one another.
+#[derive(Debug, Clone, Copy)]
+pub struct FeePriority {
+    /// The price paid per unit of gas, in the fee token's raw units
+    amount_per_gas_unit: token::Amount,
+    /// The minimum gas price of the fee token, in its raw units
+    minimum_gas_price: token::Amount,
+}
+
+impl FeePriority {
+    /// Compute the [`FeePriority`] of the given wrapper tx. Returns `None` if
+    /// the fee token is not allowed for fee payment or if the fee amount
+    /// cannot be converted to the token's denomination.
+    pub fn of_wrapper<S>(wrapper: &WrapperTx, storage: &S) -> Option<Self>
+    where
+        S: StorageRead,
+    {
+        let minimum_gas_price =
+            parameters::read_gas_cost(storage, &wrapper.fee.token)
+                .ok()
+                .flatten()?;
+        let amount_per_gas_unit = token::denom_to_amount(
+            wrapper.fee.amount_per_gas_unit,
+            &wrapper.fee.token,
+            storage,
+        )
+        .ok()?;
+        Some(Self {
+            amount_per_gas_unit,
+            // A zero minimum gas price cannot be used to normalise the fee,
+            // so we treat it as the smallest possible price
+            minimum_gas_price: std::cmp::max(
+                minimum_gas_price,
+                token::Amount::from_u64(1),
+            ),
+        })
+    }
+}
+
+impl PartialEq for FeePriority {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for FeePriority {}
+
+impl PartialOrd for FeePriority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FeePriority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Compare `a / b` against `c / d` as `a * d` against `c * b`,
+        // saturating on (practically unreachable) overflows
+        let lhs = self
+            .amount_per_gas_unit
+            .checked_mul(other.minimum_gas_price)
+            .unwrap_or_else(token::Amount::max);
+        let rhs = other
+            .amount_per_gas_unit
+            .checked_mul(self.minimum_gas_price)
+            .unwrap_or_else(token::Amount::max);
+        lhs.cmp(&rhs)
+    }
+}
+
+/// Compute the [`FeePriority`] of some tx bytes. Returns `None` if the bytes
+/// do not decode to a wrapper tx whose fees can be normalised.
+pub fn tx_fee_priority<S>(tx_bytes: &[u8], storage: &S) -> Option<FeePriority>
+where
+    S: StorageRead,
+{
+    let tx = Tx::try_from_bytes(tx_bytes).ok()?;
+    let wrapper = tx.header.wrapper()?;
+    FeePriority::of_wrapper(&wrapper, storage)
+}
+
 /// for the shell
 #[allow(clippy::arithmetic_side_effects, clippy::cast_possible_wrap)]
 #[cfg(any(test, feature = "testing"))]
@@ -1872,30 +2007,10 @@ pub mod test_utils {
         }
     }
 
-    /// Start a new test shell and initialize it. Returns the shell paired with
-    /// a broadcast receiver, which will receives any protocol txs sent by the
-    /// shell.
-    pub fn setup_with_cfg<H: Into<BlockHeight>>(
-        SetupCfg {
-            last_height,
-            num_validators,
-            enable_ethereum_oracle,
-        }: SetupCfg<H>,
-    ) -> (
-        TestShell,
-        UnboundedReceiver<Vec<u8>>,
-        Sender<EthereumEvent>,
-        Receiver<oracle::control::Command>,
-    ) {
-        let (mut test, receiver, eth_sender, control_receiver) =
-            TestShell::new_at_height(last_height);
-        if !enable_ethereum_oracle {
-            if let ShellMode::Validator { eth_oracle, .. } = &mut test.mode {
-                // drop the eth oracle event receiver
-                _ = eth_oracle.take();
-            }
-        }
-        let req = request::InitChain {
+    /// An InitChain request of a test chain, whose first block is at the
+    /// given initial height
+    pub fn init_chain_req(initial_height: BlockHeight) -> request::InitChain {
+        request::InitChain {
             time: Timestamp {
                 seconds: 0,
                 nanos: 0,
@@ -1926,9 +2041,34 @@ pub mod test_utils {
             },
             validators: vec![],
             app_state_bytes: vec![].into(),
-            initial_height: 1_u32.into(),
-        };
-        test.init_chain(req, num_validators);
+            initial_height: initial_height.0.try_into().unwrap(),
+        }
+    }
+
+    /// Start a new test shell and initialize it. Returns the shell paired with
+    /// a broadcast receiver, which will receives any protocol txs sent by the
+    /// shell.
+    pub fn setup_with_cfg<H: Into<BlockHeight>>(
+        SetupCfg {
+            last_height,
+            num_validators,
+            enable_ethereum_oracle,
+        }: SetupCfg<H>,
+    ) -> (
+        TestShell,
+        UnboundedReceiver<Vec<u8>>,
+        Sender<EthereumEvent>,
+        Receiver<oracle::control::Command>,
+    ) {
+        let (mut test, receiver, eth_sender, control_receiver) =
+            TestShell::new_at_height(last_height);
+        if !enable_ethereum_oracle {
+            if let ShellMode::Validator { eth_oracle, .. } = &mut test.mode {
+                // drop the eth oracle event receiver
+                _ = eth_oracle.take();
+            }
+        }
+        test.init_chain(init_chain_req(BlockHeight(1)), num_validators);
         test.state.commit_block().expect("Test failed");
         (test, receiver, eth_sender, control_receiver)
     }
@@ -2852,6 +2992,78 @@ mod shell_tests {
         assert_eq!(result.code, ResultCode::FeeError.into());
     }
 
+    // Check that a sponsored wrapper is accepted only if its fees can be paid
+    // from the sponsor's allowance
+    #[test]
+    fn test_sponsored_fee() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let native_token = shell.state.in_mem().native_token.clone();
+        let sponsor = address::testing::established_address_1();
+        let keypair = super::test_utils::gen_keypair();
+        let fee_amount: token::Amount = (100 * GAS_LIMIT).into();
+
+        namada_sdk::token::credit_tokens(
+            &mut shell.state,
+            &native_token,
+            &sponsor,
+            fee_amount,
+        )
+        .unwrap();
+        token::write_fee_sponsorship(
+            &mut shell.state,
+            &sponsor,
+            token::FeeSponsorship {
+                token: native_token.clone(),
+                budget: fee_amount,
+                epoch_cap: fee_amount,
+                code_hashes: [Hash::sha256("wasm_code")].into_iter().collect(),
+                targets: Default::default(),
+                epoch: Epoch::default(),
+                epoch_spent: token::Amount::zero(),
+            },
+        )
+        .unwrap();
+        shell.commit();
+
+        let validate = |code: &str, gas_price: u64| {
+            let mut wrapper = Tx::from_type(TxType::Wrapper(Box::new(
+                WrapperTx::new(
+                    Fee {
+                        amount_per_gas_unit: DenominatedAmount::native(
+                            gas_price.into(),
+                        ),
+                        token: native_token.clone(),
+                    },
+                    keypair.ref_to(),
+                    GAS_LIMIT.into(),
+                )
+                .with_fee_sponsor(sponsor.clone()),
+            )));
+            wrapper.header.chain_id = shell.chain_id.clone();
+            wrapper.set_code(Code::new(code.as_bytes().to_owned(), None));
+            wrapper
+                .set_data(Data::new("transaction data".as_bytes().to_owned()));
+            wrapper.sign_wrapper(keypair.clone());
+
+            shell.mempool_validate(
+                wrapper.to_bytes().as_ref(),
+                MempoolTxType::NewTransaction,
+            )
+        };
+
+        assert_eq!(validate("wasm_code", 100).code, ResultCode::Ok.into());
+        // The tx code is not sponsored
+        assert_eq!(
+            validate("other_code", 100).code,
+            ResultCode::FeeError.into()
+        );
+        // The fees exceed the allowance
+        assert_eq!(
+            validate("wasm_code", 101).code,
+            ResultCode::FeeError.into()
+        );
+    }
+
     // Check that a fee overflow in the wrapper transaction is rejected
     #[test]
     fn test_wrapper_fee_overflow() {
@@ -2985,6 +3197,95 @@ mod shell_tests {
         assert_eq!(shell.state.in_mem().merkle_root(), original_root,);
     }
 
+    /// Test that a node restored from an exported snapshot bootstraps
+    /// CometBFT's state from the restored DB and starts from the height of
+    /// the snapshot
+    #[test]
+    fn test_import_snapshot_and_start() {
+        let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
+        let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
+        let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
+        let new_config = || {
+            let base_dir = tempdir().unwrap().into_path();
+            config::Ledger::new(
+                base_dir.canonicalize().unwrap(),
+                Default::default(),
+                TendermintMode::Validator,
+            )
+        };
+        let start_shell = |config: &config::Ledger| {
+            Shell::<PersistentDB, Sha256Hasher>::new(
+                config.clone(),
+                top_level_directory().join("wasm"),
+                sender.clone(),
+                None,
+                None,
+                None,
+                vp_wasm_compilation_cache,
+                tx_wasm_compilation_cache,
+            )
+        };
+
+        // Run a chain up to the height of the snapshot and export it
+        let config = new_config();
+        let mut shell = start_shell(&config);
+        shell.state.in_mem_mut().block.height = BlockHeight::first();
+        shell
+            .init_chain(test_utils::init_chain_req(BlockHeight::first()), 1)
+            .expect("Test failed");
+        shell.state.commit_block().expect("Test failed");
+        shell.state.in_mem_mut().block.height = BlockHeight(2);
+        shell
+            .state
+            .write(&Key::parse("bing/bong").expect("Test failed"), [1u8; 64])
+            .expect("Test failed");
+        shell.state.commit_block().expect("Test failed");
+        shell.state.db_mut().flush(true).expect("Test failed");
+        let root = shell.state.in_mem().merkle_root();
+        drop(shell);
+        let export_dir = tempdir().unwrap();
+        let meta = export_snapshot(config, None, export_dir.path())
+            .expect("Test failed");
+        assert_eq!(meta.height, BlockHeight(2));
+
+        // Restore a new node from the snapshot
+        let config = new_config();
+        assert!(
+            import_snapshot(config.clone(), export_dir.path(), Hash::zero())
+                .is_err()
+        );
+        import_snapshot(config.clone(), export_dir.path(), meta.root_hash)
+            .expect("Test failed");
+        assert_eq!(
+            crate::bootstrapping_state(&config.chain_dir()),
+            Some(BlockHeight(3))
+        );
+
+        // Start the node. CometBFT's state is bootstrapped with InitChain,
+        // which must return the state of the snapshot.
+        let mut shell = start_shell(&config);
+        assert_eq!(shell.state.in_mem().get_last_block_height(), meta.height);
+        assert_eq!(shell.state.in_mem().merkle_root(), root);
+        assert_eq!(u64::from(shell.last_state().last_block_height), 0);
+        let rsp = shell
+            .init_chain(test_utils::init_chain_req(BlockHeight(3)), 1)
+            .expect("Test failed");
+        assert_eq!(rsp.app_hash.as_bytes(), root.0.as_slice());
+        assert!(!rsp.validators.is_empty());
+        assert_eq!(crate::bootstrapping_state(&config.chain_dir()), None);
+
+        // Once bootstrapped, the node resumes from the snapshot's state
+        let info = shell.last_state();
+        assert_eq!(u64::from(info.last_block_height), meta.height.0);
+        assert_eq!(info.last_block_app_hash.as_bytes(), root.0.as_slice());
+        shell.state.in_mem_mut().block.height = BlockHeight(3);
+        shell.state.commit_block().expect("Test failed");
+        assert_eq!(
+            shell.state.in_mem().get_last_block_height(),
+            BlockHeight(3)
+        );
+    }
+
     /// Helper function for the `test_restore_database_from_snapshot` test
     fn make_snapshot(db_dir: PathBuf, base_dir: PathBuf) -> File {
         let snapshot =
@@ -2992,8 +3293,7 @@ mod shell_tests {
         std::fs::create_dir_all(base_dir.join("snapshots"))
             .expect("Test failed");
         std::fs::create_dir_all(snapshot.0.base()).expect("Test failed");
-        std::fs::create_dir_all(snapshot.0.temp_rocksdb())
-            .expect("Test failed");
+        std::fs::create_dir_all(snapshot.0.temp_db()).expect("Test failed");
         for entry in std::fs::read_dir(db_dir).expect("Test failed") {
             let entry = entry.expect("Test failed");
             let dest_file = snapshot
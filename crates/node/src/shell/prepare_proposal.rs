@@ -20,6 +20,7 @@ use super::block_alloc::states::{
     WithNormalTxs, WithoutNormalTxs,
 };
 use super::block_alloc::{AllocFailure, BlockAllocator, BlockResources};
+use super::tx_fee_priority;
 use crate::config::ValidatorLocalConfig;
 use crate::protocol::{self, ShellParams};
 use crate::shell::ShellMode;
@@ -102,6 +103,13 @@ where
 
     /// Builds a batch of encrypted transactions, retrieved from
     /// CometBFT's mempool.
+    ///
+    /// Wrapper txs are ranked by their [`super::FeePriority`], from the
+    /// highest to the lowest, and greedily allocated in that order. Txs that
+    /// do not fit in the remaining block resources are skipped, such that
+    /// smaller txs further down the ranking may still be included. When the
+    /// `enforce_fee_priority_ordering` protocol parameter is set, blocks that
+    /// are not ordered this way are rejected in `ProcessProposal`.
     fn build_normal_txs(
         &self,
         mut alloc: BlockAllocator<BuildingNormalTxBatch>,
@@ -126,63 +134,63 @@ where
         let mut vp_wasm_cache = self.vp_wasm_cache.clone();
         let mut tx_wasm_cache = self.tx_wasm_cache.clone();
 
-        let txs = txs
+        // NB: the sort is stable, therefore txs with the same priority
+        // preserve the order in which they were received from the mempool
+        let mut ranked_txs: Vec<_> = txs
             .iter()
             .enumerate()
-            .filter_map(|(tx_index, tx_bytes)| {
-                let result = validate_wrapper_bytes(
-                    tx_bytes,
-                    &TxIndex::must_from_usize(tx_index),
-                    block_time,
-                    block_proposer,
-                    proposer_local_config,
-                    &mut temp_state,
-                    &mut vp_wasm_cache,
-                    &mut tx_wasm_cache
-                );
-                match result {
-                    Ok(gas) => {
-                        temp_state.write_log_mut().commit_batch_and_current_tx();
-                        Some((tx_bytes.to_owned(), gas))
-                    },
-                    Err(()) => {
-                        temp_state.write_log_mut().drop_batch();
-                        None
-                    }
-                }
-            })
-            .take_while(|(tx_bytes, tx_gas)| {
-                alloc.try_alloc(BlockResources::new(&tx_bytes[..], tx_gas.to_owned()))
-                    .map_or_else(
-                        |status| match status {
-                            AllocFailure::Rejected { bin_resource_left} => {
-                                tracing::debug!(
-                                    ?tx_bytes,
-                                    bin_resource_left,
-                                    proposal_height =
-                                        ?self.get_current_decision_height(),
-                                    "Dropping encrypted tx from the current proposal",
-                                );
-                                false
-                            }
-                            AllocFailure::OverflowsBin { bin_resource} => {
-                                // TODO(namada#3250): handle tx whose size is greater
-                                // than bin size
-                                tracing::warn!(
-                                    ?tx_bytes,
-                                    bin_resource,
-                                    proposal_height =
-                                        ?self.get_current_decision_height(),
-                                    "Dropping large encrypted tx from the current proposal",
-                                );
-                                true
-                            }
-                        },
-                        |()| true,
-                    )
+            .map(|(tx_index, tx_bytes)| {
+                let priority = tx_fee_priority(tx_bytes, &temp_state);
+                (tx_index, tx_bytes, priority)
             })
-            .map(|(tx, _)| tx)
             .collect();
+        ranked_txs.sort_by(|(_, _, p1), (_, _, p2)| p2.cmp(p1));
+
+        let mut txs = vec![];
+        for (tx_index, tx_bytes, _) in ranked_txs {
+            let result = validate_wrapper_bytes(
+                tx_bytes,
+                &TxIndex::must_from_usize(tx_index),
+                block_time,
+                block_proposer,
+                proposer_local_config,
+                &mut temp_state,
+                &mut vp_wasm_cache,
+                &mut tx_wasm_cache,
+            );
+            let Ok(tx_gas) = result else {
+                temp_state.write_log_mut().drop_batch();
+                continue;
+            };
+            match alloc.try_alloc(BlockResources::new(&tx_bytes[..], tx_gas)) {
+                Ok(()) => {
+                    temp_state.write_log_mut().commit_batch_and_current_tx();
+                    txs.push(tx_bytes.to_owned());
+                }
+                Err(AllocFailure::Rejected { bin_resource_left }) => {
+                    tracing::debug!(
+                        ?tx_bytes,
+                        bin_resource_left,
+                        proposal_height =
+                            ?self.get_current_decision_height(),
+                        "Skipping encrypted tx from the current proposal",
+                    );
+                    temp_state.write_log_mut().drop_batch();
+                }
+                Err(AllocFailure::OverflowsBin { bin_resource }) => {
+                    // TODO(namada#3250): handle tx whose size is greater
+                    // than bin size
+                    tracing::warn!(
+                        ?tx_bytes,
+                        bin_resource,
+                        proposal_height =
+                            ?self.get_current_decision_height(),
+                        "Dropping large encrypted tx from the current proposal",
+                    );
+                    temp_state.write_log_mut().drop_batch();
+                }
+            }
+        }
         let alloc = alloc.next_state();
 
         (txs, alloc)
@@ -307,7 +315,10 @@ where
     let gas_scale = get_gas_scale(temp_state).map_err(|_| ())?;
     let gas_limit =
         wrapper.gas_limit.as_scaled_gas(gas_scale).map_err(|_| ())?;
-    let mut tx_gas_meter = TxGasMeter::new(gas_limit);
+    let mut tx_gas_meter = TxGasMeter::new_with_costs(
+        gas_limit,
+        temp_state.in_mem().gas_cost_schedule,
+    );
     tx_gas_meter.add_wrapper_gas(tx_bytes).map_err(|_| ())?;
 
     super::replay_protection_checks(&tx, temp_state).map_err(|_| ())?;
@@ -426,7 +437,7 @@ mod test_prepare_proposal {
     use namada_apps_lib::wallet;
     use namada_replay_protection as replay_protection;
     use namada_sdk::ethereum_events::EthereumEvent;
-    use namada_sdk::key::RefTo;
+    use namada_sdk::key::{common, RefTo};
     use namada_sdk::proof_of_stake::storage::{
         consensus_validator_set_handle,
         read_consensus_validator_set_addresses_with_stake, read_pos_params,
@@ -1469,4 +1480,120 @@ mod test_prepare_proposal {
 
         assert_eq!(computed_min_gas_price, consensus_min_gas_price);
     }
+
+    /// Build a signed wrapper tx paying fees in the native token.
+    fn native_fee_wrapper(
+        shell: &TestShell,
+        keypair: common::SecretKey,
+        amount_per_gas_unit: u64,
+        gas_limit: u64,
+    ) -> Tx {
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(
+                        amount_per_gas_unit.into(),
+                    ),
+                    token: shell.state.in_mem().native_token.clone(),
+                },
+                keypair.ref_to(),
+                gas_limit.into(),
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.sign_wrapper(keypair);
+        wrapper
+    }
+
+    /// Test that wrapper txs are included in the block by decreasing fee
+    /// priority, regardless of the order in which they were received.
+    #[test]
+    fn test_wrappers_ordered_by_fee_priority() {
+        let (shell, _recv, _, _) = test_utils::setup();
+
+        let low = native_fee_wrapper(
+            &shell,
+            wallet::defaults::daewon_keypair(),
+            1,
+            GAS_LIMIT,
+        );
+        let mid = native_fee_wrapper(
+            &shell,
+            wallet::defaults::bertha_keypair(),
+            5,
+            GAS_LIMIT,
+        );
+        let high = native_fee_wrapper(
+            &shell,
+            wallet::defaults::albert_keypair(),
+            10,
+            GAS_LIMIT,
+        );
+
+        let req = RequestPrepareProposal {
+            txs: vec![
+                low.to_bytes().into(),
+                high.to_bytes().into(),
+                mid.to_bytes().into(),
+            ],
+            ..Default::default()
+        };
+        let received_txs: Vec<Vec<u8>> = shell
+            .prepare_proposal(req)
+            .txs
+            .into_iter()
+            .map(|tx| tx.to_vec())
+            .collect();
+        assert_eq!(
+            received_txs,
+            vec![high.to_bytes(), mid.to_bytes(), low.to_bytes()]
+        );
+    }
+
+    /// Test that a wrapper tx that does not fit in the remaining block gas
+    /// is skipped, without preventing the inclusion of lower priority txs.
+    #[test]
+    fn test_skip_wrapper_exceeding_remaining_block_gas() {
+        let (shell, _recv, _, _) = test_utils::setup();
+
+        let block_gas_limit =
+            namada_sdk::parameters::get_max_block_gas(&shell.state).unwrap();
+
+        let high = native_fee_wrapper(
+            &shell,
+            wallet::defaults::albert_keypair(),
+            10,
+            block_gas_limit - GAS_LIMIT,
+        );
+        // does not fit in the block together with `high`
+        let mid = native_fee_wrapper(
+            &shell,
+            wallet::defaults::bertha_keypair(),
+            5,
+            2 * GAS_LIMIT,
+        );
+        let low = native_fee_wrapper(
+            &shell,
+            wallet::defaults::daewon_keypair(),
+            1,
+            GAS_LIMIT,
+        );
+
+        let req = RequestPrepareProposal {
+            txs: vec![
+                low.to_bytes().into(),
+                mid.to_bytes().into(),
+                high.to_bytes().into(),
+            ],
+            ..Default::default()
+        };
+        let received_txs: Vec<Vec<u8>> = shell
+            .prepare_proposal(req)
+            .txs
+            .into_iter()
+            .map(|tx| tx.to_vec())
+            .collect();
+        assert_eq!(received_txs, vec![high.to_bytes(), low.to_bytes()]);
+    }
 }
@@ -105,8 +105,21 @@ where
                 "Found invalid transactions, proposed block will be rejected"
             );
         }
+        let misordered_txs =
+            parameters::is_fee_priority_ordering_enforced(&self.state)
+                .expect("Must be able to read the fee priority ordering flag")
+                && !self.are_wrappers_ordered_by_fee_priority(&req.txs);
+        if misordered_txs {
+            tracing::warn!(
+                proposer = ?HEXUPPER.encode(&req.proposer_address),
+                height = req.height,
+                hash = ?HEXUPPER.encode(&req.hash),
+                "Found wrapper transactions not ordered by fee priority, \
+                 proposed block will be rejected"
+            );
+        }
         (
-            if invalid_txs {
+            if invalid_txs || misordered_txs {
                 ProcessProposal::Reject
             } else {
                 ProcessProposal::Accept
@@ -115,6 +128,28 @@ where
         )
     }
 
+    /// Check that the wrapper txs in a proposal are ordered by
+    /// non-increasing [`FeePriority`], as done in `PrepareProposal`. Any
+    /// other kind of tx is ignored.
+    pub fn are_wrappers_ordered_by_fee_priority(
+        &self,
+        txs: &[TxBytes],
+    ) -> bool {
+        let mut last_priority: Option<FeePriority> = None;
+        for tx_bytes in txs {
+            // NB: wrappers whose priority cannot be computed are invalid,
+            // and would have been rejected by `process_txs` anyway
+            let Some(priority) = tx_fee_priority(tx_bytes, &self.state) else {
+                continue;
+            };
+            if last_priority.is_some_and(|last| priority > last) {
+                return false;
+            }
+            last_priority = Some(priority);
+        }
+        true
+    }
+
     /// Evaluates the corresponding [`TxResult`] for each tx in the
     /// proposal.
     ///
@@ -439,7 +474,10 @@ where
                         };
                     }
                 };
-                let mut tx_gas_meter = TxGasMeter::new(gas_limit);
+                let mut tx_gas_meter = TxGasMeter::new_with_costs(
+                    gas_limit,
+                    temp_state.in_mem().gas_cost_schedule,
+                );
                 if tx_gas_meter.add_wrapper_gas(tx_bytes).is_err()
                     || allocated_gas.is_err()
                 {
@@ -1871,4 +1909,69 @@ mod test_process_proposal {
             assert!(rsp.is_ok());
         }
     }
+
+    /// Test that, when enabled by the protocol parameter, wrapper txs that
+    /// are not ordered by decreasing fee priority cause a block rejection.
+    #[test]
+    fn test_enforce_fee_priority_ordering() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+
+        let make_wrapper = |keypair: common::SecretKey, price: u64| {
+            let mut wrapper =
+                Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                    Fee {
+                        amount_per_gas_unit: DenominatedAmount::native(
+                            price.into(),
+                        ),
+                        token: shell.state.in_mem().native_token.clone(),
+                    },
+                    keypair.ref_to(),
+                    GAS_LIMIT.into(),
+                ))));
+            wrapper.header.chain_id = shell.chain_id.clone();
+            wrapper
+                .set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+            wrapper
+                .set_data(Data::new("transaction data".as_bytes().to_owned()));
+            wrapper.sign_wrapper(keypair);
+            wrapper.to_bytes()
+        };
+        let low = make_wrapper(wallet::defaults::albert_keypair(), 1);
+        let high = make_wrapper(wallet::defaults::bertha_keypair(), 10);
+
+        // the ordering is not enforced by default
+        let request = ProcessProposal {
+            txs: vec![low.clone(), high.clone()],
+        };
+        assert!(shell.process_proposal(request).is_ok());
+
+        shell
+            .state
+            .write(
+                &parameters::storage::get_enforce_fee_priority_ordering_key(),
+                true,
+            )
+            .unwrap();
+
+        let request = ProcessProposal {
+            txs: vec![low.clone(), high.clone()],
+        };
+        match shell.process_proposal(request) {
+            Ok(_) => panic!("Test failed"),
+            Err(TestError::RejectProposal(response)) => {
+                // the txs are valid, only their ordering is not
+                for processed in response {
+                    assert_eq!(
+                        processed.result.code,
+                        u32::from(ResultCode::Ok)
+                    );
+                }
+            }
+        }
+
+        let request = ProcessProposal {
+            txs: vec![high, low],
+        };
+        assert!(shell.process_proposal(request).is_ok());
+    }
 }
@@ -1,19 +1,20 @@
 use std::io::Write;
+use std::path::Path;
 
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use namada_sdk::arith::checked;
 use namada_sdk::hash::{Hash, Sha256Hasher};
-use namada_sdk::state::{BlockHeight, StorageRead};
+use namada_sdk::state::{BlockHeight, StorageRead, DB};
 
-use super::SnapshotSync;
+use super::{Error, ShellResult, SnapshotSync};
 use crate::shell::Shell;
-use crate::storage;
-use crate::storage::{DbSnapshot, DbSnapshotMeta};
+use crate::storage::{DbSnapshot, DbSnapshotMeta, SnapshotPath};
 use crate::tendermint::abci::types::Snapshot;
 use crate::tendermint::abci::{
     request as tm_request, response as tm_response, ApplySnapshotChunkResult,
 };
+use crate::{config, storage, tendermint_node};
 
 pub const MAX_SENDER_STRIKES: u64 = 5;
 
@@ -271,3 +272,136 @@ impl Shell<storage::PersistentDB, Sha256Hasher> {
         }
     }
 }
+
+/// Export a snapshot of the DB at the given height (or at the last committed
+/// block, if not specified) into the `out_dir` directory. A snapshot stored
+/// by the node at this height is exported as is, otherwise a new snapshot can
+/// only be taken of the last committed block.
+pub fn export_snapshot(
+    config: config::Ledger,
+    height: Option<BlockHeight>,
+    out_dir: &Path,
+) -> ShellResult<DbSnapshotMeta> {
+    storage::check_storage_backend(config.shell.storage_backend)?;
+    let base_dir = config.shell.base_dir.clone();
+
+    let db = storage::open(config.db_dir(), true, None)
+        .map_err(|e| Error::Storage(namada_sdk::state::Error::new(e)))?;
+    let last_height = db
+        .read_last_block()
+        .map_err(|e| Error::Storage(namada_sdk::state::Error::new(e)))?
+        .map(|block| block.height)
+        .ok_or_else(|| {
+            Error::Snapshot(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "The DB has no committed blocks",
+            ))
+        })?;
+    let height = height.unwrap_or(last_height);
+
+    let stored_heights =
+        match DbSnapshot::heights_of_stored_snapshots(&base_dir) {
+            Ok(heights) => heights,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(Error::Snapshot(e)),
+        };
+    if stored_heights.contains(&height.0) {
+        tracing::info!("Exporting the stored snapshot at height {height}");
+        return DbSnapshot(SnapshotPath(base_dir, height))
+            .export(out_dir)
+            .map_err(Error::Snapshot);
+    }
+    if height != last_height {
+        return Err(Error::Snapshot(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "No snapshot is stored at height {height}. A new snapshot can \
+                 only be taken at the last committed height {last_height}."
+            ),
+        )));
+    }
+
+    tracing::info!("Taking a new snapshot at height {height}");
+    let snapshot = db
+        .checkpoint(base_dir, height)
+        .map_err(|e| Error::Storage(namada_sdk::state::Error::new(e)))?;
+    drop(db);
+    let snapshot_path = snapshot.0.clone();
+    snapshot.package().map_err(Error::Snapshot)?;
+    let exported = DbSnapshot(snapshot_path.clone()).export(out_dir);
+    // the snapshot was only taken to be exported, it's not kept around
+    snapshot_path.remove().map_err(Error::Snapshot)?;
+    exported.map_err(Error::Snapshot)
+}
+
+/// Verify a snapshot exported into the `snapshot_dir` directory and restore
+/// the DB from it, without going through state sync. The root hash of the
+/// snapshot must match the trusted `root_hash`. The DB must be behind the
+/// height of the snapshot. CometBFT's state is reset, to be bootstrapped from
+/// the restored DB on the next start of the ledger, such that its chain
+/// resumes right after the height of the snapshot.
+pub fn import_snapshot(
+    config: config::Ledger,
+    snapshot_dir: &Path,
+    root_hash: Hash,
+) -> ShellResult<DbSnapshotMeta> {
+    storage::check_storage_backend(config.shell.storage_backend)?;
+    let (meta, mut tarball) =
+        DbSnapshot::load_exported(snapshot_dir, root_hash)
+            .map_err(Error::Snapshot)?;
+
+    // NB: the DB is closed right after the restore, so it doesn't need a
+    // block cache
+    let db_cache = storage::DbCache::new_lru_cache(0);
+    let mut db = storage::PersistentDB::open(config.db_dir(), Some(&db_cache));
+    let read_last_height = |db: &storage::PersistentDB| {
+        db.read_last_block()
+            .map(|block| block.map(|block| block.height))
+            .map_err(|e| Error::Storage(namada_sdk::state::Error::new(e)))
+    };
+    if let Some(last_height) = read_last_height(&db)? {
+        if last_height >= meta.height {
+            return Err(Error::Snapshot(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "The DB is already at height {last_height}, which is not \
+                     behind the snapshot's height {}",
+                    meta.height
+                ),
+            )));
+        }
+    }
+
+    tracing::info!(
+        "Restoring the DB from the snapshot at height {}",
+        meta.height
+    );
+    db.restore_from((&db_cache, &mut tarball))
+        .map_err(|e| Error::Storage(namada_sdk::state::Error::new(e)))?;
+    let restored_height = read_last_height(&db)?;
+    if restored_height != Some(meta.height) {
+        return Err(Error::Snapshot(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "The restored DB is at height {restored_height:?}, but the \
+                 snapshot is of height {}",
+                meta.height
+            ),
+        )));
+    }
+    drop(db);
+
+    let cometbft_dir = config.cometbft_dir();
+    if cometbft_dir.exists() {
+        tracing::info!("Resetting CometBFT's state");
+        tendermint_node::reset_state(cometbft_dir)
+            .map_err(Error::Tendermint)?;
+    }
+    crate::start_bootstrapping_state(
+        &config.chain_dir(),
+        meta.height.next_height(),
+    )
+    .map_err(Error::Snapshot)?;
+
+    Ok(meta)
+}
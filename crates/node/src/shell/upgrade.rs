@@ -0,0 +1,196 @@
+//! Upgrades of the node binary approved by governance.
+//!
+//! A passed upgrade proposal schedules an [`UpgradePlan`]. The block at the
+//! upgrade height must be finalized by the binary whose hash is in the plan:
+//! any other binary halts before finalizing it, and the operator restarts the
+//! node on the new binary. If the plan has a DB migration, the new binary
+//! applies it when committing the upgrade block. The migration is read from
+//! the `upgrades` directory of the chain, from a file named after the upgrade
+//! (e.g. `upgrades/v1.1.0.json`), and must match the hash in the plan.
+
+use namada_sdk::governance::storage::get_upgrade_plan;
+use namada_sdk::governance::storage::proposal::UpgradePlan;
+
+use super::*;
+
+/// The directory of the chain with the DB migrations of the upgrade plans
+pub const UPGRADES_DIR: &str = "upgrades";
+
+/// Compute the SHA-256 hash of the running binary
+pub fn running_binary_hash() -> std::io::Result<Hash> {
+    let path = std::env::current_exe()?;
+    std::fs::read(path).map(Hash::sha256)
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Check the upgrade plan before finalizing the block at the given
+    /// height. Returns an error, halting the node, if the block is at the
+    /// upgrade height and this isn't the binary of the plan. Otherwise,
+    /// schedules the DB migration of the plan, if any.
+    pub(super) fn check_upgrade_plan(
+        &mut self,
+        height: BlockHeight,
+    ) -> ShellResult<()> {
+        let Some(plan) = get_upgrade_plan(&self.state)? else {
+            return Ok(());
+        };
+        if plan.height != height {
+            return Ok(());
+        }
+
+        let binary_hash = match self.binary_hash {
+            Some(hash) => hash,
+            None => {
+                let hash = running_binary_hash().map_err(|err| {
+                    Error::Upgrade(format!(
+                        "Failed to compute the hash of the running binary for \
+                         the upgrade {}: {err}",
+                        plan.name
+                    ))
+                })?;
+                *self.binary_hash.insert(hash)
+            }
+        };
+        if binary_hash != plan.binary_hash {
+            tracing::error!(
+                "Reached the height {height} of the upgrade {}, halting the \
+                 chain. Restart the node with the new binary.",
+                plan.name
+            );
+            return Err(Error::Upgrade(format!(
+                "The upgrade {} requires the binary with the hash {}, but the \
+                 hash of the running binary is {binary_hash}",
+                plan.name, plan.binary_hash
+            )));
+        }
+        tracing::info!(
+            "Reached the height {height} of the upgrade {}, running the new \
+             binary.",
+            plan.name
+        );
+
+        self.schedule_upgrade_migration(&plan)
+    }
+
+    /// Schedule the DB migration of the upgrade plan at the upgrade height.
+    /// A migration scheduled from the command line at that height must be the
+    /// one of the plan.
+    fn schedule_upgrade_migration(
+        &mut self,
+        plan: &UpgradePlan,
+    ) -> ShellResult<()> {
+        let Some(migration_hash) = plan.migration_hash else {
+            return Ok(());
+        };
+        if let Some(migration) = self
+            .scheduled_migration
+            .as_ref()
+            .filter(|migration| migration.height == plan.height)
+        {
+            return if migration.hash == migration_hash {
+                Ok(())
+            } else {
+                Err(Error::Upgrade(format!(
+                    "The migration scheduled at the height {} has the hash \
+                     {}, but the upgrade {} requires the migration with the \
+                     hash {migration_hash}",
+                    migration.height, migration.hash, plan.name
+                )))
+            };
+        }
+
+        let path = self
+            .base_dir
+            .join(self.chain_id.as_str())
+            .join(UPGRADES_DIR)
+            .join(format!("{}.json", plan.name));
+        let migration =
+            ScheduledMigration::from_path(&path, migration_hash, plan.height)
+                .map_err(|err| {
+                Error::Upgrade(format!(
+                    "Failed to load the migration of the upgrade {} from {}: \
+                     {err}",
+                    plan.name,
+                    path.to_string_lossy()
+                ))
+            })?;
+        tracing::info!(
+            "Scheduled the migration of the upgrade {} from {}.",
+            plan.name,
+            path.to_string_lossy()
+        );
+        self.scheduled_migration = Some(migration);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_upgrade {
+    use namada_sdk::governance::storage::write_upgrade_plan;
+
+    use super::*;
+    use crate::shell::test_utils::{FinalizeBlock, setup_at_height};
+
+    fn plan(height: u64, migration: Option<&[u8]>) -> UpgradePlan {
+        UpgradePlan {
+            name: "v1.1.0".to_string(),
+            height: BlockHeight(height),
+            binary_hash: Hash::sha256(b"new binary"),
+            migration_hash: migration.map(Hash::sha256),
+        }
+    }
+
+    /// Test that the old binary halts at the upgrade height
+    #[test]
+    fn test_upgrade_halts_old_binary() {
+        let (mut shell, _, _, _) = setup_at_height(3u64);
+        shell.binary_hash = Some(Hash::sha256(b"old binary"));
+        write_upgrade_plan(&mut shell.state, plan(5, None)).unwrap();
+        shell.state.commit_block().unwrap();
+
+        // the blocks before the upgrade height are finalized
+        shell.finalize_and_commit(None);
+        assert_eq!(shell.state.in_mem().get_last_block_height().0, 4);
+
+        let result = shell.finalize_block(FinalizeBlock::default());
+        assert!(matches!(result, Err(Error::Upgrade(_))));
+        assert_eq!(shell.state.in_mem().get_last_block_height().0, 4);
+    }
+
+    /// Test that the new binary requires the migration of the plan at the
+    /// upgrade height and schedules it
+    #[test]
+    fn test_upgrade_schedules_migration() {
+        let (mut shell, _, _, _) = setup_at_height(3u64);
+        shell.binary_hash = Some(Hash::sha256(b"new binary"));
+        let migration = br#"{"changes":[]}"#;
+        write_upgrade_plan(&mut shell.state, plan(4, Some(&migration[..])))
+            .unwrap();
+        shell.state.commit_block().unwrap();
+
+        // the migration of the plan is required
+        let result = shell.finalize_block(FinalizeBlock::default());
+        assert!(matches!(result, Err(Error::Upgrade(_))));
+        assert!(shell.scheduled_migration.is_none());
+
+        let dir = shell
+            .base_dir
+            .join(shell.chain_id.as_str())
+            .join(UPGRADES_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("v1.1.0.json"), b"{}").unwrap();
+        let result = shell.check_upgrade_plan(BlockHeight(4));
+        assert!(matches!(result, Err(Error::Upgrade(_))));
+
+        std::fs::write(dir.join("v1.1.0.json"), migration).unwrap();
+        shell.check_upgrade_plan(BlockHeight(4)).unwrap();
+        let scheduled = shell.scheduled_migration.as_ref().unwrap();
+        assert_eq!(scheduled.height, BlockHeight(4));
+        assert_eq!(scheduled.hash, Hash::sha256(migration));
+    }
+}
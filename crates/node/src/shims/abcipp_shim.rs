@@ -25,7 +25,7 @@ use super::abcipp_shim_types::shim::{
 use crate::config;
 use crate::config::{Action, ActionAtHeight};
 use crate::shell::{EthereumOracleChannels, Shell};
-use crate::storage::DbSnapshot;
+use crate::storage::{DbCache, DbSnapshot};
 use crate::tendermint::abci::{request, Request as Req, Response as Resp};
 use crate::tower_abci::BoxError;
 
@@ -54,7 +54,7 @@ impl AbcippShim {
         wasm_dir: PathBuf,
         broadcast_sender: UnboundedSender<Vec<u8>>,
         eth_oracle: Option<EthereumOracleChannels>,
-        db_cache: &rocksdb::Cache,
+        db_cache: &DbCache,
         scheduled_migration: Option<ScheduledMigration>,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
@@ -1,23 +1,61 @@
 //! The storage module handles both the current state in-memory and the stored
 //! state in DB.
 
+#[cfg(not(any(feature = "rocksdb", feature = "redb")))]
+compile_error!(
+    "A storage backend must be enabled with the `rocksdb` or `redb` feature"
+);
+
+#[cfg(feature = "redb")]
+mod redb;
+#[cfg(feature = "rocksdb")]
+#[cfg_attr(feature = "redb", allow(dead_code))]
 mod rocksdb;
 
 use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use arse_merkle_tree::blake2b::Blake2bHasher;
 use arse_merkle_tree::traits::Hasher;
 use arse_merkle_tree::H256;
 use blake2b_rs::{Blake2b, Blake2bBuilder};
-use namada_sdk::state::{FullAccessState, StorageHasher};
-#[cfg(test)]
-pub use rocksdb::SnapshotPath;
-pub use rocksdb::{open, DbSnapshot, DbSnapshotMeta, RocksDBUpdateVisitor};
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use namada_apps_lib::config::StorageBackend;
+use namada_sdk::arith::checked;
+use namada_sdk::hash::Hash;
+use namada_sdk::state::{DbError, DbResult, FullAccessState, StorageHasher, DB};
+use namada_sdk::storage::{BlockHeight, Key, KeySeg};
+#[cfg(feature = "redb")]
+pub use redb::open;
+#[cfg(not(feature = "redb"))]
+pub use rocksdb::open;
+
+use crate::shell::{Error, ShellResult};
 
 #[derive(Default)]
 pub struct PersistentStorageHasher(Blake2bHasher);
 
+/// The storage backend that the node is built with
+#[cfg(not(feature = "redb"))]
+pub const STORAGE_BACKEND: StorageBackend = StorageBackend::RocksDb;
+/// The storage backend that the node is built with
+#[cfg(feature = "redb")]
+pub const STORAGE_BACKEND: StorageBackend = StorageBackend::Redb;
+
+#[cfg(not(feature = "redb"))]
 pub type PersistentDB = rocksdb::RocksDB;
+#[cfg(feature = "redb")]
+pub type PersistentDB = redb::RedbDB;
+
+#[cfg(not(feature = "redb"))]
+pub type PersistentDBUpdateVisitor<'db> = rocksdb::RocksDBUpdateVisitor<'db>;
+#[cfg(feature = "redb")]
+pub type PersistentDBUpdateVisitor<'db> = redb::RedbUpdateVisitor<'db>;
+
+pub type DbCache = <PersistentDB as DB>::Cache;
 
 pub type PersistentState =
     FullAccessState<PersistentDB, PersistentStorageHasher>;
@@ -52,9 +90,1344 @@ fn new_blake2b() -> Blake2b {
     Blake2bBuilder::new(32).personal(b"namada storage").build()
 }
 
+// The keys of the storage tree, which is shared by all the DB backends (see
+// the `rocksdb` module for its layout)
+const BLOCK_HEIGHT_KEY: &str = "height";
+const NEXT_EPOCH_MIN_START_HEIGHT_KEY: &str = "next_epoch_min_start_height";
+const NEXT_EPOCH_MIN_START_TIME_KEY: &str = "next_epoch_min_start_time";
+const UPDATE_EPOCH_BLOCKS_DELAY_KEY: &str = "update_epoch_blocks_delay";
+const COMMIT_ONLY_DATA_KEY: &str = "commit_only_data_commitment";
+const CONVERSION_STATE_KEY: &str = "conversion_state";
+const ETHEREUM_HEIGHT_KEY: &str = "ethereum_height";
+const ETH_EVENTS_QUEUE_KEY: &str = "eth_events_queue";
+const RESULTS_KEY_PREFIX: &str = "results";
+const PRED_KEY_PREFIX: &str = "pred";
+const ARCHIVE_START_HEIGHT_KEY: &str = "archive_start_height";
+
+const MERKLE_TREE_ROOT_KEY_SEGMENT: &str = "root";
+const MERKLE_TREE_STORE_KEY_SEGMENT: &str = "store";
+const BLOCK_HEADER_KEY_SEGMENT: &str = "header";
+const BLOCK_TIME_KEY_SEGMENT: &str = "time";
+const EPOCH_KEY_SEGMENT: &str = "epoch";
+const PRED_EPOCHS_KEY_SEGMENT: &str = "pred_epochs";
+const ADDRESS_GEN_KEY_SEGMENT: &str = "address_gen";
+
+const OLD_DIFF_PREFIX: &str = "old";
+const NEW_DIFF_PREFIX: &str = "new";
+
+// NB: the separator must sort before `/`, such that the history of a key is
+// never interleaved with the history of its sub-keys
+const HISTORY_KEY_SEPARATOR: char = '\0';
+
+// 10 MB
+const MAX_STATE_SYNC_CHUNK_SIZE: usize = 10_000_000;
+
+/// The file name of the tarball of an exported snapshot
+const EXPORTED_TARBALL_FILE: &str = "db.tar.zst";
+/// The file name of the [`DbSnapshotMeta`] of an exported snapshot
+const EXPORTED_META_FILE: &str = "snapshot-meta";
+
+/// The old and new values of a subspace key modified at some height, as
+/// stored in the history CF in archive mode.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct HistoryEntry {
+    pub old: Option<Vec<u8>>,
+    pub new: Option<Vec<u8>>,
+}
+
+/// The path to a snapshot.
+#[derive(Clone, Debug)]
+pub struct SnapshotPath(pub PathBuf, pub BlockHeight);
+
+impl SnapshotPath {
+    /// Return the root path where snapshots are stored.
+    pub fn snapshot_root_path(mut base_dir: PathBuf) -> PathBuf {
+        base_dir.push("snapshots");
+        base_dir
+    }
+
+    /// Remove all data pertaining to the current snapshot.
+    pub fn remove(&self) -> std::io::Result<()> {
+        std::fs::remove_dir_all(self.base())
+    }
+
+    /// Return the base path associated with this [`SnapshotPath`].
+    pub fn base(&self) -> PathBuf {
+        let mut buf = Self::snapshot_root_path(self.0.clone());
+        let height = self.1.0;
+        buf.push(format!("block-{height:016}"));
+        buf
+    }
+
+    /// Return the chunk hashes path associated with this [`SnapshotPath`].
+    pub fn chunk_hashes(&self) -> PathBuf {
+        let mut buf = self.base();
+        buf.push("chunks-hashed");
+        buf
+    }
+
+    /// Return the root of the chunk hashes tree path associated with this
+    /// [`SnapshotPath`].
+    pub fn chunks_root_hash(&self) -> PathBuf {
+        let mut buf = self.base();
+        buf.push("chunks-root-hash");
+        buf
+    }
+
+    /// Return the temporary DB path associated with this [`SnapshotPath`].
+    pub fn temp_db(&self) -> PathBuf {
+        let mut buf = self.base();
+        buf.push("db");
+        buf
+    }
+
+    /// Return the temporary tarball path associated with this [`SnapshotPath`].
+    ///
+    /// The value of `compression_extension` should reflect the compression
+    /// algorithm used (e.g. `gz` for Gzip).
+    pub fn temp_tarball(&self, compression_extension: &str) -> PathBuf {
+        let mut buf = self.base();
+        buf.push(format!("db.tar.{compression_extension}"));
+        buf
+    }
+
+    /// Return the path of the chunk `chk` associated with this
+    /// [`SnapshotPath`].
+    pub fn chunk_with_id(&self, chk: usize) -> PathBuf {
+        let mut buf = self.base();
+        buf.push(format!("chunk-{chk:032}"));
+        buf
+    }
+}
+
+/// Metadata pertaining to some database snapshot.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct DbSnapshotMeta {
+    /// The height of the snapshot.
+    pub height: BlockHeight,
+    /// List of the hashes of all chunks.
+    pub chunk_hashes: Vec<Hash>,
+    /// Hash of all the chunk hashes, forming a shallow tree.
+    pub root_hash: Hash,
+}
+
+#[derive(Clone)]
+pub struct DbSnapshot(pub SnapshotPath);
+
+impl DbSnapshot {
+    /// The magic number referring to the format of the snapshot. The
+    /// snapshots contain the raw DB files, hence the format depends on the
+    /// storage backend.
+    #[cfg(not(feature = "redb"))]
+    pub const FORMAT_MAGIC: u32 = 0;
+    /// The magic number referring to the format of the snapshot. The
+    /// snapshots contain the raw DB files, hence the format depends on the
+    /// storage backend.
+    #[cfg(feature = "redb")]
+    pub const FORMAT_MAGIC: u32 = 1;
+
+    /// Package and chunk the contents of the db snapshot.
+    // NB: passing an owned `self` guarantees we don't attempt to call
+    // this method again, which removes the temporary checkpoint dir
+    // created by rocksdb
+    pub fn package(self) -> std::io::Result<()> {
+        self.build_tarball()?;
+        self.chunk_snapshot(MAX_STATE_SYNC_CHUNK_SIZE)?;
+        Ok(())
+    }
+
+    pub fn unpack(
+        archive_file: &mut std::fs::File,
+        dest: impl AsRef<Path>,
+    ) -> std::io::Result<()> {
+        use zstd::stream::read::Decoder;
+
+        let file_buf_reader = std::io::BufReader::new(archive_file);
+        let zstd_decoder = Decoder::new(file_buf_reader)?;
+
+        let mut archive = tar::Archive::new(zstd_decoder);
+        archive.unpack(dest)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn build_tarball(&self) -> std::io::Result<()> {
+        use zstd::stream::write::Encoder;
+
+        let snapshot_temp_db_path = self.0.temp_db();
+
+        let mut tar_builder = {
+            let file_handle = File::create(self.0.temp_tarball("zst"))?;
+            let zstd_encoder = Encoder::new(file_handle, 0)?.auto_finish();
+            tar::Builder::new(zstd_encoder)
+        };
+
+        // build tarball with rocksdb checkpoint contents
+        tar_builder.append_dir_all("db", &snapshot_temp_db_path)?;
+        tar_builder.finish()?;
+        _ = tar_builder;
+
+        // remove aux checkpoint dir
+        std::fs::remove_dir_all(&snapshot_temp_db_path)
+    }
+
+    fn chunk_snapshot(&self, max_chunk: usize) -> std::io::Result<()> {
+        let tarball_path = self.0.temp_tarball("zst");
+
+        let mut buf = vec![0; max_chunk];
+        let mut file = File::open(&tarball_path)?;
+
+        let mut eof = false;
+        let mut chunk_hashes = vec![];
+
+        // TODO: we can use tokio here to read chunks
+        // in parallel
+        //
+        // 1. determine tar archive size
+        // 2. spawn len / MAX_STATE_SYNC_CHUNK_SIZE tasks
+        // 3. spawn one more task if necessary to read chunk smaller than
+        //    MAX_STATE_SYNC_CHUNK_SIZE
+        // 4. assemble read data (need to store hash of the chunk)
+
+        for chunk_id in 0.. {
+            let mut read = 0;
+
+            // read up to `MAX_STATE_SYNC_CHUNK_SIZE` bytes
+            while read != max_chunk {
+                match file.read(&mut buf[read..]) {
+                    Ok(0) => {
+                        eof = true;
+                        break;
+                    }
+                    Ok(n) => checked!(read += n).unwrap(),
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let chunk = &buf[..read];
+
+            std::fs::write(self.0.chunk_with_id(chunk_id), chunk)?;
+            chunk_hashes.push(Hash::sha256(chunk));
+
+            if eof {
+                break;
+            }
+        }
+
+        let snapshot_hash = Self::root_hash(&chunk_hashes);
+        let chunk_hashes = chunk_hashes.serialize_to_vec();
+
+        std::fs::remove_file(tarball_path)?;
+        std::fs::write(self.0.chunk_hashes(), chunk_hashes)?;
+        std::fs::write(self.0.chunks_root_hash(), snapshot_hash)?;
+
+        Ok(())
+    }
+
+    /// Compute the root hash of a snapshot from the hashes of its chunks.
+    fn root_hash(chunk_hashes: &[Hash]) -> Hash {
+        let hash_of_all_chunks = Hash::sha256(chunk_hashes.serialize_to_vec());
+        Hash::sha256(
+            (Self::FORMAT_MAGIC, hash_of_all_chunks).serialize_to_vec(),
+        )
+    }
+
+    /// Export the snapshot into the `dest` directory, such that it can be
+    /// handed around as a file. The chunks are put back together into a
+    /// single tarball, which is written next to the snapshot's metadata.
+    pub fn export(&self, dest: &Path) -> std::io::Result<DbSnapshotMeta> {
+        let meta = Self::load_snapshot_metadata(&self.0.0, [self.0.1.0])
+            .next()
+            .expect("The metadata of a snapshot height must be loaded")?;
+
+        std::fs::create_dir_all(dest)?;
+        let mut tarball = File::create(dest.join(EXPORTED_TARBALL_FILE))?;
+        for chunk_id in 0..meta.chunk_hashes.len() {
+            let mut chunk = File::open(self.0.chunk_with_id(chunk_id))?;
+            std::io::copy(&mut chunk, &mut tarball)?;
+        }
+        tarball.sync_all()?;
+        std::fs::write(dest.join(EXPORTED_META_FILE), meta.serialize_to_vec())?;
+
+        Ok(meta)
+    }
+
+    /// Load a snapshot that was exported into the `src` directory and verify
+    /// its tarball against the chunk hashes and the root hash in its
+    /// metadata, which must match the trusted `root_hash`. Returns the
+    /// metadata and the tarball to restore the DB from.
+    pub fn load_exported(
+        src: &Path,
+        root_hash: Hash,
+    ) -> std::io::Result<(DbSnapshotMeta, File)> {
+        let invalid = |msg: String| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+        };
+
+        let meta = DbSnapshotMeta::try_from_slice(&std::fs::read(
+            src.join(EXPORTED_META_FILE),
+        )?)?;
+        // NB: the format is committed to by the root hash, so this also
+        // rejects snapshots of a different storage backend
+        if Self::root_hash(&meta.chunk_hashes) != meta.root_hash {
+            return Err(invalid(format!(
+                "The chunk hashes of the snapshot don't match its root hash \
+                 {}, or the snapshot has a different format",
+                meta.root_hash
+            )));
+        }
+        if root_hash != meta.root_hash {
+            return Err(invalid(format!(
+                "The root hash of the snapshot {} doesn't match the trusted \
+                 root hash {root_hash}",
+                meta.root_hash
+            )));
+        }
+
+        let mut tarball = File::open(src.join(EXPORTED_TARBALL_FILE))?;
+        let mut buf = Vec::with_capacity(MAX_STATE_SYNC_CHUNK_SIZE);
+        for (chunk_id, expected_hash) in meta.chunk_hashes.iter().enumerate() {
+            buf.clear();
+            (&mut tarball)
+                .take(MAX_STATE_SYNC_CHUNK_SIZE as u64)
+                .read_to_end(&mut buf)?;
+            let chunk_hash = Hash::sha256(&buf);
+            if chunk_hash != *expected_hash {
+                return Err(invalid(format!(
+                    "Hash of the snapshot chunk {chunk_id} did not match, \
+                     expected {expected_hash}, got {chunk_hash}"
+                )));
+            }
+        }
+        if tarball.read(&mut [0])? != 0 {
+            return Err(invalid(
+                "The snapshot tarball is longer than its chunks".to_string(),
+            ));
+        }
+
+        Ok((meta, tarball))
+    }
+
+    /// Keep `number_to_keep` latest snapshots. All others
+    /// are deleted.
+    pub fn cleanup(
+        latest_height: BlockHeight,
+        base_dir: &Path,
+        number_to_keep: u64,
+    ) -> std::io::Result<()> {
+        let latest_height = latest_height.0;
+
+        for height in Self::heights_of_stored_snapshots(base_dir)? {
+            // this is correct... don't worry about it
+            if checked!(height + number_to_keep <= latest_height).unwrap() {
+                let snap = SnapshotPath(base_dir.into(), BlockHeight(height));
+                snap.remove()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the metadata of the given snapshot heights.
+    pub fn load_snapshot_metadata(
+        base_dir: &Path,
+        snapshot_heights: impl IntoIterator<Item = u64>,
+    ) -> impl Iterator<Item = std::io::Result<DbSnapshotMeta>> {
+        let mut iter = snapshot_heights.into_iter();
+        let base_dir = base_dir.to_owned();
+
+        std::iter::from_fn(move || {
+            let height = BlockHeight(iter.next()?);
+
+            let load = || {
+                let snap = SnapshotPath(base_dir.clone(), height);
+
+                let chunk_hashes = BorshDeserialize::try_from_slice(
+                    &std::fs::read(snap.chunk_hashes())?,
+                )?;
+                let root_hash = BorshDeserialize::try_from_slice(
+                    &std::fs::read(snap.chunks_root_hash())?,
+                )?;
+
+                Ok(DbSnapshotMeta {
+                    height,
+                    chunk_hashes,
+                    root_hash,
+                })
+            };
+
+            Some(load())
+        })
+    }
+
+    /// List all block heights whose state snapshots exist.
+    pub fn heights_of_stored_snapshots(
+        base_dir: &Path,
+    ) -> std::io::Result<Vec<u64>> {
+        let snapshot_root = SnapshotPath::snapshot_root_path(base_dir.into());
+        let mut heights = vec![];
+
+        for entry in std::fs::read_dir(snapshot_root)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+
+            if entry_path.is_dir() {
+                let Some(file_name) =
+                    entry_path.file_name().and_then(|f| f.to_str())
+                else {
+                    continue;
+                };
+                let Some(("block", height_str)) = file_name.split_once('-')
+                else {
+                    continue;
+                };
+                let Some(height): Option<u64> = height_str.parse().ok() else {
+                    continue;
+                };
+                heights.push(height);
+            }
+        }
+
+        Ok(heights)
+    }
+
+    /// Load the specified chunk of a snapshot at the given block height
+    pub fn load_chunk(
+        height: BlockHeight,
+        chunk: u64,
+        base_dir: &Path,
+    ) -> std::io::Result<Vec<u8>> {
+        let snap = SnapshotPath(base_dir.into(), height);
+        #[allow(clippy::cast_possible_truncation)]
+        std::fs::read(snap.chunk_with_id(chunk as _))
+    }
+}
+
+fn old_and_new_diff_key(
+    key: &Key,
+    height: BlockHeight,
+) -> DbResult<(String, String)> {
+    let key_prefix = Key::from(height.to_db_key());
+    let old = key_prefix
+        .push(&OLD_DIFF_PREFIX.to_owned())
+        .map_err(DbError::KeyError)?
+        .join(key);
+    let new = key_prefix
+        .push(&NEW_DIFF_PREFIX.to_owned())
+        .map_err(DbError::KeyError)?
+        .join(key);
+    Ok((old.to_string(), new.to_string()))
+}
+
+/// The prefix of all the history entries of the given subspace key.
+fn history_key_prefix(key: impl std::fmt::Display) -> String {
+    format!("{key}{HISTORY_KEY_SEPARATOR}")
+}
+
+/// The history entry of the given subspace key at the given height.
+fn history_key(key: impl std::fmt::Display, height: BlockHeight) -> String {
+    // NB: the raw height segment is a fixed-length encoding that preserves
+    // the ordering of heights
+    format!("{}{}", history_key_prefix(key), height.raw())
+}
+
+/// The first key of the history CF ordered after all the history entries of
+/// the given subspace key.
+fn history_key_upper_bound(key: impl std::fmt::Display) -> String {
+    // NB: `\u{1}` is the successor of the `HISTORY_KEY_SEPARATOR`, which
+    // never occurs in subspace keys
+    format!("{key}\u{1}")
+}
+
+/// Split a key of the history CF into its subspace key and height.
+fn parse_history_key(raw_key: &[u8]) -> DbResult<(String, BlockHeight)> {
+    let raw_key = std::str::from_utf8(raw_key)
+        .map_err(|e| DbError::DBError(e.to_string()))?;
+    let (key, height) =
+        raw_key.rsplit_once(HISTORY_KEY_SEPARATOR).ok_or_else(|| {
+            DbError::DBError(format!("Invalid history key {raw_key:?}"))
+        })?;
+    let height = u64::parse(height.to_owned()).map_err(DbError::KeyError)?;
+    Ok((key.to_owned(), BlockHeight(height)))
+}
+
+/// Check that the storage backend of the DB set in the config is the one that
+/// the node is built with.
+pub fn check_storage_backend(backend: StorageBackend) -> ShellResult<()> {
+    if backend != STORAGE_BACKEND {
+        return Err(Error::StorageBackend(backend, STORAGE_BACKEND));
+    }
+    Ok(())
+}
+
+/// A test suite for the [`DB`] trait that is run against every DB backend
+#[allow(clippy::arithmetic_side_effects)]
+#[cfg(test)]
+mod db_tests {
+    use namada_replay_protection as replay_protection;
+    use namada_sdk::address::EstablishedAddressGen;
+    use namada_sdk::collections::HashMap;
+    use namada_sdk::encode;
+    use namada_sdk::state::{BlockStateWrite, DBIter, MerkleTree, Sha256Hasher};
+    use namada_sdk::storage::conversion_state::ConversionState;
+    use namada_sdk::storage::types::CommitOnlyData;
+    use namada_sdk::storage::{
+        BlockResults, Epoch, Epochs, EthEventsQueue, DIFFS_CF, ROLLBACK_CF,
+        STATE_CF,
+    };
+    use namada_sdk::time::DateTimeUtc;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    /// The operations of a DB backend that the test suite needs on top of
+    /// the [`DB`] trait
+    pub trait TestDB: DB + for<'iter> DBIter<'iter> + Sized {
+        /// Open the DB at the given path, possibly in read-only mode
+        fn open_db(path: &Path, read_only: bool) -> Self;
+
+        /// Roll the DB back to the given block height
+        fn rollback(&mut self, height: BlockHeight) -> DbResult<()>;
+
+        /// Create a checkpoint of the DB at the given block height
+        fn checkpoint(
+            &self,
+            base_dir: PathBuf,
+            height: BlockHeight,
+        ) -> DbResult<DbSnapshot>;
+
+        /// Open a DB at the given path, restored from a snapshot tarball
+        fn restore(path: &Path, tarball: &mut File) -> Self;
+
+        /// Read the raw value of a key in a column family
+        fn read_raw(&self, cf: &str, key: &str) -> Option<Vec<u8>>;
+
+        /// Collect the raw key-vals of all the column families
+        fn raw_entries(&self) -> HashMap<String, Vec<u8>>;
+    }
+
+    /// Run the test suite against a DB backend that implements [`TestDB`]
+    macro_rules! db_test_suite {
+        ($db:ty) => {
+            $crate::storage::db_tests::db_test_suite!(
+                $db;
+                test_load_state,
+                test_read,
+                test_prefix_iter,
+                test_rollback,
+                test_diffs,
+                test_archive_mode,
+                test_archive_mode_prefix_at_old_height,
+                test_snapshot_creation,
+                test_snapshot_export_import,
+            );
+        };
+        ($db:ty; $($test:ident),* $(,)?) => {
+            $(
+                #[test]
+                fn $test() {
+                    $crate::storage::db_tests::$test::<$db>()
+                }
+            )*
+        };
+    }
+    pub(super) use db_test_suite;
+
+    /// Test that a block written can be loaded back from DB.
+    pub fn test_load_state<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let db = D::open(dir.path(), None);
+
+        let mut batch = D::batch();
+        let last_height = BlockHeight::default();
+        db.batch_write_subspace_val(
+            &mut batch,
+            last_height,
+            &Key::parse("test").unwrap(),
+            vec![1_u8, 1, 1, 1],
+            true,
+        )
+        .unwrap();
+
+        add_block_to_batch(
+            &db,
+            &mut batch,
+            BlockHeight::default(),
+            Epoch::default(),
+            Epochs::default(),
+            &ConversionState::default(),
+        )
+        .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        let _state = db
+            .read_last_block()
+            .expect("Should be able to read last block")
+            .expect("Block should have been written");
+    }
+
+    pub fn test_read<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let mut db = D::open(dir.path(), None);
+
+        let key = Key::parse("test").unwrap();
+        let batch_key = Key::parse("batch").unwrap();
+
+        let mut batch = D::batch();
+        let last_height = BlockHeight(100);
+        db.batch_write_subspace_val(
+            &mut batch,
+            last_height,
+            &batch_key,
+            vec![1_u8, 1, 1, 1],
+            true,
+        )
+        .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        db.write_subspace_val(last_height, &key, vec![1_u8, 1, 1, 0], true)
+            .unwrap();
+
+        let mut batch = D::batch();
+        let last_height = BlockHeight(111);
+        db.batch_write_subspace_val(
+            &mut batch,
+            last_height,
+            &batch_key,
+            vec![2_u8, 2, 2, 2],
+            true,
+        )
+        .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        db.write_subspace_val(last_height, &key, vec![2_u8, 2, 2, 0], true)
+            .unwrap();
+
+        let prev_value = db
+            .read_subspace_val_with_height(
+                &batch_key,
+                BlockHeight(100),
+                last_height,
+            )
+            .expect("read should succeed");
+        assert_eq!(prev_value, Some(vec![1_u8, 1, 1, 1]));
+        let prev_value = db
+            .read_subspace_val_with_height(&key, BlockHeight(100), last_height)
+            .expect("read should succeed");
+        assert_eq!(prev_value, Some(vec![1_u8, 1, 1, 0]));
+
+        let updated_value = db
+            .read_subspace_val_with_height(
+                &batch_key,
+                BlockHeight(111),
+                last_height,
+            )
+            .expect("read should succeed");
+        assert_eq!(updated_value, Some(vec![2_u8, 2, 2, 2]));
+        let updated_value = db
+            .read_subspace_val_with_height(&key, BlockHeight(111), last_height)
+            .expect("read should succeed");
+        assert_eq!(updated_value, Some(vec![2_u8, 2, 2, 0]));
+
+        let latest_value = db
+            .read_subspace_val(&batch_key)
+            .expect("read should succeed");
+        assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 2]));
+        let latest_value =
+            db.read_subspace_val(&key).expect("read should succeed");
+        assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 0]));
+
+        let mut batch = D::batch();
+        let last_height = BlockHeight(222);
+        db.batch_delete_subspace_val(&mut batch, last_height, &batch_key, true)
+            .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        db.delete_subspace_val(last_height, &key, true).unwrap();
+
+        let deleted_value = db
+            .read_subspace_val_with_height(
+                &batch_key,
+                BlockHeight(222),
+                last_height,
+            )
+            .expect("read should succeed");
+        assert_eq!(deleted_value, None);
+        let deleted_value = db
+            .read_subspace_val_with_height(&key, BlockHeight(222), last_height)
+            .expect("read should succeed");
+        assert_eq!(deleted_value, None);
+
+        let latest_value = db
+            .read_subspace_val(&batch_key)
+            .expect("read should succeed");
+        assert_eq!(latest_value, None);
+        let latest_value =
+            db.read_subspace_val(&key).expect("read should succeed");
+        assert_eq!(latest_value, None);
+    }
+
+    pub fn test_prefix_iter<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let db = D::open(dir.path(), None);
+
+        let prefix_0 = Key::parse("0").unwrap();
+        let key_0_a = prefix_0.push(&"a".to_string()).unwrap();
+        let key_0_b = prefix_0.push(&"b".to_string()).unwrap();
+        let key_0_c = prefix_0.push(&"c".to_string()).unwrap();
+        let prefix_1 = Key::parse("1").unwrap();
+        let key_1_a = prefix_1.push(&"a".to_string()).unwrap();
+        let key_1_b = prefix_1.push(&"b".to_string()).unwrap();
+        let key_1_c = prefix_1.push(&"c".to_string()).unwrap();
+        let prefix_01 = Key::parse("01").unwrap();
+        let key_01_a = prefix_01.push(&"a".to_string()).unwrap();
+
+        let keys_0 = vec![key_0_a, key_0_b, key_0_c];
+        let keys_1 = vec![key_1_a, key_1_b, key_1_c];
+        let keys_01 = vec![key_01_a];
+        let all_keys = [keys_0.clone(), keys_01, keys_1.clone()].concat();
+
+        // Write the keys
+        let mut batch = D::batch();
+        let height = BlockHeight(1);
+        for key in &all_keys {
+            db.batch_write_subspace_val(&mut batch, height, key, [0_u8], true)
+                .unwrap();
+        }
+        db.exec_batch(batch).unwrap();
+
+        // Prefix "0" shouldn't match prefix "01"
+        let itered_keys: Vec<Key> = db
+            .iter_prefix(Some(&prefix_0))
+            .map(|(key, _val, _)| Key::parse(key).unwrap())
+            .collect();
+        itertools::assert_equal(keys_0, itered_keys);
+
+        let itered_keys: Vec<Key> = db
+            .iter_prefix(Some(&prefix_1))
+            .map(|(key, _val, _)| Key::parse(key).unwrap())
+            .collect();
+        itertools::assert_equal(keys_1, itered_keys);
+
+        let itered_keys: Vec<Key> = db
+            .iter_prefix(None)
+            .map(|(key, _val, _)| Key::parse(key).unwrap())
+            .collect();
+        itertools::assert_equal(all_keys, itered_keys);
+    }
+
+    pub fn test_rollback<D: TestDB>() {
+        for persist_diffs in [true, false] {
+            println!("Running with persist_diffs: {persist_diffs}");
+
+            let dir = tempdir().unwrap();
+            let mut db = D::open(dir.path(), None);
+
+            // A key that's gonna be added on a second block
+            let add_key = Key::parse("add").unwrap();
+            // A key that's gonna be deleted on a second block
+            let delete_key = Key::parse("delete").unwrap();
+            // A key that's gonna be overwritten on a second block
+            let overwrite_key = Key::parse("overwrite").unwrap();
+
+            // Write first block
+            let mut batch = D::batch();
+            let height_0 = BlockHeight(100);
+            let mut pred_epochs = Epochs::default();
+            pred_epochs.new_epoch(height_0);
+            let conversion_state_0 = ConversionState::default();
+            let to_delete_val = vec![1_u8, 1, 0, 0];
+            let to_overwrite_val = vec![1_u8, 1, 1, 0];
+            db.batch_write_subspace_val(
+                &mut batch,
+                height_0,
+                &delete_key,
+                &to_delete_val,
+                persist_diffs,
+            )
+            .unwrap();
+            db.batch_write_subspace_val(
+                &mut batch,
+                height_0,
+                &overwrite_key,
+                &to_overwrite_val,
+                persist_diffs,
+            )
+            .unwrap();
+            for tx in [b"tx1", b"tx2"] {
+                db.write_replay_protection_entry(
+                    &mut batch,
+                    &replay_protection::key(&Hash::sha256(tx)),
+                )
+                .unwrap();
+            }
+
+            for tx in [b"tx3", b"tx4"] {
+                db.write_replay_protection_entry(
+                    &mut batch,
+                    &replay_protection::current_key(&Hash::sha256(tx)),
+                )
+                .unwrap();
+            }
+
+            add_block_to_batch(
+                &db,
+                &mut batch,
+                height_0,
+                Epoch(1),
+                pred_epochs.clone(),
+                &conversion_state_0,
+            )
+            .unwrap();
+            db.exec_batch(batch).unwrap();
+
+            // Write second block
+            let mut batch = D::batch();
+            let height_1 = BlockHeight(101);
+            pred_epochs.new_epoch(height_1);
+            let conversion_state_1 = ConversionState::default();
+            let add_val = vec![1_u8, 0, 0, 0];
+            let overwrite_val = vec![1_u8, 1, 1, 1];
+            db.batch_write_subspace_val(
+                &mut batch,
+                height_1,
+                &add_key,
+                &add_val,
+                persist_diffs,
+            )
+            .unwrap();
+            db.batch_write_subspace_val(
+                &mut batch,
+                height_1,
+                &overwrite_key,
+                &overwrite_val,
+                persist_diffs,
+            )
+            .unwrap();
+            db.batch_delete_subspace_val(
+                &mut batch,
+                height_1,
+                &delete_key,
+                persist_diffs,
+            )
+            .unwrap();
+
+            db.move_current_replay_protection_entries(&mut batch)
+                .unwrap();
+
+            for tx in [b"tx5", b"tx6"] {
+                db.write_replay_protection_entry(
+                    &mut batch,
+                    &replay_protection::current_key(&Hash::sha256(tx)),
+                )
+                .unwrap();
+            }
+
+            add_block_to_batch(
+                &db,
+                &mut batch,
+                height_1,
+                Epoch(2),
+                pred_epochs,
+                &conversion_state_1,
+            )
+            .unwrap();
+            db.exec_batch(batch).unwrap();
+
+            // Check that the values are as expected from second block
+            let added = db.read_subspace_val(&add_key).unwrap();
+            assert_eq!(added, Some(add_val));
+            let overwritten = db.read_subspace_val(&overwrite_key).unwrap();
+            assert_eq!(overwritten, Some(overwrite_val));
+            let deleted = db.read_subspace_val(&delete_key).unwrap();
+            assert_eq!(deleted, None);
+
+            for tx in [b"tx1", b"tx2", b"tx3", b"tx4", b"tx5", b"tx6"] {
+                assert!(
+                    db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
+                );
+            }
+
+            // Rollback to the first block height
+            db.rollback(height_0).unwrap();
+
+            // Check that the values are back to the state at the first block
+            let added = db.read_subspace_val(&add_key).unwrap();
+            assert_eq!(added, None);
+            let overwritten = db.read_subspace_val(&overwrite_key).unwrap();
+            assert_eq!(overwritten, Some(to_overwrite_val));
+            let deleted = db.read_subspace_val(&delete_key).unwrap();
+            assert_eq!(deleted, Some(to_delete_val));
+            // Check the conversion state
+            let conversion_state =
+                db.read_raw(STATE_CF, CONVERSION_STATE_KEY).unwrap();
+            assert_eq!(conversion_state, encode(&conversion_state_0));
+            for tx in [b"tx1", b"tx2", b"tx3", b"tx4"] {
+                assert!(
+                    db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
+                );
+            }
+
+            for tx in [b"tx5", b"tx6"] {
+                assert!(
+                    !db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
+                );
+            }
+        }
+    }
+
+    pub fn test_diffs<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let mut db = D::open(dir.path(), None);
+
+        let key_with_diffs = Key::parse("with_diffs").unwrap();
+        let key_without_diffs = Key::parse("without_diffs").unwrap();
+
+        let initial_val = vec![1_u8, 1, 0, 0];
+        let overwrite_val = vec![1_u8, 1, 1, 0];
+
+        let is_stored =
+            |db: &D, cf: &str, key: String| db.read_raw(cf, &key).is_some();
+
+        // Write first block
+        let mut batch = D::batch();
+        let height_0 = BlockHeight::first();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_0,
+            &key_with_diffs,
+            &initial_val,
+            true,
+        )
+        .unwrap();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_0,
+            &key_without_diffs,
+            &initial_val,
+            false,
+        )
+        .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        // Diffs new key for `key_with_diffs` at height_0 must be present
+        let (old_with_h0, new_with_h0) =
+            old_and_new_diff_key(&key_with_diffs, height_0).unwrap();
+        assert!(!is_stored(&db, DIFFS_CF, old_with_h0.clone()));
+        assert!(is_stored(&db, DIFFS_CF, new_with_h0.clone()));
+
+        // Diffs new key for `key_without_diffs` at height_0 must be present
+        let (old_wo_h0, new_wo_h0) =
+            old_and_new_diff_key(&key_without_diffs, height_0).unwrap();
+        assert!(!is_stored(&db, ROLLBACK_CF, old_wo_h0.clone()));
+        assert!(is_stored(&db, ROLLBACK_CF, new_wo_h0.clone()));
+
+        // Write second block
+        let mut batch = D::batch();
+        let height_1 = height_0 + 10u64;
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_1,
+            &key_with_diffs,
+            &overwrite_val,
+            true,
+        )
+        .unwrap();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_1,
+            &key_without_diffs,
+            &overwrite_val,
+            false,
+        )
+        .unwrap();
+        db.prune_non_persisted_diffs(&mut batch, height_0).unwrap();
+        db.exec_batch(batch).unwrap();
+
+        // Diffs keys for `key_with_diffs` at height_0 must be present
+        assert!(!is_stored(&db, DIFFS_CF, old_with_h0));
+        assert!(is_stored(&db, DIFFS_CF, new_with_h0));
+
+        // Diffs keys for `key_without_diffs` at height_0 must be gone
+        assert!(!is_stored(&db, ROLLBACK_CF, old_wo_h0));
+        assert!(!is_stored(&db, ROLLBACK_CF, new_wo_h0));
+
+        // Diffs keys for `key_with_diffs` at height_1 must be present
+        let (old_with_h1, new_with_h1) =
+            old_and_new_diff_key(&key_with_diffs, height_1).unwrap();
+        assert!(is_stored(&db, DIFFS_CF, old_with_h1.clone()));
+        assert!(is_stored(&db, DIFFS_CF, new_with_h1.clone()));
+
+        // Diffs keys for `key_without_diffs` at height_1 must be present
+        let (old_wo_h1, new_wo_h1) =
+            old_and_new_diff_key(&key_without_diffs, height_1).unwrap();
+        assert!(is_stored(&db, ROLLBACK_CF, old_wo_h1.clone()));
+        assert!(is_stored(&db, ROLLBACK_CF, new_wo_h1.clone()));
+
+        // Write third block
+        let mut batch = D::batch();
+        let height_2 = height_1 + 10;
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_2,
+            &key_with_diffs,
+            &initial_val,
+            true,
+        )
+        .unwrap();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_2,
+            &key_without_diffs,
+            &initial_val,
+            false,
+        )
+        .unwrap();
+        db.prune_non_persisted_diffs(&mut batch, height_1).unwrap();
+        db.exec_batch(batch).unwrap();
+
+        // Diffs keys for `key_with_diffs` at height_1 must be present
+        assert!(is_stored(&db, DIFFS_CF, old_with_h1));
+        assert!(is_stored(&db, DIFFS_CF, new_with_h1));
+
+        // Diffs keys for `key_without_diffs` at height_1 must be gone
+        assert!(!is_stored(&db, ROLLBACK_CF, old_wo_h1));
+        assert!(!is_stored(&db, ROLLBACK_CF, new_wo_h1));
+
+        // Diffs keys for `key_with_diffs` at height_2 must be present
+        let (old_with_h2, new_with_h2) =
+            old_and_new_diff_key(&key_with_diffs, height_2).unwrap();
+        assert!(is_stored(&db, DIFFS_CF, old_with_h2));
+        assert!(is_stored(&db, DIFFS_CF, new_with_h2));
+
+        // Diffs keys for `key_without_diffs` at height_2 must be present
+        let (old_wo_h2, new_wo_h2) =
+            old_and_new_diff_key(&key_without_diffs, height_2).unwrap();
+        assert!(is_stored(&db, ROLLBACK_CF, old_wo_h2));
+        assert!(is_stored(&db, ROLLBACK_CF, new_wo_h2));
+    }
+
+    /// A test helper to write a block
+    fn add_block_to_batch<D: DB>(
+        db: &D,
+        batch: &mut D::WriteBatch,
+        height: BlockHeight,
+        epoch: Epoch,
+        pred_epochs: Epochs,
+        conversion_state: &ConversionState,
+    ) -> DbResult<()> {
+        let merkle_tree = MerkleTree::<Sha256Hasher>::default();
+        let merkle_tree_stores = merkle_tree.stores();
+        #[allow(clippy::disallowed_methods)]
+        let time = DateTimeUtc::now();
+        let next_epoch_min_start_height = BlockHeight::default();
+        #[allow(clippy::disallowed_methods)]
+        let next_epoch_min_start_time = DateTimeUtc::now();
+        let update_epoch_blocks_delay = None;
+        let address_gen = EstablishedAddressGen::new("whatever");
+        let results = BlockResults::default();
+        let eth_events_queue = EthEventsQueue::default();
+        let commit_only_data = CommitOnlyData::default();
+        let block = BlockStateWrite {
+            merkle_tree_stores,
+            header: None,
+            height,
+            time,
+            epoch,
+            results: &results,
+            conversion_state,
+            pred_epochs: &pred_epochs,
+            next_epoch_min_start_height,
+            next_epoch_min_start_time,
+            update_epoch_blocks_delay,
+            address_gen: &address_gen,
+            ethereum_height: None,
+            eth_events_queue: &eth_events_queue,
+            commit_only_data: &commit_only_data,
+        };
+
+        db.add_block_to_batch(block, batch, true)
+    }
+
+    /// Test that in archive mode, past values of keys without persisted
+    /// diffs can be read at any height, both by key and by prefix.
+    pub fn test_archive_mode<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let mut db = D::open(dir.path(), None);
+        db.enable_archive_mode().unwrap();
+        assert_eq!(db.archive_start_height(), Some(BlockHeight(0)));
+
+        let prefix = Key::parse("prefix").unwrap();
+        let key_a = prefix.push(&"a".to_owned()).unwrap();
+        let key_b = prefix.push(&"b".to_owned()).unwrap();
+        let height_1 = BlockHeight(1);
+        let height_2 = BlockHeight(2);
+        let height_3 = BlockHeight(3);
+
+        // Write both keys in the first block
+        let mut batch = D::batch();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_1,
+            &key_a,
+            [1_u8],
+            false,
+        )
+        .unwrap();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_1,
+            &key_b,
+            [1_u8],
+            false,
+        )
+        .unwrap();
+        db.exec_batch(batch).unwrap();
+
+        // Overwrite a key in the second block
+        let mut batch = D::batch();
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_2,
+            &key_a,
+            [2_u8],
+            false,
+        )
+        .unwrap();
+        db.prune_non_persisted_diffs(&mut batch, height_1).unwrap();
+        db.exec_batch(batch).unwrap();
+
+        // Delete the other key in the third block
+        let mut batch = D::batch();
+        db.batch_delete_subspace_val(&mut batch, height_3, &key_b, false)
+            .unwrap();
+        db.prune_non_persisted_diffs(&mut batch, height_2).unwrap();
+        db.exec_batch(batch).unwrap();
+
+        let read = |key: &Key, height: BlockHeight| {
+            db.read_subspace_val_with_height(key, height, height_3)
+                .unwrap()
+        };
+        assert_eq!(read(&key_a, height_1), Some(vec![1]));
+        assert_eq!(read(&key_a, height_2), Some(vec![2]));
+        assert_eq!(read(&key_b, height_2), Some(vec![1]));
+        assert_eq!(read(&key_b, height_3), None);
+
+        let read_prefix = |height: BlockHeight| {
+            db.read_subspace_prefix_with_height(&prefix, height)
+                .unwrap()
+        };
+        assert_eq!(
+            read_prefix(height_1),
+            vec![(key_a.to_string(), vec![1]), (key_b.to_string(), vec![1])]
+        );
+        assert_eq!(
+            read_prefix(height_2),
+            vec![(key_a.to_string(), vec![2]), (key_b.to_string(), vec![1])]
+        );
+        assert_eq!(read_prefix(height_3), vec![(key_a.to_string(), vec![2])]);
+
+        // The archive mode is persisted in the DB
+        drop(db);
+        let db = D::open(dir.path(), None);
+        assert_eq!(db.archive_start_height(), Some(BlockHeight(0)));
+    }
+
+    /// Test that in archive mode, a prefix can be read at an old height of a
+    /// long history, including the prefix key itself, keys last modified
+    /// before archive mode was enabled and keys created after that height.
+    pub fn test_archive_mode_prefix_at_old_height<D: TestDB>() {
+        let dir = tempdir().unwrap();
+        let mut db = D::open(dir.path(), None);
+
+        let prefix = Key::parse("prefix").unwrap();
+        let key_a = prefix.push(&"a".to_owned()).unwrap();
+        let key_b = prefix.push(&"b".to_owned()).unwrap();
+        let key_c = prefix.push(&"c".to_owned()).unwrap();
+        // A key that shares the prefix string, but not the prefix segment
+        let sibling = Key::parse("prefixed/a").unwrap();
+        let height_1 = BlockHeight(1);
+        let height_2 = BlockHeight(2);
+        let height_3 = BlockHeight(3);
+        let height_4 = BlockHeight(4);
+
+        // Write some keys before archive mode is enabled
+        db.write_subspace_val(height_1, &key_a, [1_u8], false)
+            .unwrap();
+        db.write_subspace_val(height_1, &key_b, [1_u8], false)
+            .unwrap();
+        db.write_subspace_val(height_1, &sibling, [1_u8], false)
+            .unwrap();
+        db.enable_archive_mode().unwrap();
+
+        db.write_subspace_val(height_2, &prefix, [2_u8], false)
+            .unwrap();
+        db.write_subspace_val(height_2, &key_a, [2_u8], false)
+            .unwrap();
+        db.write_subspace_val(height_2, &sibling, [2_u8], false)
+            .unwrap();
+
+        db.delete_subspace_val(height_3, &prefix, false).unwrap();
+        db.write_subspace_val(height_3, &key_a, [3_u8], false)
+            .unwrap();
+        db.write_subspace_val(height_3, &key_c, [3_u8], false)
+            .unwrap();
+
+        db.write_subspace_val(height_4, &key_a, [4_u8], false)
+            .unwrap();
+
+        let read_prefix = |height: BlockHeight| {
+            db.read_subspace_prefix_with_height(&prefix, height)
+                .unwrap()
+        };
+        assert_eq!(
+            read_prefix(height_1),
+            vec![(key_a.to_string(), vec![1]), (key_b.to_string(), vec![1])]
+        );
+        assert_eq!(
+            read_prefix(height_2),
+            vec![
+                (prefix.to_string(), vec![2]),
+                (key_a.to_string(), vec![2]),
+                (key_b.to_string(), vec![1]),
+            ]
+        );
+        assert_eq!(
+            read_prefix(height_3),
+            vec![
+                (key_a.to_string(), vec![3]),
+                (key_b.to_string(), vec![1]),
+                (key_c.to_string(), vec![3]),
+            ]
+        );
+        assert_eq!(
+            read_prefix(height_4),
+            vec![
+                (key_a.to_string(), vec![4]),
+                (key_b.to_string(), vec![1]),
+                (key_c.to_string(), vec![3]),
+            ]
+        );
+    }
+
+    /// Test that taking a snapshot actually
+    /// freezes the database in time even if
+    /// it is written to.
+    pub fn test_snapshot_creation<D: TestDB>() {
+        let temp = tempfile::tempdir().expect("Test failed");
+        let mut db = D::open_db(temp.path(), false);
+        db.write_subspace_val(
+            1.into(),
+            &Key::parse("bing/fucking/bong").expect("Test failed"),
+            [1u8; 64],
+            false,
+        )
+        .expect("Test failed");
+        // we need to persist the changes and restart in read-only mode
+        // as rocksdb doesn't allow multiple read/write instances
+        drop(db);
+        let db = D::open_db(temp.path(), true);
+        // freeze the database at this point in time
+        let snapshot = db
+            .checkpoint(temp.path().to_path_buf(), BlockHeight::first())
+            .expect("Test failed");
+        let snapshot_db = D::open_db(&snapshot.0.temp_db(), true);
+
+        // write a new entry to the db
+        let mut db2 = D::open_db(temp.path(), false);
+        db2.write_subspace_val(
+            2.into(),
+            &Key::parse("I/AM/BATMAN").expect("Test failed"),
+            [2u8; 32],
+            false,
+        )
+        .expect("Test failed");
+        // flush the data
+        drop(db2);
+        let db2 = D::open_db(temp.path(), false);
+
+        let expected_snap = HashMap::from([
+            ("bing/fucking/bong".to_string(), vec![1u8; 64]),
+            (
+                "0000000000002/new/bing/fucking/bong".to_string(),
+                vec![1u8; 64],
+            ),
+        ]);
+        assert_eq!(expected_snap, snapshot_db.raw_entries());
+        let expected_db = HashMap::from([
+            ("bing/fucking/bong".to_string(), vec![1u8; 64]),
+            (
+                "0000000000002/new/bing/fucking/bong".to_string(),
+                vec![1u8; 64],
+            ),
+            ("I/AM/BATMAN".to_string(), vec![2u8; 32]),
+            ("0000000000004/new/I/AM/BATMAN".to_string(), vec![2u8; 32]),
+        ]);
+        assert_eq!(expected_db, db2.raw_entries());
+    }
+
+    /// Test that an exported snapshot is verified on import and that it
+    /// restores the same DB
+    pub fn test_snapshot_export_import<D: TestDB>() {
+        let temp = tempfile::tempdir().expect("Test failed");
+        let mut db = D::open_db(temp.path(), false);
+        db.write_subspace_val(
+            1.into(),
+            &Key::parse("bing/fucking/bong").expect("Test failed"),
+            [1u8; 64],
+            false,
+        )
+        .expect("Test failed");
+        drop(db);
+        let db = D::open_db(temp.path(), true);
+        let snapshot = db
+            .checkpoint(temp.path().to_path_buf(), BlockHeight::first())
+            .expect("Test failed");
+        drop(db);
+        let snapshot_path = snapshot.0.clone();
+        snapshot.package().expect("Test failed");
+
+        let export_dir = tempfile::tempdir().expect("Test failed");
+        let meta = DbSnapshot(snapshot_path)
+            .export(export_dir.path())
+            .expect("Test failed");
+
+        // the root hash must match the trusted one
+        assert!(
+            DbSnapshot::load_exported(export_dir.path(), Hash::zero()).is_err()
+        );
+        let (loaded_meta, mut tarball) =
+            DbSnapshot::load_exported(export_dir.path(), meta.root_hash)
+                .expect("Test failed");
+        assert_eq!(loaded_meta.chunk_hashes, meta.chunk_hashes);
+
+        // restore a new DB from the exported snapshot. NB: the tarball is
+        // unpacked into a `db` directory
+        let restored = tempfile::tempdir().expect("Test failed");
+        let db = D::restore(&restored.path().join("db"), &mut tarball);
+        let key = Key::parse("bing/fucking/bong").expect("Test failed");
+        assert_eq!(
+            db.read_subspace_val(&key).expect("Test failed"),
+            Some(vec![1u8; 64])
+        );
+
+        // a tampered tarball must be rejected
+        let tarball_path = export_dir.path().join(EXPORTED_TARBALL_FILE);
+        let mut tampered = std::fs::read(&tarball_path).expect("Test failed");
+        tampered[0] ^= 1;
+        std::fs::write(&tarball_path, tampered).expect("Test failed");
+        assert!(
+            DbSnapshot::load_exported(export_dir.path(), meta.root_hash)
+                .is_err()
+        );
+    }
+}
+
 #[allow(clippy::arithmetic_side_effects, clippy::cast_sign_loss)]
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use borsh::BorshDeserialize;
     use itertools::Itertools;
     use namada_sdk::chain::{BlockHeight, ChainId};
@@ -1017,4 +2390,79 @@ mod tests {
             .unwrap();
         assert!(res2.is_none());
     }
+
+    /// Test that we chunk a file into
+    /// pieces respecting the max chunk size.
+    #[test]
+    fn test_chunking() {
+        let temp = tempfile::tempdir().expect("Test failed");
+        let base_dir = temp.path().to_path_buf();
+
+        let snap_path = SnapshotPath(base_dir, BlockHeight::first());
+        let snapshot_base = snap_path.base().clone();
+        std::fs::create_dir_all(&snapshot_base).expect("Test failed");
+        let snapshot = DbSnapshot(snap_path);
+
+        let tar = snapshot.0.temp_tarball("zst");
+        std::fs::write(tar, vec![16; 21]).expect("Test failed");
+        snapshot.chunk_snapshot(10).unwrap();
+        let mut file_number = 0;
+        for entry in std::fs::read_dir(snapshot_base).expect("Test failed") {
+            let entry = entry.expect("Test failed");
+            file_number += 1;
+            if entry.path().is_file() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                let Some(("chunk", chunk_nbr)) = name.split_once('-') else {
+                    continue;
+                };
+                let chunk_nbr = u64::from_str(chunk_nbr).expect("Test failed");
+                match chunk_nbr {
+                    0 | 1 => assert_eq!(
+                        entry.metadata().expect("Test failed").len(),
+                        10
+                    ),
+                    2 => assert_eq!(
+                        entry.metadata().expect("Test failed").len(),
+                        1
+                    ),
+                    _ => panic!("Snapshot too chunky"),
+                }
+            } else {
+                panic!("Found unexpected dir in snapshots")
+            }
+        }
+        assert_eq!(file_number, 5)
+    }
+
+    /// Test that we correctly delete snapshots
+    /// older than a given block height
+    #[test]
+    fn test_snapshot_cleanup() {
+        let temp = tempfile::tempdir().expect("Test failed");
+        let base_dir = temp.path().to_path_buf();
+        for height in 1..4 {
+            let snap_path = SnapshotPath(base_dir.clone(), height.into());
+            let snapshot_base = snap_path.base().clone();
+            std::fs::create_dir_all(&snapshot_base).expect("Test failed");
+        }
+
+        std::fs::write(base_dir.join("big.chungus"), "howdy".as_bytes())
+            .expect("Test failed");
+        DbSnapshot::cleanup(3.into(), &base_dir, 2).unwrap();
+        // Big Chungus lives!!!
+        assert_eq!(
+            std::fs::read_to_string(base_dir.join("big.chungus"))
+                .expect("Test failed"),
+            "howdy"
+        );
+
+        let heights = {
+            let mut h = DbSnapshot::heights_of_stored_snapshots(&base_dir)
+                .expect("Test failed");
+            h.sort();
+            h
+        };
+        assert_eq!(heights, vec![2, 3]);
+    }
 }
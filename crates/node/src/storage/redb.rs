@@ -0,0 +1,1848 @@
+//! The persistent storage in redb.
+//!
+//! This backend is enabled with the `redb` feature in place of RocksDB. It
+//! keeps the same storage tree as the RocksDB backend, with every column
+//! family stored in a redb table of the same name. All the tables are kept in
+//! a single [`DB_FILE_NAME`] file inside the DB directory.
+//!
+//! A redb file can only be opened once per process, so the handle of an open
+//! DB is shared by all the [`RedbDB`]s opened at the same path (e.g. the
+//! read-only instance that is used to create a snapshot).
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use data_encoding::HEXLOWER;
+use itertools::Either;
+use namada_replay_protection as replay_protection;
+use namada_sdk::arith::checked;
+use namada_sdk::collections::{HashMap, HashSet};
+use namada_sdk::eth_bridge::storage::bridge_pool;
+use namada_sdk::eth_bridge::storage::proof::BridgePoolRootProof;
+use namada_sdk::gas::Gas;
+use namada_sdk::migrations::{DBUpdateVisitor, DbUpdateType};
+use namada_sdk::state::merkle_tree::{
+    tree_key_prefix_with_epoch, tree_key_prefix_with_height,
+};
+use namada_sdk::state::{
+    BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, DbError as Error,
+    DbResult as Result, MerkleTreeStoresRead, PatternIterator, PrefixIterator,
+    StoreType, DB,
+};
+use namada_sdk::storage::{
+    BlockHeader, BlockHeight, DbColFam, Epoch, Key, KeySeg, BLOCK_CF, DIFFS_CF,
+    HISTORY_CF, REPLAY_PROTECTION_CF, ROLLBACK_CF, STATE_CF, SUBSPACE_CF,
+};
+use namada_sdk::{decode, encode, ethereum_events};
+use once_cell::sync::Lazy;
+use redb::{AccessGuard, ReadOnlyTable, TableDefinition, TableError};
+use regex::Regex;
+
+use super::{
+    history_key, history_key_prefix, history_key_upper_bound,
+    old_and_new_diff_key, parse_history_key, DbSnapshot, HistoryEntry,
+    SnapshotPath, ADDRESS_GEN_KEY_SEGMENT, ARCHIVE_START_HEIGHT_KEY,
+    BLOCK_HEADER_KEY_SEGMENT, BLOCK_HEIGHT_KEY, BLOCK_TIME_KEY_SEGMENT,
+    COMMIT_ONLY_DATA_KEY, CONVERSION_STATE_KEY, EPOCH_KEY_SEGMENT,
+    ETHEREUM_HEIGHT_KEY, ETH_EVENTS_QUEUE_KEY, MERKLE_TREE_ROOT_KEY_SEGMENT,
+    MERKLE_TREE_STORE_KEY_SEGMENT, NEW_DIFF_PREFIX,
+    NEXT_EPOCH_MIN_START_HEIGHT_KEY, NEXT_EPOCH_MIN_START_TIME_KEY,
+    OLD_DIFF_PREFIX, PRED_EPOCHS_KEY_SEGMENT, PRED_KEY_PREFIX,
+    RESULTS_KEY_PREFIX, UPDATE_EPOCH_BLOCKS_DELAY_KEY,
+};
+
+/// The name of the file that holds all the tables in the DB directory
+pub const DB_FILE_NAME: &str = "namada.redb";
+
+/// A table holding the key-vals of a column family
+type Table<'a> = TableDefinition<'a, &'static str, &'static [u8]>;
+
+/// An ordered range over the key-vals of a table
+type TableRange = redb::Range<'static, &'static str, &'static [u8]>;
+
+/// A key-val read from a table range
+type TableEntry = std::result::Result<
+    (
+        AccessGuard<'static, &'static str>,
+        AccessGuard<'static, &'static [u8]>,
+    ),
+    redb::StorageError,
+>;
+
+/// The handles of the DBs that are currently open, by their canonical path
+static OPEN_DBS: Lazy<Mutex<HashMap<PathBuf, Weak<redb::Database>>>> =
+    Lazy::new(Default::default);
+
+/// redb handle
+pub struct RedbDB {
+    /// Handle to the db
+    inner: ManuallyDrop<Arc<redb::Database>>,
+    /// The DB directory
+    path: PathBuf,
+    /// Indicates if read only
+    read_only: bool,
+    /// Whether the handle is invalid
+    invalid_handle: bool,
+    /// The height from which the history of the subspace is kept, if the DB
+    /// is in archive mode
+    archive_start: Option<BlockHeight>,
+}
+
+impl std::fmt::Debug for RedbDB {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedbDB")
+            .field("path", &self.path)
+            .field("read_only", &self.read_only)
+            .field("archive_start", &self.archive_start)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The size of the page cache of a redb DB.
+#[derive(Clone, Copy, Debug)]
+pub struct RedbCache {
+    capacity: usize,
+}
+
+impl RedbCache {
+    /// A cache of the given capacity in bytes. Named after the RocksDB cache
+    /// constructor, such that the backends are interchangeable.
+    pub fn new_lru_cache(capacity: usize) -> Self {
+        Self { capacity }
+    }
+}
+
+/// DB Handle for batch writes. The writes are applied atomically in a single
+/// write transaction, where the last write of a key takes precedence.
+#[derive(Default)]
+pub struct RedbWriteBatch(
+    BTreeMap<&'static str, BTreeMap<String, Option<Vec<u8>>>>,
+);
+
+impl RedbWriteBatch {
+    fn put(
+        &mut self,
+        table: &'static str,
+        key: impl AsRef<str>,
+        value: impl AsRef<[u8]>,
+    ) {
+        self.0
+            .entry(table)
+            .or_default()
+            .insert(key.as_ref().to_owned(), Some(value.as_ref().to_vec()));
+    }
+
+    fn delete(&mut self, table: &'static str, key: impl AsRef<str>) {
+        self.0
+            .entry(table)
+            .or_default()
+            .insert(key.as_ref().to_owned(), None);
+    }
+}
+
+/// Open redb for the DB
+pub fn open(
+    path: impl AsRef<Path>,
+    read_only: bool,
+    cache: Option<&RedbCache>,
+) -> Result<RedbDB> {
+    let path = path.as_ref();
+    if read_only && !path.join(DB_FILE_NAME).is_file() {
+        return Err(Error::DBError(format!(
+            "No DB found at {}",
+            path.to_string_lossy()
+        )));
+    }
+    std::fs::create_dir_all(path).map_err(db_error)?;
+    let path = path.canonicalize().map_err(db_error)?;
+
+    let inner = {
+        let mut open_dbs = OPEN_DBS.lock().unwrap();
+        match open_dbs.get(&path).and_then(Weak::upgrade) {
+            Some(inner) => inner,
+            None => {
+                let mut builder = redb::Builder::new();
+                if let Some(cache) = cache {
+                    builder.set_cache_size(cache.capacity);
+                }
+                let inner = Arc::new(
+                    builder
+                        .create(path.join(DB_FILE_NAME))
+                        .map_err(db_error)?,
+                );
+                open_dbs.insert(path.clone(), Arc::downgrade(&inner));
+                inner
+            }
+        }
+    };
+
+    if !read_only {
+        // Create all the tables up-front
+        let txn = inner.begin_write().map_err(db_error)?;
+        for cf in DbColFam::all() {
+            txn.open_table(table_def(cf)).map_err(db_error)?;
+        }
+        txn.commit().map_err(db_error)?;
+    }
+
+    let mut db = RedbDB {
+        inner: ManuallyDrop::new(inner),
+        path,
+        read_only,
+        invalid_handle: false,
+        archive_start: None,
+    };
+    db.archive_start = db.read_value(STATE_CF, ARCHIVE_START_HEIGHT_KEY)?;
+    Ok(db)
+}
+
+impl Drop for RedbDB {
+    fn drop(&mut self) {
+        if self.invalid_handle {
+            return;
+        }
+        unsafe { ManuallyDrop::drop(&mut self.inner) }
+    }
+}
+
+impl RedbDB {
+    fn read_value<T>(
+        &self,
+        table: &str,
+        key: impl AsRef<str>,
+    ) -> Result<Option<T>>
+    where
+        T: BorshDeserialize,
+    {
+        self.read_value_bytes(table, key)?
+            .map(|bytes| decode(bytes).map_err(Error::CodingError))
+            .transpose()
+    }
+
+    fn read_value_bytes(
+        &self,
+        table: &str,
+        key: impl AsRef<str>,
+    ) -> Result<Option<Vec<u8>>> {
+        let Some(table) = self.open_read_table(table)? else {
+            return Ok(None);
+        };
+        let value = table.get(key.as_ref()).map_err(db_error)?;
+        Ok(value.map(|value| value.value().to_vec()))
+    }
+
+    /// Open a table for reading in a new read transaction. The table may be
+    /// missing from a DB that was only ever opened in read-only mode.
+    fn open_read_table(
+        &self,
+        name: &str,
+    ) -> Result<Option<ReadOnlyTable<&'static str, &'static [u8]>>> {
+        let txn = self.inner.begin_read().map_err(db_error)?;
+        match txn.open_table(table_def(name)) {
+            Ok(table) => Ok(Some(table)),
+            Err(TableError::TableDoesNotExist(_)) => Ok(None),
+            Err(e) => Err(db_error(e)),
+        }
+    }
+
+    /// Iterate the key-vals of a table in order, starting from the given key.
+    fn range_from(
+        &self,
+        table: &str,
+        start: &str,
+    ) -> Result<Option<TableRange>> {
+        let Some(table) = self.open_read_table(table)? else {
+            return Ok(None);
+        };
+        table.range::<&str>(start..).map(Some).map_err(db_error)
+    }
+
+    fn add_state_value_to_batch<T>(
+        &self,
+        table: &'static str,
+        key: impl AsRef<str>,
+        value: &T,
+        batch: &mut RedbWriteBatch,
+    ) -> Result<()>
+    where
+        T: BorshSerialize,
+    {
+        if let Some(current_value) = self.read_value_bytes(table, &key)? {
+            batch.put(
+                table,
+                format!("{PRED_KEY_PREFIX}/{}", key.as_ref()),
+                current_value,
+            );
+        }
+        self.add_value_to_batch(table, key, value, batch);
+        Ok(())
+    }
+
+    fn add_value_to_batch<T>(
+        &self,
+        table: &'static str,
+        key: impl AsRef<str>,
+        value: &T,
+        batch: &mut RedbWriteBatch,
+    ) where
+        T: BorshSerialize,
+    {
+        batch.put(table, key, encode(&value))
+    }
+
+    /// Persist the diff of an account subspace key-val under the height where
+    /// it was changed in a batch write.
+    fn batch_write_subspace_diff(
+        &self,
+        batch: &mut RedbWriteBatch,
+        height: BlockHeight,
+        key: &Key,
+        old_value: Option<&[u8]>,
+        new_value: Option<&[u8]>,
+        persist_diffs: bool,
+    ) -> Result<()> {
+        let table = if persist_diffs { DIFFS_CF } else { ROLLBACK_CF };
+        let (old_val_key, new_val_key) = old_and_new_diff_key(key, height)?;
+
+        if let Some(old_value) = old_value {
+            batch.put(table, old_val_key, old_value);
+        }
+
+        if let Some(new_value) = new_value {
+            batch.put(table, new_val_key, new_value);
+        }
+
+        if self.archive_start.is_some() {
+            let entry = HistoryEntry {
+                old: old_value.map(<[u8]>::to_vec),
+                new: new_value.map(<[u8]>::to_vec),
+            };
+            batch.put(HISTORY_CF, history_key(key, height), encode(&entry));
+        }
+        Ok(())
+    }
+
+    /// Read the value of an account subspace key at the given height from the
+    /// history kept in archive mode.
+    fn read_history_val(
+        &self,
+        key: &Key,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        let Some(history) = self.open_read_table(HISTORY_CF)? else {
+            return self.read_subspace_val(key);
+        };
+        match read_history_entry_val(&history, key, height)? {
+            Some(value) => Ok(value),
+            // The key wasn't modified since archive mode was enabled
+            None => self.read_subspace_val(key),
+        }
+    }
+
+    /// Check if the history of the subspace at the given height is kept.
+    fn is_archived_height(&self, height: BlockHeight) -> bool {
+        self.archive_start.is_some_and(|start| height >= start)
+    }
+
+    /// Dump last known block
+    pub fn dump_block(
+        &self,
+        out_file_path: std::path::PathBuf,
+        historic: bool,
+        height: Option<BlockHeight>,
+    ) {
+        // Find the last block height
+        let last_height = self
+            .read_value(STATE_CF, BLOCK_HEIGHT_KEY)
+            .expect("Unable to read DB")
+            .expect("No block height found");
+
+        let height = height.unwrap_or(last_height);
+
+        let full_path = out_file_path
+            .with_file_name(format!(
+                "{}_{height}",
+                out_file_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "dump_db".to_string())
+            ))
+            .with_extension("toml");
+
+        let mut file = File::options()
+            .append(true)
+            .create_new(true)
+            .open(&full_path)
+            .expect("Cannot open the output file");
+
+        println!("Will write to {} ...", full_path.to_string_lossy());
+
+        if historic {
+            // Dump the keys prepended with the selected block height (includes
+            // subspace diff keys)
+            let prefix = height.raw();
+            self.dump_it(DIFFS_CF, Some(prefix.clone()), &mut file);
+            self.dump_it(BLOCK_CF, Some(prefix), &mut file);
+        }
+
+        // subspace
+        if height != last_height {
+            // Restoring subspace at specified height
+            let mut buf = BufWriter::new(&mut file);
+            for (key, _value, _gas) in self.iter_prefix(None) {
+                let value = self
+                    .read_subspace_val_with_height(
+                        &Key::from(key.to_db_key()),
+                        height,
+                        last_height,
+                    )
+                    .expect("Unable to find subspace key");
+                if let Some(value) = value {
+                    let val = HEXLOWER.encode(&value);
+                    let bytes = format!("\"{key}\" = \"{val}\"\n");
+                    buf.write_all(bytes.as_bytes())
+                        .expect("Unable to write to buffer");
+                }
+            }
+            buf.flush().expect("Unable to write to output file");
+        } else {
+            // Just dump the current subspace
+            self.dump_it(SUBSPACE_CF, None, &mut file);
+        }
+
+        // replay protection
+        // Dump of replay protection keys is possible only at the last height
+        if height == last_height {
+            self.dump_it(REPLAY_PROTECTION_CF, None, &mut file);
+        }
+
+        println!("Done writing to {}", full_path.to_string_lossy());
+    }
+
+    /// Dump data
+    fn dump_it(&self, table: &str, prefix: Option<String>, file: &mut File) {
+        let mut buf = BufWriter::new(file);
+        for (key, raw_val, _gas) in
+            iter_raw_prefix(self, table, prefix.unwrap_or_default())
+        {
+            let val = HEXLOWER.encode(&raw_val);
+            let bytes = format!("\"{key}\" = \"{val}\"\n");
+            buf.write_all(bytes.as_bytes())
+                .expect("Unable to write to buffer");
+        }
+        buf.flush().expect("Unable to write to output file");
+    }
+
+    /// Create a checkpoint of the state in redb at block height
+    /// `block_height`. All the tables are copied within a single read
+    /// transaction, so the checkpoint isn't affected by concurrent writes.
+    pub fn checkpoint(
+        &self,
+        base_dir: PathBuf,
+        block_height: BlockHeight,
+    ) -> Result<DbSnapshot> {
+        let snapshot_path = SnapshotPath(base_dir, block_height);
+        let checkpoint_dir = snapshot_path.temp_db();
+        std::fs::create_dir_all(&checkpoint_dir).or_else(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                Ok(())
+            } else {
+                Err(Error::DBError(e.to_string()))
+            }
+        })?;
+
+        let read_txn = self.inner.begin_read().map_err(db_error)?;
+        let checkpoint =
+            redb::Database::create(checkpoint_dir.join(DB_FILE_NAME))
+                .map_err(db_error)?;
+        let write_txn = checkpoint.begin_write().map_err(db_error)?;
+        for cf in DbColFam::all() {
+            let mut dest =
+                write_txn.open_table(table_def(cf)).map_err(db_error)?;
+            let src = match read_txn.open_table(table_def(cf)) {
+                Ok(src) => src,
+                Err(TableError::TableDoesNotExist(_)) => continue,
+                Err(e) => return Err(db_error(e)),
+            };
+            for entry in src.range::<&str>(..).map_err(db_error)? {
+                let (key, value) = entry.map_err(db_error)?;
+                dest.insert(key.value(), value.value()).map_err(db_error)?;
+            }
+        }
+        write_txn.commit().map_err(db_error)?;
+        Ok(DbSnapshot(snapshot_path))
+    }
+
+    /// Rollback to previous block. Given the inner working of tendermint
+    /// rollback and of the key structure of Namada, calling rollback more than
+    /// once without restarting the chain results in a single rollback.
+    pub fn rollback(
+        &mut self,
+        tendermint_block_height: BlockHeight,
+    ) -> Result<()> {
+        let last_block = self.read_last_block()?.ok_or(Error::DBError(
+            "Missing last block in storage".to_string(),
+        ))?;
+        tracing::info!(
+            "Namada last block height: {}, Tendermint last block height: {}",
+            last_block.height,
+            tendermint_block_height
+        );
+
+        // If the block height to which tendermint rolled back matches the
+        // Namada height, there's no need to rollback
+        if tendermint_block_height == last_block.height {
+            tracing::info!(
+                "Namada height already matches the rollback Tendermint \
+                 height, no need to rollback."
+            );
+            return Ok(());
+        }
+
+        let mut batch = RedbDB::batch();
+        let previous_height =
+            last_block.height.prev_height().expect("Must have a pred");
+
+        // Revert the non-height-prepended metadata storage keys which get
+        // updated with every block. Because of the way we save these
+        // three keys in storage we can only perform one rollback before
+        // restarting the chain
+        tracing::info!("Reverting non-height-prepended metadata keys");
+        batch.put(STATE_CF, BLOCK_HEIGHT_KEY, encode(&previous_height));
+        for metadata_key in [
+            NEXT_EPOCH_MIN_START_HEIGHT_KEY,
+            NEXT_EPOCH_MIN_START_TIME_KEY,
+            COMMIT_ONLY_DATA_KEY,
+            UPDATE_EPOCH_BLOCKS_DELAY_KEY,
+        ] {
+            let previous_key = format!("{PRED_KEY_PREFIX}/{metadata_key}");
+            let previous_value = self
+                .read_value_bytes(STATE_CF, &previous_key)?
+                .ok_or(Error::UnknownKey { key: previous_key })?;
+            batch.put(STATE_CF, metadata_key, previous_value);
+        }
+
+        // Revert conversion state if the epoch had been changed
+        if last_block.pred_epochs.get_epoch(previous_height)
+            != Some(last_block.epoch)
+        {
+            let previous_key =
+                format!("{PRED_KEY_PREFIX}/{CONVERSION_STATE_KEY}");
+            let previous_value = self
+                .read_value_bytes(STATE_CF, &previous_key)?
+                .ok_or(Error::UnknownKey { key: previous_key })?;
+            batch.put(STATE_CF, CONVERSION_STATE_KEY, previous_value);
+        }
+
+        // Delete block results for the last block
+        tracing::info!("Removing last block results");
+        batch.delete(
+            BLOCK_CF,
+            format!("{RESULTS_KEY_PREFIX}/{}", last_block.height),
+        );
+
+        // Restore the state of replay protection to the last block
+        tracing::info!("Restoring replay protection state");
+        // Remove the "current" tx hashes
+        for (ref current_key, _, _) in self.iter_current_replay_protection() {
+            batch.delete(REPLAY_PROTECTION_CF, current_key);
+        }
+
+        tracing::info!("Restoring previous height subspace diffs");
+        for (key, _value, _gas) in self.iter_prefix(None) {
+            // Restore previous height diff if present, otherwise delete the
+            // subspace key
+            match self.read_subspace_val_with_height(
+                &Key::from(key.to_db_key()),
+                previous_height,
+                last_block.height,
+            )? {
+                Some(previous_value) => {
+                    batch.put(SUBSPACE_CF, &key, previous_value)
+                }
+                None => batch.delete(SUBSPACE_CF, &key),
+            }
+        }
+
+        // Look for diffs in this block to find what has been deleted
+        let diff_new_key_prefix = Key {
+            segments: vec![
+                last_block.height.to_db_key(),
+                NEW_DIFF_PREFIX.to_string().to_db_key(),
+            ],
+        };
+        for (key_str, val, _) in
+            iter_diffs_prefix(self, DIFFS_CF, last_block.height, None, true)
+        {
+            let key = Key::parse(&key_str).unwrap();
+            let diff_new_key = diff_new_key_prefix.join(&key);
+            if self.read_subspace_val(&diff_new_key)?.is_none() {
+                // If there is no new value, it has been deleted in this
+                // block and we have to restore it
+                batch.put(SUBSPACE_CF, key_str, val)
+            }
+        }
+
+        // Look for non-persisted diffs for rollback
+        // Iterate the old keys first and keep a set of keys that have old val
+        let mut keys_with_old_value = HashSet::<String>::new();
+        for (key_str, val, _) in
+            iter_diffs_prefix(self, ROLLBACK_CF, last_block.height, None, true)
+        {
+            // If there is no new value, it has been deleted in this
+            // block and we have to restore it
+            keys_with_old_value.insert(key_str.clone());
+            batch.put(SUBSPACE_CF, key_str, val)
+        }
+        // Then the new keys
+        for (key_str, _val, _) in
+            iter_diffs_prefix(self, ROLLBACK_CF, last_block.height, None, false)
+        {
+            if !keys_with_old_value.contains(&key_str) {
+                // If there was no old value it means that the key was newly
+                // written in the last block and we have to delete it
+                batch.delete(SUBSPACE_CF, key_str)
+            }
+        }
+
+        if self.archive_start.is_some() {
+            tracing::info!("Removing the last block from the subspace history");
+            for table in [DIFFS_CF, ROLLBACK_CF] {
+                for is_old in [true, false] {
+                    for (key_str, _val, _) in iter_diffs_prefix(
+                        self,
+                        table,
+                        last_block.height,
+                        None,
+                        is_old,
+                    ) {
+                        batch.delete(
+                            HISTORY_CF,
+                            history_key(key_str, last_block.height),
+                        );
+                    }
+                }
+            }
+        }
+
+        tracing::info!("Deleting keys prepended with the last height");
+        let prefix = last_block.height.to_string();
+        // Delete any height-prepended key in subspace diffs and in the block
+        for table in [DIFFS_CF, BLOCK_CF] {
+            for (key, _value, _gas) in
+                iter_raw_prefix(self, table, prefix.clone())
+            {
+                batch.delete(table, key);
+            }
+        }
+
+        // Write the batch and persist changes to disk
+        tracing::info!("Flushing restored state to disk");
+        self.exec_batch(batch)
+    }
+
+    /// Read diffs of non-persisted key-vals that are only kept for rollback of
+    /// one block height.
+    #[cfg(test)]
+    pub fn read_rollback_val(
+        &self,
+        key: &Key,
+        height: BlockHeight,
+        is_old: bool,
+    ) -> Result<Option<Vec<u8>>> {
+        let key = if is_old {
+            old_and_new_diff_key(key, height)?.0
+        } else {
+            old_and_new_diff_key(key, height)?.1
+        };
+        self.read_value_bytes(ROLLBACK_CF, key)
+    }
+
+    /// Writes an entry directly to a db batch update
+    /// directly
+    pub fn insert_entry(
+        &self,
+        batch: &mut RedbWriteBatch,
+        cf: &DbColFam,
+        key: &Key,
+        new_value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        // NB: the following code only updates values
+        // written to at the last committed height
+        batch.put(cf.to_str(), key.to_string(), new_value);
+        Ok(())
+    }
+}
+
+impl DB for RedbDB {
+    type Cache = RedbCache;
+    type Migrator = DbUpdateType;
+    type RestoreSource<'a> = (&'a RedbCache, &'a mut std::fs::File);
+    type WriteBatch = RedbWriteBatch;
+
+    fn open(
+        db_path: impl AsRef<std::path::Path>,
+        cache: Option<&Self::Cache>,
+    ) -> Self {
+        open(db_path, false, cache).expect("cannot open the DB")
+    }
+
+    fn restore_from(
+        &mut self,
+        (cache, snapshot): Self::RestoreSource<'_>,
+    ) -> Result<()> {
+        snapshot.rewind().map_err(|e| {
+            Error::DBError(format!("Failed to rewind snapshot file: {e}",))
+        })?;
+
+        let db_dir = self.path.clone();
+
+        let unpack_dir = db_dir.parent().ok_or_else(|| {
+            Error::DBError(format!(
+                "Failed to query parent directory of db: {}",
+                db_dir.to_string_lossy()
+            ))
+        })?;
+
+        // NB: close the current database handle before its file is removed
+        unsafe {
+            self.invalid_handle = true;
+            ManuallyDrop::drop(&mut self.inner);
+        }
+
+        std::fs::remove_dir_all(&db_dir)
+            .expect("Failed to nuke database directory");
+        DbSnapshot::unpack(snapshot, unpack_dir)
+            .expect("Failed to unpack new db");
+
+        *self = Self::open(db_dir, Some(cache));
+
+        Ok(())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn flush(&self, _wait: bool) -> Result<()> {
+        // Every write transaction is durable once committed
+        Ok(())
+    }
+
+    fn read_last_block(&self) -> Result<Option<BlockStateRead>> {
+        // Block height
+        let height: BlockHeight =
+            match self.read_value(STATE_CF, BLOCK_HEIGHT_KEY)? {
+                Some(h) => h,
+                None => return Ok(None),
+            };
+
+        // Epoch start height and time
+        let next_epoch_min_start_height =
+            match self.read_value(STATE_CF, NEXT_EPOCH_MIN_START_HEIGHT_KEY)? {
+                Some(h) => h,
+                None => return Ok(None),
+            };
+
+        let next_epoch_min_start_time =
+            match self.read_value(STATE_CF, NEXT_EPOCH_MIN_START_TIME_KEY)? {
+                Some(t) => t,
+                None => return Ok(None),
+            };
+
+        let update_epoch_blocks_delay =
+            match self.read_value(STATE_CF, UPDATE_EPOCH_BLOCKS_DELAY_KEY)? {
+                Some(d) => d,
+                None => return Ok(None),
+            };
+
+        let commit_only_data =
+            match self.read_value(STATE_CF, COMMIT_ONLY_DATA_KEY)? {
+                Some(d) => d,
+                None => return Ok(None),
+            };
+
+        let conversion_state =
+            match self.read_value(STATE_CF, CONVERSION_STATE_KEY)? {
+                Some(c) => c,
+                None => return Ok(None),
+            };
+
+        let ethereum_height =
+            match self.read_value(STATE_CF, ETHEREUM_HEIGHT_KEY)? {
+                Some(h) => h,
+                None => return Ok(None),
+            };
+
+        let eth_events_queue =
+            match self.read_value(STATE_CF, ETH_EVENTS_QUEUE_KEY)? {
+                Some(q) => q,
+                None => return Ok(None),
+            };
+
+        // Block results
+        let results_key = format!("{RESULTS_KEY_PREFIX}/{}", height.raw());
+        let results = match self.read_value(BLOCK_CF, results_key)? {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        let prefix = height.raw();
+
+        // Restoring the Merkle tree later
+
+        let time_key = format!("{prefix}/{BLOCK_TIME_KEY_SEGMENT}");
+        let time = match self.read_value(BLOCK_CF, time_key)? {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        let epoch_key = format!("{prefix}/{EPOCH_KEY_SEGMENT}");
+        let epoch = match self.read_value(BLOCK_CF, epoch_key)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        let pred_epochs_key = format!("{prefix}/{PRED_EPOCHS_KEY_SEGMENT}");
+        let pred_epochs = match self.read_value(BLOCK_CF, pred_epochs_key)? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        let address_gen_key = format!("{prefix}/{ADDRESS_GEN_KEY_SEGMENT}");
+        let address_gen = match self.read_value(BLOCK_CF, address_gen_key)? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        Ok(Some(BlockStateRead {
+            height,
+            time,
+            epoch,
+            pred_epochs,
+            results,
+            conversion_state,
+            next_epoch_min_start_height,
+            next_epoch_min_start_time,
+            update_epoch_blocks_delay,
+            address_gen,
+            ethereum_height,
+            eth_events_queue,
+            commit_only_data,
+        }))
+    }
+
+    fn add_block_to_batch(
+        &self,
+        state: BlockStateWrite<'_>,
+        batch: &mut Self::WriteBatch,
+        is_full_commit: bool,
+    ) -> Result<()> {
+        let BlockStateWrite {
+            merkle_tree_stores,
+            header,
+            height,
+            time,
+            epoch,
+            pred_epochs,
+            next_epoch_min_start_height,
+            next_epoch_min_start_time,
+            update_epoch_blocks_delay,
+            address_gen,
+            results,
+            conversion_state,
+            ethereum_height,
+            eth_events_queue,
+            commit_only_data,
+        }: BlockStateWrite<'_> = state;
+
+        // Epoch start height and time
+        self.add_state_value_to_batch(
+            STATE_CF,
+            NEXT_EPOCH_MIN_START_HEIGHT_KEY,
+            &next_epoch_min_start_height,
+            batch,
+        )?;
+        self.add_state_value_to_batch(
+            STATE_CF,
+            NEXT_EPOCH_MIN_START_TIME_KEY,
+            &next_epoch_min_start_time,
+            batch,
+        )?;
+
+        self.add_state_value_to_batch(
+            STATE_CF,
+            UPDATE_EPOCH_BLOCKS_DELAY_KEY,
+            &update_epoch_blocks_delay,
+            batch,
+        )?;
+
+        self.add_state_value_to_batch(
+            STATE_CF,
+            COMMIT_ONLY_DATA_KEY,
+            &commit_only_data,
+            batch,
+        )?;
+
+        // Save the conversion state when the epoch is updated
+        if is_full_commit {
+            self.add_state_value_to_batch(
+                STATE_CF,
+                CONVERSION_STATE_KEY,
+                &conversion_state,
+                batch,
+            )?;
+        }
+
+        self.add_value_to_batch(
+            STATE_CF,
+            ETHEREUM_HEIGHT_KEY,
+            &ethereum_height,
+            batch,
+        );
+        self.add_value_to_batch(
+            STATE_CF,
+            ETH_EVENTS_QUEUE_KEY,
+            &eth_events_queue,
+            batch,
+        );
+
+        let prefix = height.raw();
+
+        // Merkle tree
+        for st in StoreType::iter() {
+            if st.is_stored_every_block() || is_full_commit {
+                let key_prefix = if st.is_stored_every_block() {
+                    tree_key_prefix_with_height(st, height)
+                } else {
+                    tree_key_prefix_with_epoch(st, epoch)
+                };
+                let root_key =
+                    format!("{key_prefix}/{MERKLE_TREE_ROOT_KEY_SEGMENT}");
+                self.add_value_to_batch(
+                    BLOCK_CF,
+                    root_key,
+                    merkle_tree_stores.root(st),
+                    batch,
+                );
+                let store_key =
+                    format!("{key_prefix}/{MERKLE_TREE_STORE_KEY_SEGMENT}");
+                batch.put(
+                    BLOCK_CF,
+                    store_key,
+                    merkle_tree_stores.store(st).encode(),
+                );
+            }
+        }
+
+        // Block header
+        if let Some(h) = header {
+            let header_key = format!("{prefix}/{BLOCK_HEADER_KEY_SEGMENT}");
+            self.add_value_to_batch(BLOCK_CF, header_key, &h, batch);
+        }
+        // Block time
+        let time_key = format!("{prefix}/{BLOCK_TIME_KEY_SEGMENT}");
+        self.add_value_to_batch(BLOCK_CF, time_key, &time, batch);
+        // Block epoch
+        let epoch_key = format!("{prefix}/{EPOCH_KEY_SEGMENT}");
+        self.add_value_to_batch(BLOCK_CF, epoch_key, &epoch, batch);
+        // Block results
+        let results_key = format!("{RESULTS_KEY_PREFIX}/{}", height.raw());
+        self.add_value_to_batch(BLOCK_CF, results_key, &results, batch);
+        // Predecessor block epochs
+        let pred_epochs_key = format!("{prefix}/{PRED_EPOCHS_KEY_SEGMENT}");
+        self.add_value_to_batch(BLOCK_CF, pred_epochs_key, &pred_epochs, batch);
+        // Address gen
+        let address_gen_key = format!("{prefix}/{ADDRESS_GEN_KEY_SEGMENT}");
+        self.add_value_to_batch(BLOCK_CF, address_gen_key, &address_gen, batch);
+
+        // Block height
+        self.add_value_to_batch(STATE_CF, BLOCK_HEIGHT_KEY, &height, batch);
+
+        Ok(())
+    }
+
+    fn read_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<BlockHeader>> {
+        let header_key = format!("{}/{BLOCK_HEADER_KEY_SEGMENT}", height.raw());
+        self.read_value(BLOCK_CF, header_key)
+    }
+
+    fn read_merkle_tree_stores(
+        &self,
+        epoch: Epoch,
+        base_height: BlockHeight,
+        store_type: Option<StoreType>,
+    ) -> Result<Option<MerkleTreeStoresRead>> {
+        let mut merkle_tree_stores = MerkleTreeStoresRead::default();
+        let store_types = store_type
+            .as_ref()
+            .map(|st| Either::Left(std::iter::once(st)))
+            .unwrap_or_else(|| Either::Right(StoreType::iter()));
+        for st in store_types {
+            let key_prefix = if st.is_stored_every_block() {
+                tree_key_prefix_with_height(st, base_height)
+            } else {
+                tree_key_prefix_with_epoch(st, epoch)
+            };
+            let root_key =
+                format!("{key_prefix}/{MERKLE_TREE_ROOT_KEY_SEGMENT}");
+            match self.read_value(BLOCK_CF, root_key)? {
+                Some(root) => merkle_tree_stores.set_root(st, root),
+                None => return Ok(None),
+            }
+
+            let store_key =
+                format!("{key_prefix}/{MERKLE_TREE_STORE_KEY_SEGMENT}");
+            match self.read_value_bytes(BLOCK_CF, store_key)? {
+                Some(bytes) => {
+                    merkle_tree_stores.set_store(st.decode_store(bytes)?)
+                }
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(merkle_tree_stores))
+    }
+
+    fn has_replay_protection_entry(
+        &self,
+        hash: &namada_sdk::hash::Hash,
+    ) -> Result<bool> {
+        for key in [
+            replay_protection::current_key(hash),
+            replay_protection::key(hash),
+        ] {
+            if self
+                .read_value_bytes(REPLAY_PROTECTION_CF, key.to_string())?
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn read_diffs_val(
+        &self,
+        key: &Key,
+        height: BlockHeight,
+        is_old: bool,
+    ) -> Result<Option<Vec<u8>>> {
+        let key = if is_old {
+            old_and_new_diff_key(key, height)?.0
+        } else {
+            old_and_new_diff_key(key, height)?.1
+        };
+        self.read_value_bytes(DIFFS_CF, key)
+    }
+
+    fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
+        self.read_value_bytes(SUBSPACE_CF, key.to_string())
+    }
+
+    fn read_subspace_val_with_height(
+        &self,
+        key: &Key,
+        height: BlockHeight,
+        last_height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        if self.is_archived_height(height) {
+            return self.read_history_val(key, height);
+        }
+
+        // Check if the value changed at this height
+        let (old_val_key, new_val_key) = old_and_new_diff_key(key, height)?;
+
+        // If it has a "new" val, it was written at this height
+        if let Some(new_val) = self.read_value_bytes(DIFFS_CF, new_val_key)? {
+            return Ok(Some(new_val));
+        }
+        // If it has an "old" val, it was deleted at this height
+        if self.read_value_bytes(DIFFS_CF, old_val_key)?.is_some() {
+            return Ok(None);
+        }
+
+        // If the value didn't change at the given height, we try to look for it
+        // at successor heights, up to the `last_height`
+        let mut raw_height = checked!(height.0 + 1)?;
+        loop {
+            // Try to find the next diff on this key
+            let (old_val_key, new_val_key) =
+                old_and_new_diff_key(key, BlockHeight(raw_height))?;
+            // If it has an "old" val, it's the one we're looking for
+            if let Some(bytes) = self.read_value_bytes(DIFFS_CF, old_val_key)? {
+                return Ok(Some(bytes));
+            }
+            // Check if the value was created at this height instead, which
+            // would mean that it wasn't present before
+            if self.read_value_bytes(DIFFS_CF, new_val_key)?.is_some() {
+                return Ok(None);
+            }
+
+            if raw_height >= last_height.0 {
+                // Read from latest height
+                return self.read_subspace_val(key);
+            } else {
+                checked!(raw_height += 1)?
+            }
+        }
+    }
+
+    fn enable_archive_mode(&mut self) -> Result<()> {
+        if self.archive_start.is_some() {
+            return Ok(());
+        }
+        if self.read_only {
+            return Err(Error::DBError(
+                "Cannot enable archive mode on a read-only DB".to_string(),
+            ));
+        }
+        // The history is kept starting from the next block to be committed
+        let start_height = self
+            .read_value::<BlockHeight>(STATE_CF, BLOCK_HEIGHT_KEY)?
+            .map(|last_height| last_height.next_height())
+            .unwrap_or(BlockHeight(0));
+        let mut batch = RedbDB::batch();
+        batch.put(STATE_CF, ARCHIVE_START_HEIGHT_KEY, encode(&start_height));
+        self.exec_batch(batch)?;
+        tracing::info!(
+            "Archive mode enabled, keeping the full storage history from \
+             height {start_height}"
+        );
+        self.archive_start = Some(start_height);
+        Ok(())
+    }
+
+    fn archive_start_height(&self) -> Option<BlockHeight> {
+        self.archive_start
+    }
+
+    fn read_subspace_prefix_with_height(
+        &self,
+        prefix: &Key,
+        height: BlockHeight,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        if !self.is_archived_height(height) {
+            return Err(Error::DBError(format!(
+                "The storage history at height {height} is not available, it \
+                 is only kept in archive mode"
+            )));
+        }
+
+        // Start from the latest key-vals...
+        let mut key_vals: BTreeMap<String, Vec<u8>> = self
+            .iter_prefix(Some(prefix))
+            .map(|(key, val, _gas)| (key, val))
+            .collect();
+        let Some(history) = self.open_read_table(HISTORY_CF)? else {
+            return Ok(key_vals.into_iter().collect());
+        };
+        let mut values_at_height = BTreeMap::<String, Option<Vec<u8>>>::new();
+
+        // ...including the prefix key itself, which isn't matched by the
+        // prefix iterators...
+        if !prefix.is_empty() {
+            let key = prefix.to_string();
+            if let Some(value) = self.read_subspace_val(prefix)? {
+                key_vals.insert(key.clone(), value);
+            }
+            if let Some(value) = read_history_entry_val(&history, &key, height)?
+            {
+                values_at_height.insert(key, value);
+            }
+        }
+
+        // ...and find the value of every key modified since archive mode
+        // was enabled. The history of each key is contiguous, so after
+        // looking up its value at the given height we skip past it.
+        let history_prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}/")
+        };
+        let mut start = history_prefix.clone();
+        loop {
+            let next_raw_key = {
+                let mut iter = history
+                    .range::<&str>(start.as_str()..)
+                    .map_err(db_error)?;
+                match iter.next() {
+                    Some(result) => {
+                        let (raw_key, _) = result.map_err(db_error)?;
+                        Some(raw_key.value().to_owned())
+                    }
+                    None => None,
+                }
+            };
+            let Some(raw_key) = next_raw_key
+                .filter(|raw_key| raw_key.starts_with(&history_prefix))
+            else {
+                break;
+            };
+            let (key, _) = parse_history_key(raw_key.as_bytes())?;
+            start = history_key_upper_bound(&key);
+            if let Some(value) = read_history_entry_val(&history, &key, height)?
+            {
+                values_at_height.insert(key, value);
+            }
+        }
+
+        for (key, value) in values_at_height {
+            match value {
+                Some(value) => key_vals.insert(key, value),
+                None => key_vals.remove(&key),
+            };
+        }
+        Ok(key_vals.into_iter().collect())
+    }
+
+    fn write_subspace_val(
+        &mut self,
+        height: BlockHeight,
+        key: &Key,
+        value: impl AsRef<[u8]>,
+        persist_diffs: bool,
+    ) -> Result<i64> {
+        let mut batch = RedbDB::batch();
+        let size_diff = self.batch_write_subspace_val(
+            &mut batch,
+            height,
+            key,
+            value,
+            persist_diffs,
+        )?;
+        self.exec_batch(batch)?;
+        Ok(size_diff)
+    }
+
+    fn delete_subspace_val(
+        &mut self,
+        height: BlockHeight,
+        key: &Key,
+        persist_diffs: bool,
+    ) -> Result<i64> {
+        let mut batch = RedbDB::batch();
+        let prev_len = self.batch_delete_subspace_val(
+            &mut batch,
+            height,
+            key,
+            persist_diffs,
+        )?;
+        self.exec_batch(batch)?;
+        Ok(prev_len)
+    }
+
+    fn batch() -> Self::WriteBatch {
+        RedbWriteBatch::default()
+    }
+
+    fn exec_batch(&self, batch: Self::WriteBatch) -> Result<()> {
+        if self.read_only {
+            return Err(Error::DBError(
+                "Cannot write to a read-only DB".to_string(),
+            ));
+        }
+        let txn = self.inner.begin_write().map_err(db_error)?;
+        for (name, entries) in batch.0 {
+            let mut table =
+                txn.open_table(table_def(name)).map_err(db_error)?;
+            for (key, value) in entries {
+                match value {
+                    Some(value) => {
+                        table
+                            .insert(key.as_str(), value.as_slice())
+                            .map_err(db_error)?;
+                    }
+                    None => {
+                        table.remove(key.as_str()).map_err(db_error)?;
+                    }
+                }
+            }
+        }
+        txn.commit().map_err(db_error)
+    }
+
+    fn batch_write_subspace_val(
+        &self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        key: &Key,
+        value: impl AsRef<[u8]>,
+        persist_diffs: bool,
+    ) -> Result<i64> {
+        let value = value.as_ref();
+        let size_diff = match self.read_subspace_val(key)? {
+            Some(old_value) => {
+                let len = i64::try_from(value.len())?;
+                let old_len = i64::try_from(old_value.len())?;
+                let size_diff = checked!(len - old_len)?;
+                // Persist the previous value
+                self.batch_write_subspace_diff(
+                    batch,
+                    height,
+                    key,
+                    Some(&old_value),
+                    Some(value),
+                    persist_diffs,
+                )?;
+                size_diff
+            }
+            None => {
+                self.batch_write_subspace_diff(
+                    batch,
+                    height,
+                    key,
+                    None,
+                    Some(value),
+                    persist_diffs,
+                )?;
+                i64::try_from(value.len())?
+            }
+        };
+
+        // Write the new key-val
+        batch.put(SUBSPACE_CF, key.to_string(), value);
+
+        Ok(size_diff)
+    }
+
+    fn batch_delete_subspace_val(
+        &self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        key: &Key,
+        persist_diffs: bool,
+    ) -> Result<i64> {
+        // Check the length of previous value, if any
+        let prev_len = match self.read_subspace_val(key)? {
+            Some(prev_value) => {
+                let prev_len = i64::try_from(prev_value.len())?;
+                // Persist the previous value
+                self.batch_write_subspace_diff(
+                    batch,
+                    height,
+                    key,
+                    Some(&prev_value),
+                    None,
+                    persist_diffs,
+                )?;
+                prev_len
+            }
+            None => 0,
+        };
+
+        // Delete the key-val
+        batch.delete(SUBSPACE_CF, key.to_string());
+
+        Ok(prev_len)
+    }
+
+    fn prune_merkle_tree_store(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        store_type: &StoreType,
+        epoch: Epoch,
+    ) -> Result<()> {
+        let key_prefix = tree_key_prefix_with_epoch(store_type, epoch);
+        let root_key = format!("{key_prefix}/{MERKLE_TREE_ROOT_KEY_SEGMENT}");
+        batch.delete(BLOCK_CF, root_key);
+        let store_key = format!("{key_prefix}/{MERKLE_TREE_STORE_KEY_SEGMENT}");
+        batch.delete(BLOCK_CF, store_key);
+        Ok(())
+    }
+
+    fn read_bridge_pool_signed_nonce(
+        &self,
+        height: BlockHeight,
+        last_height: BlockHeight,
+    ) -> Result<Option<ethereum_events::Uint>> {
+        let nonce_key = bridge_pool::get_signed_root_key();
+        let bytes = if height == BlockHeight(0) || height >= last_height {
+            self.read_subspace_val(&nonce_key)?
+        } else {
+            self.read_subspace_val_with_height(&nonce_key, height, last_height)?
+        };
+        match bytes {
+            Some(bytes) => {
+                let bp_root_proof = BridgePoolRootProof::try_from_slice(&bytes)
+                    .map_err(Error::BorshCodingError)?;
+                Ok(Some(bp_root_proof.data.1))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn write_replay_protection_entry(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        key: &Key,
+    ) -> Result<()> {
+        batch.put(REPLAY_PROTECTION_CF, key.to_string(), b"");
+        Ok(())
+    }
+
+    fn move_current_replay_protection_entries(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        let stripped_prefix = Some(replay_protection::current_prefix());
+
+        for (ref hash_str, _, _) in iter_prefix(
+            self,
+            REPLAY_PROTECTION_CF,
+            stripped_prefix.as_ref(),
+            None,
+        ) {
+            let hash = namada_sdk::hash::Hash::from_str(hash_str)
+                .expect("Failed hash conversion");
+            let current_key = replay_protection::current_key(&hash);
+            let key = replay_protection::key(&hash);
+
+            // Delete the current key and move it to the general bucket
+            batch.delete(REPLAY_PROTECTION_CF, current_key.to_string());
+            batch.put(REPLAY_PROTECTION_CF, key.to_string(), b"");
+        }
+
+        Ok(())
+    }
+
+    fn prune_non_persisted_diffs(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+    ) -> Result<()> {
+        for kind in [OLD_DIFF_PREFIX, NEW_DIFF_PREFIX] {
+            let diff_key_prefix = Key {
+                segments: vec![
+                    height.to_db_key(),
+                    kind.to_string().to_db_key(),
+                ],
+            };
+            for (key_str, _val, _) in
+                iter_prefix(self, ROLLBACK_CF, None, Some(&diff_key_prefix))
+            {
+                batch.delete(ROLLBACK_CF, key_str)
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn overwrite_entry(
+        &self,
+        batch: &mut Self::WriteBatch,
+        cf: &DbColFam,
+        key: &Key,
+        new_value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        self.insert_entry(batch, cf, key, new_value.as_ref())?;
+        let last_height: BlockHeight = self
+            .read_value(STATE_CF, BLOCK_HEIGHT_KEY)?
+            .ok_or_else(|| {
+                Error::DBError("No block height found".to_string())
+            })?;
+
+        // If the CF is subspace, additionally update the diffs
+        if cf == &DbColFam::SUBSPACE {
+            let diffs_key = Key::from(last_height.to_db_key())
+                .with_segment("new".to_owned())
+                .join(key)
+                .to_string();
+            batch.put(DIFFS_CF, diffs_key, new_value.as_ref());
+
+            if self.archive_start.is_some() {
+                let entry_key = history_key(key, last_height);
+                // Keep the value from before the last height, if the key was
+                // already modified at that height
+                let old = match self
+                    .read_value::<HistoryEntry>(HISTORY_CF, &entry_key)?
+                {
+                    Some(entry) => entry.old,
+                    None => self.read_subspace_val(key)?,
+                };
+                let entry = HistoryEntry {
+                    old,
+                    new: Some(new_value.as_ref().to_vec()),
+                };
+                self.add_value_to_batch(HISTORY_CF, entry_key, &entry, batch);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn apply_migration_to_batch(
+        &self,
+        updates: impl IntoIterator<Item = DbUpdateType>,
+    ) -> Result<RedbWriteBatch> {
+        let mut db_visitor = RedbUpdateVisitor::new(self);
+        for change in updates.into_iter() {
+            match change.update(&mut db_visitor) {
+                Ok(status) => {
+                    tracing::info!("{}", status);
+                }
+                Err(e) => {
+                    let error = format!(
+                        "Attempt to write to key/pattern <{}> failed:\n{}.",
+                        change.pattern(),
+                        e
+                    );
+                    tracing::error!(error);
+                    return Err(Error::DBError(error));
+                }
+            }
+        }
+        Ok(db_visitor.take_batch())
+    }
+}
+
+/// A struct that can visit a set of updates,
+/// registering them all in the batch
+pub struct RedbUpdateVisitor<'db> {
+    db: &'db RedbDB,
+    batch: RedbWriteBatch,
+}
+
+impl<'db> RedbUpdateVisitor<'db> {
+    pub fn new(db: &'db RedbDB) -> Self {
+        Self {
+            db,
+            batch: Default::default(),
+        }
+    }
+
+    pub fn take_batch(self) -> RedbWriteBatch {
+        self.batch
+    }
+}
+
+impl<'db> DBUpdateVisitor for RedbUpdateVisitor<'db> {
+    fn read(&self, key: &Key, cf: &DbColFam) -> Option<Vec<u8>> {
+        self.db
+            .read_value_bytes(cf.to_str(), key.to_string())
+            .expect("Failed to get key from storage")
+    }
+
+    fn write(&mut self, key: &Key, cf: &DbColFam, value: impl AsRef<[u8]>) {
+        self.db
+            .overwrite_entry(&mut self.batch, cf, key, value)
+            .expect("Failed to overwrite a key in storage")
+    }
+
+    fn delete(&mut self, key: &Key, cf: &DbColFam) {
+        let last_height: BlockHeight = self
+            .db
+            .read_value(STATE_CF, BLOCK_HEIGHT_KEY)
+            .unwrap()
+            .unwrap();
+        match cf {
+            DbColFam::SUBSPACE => {
+                self.db
+                    .batch_delete_subspace_val(
+                        &mut self.batch,
+                        last_height,
+                        key,
+                        true,
+                    )
+                    .expect("Failed to delete key from storage");
+            }
+            _ => {
+                self.batch.delete(cf.to_str(), key.to_string());
+            }
+        };
+    }
+
+    fn get_pattern(&self, pattern: Regex) -> Vec<(String, Vec<u8>)> {
+        self.db
+            .iter_pattern(None, pattern)
+            .map(|(k, v, _)| (k, v))
+            .collect()
+    }
+}
+
+impl<'iter> DBIter<'iter> for RedbDB {
+    type PatternIter = PersistentPatternIterator;
+    type PrefixIter = PersistentPrefixIterator;
+
+    fn iter_prefix(
+        &'iter self,
+        prefix: Option<&Key>,
+    ) -> PersistentPrefixIterator {
+        iter_prefix(self, SUBSPACE_CF, None, prefix)
+    }
+
+    fn iter_pattern(
+        &'iter self,
+        prefix: Option<&Key>,
+        pattern: Regex,
+    ) -> PersistentPatternIterator {
+        PersistentPatternIterator {
+            inner: PatternIterator {
+                iter: iter_prefix(self, SUBSPACE_CF, None, prefix),
+                pattern,
+            },
+        }
+    }
+
+    fn iter_results(&'iter self) -> PersistentPrefixIterator {
+        let mut iter = iter_raw_prefix(self, BLOCK_CF, "results".to_owned());
+        iter.inner.stripped_prefix = "results/".to_owned();
+        iter
+    }
+
+    fn iter_old_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: Option<&'iter Key>,
+    ) -> PersistentPrefixIterator {
+        iter_diffs_prefix(self, DIFFS_CF, height, prefix, true)
+    }
+
+    fn iter_new_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: Option<&'iter Key>,
+    ) -> PersistentPrefixIterator {
+        iter_diffs_prefix(self, DIFFS_CF, height, prefix, false)
+    }
+
+    fn iter_current_replay_protection(&'iter self) -> Self::PrefixIter {
+        let prefix = Some(replay_protection::current_prefix());
+        iter_prefix(self, REPLAY_PROTECTION_CF, None, prefix.as_ref())
+    }
+}
+
+fn iter_diffs_prefix(
+    db: &RedbDB,
+    table: &str,
+    height: BlockHeight,
+    prefix: Option<&Key>,
+    is_old: bool,
+) -> PersistentPrefixIterator {
+    let kind = if is_old {
+        OLD_DIFF_PREFIX
+    } else {
+        NEW_DIFF_PREFIX
+    };
+    let stripped_prefix = Some(
+        Key::from(height.to_db_key())
+            .push(&kind.to_string())
+            .unwrap(),
+    );
+    // get keys without the `stripped_prefix`
+    iter_prefix(db, table, stripped_prefix.as_ref(), prefix)
+}
+
+/// Create an iterator over key-vals in the given table matching the given
+/// prefix(es). If any, the `stripped_prefix` is matched first and will be
+/// removed from the matched keys. If any, the second `prefix` is matched
+/// against the stripped keys and remains in the matched keys.
+fn iter_prefix(
+    db: &RedbDB,
+    table: &str,
+    stripped_prefix: Option<&Key>,
+    prefix: Option<&Key>,
+) -> PersistentPrefixIterator {
+    let stripped_prefix = match stripped_prefix {
+        Some(p) if !p.is_empty() => format!("{p}/"),
+        _ => "".to_owned(),
+    };
+    let prefix = match prefix {
+        Some(p) if !p.is_empty() => {
+            format!("{stripped_prefix}{p}/")
+        }
+        _ => stripped_prefix.clone(),
+    };
+    let mut iter = iter_raw_prefix(db, table, prefix);
+    iter.inner.stripped_prefix = stripped_prefix;
+    iter
+}
+
+/// Create an iterator over the raw key-vals in the given table that start with
+/// the given string, without stripping it from the matched keys.
+fn iter_raw_prefix(
+    db: &RedbDB,
+    table: &str,
+    prefix: String,
+) -> PersistentPrefixIterator {
+    let range = db
+        .range_from(table, &prefix)
+        .expect("Prefix iterator shouldn't fail");
+    PersistentPrefixIterator {
+        inner: PrefixIterator {
+            iter: range,
+            stripped_prefix: String::default(),
+        },
+        prefix,
+    }
+}
+
+#[derive(Debug)]
+pub struct PersistentPrefixIterator {
+    inner: PrefixIterator<Option<TableRange>>,
+    /// The prefix of all the matched keys, at which the iteration stops
+    prefix: String,
+}
+
+impl Iterator for PersistentPrefixIterator {
+    type Item = (String, Vec<u8>, Gas);
+
+    /// Returns the next pair and the gas cost
+    fn next(&mut self) -> Option<(String, Vec<u8>, Gas)> {
+        loop {
+            let (key, val) = self
+                .inner
+                .iter
+                .as_mut()?
+                .next()?
+                .expect("Prefix iterator shouldn't fail");
+            let key = key.value();
+            if !key.starts_with(&self.prefix) {
+                // The keys are ordered, so there are no more matches
+                self.inner.iter = None;
+                return None;
+            }
+            let val = val.value();
+            if let Some(k) = key.strip_prefix(&self.inner.stripped_prefix) {
+                let gas = k.len().checked_add(val.len())?;
+                return Some((k.to_owned(), val.to_vec(), (gas as u64).into()));
+            } else {
+                tracing::warn!(
+                    "Unmatched prefix \"{}\" in iterator's key \"{key}\"",
+                    self.inner.stripped_prefix
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PersistentPatternIterator {
+    inner: PatternIterator<PersistentPrefixIterator>,
+}
+
+impl Iterator for PersistentPatternIterator {
+    type Item = (String, Vec<u8>, Gas);
+
+    /// Returns the next pair and the gas cost
+    fn next(&mut self) -> Option<(String, Vec<u8>, Gas)> {
+        loop {
+            let next_result = self.inner.iter.next()?;
+            if self.inner.pattern.is_match(&next_result.0) {
+                return Some(next_result);
+            }
+        }
+    }
+}
+
+impl DBWriteBatch for RedbWriteBatch {}
+
+/// The definition of the table that holds the column family of the given name
+fn table_def(name: &str) -> Table<'_> {
+    TableDefinition::new(name)
+}
+
+fn db_error(error: impl std::fmt::Display) -> Error {
+    Error::DBError(error.to_string())
+}
+
+/// Read an entry of a range over the history table, if it belongs to the
+/// history of the key with the given prefix.
+fn next_history_entry(
+    next: Option<TableEntry>,
+    key_prefix: &str,
+) -> Result<Option<HistoryEntry>> {
+    let Some(result) = next else {
+        return Ok(None);
+    };
+    let (raw_key, raw_entry) = result.map_err(db_error)?;
+    if !raw_key.value().starts_with(key_prefix) {
+        return Ok(None);
+    }
+    let entry = decode(raw_entry.value()).map_err(Error::CodingError)?;
+    Ok(Some(entry))
+}
+
+/// Read the value of an account subspace key at the given height from its
+/// history entries. Returns `None` if the key has no history entries.
+fn read_history_entry_val(
+    history: &ReadOnlyTable<&'static str, &'static [u8]>,
+    key: impl std::fmt::Display,
+    height: BlockHeight,
+) -> Result<Option<Option<Vec<u8>>>> {
+    let key_prefix = history_key_prefix(&key);
+
+    // The last modification at or before the given height holds the value
+    let entry_key = history_key(&key, height);
+    let mut iter = history
+        .range::<&str>(..=entry_key.as_str())
+        .map_err(db_error)?;
+    if let Some(entry) = next_history_entry(iter.next_back(), &key_prefix)? {
+        return Ok(Some(entry.new));
+    }
+
+    // Otherwise, the key was last modified before archive mode was enabled
+    // and its next modification, if any, holds the value
+    let mut iter = history
+        .range::<&str>(key_prefix.as_str()..)
+        .map_err(db_error)?;
+    Ok(next_history_entry(iter.next(), &key_prefix)?.map(|entry| entry.old))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::storage::db_tests::{db_test_suite, TestDB};
+
+    impl TestDB for RedbDB {
+        fn open_db(path: &Path, read_only: bool) -> Self {
+            open(path, read_only, None).expect("Test failed")
+        }
+
+        fn rollback(&mut self, height: BlockHeight) -> Result<()> {
+            RedbDB::rollback(self, height)
+        }
+
+        fn checkpoint(
+            &self,
+            base_dir: PathBuf,
+            height: BlockHeight,
+        ) -> Result<DbSnapshot> {
+            RedbDB::checkpoint(self, base_dir, height)
+        }
+
+        fn restore(path: &Path, tarball: &mut File) -> Self {
+            let cache = RedbCache::new_lru_cache(0);
+            let mut db = RedbDB::open(path, Some(&cache));
+            db.restore_from((&cache, tarball)).expect("Test failed");
+            db
+        }
+
+        fn read_raw(&self, cf: &str, key: &str) -> Option<Vec<u8>> {
+            self.read_value_bytes(cf, key).expect("Test failed")
+        }
+
+        fn raw_entries(&self) -> HashMap<String, Vec<u8>> {
+            let mut entries = HashMap::new();
+            for cf in DbColFam::all() {
+                for (key, raw_val, _gas) in
+                    iter_raw_prefix(self, cf, String::default())
+                {
+                    entries.insert(key, raw_val);
+                }
+            }
+            entries
+        }
+    }
+
+    db_test_suite!(RedbDB);
+}
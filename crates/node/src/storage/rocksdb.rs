@@ -19,6 +19,8 @@
 //!     - `commit_only_data_commitment`
 //!     - `update_epoch_blocks_delay`
 //!   - `conversion_state`: MASP conversion state
+//!   - `archive_start_height`: the height from which the `history` is kept, if
+//!     the DB is in archive mode
 //! - `subspace`: accounts sub-spaces
 //!   - `{address}/{dyn}`: any byte data associated with accounts
 //! - `diffs`: diffs in account subspaces' key-vals modified with `persist_diff
@@ -43,16 +45,20 @@
 //! - `replay_protection`: hashes of processed tx for replay protection purposes
 //!     - `current/{hash}`: a hash included in the current block
 //!     - `{hash}`: a hash included in previous blocks
+//! - `history`: only written in archive mode, the old and new values of every
+//!   account subspace key-val modified at some height, ordered by key first to
+//!   allow for fast lookups of the value at any height
+//!   - `{dyn}\0{height}`: old and new values of the key at height `h`
 
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufWriter, Read, Seek, Write};
+use std::io::{BufWriter, Seek, Write};
 use std::mem::ManuallyDrop;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Mutex;
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use itertools::Either;
 use namada_replay_protection as replay_protection;
@@ -73,7 +79,7 @@ use namada_sdk::state::{
 };
 use namada_sdk::storage::{
     BlockHeader, BlockHeight, DbColFam, Epoch, Key, KeySeg, BLOCK_CF, DIFFS_CF,
-    REPLAY_PROTECTION_CF, ROLLBACK_CF, STATE_CF, SUBSPACE_CF,
+    HISTORY_CF, REPLAY_PROTECTION_CF, ROLLBACK_CF, STATE_CF, SUBSPACE_CF,
 };
 use namada_sdk::{decode, encode, ethereum_events};
 use rayon::prelude::*;
@@ -84,8 +90,19 @@ use rocksdb::{
     ReadOptions, WriteBatch,
 };
 
+use super::{
+    history_key, history_key_prefix, history_key_upper_bound,
+    old_and_new_diff_key, parse_history_key, DbSnapshot, HistoryEntry,
+    SnapshotPath, ADDRESS_GEN_KEY_SEGMENT, ARCHIVE_START_HEIGHT_KEY,
+    BLOCK_HEADER_KEY_SEGMENT, BLOCK_HEIGHT_KEY, BLOCK_TIME_KEY_SEGMENT,
+    COMMIT_ONLY_DATA_KEY, CONVERSION_STATE_KEY, EPOCH_KEY_SEGMENT,
+    ETHEREUM_HEIGHT_KEY, ETH_EVENTS_QUEUE_KEY, MERKLE_TREE_ROOT_KEY_SEGMENT,
+    MERKLE_TREE_STORE_KEY_SEGMENT, NEW_DIFF_PREFIX,
+    NEXT_EPOCH_MIN_START_HEIGHT_KEY, NEXT_EPOCH_MIN_START_TIME_KEY,
+    OLD_DIFF_PREFIX, PRED_EPOCHS_KEY_SEGMENT, PRED_KEY_PREFIX,
+    RESULTS_KEY_PREFIX, UPDATE_EPOCH_BLOCKS_DELAY_KEY,
+};
 use crate::config::utils::num_of_threads;
-use crate::storage;
 
 // TODO the DB schema will probably need some kind of versioning
 
@@ -93,31 +110,6 @@ use crate::storage;
 const ENV_VAR_ROCKSDB_COMPACTION_THREADS: &str =
     "NAMADA_ROCKSDB_COMPACTION_THREADS";
 
-const BLOCK_HEIGHT_KEY: &str = "height";
-const NEXT_EPOCH_MIN_START_HEIGHT_KEY: &str = "next_epoch_min_start_height";
-const NEXT_EPOCH_MIN_START_TIME_KEY: &str = "next_epoch_min_start_time";
-const UPDATE_EPOCH_BLOCKS_DELAY_KEY: &str = "update_epoch_blocks_delay";
-const COMMIT_ONLY_DATA_KEY: &str = "commit_only_data_commitment";
-const CONVERSION_STATE_KEY: &str = "conversion_state";
-const ETHEREUM_HEIGHT_KEY: &str = "ethereum_height";
-const ETH_EVENTS_QUEUE_KEY: &str = "eth_events_queue";
-const RESULTS_KEY_PREFIX: &str = "results";
-const PRED_KEY_PREFIX: &str = "pred";
-
-const MERKLE_TREE_ROOT_KEY_SEGMENT: &str = "root";
-const MERKLE_TREE_STORE_KEY_SEGMENT: &str = "store";
-const BLOCK_HEADER_KEY_SEGMENT: &str = "header";
-const BLOCK_TIME_KEY_SEGMENT: &str = "time";
-const EPOCH_KEY_SEGMENT: &str = "epoch";
-const PRED_EPOCHS_KEY_SEGMENT: &str = "pred_epochs";
-const ADDRESS_GEN_KEY_SEGMENT: &str = "address_gen";
-
-const OLD_DIFF_PREFIX: &str = "old";
-const NEW_DIFF_PREFIX: &str = "new";
-
-// 10 MB
-const MAX_STATE_SYNC_CHUNK_SIZE: usize = 10_000_000;
-
 /// RocksDB handle
 #[derive(Debug)]
 pub struct RocksDB {
@@ -127,6 +119,9 @@ pub struct RocksDB {
     read_only: bool,
     /// Whether the handle is invalid
     invalid_handle: bool,
+    /// The height from which the history of the subspace is kept, if the DB
+    /// is in archive mode
+    archive_start: Option<BlockHeight>,
 }
 
 /// DB Handle for batch writes.
@@ -232,7 +227,18 @@ pub fn open(
         REPLAY_PROTECTION_CF,
         replay_protection_cf_opts,
     ));
-    Ok(if read_only {
+
+    // for the subspace history in archive mode (insert-intensive, with
+    // ordered lookups)
+    let mut history_cf_opts = Options::default();
+    history_cf_opts.set_compression_type(DBCompressionType::Zstd);
+    history_cf_opts.set_compression_options(0, 0, 0, 1024 * 1024);
+    history_cf_opts.set_level_compaction_dynamic_level_bytes(true);
+    history_cf_opts.set_compaction_style(DBCompactionStyle::Level);
+    history_cf_opts.set_block_based_table_factory(&table_opts);
+    cfs.push(ColumnFamilyDescriptor::new(HISTORY_CF, history_cf_opts));
+
+    let mut db = if read_only {
         RocksDB {
             inner: ManuallyDrop::new(
                 rocksdb::DB::open_cf_descriptors_read_only(
@@ -242,6 +248,7 @@ pub fn open(
             ),
             invalid_handle: false,
             read_only: true,
+            archive_start: None,
         }
     } else {
         RocksDB {
@@ -251,8 +258,12 @@ pub fn open(
             ),
             invalid_handle: false,
             read_only: false,
+            archive_start: None,
         }
-    })
+    };
+    let state_cf = db.get_column_family(STATE_CF)?;
+    db.archive_start = db.read_value(state_cf, ARCHIVE_START_HEIGHT_KEY)?;
+    Ok(db)
 }
 
 impl Drop for RocksDB {
@@ -369,9 +380,73 @@ impl RocksDB {
         if let Some(new_value) = new_value {
             batch.0.put_cf(cf, new_val_key, new_value);
         }
+
+        if self.archive_start.is_some() {
+            let history_cf = self.get_column_family(HISTORY_CF)?;
+            let entry = HistoryEntry {
+                old: old_value.map(<[u8]>::to_vec),
+                new: new_value.map(<[u8]>::to_vec),
+            };
+            batch.0.put_cf(
+                history_cf,
+                history_key(key, height),
+                encode(&entry),
+            );
+        }
         Ok(())
     }
 
+    /// Read the value of an account subspace key at the given height from the
+    /// history kept in archive mode.
+    fn read_history_val(
+        &self,
+        key: &Key,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        match self.read_history_entry_val(key, height)? {
+            Some(value) => Ok(value),
+            // The key wasn't modified since archive mode was enabled
+            None => self.read_subspace_val(key),
+        }
+    }
+
+    /// Read the value of an account subspace key at the given height from its
+    /// history entries. Returns `None` if the key has no history entries.
+    fn read_history_entry_val(
+        &self,
+        key: impl std::fmt::Display,
+        height: BlockHeight,
+    ) -> Result<Option<Option<Vec<u8>>>> {
+        let history_cf = self.get_column_family(HISTORY_CF)?;
+        let key_prefix = history_key_prefix(&key);
+
+        // The last modification at or before the given height holds the value
+        let mut iter = self.inner.iterator_cf(
+            history_cf,
+            IteratorMode::From(
+                history_key(&key, height).as_bytes(),
+                Direction::Reverse,
+            ),
+        );
+        if let Some((_, entry)) = next_history_entry(&mut iter, &key_prefix)? {
+            return Ok(Some(entry.new));
+        }
+
+        // Otherwise, the key was last modified before archive mode was enabled
+        // and its next modification, if any, holds the value
+        let mut iter = self.inner.iterator_cf(
+            history_cf,
+            IteratorMode::From(key_prefix.as_bytes(), Direction::Forward),
+        );
+        Ok(next_history_entry(&mut iter, &key_prefix)?
+            .map(|(_, entry)| entry.old))
+    }
+
+    /// Check if the history of the subspace at the given height is kept.
+    fn is_archived_height(&self, height: BlockHeight) -> bool {
+        self.archive_start.is_some_and(|start| height >= start)
+    }
+
     /// Dump last known block
     pub fn dump_block(
         &self,
@@ -532,7 +607,7 @@ impl RocksDB {
             }
         })?;
         checkpoint
-            .create_checkpoint(snapshot_path.temp_rocksdb())
+            .create_checkpoint(snapshot_path.temp_db())
             .map_err(|e| Error::DBError(e.to_string()))?;
         Ok(DbSnapshot(snapshot_path))
     }
@@ -706,6 +781,27 @@ impl RocksDB {
             }
         }
 
+        if self.archive_start.is_some() {
+            tracing::info!("Removing the last block from the subspace history");
+            let history_cf = self.get_column_family(HISTORY_CF)?;
+            for cf in [diffs_cf, rollback_cf] {
+                for is_old in [true, false] {
+                    for (key_str, _val, _) in iter_diffs_prefix(
+                        self,
+                        cf,
+                        last_block.height,
+                        None,
+                        is_old,
+                    ) {
+                        batch.0.delete_cf(
+                            history_cf,
+                            history_key(key_str, last_block.height),
+                        );
+                    }
+                }
+            }
+        }
+
         tracing::info!("Deleting keys prepended with the last height");
         let prefix = last_block.height.to_string();
         let mut delete_keys = |cf: &ColumnFamily| {
@@ -734,7 +830,7 @@ impl RocksDB {
     }
 
     #[inline]
-    pub fn column_families(&self) -> [(&'static str, &ColumnFamily); 6] {
+    pub fn column_families(&self) -> [(&'static str, &ColumnFamily); 7] {
         DbColFam::all()
             .iter()
             .map(|cf| {
@@ -746,7 +842,7 @@ impl RocksDB {
             })
             .collect::<Vec<_>>()
             .try_into()
-            .map_err(|_| "There should be exactly six column families")
+            .map_err(|_| "There should be exactly seven column families")
             .unwrap()
     }
 
@@ -796,285 +892,6 @@ impl RocksDB {
     }
 }
 
-/// The path to a snapshot.
-#[derive(Clone, Debug)]
-pub struct SnapshotPath(pub PathBuf, pub BlockHeight);
-
-impl SnapshotPath {
-    /// Return the root path where snapshots are stored.
-    pub fn snapshot_root_path(mut base_dir: PathBuf) -> PathBuf {
-        base_dir.push("snapshots");
-        base_dir
-    }
-
-    /// Remove all data pertaining to the current snapshot.
-    pub fn remove(&self) -> std::io::Result<()> {
-        std::fs::remove_dir_all(self.base())
-    }
-
-    /// Return the base path associated with this [`SnapshotPath`].
-    pub fn base(&self) -> PathBuf {
-        let mut buf = Self::snapshot_root_path(self.0.clone());
-        let height = self.1.0;
-        buf.push(format!("block-{height:016}"));
-        buf
-    }
-
-    /// Return the chunk hashes path associated with this [`SnapshotPath`].
-    pub fn chunk_hashes(&self) -> PathBuf {
-        let mut buf = self.base();
-        buf.push("chunks-hashed");
-        buf
-    }
-
-    /// Return the root of the chunk hashes tree path associated with this
-    /// [`SnapshotPath`].
-    pub fn chunks_root_hash(&self) -> PathBuf {
-        let mut buf = self.base();
-        buf.push("chunks-root-hash");
-        buf
-    }
-
-    /// Return the temporary rocksdb path associated with this [`SnapshotPath`].
-    pub fn temp_rocksdb(&self) -> PathBuf {
-        let mut buf = self.base();
-        buf.push("db");
-        buf
-    }
-
-    /// Return the temporary tarball path associated with this [`SnapshotPath`].
-    ///
-    /// The value of `compression_extension` should reflect the compression
-    /// algorithm used (e.g. `gz` for Gzip).
-    pub fn temp_tarball(&self, compression_extension: &str) -> PathBuf {
-        let mut buf = self.base();
-        buf.push(format!("db.tar.{compression_extension}"));
-        buf
-    }
-
-    /// Return the path of the chunk `chk` associated with this
-    /// [`SnapshotPath`].
-    pub fn chunk_with_id(&self, chk: usize) -> PathBuf {
-        let mut buf = self.base();
-        buf.push(format!("chunk-{chk:032}"));
-        buf
-    }
-}
-
-/// Metadata pertaining to some database snapshot.
-#[derive(Debug)]
-pub struct DbSnapshotMeta {
-    /// The height of the snapshot.
-    pub height: BlockHeight,
-    /// List of the hashes of all chunks.
-    pub chunk_hashes: Vec<Hash>,
-    /// Hash of all the chunk hashes, forming a shallow tree.
-    pub root_hash: Hash,
-}
-
-#[derive(Clone)]
-pub struct DbSnapshot(pub SnapshotPath);
-
-impl DbSnapshot {
-    /// The magic number referring to the format of the snapshot.
-    pub const FORMAT_MAGIC: u32 = 0;
-
-    /// Package and chunk the contents of the db snapshot.
-    // NB: passing an owned `self` guarantees we don't attempt to call
-    // this method again, which removes the temporary checkpoint dir
-    // created by rocksdb
-    pub fn package(self) -> std::io::Result<()> {
-        self.build_tarball()?;
-        self.chunk_snapshot(MAX_STATE_SYNC_CHUNK_SIZE)?;
-        Ok(())
-    }
-
-    pub fn unpack(
-        archive_file: &mut std::fs::File,
-        dest: impl AsRef<Path>,
-    ) -> std::io::Result<()> {
-        use zstd::stream::read::Decoder;
-
-        let file_buf_reader = std::io::BufReader::new(archive_file);
-        let zstd_decoder = Decoder::new(file_buf_reader)?;
-
-        let mut archive = tar::Archive::new(zstd_decoder);
-        archive.unpack(dest)?;
-
-        Ok(())
-    }
-
-    pub(crate) fn build_tarball(&self) -> std::io::Result<()> {
-        use zstd::stream::write::Encoder;
-
-        let snapshot_temp_db_path = self.0.temp_rocksdb();
-
-        let mut tar_builder = {
-            let file_handle = File::create(self.0.temp_tarball("zst"))?;
-            let zstd_encoder = Encoder::new(file_handle, 0)?.auto_finish();
-            tar::Builder::new(zstd_encoder)
-        };
-
-        // build tarball with rocksdb checkpoint contents
-        tar_builder.append_dir_all("db", &snapshot_temp_db_path)?;
-        tar_builder.finish()?;
-        _ = tar_builder;
-
-        // remove aux checkpoint dir
-        std::fs::remove_dir_all(&snapshot_temp_db_path)
-    }
-
-    fn chunk_snapshot(&self, max_chunk: usize) -> std::io::Result<()> {
-        let tarball_path = self.0.temp_tarball("zst");
-
-        let mut buf = vec![0; max_chunk];
-        let mut file = File::open(&tarball_path)?;
-
-        let mut eof = false;
-        let mut chunk_hashes = vec![];
-
-        // TODO: we can use tokio here to read chunks
-        // in parallel
-        //
-        // 1. determine tar archive size
-        // 2. spawn len / MAX_STATE_SYNC_CHUNK_SIZE tasks
-        // 3. spawn one more task if necessary to read chunk smaller than
-        //    MAX_STATE_SYNC_CHUNK_SIZE
-        // 4. assemble read data (need to store hash of the chunk)
-
-        for chunk_id in 0.. {
-            let mut read = 0;
-
-            // read up to `MAX_STATE_SYNC_CHUNK_SIZE` bytes
-            while read != max_chunk {
-                match file.read(&mut buf[read..]) {
-                    Ok(0) => {
-                        eof = true;
-                        break;
-                    }
-                    Ok(n) => checked!(read += n).unwrap(),
-                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                    Err(e) => return Err(e),
-                }
-            }
-
-            let chunk = &buf[..read];
-
-            std::fs::write(self.0.chunk_with_id(chunk_id), chunk)?;
-            chunk_hashes.push(Hash::sha256(chunk));
-
-            if eof {
-                break;
-            }
-        }
-
-        let chunk_hashes = chunk_hashes.serialize_to_vec();
-        let hash_of_all_chunks = Hash::sha256(&chunk_hashes);
-        let snapshot_hash = Hash::sha256(
-            (Self::FORMAT_MAGIC, hash_of_all_chunks).serialize_to_vec(),
-        );
-
-        std::fs::remove_file(tarball_path)?;
-        std::fs::write(self.0.chunk_hashes(), chunk_hashes)?;
-        std::fs::write(self.0.chunks_root_hash(), snapshot_hash)?;
-
-        Ok(())
-    }
-
-    /// Keep `number_to_keep` latest snapshots. All others
-    /// are deleted.
-    pub fn cleanup(
-        latest_height: BlockHeight,
-        base_dir: &Path,
-        number_to_keep: u64,
-    ) -> std::io::Result<()> {
-        let latest_height = latest_height.0;
-
-        for height in Self::heights_of_stored_snapshots(base_dir)? {
-            // this is correct... don't worry about it
-            if checked!(height + number_to_keep <= latest_height).unwrap() {
-                let snap = SnapshotPath(base_dir.into(), BlockHeight(height));
-                snap.remove()?;
-            }
-        }
-        Ok(())
-    }
-
-    /// Load the metadata of the given snapshot heights.
-    pub fn load_snapshot_metadata(
-        base_dir: &Path,
-        snapshot_heights: impl IntoIterator<Item = u64>,
-    ) -> impl Iterator<Item = std::io::Result<DbSnapshotMeta>> {
-        let mut iter = snapshot_heights.into_iter();
-        let base_dir = base_dir.to_owned();
-
-        std::iter::from_fn(move || {
-            let height = BlockHeight(iter.next()?);
-
-            let load = || {
-                let snap = SnapshotPath(base_dir.clone(), height);
-
-                let chunk_hashes = BorshDeserialize::try_from_slice(
-                    &std::fs::read(snap.chunk_hashes())?,
-                )?;
-                let root_hash = BorshDeserialize::try_from_slice(
-                    &std::fs::read(snap.chunks_root_hash())?,
-                )?;
-
-                Ok(DbSnapshotMeta {
-                    height,
-                    chunk_hashes,
-                    root_hash,
-                })
-            };
-
-            Some(load())
-        })
-    }
-
-    /// List all block heights whose state snapshots exist.
-    pub fn heights_of_stored_snapshots(
-        base_dir: &Path,
-    ) -> std::io::Result<Vec<u64>> {
-        let snapshot_root = SnapshotPath::snapshot_root_path(base_dir.into());
-        let mut heights = vec![];
-
-        for entry in std::fs::read_dir(snapshot_root)? {
-            let entry = entry?;
-            let entry_path = entry.path();
-
-            if entry_path.is_dir() {
-                let Some(file_name) =
-                    entry_path.file_name().and_then(|f| f.to_str())
-                else {
-                    continue;
-                };
-                let Some(("block", height_str)) = file_name.split_once('-')
-                else {
-                    continue;
-                };
-                let Some(height): Option<u64> = height_str.parse().ok() else {
-                    continue;
-                };
-                heights.push(height);
-            }
-        }
-
-        Ok(heights)
-    }
-
-    /// Load the specified chunk of a snapshot at the given block height
-    pub fn load_chunk(
-        height: BlockHeight,
-        chunk: u64,
-        base_dir: &Path,
-    ) -> std::io::Result<Vec<u8>> {
-        let snap = SnapshotPath(base_dir.into(), height);
-        #[allow(clippy::cast_possible_truncation)]
-        std::fs::read(snap.chunk_with_id(chunk as _))
-    }
-}
-
 impl DB for RocksDB {
     type Cache = rocksdb::Cache;
     type Migrator = DbUpdateType;
@@ -1474,6 +1291,10 @@ impl DB for RocksDB {
         height: BlockHeight,
         last_height: BlockHeight,
     ) -> Result<Option<Vec<u8>>> {
+        if self.is_archived_height(height) {
+            return self.read_history_val(key, height);
+        }
+
         // Check if the value changed at this height
         let diffs_cf = self.get_column_family(DIFFS_CF)?;
         let (old_val_key, new_val_key) = old_and_new_diff_key(key, height)?;
@@ -1529,6 +1350,97 @@ impl DB for RocksDB {
         }
     }
 
+    fn enable_archive_mode(&mut self) -> Result<()> {
+        if self.archive_start.is_some() {
+            return Ok(());
+        }
+        if self.read_only {
+            return Err(Error::DBError(
+                "Cannot enable archive mode on a read-only DB".to_string(),
+            ));
+        }
+        let state_cf = self.get_column_family(STATE_CF)?;
+        // The history is kept starting from the next block to be committed
+        let start_height = self
+            .read_value::<BlockHeight>(state_cf, BLOCK_HEIGHT_KEY)?
+            .map(|last_height| last_height.next_height())
+            .unwrap_or(BlockHeight(0));
+        self.inner
+            .put_cf(state_cf, ARCHIVE_START_HEIGHT_KEY, encode(&start_height))
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        tracing::info!(
+            "Archive mode enabled, keeping the full storage history from \
+             height {start_height}"
+        );
+        self.archive_start = Some(start_height);
+        Ok(())
+    }
+
+    fn archive_start_height(&self) -> Option<BlockHeight> {
+        self.archive_start
+    }
+
+    fn read_subspace_prefix_with_height(
+        &self,
+        prefix: &Key,
+        height: BlockHeight,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        if !self.is_archived_height(height) {
+            return Err(Error::DBError(format!(
+                "The storage history at height {height} is not available, it \
+                 is only kept in archive mode"
+            )));
+        }
+
+        // Start from the latest key-vals...
+        let mut key_vals: BTreeMap<String, Vec<u8>> = self
+            .iter_prefix(Some(prefix))
+            .map(|(key, val, _gas)| (key, val))
+            .collect();
+        let mut values_at_height = BTreeMap::<String, Option<Vec<u8>>>::new();
+
+        // ...including the prefix key itself, which isn't matched by the
+        // prefix iterators...
+        if !prefix.is_empty() {
+            let key = prefix.to_string();
+            if let Some(value) = self.read_subspace_val(prefix)? {
+                key_vals.insert(key.clone(), value);
+            }
+            if let Some(value) = self.read_history_entry_val(&key, height)? {
+                values_at_height.insert(key, value);
+            }
+        }
+
+        // ...and find the value of every key modified since archive mode
+        // was enabled. The history of each key is contiguous, so after
+        // looking up its value at the given height we seek past it.
+        let history_cf = self.get_column_family(HISTORY_CF)?;
+        let history_prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{prefix}/")
+        };
+        let read_opts = make_iter_read_opts(Some(history_prefix.clone()));
+        let mut iter = self.inner.raw_iterator_cf_opt(history_cf, read_opts);
+        iter.seek(history_prefix.as_bytes());
+        while let Some(raw_key) = iter.key() {
+            let (key, _) = parse_history_key(raw_key)?;
+            iter.seek(history_key_upper_bound(&key).as_bytes());
+            if let Some(value) = self.read_history_entry_val(&key, height)? {
+                values_at_height.insert(key, value);
+            }
+        }
+        iter.status().map_err(|e| Error::DBError(e.into_string()))?;
+
+        for (key, value) in values_at_height {
+            match value {
+                Some(value) => key_vals.insert(key, value),
+                None => key_vals.remove(&key),
+            };
+        }
+        Ok(key_vals.into_iter().collect())
+    }
+
     fn write_subspace_val(
         &mut self,
         height: BlockHeight,
@@ -1803,6 +1715,24 @@ impl DB for RocksDB {
                 new_value.as_ref().to_vec(),
                 batch,
             );
+
+            if self.archive_start.is_some() {
+                let history_cf = self.get_column_family(HISTORY_CF)?;
+                let entry_key = history_key(key, last_height);
+                // Keep the value from before the last height, if the key was
+                // already modified at that height
+                let old = match self
+                    .read_value::<HistoryEntry>(history_cf, &entry_key)?
+                {
+                    Some(entry) => entry.old,
+                    None => self.read_subspace_val(key)?,
+                };
+                let entry = HistoryEntry {
+                    old,
+                    new: Some(new_value.as_ref().to_vec()),
+                };
+                self.add_value_to_batch(history_cf, entry_key, &entry, batch);
+            }
         }
 
         Ok(())
@@ -1813,7 +1743,7 @@ impl DB for RocksDB {
         &self,
         updates: impl IntoIterator<Item = DbUpdateType>,
     ) -> Result<RocksDBWriteBatch> {
-        let mut db_visitor = storage::RocksDBUpdateVisitor::new(self);
+        let mut db_visitor = RocksDBUpdateVisitor::new(self);
         for change in updates.into_iter() {
             match change.update(&mut db_visitor) {
                 Ok(status) => {
@@ -2150,20 +2080,23 @@ fn make_iter_read_opts(prefix: Option<String>) -> ReadOptions {
 
 impl DBWriteBatch for RocksDBWriteBatch {}
 
-fn old_and_new_diff_key(
-    key: &Key,
-    height: BlockHeight,
-) -> Result<(String, String)> {
-    let key_prefix = Key::from(height.to_db_key());
-    let old = key_prefix
-        .push(&OLD_DIFF_PREFIX.to_owned())
-        .map_err(Error::KeyError)?
-        .join(key);
-    let new = key_prefix
-        .push(&NEW_DIFF_PREFIX.to_owned())
-        .map_err(Error::KeyError)?
-        .join(key);
-    Ok((old.to_string(), new.to_string()))
+/// Read the next entry of the history CF iterator, if it belongs to the
+/// history of the key with the given prefix.
+fn next_history_entry(
+    iter: &mut rocksdb::DBIterator<'_>,
+    key_prefix: &str,
+) -> Result<Option<(BlockHeight, HistoryEntry)>> {
+    let Some(result) = iter.next() else {
+        return Ok(None);
+    };
+    let (raw_key, raw_entry) =
+        result.map_err(|e| Error::DBError(e.into_string()))?;
+    if !raw_key.starts_with(key_prefix.as_bytes()) {
+        return Ok(None);
+    }
+    let (_, height) = parse_history_key(&raw_key)?;
+    let entry = decode(raw_entry).map_err(Error::CodingError)?;
+    Ok(Some((height, entry)))
 }
 
 /// Try to increase NOFILE limit and set the `max_open_files` limit to it in
@@ -2223,732 +2156,63 @@ mod imp {
     }
 }
 
-#[allow(clippy::arithmetic_side_effects)]
 #[cfg(test)]
 mod test {
-    use namada_apps_lib::collections::HashMap;
-    use namada_sdk::address::EstablishedAddressGen;
-    use namada_sdk::state::{MerkleTree, Sha256Hasher};
-    use namada_sdk::storage::conversion_state::ConversionState;
-    use namada_sdk::storage::types::CommitOnlyData;
-    use namada_sdk::storage::{BlockResults, Epochs, EthEventsQueue};
-    use namada_sdk::time::DateTimeUtc;
-    use tempfile::tempdir;
+    use namada_sdk::collections::HashMap;
 
     use super::*;
+    use crate::storage::db_tests::{db_test_suite, TestDB};
 
-    /// Test that a block written can be loaded back from DB.
-    #[test]
-    fn test_load_state() {
-        let dir = tempdir().unwrap();
-        let db = RocksDB::open(dir.path(), None);
-
-        let mut batch = RocksDB::batch();
-        let last_height = BlockHeight::default();
-        db.batch_write_subspace_val(
-            &mut batch,
-            last_height,
-            &Key::parse("test").unwrap(),
-            vec![1_u8, 1, 1, 1],
-            true,
-        )
-        .unwrap();
-
-        add_block_to_batch(
-            &db,
-            &mut batch,
-            BlockHeight::default(),
-            Epoch::default(),
-            Epochs::default(),
-            &ConversionState::default(),
-        )
-        .unwrap();
-        db.exec_batch(batch).unwrap();
-
-        let _state = db
-            .read_last_block()
-            .expect("Should be able to read last block")
-            .expect("Block should have been written");
-    }
-
-    #[test]
-    fn test_read() {
-        let dir = tempdir().unwrap();
-        let mut db = RocksDB::open(dir.path(), None);
-
-        let key = Key::parse("test").unwrap();
-        let batch_key = Key::parse("batch").unwrap();
-
-        let mut batch = RocksDB::batch();
-        let last_height = BlockHeight(100);
-        db.batch_write_subspace_val(
-            &mut batch,
-            last_height,
-            &batch_key,
-            vec![1_u8, 1, 1, 1],
-            true,
-        )
-        .unwrap();
-        db.exec_batch(batch).unwrap();
-
-        db.write_subspace_val(last_height, &key, vec![1_u8, 1, 1, 0], true)
-            .unwrap();
-
-        let mut batch = RocksDB::batch();
-        let last_height = BlockHeight(111);
-        db.batch_write_subspace_val(
-            &mut batch,
-            last_height,
-            &batch_key,
-            vec![2_u8, 2, 2, 2],
-            true,
-        )
-        .unwrap();
-        db.exec_batch(batch).unwrap();
-
-        db.write_subspace_val(last_height, &key, vec![2_u8, 2, 2, 0], true)
-            .unwrap();
-
-        let prev_value = db
-            .read_subspace_val_with_height(
-                &batch_key,
-                BlockHeight(100),
-                last_height,
-            )
-            .expect("read should succeed");
-        assert_eq!(prev_value, Some(vec![1_u8, 1, 1, 1]));
-        let prev_value = db
-            .read_subspace_val_with_height(&key, BlockHeight(100), last_height)
-            .expect("read should succeed");
-        assert_eq!(prev_value, Some(vec![1_u8, 1, 1, 0]));
-
-        let updated_value = db
-            .read_subspace_val_with_height(
-                &batch_key,
-                BlockHeight(111),
-                last_height,
-            )
-            .expect("read should succeed");
-        assert_eq!(updated_value, Some(vec![2_u8, 2, 2, 2]));
-        let updated_value = db
-            .read_subspace_val_with_height(&key, BlockHeight(111), last_height)
-            .expect("read should succeed");
-        assert_eq!(updated_value, Some(vec![2_u8, 2, 2, 0]));
-
-        let latest_value = db
-            .read_subspace_val(&batch_key)
-            .expect("read should succeed");
-        assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 2]));
-        let latest_value =
-            db.read_subspace_val(&key).expect("read should succeed");
-        assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 0]));
-
-        let mut batch = RocksDB::batch();
-        let last_height = BlockHeight(222);
-        db.batch_delete_subspace_val(&mut batch, last_height, &batch_key, true)
-            .unwrap();
-        db.exec_batch(batch).unwrap();
-
-        db.delete_subspace_val(last_height, &key, true).unwrap();
-
-        let deleted_value = db
-            .read_subspace_val_with_height(
-                &batch_key,
-                BlockHeight(222),
-                last_height,
-            )
-            .expect("read should succeed");
-        assert_eq!(deleted_value, None);
-        let deleted_value = db
-            .read_subspace_val_with_height(&key, BlockHeight(222), last_height)
-            .expect("read should succeed");
-        assert_eq!(deleted_value, None);
-
-        let latest_value = db
-            .read_subspace_val(&batch_key)
-            .expect("read should succeed");
-        assert_eq!(latest_value, None);
-        let latest_value =
-            db.read_subspace_val(&key).expect("read should succeed");
-        assert_eq!(latest_value, None);
-    }
-
-    #[test]
-    fn test_prefix_iter() {
-        let dir = tempdir().unwrap();
-        let db = RocksDB::open(dir.path(), None);
-
-        let prefix_0 = Key::parse("0").unwrap();
-        let key_0_a = prefix_0.push(&"a".to_string()).unwrap();
-        let key_0_b = prefix_0.push(&"b".to_string()).unwrap();
-        let key_0_c = prefix_0.push(&"c".to_string()).unwrap();
-        let prefix_1 = Key::parse("1").unwrap();
-        let key_1_a = prefix_1.push(&"a".to_string()).unwrap();
-        let key_1_b = prefix_1.push(&"b".to_string()).unwrap();
-        let key_1_c = prefix_1.push(&"c".to_string()).unwrap();
-        let prefix_01 = Key::parse("01").unwrap();
-        let key_01_a = prefix_01.push(&"a".to_string()).unwrap();
-
-        let keys_0 = vec![key_0_a, key_0_b, key_0_c];
-        let keys_1 = vec![key_1_a, key_1_b, key_1_c];
-        let keys_01 = vec![key_01_a];
-        let all_keys = [keys_0.clone(), keys_01, keys_1.clone()].concat();
-
-        // Write the keys
-        let mut batch = RocksDB::batch();
-        let height = BlockHeight(1);
-        for key in &all_keys {
-            db.batch_write_subspace_val(&mut batch, height, key, [0_u8], true)
-                .unwrap();
+    impl TestDB for RocksDB {
+        fn open_db(path: &Path, read_only: bool) -> Self {
+            open(path, read_only, None).expect("Test failed")
         }
-        db.exec_batch(batch).unwrap();
-
-        // Prefix "0" shouldn't match prefix "01"
-        let itered_keys: Vec<Key> = db
-            .iter_prefix(Some(&prefix_0))
-            .map(|(key, _val, _)| Key::parse(key).unwrap())
-            .collect();
-        itertools::assert_equal(keys_0, itered_keys);
-
-        let itered_keys: Vec<Key> = db
-            .iter_prefix(Some(&prefix_1))
-            .map(|(key, _val, _)| Key::parse(key).unwrap())
-            .collect();
-        itertools::assert_equal(keys_1, itered_keys);
-
-        let itered_keys: Vec<Key> = db
-            .iter_prefix(None)
-            .map(|(key, _val, _)| Key::parse(key).unwrap())
-            .collect();
-        itertools::assert_equal(all_keys, itered_keys);
-    }
-
-    #[test]
-    fn test_rollback() {
-        for persist_diffs in [true, false] {
-            println!("Running with persist_diffs: {persist_diffs}");
-
-            let dir = tempdir().unwrap();
-            let mut db = RocksDB::open(dir.path(), None);
-
-            // A key that's gonna be added on a second block
-            let add_key = Key::parse("add").unwrap();
-            // A key that's gonna be deleted on a second block
-            let delete_key = Key::parse("delete").unwrap();
-            // A key that's gonna be overwritten on a second block
-            let overwrite_key = Key::parse("overwrite").unwrap();
-
-            // Write first block
-            let mut batch = RocksDB::batch();
-            let height_0 = BlockHeight(100);
-            let mut pred_epochs = Epochs::default();
-            pred_epochs.new_epoch(height_0);
-            let conversion_state_0 = ConversionState::default();
-            let to_delete_val = vec![1_u8, 1, 0, 0];
-            let to_overwrite_val = vec![1_u8, 1, 1, 0];
-            db.batch_write_subspace_val(
-                &mut batch,
-                height_0,
-                &delete_key,
-                &to_delete_val,
-                persist_diffs,
-            )
-            .unwrap();
-            db.batch_write_subspace_val(
-                &mut batch,
-                height_0,
-                &overwrite_key,
-                &to_overwrite_val,
-                persist_diffs,
-            )
-            .unwrap();
-            for tx in [b"tx1", b"tx2"] {
-                db.write_replay_protection_entry(
-                    &mut batch,
-                    &replay_protection::key(&Hash::sha256(tx)),
-                )
-                .unwrap();
-            }
-
-            for tx in [b"tx3", b"tx4"] {
-                db.write_replay_protection_entry(
-                    &mut batch,
-                    &replay_protection::current_key(&Hash::sha256(tx)),
-                )
-                .unwrap();
-            }
-
-            add_block_to_batch(
-                &db,
-                &mut batch,
-                height_0,
-                Epoch(1),
-                pred_epochs.clone(),
-                &conversion_state_0,
-            )
-            .unwrap();
-            db.exec_batch(batch).unwrap();
-
-            // Write second block
-            let mut batch = RocksDB::batch();
-            let height_1 = BlockHeight(101);
-            pred_epochs.new_epoch(height_1);
-            let conversion_state_1 = ConversionState::default();
-            let add_val = vec![1_u8, 0, 0, 0];
-            let overwrite_val = vec![1_u8, 1, 1, 1];
-            db.batch_write_subspace_val(
-                &mut batch,
-                height_1,
-                &add_key,
-                &add_val,
-                persist_diffs,
-            )
-            .unwrap();
-            db.batch_write_subspace_val(
-                &mut batch,
-                height_1,
-                &overwrite_key,
-                &overwrite_val,
-                persist_diffs,
-            )
-            .unwrap();
-            db.batch_delete_subspace_val(
-                &mut batch,
-                height_1,
-                &delete_key,
-                persist_diffs,
-            )
-            .unwrap();
-
-            db.move_current_replay_protection_entries(&mut batch)
-                .unwrap();
-
-            for tx in [b"tx5", b"tx6"] {
-                db.write_replay_protection_entry(
-                    &mut batch,
-                    &replay_protection::current_key(&Hash::sha256(tx)),
-                )
-                .unwrap();
-            }
-
-            add_block_to_batch(
-                &db,
-                &mut batch,
-                height_1,
-                Epoch(2),
-                pred_epochs,
-                &conversion_state_1,
-            )
-            .unwrap();
-            db.exec_batch(batch).unwrap();
-
-            // Check that the values are as expected from second block
-            let added = db.read_subspace_val(&add_key).unwrap();
-            assert_eq!(added, Some(add_val));
-            let overwritten = db.read_subspace_val(&overwrite_key).unwrap();
-            assert_eq!(overwritten, Some(overwrite_val));
-            let deleted = db.read_subspace_val(&delete_key).unwrap();
-            assert_eq!(deleted, None);
-
-            for tx in [b"tx1", b"tx2", b"tx3", b"tx4", b"tx5", b"tx6"] {
-                assert!(
-                    db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
-                );
-            }
-
-            // Rollback to the first block height
-            db.rollback(height_0).unwrap();
-
-            // Check that the values are back to the state at the first block
-            let added = db.read_subspace_val(&add_key).unwrap();
-            assert_eq!(added, None);
-            let overwritten = db.read_subspace_val(&overwrite_key).unwrap();
-            assert_eq!(overwritten, Some(to_overwrite_val));
-            let deleted = db.read_subspace_val(&delete_key).unwrap();
-            assert_eq!(deleted, Some(to_delete_val));
-            // Check the conversion state
-            let state_cf = db.get_column_family(STATE_CF).unwrap();
-            let conversion_state = db
-                .inner
-                .get_cf(state_cf, "conversion_state".as_bytes())
-                .unwrap()
-                .unwrap();
-            assert_eq!(conversion_state, encode(&conversion_state_0));
-            for tx in [b"tx1", b"tx2", b"tx3", b"tx4"] {
-                assert!(
-                    db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
-                );
-            }
 
-            for tx in [b"tx5", b"tx6"] {
-                assert!(
-                    !db.has_replay_protection_entry(&Hash::sha256(tx)).unwrap()
-                );
-            }
+        fn rollback(&mut self, height: BlockHeight) -> Result<()> {
+            RocksDB::rollback(self, height)
         }
-    }
-
-    #[test]
-    fn test_diffs() {
-        let dir = tempdir().unwrap();
-        let mut db = RocksDB::open(dir.path(), None);
-
-        let key_with_diffs = Key::parse("with_diffs").unwrap();
-        let key_without_diffs = Key::parse("without_diffs").unwrap();
-
-        let initial_val = vec![1_u8, 1, 0, 0];
-        let overwrite_val = vec![1_u8, 1, 1, 0];
-
-        // Write first block
-        let mut batch = RocksDB::batch();
-        let height_0 = BlockHeight::first();
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_0,
-            &key_with_diffs,
-            &initial_val,
-            true,
-        )
-        .unwrap();
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_0,
-            &key_without_diffs,
-            &initial_val,
-            false,
-        )
-        .unwrap();
-        db.exec_batch(batch).unwrap();
 
-        {
-            let diffs_cf = db.get_column_family(DIFFS_CF).unwrap();
-            let rollback_cf = db.get_column_family(ROLLBACK_CF).unwrap();
-
-            // Diffs new key for `key_with_diffs` at height_0 must be
-            // present
-            let (old_with_h0, new_with_h0) =
-                old_and_new_diff_key(&key_with_diffs, height_0).unwrap();
-            assert!(db.inner.get_cf(diffs_cf, old_with_h0).unwrap().is_none());
-            assert!(db.inner.get_cf(diffs_cf, new_with_h0).unwrap().is_some());
-
-            // Diffs new key for `key_without_diffs` at height_0 must be
-            // present
-            let (old_wo_h0, new_wo_h0) =
-                old_and_new_diff_key(&key_without_diffs, height_0).unwrap();
-            assert!(db.inner.get_cf(rollback_cf, old_wo_h0).unwrap().is_none());
-            assert!(db.inner.get_cf(rollback_cf, new_wo_h0).unwrap().is_some());
+        fn checkpoint(
+            &self,
+            base_dir: PathBuf,
+            height: BlockHeight,
+        ) -> Result<DbSnapshot> {
+            RocksDB::checkpoint(self, base_dir, height)
         }
 
-        // Write second block
-        let mut batch = RocksDB::batch();
-        let height_1 = height_0 + 10u64;
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_1,
-            &key_with_diffs,
-            &overwrite_val,
-            true,
-        )
-        .unwrap();
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_1,
-            &key_without_diffs,
-            &overwrite_val,
-            false,
-        )
-        .unwrap();
-        db.prune_non_persisted_diffs(&mut batch, height_0).unwrap();
-        db.exec_batch(batch).unwrap();
-
-        {
-            let diffs_cf = db.get_column_family(DIFFS_CF).unwrap();
-            let rollback_cf = db.get_column_family(ROLLBACK_CF).unwrap();
-
-            // Diffs keys for `key_with_diffs` at height_0 must be present
-            let (old_with_h0, new_with_h0) =
-                old_and_new_diff_key(&key_with_diffs, height_0).unwrap();
-            assert!(db.inner.get_cf(diffs_cf, old_with_h0).unwrap().is_none());
-            assert!(db.inner.get_cf(diffs_cf, new_with_h0).unwrap().is_some());
-
-            // Diffs keys for `key_without_diffs` at height_0 must be gone
-            let (old_wo_h0, new_wo_h0) =
-                old_and_new_diff_key(&key_without_diffs, height_0).unwrap();
-            assert!(db.inner.get_cf(rollback_cf, old_wo_h0).unwrap().is_none());
-            assert!(db.inner.get_cf(rollback_cf, new_wo_h0).unwrap().is_none());
-
-            // Diffs keys for `key_with_diffs` at height_1 must be present
-            let (old_with_h1, new_with_h1) =
-                old_and_new_diff_key(&key_with_diffs, height_1).unwrap();
-            assert!(db.inner.get_cf(diffs_cf, old_with_h1).unwrap().is_some());
-            assert!(db.inner.get_cf(diffs_cf, new_with_h1).unwrap().is_some());
-
-            // Diffs keys for `key_without_diffs` at height_1 must be
-            // present
-            let (old_wo_h1, new_wo_h1) =
-                old_and_new_diff_key(&key_without_diffs, height_1).unwrap();
-            assert!(db.inner.get_cf(rollback_cf, old_wo_h1).unwrap().is_some());
-            assert!(db.inner.get_cf(rollback_cf, new_wo_h1).unwrap().is_some());
+        fn restore(path: &Path, tarball: &mut File) -> Self {
+            let cache = rocksdb::Cache::new_lru_cache(0);
+            let mut db = RocksDB::open(path, Some(&cache));
+            db.restore_from((&cache, tarball)).expect("Test failed");
+            db
         }
 
-        // Write third block
-        let mut batch = RocksDB::batch();
-        let height_2 = height_1 + 10;
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_2,
-            &key_with_diffs,
-            &initial_val,
-            true,
-        )
-        .unwrap();
-        db.batch_write_subspace_val(
-            &mut batch,
-            height_2,
-            &key_without_diffs,
-            &initial_val,
-            false,
-        )
-        .unwrap();
-        db.prune_non_persisted_diffs(&mut batch, height_1).unwrap();
-        db.exec_batch(batch).unwrap();
-
-        {
-            let diffs_cf = db.get_column_family(DIFFS_CF).unwrap();
-            let rollback_cf = db.get_column_family(ROLLBACK_CF).unwrap();
-
-            // Diffs keys for `key_with_diffs` at height_1 must be present
-            let (old_with_h1, new_with_h1) =
-                old_and_new_diff_key(&key_with_diffs, height_1).unwrap();
-            assert!(db.inner.get_cf(diffs_cf, old_with_h1).unwrap().is_some());
-            assert!(db.inner.get_cf(diffs_cf, new_with_h1).unwrap().is_some());
-
-            // Diffs keys for `key_without_diffs` at height_1 must be gone
-            let (old_wo_h1, new_wo_h1) =
-                old_and_new_diff_key(&key_without_diffs, height_1).unwrap();
-            assert!(db.inner.get_cf(rollback_cf, old_wo_h1).unwrap().is_none());
-            assert!(db.inner.get_cf(rollback_cf, new_wo_h1).unwrap().is_none());
-
-            // Diffs keys for `key_with_diffs` at height_2 must be present
-            let (old_with_h2, new_with_h2) =
-                old_and_new_diff_key(&key_with_diffs, height_2).unwrap();
-            assert!(db.inner.get_cf(diffs_cf, old_with_h2).unwrap().is_some());
-            assert!(db.inner.get_cf(diffs_cf, new_with_h2).unwrap().is_some());
-
-            // Diffs keys for `key_without_diffs` at height_2 must be
-            // present
-            let (old_wo_h2, new_wo_h2) =
-                old_and_new_diff_key(&key_without_diffs, height_2).unwrap();
-            assert!(db.inner.get_cf(rollback_cf, old_wo_h2).unwrap().is_some());
-            assert!(db.inner.get_cf(rollback_cf, new_wo_h2).unwrap().is_some());
+        fn read_raw(&self, cf: &str, key: &str) -> Option<Vec<u8>> {
+            let cf = self.get_column_family(cf).expect("Test failed");
+            self.read_value_bytes(cf, key).expect("Test failed")
         }
-    }
 
-    /// A test helper to write a block
-    fn add_block_to_batch(
-        db: &RocksDB,
-        batch: &mut RocksDBWriteBatch,
-        height: BlockHeight,
-        epoch: Epoch,
-        pred_epochs: Epochs,
-        conversion_state: &ConversionState,
-    ) -> Result<()> {
-        let merkle_tree = MerkleTree::<Sha256Hasher>::default();
-        let merkle_tree_stores = merkle_tree.stores();
-        #[allow(clippy::disallowed_methods)]
-        let time = DateTimeUtc::now();
-        let next_epoch_min_start_height = BlockHeight::default();
-        #[allow(clippy::disallowed_methods)]
-        let next_epoch_min_start_time = DateTimeUtc::now();
-        let update_epoch_blocks_delay = None;
-        let address_gen = EstablishedAddressGen::new("whatever");
-        let results = BlockResults::default();
-        let eth_events_queue = EthEventsQueue::default();
-        let commit_only_data = CommitOnlyData::default();
-        let block = BlockStateWrite {
-            merkle_tree_stores,
-            header: None,
-            height,
-            time,
-            epoch,
-            results: &results,
-            conversion_state,
-            pred_epochs: &pred_epochs,
-            next_epoch_min_start_height,
-            next_epoch_min_start_time,
-            update_epoch_blocks_delay,
-            address_gen: &address_gen,
-            ethereum_height: None,
-            eth_events_queue: &eth_events_queue,
-            commit_only_data: &commit_only_data,
-        };
+        fn raw_entries(&self) -> HashMap<String, Vec<u8>> {
+            let mut entries = HashMap::new();
+            for (_, cf) in self.column_families() {
+                let read_opts = make_iter_read_opts(None);
+                let iter = self.inner.iterator_cf_opt(
+                    cf,
+                    read_opts,
+                    IteratorMode::Start,
+                );
 
-        db.add_block_to_batch(block, batch, true)
-    }
-
-    /// Test that we chunk a file into
-    /// pieces respecting the max chunk size.
-    #[test]
-    fn test_chunking() {
-        let temp = tempfile::tempdir().expect("Test failed");
-        let base_dir = temp.path().to_path_buf();
-
-        let snap_path = SnapshotPath(base_dir, BlockHeight::first());
-        let snapshot_base = snap_path.base().clone();
-        std::fs::create_dir_all(&snapshot_base).expect("Test failed");
-        let snapshot = DbSnapshot(snap_path);
-
-        let tar = snapshot.0.temp_tarball("zst");
-        std::fs::write(tar, vec![16; 21]).expect("Test failed");
-        snapshot.chunk_snapshot(10).unwrap();
-        let mut file_number = 0;
-        for entry in std::fs::read_dir(snapshot_base).expect("Test failed") {
-            let entry = entry.expect("Test failed");
-            file_number += 1;
-            if entry.path().is_file() {
-                let name = entry.file_name();
-                let name = name.to_string_lossy();
-                let Some(("chunk", chunk_nbr)) = name.split_once('-') else {
-                    continue;
-                };
-                let chunk_nbr = u64::from_str(chunk_nbr).expect("Test failed");
-                match chunk_nbr {
-                    0 | 1 => assert_eq!(
-                        entry.metadata().expect("Test failed").len(),
-                        10
-                    ),
-                    2 => assert_eq!(
-                        entry.metadata().expect("Test failed").len(),
-                        1
-                    ),
-                    _ => panic!("Snapshot too chunky"),
+                for (key, raw_val, _gas) in PersistentPrefixIterator(
+                    PrefixIterator::new(iter, String::default()),
+                    // Empty string to prevent prefix stripping, the prefix is
+                    // already in the enclosed iterator
+                ) {
+                    entries.insert(key, raw_val);
                 }
-            } else {
-                panic!("Found unexpected dir in snapshots")
             }
+            entries
         }
-        assert_eq!(file_number, 5)
-    }
-
-    /// Test that we correctly delete snapshots
-    /// older than a given block height
-    #[test]
-    fn test_snapshot_cleanup() {
-        let temp = tempfile::tempdir().expect("Test failed");
-        let base_dir = temp.path().to_path_buf();
-        for height in 1..4 {
-            let snap_path = SnapshotPath(base_dir.clone(), height.into());
-            let snapshot_base = snap_path.base().clone();
-            std::fs::create_dir_all(&snapshot_base).expect("Test failed");
-        }
-
-        std::fs::write(base_dir.join("big.chungus"), "howdy".as_bytes())
-            .expect("Test failed");
-        DbSnapshot::cleanup(3.into(), &base_dir, 2).unwrap();
-        // Big Chungus lives!!!
-        assert_eq!(
-            std::fs::read_to_string(base_dir.join("big.chungus"))
-                .expect("Test failed"),
-            "howdy"
-        );
-
-        let heights = {
-            let mut h = DbSnapshot::heights_of_stored_snapshots(&base_dir)
-                .expect("Test failed");
-            h.sort();
-            h
-        };
-        assert_eq!(heights, vec![2, 3]);
-    }
-
-    /// Test that taking a snapshot actually
-    /// freezes the database in time even if
-    /// it is written to.
-    #[test]
-    fn test_snapshot_creation() {
-        let temp = tempfile::tempdir().expect("Test failed");
-        let mut db = open(&temp, false, None).expect("Test failed");
-        db.write_subspace_val(
-            1.into(),
-            &Key::parse("bing/fucking/bong").expect("Test failed"),
-            [1u8; 64],
-            false,
-        )
-        .expect("Test failed");
-        // we need to persist the changes and restart in read-only mode
-        // as rocksdb doesn't allow multiple read/write instances
-        drop(db);
-        let db = open(&temp, true, None).expect("Test failed");
-        // freeze the database at this point in time
-        let snapshot = db
-            .checkpoint(temp.path().to_path_buf(), BlockHeight::first())
-            .expect("Test failed");
-        let db =
-            open(snapshot.0.temp_rocksdb(), true, None).expect("Test failed");
-
-        // write a new entry to the db
-        let mut db2 = open(&temp, false, None).expect("Test failed");
-        db2.write_subspace_val(
-            2.into(),
-            &Key::parse("I/AM/BATMAN").expect("Test failed"),
-            [2u8; 32],
-            false,
-        )
-        .expect("Test failed");
-        // flush the data
-        drop(db2);
-        let db2 = open(&temp, false, None).expect("Test failed");
-
-        // collect all entries in the snapshot
-        let mut snapshot_entries = HashMap::new();
-        for (_, cf) in db.column_families() {
-            let read_opts = make_iter_read_opts(None);
-            let iter =
-                db.inner.iterator_cf_opt(cf, read_opts, IteratorMode::Start);
-
-            for (key, raw_val, _gas) in PersistentPrefixIterator(
-                PrefixIterator::new(iter, String::default()),
-                // Empty string to prevent prefix stripping, the prefix is
-                // already in the enclosed iterator
-            ) {
-                snapshot_entries.insert(key, raw_val);
-            }
-        }
-
-        // collect ALL entries in the db
-        let mut db_entries = HashMap::new();
-        for (_, cf) in db2.column_families() {
-            let read_opts = make_iter_read_opts(None);
-            let iter =
-                db2.inner
-                    .iterator_cf_opt(cf, read_opts, IteratorMode::Start);
-
-            for (key, raw_val, _gas) in PersistentPrefixIterator(
-                PrefixIterator::new(iter, String::default()),
-                // Empty string to prevent prefix stripping, the prefix is
-                // already in the enclosed iterator
-            ) {
-                db_entries.insert(key, raw_val);
-            }
-        }
-
-        let expected_snap = HashMap::from([
-            ("bing/fucking/bong".to_string(), vec![1u8; 64]),
-            (
-                "0000000000002/new/bing/fucking/bong".to_string(),
-                vec![1u8; 64],
-            ),
-        ]);
-        assert_eq!(expected_snap, snapshot_entries);
-        let expected_db = HashMap::from([
-            ("bing/fucking/bong".to_string(), vec![1u8; 64]),
-            (
-                "0000000000002/new/bing/fucking/bong".to_string(),
-                vec![1u8; 64],
-            ),
-            ("I/AM/BATMAN".to_string(), vec![2u8; 32]),
-            ("0000000000004/new/I/AM/BATMAN".to_string(), vec![2u8; 32]),
-        ]);
-        assert_eq!(expected_db, db_entries);
     }
+
+    db_test_suite!(RocksDB);
 }
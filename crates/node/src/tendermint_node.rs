@@ -111,7 +111,9 @@ async fn initalize_config(
         panic!("Tendermint failed to initialize with {:#?}", output);
     }
 
-    write_tm_genesis(&home_dir, chain_id, genesis_time).await?;
+    let initial_height = super::migrating_state()
+        .or_else(|| super::bootstrapping_state(&config.chain_dir()));
+    write_tm_genesis(&home_dir, chain_id, genesis_time, initial_height).await?;
 
     update_tendermint_config(&home_dir, config.cometbft).await?;
     Ok((home_dir_string, tendermint_path))
@@ -337,6 +339,7 @@ async fn write_tm_genesis(
     home_dir: impl AsRef<Path>,
     chain_id: ChainId,
     genesis_time: DateTimeUtc,
+    initial_height: Option<BlockHeight>,
 ) -> Result<()> {
     let path = genesis(home_dir);
     let mut file = File::open(&path).await.unwrap_or_else(|err| {
@@ -358,7 +361,7 @@ async fn write_tm_genesis(
     genesis.genesis_time = genesis_time
         .try_into()
         .expect("Couldn't convert DateTimeUtc to Tendermint Time");
-    if let Some(height) = super::migrating_state() {
+    if let Some(height) = initial_height {
         genesis.initial_height = height
             .0
             .try_into()
@@ -31,7 +31,9 @@ use namada_core::time::DurationSecs;
 use namada_core::{hints, token};
 use namada_state::{Error, Key, ResultExt, StorageRead, StorageWrite};
 pub use namada_systems::parameters::*;
-pub use storage::{get_gas_scale, get_max_block_gas};
+pub use storage::{
+    get_gas_scale, get_max_block_gas, is_fee_priority_ordering_enforced,
+};
 use thiserror::Error;
 pub use wasm_allowlist::{is_tx_allowed, is_vp_allowed};
 
@@ -80,6 +82,10 @@ where
             num_blocks_to_read,
         )
     }
+
+    fn gas_cost_schedule(storage: &S) -> Result<GasCostSchedule> {
+        read_gas_cost_schedule(storage)
+    }
 }
 
 impl<S> Write<S> for Store<S>
@@ -89,6 +95,13 @@ where
     fn write(storage: &mut S, parameters: &Parameters) -> Result<()> {
         init_storage(parameters, storage)
     }
+
+    fn write_pending_gas_cost_schedule(
+        storage: &mut S,
+        schedule: &GasCostSchedule,
+    ) -> Result<()> {
+        write_pending_gas_cost_schedule(storage, schedule)
+    }
 }
 
 /// The internal address for storage keys representing parameters than
@@ -134,6 +147,7 @@ where
         masp_fee_payment_gas_limit,
         gas_scale,
         is_native_token_transferable,
+        enforce_fee_priority_ordering,
     } = parameters;
 
     // write max tx bytes parameter
@@ -199,6 +213,13 @@ where
     storage
         .write(&native_token_transferable_key, is_native_token_transferable)?;
 
+    let enforce_fee_priority_ordering_key =
+        storage::get_enforce_fee_priority_ordering_key();
+    storage.write(
+        &enforce_fee_priority_ordering_key,
+        enforce_fee_priority_ordering,
+    )?;
+
     Ok(())
 }
 
@@ -447,6 +468,13 @@ where
         .ok_or(ReadError::ParametersMissing)
         .into_storage_result()?;
 
+    let enforce_fee_priority_ordering_key =
+        storage::get_enforce_fee_priority_ordering_key();
+    let value = storage.read(&enforce_fee_priority_ordering_key)?;
+    let enforce_fee_priority_ordering = value
+        .ok_or(ReadError::ParametersMissing)
+        .into_storage_result()?;
+
     Ok(Parameters {
         max_tx_bytes,
         epoch_duration,
@@ -461,6 +489,7 @@ where
         masp_fee_payment_gas_limit,
         gas_scale,
         is_native_token_transferable,
+        enforce_fee_priority_ordering,
     })
 }
 
@@ -480,6 +509,82 @@ pub fn native_erc20_key() -> storage::Key {
     storage::get_native_erc20_key_at_addr(ADDRESS)
 }
 
+/// Initialize the gas cost schedule in the genesis block, as the version `0`.
+pub fn init_gas_cost_schedule<S>(
+    storage: &mut S,
+    schedule: &GasCostSchedule,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::get_gas_cost_schedule_key(0), schedule)?;
+    storage.write(&storage::get_gas_cost_schedule_version_key(), 0_u64)
+}
+
+/// Read the version of the active gas cost schedule
+pub fn read_gas_cost_schedule_version<S>(storage: &S) -> Result<u64>
+where
+    S: StorageRead,
+{
+    let version = storage.read(&storage::get_gas_cost_schedule_version_key())?;
+    Ok(version.unwrap_or_default())
+}
+
+/// Read the gas cost schedule of the given version. Previous versions are
+/// kept to allow pricing the transactions of past blocks.
+pub fn read_gas_cost_schedule_at<S>(
+    storage: &S,
+    version: u64,
+) -> Result<Option<GasCostSchedule>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_gas_cost_schedule_key(version))
+}
+
+/// Read the active gas cost schedule. Falls back to the default schedule when
+/// it hasn't been initialized.
+pub fn read_gas_cost_schedule<S>(storage: &S) -> Result<GasCostSchedule>
+where
+    S: StorageRead,
+{
+    let version = read_gas_cost_schedule_version(storage)?;
+    Ok(read_gas_cost_schedule_at(storage, version)?.unwrap_or_default())
+}
+
+/// Write the gas cost schedule to be activated at the end of the current
+/// block
+pub fn write_pending_gas_cost_schedule<S>(
+    storage: &mut S,
+    schedule: &GasCostSchedule,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::get_pending_gas_cost_schedule_key(), schedule)
+}
+
+/// Activate the pending gas cost schedule, if any, as the next version.
+/// Returns the new version.
+pub fn activate_pending_gas_cost_schedule<S>(
+    storage: &mut S,
+) -> Result<Option<u64>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let pending_key = storage::get_pending_gas_cost_schedule_key();
+    let Some(schedule) = storage.read::<GasCostSchedule>(&pending_key)? else {
+        return Ok(None);
+    };
+    storage.delete(&pending_key)?;
+
+    let version = read_gas_cost_schedule_version(storage)?;
+    let version = checked!(version + 1).into_storage_result()?;
+    storage.write(&storage::get_gas_cost_schedule_key(version), schedule)?;
+    storage.write(&storage::get_gas_cost_schedule_version_key(), version)?;
+    Ok(Some(version))
+}
+
 /// Initialize parameters to the storage for testing
 #[cfg(any(test, feature = "testing"))]
 pub fn init_test_storage<S>(storage: &mut S) -> Result<()>
@@ -503,6 +608,7 @@ where
         gas_scale: 10_000_000,
         minimum_gas_price: Default::default(),
         is_native_token_transferable: true,
+        enforce_fee_priority_ordering: false,
     };
     init_storage(&params, storage)
 }
@@ -618,6 +724,57 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_gas_cost_schedule_activation() {
+        let mut storage = TestStorage::default();
+
+        // The default schedule is used before initialization
+        assert_eq!(
+            read_gas_cost_schedule(&storage).unwrap(),
+            GasCostSchedule::default()
+        );
+        init_gas_cost_schedule(&mut storage, &GasCostSchedule::default())
+            .unwrap();
+        assert_eq!(read_gas_cost_schedule_version(&storage).unwrap(), 0);
+
+        // Nothing to activate
+        assert_eq!(
+            activate_pending_gas_cost_schedule(&mut storage).unwrap(),
+            None
+        );
+
+        let schedule = GasCostSchedule {
+            storage_write_per_byte: 1,
+            ..Default::default()
+        };
+        write_pending_gas_cost_schedule(&mut storage, &schedule).unwrap();
+        // The pending schedule isn't active yet
+        assert_eq!(
+            read_gas_cost_schedule(&storage).unwrap(),
+            GasCostSchedule::default()
+        );
+
+        assert_eq!(
+            activate_pending_gas_cost_schedule(&mut storage).unwrap(),
+            Some(1)
+        );
+        assert_eq!(read_gas_cost_schedule(&storage).unwrap(), schedule);
+        assert!(
+            !storage
+                .has_key(&storage::get_pending_gas_cost_schedule_key())
+                .unwrap()
+        );
+        // The previous version is kept
+        assert_eq!(
+            read_gas_cost_schedule_at(&storage, 0).unwrap(),
+            Some(GasCostSchedule::default())
+        );
+        assert_eq!(
+            activate_pending_gas_cost_schedule(&mut storage).unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_estimate_max_block_time_from_parameters_lower_bound() {
         let mut storage = TestStorage::default();
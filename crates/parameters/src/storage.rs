@@ -41,6 +41,17 @@ struct Keys {
     masp_fee_payment_gas_limit: &'static str,
     gas_scale: &'static str,
     native_token_transferable: &'static str,
+    enforce_fee_priority_ordering: &'static str,
+    // ========================================
+    // Gas cost schedule
+    // ========================================
+    /// Sub-key for storing the version of the active gas cost schedule
+    gas_cost_schedule_version: &'static str,
+    /// Sub-key prefix for storing the gas cost schedules by version
+    gas_cost_schedules: &'static str,
+    /// Sub-key for storing the gas cost schedule approved by governance,
+    /// until it's activated
+    pending_gas_cost_schedule: &'static str,
 }
 
 /// Returns if the key is a parameter key.
@@ -156,6 +167,23 @@ pub fn get_gas_cost_key() -> Key {
     get_minimum_gas_price_key_at_addr(ADDRESS)
 }
 
+/// Storage key used for the version of the active gas cost schedule
+pub fn get_gas_cost_schedule_version_key() -> Key {
+    get_gas_cost_schedule_version_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the gas cost schedule of the given version
+pub fn get_gas_cost_schedule_key(version: u64) -> Key {
+    get_gas_cost_schedules_key_at_addr(ADDRESS)
+        .push(&version)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key used for the gas cost schedule waiting to be activated
+pub fn get_pending_gas_cost_schedule_key() -> Key {
+    get_pending_gas_cost_schedule_key_at_addr(ADDRESS)
+}
+
 /// Helper function to retrieve the `max_block_gas` protocol parameter from
 /// storage
 pub fn get_max_block_gas(storage: &impl StorageRead) -> Result<u64> {
@@ -192,3 +220,21 @@ pub fn is_native_token_transferable(
         ),
     )
 }
+
+/// Storage key used for the flag to enforce the fee priority ordering of the
+/// wrapper txs in block proposals
+pub fn get_enforce_fee_priority_ordering_key() -> Key {
+    get_enforce_fee_priority_ordering_key_at_addr(ADDRESS)
+}
+
+/// Helper function to retrieve the `enforce_fee_priority_ordering` protocol
+/// parameter from storage
+pub fn is_fee_priority_ordering_enforced(
+    storage: &impl StorageRead,
+) -> Result<bool> {
+    storage
+        .read(&get_enforce_fee_priority_ordering_key())?
+        .ok_or(Error::SimpleMessage(
+            "Missing enforce_fee_priority_ordering parameter from storage",
+        ))
+}
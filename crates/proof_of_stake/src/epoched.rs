@@ -595,7 +595,7 @@ where
     /// Update the data associated with epochs to trim historical data, if
     /// needed. Any value with epoch before the oldest epoch to be kept is
     /// added to the value at the oldest stored epoch that is kept.
-    fn update_data<S>(
+    pub(crate) fn update_data<S>(
         &self,
         storage: &mut S,
         params: &PosParams,
@@ -165,6 +165,41 @@ pub enum ConsensusKeyChangeError {
     MustBeEd25519,
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum LiquidShareError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("The bond of a validator cannot be tokenized: {0}")]
+    SourceIsValidator(Address),
+    #[error("Trying to tokenize or redeem shares of a frozen validator: {0}")]
+    ValidatorIsFrozen(Address),
+    #[error(
+        "Trying to tokenize more than the bond amount. Bond amount: {0}, \
+         requested: {1}"
+    )]
+    InsufficientBond(String, String),
+    #[error("Bonds containing redelegated tokens cannot be tokenized")]
+    RedelegatedBond,
+    #[error("The token {0} is not a liquid staking share token")]
+    UnknownShareToken(Address),
+    #[error(
+        "Trying to redeem more than the total share supply. Supply: {0}, \
+         requested: {1}"
+    )]
+    InsufficientSupply(String, String),
+    #[error(
+        "Trying to redeem more than the owned shares. Balance: {0}, \
+         requested: {1}"
+    )]
+    InsufficientShares(String, String),
+    #[error(
+        "The liquid staking shares of validator {0} are not backed by any \
+         stake"
+    )]
+    NoBackingStake(Address),
+}
+
 impl From<BecomeValidatorError> for Error {
     fn from(err: BecomeValidatorError) -> Self {
         Self::new(err)
@@ -230,3 +265,9 @@ impl From<ConsensusKeyChangeError> for Error {
         Self::new(err)
     }
 }
+
+impl From<LiquidShareError> for Error {
+    fn from(err: LiquidShareError) -> Self {
+        Self::new(err)
+    }
+}
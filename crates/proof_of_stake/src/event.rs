@@ -15,6 +15,9 @@ pub mod types {
 
     /// Slash event.
     pub const SLASH: EventType = event_type!(PosEvent, "slash");
+
+    /// Automatic rewards compounding event.
+    pub const AUTO_COMPOUND: EventType = event_type!(PosEvent, "auto-compound");
 }
 
 /// Proof of Stake event.
@@ -27,6 +30,15 @@ pub enum PosEvent {
         /// Amount of tokens that have been slashed.
         amount: token::Amount,
     },
+    /// Automatic rewards compounding event.
+    AutoCompound {
+        /// The source of the compounded bond.
+        source: Address,
+        /// The validator of the compounded bond.
+        validator: Address,
+        /// Amount of reward tokens that have been bonded.
+        amount: token::Amount,
+    },
 }
 
 impl EventToEmit for PosEvent {
@@ -42,6 +54,15 @@ impl From<PosEvent> for Event {
                     .with(SlashedAmount(&amount.into()))
                     .into()
             }
+            PosEvent::AutoCompound {
+                source,
+                validator,
+                amount,
+            } => Event::new(types::AUTO_COMPOUND, EventLevel::Block)
+                .with(CompoundedSource(source))
+                .with(CompoundedValidator(validator))
+                .with(CompoundedAmount(&amount.into()))
+                .into(),
         }
     }
 }
@@ -73,3 +94,45 @@ impl<'amt> EventAttributeEntry<'amt> for SlashedAmount<'amt> {
         self.0
     }
 }
+
+/// Extend an [`Event`] with the source of an auto-compounded bond.
+pub struct CompoundedSource(pub Address);
+
+impl EventAttributeEntry<'static> for CompoundedSource {
+    type Value = Address;
+    type ValueOwned = Self::Value;
+
+    const KEY: &'static str = "compounded-source";
+
+    fn into_value(self) -> Self::Value {
+        self.0
+    }
+}
+
+/// Extend an [`Event`] with the validator of an auto-compounded bond.
+pub struct CompoundedValidator(pub Address);
+
+impl EventAttributeEntry<'static> for CompoundedValidator {
+    type Value = Address;
+    type ValueOwned = Self::Value;
+
+    const KEY: &'static str = "compounded-validator";
+
+    fn into_value(self) -> Self::Value {
+        self.0
+    }
+}
+
+/// Extend an [`Event`] with auto-compounded amount data.
+pub struct CompoundedAmount<'amt>(pub &'amt Uint);
+
+impl<'amt> EventAttributeEntry<'amt> for CompoundedAmount<'amt> {
+    type Value = &'amt Uint;
+    type ValueOwned = Uint;
+
+    const KEY: &'static str = "compounded-amount";
+
+    fn into_value(self) -> Self::Value {
+        self.0
+    }
+}
@@ -19,6 +19,7 @@
 
 pub mod epoched;
 pub mod event;
+pub mod liquid_shares;
 pub mod parameters;
 pub mod queries;
 pub mod rewards;
@@ -67,7 +68,8 @@ use storage::write_validator_name;
 pub use types::GenesisValidator;
 use types::{into_tm_voting_power, DelegationEpochs};
 
-use crate::queries::{find_bonds, has_bonds};
+use crate::event::PosEvent;
+use crate::queries::{find_bonds, find_delegation_validators, has_bonds};
 use crate::rewards::{
     add_rewards_to_counter, compute_current_rewards_from_bonds,
     read_rewards_counter, take_rewards_from_counter,
@@ -77,16 +79,18 @@ use crate::slashing::{
     compute_amount_after_slashing_withdraw, find_validator_slashes,
 };
 use crate::storage::{
+    auto_compound_bonds_handle, auto_compound_queue_handle,
     below_capacity_validator_set_handle, bond_handle,
     consensus_validator_set_handle, delegation_targets_handle,
     delegator_redelegated_bonds_handle, delegator_redelegated_unbonds_handle,
     get_last_reward_claim_epoch, liveness_missed_votes_handle,
-    liveness_sum_missed_votes_handle, read_consensus_validator_set_addresses,
-    read_non_pos_owned_params, read_pos_params,
-    read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
-    read_validator_stake, total_bonded_handle, total_consensus_stake_handle,
-    total_unbonded_handle, try_insert_consensus_key, unbond_handle,
-    update_total_deltas, update_validator_deltas, validator_addresses_handle,
+    liveness_sum_missed_votes_handle, read_auto_compound_epoch,
+    read_consensus_validator_set_addresses, read_non_pos_owned_params,
+    read_pos_params, read_validator_last_slash_epoch,
+    read_validator_max_commission_rate_change, read_validator_stake,
+    total_bonded_handle, total_consensus_stake_handle, total_unbonded_handle,
+    try_insert_consensus_key, unbond_handle, update_total_deltas,
+    update_validator_deltas, validator_addresses_handle,
     validator_commission_rate_handle, validator_consensus_key_handle,
     validator_deltas_handle, validator_eth_cold_key_handle,
     validator_eth_hot_key_handle, validator_incoming_redelegations_handle,
@@ -94,7 +98,7 @@ use crate::storage::{
     validator_rewards_products_handle, validator_set_positions_handle,
     validator_slashes_handle, validator_state_handle,
     validator_total_redelegated_bonded_handle,
-    validator_total_redelegated_unbonded_handle,
+    validator_total_redelegated_unbonded_handle, write_auto_compound_epoch,
     write_last_pos_inflation_amount, write_last_reward_claim_epoch,
     write_last_staked_ratio, write_pos_params,
     write_validator_address_raw_hash, write_validator_avatar,
@@ -196,11 +200,42 @@ where
         };
         bond_amount::<S, Gov>(storage, &bond_id, epoch)
     }
+
+    fn delegation_validators(
+        storage: &S,
+        delegator: &Address,
+        epoch: Epoch,
+    ) -> Result<HashSet<Address>> {
+        find_delegation_validators(storage, delegator, &epoch)
+    }
+}
+
+impl<S> Write<S> for Store<S>
+where
+    S: StorageRead + StorageWrite,
+{
+    fn apply_param_change(storage: &mut S, change: &ParamChange) -> Result<()> {
+        let mut params = storage::read_owned_pos_params(storage)?;
+        params.apply_change(change);
+        let errors = params.validate();
+        if !errors.is_empty() {
+            return Err(Error::new_alloc(format!(
+                "Invalid PoS parameters after applying change {change}: \
+                 {errors:?}"
+            )));
+        }
+        write_pos_params(storage, &params)
+    }
 }
 
 /// Address of the PoS account implemented as a native VP
 pub const ADDRESS: Address = Address::Internal(InternalAddress::PoS);
 
+/// Maximum number of due auto-compounding bonds processed in a single block.
+/// This bounds the extra work done in block finalization, the remaining bonds
+/// are compounded in the following blocks.
+pub const MAX_AUTO_COMPOUNDS_PER_BLOCK: usize = 256;
+
 /// Address of the PoS slash pool account
 pub const SLASH_POOL_ADDRESS: Address =
     Address::Internal(InternalAddress::PosSlashPool);
@@ -354,6 +389,31 @@ where
     let staking_token = staking_token_address(storage);
     Token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
 
+    increment_bond::<S, Gov>(
+        storage,
+        source,
+        validator,
+        amount,
+        current_epoch,
+        offset_opt,
+    )
+}
+
+/// Add the given amount to the bond at the pipeline offset and update the
+/// validator's stake accordingly. The bonded tokens must already be held by
+/// the PoS account.
+fn increment_bond<S, Gov>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    offset_opt: Option<u64>,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
     let params = read_pos_params::<S, Gov>(storage)?;
     let offset = offset_opt.unwrap_or(params.pipeline_len);
     let offset_epoch = checked!(current_epoch + offset)?;
@@ -2857,6 +2917,191 @@ where
     Ok(res)
 }
 
+/// Enable or disable the automatic compounding of the rewards of a bond. While
+/// enabled, the claimable rewards of the bond are re-bonded at the pipeline
+/// offset in every epoch.
+pub fn set_auto_compound<S, Gov>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    enabled: bool,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
+    let source = source.unwrap_or(validator);
+    let auto_compound_bonds = auto_compound_bonds_handle().at(source);
+    if !enabled {
+        auto_compound_bonds.remove(storage, validator)?;
+        return Ok(());
+    }
+
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    let params = read_pos_params::<S, Gov>(storage)?;
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+    let bonded = bond_handle(source, validator)
+        .get_sum(storage, pipeline_epoch, &params)?
+        .unwrap_or_default();
+    if bonded.is_zero() {
+        return Err(UnbondError::NoBondFound.into());
+    }
+    if !auto_compound_bonds.contains(storage, validator)? {
+        auto_compound_bonds.insert(storage, validator.clone())?;
+        // The bond is due to be compounded from the current epoch
+        auto_compound_queue_handle()
+            .at(&current_epoch)
+            .at(source)
+            .insert(storage, validator.clone())?;
+    }
+    Ok(())
+}
+
+/// Check if the rewards of a bond are automatically compounded.
+pub fn is_auto_compound<S>(
+    storage: &S,
+    source: Option<&Address>,
+    validator: &Address,
+) -> Result<bool>
+where
+    S: StorageRead,
+{
+    let source = source.unwrap_or(validator);
+    auto_compound_bonds_handle()
+        .at(source)
+        .contains(storage, validator)
+}
+
+/// Re-bond the claimable rewards of the auto-compounding bonds that are due to
+/// be compounded. The bonds are taken from the queue starting from the epoch
+/// where the previous block stopped, and at most
+/// [`MAX_AUTO_COMPOUNDS_PER_BLOCK`] bonds are visited, the rest is left for the
+/// following blocks. A compounded bond is due again in the next epoch.
+fn auto_compound_rewards<S, Gov>(
+    storage: &mut S,
+    events: &mut impl EmitEvents,
+    params: &PosParams,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
+    let queue = auto_compound_queue_handle();
+    let mut epoch = read_auto_compound_epoch(storage)?.unwrap_or_default();
+    let mut remaining = MAX_AUTO_COMPOUNDS_PER_BLOCK;
+    while remaining > 0 {
+        let mut due_bonds: Vec<BondId> = Vec::new();
+        for entry in queue.at(&epoch).iter(storage)?.take(remaining) {
+            let (
+                lazy_map::NestedSubKey::Data {
+                    key: source,
+                    nested_sub_key: lazy_set::SubKey::Data(validator),
+                },
+                (),
+            ) = entry?;
+            due_bonds.push(BondId { source, validator });
+        }
+        let is_epoch_done = due_bonds.len() < remaining;
+        remaining = checked!(remaining - due_bonds.len())?;
+
+        for bond_id in due_bonds {
+            queue
+                .at(&epoch)
+                .at(&bond_id.source)
+                .remove(storage, &bond_id.validator)?;
+            if compound_bond_rewards::<S, Gov>(
+                storage,
+                events,
+                params,
+                &bond_id,
+                current_epoch,
+            )? {
+                queue
+                    .at(&current_epoch.next())
+                    .at(&bond_id.source)
+                    .insert(storage, bond_id.validator)?;
+            }
+        }
+
+        if !is_epoch_done || epoch >= current_epoch {
+            break;
+        }
+        // All the bonds due in this epoch have been compounded
+        epoch = epoch.next();
+    }
+    write_auto_compound_epoch(storage, epoch)
+}
+
+/// Re-bond the claimable rewards of the auto-compounding bond if they haven't
+/// been claimed in the current epoch yet. Returns `false` when the bond
+/// shouldn't be compounded anymore.
+fn compound_bond_rewards<S, Gov>(
+    storage: &mut S,
+    events: &mut impl EmitEvents,
+    params: &PosParams,
+    bond_id: &BondId,
+    current_epoch: Epoch,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
+    let BondId { source, validator } = bond_id;
+    let auto_compound_bonds = auto_compound_bonds_handle().at(source);
+    if !auto_compound_bonds.contains(storage, validator)? {
+        // The auto-compounding has been disabled
+        return Ok(false);
+    }
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+    let bonded = bond_handle(source, validator)
+        .get_sum(storage, pipeline_epoch, params)?
+        .unwrap_or_default();
+    if bonded.is_zero() || !is_validator(storage, validator)? {
+        // Nothing left to compound into
+        auto_compound_bonds.remove(storage, validator)?;
+        return Ok(false);
+    }
+    let last_claim_epoch =
+        get_last_reward_claim_epoch(storage, source, validator)?;
+    if matches!(last_claim_epoch, Some(epoch) if epoch >= current_epoch) {
+        return Ok(true);
+    }
+
+    let mut reward_tokens = compute_current_rewards_from_bonds::<S, Gov>(
+        storage,
+        source,
+        validator,
+        current_epoch,
+    )?;
+    let counter_rewards =
+        take_rewards_from_counter(storage, source, validator)?;
+    checked!(reward_tokens += counter_rewards)?;
+    write_last_reward_claim_epoch(storage, source, validator, current_epoch)?;
+
+    // The reward tokens are already held by the PoS account, so they only
+    // need to be bonded
+    if !reward_tokens.is_zero() {
+        increment_bond::<S, Gov>(
+            storage,
+            source,
+            validator,
+            reward_tokens,
+            current_epoch,
+            None,
+        )?;
+        events.emit(PosEvent::AutoCompound {
+            source: source.clone(),
+            validator: validator.clone(),
+            amount: reward_tokens,
+        });
+    }
+    Ok(true)
+}
+
 /// Jail a validator by removing it from and updating the validator sets and
 /// changing a its state to `Jailed`. Validators are jailed for liveness and for
 /// misbehaving.
@@ -3063,6 +3308,17 @@ where
         // Prune liveness data from validators that are no longer in the
         // consensus set
         prune_liveness_data(storage, current_epoch)?;
+    } else {
+        // Compound the rewards of the auto-compounding bonds in batches. This
+        // starts only after the first block of an epoch, because the rewards
+        // products of the last epoch are recorded by the inflation that is
+        // applied after PoS block finalization.
+        auto_compound_rewards::<S, Gov>(
+            storage,
+            events,
+            &pos_params,
+            current_epoch,
+        )?;
     }
 
     Ok(())
@@ -3274,6 +3530,7 @@ pub mod test_utils {
             gas_scale: 100_000_000,
             minimum_gas_price: BTreeMap::new(),
             is_native_token_transferable: true,
+            enforce_fee_priority_ordering: false,
         };
         Params::write(storage, &chain_parameters).unwrap();
         init_genesis_helper::<S, Gov, Token>(
@@ -0,0 +1,448 @@
+//! Liquid staking shares.
+//!
+//! A delegator can tokenize (a part of) its bond to a validator into liquid
+//! staking shares of that validator. The tokenized bond entries are moved,
+//! keeping their start epochs, into a pool bond whose source is the share
+//! token address of the validator (see [`address::liquid_share_token`]) and
+//! share tokens are minted to the delegator in proportion to the value added
+//! to the pool. Since the bond entries keep their start epochs and neither the
+//! validator's stake nor its total bonded amounts change, slashes apply to the
+//! pool exactly as they would have applied to the original bonds.
+//!
+//! The rewards of the pool are re-bonded into it whenever shares are minted or
+//! redeemed, so the value of a share grows with the validator's rewards.
+//! Shares can be redeemed back into a bond of the owner, which again keeps the
+//! start epochs of the pool's bond entries.
+
+use std::collections::BTreeMap;
+
+use namada_core::address::{self, Address};
+use namada_core::arith::checked;
+use namada_core::chain::Epoch;
+use namada_core::token;
+use namada_systems::{governance, trans_token};
+
+use crate::parameters::PosParams;
+use crate::rewards::{
+    add_rewards_to_counter, compute_current_rewards_from_bonds,
+    read_rewards_counter, take_rewards_from_counter,
+};
+use crate::storage::{
+    bond_handle, delegator_redelegated_bonds_handle, read_liquid_share_supply,
+    read_liquid_share_validator, read_pos_params,
+    write_last_reward_claim_epoch, write_liquid_share_supply,
+    write_liquid_share_validator,
+};
+use crate::types::BondId;
+use crate::{
+    ADDRESS, LiquidShareError, OptionExt, Result, StorageRead, StorageWrite,
+    add_delegation_target, bond_amount, find_bonds_to_remove, increment_bond,
+    is_validator, is_validator_frozen, remove_delegation_target,
+};
+
+/// Tokenize the given `amount` of the bond of the `delegator` to the
+/// `validator` into liquid staking shares of the validator, credited to the
+/// delegator. Returns the amount of minted shares.
+pub fn tokenize_bond<S, Gov, Token>(
+    storage: &mut S,
+    delegator: &Address,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+    Token: trans_token::Write<S>,
+{
+    if amount.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+
+    let params = read_pos_params::<S, Gov>(storage)?;
+    check_validator(storage, &params, validator, current_epoch)?;
+    if delegator == validator || is_validator(storage, delegator)? {
+        return Err(
+            LiquidShareError::SourceIsValidator(delegator.clone()).into()
+        );
+    }
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+    let share_token = address::liquid_share_token(validator);
+
+    // Make sure there's enough bonded at the pipeline epoch
+    let bonds = bond_handle(delegator, validator);
+    let bonded = bonds
+        .get_sum(storage, pipeline_epoch, &params)?
+        .unwrap_or_default();
+    if amount > bonded {
+        return Err(LiquidShareError::InsufficientBond(
+            bonded.to_string_native(),
+            amount.to_string_native(),
+        )
+        .into());
+    }
+
+    // Find the bond entries to move into the pool, starting from the most
+    // recent ones
+    let bonds_data = bonds.get_data_handler();
+    let to_remove = find_bonds_to_remove(storage, &bonds_data, amount)?;
+    let mut entries = BTreeMap::new();
+    for start in to_remove.epochs {
+        let entry = bonds_data.get(storage, &start)?.unwrap_or_default();
+        entries.insert(start, entry);
+    }
+    if let Some((start, new_amount)) = to_remove.new_entry {
+        let entry = bonds_data.get(storage, &start)?.unwrap_or_default();
+        entries.insert(start, checked!(entry - new_amount)?);
+    }
+
+    // The redelegated tokens carry the slashes of their source validators,
+    // which cannot be tracked in the pool
+    let redelegated =
+        delegator_redelegated_bonds_handle(delegator).at(validator);
+    for start in entries.keys() {
+        if redelegated.contains(storage, start)? {
+            return Err(LiquidShareError::RedelegatedBond.into());
+        }
+    }
+
+    // Settle the rewards before any bond changes its owner
+    compound_pool_rewards::<S, Gov>(
+        storage,
+        &share_token,
+        validator,
+        current_epoch,
+    )?;
+    settle_rewards::<S, Gov>(storage, delegator, validator, current_epoch)?;
+
+    let pool_id = BondId {
+        source: share_token.clone(),
+        validator: validator.clone(),
+    };
+    let pool_value_pre =
+        bond_amount::<S, Gov>(storage, &pool_id, pipeline_epoch)?;
+    let supply = read_liquid_share_supply(storage, &share_token)?;
+
+    move_bond_entries(
+        storage,
+        &params,
+        delegator,
+        &share_token,
+        validator,
+        &entries,
+        current_epoch,
+    )?;
+
+    let pool_value_post =
+        bond_amount::<S, Gov>(storage, &pool_id, pipeline_epoch)?;
+    let value = checked!(pool_value_post - pool_value_pre)?;
+    let shares = if supply.is_zero() {
+        value
+    } else if pool_value_pre.is_zero() {
+        return Err(LiquidShareError::NoBackingStake(validator.clone()).into());
+    } else {
+        mul_div(value, supply, pool_value_pre)?
+    };
+
+    write_liquid_share_validator(storage, &share_token, validator)?;
+    write_liquid_share_supply(
+        storage,
+        &share_token,
+        checked!(supply + shares)?,
+    )?;
+    Token::mint_tokens(storage, &ADDRESS, &share_token, delegator, shares)?;
+
+    Ok(shares)
+}
+
+/// Redeem the given `shares` amount of liquid staking shares held by the
+/// `owner` back into a bond of the owner to the validator backing the shares.
+/// Returns the value of the redeemed bond.
+pub fn redeem_liquid_shares<S, Gov, Token>(
+    storage: &mut S,
+    owner: &Address,
+    share_token: &Address,
+    shares: token::Amount,
+    current_epoch: Epoch,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+    Token: trans_token::Write<S>,
+{
+    if shares.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+
+    let validator = read_liquid_share_validator(storage, share_token)?
+        .ok_or_else(|| {
+            LiquidShareError::UnknownShareToken(share_token.clone())
+        })?;
+    let params = read_pos_params::<S, Gov>(storage)?;
+    check_validator(storage, &params, &validator, current_epoch)?;
+    if owner == &validator || is_validator(storage, owner)? {
+        return Err(LiquidShareError::SourceIsValidator(owner.clone()).into());
+    }
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+
+    let balance = Token::read_balance(storage, share_token, owner)?;
+    if shares > balance {
+        return Err(LiquidShareError::InsufficientShares(
+            balance.to_string_native(),
+            shares.to_string_native(),
+        )
+        .into());
+    }
+    let supply = read_liquid_share_supply(storage, share_token)?;
+    if shares > supply {
+        return Err(LiquidShareError::InsufficientSupply(
+            supply.to_string_native(),
+            shares.to_string_native(),
+        )
+        .into());
+    }
+
+    // Settle the rewards before any bond changes its owner
+    compound_pool_rewards::<S, Gov>(
+        storage,
+        share_token,
+        &validator,
+        current_epoch,
+    )?;
+    settle_rewards::<S, Gov>(storage, owner, &validator, current_epoch)?;
+
+    // Take the shares' proportion of every pool bond entry, or all of them
+    // when the whole supply is redeemed
+    let pool_bonds = bond_handle(share_token, &validator).get_data_handler();
+    let mut entries = BTreeMap::new();
+    for entry in pool_bonds.iter(storage)? {
+        let (start, amount) = entry?;
+        let amount = if shares == supply {
+            amount
+        } else {
+            mul_div(amount, shares, supply)?
+        };
+        if !amount.is_zero() {
+            entries.insert(start, amount);
+        }
+    }
+
+    let owner_id = BondId {
+        source: owner.clone(),
+        validator: validator.clone(),
+    };
+    let value_pre = bond_amount::<S, Gov>(storage, &owner_id, pipeline_epoch)?;
+
+    move_bond_entries(
+        storage,
+        &params,
+        share_token,
+        owner,
+        &validator,
+        &entries,
+        current_epoch,
+    )?;
+
+    let value_post = bond_amount::<S, Gov>(storage, &owner_id, pipeline_epoch)?;
+
+    write_liquid_share_supply(
+        storage,
+        share_token,
+        checked!(supply - shares)?,
+    )?;
+    Token::burn_tokens(storage, share_token, owner, shares)?;
+
+    Ok(checked!(value_post - value_pre)?)
+}
+
+/// Query the value of the given amount of liquid staking shares in the bonded
+/// tokens of the backing validator, including the pool's unclaimed rewards.
+/// Returns `None` if the token is not a liquid staking share token.
+pub fn query_liquid_share_value<S, Gov>(
+    storage: &S,
+    share_token: &Address,
+    shares: token::Amount,
+    current_epoch: Epoch,
+) -> Result<Option<token::Amount>>
+where
+    S: StorageRead,
+    Gov: governance::Read<S>,
+{
+    let Some(validator) = read_liquid_share_validator(storage, share_token)?
+    else {
+        return Ok(None);
+    };
+    let supply = read_liquid_share_supply(storage, share_token)?;
+    if supply.is_zero() {
+        return Ok(Some(token::Amount::zero()));
+    }
+
+    let params = read_pos_params::<S, Gov>(storage)?;
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+    let pool_id = BondId {
+        source: share_token.clone(),
+        validator: validator.clone(),
+    };
+    let bonded = bond_amount::<S, Gov>(storage, &pool_id, pipeline_epoch)?;
+    let rewards = compute_current_rewards_from_bonds::<S, Gov>(
+        storage,
+        share_token,
+        &validator,
+        current_epoch,
+    )?;
+    let counter = read_rewards_counter(storage, share_token, &validator)?;
+    let pool_value = checked!(bonded + rewards + counter)?;
+
+    Ok(Some(mul_div(pool_value, shares, supply)?))
+}
+
+/// Check that the target of liquid staking shares is a validator that is not
+/// frozen.
+fn check_validator<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead,
+{
+    if !is_validator(storage, validator)? {
+        return Err(LiquidShareError::NotAValidator(validator.clone()).into());
+    }
+    // Bonds of a frozen validator may still get slashed for an infraction that
+    // hasn't been processed yet
+    if is_validator_frozen(storage, validator, current_epoch, params)? {
+        return Err(
+            LiquidShareError::ValidatorIsFrozen(validator.clone()).into()
+        );
+    }
+    Ok(())
+}
+
+/// Re-bond the rewards of the pool of the given share token into it.
+fn compound_pool_rewards<S, Gov>(
+    storage: &mut S,
+    share_token: &Address,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
+    let mut rewards = compute_current_rewards_from_bonds::<S, Gov>(
+        storage,
+        share_token,
+        validator,
+        current_epoch,
+    )?;
+    let counter = take_rewards_from_counter(storage, share_token, validator)?;
+    checked!(rewards += counter)?;
+    write_last_reward_claim_epoch(
+        storage,
+        share_token,
+        validator,
+        current_epoch,
+    )?;
+
+    // The reward tokens are already held by the PoS account, so they only
+    // need to be bonded
+    if !rewards.is_zero() {
+        increment_bond::<S, Gov>(
+            storage,
+            share_token,
+            validator,
+            rewards,
+            current_epoch,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Move the rewards accrued by the bond so far into its rewards counter so
+/// that they stay with the current owner of the bond.
+fn settle_rewards<S, Gov>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+    Gov: governance::Read<S>,
+{
+    let rewards = compute_current_rewards_from_bonds::<S, Gov>(
+        storage,
+        source,
+        validator,
+        current_epoch,
+    )?;
+    add_rewards_to_counter(storage, source, validator, rewards)?;
+    write_last_reward_claim_epoch(storage, source, validator, current_epoch)
+}
+
+/// Move the given bond entries, keyed by their start epochs, from the bond of
+/// `from` to the bond of `to`. The validator's stake and total bonded amounts
+/// are unaffected.
+fn move_bond_entries<S>(
+    storage: &mut S,
+    params: &PosParams,
+    from: &Address,
+    to: &Address,
+    validator: &Address,
+    entries: &BTreeMap<Epoch, token::Amount>,
+    current_epoch: Epoch,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let pipeline_epoch = checked!(current_epoch + params.pipeline_len)?;
+    let from_bonds = bond_handle(from, validator);
+    let to_bonds = bond_handle(to, validator);
+    to_bonds.update_data(storage, params, current_epoch)?;
+
+    let from_data = from_bonds.get_data_handler();
+    let to_data = to_bonds.get_data_handler();
+    for (start, amount) in entries {
+        let cur = from_data.get(storage, start)?.unwrap_or_default();
+        let new = checked!(cur - *amount)?;
+        if new.is_zero() {
+            from_data.remove(storage, start)?;
+        } else {
+            from_data.insert(storage, *start, new)?;
+        }
+        to_data.try_update(storage, *start, |cur| {
+            Ok(checked!(cur.unwrap_or_default() + *amount)?)
+        })?;
+    }
+
+    // The moved bonds change owner immediately
+    let from_remaining = from_bonds
+        .get_sum(storage, pipeline_epoch, params)?
+        .unwrap_or_default();
+    if from_remaining.is_zero() {
+        remove_delegation_target(
+            storage,
+            params,
+            from,
+            validator,
+            current_epoch,
+            current_epoch,
+        )?;
+    }
+    add_delegation_target(storage, to, validator, current_epoch, current_epoch)
+}
+
+/// Compute `amount * numerator / denominator`, rounded down.
+fn mul_div(
+    amount: token::Amount,
+    numerator: token::Amount,
+    denominator: token::Amount,
+) -> Result<token::Amount> {
+    amount
+        .checked_mul(numerator)
+        .and_then(|product| product.checked_div(denominator))
+        .ok_or_err_msg("Arithmetic error in liquid staking shares")
+}
@@ -13,6 +13,7 @@ use namada_governance::parameters::GovernanceParameters;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
 use namada_migrations::*;
+use namada_systems::proof_of_stake::ParamChange;
 use serde::Serialize;
 use thiserror::Error;
 
@@ -215,6 +216,38 @@ impl OwnedPosParams {
         errors
     }
 
+    /// Apply a governance change of a single parameter
+    pub fn apply_change(&mut self, change: &ParamChange) {
+        match change {
+            ParamChange::BlockProposerReward(value) => {
+                self.block_proposer_reward = *value
+            }
+            ParamChange::BlockVoteReward(value) => {
+                self.block_vote_reward = *value
+            }
+            ParamChange::MaxInflationRate(value) => {
+                self.max_inflation_rate = *value
+            }
+            ParamChange::TargetStakedRatio(value) => {
+                self.target_staked_ratio = *value
+            }
+            ParamChange::DuplicateVoteMinSlashRate(value) => {
+                self.duplicate_vote_min_slash_rate = *value
+            }
+            ParamChange::LightClientAttackMinSlashRate(value) => {
+                self.light_client_attack_min_slash_rate = *value
+            }
+            ParamChange::ValidatorStakeThreshold(value) => {
+                self.validator_stake_threshold = *value
+            }
+            ParamChange::LivenessThreshold(value) => {
+                self.liveness_threshold = *value
+            }
+            ParamChange::RewardsGainP(value) => self.rewards_gain_p = *value,
+            ParamChange::RewardsGainD(value) => self.rewards_gain_d = *value,
+        }
+    }
+
     /// Get the epoch offset from which an unbonded bond can withdrawn
     pub fn withdrawable_epoch_offset(&self) -> u64 {
         checked!(
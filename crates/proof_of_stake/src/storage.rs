@@ -16,17 +16,17 @@ use namada_systems::governance;
 use crate::lazy_map::NestedSubKey;
 use crate::storage_key::consensus_keys_key;
 use crate::types::{
-    BelowCapacityValidatorSets, BondId, Bonds, CommissionRates,
-    ConsensusValidatorSets, DelegationTargets, DelegatorRedelegatedBonded,
-    DelegatorRedelegatedUnbonded, EpochedSlashes, IncomingRedelegations,
-    LivenessMissedVotes, LivenessSumMissedVotes, OutgoingRedelegations,
-    ReverseOrdTokenAmount, RewardsAccumulator, RewardsProducts, Slashes,
-    TotalConsensusStakes, TotalDeltas, TotalRedelegatedBonded,
-    TotalRedelegatedUnbonded, Unbonds, ValidatorAddresses,
-    ValidatorConsensusKeys, ValidatorDeltas, ValidatorEthColdKeys,
-    ValidatorEthHotKeys, ValidatorMetaData, ValidatorProtocolKeys,
-    ValidatorSetPositions, ValidatorState, ValidatorStates,
-    ValidatorTotalUnbonded, WeightedValidator,
+    AutoCompoundBonds, AutoCompoundQueue, BelowCapacityValidatorSets, BondId,
+    Bonds, CommissionRates, ConsensusValidatorSets, DelegationTargets,
+    DelegatorRedelegatedBonded, DelegatorRedelegatedUnbonded, EpochedSlashes,
+    IncomingRedelegations, LivenessMissedVotes, LivenessSumMissedVotes,
+    OutgoingRedelegations, ReverseOrdTokenAmount, RewardsAccumulator,
+    RewardsProducts, Slashes, TotalConsensusStakes, TotalDeltas,
+    TotalRedelegatedBonded, TotalRedelegatedUnbonded, Unbonds,
+    ValidatorAddresses, ValidatorConsensusKeys, ValidatorDeltas,
+    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorMetaData,
+    ValidatorProtocolKeys, ValidatorSetPositions, ValidatorState,
+    ValidatorStates, ValidatorTotalUnbonded, WeightedValidator,
 };
 use crate::{
     storage_key, LazyCollection, LazySet, MetadataError, OwnedPosParams,
@@ -258,6 +258,19 @@ pub fn delegation_targets_handle(delegator: &Address) -> DelegationTargets {
     DelegationTargets::open(key)
 }
 
+/// Get the storage handle to the bonds whose rewards are automatically
+/// compounded
+pub fn auto_compound_bonds_handle() -> AutoCompoundBonds {
+    let key = storage_key::auto_compound_bonds_key();
+    AutoCompoundBonds::open(key)
+}
+
+/// Get the storage handle to the auto-compounding bonds due to be compounded
+pub fn auto_compound_queue_handle() -> AutoCompoundQueue {
+    let key = storage_key::auto_compound_queue_key();
+    AutoCompoundQueue::open(key)
+}
+
 // ---- Storage read + write ----
 
 /// Read owned PoS parameters
@@ -1046,3 +1059,73 @@ where
     let params = read_pos_params::<S, Gov>(storage)?;
     validator_consensus_key_handle(addr).get(storage, epoch, &params)
 }
+
+/// Read the validator whose bonds back the given liquid staking share token,
+/// if any.
+pub fn read_liquid_share_validator<S>(
+    storage: &S,
+    share_token: &Address,
+) -> Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    let key = storage_key::liquid_share_validator_key(share_token);
+    storage.read(&key)
+}
+
+/// Write the validator whose bonds back the given liquid staking share token.
+pub fn write_liquid_share_validator<S>(
+    storage: &mut S,
+    share_token: &Address,
+    validator: &Address,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage_key::liquid_share_validator_key(share_token);
+    storage.write(&key, validator)
+}
+
+/// Read the total supply of the given liquid staking share token.
+pub fn read_liquid_share_supply<S>(
+    storage: &S,
+    share_token: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = storage_key::liquid_share_supply_key(share_token);
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Write the total supply of the given liquid staking share token.
+pub fn write_liquid_share_supply<S>(
+    storage: &mut S,
+    share_token: &Address,
+    supply: token::Amount,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage_key::liquid_share_supply_key(share_token);
+    storage.write(&key, supply)
+}
+
+/// Read the epoch of the due auto-compounding bonds to be compounded next.
+/// The bonds due in the previous epochs have all been compounded.
+pub fn read_auto_compound_epoch<S>(storage: &S) -> Result<Option<Epoch>>
+where
+    S: StorageRead,
+{
+    let key = storage_key::auto_compound_epoch_key();
+    storage.read(&key)
+}
+
+/// Write the epoch of the due auto-compounding bonds to be compounded next.
+pub fn write_auto_compound_epoch<S>(storage: &mut S, epoch: Epoch) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage_key::auto_compound_epoch_key();
+    storage.write(&key, epoch)
+}
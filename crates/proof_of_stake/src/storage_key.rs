@@ -62,6 +62,13 @@ const LAST_STAKED_RATIO_KEY: &str = "last_staked_ratio";
 const LAST_POS_INFLATION_AMOUNT_KEY: &str = "last_inflation_amount";
 const TOTAL_ACTIVE_DELTAS_KEY: &str = "total_active_deltas";
 const DELEGATION_TARGETS_PREFIX: &str = "delegation_targets";
+const LIQUID_SHARES_PREFIX: &str = "liquid_shares";
+const LIQUID_SHARE_VALIDATOR_KEY: &str = "validator";
+const LIQUID_SHARE_SUPPLY_KEY: &str = "supply";
+const AUTO_COMPOUND_PREFIX: &str = "auto_compound";
+const AUTO_COMPOUND_BONDS_KEY: &str = "bonds";
+const AUTO_COMPOUND_QUEUE_KEY: &str = "queue";
+const AUTO_COMPOUND_EPOCH_KEY: &str = "epoch";
 
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
@@ -1122,3 +1129,78 @@ pub fn is_delegation_targets_key(key: &Key) -> bool {
         false
     }
 }
+
+/// Storage prefix for liquid staking shares.
+pub fn liquid_shares_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&LIQUID_SHARES_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the validator whose bonds back the given liquid staking
+/// share token.
+pub fn liquid_share_validator_key(share_token: &Address) -> Key {
+    liquid_shares_prefix()
+        .push(&share_token.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&LIQUID_SHARE_VALIDATOR_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the total supply of the given liquid staking share token.
+pub fn liquid_share_supply_key(share_token: &Address) -> Key {
+    liquid_shares_prefix()
+        .push(&share_token.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&LIQUID_SHARE_SUPPLY_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for liquid staking shares? Returns the share token, if so.
+pub fn is_liquid_share_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(share_token),
+            DbKeySeg::StringSeg(_),
+        ] if addr == &ADDRESS && prefix == LIQUID_SHARES_PREFIX => {
+            Some(share_token)
+        }
+        _ => None,
+    }
+}
+
+/// Storage prefix for automatic compounding of rewards.
+pub fn auto_compound_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&AUTO_COMPOUND_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the bonds whose rewards are automatically compounded.
+pub fn auto_compound_bonds_key() -> Key {
+    auto_compound_prefix()
+        .push(&AUTO_COMPOUND_BONDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the auto-compounding bonds due to be compounded.
+pub fn auto_compound_queue_key() -> Key {
+    auto_compound_prefix()
+        .push(&AUTO_COMPOUND_QUEUE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the epoch of the due auto-compounding bonds to be
+/// compounded next.
+pub fn auto_compound_epoch_key() -> Key {
+    auto_compound_prefix()
+        .push(&AUTO_COMPOUND_EPOCH_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for automatic compounding of rewards?
+pub fn is_auto_compound_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..] if addr == &ADDRESS && prefix == AUTO_COMPOUND_PREFIX)
+}
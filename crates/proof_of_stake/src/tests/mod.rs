@@ -109,6 +109,46 @@ where
     )
 }
 
+/// DI indirection
+pub fn tokenize_bond<S>(
+    storage: &mut S,
+    delegator: &Address,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    crate::liquid_shares::tokenize_bond::<S, GovStore<S>, token::Store<_>>(
+        storage,
+        delegator,
+        validator,
+        amount,
+        current_epoch,
+    )
+}
+
+/// DI indirection
+pub fn redeem_liquid_shares<S>(
+    storage: &mut S,
+    owner: &Address,
+    share_token: &Address,
+    shares: token::Amount,
+    current_epoch: Epoch,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    crate::liquid_shares::redeem_liquid_shares::<S, GovStore<S>, token::Store<_>>(
+        storage,
+        owner,
+        share_token,
+        shares,
+        current_epoch,
+    )
+}
+
 /// DI indirection
 pub fn bonds_and_unbonds<S>(
     storage: &S,
@@ -35,7 +35,8 @@ use crate::rewards::{
 use crate::storage::{
     delegation_targets_handle, get_consensus_key_set,
     liveness_sum_missed_votes_handle,
-    read_consensus_validator_set_addresses_with_stake, read_total_stake,
+    read_consensus_validator_set_addresses_with_stake,
+    read_liquid_share_supply, read_liquid_share_validator, read_total_stake,
     read_validator_deltas_value, rewards_accumulator_handle,
     total_deltas_handle,
 };
@@ -46,9 +47,9 @@ use crate::tests::helpers::{
 use crate::tests::{
     bond_amount, bond_tokens, bonds_and_unbonds, change_consensus_key,
     find_delegations, process_slashes,
-    read_below_threshold_validator_set_addresses, redelegate_tokens, slash,
-    test_init_genesis, unbond_tokens, unjail_validator, withdraw_tokens,
-    GovStore,
+    read_below_threshold_validator_set_addresses, redeem_liquid_shares,
+    redelegate_tokens, slash, test_init_genesis, tokenize_bond, unbond_tokens,
+    unjail_validator, withdraw_tokens, GovStore,
 };
 use crate::types::{
     into_tm_voting_power, BondDetails, BondId, BondsAndUnbondsDetails,
@@ -2210,3 +2211,449 @@ fn test_delegation_targets() {
     assert!(de_2.prev_ranges.is_empty());
     assert_eq!(de_2.last_range.1, None);
 }
+
+#[test]
+fn test_liquid_shares() {
+    let stakes = vec![token::Amount::native_whole(1)];
+    let mut storage = TestState::default();
+    let mut current_epoch = storage.in_mem().block.epoch;
+    let params = OwnedPosParams::default();
+
+    let genesis_validators = get_genesis_validators(1, stakes.clone());
+    let validator = genesis_validators[0].address.clone();
+    let share_token = address::liquid_share_token(&validator);
+
+    let delegator = address::testing::gen_implicit_address();
+    let staking_token = staking_token_address(&storage);
+    credit_tokens(
+        &mut storage,
+        &staking_token,
+        &delegator,
+        token::Amount::native_whole(20),
+    )
+    .unwrap();
+
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        genesis_validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        token::Amount::native_whole(10),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    for _ in 0..=params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let stake_pre =
+        read_validator_stake(&storage, &params, &validator, pipeline_epoch)
+            .unwrap();
+
+    // Validators cannot tokenize their bonds and the amount is limited by the
+    // bond
+    assert!(
+        tokenize_bond(
+            &mut storage,
+            &validator,
+            &validator,
+            token::Amount::native_whole(1),
+            current_epoch,
+        )
+        .is_err()
+    );
+    assert!(
+        tokenize_bond(
+            &mut storage,
+            &delegator,
+            &validator,
+            token::Amount::native_whole(11),
+            current_epoch,
+        )
+        .is_err()
+    );
+
+    // Tokenize a part of the delegation
+    let shares = tokenize_bond(
+        &mut storage,
+        &delegator,
+        &validator,
+        token::Amount::native_whole(4),
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(shares, token::Amount::native_whole(4));
+    assert_eq!(
+        read_balance(&storage, &share_token, &delegator).unwrap(),
+        shares
+    );
+    assert_eq!(
+        read_liquid_share_validator(&storage, &share_token).unwrap(),
+        Some(validator.clone())
+    );
+    assert_eq!(
+        read_liquid_share_supply(&storage, &share_token).unwrap(),
+        shares
+    );
+
+    let delegator_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    let pool_id = BondId {
+        source: share_token.clone(),
+        validator: validator.clone(),
+    };
+    assert_eq!(
+        bond_amount(&storage, &delegator_id, pipeline_epoch).unwrap(),
+        token::Amount::native_whole(6)
+    );
+    assert_eq!(
+        bond_amount(&storage, &pool_id, pipeline_epoch).unwrap(),
+        token::Amount::native_whole(4)
+    );
+    // The moved bond is active immediately for the pool
+    assert_eq!(
+        bond_amount(&storage, &pool_id, current_epoch).unwrap(),
+        token::Amount::native_whole(4)
+    );
+    let stake_post =
+        read_validator_stake(&storage, &params, &validator, pipeline_epoch)
+            .unwrap();
+    assert_eq!(stake_pre, stake_post);
+
+    // Redeem more shares than owned
+    assert!(
+        redeem_liquid_shares(
+            &mut storage,
+            &delegator,
+            &share_token,
+            token::Amount::native_whole(5),
+            current_epoch,
+        )
+        .is_err()
+    );
+
+    // Redeem all the shares
+    let redeemed = redeem_liquid_shares(
+        &mut storage,
+        &delegator,
+        &share_token,
+        shares,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(redeemed, token::Amount::native_whole(4));
+    assert!(
+        read_balance(&storage, &share_token, &delegator)
+            .unwrap()
+            .is_zero()
+    );
+    assert!(
+        read_liquid_share_supply(&storage, &share_token)
+            .unwrap()
+            .is_zero()
+    );
+    assert_eq!(
+        bond_amount(&storage, &delegator_id, pipeline_epoch).unwrap(),
+        token::Amount::native_whole(10)
+    );
+    assert!(
+        bond_amount(&storage, &pool_id, pipeline_epoch)
+            .unwrap()
+            .is_zero()
+    );
+    let stake_post =
+        read_validator_stake(&storage, &params, &validator, pipeline_epoch)
+            .unwrap();
+    assert_eq!(stake_pre, stake_post);
+}
+
+#[test]
+fn test_auto_compound() {
+    let stakes = vec![token::Amount::native_whole(1)];
+    let mut storage = TestState::default();
+    let mut current_epoch = storage.in_mem().block.epoch;
+    let params = OwnedPosParams::default();
+
+    let genesis_validators = get_genesis_validators(1, stakes.clone());
+    let validator = genesis_validators[0].address.clone();
+
+    let delegator = address::testing::gen_implicit_address();
+    let staking_token = staking_token_address(&storage);
+    credit_tokens(
+        &mut storage,
+        &staking_token,
+        &delegator,
+        token::Amount::native_whole(20),
+    )
+    .unwrap();
+
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        genesis_validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+
+    // Cannot auto-compound a non-existent bond
+    assert!(
+        crate::set_auto_compound::<_, GovStore<_>>(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            true,
+            current_epoch,
+        )
+        .is_err()
+    );
+
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        token::Amount::native_whole(10),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    crate::set_auto_compound::<_, GovStore<_>>(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        true,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(
+        crate::is_auto_compound(&storage, Some(&delegator), &validator)
+            .unwrap()
+    );
+
+    for _ in 0..=params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+
+    // Distribute rewards for the last epoch
+    let num_blocks_in_last_epoch = 1000;
+    rewards_accumulator_handle()
+        .insert(
+            &mut storage,
+            validator.clone(),
+            Dec::one() * num_blocks_in_last_epoch,
+        )
+        .unwrap();
+    let total_native_tokens =
+        get_effective_total_native_supply(&storage).unwrap();
+    update_rewards_products_and_mint_inflation::<_, token::Store<_>>(
+        &mut storage,
+        &params,
+        current_epoch.prev().unwrap(),
+        num_blocks_in_last_epoch,
+        token::Amount::native_whole(10_000),
+        &staking_token,
+        total_native_tokens,
+    )
+    .unwrap();
+
+    let rewards = crate::query_reward_tokens::<_, GovStore<_>>(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(!rewards.is_zero());
+
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let stake_pre =
+        read_validator_stake(&storage, &params, &validator, pipeline_epoch)
+            .unwrap();
+
+    let mut events = vec![];
+    crate::auto_compound_rewards::<_, GovStore<_>>(
+        &mut storage,
+        &mut events,
+        &params,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        bond_amount(&storage, &bond_id, pipeline_epoch).unwrap(),
+        token::Amount::native_whole(10) + rewards
+    );
+    assert_eq!(
+        read_validator_stake(&storage, &params, &validator, pipeline_epoch)
+            .unwrap(),
+        stake_pre + rewards
+    );
+    assert!(
+        crate::query_reward_tokens::<_, GovStore<_>>(
+            &storage,
+            Some(&delegator),
+            &validator,
+            current_epoch,
+        )
+        .unwrap()
+        .is_zero()
+    );
+
+    // The round is complete, nothing else is compounded in this epoch
+    crate::auto_compound_rewards::<_, GovStore<_>>(
+        &mut storage,
+        &mut events,
+        &params,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(events.len(), 1);
+
+    // Disable the auto-compounding
+    crate::set_auto_compound::<_, GovStore<_>>(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        false,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(
+        !crate::is_auto_compound(&storage, Some(&delegator), &validator)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_auto_compound_in_batches() {
+    let stakes = vec![token::Amount::native_whole(1)];
+    let mut storage = TestState::default();
+    let mut current_epoch = storage.in_mem().block.epoch;
+    let params = OwnedPosParams::default();
+
+    let genesis_validators = get_genesis_validators(1, stakes.clone());
+    let validator = genesis_validators[0].address.clone();
+    let staking_token = staking_token_address(&storage);
+
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        genesis_validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+
+    // One more bond than the number of bonds compounded in a block
+    let num_bonds = crate::MAX_AUTO_COMPOUNDS_PER_BLOCK + 1;
+    for _ in 0..num_bonds {
+        let delegator = address::testing::gen_implicit_address();
+        credit_tokens(
+            &mut storage,
+            &staking_token,
+            &delegator,
+            token::Amount::native_whole(1),
+        )
+        .unwrap();
+        bond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            token::Amount::native_whole(1),
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        crate::set_auto_compound::<_, GovStore<_>>(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            true,
+            current_epoch,
+        )
+        .unwrap();
+    }
+
+    for _ in 0..=params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+
+    // Distribute rewards for the last epoch
+    let num_blocks_in_last_epoch = 1000;
+    rewards_accumulator_handle()
+        .insert(
+            &mut storage,
+            validator.clone(),
+            Dec::one() * num_blocks_in_last_epoch,
+        )
+        .unwrap();
+    let total_native_tokens =
+        get_effective_total_native_supply(&storage).unwrap();
+    update_rewards_products_and_mint_inflation::<_, token::Store<_>>(
+        &mut storage,
+        &params,
+        current_epoch.prev().unwrap(),
+        num_blocks_in_last_epoch,
+        token::Amount::native_whole(10_000),
+        &staking_token,
+        total_native_tokens,
+    )
+    .unwrap();
+
+    // The first block compounds as many bonds as allowed
+    let mut events = vec![];
+    crate::auto_compound_rewards::<_, GovStore<_>>(
+        &mut storage,
+        &mut events,
+        &params,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(events.len(), crate::MAX_AUTO_COMPOUNDS_PER_BLOCK);
+    assert!(
+        crate::storage::read_auto_compound_epoch(&storage).unwrap()
+            < Some(current_epoch)
+    );
+
+    // The next block resumes with the remaining bond
+    crate::auto_compound_rewards::<_, GovStore<_>>(
+        &mut storage,
+        &mut events,
+        &params,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(events.len(), num_bonds);
+    assert_eq!(
+        crate::storage::read_auto_compound_epoch(&storage).unwrap(),
+        Some(current_epoch)
+    );
+
+    // All the bonds are due in the next epoch
+    crate::auto_compound_rewards::<_, GovStore<_>>(
+        &mut storage,
+        &mut events,
+        &params,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(events.len(), num_bonds);
+    let due_bonds = crate::storage::auto_compound_queue_handle()
+        .at(&current_epoch.next())
+        .iter(&storage)
+        .unwrap()
+        .count();
+    assert_eq!(due_bonds, num_bonds);
+}
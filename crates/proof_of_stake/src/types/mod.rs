@@ -258,6 +258,14 @@ pub type EagerRedelegatedBondsMap =
 /// which the consensus validator missed a vote.
 pub type LivenessMissedVotes = NestedMap<Address, LazySet<u64>>;
 
+/// The bonds whose rewards are automatically compounded, keyed by the bond
+/// source to the set of validators.
+pub type AutoCompoundBonds = NestedMap<Address, LazySet<Address>>;
+
+/// The auto-compounding bonds keyed by the epoch from which they are due to be
+/// compounded.
+pub type AutoCompoundQueue = NestedMap<Epoch, AutoCompoundBonds>;
+
 /// The sum of missed votes within some interval for each of the consensus
 /// validators.
 ///
@@ -3,12 +3,13 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 
-use namada_core::address::Address;
+use namada_core::address::{Address, InternalAddress};
 use namada_core::booleans::BoolResultUnitExt;
 use namada_core::storage::Key;
 use namada_systems::governance;
 use namada_tx::action::{
-    Action, Bond, ClaimRewards, PosAction, Redelegation, Unbond, Withdraw,
+    Action, AutoCompound, Bond, ClaimRewards, PosAction, RedeemLiquidShares,
+    Redelegation, TokenizeBond, Unbond, Withdraw,
 };
 use namada_tx::BatchedTxRef;
 use namada_vp_env::{Error, Result, VpEnv};
@@ -34,6 +35,14 @@ impl From<VpError> for Error {
     }
 }
 
+/// Check that the given address authorized an action on its bonds. The bonds
+/// of liquid staking share pools cannot be acted upon directly, they only
+/// change when shares are minted or redeemed.
+fn is_authorized(verifiers: &BTreeSet<Address>, address: &Address) -> bool {
+    !matches!(address, Address::Internal(InternalAddress::LiquidShare(_)))
+        && verifiers.contains(address)
+}
+
 /// Proof-of-Stake validity predicate
 pub struct PosVp<'ctx, CTX, Gov> {
     /// Generic types for DI
@@ -100,6 +109,11 @@ where
         let mut redelegations: BTreeMap<BondId, (Address, token::Amount)> =
             Default::default();
         let mut claimed_rewards: BTreeSet<BondId> = Default::default();
+        let mut auto_compound: BTreeMap<BondId, bool> = Default::default();
+        let mut tokenized: BTreeMap<BondId, token::Amount> = Default::default();
+        // The key is the pair of (owner, share_token) and value is amount
+        let mut redeemed: BTreeMap<(Address, Address), token::Amount> =
+            Default::default();
         let mut changed_commission: BTreeSet<Address> = Default::default();
         let mut changed_metadata: BTreeSet<Address> = Default::default();
         let mut changed_consensus_key: BTreeSet<Address> = Default::default();
@@ -166,7 +180,7 @@ where
                             source: source.unwrap_or_else(|| validator.clone()),
                             validator,
                         };
-                        if !verifiers.contains(&bond_id.source) {
+                        if !is_authorized(verifiers, &bond_id.source) {
                             tracing::info!("Unauthorized PosAction::Bond");
                             return Err(VpError::Unauthorized(
                                 "Bond",
@@ -185,7 +199,7 @@ where
                             source: source.unwrap_or_else(|| validator.clone()),
                             validator,
                         };
-                        if !verifiers.contains(&bond_id.source) {
+                        if !is_authorized(verifiers, &bond_id.source) {
                             tracing::info!("Unauthorized PosAction::Unbond");
                             return Err(VpError::Unauthorized(
                                 "Unbond",
@@ -200,7 +214,7 @@ where
                             source: source.unwrap_or_else(|| validator.clone()),
                             validator,
                         };
-                        if !verifiers.contains(&bond_id.source) {
+                        if !is_authorized(verifiers, &bond_id.source) {
                             tracing::info!("Unauthorized PosAction::Withdraw");
                             return Err(VpError::Unauthorized(
                                 "Withdraw",
@@ -216,7 +230,7 @@ where
                         owner,
                         amount,
                     }) => {
-                        if !verifiers.contains(&owner) {
+                        if !is_authorized(verifiers, &owner) {
                             tracing::info!(
                                 "Unauthorized PosAction::Redelegation"
                             );
@@ -240,7 +254,7 @@ where
                             source: source.unwrap_or_else(|| validator.clone()),
                             validator,
                         };
-                        if !verifiers.contains(&bond_id.source) {
+                        if !is_authorized(verifiers, &bond_id.source) {
                             tracing::info!(
                                 "Unauthorized PosAction::ClaimRewards"
                             );
@@ -252,6 +266,65 @@ where
                         }
                         claimed_rewards.insert(bond_id);
                     }
+                    PosAction::AutoCompound(AutoCompound {
+                        validator,
+                        source,
+                        enabled,
+                    }) => {
+                        let bond_id = BondId {
+                            source: source.unwrap_or_else(|| validator.clone()),
+                            validator,
+                        };
+                        if !is_authorized(verifiers, &bond_id.source) {
+                            tracing::info!(
+                                "Unauthorized PosAction::AutoCompound"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "AutoCompound",
+                                bond_id.source,
+                            )
+                            .into());
+                        }
+                        auto_compound.insert(bond_id, enabled);
+                    }
+                    PosAction::TokenizeBond(TokenizeBond {
+                        validator,
+                        owner,
+                        amount,
+                    }) => {
+                        if !is_authorized(verifiers, &owner) {
+                            tracing::info!(
+                                "Unauthorized PosAction::TokenizeBond"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "TokenizeBond",
+                                owner,
+                            )
+                            .into());
+                        }
+                        let bond_id = BondId {
+                            source: owner,
+                            validator,
+                        };
+                        tokenized.insert(bond_id, amount);
+                    }
+                    PosAction::RedeemLiquidShares(RedeemLiquidShares {
+                        share_token,
+                        owner,
+                        amount,
+                    }) => {
+                        if !is_authorized(verifiers, &owner) {
+                            tracing::info!(
+                                "Unauthorized PosAction::RedeemLiquidShares"
+                            );
+                            return Err(VpError::Unauthorized(
+                                "RedeemLiquidShares",
+                                owner,
+                            )
+                            .into());
+                        }
+                        redeemed.insert((owner, share_token), amount);
+                    }
                     PosAction::CommissionChange(validator) => {
                         if !verifiers.contains(&validator) {
                             tracing::info!(
@@ -1,10 +1,12 @@
 //! Structures encapsulating SDK arguments
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration as StdDuration;
 
+use namada_account::ActionClass;
 use namada_core::address::Address;
 use namada_core::chain::{BlockHeight, ChainId, Epoch};
 use namada_core::collections::HashMap;
@@ -16,7 +18,8 @@ use namada_core::masp::{MaspEpoch, PaymentAddress};
 use namada_core::time::DateTimeUtc;
 use namada_core::{storage, token};
 use namada_governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    DefaultProposal, ParameterChangeProposal, PgfFundingProposal,
+    PgfStewardProposal,
 };
 use namada_ibc::IbcShieldingData;
 use namada_token::masp::utils::RetryStrategy;
@@ -26,7 +29,10 @@ use serde::{Deserialize, Serialize};
 use zeroize::Zeroizing;
 
 use crate::eth_bridge::bridge_pool;
-use crate::ibc::core::host::types::identifiers::{ChannelId, PortId};
+use crate::ibc::core::host::types::identifiers::{
+    ChannelId, ConnectionId, PortId,
+};
+use crate::ibc::primitives::proto::Any;
 use crate::signing::SigningTxData;
 use crate::wallet::{DatedSpendingKey, DatedViewingKey};
 use crate::{rpc, tx, Namada};
@@ -471,6 +477,19 @@ impl TxUnshieldingTransfer {
     }
 }
 
+/// ICS-29 relayer fees of an IBC transfer
+#[derive(Clone, Debug)]
+pub struct IbcRelayerFee<C: NamadaTypes = SdkTypes> {
+    /// Fee token address
+    pub token: C::AddrOrNativeToken,
+    /// Fee for relaying the packet to the destination chain
+    pub recv_fee: InputAmount,
+    /// Fee for relaying the acknowledgement back
+    pub ack_fee: InputAmount,
+    /// Fee for relaying the timeout
+    pub timeout_fee: InputAmount,
+}
+
 /// IBC transfer transaction arguments
 #[derive(Clone, Debug)]
 pub struct TxIbcTransfer<C: NamadaTypes = SdkTypes> {
@@ -498,6 +517,8 @@ pub struct TxIbcTransfer<C: NamadaTypes = SdkTypes> {
     pub ibc_shielding_data: Option<IbcShieldingData>,
     /// Memo for IBC transfer packet
     pub ibc_memo: Option<String>,
+    /// ICS-29 relayer fees paid by the source
+    pub relayer_fee: Option<IbcRelayerFee<C>>,
     /// Optional additional keys for gas payment
     pub gas_spending_keys: Vec<C::SpendingKey>,
     /// Generate an ephemeral signing key to be used only once to sign the
@@ -590,6 +611,14 @@ impl<C: NamadaTypes> TxIbcTransfer<C> {
         }
     }
 
+    /// ICS-29 relayer fees
+    pub fn relayer_fee(self, relayer_fee: IbcRelayerFee<C>) -> Self {
+        Self {
+            relayer_fee: Some(relayer_fee),
+            ..self
+        }
+    }
+
     /// Gas spending keys
     pub fn gas_spending_keys(
         self,
@@ -621,6 +650,161 @@ impl TxIbcTransfer {
     }
 }
 
+/// Interchain account registration transaction arguments
+#[derive(Clone, Debug)]
+pub struct TxIcaRegister<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Owner of the interchain account
+    pub owner: C::Address,
+    /// Connection ID to the host chain
+    pub connection_id: ConnectionId,
+    /// ICS-27 version metadata. The default metadata is used if it's not
+    /// given.
+    pub version: Option<String>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxIcaRegister<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxIcaRegister {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxIcaRegister<C> {
+    /// Owner of the interchain account
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// Connection ID to the host chain
+    pub fn connection_id(self, connection_id: ConnectionId) -> Self {
+        Self {
+            connection_id,
+            ..self
+        }
+    }
+
+    /// ICS-27 version metadata
+    pub fn version(self, version: String) -> Self {
+        Self {
+            version: Some(version),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxIcaRegister {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_ica_register(context, self).await
+    }
+}
+
+/// Interchain account transaction arguments to send messages to the host
+/// chain
+#[derive(Clone, Debug)]
+pub struct TxIcaSendTx<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Owner of the interchain account
+    pub owner: C::Address,
+    /// Connection ID to the host chain
+    pub connection_id: ConnectionId,
+    /// Messages to be executed by the interchain account
+    pub messages: Vec<Any>,
+    /// Memo for the packet
+    pub ica_memo: Option<String>,
+    /// Timeout timestamp offset
+    pub timeout_sec_offset: Option<u64>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxIcaSendTx<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxIcaSendTx {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxIcaSendTx<C> {
+    /// Owner of the interchain account
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// Connection ID to the host chain
+    pub fn connection_id(self, connection_id: ConnectionId) -> Self {
+        Self {
+            connection_id,
+            ..self
+        }
+    }
+
+    /// Messages to be executed by the interchain account
+    pub fn messages(self, messages: Vec<Any>) -> Self {
+        Self { messages, ..self }
+    }
+
+    /// Memo for the packet
+    pub fn ica_memo(self, ica_memo: String) -> Self {
+        Self {
+            ica_memo: Some(ica_memo),
+            ..self
+        }
+    }
+
+    /// Timeout timestamp offset
+    pub fn timeout_sec_offset(self, timeout_sec_offset: u64) -> Self {
+        Self {
+            timeout_sec_offset: Some(timeout_sec_offset),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxIcaSendTx {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_ica_send_tx(context, self).await
+    }
+}
+
 /// Transaction to initialize create a new proposal
 #[derive(Clone, Debug)]
 pub struct InitProposal<C: NamadaTypes = SdkTypes> {
@@ -632,6 +816,8 @@ pub struct InitProposal<C: NamadaTypes = SdkTypes> {
     pub is_pgf_stewards: bool,
     /// Flag if proposal is of type Pgf funding
     pub is_pgf_funding: bool,
+    /// Flag if proposal is of type parameter change
+    pub is_parameter_change: bool,
     /// Path to the tx WASM file
     pub tx_code_path: PathBuf,
 }
@@ -673,6 +859,14 @@ impl<C: NamadaTypes> InitProposal<C> {
         }
     }
 
+    /// Flag if proposal is of type parameter change
+    pub fn is_parameter_change(self, is_parameter_change: bool) -> Self {
+        Self {
+            is_parameter_change,
+            ..self
+        }
+    }
+
     /// Path to the tx WASM file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -736,6 +930,35 @@ impl InitProposal {
                 })?;
 
             tx::build_pgf_stewards_proposal(context, self, proposal).await
+        } else if self.is_parameter_change {
+            let proposal = ParameterChangeProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxSubmitError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+                None,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxSubmitError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_parameter_change_proposal(context, self, proposal).await
         } else {
             let proposal = DefaultProposal::try_from(
                 self.proposal_data.as_ref(),
@@ -1068,6 +1291,10 @@ pub struct TxUpdateAccount<C: NamadaTypes = SdkTypes> {
     pub public_keys: Vec<C::PublicKey>,
     /// The account threshold
     pub threshold: Option<u8>,
+    /// The weights of the public keys, by index
+    pub weights: Vec<u8>,
+    /// The thresholds of specific classes of actions
+    pub action_thresholds: BTreeMap<ActionClass, u8>,
 }
 
 impl<C: NamadaTypes> TxBuilder<C> for TxUpdateAccount<C> {
@@ -1119,6 +1346,21 @@ impl<C: NamadaTypes> TxUpdateAccount<C> {
             ..self
         }
     }
+
+    /// The weights of the public keys
+    pub fn weights(self, weights: Vec<u8>) -> Self {
+        Self { weights, ..self }
+    }
+
+    /// The threshold of a class of actions. A threshold of 0 removes it.
+    pub fn action_threshold(
+        mut self,
+        class: ActionClass,
+        threshold: u8,
+    ) -> Self {
+        self.action_thresholds.insert(class, threshold);
+        self
+    }
 }
 
 impl TxUpdateAccount {
@@ -1475,6 +1717,15 @@ pub struct QueryProtocolParameters<C: NamadaTypes = SdkTypes> {
     pub query: Query<C>,
 }
 
+/// Query the upgrade plan scheduled by governance
+#[derive(Clone, Debug)]
+pub struct QueryUpgradePlan<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Path of a node binary to check against the upgrade plan
+    pub binary: Option<PathBuf>,
+}
+
 /// Query pgf data
 #[derive(Clone, Debug)]
 pub struct QueryPgf<C: NamadaTypes = SdkTypes> {
@@ -2327,6 +2578,8 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub fee_amount: Option<InputAmount>,
     /// The fee payer signing key
     pub wrapper_fee_payer: Option<C::PublicKey>,
+    /// The sponsor paying the fees from its pre-authorized budget
+    pub fee_sponsor: Option<C::Address>,
     /// The token in which the fee is being paid
     pub fee_token: C::AddrOrNativeToken,
     /// The max amount of gas used to process tx
@@ -2455,6 +2708,13 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
             ..x
         })
     }
+    /// The sponsor paying the fees from its pre-authorized budget
+    fn fee_sponsor(self, fee_sponsor: C::Address) -> Self {
+        self.tx(|x| Tx {
+            fee_sponsor: Some(fee_sponsor),
+            ..x
+        })
+    }
     /// The token in which the fee is being paid
     fn fee_token(self, fee_token: C::Address) -> Self {
         self.tx(|x| Tx {
@@ -2685,6 +2945,10 @@ pub struct RecommendBatch<C: NamadaTypes = SdkTypes> {
     pub gas: Option<u64>,
     /// Bridge pool recommendations conversion rates table.
     pub conversion_table: C::BpConversionTable,
+    /// The address of the relayer to compensate. If set, the batch
+    /// maximising the net profit is recommended, along with the
+    /// proof to relay it.
+    pub relayer: Option<Address>,
 }
 
 /// A transfer to be added to the Ethereum bridge pool.
@@ -2814,6 +3078,15 @@ pub struct BridgePoolProof<C: NamadaTypes = SdkTypes> {
     pub relayer: Address,
 }
 
+/// Bridge pool gas fee quote arguments.
+#[derive(Debug, Clone)]
+pub struct BridgePoolFeeQuote<C: NamadaTypes = SdkTypes> {
+    /// The address of the ledger node as host:port
+    pub ledger_address: C::TendermintAddress,
+    /// The token in which the gas fees are paid
+    pub token: Address,
+}
+
 /// Arguments to an Ethereum Bridge pool relay operation.
 #[derive(Debug, Clone)]
 pub struct RelayBridgePoolProof<C: NamadaTypes = SdkTypes> {
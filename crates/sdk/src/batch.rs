@@ -0,0 +1,492 @@
+//! Batches of transactions authorized by several accounts
+//!
+//! The inner transactions of a batch are all authorized by signatures over
+//! the raw header of the batch, which commits to every inner transaction.
+//! When the inner transactions belong to different accounts, their signers
+//! usually hold their keys on separate, possibly offline, devices. A
+//! [`BatchBuilder`] records the signers required by each inner transaction
+//! and builds a [`PartiallySignedBatch`], which is passed around the signers
+//! to collect their signatures. The bundles signed by the different parties
+//! are merged and, once the signatures meet the threshold of every signer,
+//! the batch is finalized and can be wrapped and signed by the fee payer.
+
+use std::collections::BTreeMap;
+use std::io;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_account::AccountPublicKeysMap;
+use namada_core::address::Address;
+use namada_core::collections::HashSet;
+use namada_core::hash::Hash;
+use namada_core::key::*;
+use namada_tx::{Authorization, SignatureIndex, Signer, Tx};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EncodingError, Error, TxSubmitError};
+use crate::signing::SigningTxData;
+use crate::tx::build_batch;
+
+/// The version of the format of the partially signed batches
+pub const PARTIALLY_SIGNED_BATCH_VERSION: u8 = 1;
+
+/// Builder of a batch whose inner transactions may require the signatures
+/// of different accounts
+#[derive(Clone, Default)]
+pub struct BatchBuilder {
+    /// The inner transactions with their signing data
+    txs: Vec<(Tx, SigningTxData)>,
+    /// Whether the inner transactions should be executed atomically
+    atomic: bool,
+}
+
+impl BatchBuilder {
+    /// Make an empty batch builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an inner transaction with the data of its signer
+    pub fn add_inner_tx(mut self, tx: Tx, signing_data: SigningTxData) -> Self {
+        self.txs.push((tx, signing_data));
+        self
+    }
+
+    /// Set whether the inner transactions should be executed atomically
+    pub fn atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Build the batch to be signed. The fee payer of the batch is the one of
+    /// the first inner transaction.
+    pub fn build(self) -> Result<PartiallySignedBatch, Error> {
+        let mut signers: Vec<BatchSigner> = vec![];
+        for (tx, signing_data) in &self.txs {
+            let cmt = tx
+                .first_commitments()
+                .ok_or_else(|| {
+                    Error::Other(
+                        "Inner tx did not contain any transaction".to_string(),
+                    )
+                })?
+                .get_hash();
+            let signer = BatchSigner::from(signing_data.clone());
+            // The inner txs of the same signer share its signatures
+            match signers.iter_mut().find(|other| other.is_same(&signer)) {
+                Some(other) => other.inner_txs.push(cmt),
+                None => signers.push(BatchSigner {
+                    inner_txs: vec![cmt],
+                    ..signer
+                }),
+            }
+        }
+        let fee_payer = self
+            .txs
+            .first()
+            .map(|(_, signing_data)| signing_data.fee_payer.clone());
+        let (mut tx, _) = build_batch(self.txs)?;
+        tx.header.atomic = self.atomic;
+
+        Ok(PartiallySignedBatch {
+            version: PARTIALLY_SIGNED_BATCH_VERSION,
+            tx,
+            signers,
+            fee_payer: fee_payer
+                .expect("The batch should contain at least one transaction"),
+            signatures: vec![],
+        })
+    }
+}
+
+/// An account required to sign some inner transactions of a batch
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct BatchSigner {
+    /// The address of the account, if any
+    pub owner: Option<Address>,
+    /// The public keys expected to sign for the account
+    pub public_keys: Vec<common::PublicKey>,
+    /// The signature threshold of the account
+    pub threshold: u8,
+    /// The public keys to index map of the account
+    pub account_public_keys_map: Option<AccountPublicKeysMap>,
+    /// The commitment hashes of the inner transactions requiring the
+    /// signatures of the account
+    pub inner_txs: Vec<Hash>,
+}
+
+impl From<SigningTxData> for BatchSigner {
+    fn from(signing_data: SigningTxData) -> Self {
+        Self {
+            owner: signing_data.owner,
+            public_keys: signing_data.public_keys,
+            threshold: signing_data.threshold,
+            account_public_keys_map: signing_data.account_public_keys_map,
+            inner_txs: vec![],
+        }
+    }
+}
+
+impl BatchSigner {
+    /// Check if the other signer requires the same signatures, ignoring the
+    /// inner transactions and the ordering of the public keys
+    fn is_same(&self, other: &Self) -> bool {
+        self.owner == other.owner
+            && self.threshold == other.threshold
+            && self.account_public_keys_map == other.account_public_keys_map
+            && self.public_keys.len() == other.public_keys.len()
+            && self
+                .public_keys
+                .iter()
+                .all(|pubkey| other.public_keys.contains(pubkey))
+    }
+
+    /// Check if the public key can sign for this signer
+    fn has_public_key(&self, pubkey: &common::PublicKey) -> bool {
+        self.public_keys.contains(pubkey)
+            || self.account_public_keys_map.as_ref().is_some_and(|map| {
+                map.get_index_from_public_key(pubkey).is_some()
+            })
+    }
+
+    /// Check if the signature counts towards the threshold of this signer
+    fn accepts(&self, signature: &SignatureIndex) -> bool {
+        match &signature.index {
+            Some((addr, idx)) => {
+                self.owner.as_ref() == Some(addr)
+                    && self
+                        .account_public_keys_map
+                        .as_ref()
+                        .and_then(|map| map.get_public_key_from_index(*idx))
+                        .as_ref()
+                        == Some(&signature.pubkey)
+            }
+            None => self.has_public_key(&signature.pubkey),
+        }
+    }
+
+    /// Sum the weights of the public keys of the given signatures
+    fn weight(&self, signatures: &[SignatureIndex]) -> u32 {
+        let pubkeys: HashSet<_> = signatures
+            .iter()
+            .filter(|signature| self.accepts(signature))
+            .map(|signature| &signature.pubkey)
+            .collect();
+        match &self.account_public_keys_map {
+            Some(map) => map.total_weight(
+                pubkeys.iter().filter_map(|pk| map.pk_to_idx.get(*pk)),
+            ),
+            None => pubkeys.len().try_into().unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// A batch with the signatures collected so far from its signers
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct PartiallySignedBatch {
+    /// The version of the format
+    pub version: u8,
+    /// The batch. Its header must not change after it has been signed.
+    pub tx: Tx,
+    /// The signers required by the inner transactions
+    pub signers: Vec<BatchSigner>,
+    /// The public key of the fee payer
+    pub fee_payer: common::PublicKey,
+    /// The signatures over the raw header of the batch
+    pub signatures: Vec<SignatureIndex>,
+}
+
+impl PartiallySignedBatch {
+    /// Serialize the batch to pretty JSON into an I/O stream
+    pub fn to_writer_json<W>(&self, writer: W) -> serde_json::Result<()>
+    where
+        W: io::Write,
+    {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Try to parse a batch from JSON string bytes. Fails if the version of
+    /// the format isn't supported.
+    pub fn try_from_json_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let batch: Self = serde_json::from_slice(bytes)
+            .map_err(|err| EncodingError::Serde(err.to_string()))?;
+        if batch.version != PARTIALLY_SIGNED_BATCH_VERSION {
+            return Err(Error::Other(format!(
+                "Unsupported partially signed batch version {}, expected {}",
+                batch.version, PARTIALLY_SIGNED_BATCH_VERSION
+            )));
+        }
+        Ok(batch)
+    }
+
+    /// Get the signers required by the inner transaction with the given
+    /// commitment hash
+    pub fn inner_tx_signers<'a>(
+        &'a self,
+        cmt: &'a Hash,
+    ) -> impl Iterator<Item = &'a BatchSigner> {
+        self.signers
+            .iter()
+            .filter(move |signer| signer.inner_txs.contains(cmt))
+    }
+
+    /// Sign the batch with the secret keys of any of its signers. The
+    /// signatures are not added to the batch.
+    pub fn sign(
+        &self,
+        secret_keys: &[common::SecretKey],
+    ) -> Vec<SignatureIndex> {
+        let mut signatures = vec![];
+        for signer in &self.signers {
+            for secret_key in secret_keys {
+                let pubkey = secret_key.to_public();
+                if !signer.has_public_key(&pubkey) {
+                    continue;
+                }
+                let sigs =
+                    match (&signer.owner, &signer.account_public_keys_map) {
+                        (Some(owner), Some(map))
+                            if map
+                                .get_index_from_public_key(&pubkey)
+                                .is_some() =>
+                        {
+                            self.tx.compute_section_signature(
+                                &[secret_key.clone()],
+                                map,
+                                Some(owner.clone()),
+                            )
+                        }
+                        _ => self.tx.compute_section_signature(
+                            &[secret_key.clone()],
+                            &AccountPublicKeysMap::from_iter([pubkey]),
+                            None,
+                        ),
+                    };
+                for sig in sigs {
+                    if !signatures.contains(&sig) {
+                        signatures.push(sig);
+                    }
+                }
+            }
+        }
+        signatures
+    }
+
+    /// Add the given signatures to the batch. Fails if a signature isn't
+    /// valid or isn't required by any signer.
+    pub fn add_signatures(
+        &mut self,
+        signatures: Vec<SignatureIndex>,
+    ) -> Result<&mut Self, Error> {
+        // The raw hash of the authorization sections of the batch
+        let target = Authorization {
+            targets: vec![self.tx.raw_header_hash()],
+            signer: Signer::PubKeys(vec![]),
+            signatures: BTreeMap::new(),
+        }
+        .get_raw_hash();
+        for signature in signatures {
+            if !self.signers.iter().any(|signer| signer.accepts(&signature)) {
+                return Err(Error::Other(format!(
+                    "The public key {} is not a signer of the batch",
+                    signature.pubkey
+                )));
+            }
+            common::SigScheme::verify_signature(
+                &signature.pubkey,
+                &target,
+                &signature.signature,
+            )
+            .map_err(|err| {
+                Error::Other(format!(
+                    "Invalid signature of the public key {}: {err}",
+                    signature.pubkey
+                ))
+            })?;
+            if !self.signatures.contains(&signature) {
+                self.signatures.push(signature);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Merge the signatures of another copy of this batch
+    pub fn merge(&mut self, other: Self) -> Result<&mut Self, Error> {
+        if self.tx.raw_header_hash() != other.tx.raw_header_hash() {
+            return Err(Error::Other(
+                "Cannot merge the signatures of a different batch".to_string(),
+            ));
+        }
+        self.add_signatures(other.signatures)
+    }
+
+    /// Get the signers whose threshold isn't met yet
+    pub fn missing_signers(&self) -> Vec<&BatchSigner> {
+        self.signers
+            .iter()
+            .filter(|signer| {
+                signer.weight(&self.signatures) < u32::from(signer.threshold)
+            })
+            .collect()
+    }
+
+    /// Check that the signatures meet the threshold of every signer
+    pub fn validate(&self) -> Result<(), Error> {
+        match self.missing_signers().first() {
+            Some(signer) => {
+                let weight = signer.weight(&self.signatures);
+                Err(Error::from(TxSubmitError::MissingSigningKeys(
+                    signer.threshold,
+                    u8::try_from(weight).unwrap_or(u8::MAX),
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Add the collected signatures to the batch once it is fully signed.
+    /// Returns the batch with the signing data of its fee payer, to be
+    /// wrapped and signed with [`crate::signing::sign_tx`].
+    pub fn finalize(self) -> Result<(Tx, SigningTxData), Error> {
+        self.validate()?;
+        let mut tx = self.tx;
+        tx.add_signatures(self.signatures);
+        let signing_data = SigningTxData {
+            owner: None,
+            public_keys: vec![],
+            threshold: 0,
+            account_public_keys_map: None,
+            fee_payer: self.fee_payer,
+        };
+        Ok((tx, signing_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::address::testing::established_address_1;
+    use namada_core::chain::ChainId;
+    use namada_core::key::testing::{
+        keypair_1, keypair_2, keypair_3, keypair_4,
+    };
+
+    use super::*;
+
+    fn inner_tx(data: u8) -> Tx {
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.add_code(vec![1u8, 2, 3], None).add_data(data);
+        tx
+    }
+
+    #[test]
+    fn test_partially_signed_batch() {
+        let implicit_pk = keypair_1().to_public();
+        let implicit = Address::from(&implicit_pk);
+        let implicit_signer = SigningTxData {
+            owner: Some(implicit.clone()),
+            public_keys: vec![implicit_pk.clone()],
+            threshold: 1,
+            account_public_keys_map: Some(AccountPublicKeysMap::from_iter([
+                implicit_pk.clone(),
+            ])),
+            fee_payer: implicit_pk.clone(),
+        };
+        let multisig = established_address_1();
+        let multisig_map = AccountPublicKeysMap::from_iter([
+            keypair_2().to_public(),
+            keypair_3().to_public(),
+        ]);
+        let multisig_signer = SigningTxData {
+            owner: Some(multisig.clone()),
+            public_keys: vec![keypair_2().to_public()],
+            threshold: 2,
+            account_public_keys_map: Some(multisig_map.clone()),
+            fee_payer: keypair_2().to_public(),
+        };
+
+        let batch = BatchBuilder::new()
+            .add_inner_tx(inner_tx(1), implicit_signer.clone())
+            .add_inner_tx(inner_tx(2), multisig_signer.clone())
+            .add_inner_tx(inner_tx(3), implicit_signer)
+            .atomic(true)
+            .build()
+            .unwrap();
+        assert!(batch.tx.header.atomic);
+        assert_eq!(batch.tx.commitments().len(), 3);
+        assert_eq!(batch.fee_payer, implicit_pk);
+        assert_eq!(batch.signers.len(), 2);
+        assert_eq!(batch.signers[0].inner_txs.len(), 2);
+        let cmt = batch.signers[1].inner_txs[0];
+        assert_eq!(
+            batch.inner_tx_signers(&cmt).collect::<Vec<_>>(),
+            vec![&batch.signers[1]]
+        );
+        assert_eq!(batch.missing_signers().len(), 2);
+
+        // The parties sign their own copies of the batch
+        let mut buffer = vec![];
+        batch.to_writer_json(&mut buffer).unwrap();
+        let mut first =
+            PartiallySignedBatch::try_from_json_bytes(&buffer).unwrap();
+        assert_eq!(first, batch);
+        let signatures = first.sign(&[keypair_1(), keypair_2()]);
+        assert_eq!(signatures.len(), 2);
+        first.add_signatures(signatures).unwrap();
+        assert_eq!(first.missing_signers(), vec![&batch.signers[1]]);
+        assert!(first.clone().finalize().is_err());
+
+        let mut second = batch.clone();
+        let signatures = second.sign(&[keypair_3()]);
+        second.add_signatures(signatures).unwrap();
+        // A signature over another batch is rejected
+        let other = BatchBuilder::new()
+            .add_inner_tx(inner_tx(4), multisig_signer)
+            .build()
+            .unwrap();
+        let signatures = other.sign(&[keypair_2()]);
+        assert!(second.add_signatures(signatures).is_err());
+        // A key which isn't a signer is rejected
+        let signatures = first.tx.compute_section_signature(
+            &[keypair_4()],
+            &AccountPublicKeysMap::from_iter([keypair_4().to_public()]),
+            None,
+        );
+        assert!(second.add_signatures(signatures).is_err());
+
+        first.merge(second).unwrap();
+        assert!(first.missing_signers().is_empty());
+        let (tx, signing_data) = first.finalize().unwrap();
+        assert_eq!(signing_data.fee_payer, implicit_pk);
+        tx.verify_signatures(
+            &[tx.raw_header_hash()],
+            multisig_map,
+            &Some(multisig),
+            2,
+            || Ok(()),
+        )
+        .unwrap();
+        tx.verify_signatures(
+            &[tx.raw_header_hash()],
+            AccountPublicKeysMap::from_iter([implicit_pk]),
+            &Some(implicit),
+            1,
+            || Ok(()),
+        )
+        .unwrap();
+    }
+}
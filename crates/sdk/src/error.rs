@@ -276,6 +276,12 @@ pub enum TxSubmitError {
     /// Account threshold is not set
     #[error("Account threshold is invalid.")]
     InvalidAccountThreshold,
+    /// Account public key weights are invalid
+    #[error(
+        "Account public key weights are invalid: there must be exactly one \
+         non-zero weight per public key."
+    )]
+    InvalidAccountWeights,
     /// Not enough signature
     #[error("Account threshold is {0} but the valid signatures are {1}.")]
     MissingSigningKeys(u8, u8),
@@ -369,4 +375,9 @@ pub enum EthereumBridgeError {
     /// Transfer already in pool error.
     #[error("An identical transfer is already present in the Bridge pool")]
     TransferAlreadyInPool,
+    /// Insufficient gas fee error.
+    #[error(
+        "The Bridge pool gas fee is lower than the minimum gas fee of {0} {1}"
+    )]
+    GasFeeBelowMinimum(String, Address),
 }
@@ -15,8 +15,8 @@ use namada_core::arith::checked;
 use namada_core::collections::{HashMap, HashSet};
 use namada_core::eth_abi::Encode;
 use namada_core::eth_bridge_pool::{
-    erc20_token_address, GasFee, PendingTransfer, TransferToEthereum,
-    TransferToEthereumKind,
+    erc20_token_address, GasFee, GasFeeQuote, PendingTransfer,
+    TransferToEthereum, TransferToEthereumKind,
 };
 use namada_core::ethereum_events::EthAddress;
 use namada_core::keccak::KeccakHash;
@@ -40,7 +40,10 @@ use crate::queries::{
     GenBridgePoolProofReq, GenBridgePoolProofRsp, TransferToErcArgs,
     TransferToEthereumStatus, RPC,
 };
-use crate::rpc::{query_storage_value, query_wasm_code_hash, validate_amount};
+use crate::rpc::{
+    format_denominated_amount, query_storage_value, query_wasm_code_hash,
+    validate_amount,
+};
 use crate::signing::{aux_signing_data, validate_transparent_fee};
 use crate::tx::prepare_tx;
 use crate::{args, MaybeSync, Namada, SigningTxData};
@@ -222,6 +225,22 @@ async fn validate_bridge_pool_tx(
         _ => {}
     }
 
+    // validate the minimum gas fee
+    let quote =
+        query_gas_fee_quote(context.client(), &transfer.gas_fee.token).await?;
+    if !quote.is_covered_by(&transfer.gas_fee) {
+        let min_fee = format_denominated_amount(
+            context.client(),
+            context.io(),
+            &quote.token,
+            quote.min_fee,
+        )
+        .await;
+        return Err(Error::EthereumBridge(
+            EthereumBridgeError::GasFeeBelowMinimum(min_fee, quote.token),
+        ));
+    }
+
     // validate wnam token caps + whitelist
     if transfer.transfer.asset == wnam_addr {
         let flow_control = RPC
@@ -311,6 +330,42 @@ async fn validate_bridge_pool_tx(
     Ok(transfer)
 }
 
+/// Query a quote of the minimum gas fee required to add a
+/// transfer to the Ethereum bridge pool, paying fees in the
+/// given token.
+pub async fn query_gas_fee_quote(
+    client: &(impl Client + Sync),
+    token: &Address,
+) -> Result<GasFeeQuote, Error> {
+    RPC.shell()
+        .eth_bridge()
+        .read_gas_fee_quote(client, token)
+        .await
+        .map_err(|e| {
+            Error::Query(QueryError::General(format!(
+                "Failed to query the Bridge pool gas fee quote: {e}"
+            )))
+        })
+}
+
+/// Query a quote of the minimum gas fee required to add a
+/// transfer to the Ethereum bridge pool.
+/// Prints out a json payload.
+pub async fn query_bridge_pool_fee_quote(
+    client: &(impl Client + Sync),
+    io: &impl Io,
+    token: &Address,
+) -> Result<GasFeeQuote, Error> {
+    let quote = query_gas_fee_quote(client, token).await?;
+    display_line!(
+        io,
+        "{}",
+        serde_json::to_string_pretty(&quote)
+            .map_err(|e| EncodingError::Serde(e.to_string()))?
+    );
+    Ok(quote)
+}
+
 /// A json serializable representation of the Ethereum
 /// bridge pool.
 #[derive(Serialize)]
@@ -828,13 +883,212 @@ mod recommendations {
         bridge_pool_gas_fees: HashMap<String, Uint>,
     }
 
+    /// Batch of transfers to Ethereum maximising the net profit
+    /// of a relayer, under a gas budget.
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct BatchRecommendation {
+        /// Hashes of the recommended transfers to be relayed.
+        pub transfer_hashes: Vec<KeccakHash>,
+        /// Estimate of the total fees, measured in gwei, that will be paid
+        /// on Ethereum.
+        pub ethereum_gas_fees: Uint,
+        /// Net profit in gwei, based on the conversion rates provided
+        /// to the algorithm.
+        pub net_profit: I256,
+        /// Gas fees paid by the recommended transfers, paid in various
+        /// token types.
+        pub bridge_pool_gas_fees: HashMap<String, Uint>,
+        /// Ethereum ABI encoded arguments to pass to `transfer_to_erc`,
+        /// if a relayer was provided.
+        pub abi_encoded_proof: Option<Vec<u8>>,
+    }
+
+    /// A json serializable representation of a [`BatchRecommendation`].
+    #[derive(Serialize)]
+    struct BatchRecommendationResponse {
+        transfer_hashes: Vec<KeccakHash>,
+        ethereum_gas_fees: Uint,
+        net_profit: String,
+        bridge_pool_gas_fees: HashMap<String, Uint>,
+        abi_encoded_proof: Option<Vec<u8>>,
+    }
+
+    impl From<BatchRecommendation> for BatchRecommendationResponse {
+        fn from(batch: BatchRecommendation) -> Self {
+            Self {
+                transfer_hashes: batch.transfer_hashes,
+                ethereum_gas_fees: batch.ethereum_gas_fees,
+                net_profit: batch.net_profit.to_string(),
+                bridge_pool_gas_fees: batch.bridge_pool_gas_fees,
+                abi_encoded_proof: batch.abi_encoded_proof,
+            }
+        }
+    }
+
     /// Recommend the most economical batch of transfers to relay based
     /// on a conversion rate estimates from NAM to ETH and gas usage
     /// heuristics.
+    ///
+    /// If a relayer is provided, the batch maximising the net profit
+    /// of the relayer is printed out as a json payload, along with the
+    /// proof to relay it.
     pub async fn recommend_batch(
         context: &impl Namada,
         args: args::RecommendBatch,
     ) -> Result<(), Error> {
+        if args.relayer.is_some() {
+            match recommend_relay_batch(context, args).await? {
+                Some(batch) => display_line!(
+                    context.io(),
+                    "{}",
+                    serde_json::to_string_pretty(
+                        &BatchRecommendationResponse::from(batch)
+                    )
+                    .map_err(|e| EncodingError::Serde(e.to_string()))?
+                ),
+                None => display_line!(
+                    context.io(),
+                    "Unable to find a recommendation satisfying the input \
+                     parameters."
+                ),
+            }
+            return Ok(());
+        }
+
+        let (in_progress, validator_gas) = relay_inputs(context).await?;
+
+        // we don't recommend transfers that have already been relayed
+        let eligible = generate_eligible(
+            context.io(),
+            &args.conversion_table,
+            &in_progress,
+            query_signed_bridge_pool(context.client(), context.io()).await?,
+        )?;
+
+        let max_gas =
+            args.max_gas.map(Uint::from_u64).unwrap_or(uint::MAX_VALUE);
+        let max_cost = args.gas.map(I256::from).unwrap_or_default();
+
+        generate_recommendations(
+            context.io(),
+            eligible,
+            &args.conversion_table,
+            validator_gas,
+            max_gas,
+            max_cost,
+        )?
+        .map(
+            |RecommendedBatch {
+                 transfer_hashes,
+                 ethereum_gas_fees,
+                 net_profit,
+                 bridge_pool_gas_fees,
+             }| {
+                display_line!(
+                    context.io(),
+                    "Recommended batch: {transfer_hashes:#?}"
+                );
+                display_line!(
+                    context.io(),
+                    "Estimated Ethereum transaction gas (in gwei): \
+                     {ethereum_gas_fees}",
+                );
+                display_line!(
+                    context.io(),
+                    "Estimated net profit (in gwei): {net_profit}"
+                );
+                display_line!(
+                    context.io(),
+                    "Total fees: {bridge_pool_gas_fees:#?}"
+                );
+            },
+        )
+        .unwrap_or_else(|| {
+            display_line!(
+                context.io(),
+                "Unable to find a recommendation satisfying the input \
+                 parameters."
+            );
+        });
+
+        Ok(())
+    }
+
+    /// Recommend the batch of transfers maximising the net profit of
+    /// a relayer, based on conversion rate estimates from the gas fee
+    /// tokens to ETH and gas usage heuristics.
+    ///
+    /// If a relayer is provided, the batch comes with the proof that
+    /// its transfers are in the Bridge pool, ready to be relayed.
+    pub async fn recommend_relay_batch(
+        context: &impl Namada,
+        args: args::RecommendBatch,
+    ) -> Result<Option<BatchRecommendation>, Error> {
+        let (in_progress, validator_gas) = relay_inputs(context).await?;
+
+        let signed_pool = RPC
+            .shell()
+            .eth_bridge()
+            .read_signed_ethereum_bridge_pool(context.client())
+            .await
+            .map_err(|e| {
+                Error::EthereumBridge(
+                    EthereumBridgeError::ReadSignedBridgePool(e.to_string()),
+                )
+            })?
+            .into_iter()
+            .map(|transfer| (transfer.keccak256().to_string(), transfer))
+            .collect();
+
+        // we don't recommend transfers that have already been relayed
+        let eligible = generate_eligible(
+            context.io(),
+            &args.conversion_table,
+            &in_progress,
+            signed_pool,
+        )?;
+
+        let max_gas =
+            args.max_gas.map(Uint::from_u64).unwrap_or(uint::MAX_VALUE);
+        let max_cost = args.gas.map(I256::from).unwrap_or_default();
+
+        let Some(mut batch) = optimize_recommendations(
+            context.io(),
+            eligible,
+            &args.conversion_table,
+            validator_gas,
+            max_gas,
+            max_cost,
+        )?
+        else {
+            return Ok(None);
+        };
+
+        if let Some(relayer) = &args.relayer {
+            let GenBridgePoolProofRsp {
+                abi_encoded_args, ..
+            } = construct_bridge_pool_proof(
+                context.client(),
+                context.io(),
+                GenBridgePoolProofReq {
+                    transfers: batch.transfer_hashes.as_slice().into(),
+                    relayer: Cow::Borrowed(relayer),
+                    with_appendix: false,
+                },
+            )
+            .await?;
+            batch.abi_encoded_proof = Some(abi_encoded_args);
+        }
+
+        Ok(Some(batch))
+    }
+
+    /// Get the transfers awaiting a quorum of backing votes and the gas
+    /// cost of checking the signatures of the latest signed Bridge pool
+    /// root, which are needed to recommend a batch of transfers.
+    async fn relay_inputs(
+        context: &impl Namada,
+    ) -> Result<(BTreeSet<String>, Uint), Error> {
         // get transfers that can already been relayed but are awaiting a quorum
         // of backing votes.
         let in_progress = RPC
@@ -936,61 +1190,7 @@ mod recommendations {
             * signature_checks(voting_powers, &bp_root.signatures)?
             + valset_fee() * valset_size;
 
-        // we don't recommend transfers that have already been relayed
-        let eligible = generate_eligible(
-            context.io(),
-            &args.conversion_table,
-            &in_progress,
-            query_signed_bridge_pool(context.client(), context.io()).await?,
-        )?;
-
-        let max_gas =
-            args.max_gas.map(Uint::from_u64).unwrap_or(uint::MAX_VALUE);
-        let max_cost = args.gas.map(I256::from).unwrap_or_default();
-
-        generate_recommendations(
-            context.io(),
-            eligible,
-            &args.conversion_table,
-            validator_gas,
-            max_gas,
-            max_cost,
-        )?
-        .map(
-            |RecommendedBatch {
-                 transfer_hashes,
-                 ethereum_gas_fees,
-                 net_profit,
-                 bridge_pool_gas_fees,
-             }| {
-                display_line!(
-                    context.io(),
-                    "Recommended batch: {transfer_hashes:#?}"
-                );
-                display_line!(
-                    context.io(),
-                    "Estimated Ethereum transaction gas (in gwei): \
-                     {ethereum_gas_fees}",
-                );
-                display_line!(
-                    context.io(),
-                    "Estimated net profit (in gwei): {net_profit}"
-                );
-                display_line!(
-                    context.io(),
-                    "Total fees: {bridge_pool_gas_fees:#?}"
-                );
-            },
-        )
-        .unwrap_or_else(|| {
-            display_line!(
-                context.io(),
-                "Unable to find a recommendation satisfying the input \
-                 parameters."
-            );
-        });
-
-        Ok(())
+        Ok((in_progress, validator_gas))
     }
 
     /// Given an ordered list of signatures, figure out the size of the first
@@ -1200,6 +1400,71 @@ mod recommendations {
         })
     }
 
+    /// Generates the recommendation maximising the net profit of the
+    /// relayer, under the gas budget given by the input parameters.
+    ///
+    /// Relaying any transfer costs the same amount of gas, thus the
+    /// most profitable batch of at most `n` transfers is made of the
+    /// `n` most profitable transfers.
+    fn optimize_recommendations<IO: Io>(
+        io: &IO,
+        contents: Vec<EligibleRecommendation>,
+        conversion_table: &HashMap<Address, args::BpConversionTableEntry>,
+        validator_gas: Uint,
+        max_gas: Uint,
+        max_cost: I256,
+    ) -> Result<Option<BatchRecommendation>, Error> {
+        let max_transfers = match max_gas.checked_sub(validator_gas) {
+            Some(budget) => checked!(budget / unsigned_transfer_fee())?,
+            None => uint::ZERO,
+        };
+
+        let mut total_gas = validator_gas;
+        let mut total_cost = I256::try_from(validator_gas).map_err(|err| {
+            Error::Encode(EncodingError::Conversion(echo_error!(
+                io,
+                "Failed to convert value to I256: {err}"
+            )))
+        })?;
+        let mut total_fees = HashMap::new();
+        let mut transfer_hashes = vec![];
+        // the transfers are sorted in increasing amounts of cost,
+        // and only the profitable ones can increase the net profit
+        for EligibleRecommendation {
+            cost,
+            pending_transfer: transfer,
+            ..
+        } in contents
+            .into_iter()
+            .take_while(|eligible| eligible.cost.is_negative())
+        {
+            if Uint::from_u64(transfer_hashes.len() as u64) >= max_transfers {
+                break;
+            }
+            total_gas = checked!(total_gas + unsigned_transfer_fee())?;
+            total_cost = checked!(total_cost + cost)?;
+            transfer_hashes.push(transfer.keccak256());
+            update_total_fees(&mut total_fees, transfer, conversion_table);
+        }
+
+        Ok(if !transfer_hashes.is_empty() && total_cost <= max_cost {
+            Some(BatchRecommendation {
+                transfer_hashes,
+                ethereum_gas_fees: total_gas,
+                net_profit: checked!(-total_cost)?,
+                bridge_pool_gas_fees: total_fees,
+                abi_encoded_proof: None,
+            })
+        } else {
+            edisplay_line!(
+                io,
+                "Unable to find a recommendation satisfying the input \
+                 parameters."
+            );
+            None
+        })
+    }
+
     fn update_total_fees(
         total_fees: &mut HashMap<String, Uint>,
         transfer: PendingTransfer,
@@ -1528,6 +1793,65 @@ mod recommendations {
             assert!(recommendation.is_none())
         }
 
+        /// Test that the optimal batch is made of the most profitable
+        /// transfers fitting in the gas budget.
+        #[test]
+        fn test_optimal_batch_max_gas() {
+            let mut transfers = vec![transfer(50_000); 2];
+            transfers.push(transfer(0));
+            transfers.extend(vec![transfer(100_000); 3]);
+            let mut eligible = process_transfers(transfers);
+            eligible.sort_by_key(|EligibleRecommendation { cost, .. }| *cost);
+            let expected: Vec<_> = [100_000, 100_000, 100_000, 50_000]
+                .into_iter()
+                .map(|fee| transfer(fee).keccak256())
+                .collect();
+
+            let batch = optimize_recommendations(
+                &StdIo,
+                eligible,
+                &Default::default(),
+                Uint::from_u64(50_000),
+                // room for four transfers
+                Uint::from_u64(200_000),
+                I256::zero(),
+            )
+            .unwrap()
+            .expect("Test failed");
+            assert_eq!(batch.transfer_hashes, expected);
+            assert_eq!(batch.ethereum_gas_fees, Uint::from_u64(200_000));
+            assert_eq!(batch.net_profit, I256::from(150_000));
+            assert_eq!(batch.abi_encoded_proof, None);
+        }
+
+        /// Test that no batch is recommended if the most profitable
+        /// one exceeds the maximum cost or the gas budget.
+        #[test]
+        fn test_optimal_batch_infeasible() {
+            let eligible = || process_transfers(vec![transfer(75_000); 4]);
+            let recommendation = optimize_recommendations(
+                &StdIo,
+                eligible(),
+                &Default::default(),
+                Uint::from_u64(300_000),
+                uint::MAX_VALUE,
+                I256::from(20_000),
+            )
+            .unwrap();
+            assert!(recommendation.is_none());
+
+            let recommendation = optimize_recommendations(
+                &StdIo,
+                eligible(),
+                &Default::default(),
+                Uint::from_u64(50_000),
+                Uint::from_u64(80_000),
+                I256(uint::MAX_SIGNED_VALUE),
+            )
+            .unwrap();
+            assert!(recommendation.is_none());
+        }
+
         /// Test the profit margin obtained from relaying two
         /// Bridge pool transfers with two distinct token types,
         /// whose relation is 1:2 in value.
@@ -1619,4 +1943,6 @@ mod recommendations {
     }
 }
 
-pub use recommendations::recommend_batch;
+pub use recommendations::{
+    BatchRecommendation, recommend_batch, recommend_relay_batch,
+};
@@ -1,5 +1,6 @@
 //! Logic to do with events emitted by the ledger.
 pub mod log;
+pub mod subscription;
 
 use namada_core::collections::HashMap;
 pub use namada_events::*;
@@ -0,0 +1,488 @@
+//! Typed subscriptions over the events emitted by the ledger.
+//!
+//! An [`EventSubscription`] polls some [`EventTransport`] block by block,
+//! keeps the events that pass an [`EventFilter`] and classifies them into
+//! [`TypedEvent`] values, such that clients do not have to parse raw
+//! Tendermint event attributes by hand.
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream};
+use namada_core::chain::BlockHeight;
+use namada_core::collections::HashMap;
+use namada_core::masp::IndexedMaspData;
+use namada_io::Client;
+
+use crate::control_flow::time::{self, Duration};
+use crate::error::{Error, QueryError};
+use crate::events::extend::{
+    ExtendEventAttributes, MaspDataRefs, ReadFromEventAttributes,
+};
+use crate::events::log::dumb_queries::QueryMatcher;
+use crate::events::{Event, EventToEmit, EventType};
+use crate::governance::event::GovernanceEvent;
+use crate::ibc::event::IbcEvent;
+use crate::proof_of_stake::event::PosEvent;
+use crate::rpc;
+use crate::tx::event::types::APPLIED as APPLIED_TX;
+
+/// Sub-domains of the IBC events related to packet lifecycles.
+const IBC_PACKET_EVENTS: &[&str] = &[
+    "send_packet",
+    "recv_packet",
+    "write_acknowledgement",
+    "acknowledge_packet",
+    "timeout_packet",
+];
+
+/// Predicate over the attributes of an [`Event`].
+type AttributePredicate = Box<dyn Fn(&Event) -> bool + Send + Sync>;
+
+/// Filter applied to the events of a [`EventSubscription`].
+///
+/// An event passes the filter if it matches any of the configured
+/// [`QueryMatcher`] instances (or if no matchers have been configured),
+/// and if it satisfies all of the attribute predicates.
+#[derive(Default)]
+pub struct EventFilter {
+    matchers: Vec<QueryMatcher>,
+    predicates: Vec<AttributePredicate>,
+    from_height: Option<BlockHeight>,
+    to_height: Option<BlockHeight>,
+}
+
+impl EventFilter {
+    /// Create a new [`EventFilter`] that lets all events through.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept events whose type falls under the domain of `E`.
+    pub fn of_domain<E: EventToEmit>(self) -> Self {
+        self.with_matcher(QueryMatcher::of_event_type::<E>())
+    }
+
+    /// Accept events whose type is exactly `event_type`.
+    pub fn of_event_type(self, event_type: EventType) -> Self {
+        self.with_matcher(QueryMatcher::with_event_type(event_type))
+    }
+
+    /// Accept events matched by `matcher`.
+    pub fn with_matcher(mut self, matcher: QueryMatcher) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Require events to contain the given attribute, with an identical
+    /// value.
+    pub fn and_attribute<DATA>(mut self, data: DATA) -> Self
+    where
+        DATA: ExtendEventAttributes,
+    {
+        let mut attributes = HashMap::new();
+        data.extend_event_attributes(&mut attributes);
+        self.predicates.push(Box::new(move |event: &Event| {
+            event.has_subset_of_attrs(&attributes)
+        }));
+        self
+    }
+
+    /// Require events to contain the attribute `DATA`, with a value in
+    /// the inclusive range delimited by `min` and `max`.
+    ///
+    /// Events whose attribute cannot be decoded are rejected.
+    pub fn and_attribute_in_range<DATA>(
+        mut self,
+        min: Option<<DATA as ReadFromEventAttributes<'static>>::Value>,
+        max: Option<<DATA as ReadFromEventAttributes<'static>>::Value>,
+    ) -> Self
+    where
+        DATA: ReadFromEventAttributes<'static> + 'static,
+        <DATA as ReadFromEventAttributes<'static>>::Value:
+            PartialOrd + Send + Sync + 'static,
+    {
+        self.predicates.push(Box::new(move |event: &Event| {
+            let Ok(value) = event.read_attribute::<DATA>() else {
+                return false;
+            };
+            min.as_ref().map_or(true, |min| &value >= min)
+                && max.as_ref().map_or(true, |max| &value <= max)
+        }));
+        self
+    }
+
+    /// Require events to pass at least one of the given filters.
+    ///
+    /// Only the matchers and attribute predicates of `alternatives` are
+    /// taken into account; their height windows are ignored.
+    pub fn and_any_of(mut self, alternatives: Vec<EventFilter>) -> Self {
+        self.predicates.push(Box::new(move |event: &Event| {
+            alternatives.iter().any(|filter| filter.matches(event))
+        }));
+        self
+    }
+
+    /// Only consider events emitted at or after `height`.
+    pub fn from_height(mut self, height: BlockHeight) -> Self {
+        self.from_height = Some(height);
+        self
+    }
+
+    /// Only consider events emitted at or before `height`.
+    pub fn to_height(mut self, height: BlockHeight) -> Self {
+        self.to_height = Some(height);
+        self
+    }
+
+    /// Check if `event` passes this filter, ignoring its height window.
+    pub fn matches(&self, event: &Event) -> bool {
+        let matches_type = self.matchers.is_empty()
+            || self.matchers.iter().any(|matcher| matcher.matches(event));
+
+        matches_type && self.predicates.iter().all(|pred| pred(event))
+    }
+}
+
+impl std::fmt::Debug for EventFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventFilter")
+            .field("matchers", &self.matchers)
+            .field("predicates", &self.predicates.len())
+            .field("from_height", &self.from_height)
+            .field("to_height", &self.to_height)
+            .finish()
+    }
+}
+
+/// Event classified by its domain.
+#[derive(Debug, Clone)]
+pub enum TypedEvent {
+    /// A transaction was applied.
+    TxApplied(Event),
+    /// MASP data was emitted by a transaction.
+    MaspData {
+        /// Location of the MASP data in the block.
+        data: IndexedMaspData,
+        /// The original event.
+        event: Event,
+    },
+    /// Proof-of-stake event.
+    Pos(Event),
+    /// Governance event.
+    Governance(Event),
+    /// IBC packet lifecycle event.
+    IbcPacket(Event),
+    /// Any other event.
+    Other(Event),
+}
+
+impl TypedEvent {
+    /// Return a reference to the underlying [`Event`].
+    pub fn event(&self) -> &Event {
+        match self {
+            Self::TxApplied(event)
+            | Self::MaspData { event, .. }
+            | Self::Pos(event)
+            | Self::Governance(event)
+            | Self::IbcPacket(event)
+            | Self::Other(event) => event,
+        }
+    }
+
+    /// Return the underlying [`Event`].
+    pub fn into_event(self) -> Event {
+        match self {
+            Self::TxApplied(event)
+            | Self::MaspData { event, .. }
+            | Self::Pos(event)
+            | Self::Governance(event)
+            | Self::IbcPacket(event)
+            | Self::Other(event) => event,
+        }
+    }
+}
+
+impl From<Event> for TypedEvent {
+    fn from(event: Event) -> Self {
+        if let Ok(Some(data)) = event.read_attribute_opt::<MaspDataRefs>() {
+            return Self::MaspData { data, event };
+        }
+
+        let kind = event.kind();
+        match kind.domain() {
+            _ if *kind == APPLIED_TX => Self::TxApplied(event),
+            PosEvent::DOMAIN => Self::Pos(event),
+            GovernanceEvent::DOMAIN => Self::Governance(event),
+            IbcEvent::DOMAIN
+                if IBC_PACKET_EVENTS.contains(&kind.sub_domain()) =>
+            {
+                Self::IbcPacket(event)
+            }
+            _ => Self::Other(event),
+        }
+    }
+}
+
+/// Source of block events for an [`EventSubscription`].
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+pub trait EventTransport {
+    /// Return the height of the last committed block, if any.
+    async fn last_committed_height(&self)
+    -> Result<Option<BlockHeight>, Error>;
+
+    /// Return all the events emitted at the given block height.
+    async fn events_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Vec<Event>, Error>;
+}
+
+/// [`EventTransport`] backed by a CometBFT RPC [`Client`].
+#[derive(Debug)]
+pub struct ClientTransport<'client, C>(pub &'client C);
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl<C: Client + Sync> EventTransport for ClientTransport<'_, C> {
+    async fn last_committed_height(
+        &self,
+    ) -> Result<Option<BlockHeight>, Error> {
+        Ok(rpc::query_block(self.0).await?.map(|block| block.height))
+    }
+
+    async fn events_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Vec<Event>, Error> {
+        let response = self
+            .0
+            .block_results(height.0)
+            .await
+            .map_err(|e| Error::from(QueryError::General(e.to_string())))?;
+
+        response
+            .finalize_block_events
+            .into_iter()
+            .chain(response.end_block_events.into_iter().flatten())
+            .map(|event| Event::try_from(event).map_err(Error::from))
+            .collect()
+    }
+}
+
+/// Typed subscription to the events emitted by the ledger.
+#[derive(Debug)]
+pub struct EventSubscription<T> {
+    transport: T,
+    filter: EventFilter,
+    next_height: Option<BlockHeight>,
+}
+
+impl<T: EventTransport> EventSubscription<T> {
+    /// Create a new [`EventSubscription`].
+    ///
+    /// If the filter does not specify a starting height, events are
+    /// delivered from the first block committed after the first poll.
+    pub fn new(transport: T, filter: EventFilter) -> Self {
+        Self {
+            next_height: filter.from_height,
+            transport,
+            filter,
+        }
+    }
+
+    /// Check if all the blocks in the height window of the subscription's
+    /// filter have been processed.
+    pub fn is_exhausted(&self) -> bool {
+        matches!(
+            (self.next_height, self.filter.to_height),
+            (Some(next), Some(last)) if next > last
+        )
+    }
+
+    /// Fetch the events emitted in all blocks committed since the
+    /// last poll.
+    pub async fn poll(&mut self) -> Result<Vec<TypedEvent>, Error> {
+        let Some(last_committed) =
+            self.transport.last_committed_height().await?
+        else {
+            return Ok(vec![]);
+        };
+        let next_height = *self
+            .next_height
+            .get_or_insert_with(|| last_committed.next_height());
+        let last_height = self
+            .filter
+            .to_height
+            .map_or(last_committed, |to| to.min(last_committed));
+
+        let mut events = vec![];
+        let mut height = next_height;
+
+        while height <= last_height {
+            events.extend(
+                self.transport
+                    .events_at_height(height)
+                    .await?
+                    .into_iter()
+                    .filter(|event| self.filter.matches(event))
+                    .map(TypedEvent::from),
+            );
+            height = height.next_height();
+            self.next_height = Some(height);
+        }
+
+        Ok(events)
+    }
+
+    /// Convert this subscription into a [`Stream`] of events, polling
+    /// for new blocks every `poll_interval`.
+    ///
+    /// The stream ends once the subscription is exhausted, or after
+    /// yielding the first error it encounters.
+    pub fn into_stream(
+        self,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<TypedEvent, Error>> {
+        stream::unfold(
+            (self, VecDeque::new(), false),
+            move |(mut sub, mut pending, mut failed)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((Ok(event), (sub, pending, failed)));
+                    }
+                    if failed || sub.is_exhausted() {
+                        return None;
+                    }
+                    match sub.poll().await {
+                        Ok(events) if events.is_empty() => {
+                            time::sleep(poll_interval).await;
+                        }
+                        Ok(events) => pending.extend(events),
+                        Err(err) => {
+                            failed = true;
+                            return Some((Err(err), (sub, pending, failed)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::events::EventLevel;
+    use crate::events::extend::{ComposeEvent, Height as HeightAttr};
+    use crate::governance::event::types::PROPOSAL_PASSED;
+
+    /// In-memory transport that serves pre-built blocks of events.
+    struct MockTransport {
+        blocks: Vec<Vec<Event>>,
+        queried: Mutex<Vec<BlockHeight>>,
+    }
+
+    #[cfg_attr(feature = "async-send", async_trait::async_trait)]
+    #[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+    impl EventTransport for MockTransport {
+        async fn last_committed_height(
+            &self,
+        ) -> Result<Option<BlockHeight>, Error> {
+            Ok(Some(BlockHeight(self.blocks.len() as u64)))
+        }
+
+        async fn events_at_height(
+            &self,
+            height: BlockHeight,
+        ) -> Result<Vec<Event>, Error> {
+            self.queried.lock().unwrap().push(height);
+            Ok(self.blocks[height.0 as usize - 1].clone())
+        }
+    }
+
+    fn tx_applied(height: u64) -> Event {
+        Event::new(APPLIED_TX, EventLevel::Tx)
+            .with(HeightAttr(BlockHeight(height)))
+            .into()
+    }
+
+    fn proposal_passed(height: u64) -> Event {
+        Event::new(PROPOSAL_PASSED, EventLevel::Block)
+            .with(HeightAttr(BlockHeight(height)))
+            .into()
+    }
+
+    /// Test that filters combine matchers and attribute predicates.
+    #[test]
+    fn test_event_filter_matching() {
+        let filter = EventFilter::new()
+            .of_domain::<GovernanceEvent>()
+            .of_event_type(APPLIED_TX)
+            .and_attribute_in_range::<HeightAttr>(
+                Some(BlockHeight(2)),
+                Some(BlockHeight(3)),
+            );
+
+        assert!(!filter.matches(&tx_applied(1)));
+        assert!(filter.matches(&tx_applied(2)));
+        assert!(filter.matches(&proposal_passed(3)));
+        assert!(!filter.matches(&proposal_passed(4)));
+
+        let filter = EventFilter::new().and_any_of(vec![
+            EventFilter::new().and_attribute(HeightAttr(BlockHeight(1))),
+            EventFilter::new().and_attribute(HeightAttr(BlockHeight(4))),
+        ]);
+
+        assert!(filter.matches(&tx_applied(1)));
+        assert!(!filter.matches(&tx_applied(2)));
+        assert!(filter.matches(&proposal_passed(4)));
+    }
+
+    /// Test that events are classified into the expected variants.
+    #[test]
+    fn test_typed_event_classification() {
+        assert!(matches!(
+            TypedEvent::from(tx_applied(1)),
+            TypedEvent::TxApplied(_)
+        ));
+        assert!(matches!(
+            TypedEvent::from(proposal_passed(1)),
+            TypedEvent::Governance(_)
+        ));
+    }
+
+    /// Test that a subscription only visits blocks within its height
+    /// window, and that each block is visited once.
+    #[tokio::test]
+    async fn test_subscription_height_window() {
+        let transport = MockTransport {
+            blocks: (1..=5)
+                .map(|h| vec![tx_applied(h), proposal_passed(h)])
+                .collect(),
+            queried: Mutex::new(vec![]),
+        };
+        let filter = EventFilter::new()
+            .of_domain::<GovernanceEvent>()
+            .from_height(BlockHeight(2))
+            .to_height(BlockHeight(3));
+        let mut subscription = EventSubscription::new(transport, filter);
+
+        let events = subscription.poll().await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(
+            events
+                .iter()
+                .all(|ev| matches!(ev, TypedEvent::Governance(_)))
+        );
+        assert!(subscription.is_exhausted());
+
+        assert!(subscription.poll().await.unwrap().is_empty());
+        assert_eq!(
+            *subscription.transport.queried.lock().unwrap(),
+            vec![BlockHeight(2), BlockHeight(3)]
+        );
+    }
+}
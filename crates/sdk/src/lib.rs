@@ -27,6 +27,7 @@ pub mod eth_bridge;
 pub mod rpc;
 
 pub mod args;
+pub mod batch;
 pub mod masp;
 pub mod signing;
 #[allow(clippy::result_large_err)]
@@ -49,7 +50,10 @@ use namada_core::address::Address;
 use namada_core::collections::HashSet;
 use namada_core::dec::Dec;
 use namada_core::ethereum_events::EthAddress;
-use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
+use namada_core::ibc::core::host::types::identifiers::{
+    ChannelId, ConnectionId, PortId,
+};
+use namada_core::ibc::primitives::proto::Any;
 use namada_core::key::*;
 pub use namada_core::masp::{
     ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress, TransferSource,
@@ -139,6 +143,7 @@ pub trait Namada: NamadaIo {
             wallet_alias_force: false,
             fee_amount: None,
             wrapper_fee_payer: None,
+            fee_sponsor: None,
             fee_token: self.native_token(),
             gas_limit: GasLimit::from(DEFAULT_GAS_LIMIT),
             expiration: Default::default(),
@@ -316,18 +321,53 @@ pub trait Namada: NamadaIo {
             refund_target: None,
             ibc_shielding_data: None,
             ibc_memo: None,
+            relayer_fee: None,
             gas_spending_keys: Default::default(),
             tx: self.tx_builder(),
             tx_code_path: PathBuf::from(TX_IBC_WASM),
         }
     }
 
+    /// Make a TxIcaRegister builder from the given minimum set of arguments
+    fn new_ica_register(
+        &self,
+        owner: Address,
+        connection_id: ConnectionId,
+    ) -> args::TxIcaRegister {
+        args::TxIcaRegister {
+            owner,
+            connection_id,
+            version: None,
+            tx: self.tx_builder(),
+            tx_code_path: PathBuf::from(TX_IBC_WASM),
+        }
+    }
+
+    /// Make a TxIcaSendTx builder from the given minimum set of arguments
+    fn new_ica_send_tx(
+        &self,
+        owner: Address,
+        connection_id: ConnectionId,
+        messages: Vec<Any>,
+    ) -> args::TxIcaSendTx {
+        args::TxIcaSendTx {
+            owner,
+            connection_id,
+            messages,
+            ica_memo: None,
+            timeout_sec_offset: None,
+            tx: self.tx_builder(),
+            tx_code_path: PathBuf::from(TX_IBC_WASM),
+        }
+    }
+
     /// Make a InitProposal builder from the given minimum set of arguments
     fn new_init_proposal(&self, proposal_data: Vec<u8>) -> args::InitProposal {
         args::InitProposal {
             proposal_data,
             is_pgf_stewards: false,
             is_pgf_funding: false,
+            is_parameter_change: false,
             tx_code_path: PathBuf::from(TX_INIT_PROPOSAL),
             tx: self.tx_builder(),
         }
@@ -345,6 +385,8 @@ pub trait Namada: NamadaIo {
             vp_code_path: None,
             public_keys,
             threshold: Some(threshold),
+            weights: vec![],
+            action_thresholds: Default::default(),
             tx_code_path: PathBuf::from(TX_UPDATE_ACCOUNT_WASM),
             tx: self.tx_builder(),
         }
@@ -717,6 +759,7 @@ where
                 wallet_alias_force: false,
                 fee_amount: None,
                 wrapper_fee_payer: None,
+                fee_sponsor: None,
                 fee_token: native_token,
                 gas_limit: GasLimit::from(DEFAULT_GAS_LIMIT),
                 expiration: Default::default(),
@@ -1037,11 +1080,13 @@ pub mod testing {
             fee in arb_fee(),
             pk in arb_common_pk(),
             gas_limit in arb_gas_limit(),
+            fee_sponsor in option::of(arb_non_internal_address()),
         ) -> WrapperTx {
             WrapperTx {
                 fee,
                 pk,
                 gas_limit,
+                fee_sponsor,
             }
         }
     }
@@ -1497,9 +1542,14 @@ pub mod testing {
             Option<(ShieldedTransfer, HashMap<AssetData, u64>, StoredBuildParams)>,
         ) {
             if let Some((transfer, aux)) = transfer_aux {
-                (MsgTransfer { message, transfer: Some(transfer) }, aux)
+                let msg = MsgTransfer {
+                    message,
+                    transfer: Some(transfer),
+                    fee: None,
+                };
+                (msg, aux)
             } else {
-                (MsgTransfer { message, transfer: None }, None)
+                (MsgTransfer { message, transfer: None, fee: None }, None)
             }
         }
     }
@@ -6,8 +6,8 @@ use namada_state::{DBIter, StorageHasher, DB};
 pub use shell::Shell;
 use shell::SHELL;
 pub use types::{
-    EncodedResponseQuery, Error, RequestCtx, RequestQuery, ResponseQuery,
-    Router,
+    EncodedResponseQuery, Error, HistoricalPrefixIter, HistoricalState,
+    RequestCtx, RequestQuery, ResponseQuery, Router,
 };
 use vp::{Vp, VP};
 
@@ -68,6 +68,37 @@ macro_rules! handle_match {
         return Ok(result);
     };
 
+    // Handler function that reads the storage at the requested block height
+    // (`with_height`)
+    (
+        $ctx:ident, $request:ident, $start:ident, $end:ident,
+        (with_height $handle:tt), ( $( $matched_args:ident, )* ),
+    ) => {
+        // check that we're at the end of the path - trailing slash is optional
+        if !($end == $request.path.len() ||
+            // ignore trailing slashes
+            $end == $request.path.len() - 1 && &$request.path[$end..] == "/") {
+                // we're not at the end, no match
+                // println!("Not fully matched");
+                break
+        }
+        // Check that the request is not sent with unsupported non-default
+        $crate::queries::require_no_proof($request)?;
+        $crate::queries::require_no_data($request)?;
+
+        let state =
+            $crate::queries::HistoricalState::at_request_height(&$ctx, $request)?;
+        let data = $handle(&state, $( $matched_args ),* )?;
+        // Encode the returned data with borsh
+        let data = borsh::to_vec(&data).into_storage_result()?;
+        return Ok($crate::queries::EncodedResponseQuery {
+            data,
+            info: Default::default(),
+            proof: None,
+            height: state.height(),
+        });
+    };
+
     // Handler function that doesn't use the request, just the path args, if any
     (
         $ctx:ident, $request:ident, $start:ident, $end:ident,
@@ -486,6 +517,68 @@ macro_rules! pattern_and_handler_to_method {
         }
     };
 
+    // terminal rule for $handle that reads the storage at a block height
+    // (`with_height`)
+    (
+        ( $( $param:tt: $param_ty:ty ),* )
+        [ $( { $prefix:expr } ),* ]
+        $return_type:path,
+        (with_height $handle:tt),
+        ()
+    ) => {
+        // paste! used to construct the `fn $handle_path`'s name.
+        paste::paste! {
+            #[allow(dead_code)]
+            #[doc = "Get a path to query `" $handle "`."]
+            pub fn [<$handle _path>](&self, $( $param: &$param_ty ),* ) -> String {
+                itertools::join(
+                    [ Some(std::borrow::Cow::from(&self.prefix)), $( $prefix ),* ]
+                    .into_iter()
+                    .filter_map(|x| x), "/")
+            }
+
+            #[allow(dead_code)]
+            #[allow(clippy::too_many_arguments)]
+            #[doc = "Request a simple borsh-encoded value from `" $handle "`, \
+                at the last committed block height."]
+            pub async fn $handle<CLIENT>(&self, client: &CLIENT,
+                $( $param: &$param_ty ),*
+            )
+                -> std::result::Result<
+                    $return_type,
+                    <CLIENT as namada_io::Client>::Error
+                >
+                where CLIENT: namada_io::Client + std::marker::Sync {
+                    self.[<$handle _at_height>](client, None, $( $param ),* ).await
+            }
+
+            #[allow(dead_code)]
+            #[allow(clippy::too_many_arguments)]
+            #[doc = "Request a simple borsh-encoded value from `" $handle "`, \
+                optionally at the specified block height. Heights older than \
+                the last committed one are only supported by nodes running \
+                in archive mode."]
+            pub async fn [<$handle _at_height>]<CLIENT>(&self, client: &CLIENT,
+                height: Option<namada_core::chain::BlockHeight>,
+                $( $param: &$param_ty ),*
+            )
+                -> std::result::Result<
+                    $return_type,
+                    <CLIENT as namada_io::Client>::Error
+                >
+                where CLIENT: namada_io::Client + std::marker::Sync {
+                    let path = self.[<$handle _path>]( $( $param ),* );
+
+                    let $crate::queries::ResponseQuery { data, .. } =
+                        client.request(path, None, height, false).await?;
+
+                    let decoded: $return_type =
+                        borsh::BorshDeserialize::try_from_slice(&data[..])?;
+                    Ok(decoded)
+            }
+        }
+    };
+
     // terminal rule that $handle that doesn't use request
     (
         ( $( $param:tt: $param_ty:ty ),* )
@@ -761,6 +854,10 @@ macro_rules! router_type {
 ///   // necessary), which can have some `info` string and a proof.
 ///   ( "pattern_e" ) -> ReturnType = (with_options handler),
 ///
+///   // The handler reads the storage at the block height specified in the
+///   // request, if any. Past heights are only supported in archive mode.
+///   ( "pattern_f" ) -> ReturnType = (with_height handler),
+///
 ///   ( "another" / "pattern" / "that" / "goes" / "deep" ) -> ReturnType = handler,
 ///
 ///   // Inlined sub-tree
@@ -796,6 +893,16 @@ macro_rules! router_type {
 ///     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
 ///     H: 'static + StorageHasher + Sync;
 /// ```
+///
+/// If the handler supports reading the storage at past block heights, it can
+/// be defined as `(with_height $handler)` and then the expected signature is:
+/// ```rust,ignore
+/// fn handler<D, H>(state: &HistoricalState<'_, D, H>, args ...)
+///   -> namada_storage::Result<ReturnType>
+/// where
+///     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+///     H: 'static + StorageHasher + Sync;
+/// ```
 #[macro_export]
 macro_rules! router {
     { $name:ident, $( $pattern:tt $( -> $return_type:path )? = $handle:tt , )* } => (
@@ -862,7 +969,8 @@ mod test_rpc_handlers {
     use namada_state::{DBIter, StorageHasher, DB};
 
     use crate::queries::{
-        EncodedResponseQuery, RequestCtx, RequestQuery, ResponseQuery,
+        EncodedResponseQuery, HistoricalState, RequestCtx, RequestQuery,
+        ResponseQuery,
     };
 
     /// A little macro to generate boilerplate for RPC handler functions.
@@ -976,6 +1084,18 @@ mod test_rpc_handlers {
             ..ResponseQuery::default()
         })
     }
+
+    /// This handler is hand-written, because the test helper macro doesn't
+    /// support handlers with `with_height`.
+    pub fn d<D, H>(
+        state: &HistoricalState<'_, D, H>,
+    ) -> namada_storage::Result<String>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+    {
+        Ok(format!("d/{}", state.height()))
+    }
 }
 
 /// You can expand the `router!` macro invocation with e.g.:
@@ -1011,6 +1131,7 @@ mod test_rpc {
             },
         },
         ( "c" ) -> String = (with_options c),
+        ( "d" ) -> String = (with_height d),
     }
 
     router! {TEST_SUB_RPC,
@@ -1164,6 +1285,12 @@ mod test {
         let result = TEST_RPC.c(&client, None, None, false).await.unwrap();
         assert_eq!(result.data, format!("c"));
 
+        let result = TEST_RPC.d(&client).await.unwrap();
+        assert_eq!(
+            result,
+            format!("d/{}", client.state.in_mem().get_last_block_height())
+        );
+
         let result = TEST_RPC.test_sub_rpc().x(&client).await.unwrap();
         assert_eq!(result, format!("x"));
 
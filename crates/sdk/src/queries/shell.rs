@@ -7,7 +7,7 @@ use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
-use namada_account::{Account, AccountPublicKeysMap};
+use namada_account::Account;
 use namada_core::address::Address;
 use namada_core::arith::checked;
 use namada_core::chain::{BlockHeader, BlockHeight, Epoch};
@@ -20,6 +20,7 @@ use namada_core::time::DurationSecs;
 use namada_core::token::{Denomination, MaspDigitPos};
 use namada_core::uint::Uint;
 use namada_ibc::event::IbcEventType;
+use namada_ibc::rate_limit::{RateLimitFlow, RateLimitQuota};
 use namada_state::{DBIter, LastBlock, StateRead, StorageHasher, DB};
 use namada_storage::{ResultExt, StorageRead};
 use namada_token::masp::MaspTokenRewardData;
@@ -33,7 +34,7 @@ use crate::ibc::core::host::types::identifiers::{
     ChannelId, ClientId, PortId, Sequence,
 };
 use crate::queries::types::{RequestCtx, RequestQuery};
-use crate::queries::{require_latest_height, EncodedResponseQuery};
+use crate::queries::{EncodedResponseQuery, HistoricalState};
 use crate::tendermint::merkle::proof::ProofOps;
 
 type ConversionWithoutPath = (
@@ -119,6 +120,9 @@ router! {SHELL,
     // IBC packet event
     ( "ibc_packet" / [event_type: IbcEventType] / [source_port: PortId] / [source_channel: ChannelId] / [destination_port: PortId] / [destination_channel: ChannelId] / [sequence: Sequence]) -> Option<Event> = ibc_packet,
 
+    // IBC rolling-window rate limits and the remaining quotas of a token
+    ( "ibc_rate_limit" / [token: Address] / [channel_id: opt ChannelId] ) -> RateLimitQuota = ibc_rate_limit,
+
     // Get the block header associated with the requested height
     ( "block_header" / [height: BlockHeight] ) -> Option<BlockHeader> = block_header,
 
@@ -450,7 +454,17 @@ where
         }
     };
 
-    if let Some(past_height_limit) = ctx.storage_read_past_height_limit {
+    // Nodes in archive mode keep the history of all the heights since the
+    // archive start, so the past height limit doesn't apply to those
+    let is_archived = ctx
+        .state
+        .db()
+        .archive_start_height()
+        .is_some_and(|start| queried_height >= start);
+    if let Some(past_height_limit) = ctx
+        .storage_read_past_height_limit
+        .filter(|_| !is_archived)
+    {
         if checked!(queried_height + past_height_limit)? < last_committed_height
         {
             return Err(namada_storage::Error::new(std::io::Error::new(
@@ -515,9 +529,10 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    require_latest_height(&ctx, request)?;
+    let state = HistoricalState::at_request_height(&ctx, request)?;
+    let queried_height = state.height();
 
-    let iter = namada_storage::iter_prefix_bytes(ctx.state, &storage_key)?;
+    let iter = namada_storage::iter_prefix_bytes(&state, &storage_key)?;
     let data: namada_storage::Result<Vec<PrefixValue>> = iter
         .map(|iter_result| {
             let (key, value) = iter_result?;
@@ -525,16 +540,6 @@ where
         })
         .collect();
     let data = data?;
-    let queried_height = {
-        let height: BlockHeight = request.height.into();
-        let is_last_height_query = height.0 == 0;
-
-        if hints::likely(is_last_height_query) {
-            ctx.state.in_mem().get_last_block_height()
-        } else {
-            height
-        }
-    };
     let proof = if request.prove {
         let mut ops = vec![];
         for PrefixValue { key, value } in &data {
@@ -623,6 +628,36 @@ where
     Ok(ctx.event_log.with_matcher(matcher).iter().next().cloned())
 }
 
+fn ibc_rate_limit<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    token: Address,
+    channel_id: Option<ChannelId>,
+) -> namada_storage::Result<RateLimitQuota>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let limits = namada_ibc::storage::get_rate_limits(
+        ctx.state,
+        &token,
+        channel_id.as_ref(),
+    )?;
+    let key =
+        namada_ibc::storage::rate_limit_flow_key(&token, channel_id.as_ref());
+    let flow: RateLimitFlow = ctx.state.read(&key)?.unwrap_or_default();
+    // The quotas are estimated at the last block time
+    let now = ctx
+        .state
+        .in_mem()
+        .last_block
+        .as_ref()
+        .map(|last_block| last_block.time.to_unix_timestamp())
+        .and_then(|timestamp| u64::try_from(timestamp).ok())
+        .unwrap_or(flow.updated_at);
+    let remaining = flow.remaining(&limits, now);
+    Ok(RateLimitQuota { limits, remaining })
+}
+
 fn account<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     owner: Address,
@@ -634,13 +669,17 @@ where
     let account_exists = namada_account::exists(ctx.state, &owner)?;
 
     if account_exists {
-        let public_keys = namada_account::public_keys(ctx.state, &owner)?;
+        let public_keys_map =
+            namada_account::public_keys_index_map(ctx.state, &owner)?;
         let threshold = namada_account::threshold(ctx.state, &owner)?;
+        let action_thresholds =
+            namada_account::action_thresholds(ctx.state, &owner)?;
 
         Ok(Some(Account {
-            public_keys_map: AccountPublicKeysMap::from_iter(public_keys),
+            public_keys_map,
             address: owner,
             threshold: threshold.unwrap_or(1),
+            action_thresholds,
         }))
     } else {
         Ok(None)
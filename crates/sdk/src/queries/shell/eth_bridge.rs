@@ -10,7 +10,9 @@ use namada_core::arith::checked;
 use namada_core::chain::{BlockHeight, Epoch};
 use namada_core::collections::{HashMap, HashSet};
 use namada_core::eth_abi::{Encode, EncodeCell};
-use namada_core::eth_bridge_pool::{PendingTransfer, PendingTransferAppendix};
+use namada_core::eth_bridge_pool::{
+    GasFeeQuote, PendingTransfer, PendingTransferAppendix,
+};
 use namada_core::ethereum_events::{
     EthAddress, EthereumEvent, TransferToEthereum,
 };
@@ -23,7 +25,7 @@ use namada_ethereum_bridge::event::{BpTransferStatus, BridgePoolTxHash};
 use namada_ethereum_bridge::protocol::transactions::votes::{
     EpochedVotingPower, EpochedVotingPowerExt,
 };
-use namada_ethereum_bridge::storage::bridge_pool::get_key_from_hash;
+use namada_ethereum_bridge::storage::bridge_pool::{self, get_key_from_hash};
 use namada_ethereum_bridge::storage::eth_bridge_queries::EthBridgeQueries;
 use namada_ethereum_bridge::storage::parameters::UpgradeableContract;
 use namada_ethereum_bridge::storage::proof::{sort_sigs, EthereumProof};
@@ -190,6 +192,11 @@ router! {ETH_BRIDGE,
     ( "pool" / "transfer_status" )
         -> TransferToEthereumStatus = (with_options pending_eth_transfer_status),
 
+    // Get a quote of the minimum gas fee required to add a transfer
+    // to the Ethereum bridge pool, paying fees in the given token.
+    ( "pool" / "fee_quote" / [token: Address] )
+        -> GasFeeQuote = read_gas_fee_quote,
+
     // Request a proof of a validator set signed off for
     // the given epoch.
     //
@@ -410,6 +417,19 @@ where
     read_contract(&native_erc20_key(), ctx)
 }
 
+/// Get a quote of the minimum gas fee required to add a
+/// transfer to the Ethereum bridge pool.
+fn read_gas_fee_quote<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    token: Address,
+) -> namada_storage::Result<GasFeeQuote>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    bridge_pool::read_gas_fee_quote(ctx.state, &token)
+}
+
 /// Read the current contents of the Ethereum bridge
 /// pool.
 fn read_ethereum_bridge_pool<D, H, V, T>(
@@ -848,7 +868,8 @@ mod test_ethbridge_router {
     use namada_core::voting_power::EthBridgeVotingPower;
     use namada_ethereum_bridge::protocol::transactions::validator_set_update::aggregate_votes;
     use namada_ethereum_bridge::storage::bridge_pool::{
-        get_pending_key, get_signed_root_key, BridgePoolTree,
+        get_pending_key, get_relay_cost_key, get_signed_root_key,
+        BridgePoolTree,
     };
     use namada_ethereum_bridge::storage::proof::BridgePoolRootProof;
     use namada_ethereum_bridge::storage::whitelist;
@@ -1092,6 +1113,32 @@ mod test_ethbridge_router {
         assert_eq!(pool, Vec::from([transfer]));
     }
 
+    /// Test that reading the gas fee quote of a token works
+    #[tokio::test]
+    async fn test_read_gas_fee_quote() {
+        let mut client = TestClient::new(RPC);
+        client
+            .state
+            .write(&get_relay_cost_key(&nam()), Amount::from(10))
+            .expect("Test failed");
+        client.state.commit_block().expect("Test failed");
+
+        let quote = RPC
+            .shell()
+            .eth_bridge()
+            .read_gas_fee_quote(&client, &nam())
+            .await
+            .unwrap();
+        assert_eq!(
+            quote,
+            GasFeeQuote {
+                token: nam(),
+                relay_cost: Amount::from(10),
+                min_fee: Amount::from(5),
+            }
+        );
+    }
+
     /// Test that reading the bridge pool always gets
     /// the latest pool
     #[tokio::test]
@@ -1,7 +1,11 @@
 use std::fmt::Debug;
 
+use namada_core::address::Address;
+use namada_core::chain::{BlockHeader, BlockHeight, ChainId, Epoch, Epochs};
+use namada_core::storage::{self, TxIndex};
 pub use namada_io::client::{EncodedResponseQuery, Error, ResponseQuery};
-use namada_state::{DBIter, StorageHasher, WlState, DB};
+use namada_state::{DBIter, PrefixIter, StateRead, StorageHasher, WlState, DB};
+use namada_storage::{ResultExt, StorageRead};
 
 use crate::events::log::EventLog;
 pub use crate::tendermint::abci::request::Query as RequestQuery;
@@ -65,3 +69,189 @@ pub trait Router {
         D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
         H: 'static + StorageHasher + Sync;
 }
+
+/// Read-only view of the ledger's storage at some block height, for queries
+/// that support arbitrary heights.
+///
+/// Reads at the last committed height are served by the [`WlState`], while
+/// reads at past heights are served from the history of the storage, which
+/// is only kept when the node runs in archive mode.
+#[derive(Debug)]
+pub struct HistoricalState<'shell, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    state: &'shell WlState<D, H>,
+    height: BlockHeight,
+    is_latest: bool,
+}
+
+impl<'shell, D, H> HistoricalState<'shell, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    /// Get a view of the storage at the height of the given request, where
+    /// `0` stands for the last committed height.
+    pub fn at_request_height<V, T>(
+        ctx: &RequestCtx<'shell, D, H, V, T>,
+        request: &RequestQuery,
+    ) -> namada_storage::Result<Self> {
+        let last_committed_height = ctx.state.in_mem().get_last_block_height();
+        let height = match request.height.value() {
+            0 => last_committed_height,
+            height => BlockHeight(height),
+        };
+
+        if height == last_committed_height {
+            return Ok(Self {
+                state: ctx.state,
+                height,
+                is_latest: true,
+            });
+        }
+        if height > last_committed_height {
+            return Err(namada_storage::Error::new_alloc(format!(
+                "Cannot query height {height}, which is past the last \
+                 committed height {last_committed_height}"
+            )));
+        }
+        let is_archived = ctx
+            .state
+            .db()
+            .archive_start_height()
+            .is_some_and(|start| height >= start);
+        if !is_archived {
+            return Err(namada_storage::Error::new_alloc(format!(
+                "The storage at height {height} is not available. Querying \
+                 past heights requires a node running in archive mode \
+                 (configured via `shell.archive_mode`)."
+            )));
+        }
+        Ok(Self {
+            state: ctx.state,
+            height,
+            is_latest: false,
+        })
+    }
+
+    /// The height at which the storage is read.
+    pub fn height(&self) -> BlockHeight {
+        self.height
+    }
+}
+
+/// Prefix iterator of a [`HistoricalState`].
+pub enum HistoricalPrefixIter<'iter, D>
+where
+    D: 'static + DB + for<'it> DBIter<'it>,
+{
+    /// Iterator over the latest storage.
+    Latest(PrefixIter<'iter, D>),
+    /// Iterator over the key-vals at a past height.
+    Past(std::vec::IntoIter<(String, Vec<u8>)>),
+}
+
+impl<D, H> StorageRead for HistoricalState<'_, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    type PrefixIter<'iter>
+        = HistoricalPrefixIter<'iter, D>
+    where
+        Self: 'iter;
+
+    fn read_bytes(
+        &self,
+        key: &storage::Key,
+    ) -> namada_storage::Result<Option<Vec<u8>>> {
+        if self.is_latest {
+            return self.state.read_bytes(key);
+        }
+        let (value, _gas) = self
+            .state
+            .db_read_with_height(key, self.height)
+            .into_storage_result()?;
+        Ok(value)
+    }
+
+    fn has_key(&self, key: &storage::Key) -> namada_storage::Result<bool> {
+        if self.is_latest {
+            return self.state.has_key(key);
+        }
+        Ok(self.read_bytes(key)?.is_some())
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+    ) -> namada_storage::Result<Self::PrefixIter<'iter>> {
+        if self.is_latest {
+            return Ok(HistoricalPrefixIter::Latest(
+                self.state.iter_prefix(prefix)?,
+            ));
+        }
+        let key_vals = self
+            .state
+            .db()
+            .read_subspace_prefix_with_height(prefix, self.height)
+            .into_storage_result()?;
+        Ok(HistoricalPrefixIter::Past(key_vals.into_iter()))
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> namada_storage::Result<Option<(String, Vec<u8>)>> {
+        match iter {
+            HistoricalPrefixIter::Latest(iter) => self.state.iter_next(iter),
+            HistoricalPrefixIter::Past(iter) => Ok(iter.next()),
+        }
+    }
+
+    fn get_chain_id(&self) -> namada_storage::Result<ChainId> {
+        self.state.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> namada_storage::Result<BlockHeight> {
+        Ok(self.height)
+    }
+
+    fn get_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> namada_storage::Result<Option<BlockHeader>> {
+        StorageRead::get_block_header(self.state, height)
+    }
+
+    fn get_block_epoch(&self) -> namada_storage::Result<Epoch> {
+        if self.is_latest {
+            return Ok(self.state.in_mem().last_epoch);
+        }
+        self.state
+            .in_mem()
+            .block
+            .pred_epochs
+            .get_epoch(self.height)
+            .ok_or_else(|| {
+                namada_storage::Error::new_alloc(format!(
+                    "No epoch found for height {}",
+                    self.height
+                ))
+            })
+    }
+
+    fn get_pred_epochs(&self) -> namada_storage::Result<Epochs> {
+        self.state.get_pred_epochs()
+    }
+
+    fn get_tx_index(&self) -> namada_storage::Result<TxIndex> {
+        self.state.get_tx_index()
+    }
+
+    fn get_native_token(&self) -> namada_storage::Result<Address> {
+        self.state.get_native_token()
+    }
+}
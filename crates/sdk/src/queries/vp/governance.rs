@@ -1,7 +1,11 @@
 // cd namada && cargo expand ledger::queries::vp::governance
 
-use namada_governance::parameters::GovernanceParameters;
-use namada_governance::storage::proposal::StorageProposal;
+use namada_core::address::Address;
+use namada_governance::parameters::{GovernanceParameters, VoteLockParams};
+use namada_governance::storage::proposal::{
+    StorageProposal, UpgradePlanStatus,
+};
+use namada_governance::storage::vote::VoteLock;
 use namada_governance::utils::{ProposalResult, Vote};
 use namada_state::{DBIter, StorageHasher, DB};
 
@@ -13,6 +17,10 @@ router! {GOV,
     ( "proposal" / [id: u64 ] / "votes" ) -> Vec<Vote> = proposal_id_votes,
     ( "parameters" ) -> GovernanceParameters = parameters,
     ( "stored_proposal_result" / [id: u64] ) -> Option<ProposalResult> = proposal_result,
+    ( "upgrade_plan" ) -> Option<UpgradePlanStatus> = upgrade_plan,
+    ( "vote_delegate" / [delegator: Address] ) -> Option<Address> = vote_delegate,
+    ( "vote_lock" / [owner: Address] ) -> Option<VoteLock> = vote_lock,
+    ( "vote_lock_params" ) -> Option<VoteLockParams> = vote_lock_params,
 }
 
 /// Query the provided proposal id
@@ -61,3 +69,54 @@ where
 {
     namada_governance::storage::get_proposal_result(ctx.state, id)
 }
+
+/// Get the scheduled upgrade plan with its status
+fn upgrade_plan<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Option<UpgradePlanStatus>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let last_committed_height = ctx.state.in_mem().get_last_block_height();
+    let plan = namada_governance::storage::get_upgrade_plan(ctx.state)?;
+    Ok(plan.map(|plan| UpgradePlanStatus {
+        status: plan.status(last_committed_height),
+        plan,
+    }))
+}
+
+/// Get the address the delegator delegated its vote to, if any
+fn vote_delegate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    delegator: Address,
+) -> namada_storage::Result<Option<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_governance::storage::read_vote_delegate(ctx.state, &delegator)
+}
+
+/// Get the locked voting tokens of the owner, if any
+fn vote_lock<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> namada_storage::Result<Option<VoteLock>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_governance::storage::read_vote_lock(ctx.state, &owner)
+}
+
+/// Get the lock-weighted voting parameters, if enabled
+fn vote_lock_params<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Option<VoteLockParams>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_governance::storage::get_vote_lock_params(ctx.state)
+}
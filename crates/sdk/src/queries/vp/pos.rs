@@ -9,6 +9,7 @@ use namada_core::chain::Epoch;
 use namada_core::collections::{HashMap, HashSet};
 use namada_core::key::{common, tm_consensus_key_raw_hash};
 use namada_core::token;
+use namada_proof_of_stake::liquid_shares::query_liquid_share_value;
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::queries::{
     find_delegation_validators, find_delegations,
@@ -36,12 +37,13 @@ use namada_proof_of_stake::types::{
     LivenessInfo, Slash, ValidatorLiveness, ValidatorMetaData,
     WeightedValidator,
 };
-use namada_proof_of_stake::{bond_amount, query_reward_tokens};
+use namada_proof_of_stake::{bond_amount, is_auto_compound, query_reward_tokens};
 use namada_state::{DBIter, KeySeg, StorageHasher, DB};
 use namada_storage::collections::lazy_map;
-use namada_storage::OptionExt;
+use namada_storage::{OptionExt, StorageRead};
 
 use crate::governance;
+use crate::queries::HistoricalState;
 use crate::queries::types::RequestCtx;
 
 // PoS validity predicate queries
@@ -57,7 +59,7 @@ router! {POS,
         ( "liveness_info" ) -> LivenessInfo = liveness_info,
 
         ( "stake" / [validator: Address] / [epoch: opt Epoch] )
-            -> Option<token::Amount> = validator_stake,
+            -> Option<token::Amount> = (with_height validator_stake),
 
         ( "slashes" / [validator: Address] )
             -> Vec<Slash> = validator_slashes,
@@ -69,7 +71,7 @@ router! {POS,
             -> Option<ValidatorMetaData> = validator_metadata,
 
         ( "state" / [validator: Address] / [epoch: opt Epoch] )
-            -> ValidatorStateInfo = validator_state,
+            -> ValidatorStateInfo = (with_height validator_state),
 
         ( "incoming_redelegation" / [src_validator: Address] / [delegator: Address] )
             -> Option<Epoch> = validator_incoming_redelegation,
@@ -89,28 +91,34 @@ router! {POS,
     ( "pos_params") -> PosParams = pos_params,
 
     ( "total_stake" / [epoch: opt Epoch] )
-        -> token::Amount = total_stake,
+        -> token::Amount = (with_height total_stake),
 
     ( "total_active_voting_power" / [epoch: opt Epoch] )
-        -> token::Amount = total_active_voting_power,
+        -> token::Amount = (with_height total_active_voting_power),
 
     ( "delegations" / [owner: Address] / [epoch: opt Epoch] )
         -> HashSet<Address> = delegation_validators,
 
     ( "delegations_at" / [owner: Address] / [epoch: opt Epoch] )
-        -> HashMap<Address, token::Amount> = delegations,
+        -> HashMap<Address, token::Amount> = (with_height delegations),
 
     ( "bond_deltas" / [source: Address] / [validator: Address] )
         -> HashMap<Epoch, token::Change> = bond_deltas,
 
     ( "bond" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
-        -> token::Amount = bond,
+        -> token::Amount = (with_height bond),
 
     ( "rewards" / [validator: Address] / [source: opt Address] )
         -> token::Amount = rewards,
 
+    ( "auto_compound" / [validator: Address] / [source: opt Address] )
+        -> bool = auto_compound,
+
+    ( "liquid_share_value" / [share_token: Address] / [shares: token::Amount] )
+        -> Option<token::Amount> = liquid_share_value,
+
     ( "bond_with_slashing" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
-        -> token::Amount = bond_with_slashing,
+        -> token::Amount = (with_height bond_with_slashing),
 
     ( "unbond" / [source: Address] / [validator: Address] )
         -> HashMap<(Epoch, Epoch), token::Amount> = unbond,
@@ -347,8 +355,8 @@ where
 }
 
 /// Get the validator state
-fn validator_state<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn validator_state<D, H>(
+    state: &HistoricalState<'_, D, H>,
     validator: Address,
     epoch: Option<Epoch>,
 ) -> namada_storage::Result<ValidatorStateInfo>
@@ -356,12 +364,15 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
-    let state = namada_proof_of_stake::storage::read_validator_state::<
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
+    let validator_state = namada_proof_of_stake::storage::read_validator_state::<
         _,
         governance::Store<_>,
-    >(ctx.state, &validator, epoch)?;
-    Ok((state, epoch))
+    >(state, &validator, epoch)?;
+    Ok((validator_state, epoch))
 }
 
 /// Get the validator state
@@ -381,8 +392,8 @@ where
 /// to their address.
 /// Returns `None` when the given address is not a validator address. For a
 /// validator with `0` stake, this returns `Ok(token::Amount::zero())`.
-fn validator_stake<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn validator_stake<D, H>(
+    state: &HistoricalState<'_, D, H>,
     validator: Address,
     epoch: Option<Epoch>,
 ) -> namada_storage::Result<Option<token::Amount>>
@@ -390,11 +401,13 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
-    let params = read_pos_params::<_, governance::Store<_>>(ctx.state)?;
-    if namada_proof_of_stake::is_validator(ctx.state, &validator)? {
-        let stake =
-            read_validator_stake(ctx.state, &params, &validator, epoch)?;
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
+    let params = read_pos_params::<_, governance::Store<_>>(state)?;
+    if namada_proof_of_stake::is_validator(state, &validator)? {
+        let stake = read_validator_stake(state, &params, &validator, epoch)?;
         Ok(Some(stake))
     } else {
         Ok(None)
@@ -443,32 +456,38 @@ where
 }
 
 /// Get the total stake in PoS system at the given epoch or current when `None`.
-fn total_stake<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn total_stake<D, H>(
+    state: &HistoricalState<'_, D, H>,
     epoch: Option<Epoch>,
 ) -> namada_storage::Result<token::Amount>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
-    let params = read_pos_params::<_, governance::Store<_>>(ctx.state)?;
-    read_total_stake(ctx.state, &params, epoch)
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
+    let params = read_pos_params::<_, governance::Store<_>>(state)?;
+    read_total_stake(state, &params, epoch)
 }
 
 /// Get the total active voting power in PoS system at the given epoch or
 /// current when `None`.
-fn total_active_voting_power<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn total_active_voting_power<D, H>(
+    state: &HistoricalState<'_, D, H>,
     epoch: Option<Epoch>,
 ) -> namada_storage::Result<token::Amount>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
-    let params = read_pos_params::<_, governance::Store<_>>(ctx.state)?;
-    read_total_active_stake(ctx.state, &params, epoch)
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
+    let params = read_pos_params::<_, governance::Store<_>>(state)?;
+    read_total_active_stake(state, &params, epoch)
 }
 
 fn bond_deltas<D, H, V, T>(
@@ -485,8 +504,8 @@ where
 
 /// Find the sum of bond amount up the given epoch when `Some`, or up to the
 /// pipeline length parameter offset otherwise
-fn bond<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn bond<D, H>(
+    state: &HistoricalState<'_, D, H>,
     source: Address,
     validator: Address,
     epoch: Option<Epoch>,
@@ -495,22 +514,18 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let params = read_pos_params::<_, governance::Store<_>>(ctx.state)?;
-    let epoch = epoch.unwrap_or(
-        ctx.state
-            .in_mem()
-            .last_epoch
-            .unchecked_add(params.pipeline_len),
-    );
+    let params = read_pos_params::<_, governance::Store<_>>(state)?;
+    let epoch = epoch
+        .unwrap_or(state.get_block_epoch()?.unchecked_add(params.pipeline_len));
 
     let handle = bond_handle(&source, &validator);
     handle
-        .get_sum(ctx.state, epoch, &params)?
+        .get_sum(state, epoch, &params)?
         .ok_or_err_msg("Cannot find bond")
 }
 
-fn bond_with_slashing<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn bond_with_slashing<D, H>(
+    state: &HistoricalState<'_, D, H>,
     source: Address,
     validator: Address,
     epoch: Option<Epoch>,
@@ -519,10 +534,13 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
     let bond_id = BondId { source, validator };
 
-    bond_amount::<_, governance::Store<_>>(ctx.state, &bond_id, epoch)
+    bond_amount::<_, governance::Store<_>>(state, &bond_id, epoch)
 }
 
 fn unbond<D, H, V, T>(
@@ -623,6 +641,36 @@ where
     )
 }
 
+fn auto_compound<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    source: Option<Address>,
+) -> namada_storage::Result<bool>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    is_auto_compound(ctx.state, source.as_ref(), &validator)
+}
+
+fn liquid_share_value<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    share_token: Address,
+    shares: token::Amount,
+) -> namada_storage::Result<Option<token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    query_liquid_share_value::<_, governance::Store<_>>(
+        ctx.state,
+        &share_token,
+        shares,
+        current_epoch,
+    )
+}
+
 fn bonds_and_unbonds<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     source: Option<Address>,
@@ -654,8 +702,8 @@ where
 
 /// Find all the validator addresses to whom the given `owner` address has
 /// some delegation in any epoch
-fn delegations<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn delegations<D, H>(
+    state: &HistoricalState<'_, D, H>,
     owner: Address,
     epoch: Option<Epoch>,
 ) -> namada_storage::Result<HashMap<Address, token::Amount>>
@@ -663,8 +711,11 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch: Epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
-    find_delegations::<_, governance::Store<_>>(ctx.state, &owner, &epoch)
+    let epoch: Epoch = match epoch {
+        Some(epoch) => epoch,
+        None => state.get_block_epoch()?,
+    };
+    find_delegations::<_, governance::Store<_>>(state, &owner, &epoch)
 }
 
 /// Validator slashes
@@ -1,56 +1,130 @@
 //! Token validity predicate queries
 
+use std::collections::BTreeMap;
+
 use namada_core::address::Address;
 use namada_core::token;
 use namada_proof_of_stake::rewards::estimate_staking_reward_rate;
 use namada_state::{DBIter, StorageHasher, DB};
 use namada_token::{
-    get_effective_total_native_supply, read_denom, read_total_supply, Dec,
+    get_effective_total_native_supply, read_denom, read_fee_sponsorship,
+    read_locked_amount, read_scheduled_transfers, read_total_supply,
+    read_vested_amount, read_vesting_schedule, Dec,
 };
 
-use crate::queries::RequestCtx;
+use crate::queries::{HistoricalState, RequestCtx};
 
 router! {TOKEN,
-    ( "denomination" / [token: Address] ) -> Option<token::Denomination> = denomination,
-    ( "total_supply" / [token: Address] ) -> token::Amount = total_supply,
-    ( "effective_native_supply" ) -> token::Amount = effective_native_supply,
+    ( "denomination" / [token: Address] ) -> Option<token::Denomination> = (with_height denomination),
+    ( "total_supply" / [token: Address] ) -> token::Amount = (with_height total_supply),
+    ( "effective_native_supply" ) -> token::Amount = (with_height effective_native_supply),
     ( "staking_rewards_rate" ) -> Dec = staking_rewards_rate,
+    ( "vesting_schedule" / [token: Address] / [owner: Address] ) -> Option<token::VestingSchedule> = (with_height vesting_schedule),
+    ( "vested" / [token: Address] / [owner: Address] ) -> Option<token::Amount> = (with_height vested_amount),
+    ( "unvested" / [token: Address] / [owner: Address] ) -> token::Amount = (with_height unvested_amount),
+    ( "fee_sponsorship" / [sponsor: Address] ) -> Option<token::FeeSponsorship> = (with_height fee_sponsorship),
+    ( "scheduled_transfers" / [source: Address] ) -> BTreeMap<u64, token::ScheduledTransfer> = (with_height scheduled_transfers),
 }
 
 /// Get the number of decimal places (in base 10) for a
 /// token specified by `addr`.
-fn denomination<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn denomination<D, H>(
+    state: &HistoricalState<'_, D, H>,
     token: Address,
 ) -> namada_storage::Result<Option<token::Denomination>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    read_denom(ctx.state, &token)
+    read_denom(state, &token)
 }
 
 /// Get the total supply for a token address
-fn total_supply<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn total_supply<D, H>(
+    state: &HistoricalState<'_, D, H>,
     token: Address,
 ) -> namada_storage::Result<token::Amount>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    read_total_supply(ctx.state, &token)
+    read_total_supply(state, &token)
 }
 
 /// Get the effective total supply of the native token
-fn effective_native_supply<D, H, V, T>(
-    ctx: RequestCtx<'_, D, H, V, T>,
+fn effective_native_supply<D, H>(
+    state: &HistoricalState<'_, D, H>,
+) -> namada_storage::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    get_effective_total_native_supply(state)
+}
+
+/// Get the vesting schedule of the given token and owner, if any
+fn vesting_schedule<D, H>(
+    state: &HistoricalState<'_, D, H>,
+    token: Address,
+    owner: Address,
+) -> namada_storage::Result<Option<token::VestingSchedule>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_vesting_schedule(state, &token, &owner)
+}
+
+/// Get the amount of the given token and owner that has vested, if the
+/// balance is subject to a vesting schedule
+fn vested_amount<D, H>(
+    state: &HistoricalState<'_, D, H>,
+    token: Address,
+    owner: Address,
+) -> namada_storage::Result<Option<token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_vested_amount(state, &token, &owner)
+}
+
+/// Get the amount of the given token and owner that is still locked by a
+/// vesting schedule
+fn unvested_amount<D, H>(
+    state: &HistoricalState<'_, D, H>,
+    token: Address,
+    owner: Address,
 ) -> namada_storage::Result<token::Amount>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    get_effective_total_native_supply(ctx.state)
+    read_locked_amount(state, &token, &owner)
+}
+
+/// Get the fee sponsorship of the given sponsor, if any
+fn fee_sponsorship<D, H>(
+    state: &HistoricalState<'_, D, H>,
+    sponsor: Address,
+) -> namada_storage::Result<Option<token::FeeSponsorship>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_fee_sponsorship(state, &sponsor)
+}
+
+/// Get the scheduled transfers of the given source, keyed by their ids
+fn scheduled_transfers<D, H>(
+    state: &HistoricalState<'_, D, H>,
+    source: Address,
+) -> namada_storage::Result<BTreeMap<u64, token::ScheduledTransfer>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_scheduled_transfers(state, &source)
 }
 
 /// Get the effective total supply of the native token
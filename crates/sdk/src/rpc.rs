@@ -27,13 +27,17 @@ use namada_core::token::{
 use namada_core::{storage, token};
 use namada_gas::event::GasUsed as GasUsedAttr;
 use namada_gas::WholeGas;
-use namada_governance::parameters::GovernanceParameters;
+use namada_governance::parameters::{GovernanceParameters, VoteLockParams};
 use namada_governance::pgf::parameters::PgfParameters;
 use namada_governance::pgf::storage::steward::StewardDetail;
-use namada_governance::storage::proposal::StorageProposal;
+use namada_governance::storage::proposal::{
+    StorageProposal, UpgradePlanStatus,
+};
+use namada_governance::storage::vote::VoteLock;
 use namada_governance::utils::{
     compute_proposal_result, ProposalResult, ProposalVotes, Vote,
 };
+use namada_ibc::rate_limit::RateLimitQuota;
 use namada_ibc::storage::{
     ibc_trace_key, ibc_trace_key_prefix, is_ibc_trace_key,
 };
@@ -55,6 +59,7 @@ use crate::args::InputAmount;
 use crate::control_flow::time;
 use crate::error::{EncodingError, Error, QueryError, TxSubmitError};
 use crate::events::{extend, Event};
+use crate::ibc::core::host::types::identifiers::ChannelId;
 use crate::internal_macros::echo_error;
 use crate::queries::vp::pos::{
     EnrichedBondsAndUnbondsDetails, ValidatorStateInfo,
@@ -229,6 +234,64 @@ pub async fn get_token_total_supply<C: namada_io::Client + Sync>(
     convert_response::<C, _>(RPC.vp().token().total_supply(client, token).await)
 }
 
+/// Query the vesting schedule of the owner's token balance, if any.
+pub async fn get_vesting_schedule<C: namada_io::Client + Sync>(
+    client: &C,
+    token: &Address,
+    owner: &Address,
+) -> Result<Option<token::VestingSchedule>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .token()
+            .vesting_schedule(client, token, owner)
+            .await,
+    )
+}
+
+/// Query the vested amount of the owner's token balance. Returns `None` if
+/// the balance is not subject to a vesting schedule.
+pub async fn get_vested_amount<C: namada_io::Client + Sync>(
+    client: &C,
+    token: &Address,
+    owner: &Address,
+) -> Result<Option<token::Amount>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().vested_amount(client, token, owner).await,
+    )
+}
+
+/// Query the amount of the owner's token balance that is still locked by a
+/// vesting schedule.
+pub async fn get_unvested_amount<C: namada_io::Client + Sync>(
+    client: &C,
+    token: &Address,
+    owner: &Address,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().unvested_amount(client, token, owner).await,
+    )
+}
+
+/// Query the fee sponsorship of the sponsor, if any.
+pub async fn get_fee_sponsorship<C: namada_io::Client + Sync>(
+    client: &C,
+    sponsor: &Address,
+) -> Result<Option<token::FeeSponsorship>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().fee_sponsorship(client, sponsor).await,
+    )
+}
+
+/// Query the scheduled transfers of the source, keyed by their ids.
+pub async fn get_scheduled_transfers<C: namada_io::Client + Sync>(
+    client: &C,
+    source: &Address,
+) -> Result<BTreeMap<u64, token::ScheduledTransfer>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().scheduled_transfers(client, source).await,
+    )
+}
+
 /// Query the effective total supply of the native token
 pub async fn get_effective_native_supply<C: Client + Sync>(
     client: &C,
@@ -1139,6 +1202,38 @@ pub async fn query_governance_parameters<C: namada_io::Client + Sync>(
     unwrap_client_response::<C, _>(RPC.vp().gov().parameters(client).await)
 }
 
+/// Get the upgrade plan scheduled by governance with its status, if any
+pub async fn query_upgrade_plan<C: namada_io::Client + Sync>(
+    client: &C,
+) -> Result<Option<UpgradePlanStatus>, error::Error> {
+    convert_response::<C, _>(RPC.vp().gov().upgrade_plan(client).await)
+}
+
+/// Get the address the delegator delegated its governance vote to, if any
+pub async fn query_vote_delegate<C: namada_io::Client + Sync>(
+    client: &C,
+    delegator: &Address,
+) -> Result<Option<Address>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().gov().vote_delegate(client, delegator).await,
+    )
+}
+
+/// Get the locked voting tokens of the owner, if any
+pub async fn query_vote_lock<C: namada_io::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<Option<VoteLock>, error::Error> {
+    convert_response::<C, _>(RPC.vp().gov().vote_lock(client, owner).await)
+}
+
+/// Get the lock-weighted voting parameters, if enabled
+pub async fn query_vote_lock_params<C: namada_io::Client + Sync>(
+    client: &C,
+) -> Result<Option<VoteLockParams>, error::Error> {
+    convert_response::<C, _>(RPC.vp().gov().vote_lock_params(client).await)
+}
+
 /// Get the public good fundings parameters
 pub async fn query_pgf_parameters<C: namada_io::Client + Sync>(
     client: &C,
@@ -1189,6 +1284,36 @@ pub async fn get_bond_amount_at<C: namada_io::Client + Sync>(
     Ok(total_active)
 }
 
+/// Check if the rewards of a bond are automatically compounded.
+pub async fn is_auto_compound<C: namada_io::Client + Sync>(
+    client: &C,
+    source: &Option<Address>,
+    validator: &Address,
+) -> Result<bool, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .auto_compound(client, validator, source)
+            .await,
+    )
+}
+
+/// Get the value of the given amount of liquid staking shares in bonded tokens
+/// of the backing validator. Returns `None` if the token is not a liquid
+/// staking share token.
+pub async fn get_liquid_share_value<C: namada_io::Client + Sync>(
+    client: &C,
+    share_token: &Address,
+    shares: token::Amount,
+) -> Result<Option<token::Amount>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .liquid_share_value(client, share_token, &shares)
+            .await,
+    )
+}
+
 /// Get bonds and unbonds with all details (slashes and rewards, if any)
 /// grouped by their bond IDs.
 pub async fn bonds_and_unbonds<C: namada_io::Client + Sync>(
@@ -1436,6 +1561,18 @@ pub async fn query_ibc_tokens<N: Namada>(
     Ok(tokens)
 }
 
+/// Query the IBC rolling-window rate limits and the remaining quotas of the
+/// token over the channel, or over all the channels if no channel is given
+pub async fn query_ibc_rate_limit<C: namada_io::Client + Sync>(
+    client: &C,
+    token: &Address,
+    channel_id: Option<ChannelId>,
+) -> Result<RateLimitQuota, error::Error> {
+    convert_response::<C, _>(
+        RPC.shell().ibc_rate_limit(client, token, &channel_id).await,
+    )
+}
+
 /// Look up the IBC denomination from a IbcToken.
 pub async fn query_ibc_denom<N: Namada>(
     context: &N,
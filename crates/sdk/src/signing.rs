@@ -13,7 +13,9 @@ use masp_primitives::asset_type::AssetType;
 use masp_primitives::transaction::components::sapling::fees::{
     InputView, OutputView,
 };
-use namada_account::{AccountPublicKeysMap, InitAccount, UpdateAccount};
+use namada_account::{
+    AccountPublicKeysMap, ActionClass, InitAccount, UpdateAccount,
+};
 use namada_core::address::{Address, ImplicitAddress, InternalAddress, MASP};
 use namada_core::arith::checked;
 use namada_core::collections::{HashMap, HashSet};
@@ -254,7 +256,7 @@ where
     }
 
     // Then try to sign the raw header with private keys in the software wallet
-    if let Some(account_public_keys_map) = signing_data.account_public_keys_map
+    if let Some(account_public_keys_map) = &signing_data.account_public_keys_map
     {
         let mut wallet = wallet.write().await;
         let mut signing_tx_keypairs = vec![];
@@ -276,8 +278,8 @@ where
         if !signing_tx_keypairs.is_empty() {
             tx.sign_raw(
                 signing_tx_keypairs,
-                account_public_keys_map,
-                signing_data.owner,
+                account_public_keys_map.clone(),
+                signing_data.owner.clone(),
             );
         }
     }
@@ -335,15 +337,23 @@ where
             .await?;
         }
     }
-    // Then make sure that the number of public keys used exceeds the threshold
-    let used_pubkeys_len = used_pubkeys
-        .len()
-        .try_into()
-        .expect("Public keys associated with account exceed 127");
-    if used_pubkeys_len < signing_data.threshold {
+    // Then make sure that the weight of the public keys used meets the
+    // threshold
+    let used_pubkeys_weight = match &signing_data.account_public_keys_map {
+        Some(account_public_keys_map) => account_public_keys_map.total_weight(
+            used_pubkeys
+                .iter()
+                .filter_map(|pk| account_public_keys_map.pk_to_idx.get(pk)),
+        ),
+        None => used_pubkeys
+            .len()
+            .try_into()
+            .expect("Public keys associated with account exceed 127"),
+    };
+    if used_pubkeys_weight < u32::from(signing_data.threshold) {
         Err(Error::from(TxSubmitError::MissingSigningKeys(
             signing_data.threshold,
-            used_pubkeys_len,
+            u8::try_from(used_pubkeys_weight).unwrap_or(u8::MAX),
         )))
     } else {
         Ok(())
@@ -419,6 +429,23 @@ pub async fn aux_signing_data(
     })
 }
 
+/// Require the owner's signature threshold for the given class of actions
+/// instead of its default threshold
+pub async fn with_action_threshold(
+    context: &impl Namada,
+    mut signing_data: SigningTxData,
+    class: ActionClass,
+) -> Result<SigningTxData, Error> {
+    if let Some(owner @ Address::Established(_)) = &signing_data.owner {
+        if let Some(account) =
+            rpc::get_account_info(context.client(), owner).await?
+        {
+            signing_data.threshold = account.threshold_for(class);
+        }
+    }
+    Ok(signing_data)
+}
+
 /// Information about the post-fee balance of the tx's source. Used to correctly
 /// handle balance validation in the inner tx
 #[derive(Debug)]
@@ -502,9 +529,13 @@ pub async fn validate_transparent_fee<N: Namada>(
     fee_payer: &common::PublicKey,
 ) -> Result<(DenominatedAmount, TxSourcePostBalance), Error> {
     let fee_amount = validate_fee(context, args).await?;
-    let fee_payer_address = Address::from(fee_payer);
+    // The fees of a sponsored tx are debited from the sponsor's balance
+    let fee_source = args
+        .fee_sponsor
+        .clone()
+        .unwrap_or_else(|| Address::from(fee_payer));
 
-    let balance_key = balance_key(&args.fee_token, &fee_payer_address);
+    let balance_key = balance_key(&args.fee_token, &fee_source);
     #[allow(clippy::disallowed_methods)]
     let balance = rpc::query_storage_value::<_, token::Amount>(
         context.client(),
@@ -516,7 +547,7 @@ pub async fn validate_transparent_fee<N: Namada>(
     let total_fee = checked!(fee_amount.amount() * u64::from(args.gas_limit))?;
     let mut updated_balance = TxSourcePostBalance {
         post_balance: balance,
-        source: fee_payer_address.clone(),
+        source: fee_source.clone(),
         token: args.fee_token.clone(),
     };
 
@@ -529,10 +560,7 @@ pub async fn validate_transparent_fee<N: Namada>(
 
                 let balance = context.format_amount(&token_addr, balance).await;
                 return Err(Error::from(TxSubmitError::BalanceTooLowForFees(
-                    fee_payer_address,
-                    token_addr,
-                    fee_amount,
-                    balance,
+                    fee_source, token_addr, fee_amount, balance,
                 )));
             }
 
@@ -565,6 +593,9 @@ pub async fn wrap_tx(
         // TODO(namada#1625): partially validate the gas limit in client
         args.gas_limit,
     );
+    if let Some(sponsor) = &args.fee_sponsor {
+        tx.add_fee_sponsor(sponsor.clone());
+    }
 
     Ok(())
 }
@@ -852,6 +883,27 @@ fn proposal_type_to_ledger_vector(
             output
                 .push(format!("Proposal hash : {}", HEXLOWER.encode(&extra.0)));
         }
+        ProposalType::ParameterChange(changes) => {
+            output.push("Proposal type : Parameter Change".to_string());
+            for change in changes {
+                output.push(format!("Parameter change : {}", change));
+            }
+        }
+        ProposalType::Upgrade(plan) => {
+            output.push("Proposal type : Upgrade".to_string());
+            output.push(format!("Upgrade name : {}", plan.name));
+            output.push(format!("Upgrade height : {}", plan.height));
+            output.push(format!(
+                "Upgrade binary hash : {}",
+                HEXLOWER.encode(&plan.binary_hash.0)
+            ));
+            if let Some(hash) = &plan.migration_hash {
+                output.push(format!(
+                    "Upgrade migration hash : {}",
+                    HEXLOWER.encode(&hash.0)
+                ));
+            }
+        }
         ProposalType::PGFSteward(actions) => {
             output.push("Proposal type : PGF Steward".to_string());
             let mut actions = actions.iter().collect::<Vec<_>>();
@@ -1275,6 +1327,18 @@ pub async fn to_ledger_vector(
                     update_account.threshold.unwrap()
                 )])
             }
+            tv.output.extend(
+                update_account
+                    .weights
+                    .iter()
+                    .map(|weight| format!("Weight : {}", weight)),
+            );
+            tv.output
+                .extend(update_account.action_thresholds.iter().map(
+                    |(class, threshold)| {
+                        format!("{} threshold : {}", class, threshold)
+                    },
+                ));
 
             let vp_code_data = match &update_account.vp_code_hash {
                 Some(hash) => {
@@ -1309,6 +1373,19 @@ pub async fn to_ledger_vector(
                 tv.output_expert
                     .extend(vec![format!("Threshold : {}", threshold,)])
             }
+            tv.output_expert.extend(
+                update_account
+                    .weights
+                    .iter()
+                    .map(|weight| format!("Weight : {}", weight)),
+            );
+            tv.output_expert.extend(
+                update_account.action_thresholds.iter().map(
+                    |(class, threshold)| {
+                        format!("{} threshold : {}", class, threshold)
+                    },
+                ),
+            );
             if let Some((_, extra_code_hash)) = vp_code_data {
                 tv.output_expert.extend(vec![format!(
                     "VP type : {}",
@@ -2026,6 +2103,9 @@ pub async fn to_ledger_vector(
                     format!("Fees/gas unit : {}", fee_amount_per_gas_unit),
                 ]);
             }
+            if let Some(sponsor) = &wrapper.fee_sponsor {
+                tv.output_expert.push(format!("Fee sponsor : {}", sponsor));
+            }
         }
     }
 
@@ -2079,6 +2159,7 @@ mod test_signing {
             wallet_alias_force: false,
             fee_amount: None,
             wrapper_fee_payer: None,
+            fee_sponsor: None,
             fee_token: Address::Internal(InternalAddress::Governance),
             gas_limit: namada_tx::data::GasLimit::from(2),
             expiration: Default::default(),
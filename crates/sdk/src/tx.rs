@@ -18,7 +18,9 @@ use masp_primitives::transaction::components::transparent::fees::{
 };
 use masp_primitives::transaction::components::I128Sum;
 use masp_primitives::transaction::{builder, Transaction as MaspTransaction};
-use namada_account::{InitAccount, UpdateAccount};
+use namada_account::{
+    AccountPublicKeysMap, ActionClass, InitAccount, UpdateAccount,
+};
 use namada_core::address::{Address, IBC, MASP};
 use namada_core::arith::checked;
 use namada_core::chain::Epoch;
@@ -44,13 +46,19 @@ use namada_core::masp::{
 use namada_core::storage;
 use namada_core::time::DateTimeUtc;
 use namada_governance::cli::onchain::{
-    DefaultProposal, OnChainProposal, PgfFundingProposal, PgfStewardProposal,
+    DefaultProposal, OnChainProposal, ParameterChangeProposal,
+    PgfFundingProposal, PgfStewardProposal,
 };
 use namada_governance::pgf::cli::steward::Commission;
 use namada_governance::storage::proposal::{
     InitProposalData, ProposalType, VoteProposalData,
 };
 use namada_governance::storage::vote::ProposalVote;
+use namada_ibc::fee::RelayerFee;
+use namada_ibc::ica::{
+    InterchainAccountPacketData, MsgInterchainAccount,
+    MsgRegisterInterchainAccount, MsgSendTx,
+};
 use namada_ibc::storage::channel_key;
 use namada_ibc::trace::is_nft_trace;
 use namada_ibc::{MsgNftTransfer, MsgTransfer};
@@ -108,6 +116,12 @@ pub const TX_REACTIVATE_VALIDATOR_WASM: &str = "tx_reactivate_validator.wasm";
 pub const TX_INIT_PROPOSAL: &str = "tx_init_proposal.wasm";
 /// Vote transaction WASM path
 pub const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
+/// Vote delegation transaction WASM path
+pub const TX_DELEGATE_VOTE_WASM: &str = "tx_delegate_vote.wasm";
+/// Lock voting tokens transaction WASM path
+pub const TX_LOCK_VOTING_TOKENS_WASM: &str = "tx_lock_voting_tokens.wasm";
+/// Unlock voting tokens transaction WASM path
+pub const TX_UNLOCK_VOTING_TOKENS_WASM: &str = "tx_unlock_voting_tokens.wasm";
 /// Reveal public key transaction WASM path
 pub const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 /// Update validity predicate WASM path
@@ -142,6 +156,19 @@ pub const TX_UPDATE_STEWARD_COMMISSION: &str =
     "tx_update_steward_commission.wasm";
 /// Redelegate transaction WASM path
 pub const TX_REDELEGATE_WASM: &str = "tx_redelegate.wasm";
+/// Set auto-compound transaction WASM path
+pub const TX_SET_AUTO_COMPOUND_WASM: &str = "tx_set_auto_compound.wasm";
+/// Fee sponsorship transaction WASM path
+pub const TX_SPONSOR_FEES_WASM: &str = "tx_sponsor_fees.wasm";
+/// Schedule transfer transaction WASM path
+pub const TX_SCHEDULE_TRANSFER_WASM: &str = "tx_schedule_transfer.wasm";
+/// Cancel scheduled transfer transaction WASM path
+pub const TX_CANCEL_SCHEDULED_TRANSFER_WASM: &str =
+    "tx_cancel_scheduled_transfer.wasm";
+/// Tokenize bond transaction WASM path
+pub const TX_TOKENIZE_BOND_WASM: &str = "tx_tokenize_bond.wasm";
+/// Redeem liquid staking shares transaction WASM path
+pub const TX_REDEEM_LIQUID_SHARES_WASM: &str = "tx_redeem_liquid_shares.wasm";
 
 /// Refund target alias prefix for IBC shielded transfers
 const IBC_REFUND_ALIAS_PREFIX: &str = "ibc-refund-target";
@@ -578,6 +605,12 @@ pub async fn build_change_consensus_key(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
 
     let (fee_amount, _updated_balance) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
@@ -616,6 +649,12 @@ pub async fn build_validator_commission_change(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -759,6 +798,12 @@ pub async fn build_validator_metadata_change(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -985,6 +1030,12 @@ pub async fn build_update_steward_commission(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1035,6 +1086,109 @@ pub async fn build_update_steward_commission(
     .map(|tx| (tx, signing_data))
 }
 
+/// Build a transaction to register an interchain account on the host chain
+pub async fn build_ica_register(
+    context: &impl Namada,
+    args::TxIcaRegister {
+        tx: tx_args,
+        owner,
+        connection_id,
+        version,
+        tx_code_path,
+    }: &args::TxIcaRegister,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        default_signer,
+        vec![],
+        false,
+    )
+    .await?;
+    let (fee_amount, _) =
+        validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
+            .await?;
+
+    let data = MsgInterchainAccount::Register(MsgRegisterInterchainAccount {
+        owner: owner.clone(),
+        connection_id: connection_id.to_string(),
+        version: version.clone().unwrap_or_default(),
+    });
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Build a transaction to send messages to the interchain account on the
+/// host chain
+pub async fn build_ica_send_tx(
+    context: &impl Namada,
+    args::TxIcaSendTx {
+        tx: tx_args,
+        owner,
+        connection_id,
+        messages,
+        ica_memo,
+        timeout_sec_offset,
+        tx_code_path,
+    }: &args::TxIcaSendTx,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        default_signer,
+        vec![],
+        false,
+    )
+    .await?;
+    let (fee_amount, _) =
+        validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
+            .await?;
+
+    // The packet times out after an hour by default
+    let relative_timeout =
+        Duration::from_secs(timeout_sec_offset.unwrap_or(3600))
+            .as_nanos()
+            .try_into()
+            .map_err(|_| {
+                Error::Other("The timeout is too large".to_string())
+            })?;
+    let data = MsgInterchainAccount::SendTx(MsgSendTx {
+        owner: owner.clone(),
+        connection_id: connection_id.to_string(),
+        packet_data: InterchainAccountPacketData::execute_tx(
+            messages.clone(),
+            ica_memo.clone().unwrap_or_default(),
+        ),
+        relative_timeout,
+    });
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Craft transaction to resign as a steward
 pub async fn build_resign_steward(
     context: &impl Namada,
@@ -1054,6 +1208,12 @@ pub async fn build_resign_steward(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1103,6 +1263,12 @@ pub async fn build_unjail_validator(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1208,6 +1374,12 @@ pub async fn build_deactivate_validator(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1284,6 +1456,12 @@ pub async fn build_reactivate_validator(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Validator,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1506,6 +1684,12 @@ pub async fn build_redelegation(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Staking,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1551,6 +1735,12 @@ pub async fn build_withdraw(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Staking,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1639,6 +1829,12 @@ pub async fn build_claim_rewards(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::ClaimRewards,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1744,6 +1940,12 @@ pub async fn build_unbond(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Staking,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -1980,6 +2182,12 @@ pub async fn build_bond(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Staking,
+    )
+    .await?;
     let (fee_amount, updated_balance) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -2032,6 +2240,7 @@ pub async fn build_default_proposal(
         proposal_data: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: DefaultProposal,
@@ -2046,6 +2255,12 @@ pub async fn build_default_proposal(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _updated_balance) =
         validate_transparent_fee(context, tx, &signing_data.fee_payer).await?;
 
@@ -2101,6 +2316,12 @@ pub async fn build_vote_proposal(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx, &signing_data.fee_payer).await?;
 
@@ -2429,6 +2650,7 @@ pub async fn build_pgf_funding_proposal(
         proposal_data: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfFundingProposal,
@@ -2443,6 +2665,12 @@ pub async fn build_pgf_funding_proposal(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _updated_balance) =
         validate_transparent_fee(context, tx, &signing_data.fee_payer).await?;
 
@@ -2476,6 +2704,7 @@ pub async fn build_pgf_stewards_proposal(
         proposal_data: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfStewardProposal,
@@ -2490,6 +2719,67 @@ pub async fn build_pgf_stewards_proposal(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
+    let (fee_amount, _updated_balance) =
+        validate_transparent_fee(context, tx, &signing_data.fee_payer).await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxSubmitError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Build a parameter change proposal governance
+pub async fn build_parameter_change_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: ParameterChangeProposal,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+        vec![],
+        false,
+    )
+    .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Governance,
+    )
+    .await?;
     let (fee_amount, _updated_balance) =
         validate_transparent_fee(context, tx, &signing_data.fee_payer).await?;
 
@@ -2528,6 +2818,11 @@ pub async fn build_ibc_transfer(
                 .to_string(),
         ));
     }
+    if args.relayer_fee.is_some() && args.source.spending_key().is_some() {
+        return Err(Error::Other(
+            "The relayer fees can't be paid from a shielded source".to_string(),
+        ));
+    }
 
     let refund_target =
         get_refund_target(context, &args.source, &args.refund_target).await?;
@@ -2566,6 +2861,26 @@ pub async fn build_ibc_transfer(
             .await
             .expect("expected to validate amount");
 
+    // validate the relayer fees given
+    let relayer_fee = match &args.relayer_fee {
+        Some(fee) => {
+            let mut amounts = vec![];
+            for amount in [fee.recv_fee, fee.ack_fee, fee.timeout_fee] {
+                let amount =
+                    validate_amount(context, amount, &fee.token, args.tx.force)
+                        .await?;
+                amounts.push(amount.amount());
+            }
+            Some(RelayerFee {
+                token: fee.token.clone(),
+                recv_fee: amounts[0],
+                ack_fee: amounts[1],
+                timeout_fee: amounts[2],
+            })
+        }
+        None => None,
+    };
+
     // If source is transparent check the balance (MASP balance is checked when
     // constructing the shielded part)
     if let Some(updated_balance) = updated_balance {
@@ -2735,10 +3050,21 @@ pub async fn build_ibc_transfer(
             timeout_height_on_b: timeout_height,
             timeout_timestamp_on_b: timeout_timestamp,
         };
-        MsgTransfer { message, transfer }.serialize_to_vec()
+        MsgTransfer {
+            message,
+            transfer,
+            fee: relayer_fee,
+        }
+        .serialize_to_vec()
     } else if let Some((trace_path, base_class_id, token_id)) =
         is_nft_trace(&ibc_denom)
     {
+        if relayer_fee.is_some() {
+            return Err(Error::Other(
+                "The relayer fees can't be paid for an NFT transfer"
+                    .to_string(),
+            ));
+        }
         let class_id = PrefixedClassId {
             trace_path,
             base_class_id: base_class_id.parse().map_err(|_| {
@@ -2949,6 +3275,12 @@ pub async fn build_transparent_transfer<N: Namada>(
             false,
         )
         .await?;
+        let signing_data = signing::with_action_threshold(
+            context,
+            signing_data,
+            ActionClass::Transfer,
+        )
+        .await?;
 
         // Transparent fee payment
         let (fee_amount, updated_balance) = validate_transparent_fee(
@@ -3195,6 +3527,12 @@ pub async fn build_shielding_transfer<N: Namada>(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::Transfer,
+    )
+    .await?;
 
     // Transparent fee payment
     let (fee_amount, updated_balance) =
@@ -3592,6 +3930,8 @@ pub async fn build_update_account(
         addr,
         public_keys,
         threshold,
+        weights,
+        action_thresholds,
     }: &args::TxUpdateAccount,
 ) -> Result<(Tx, SigningTxData)> {
     let default_signer = Some(addr.clone());
@@ -3604,6 +3944,12 @@ pub async fn build_update_account(
         false,
     )
     .await?;
+    let signing_data = signing::with_action_threshold(
+        context,
+        signing_data,
+        ActionClass::UpdateAccount,
+    )
+    .await?;
     let (fee_amount, _) =
         validate_transparent_fee(context, tx_args, &signing_data.fee_payer)
             .await?;
@@ -3618,23 +3964,44 @@ pub async fn build_update_account(
         )));
     };
 
+    // The public keys of the account after the update, along with their
+    // weights. The weights are reset when the public keys are replaced.
+    let public_keys_map = if public_keys.is_empty() {
+        account.public_keys_map.clone()
+    } else {
+        AccountPublicKeysMap::from_iter(public_keys.clone())
+    };
+    let public_keys_map = if weights.is_empty() {
+        public_keys_map
+    } else {
+        let invalid_weights = weights.len() != public_keys_map.idx_to_pk.len()
+            || weights.contains(&0);
+        if invalid_weights {
+            edisplay_line!(
+                context.io(),
+                "Invalid account weights: there must be exactly one non-zero \
+                 weight per public key."
+            );
+            if !tx_args.force {
+                return Err(Error::from(TxSubmitError::InvalidAccountWeights));
+            }
+        }
+        public_keys_map.with_weights((0..=u8::MAX).zip(weights.iter().copied()))
+    };
+    let total_weight = public_keys_map.total_weight_of_all();
+
     let threshold = if let Some(threshold) = threshold {
         let threshold = *threshold;
 
-        let invalid_threshold = threshold.is_zero();
-        let invalid_threshold_updated =
-            !public_keys.is_empty() && public_keys.len() < threshold as usize;
-        let invalid_threshold_current = public_keys.is_empty()
-            && account.get_all_public_keys().len() < threshold as usize;
+        let invalid_threshold =
+            threshold.is_zero() || total_weight < u32::from(threshold);
 
-        if invalid_threshold
-            || invalid_threshold_updated
-            || invalid_threshold_current
-        {
+        if invalid_threshold {
             edisplay_line!(
                 context.io(),
                 "Invalid account threshold: either the provided threshold is \
-                 zero or the number of public keys is less than the threshold."
+                 zero or the total weight of the public keys is less than the \
+                 threshold."
             );
             if !tx_args.force {
                 return Err(Error::from(
@@ -3645,8 +4012,7 @@ pub async fn build_update_account(
 
         Some(threshold)
     } else {
-        let invalid_too_few_pks = !public_keys.is_empty()
-            && public_keys.len() < account.threshold as usize;
+        let invalid_too_few_pks = total_weight < u32::from(account.threshold);
 
         if invalid_too_few_pks {
             return Err(Error::from(TxSubmitError::InvalidAccountThreshold));
@@ -3655,6 +4021,32 @@ pub async fn build_update_account(
         None
     };
 
+    // Every action threshold that will be set after the update must be
+    // reachable with the account's public keys
+    let mut updated_action_thresholds = account.action_thresholds.clone();
+    for (class, action_threshold) in action_thresholds {
+        if action_threshold.is_zero() {
+            updated_action_thresholds.remove(class);
+        } else {
+            updated_action_thresholds.insert(*class, *action_threshold);
+        }
+    }
+    for (class, action_threshold) in &updated_action_thresholds {
+        if total_weight < u32::from(*action_threshold) {
+            edisplay_line!(
+                context.io(),
+                "Invalid {} threshold: the total weight of the public keys is \
+                 less than the threshold.",
+                class
+            );
+            if !tx_args.force {
+                return Err(Error::from(
+                    TxSubmitError::InvalidAccountThreshold,
+                ));
+            }
+        }
+    }
+
     let vp_code_hash = match vp_code_path {
         Some(code_path) => {
             let vp_hash = query_wasm_code_hash_buf(context, code_path).await?;
@@ -3682,6 +4074,8 @@ pub async fn build_update_account(
         vp_code_hash: extra_section_hash,
         public_keys: public_keys.clone(),
         threshold,
+        weights: weights.clone(),
+        action_thresholds: action_thresholds.clone(),
     };
 
     let add_code_hash = |tx: &mut Tx, data: &mut UpdateAccount| {
@@ -38,6 +38,9 @@ pub type IbcVp<'a, S, CA> = ibc::vp::Ibc<
     token::Transfer,
 >;
 
+/// Native interchain account VP
+pub type IcaVp<'ctx, CTX> = ibc::vp::ica::IcaVp<'ctx, CTX, TokenKeys, GovKeys>;
+
 /// IBC VP pseudo-execution context
 pub type IbcVpContext<'view, 'a, S, CA, EVAL> =
     ibc::vp::context::PseudoExecutionContext<
@@ -47,6 +50,8 @@ pub type IbcVpContext<'view, 'a, S, CA, EVAL> =
         VpCache<CA>,
         EVAL,
         TokenStoreForIbcExec<'a, S, CA>,
+        GovKeys,
+        PosPreStore<'a, S, CA>,
     >;
 
 /// Native parameters VP
@@ -151,3 +156,6 @@ pub type TokenKeys = token::Store<()>;
 
 /// Parameters storage keys implementation
 pub type ParamKeys = parameters::Store<()>;
+
+/// Governance storage keys implementation
+pub type GovKeys = governance::Store<()>;
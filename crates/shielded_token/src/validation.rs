@@ -18,7 +18,7 @@ use masp_primitives::transaction::{
 };
 use masp_proofs::bellman::groth16::VerifyingKey;
 use masp_proofs::sapling::BatchValidator;
-use namada_gas::Gas;
+use namada_gas::{Gas, GasCostSchedule};
 use rand_core::OsRng;
 use smooth_operator::checked;
 
@@ -119,6 +119,7 @@ fn load_pvks() -> &'static PVKs {
 /// Verify a shielded transaction.
 pub fn verify_shielded_tx<F>(
     transaction: &Transaction,
+    gas_costs: &GasCostSchedule,
     consume_verify_gas: F,
 ) -> Result<()>
 where
@@ -161,7 +162,11 @@ where
     let mut ctx = testing::MockBatchValidator::default();
 
     // Charge gas before check bundle
-    charge_masp_check_bundle_gas(sapling_bundle, &consume_verify_gas)?;
+    charge_masp_check_bundle_gas(
+        sapling_bundle,
+        gas_costs,
+        &consume_verify_gas,
+    )?;
 
     if !ctx.check_bundle(sapling_bundle.to_owned(), sighash.as_ref().to_owned())
     {
@@ -171,7 +176,7 @@ where
     tracing::debug!("passed check bundle");
 
     // Charge gas before final validation
-    charge_masp_validate_gas(sapling_bundle, consume_verify_gas)?;
+    charge_masp_validate_gas(sapling_bundle, gas_costs, consume_verify_gas)?;
     if !ctx.validate(spend_vk, convert_vk, output_vk, OsRng) {
         return Err(Error::new_const("Invalid proofs or signatures"));
     }
@@ -215,6 +220,7 @@ pub fn partial_deauthorize(
 // proofs verification but not for signatures
 fn charge_masp_validate_gas<F>(
     sapling_bundle: &SaplingBundle<SaplingAuthorized>,
+    gas_costs: &GasCostSchedule,
     consume_verify_gas: F,
 ) -> Result<()>
 where
@@ -225,7 +231,7 @@ where
         checked!(
             // Add one for the binding signature
             ((sapling_bundle.shielded_spends.len() as u64) + 1)
-                * namada_gas::MASP_VERIFY_SIG_GAS
+                * gas_costs.masp_verify_sig
         )?
         .into(),
     )?;
@@ -236,36 +242,30 @@ where
     if let Some(remaining_notes) =
         sapling_bundle.shielded_spends.len().checked_sub(1)
     {
-        consume_verify_gas(namada_gas::MASP_FIXED_SPEND_GAS.into())?;
+        consume_verify_gas(gas_costs.masp_fixed_spend.into())?;
         consume_verify_gas(
-            checked!(
-                namada_gas::MASP_VARIABLE_SPEND_GAS * remaining_notes as u64
-            )?
-            .into(),
+            checked!(gas_costs.masp_variable_spend * remaining_notes as u64)?
+                .into(),
         )?;
     }
 
     if let Some(remaining_notes) =
         sapling_bundle.shielded_converts.len().checked_sub(1)
     {
-        consume_verify_gas(namada_gas::MASP_FIXED_CONVERT_GAS.into())?;
+        consume_verify_gas(gas_costs.masp_fixed_convert.into())?;
         consume_verify_gas(
-            checked!(
-                namada_gas::MASP_VARIABLE_CONVERT_GAS * remaining_notes as u64
-            )?
-            .into(),
+            checked!(gas_costs.masp_variable_convert * remaining_notes as u64)?
+                .into(),
         )?;
     }
 
     if let Some(remaining_notes) =
         sapling_bundle.shielded_outputs.len().checked_sub(1)
     {
-        consume_verify_gas(namada_gas::MASP_FIXED_OUTPUT_GAS.into())?;
+        consume_verify_gas(gas_costs.masp_fixed_output.into())?;
         consume_verify_gas(
-            checked!(
-                namada_gas::MASP_VARIABLE_OUTPUT_GAS * remaining_notes as u64
-            )?
-            .into(),
+            checked!(gas_costs.masp_variable_output * remaining_notes as u64)?
+                .into(),
         )?;
     }
 
@@ -275,6 +275,7 @@ where
 // Charge gas for the check_bundle operation which does not leverage concurrency
 fn charge_masp_check_bundle_gas<F>(
     sapling_bundle: &SaplingBundle<SaplingAuthorized>,
+    gas_costs: &GasCostSchedule,
     consume_verify_gas: F,
 ) -> Result<()>
 where
@@ -283,7 +284,7 @@ where
     consume_verify_gas(
         checked!(
             (sapling_bundle.shielded_spends.len() as u64)
-                * namada_gas::MASP_SPEND_CHECK_GAS
+                * gas_costs.masp_spend_check
         )?
         .into(),
     )?;
@@ -291,7 +292,7 @@ where
     consume_verify_gas(
         checked!(
             (sapling_bundle.shielded_converts.len() as u64)
-                * namada_gas::MASP_CONVERT_CHECK_GAS
+                * gas_costs.masp_convert_check
         )?
         .into(),
     )?;
@@ -299,7 +300,7 @@ where
     consume_verify_gas(
         checked!(
             (sapling_bundle.shielded_outputs.len() as u64)
-                * namada_gas::MASP_OUTPUT_CHECK_GAS
+                * gas_costs.masp_output_check
         )?
         .into(),
     )
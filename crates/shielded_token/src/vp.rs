@@ -601,7 +601,8 @@ where
         }
 
         // Verify the proofs
-        verify_shielded_tx(&shielded_tx, |gas| ctx.charge_gas(gas))
+        let gas_costs = Params::gas_cost_schedule(&ctx.pre())?;
+        verify_shielded_tx(&shielded_tx, &gas_costs, |gas| ctx.charge_gas(gas))
     }
 }
 
@@ -8,7 +8,7 @@ use namada_core::hash::Hash;
 use namada_core::parameters::{EpochDuration, Parameters};
 use namada_core::time::DateTimeUtc;
 use namada_core::{encode, ethereum_structs};
-use namada_gas::{Gas, MEMORY_ACCESS_GAS_PER_BYTE};
+use namada_gas::{Gas, GasCostSchedule};
 use namada_macros::BorshDeserializer;
 use namada_merkle_tree::{MerkleRoot, MerkleTree};
 #[cfg(feature = "migrations")]
@@ -84,6 +84,9 @@ where
     /// the shim or the recheck option (comet only calls it at most once
     /// for a given height/round)
     pub block_proposals_cache: CLruCache<Hash, ProcessProposalCachedResult>,
+    /// The active gas cost schedule. It's loaded from storage on start-up
+    /// and whenever governance activates a new schedule.
+    pub gas_cost_schedule: GasCostSchedule,
 }
 
 /// Last committed block
@@ -168,6 +171,7 @@ where
             block_proposals_cache: CLruCache::new(
                 NonZeroUsize::new(10).unwrap(),
             ),
+            gas_cost_schedule: GasCostSchedule::default(),
         }
     }
 
@@ -208,41 +212,41 @@ where
 
     /// Get the chain ID as a raw string
     pub fn get_chain_id(&self) -> (ChainId, Gas) {
-        // Adding consts that cannot overflow
-        #[allow(clippy::arithmetic_side_effects)]
         (
             self.chain_id.clone(),
-            (CHAIN_ID_LENGTH as u64 * MEMORY_ACCESS_GAS_PER_BYTE).into(),
+            (CHAIN_ID_LENGTH as u64)
+                .saturating_mul(self.gas_cost_schedule.memory_access_per_byte)
+                .into(),
         )
     }
 
     /// Get the block height
     pub fn get_block_height(&self) -> (BlockHeight, Gas) {
-        // Adding consts that cannot overflow
-        #[allow(clippy::arithmetic_side_effects)]
         (
             self.block.height,
-            (BLOCK_HEIGHT_LENGTH as u64 * MEMORY_ACCESS_GAS_PER_BYTE).into(),
+            (BLOCK_HEIGHT_LENGTH as u64)
+                .saturating_mul(self.gas_cost_schedule.memory_access_per_byte)
+                .into(),
         )
     }
 
     /// Get the current (yet to be committed) block epoch
     pub fn get_current_epoch(&self) -> (Epoch, Gas) {
-        // Adding consts that cannot overflow
-        #[allow(clippy::arithmetic_side_effects)]
         (
             self.block.epoch,
-            (EPOCH_TYPE_LENGTH as u64 * MEMORY_ACCESS_GAS_PER_BYTE).into(),
+            (EPOCH_TYPE_LENGTH as u64)
+                .saturating_mul(self.gas_cost_schedule.memory_access_per_byte)
+                .into(),
         )
     }
 
     /// Get the epoch of the last committed block
     pub fn get_last_epoch(&self) -> (Epoch, Gas) {
-        // Adding consts that cannot overflow
-        #[allow(clippy::arithmetic_side_effects)]
         (
             self.last_epoch,
-            (EPOCH_TYPE_LENGTH as u64 * MEMORY_ACCESS_GAS_PER_BYTE).into(),
+            (EPOCH_TYPE_LENGTH as u64)
+                .saturating_mul(self.gas_cost_schedule.memory_access_per_byte)
+                .into(),
         )
     }
 
@@ -44,9 +44,7 @@ pub use namada_core::storage::{
     BlockResults, EthEventsQueue, Key, KeySeg, TxIndex, EPOCH_TYPE_LENGTH,
 };
 use namada_core::tendermint::merkle::proof::ProofOps;
-use namada_gas::{
-    Gas, MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_ACCESS_GAS_PER_BYTE,
-};
+use namada_gas::Gas;
 use namada_merkle_tree::Error as MerkleTreeError;
 pub use namada_merkle_tree::{
     self as merkle_tree, ics23_specs, MembershipProof, MerkleTree,
@@ -95,26 +93,30 @@ pub trait StateRead: StorageRead + Debug {
     /// Check if the given key is present in storage. Returns the result and the
     /// gas cost.
     fn db_has_key(&self, key: &storage::Key) -> Result<(bool, Gas)> {
+        self.write_log().record_db_read(key);
         let len = key.len() as u64;
+        let cost = self.in_mem().gas_cost_schedule.storage_access_per_byte;
         Ok((
             self.db().read_subspace_val(key)?.is_some(),
-            checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
+            checked!(len * cost)?.into(),
         ))
     }
 
     /// Returns a value from the specified subspace and the gas cost
     fn db_read(&self, key: &storage::Key) -> Result<(Option<Vec<u8>>, Gas)> {
         tracing::trace!("storage read key {}", key);
+        self.write_log().record_db_read(key);
 
+        let cost = self.in_mem().gas_cost_schedule.storage_access_per_byte;
         match self.db().read_subspace_val(key)? {
             Some(v) => {
                 let len = checked!(key.len() + v.len())? as u64;
-                let gas = checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?;
+                let gas = checked!(len * cost)?;
                 Ok((Some(v), gas.into()))
             }
             None => {
                 let len = key.len() as u64;
-                let gas = checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?;
+                let gas = checked!(len * cost)?;
                 Ok((None, gas.into()))
             }
         }
@@ -129,10 +131,12 @@ pub trait StateRead: StorageRead + Debug {
         &self,
         prefix: &Key,
     ) -> Result<(<Self::D as DBIter<'_>>::PrefixIter, Gas)> {
+        self.write_log().record_db_iter(prefix);
         let len = prefix.len() as u64;
+        let cost = self.in_mem().gas_cost_schedule.storage_access_per_byte;
         Ok((
             self.db().iter_prefix(Some(prefix)),
-            checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
+            checked!(len * cost)?.into(),
         ))
     }
 
@@ -166,28 +170,30 @@ pub trait StateRead: StorageRead + Debug {
         &self,
         height: Option<BlockHeight>,
     ) -> Result<(Option<BlockHeader>, Gas)> {
+        let gas_costs = &self.in_mem().gas_cost_schedule;
         match height {
             Some(h) if h == self.in_mem().get_block_height().0 => {
                 let header = self.in_mem().header.clone();
                 let gas = if header.is_some() {
                     let len = BlockHeader::encoded_len() as u64;
-                    checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?
+                    checked!(len * gas_costs.memory_access_per_byte)?
                 } else {
-                    MEMORY_ACCESS_GAS_PER_BYTE
+                    gas_costs.memory_access_per_byte
                 };
                 Ok((header, gas.into()))
             }
             Some(h) => match self.db().read_block_header(h)? {
                 Some(header) => {
                     let len = BlockHeader::encoded_len() as u64;
-                    let gas = checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?;
+                    let gas =
+                        checked!(len * gas_costs.storage_access_per_byte)?;
                     Ok((Some(header), gas.into()))
                 }
-                None => Ok((None, STORAGE_ACCESS_GAS_PER_BYTE.into())),
+                None => Ok((None, gas_costs.storage_access_per_byte.into())),
             },
             None => Ok((
                 self.in_mem().header.clone(),
-                STORAGE_ACCESS_GAS_PER_BYTE.into(),
+                gas_costs.storage_access_per_byte.into(),
             )),
         }
     }
@@ -328,25 +334,25 @@ macro_rules! impl_storage_read {
             }
 
             fn get_pred_epochs(&self) -> namada_storage::Result<Epochs> {
-                self.charge_gas(
-                    namada_gas::STORAGE_ACCESS_GAS_PER_BYTE.into(),
-                ).into_storage_result()?;
+                let gas_costs = &self.in_mem().gas_cost_schedule;
+                self.charge_gas(gas_costs.storage_access_per_byte.into())
+                    .into_storage_result()?;
                 Ok(self.in_mem().block.pred_epochs.clone())
             }
 
             fn get_tx_index(
                 &self,
             ) -> std::result::Result<storage::TxIndex, namada_storage::Error> {
-                self.charge_gas(
-                    namada_gas::STORAGE_ACCESS_GAS_PER_BYTE.into(),
-                ).into_storage_result()?;
+                let gas_costs = &self.in_mem().gas_cost_schedule;
+                self.charge_gas(gas_costs.storage_access_per_byte.into())
+                    .into_storage_result()?;
                 Ok(self.in_mem().tx_index)
             }
 
             fn get_native_token(&self) -> namada_storage::Result<Address> {
-                self.charge_gas(
-                    namada_gas::STORAGE_ACCESS_GAS_PER_BYTE.into(),
-                ).into_storage_result()?;
+                let gas_costs = &self.in_mem().gas_cost_schedule;
+                self.charge_gas(gas_costs.storage_access_per_byte.into())
+                    .into_storage_result()?;
                 Ok(self.in_mem().native_token.clone())
             }
         }
@@ -475,12 +481,13 @@ where
     let storage_iter = db.iter_prefix(Some(prefix)).peekable();
     let write_log_iter = write_log.iter_prefix_pre(prefix).peekable();
     let len = prefix.len() as u64;
+    let cost = write_log.gas_costs().storage_access_per_byte;
     Ok((
         PrefixIter::<D> {
             storage_iter,
             write_log_iter,
         },
-        checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
+        checked!(len * cost)?.into(),
     ))
 }
 
@@ -500,12 +507,13 @@ where
     let storage_iter = db.iter_prefix(Some(prefix)).peekable();
     let write_log_iter = write_log.iter_prefix_post(prefix).peekable();
     let len = prefix.len() as u64;
+    let cost = write_log.gas_costs().storage_access_per_byte;
     Ok((
         PrefixIter::<D> {
             storage_iter,
             write_log_iter,
         },
-        checked!(len * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
+        checked!(len * cost)?.into(),
     ))
 }
 
@@ -762,6 +770,7 @@ mod tests {
                 gas_scale: 10_000_000,
                 minimum_gas_price: BTreeMap::default(),
                 is_native_token_transferable: true,
+                enforce_fee_priority_ordering: false,
             };
             // Initialize pred_epochs to the current height
             let height = state.in_mem().block.height;
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 use namada_core::address::Address;
 use namada_core::arith::checked;
@@ -10,7 +11,7 @@ use namada_core::parameters::{EpochDuration, Parameters};
 use namada_core::time::DateTimeUtc;
 use namada_core::{decode, storage};
 use namada_events::{EmitEvents, EventToEmit};
-use namada_gas::Gas;
+use namada_gas::{Gas, GasCostSchedule};
 use namada_merkle_tree::NO_DIFF_KEY_PREFIX;
 use namada_replay_protection as replay_protection;
 use namada_storage::conversion_state::{
@@ -26,7 +27,7 @@ use crate::{
     is_pending_transfer_key, DBIter, Epoch, Error, Hash, Key, KeySeg,
     LastBlock, MembershipProof, MerkleTree, MerkleTreeError, ProofOps, Result,
     State, StateError, StateRead, StorageHasher, StoreType, TxWrites, DB,
-    EPOCH_SWITCH_BLOCKS_DELAY, STORAGE_ACCESS_GAS_PER_BYTE,
+    EPOCH_SWITCH_BLOCKS_DELAY,
 };
 
 /// Owned state with full R/W access.
@@ -272,9 +273,9 @@ where
         &mut self,
         batch: &mut D::WriteBatch,
     ) -> Result<()> {
-        for (key, entry) in
-            std::mem::take(&mut self.0.write_log.block_write_log).into_iter()
-        {
+        for (key, entry) in Arc::unwrap_or_clone(std::mem::take(
+            &mut self.0.write_log.block_write_log,
+        )) {
             match entry {
                 StorageModification::Write { value } => {
                     self.batch_write_subspace_val(batch, &key, value)?;
@@ -681,10 +682,16 @@ where
         &mut self.write_log
     }
 
+    /// Set the gas cost schedule used to charge the accesses to the state
+    pub fn set_gas_cost_schedule(&mut self, schedule: GasCostSchedule) {
+        self.in_mem.gas_cost_schedule = schedule;
+        self.write_log.set_gas_costs(schedule);
+    }
+
     /// Borrow in-memory state and DB handle with a mutable temporary write-log.
     pub fn with_temp_write_log(&self) -> TempWlState<'_, D, H> {
         TempWlState {
-            write_log: WriteLog::default(),
+            write_log: WriteLog::with_gas_costs(self.in_mem.gas_cost_schedule),
             db: &self.db,
             in_mem: &self.in_mem,
         }
@@ -708,12 +715,23 @@ where
         &self,
     ) -> TempWlState<'static, D, H> {
         TempWlState {
-            write_log: WriteLog::default(),
+            write_log: WriteLog::with_gas_costs(self.in_mem.gas_cost_schedule),
             db: &*(&self.db as *const _),
             in_mem: &*(&self.in_mem as *const _),
         }
     }
 
+    /// Borrow in-memory state and DB handle with a speculative copy of the
+    /// write-log that tracks the reads performed through it (see
+    /// [`WriteLog::speculative_copy`]).
+    pub fn with_speculative_write_log(&self) -> TempWlState<'_, D, H> {
+        TempWlState {
+            write_log: self.write_log.speculative_copy(),
+            db: &self.db,
+            in_mem: &self.in_mem,
+        }
+    }
+
     /// Commit the current transaction's write log and the entire batch to the
     /// block. Starts a new transaction and batch write log.
     pub fn commit_tx_batch(&mut self) {
@@ -776,10 +794,16 @@ where
         {
             self.db_read(key)
         } else {
-            if !(self.diff_key_filter)(key) {
+            // In archive mode, the history of all the keys is available
+            let is_archived = self
+                .db()
+                .archive_start_height()
+                .is_some_and(|start| height >= start);
+            if !is_archived && !(self.diff_key_filter)(key) {
                 return Ok((None, Gas::default()));
             }
 
+            let cost = self.in_mem().gas_cost_schedule.storage_access_per_byte;
             match self.db().read_subspace_val_with_height(
                 key,
                 height,
@@ -787,17 +811,11 @@ where
             )? {
                 Some(v) => {
                     let gas = checked!(key.len() + v.len())? as u64;
-                    Ok((
-                        Some(v),
-                        checked!(gas * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
-                    ))
+                    Ok((Some(v), checked!(gas * cost)?.into()))
                 }
                 None => {
                     let gas = key.len() as u64;
-                    Ok((
-                        None,
-                        checked!(gas * STORAGE_ACCESS_GAS_PER_BYTE)?.into(),
-                    ))
+                    Ok((None, checked!(gas * cost)?.into()))
                 }
             }
         }
@@ -835,8 +853,8 @@ where
         }
 
         let len = value.len();
-        let gas =
-            (key.len() + len) as u64 * namada_gas::STORAGE_WRITE_GAS_PER_BYTE;
+        let gas = (key.len() + len) as u64
+            * self.in_mem.gas_cost_schedule.storage_write_per_byte;
         let size_diff = self.db.write_subspace_val(
             self.in_mem.block.height,
             key,
@@ -874,7 +892,7 @@ where
             )?;
         }
         let gas = (key.len() + deleted_bytes_len as usize) as u64
-            * namada_gas::STORAGE_WRITE_GAS_PER_BYTE;
+            * self.in_mem.gas_cost_schedule.storage_write_per_byte;
         Ok((gas, deleted_bytes_len))
     }
 
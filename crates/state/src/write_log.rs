@@ -2,6 +2,7 @@
 //! before they are committed to the ledger's storage.
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
 
 use itertools::Itertools;
 use namada_core::address::{Address, EstablishedAddressGen};
@@ -10,10 +11,7 @@ use namada_core::collections::{HashMap, HashSet};
 use namada_core::hash::Hash;
 use namada_core::{arith, storage};
 use namada_events::{Event, EventToEmit, EventType};
-use namada_gas::{
-    Gas, MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_DELETE_GAS_PER_BYTE,
-    STORAGE_WRITE_GAS_PER_BYTE,
-};
+use namada_gas::{Gas, GasCostSchedule};
 use patricia_tree::map::StringPatriciaMap;
 use thiserror::Error;
 
@@ -133,14 +131,104 @@ impl std::cmp::PartialEq for WriteLogEvents {
 
 impl std::cmp::Eq for WriteLogEvents {}
 
+/// The storage keys and prefixes read through a write log, used to detect
+/// conflicts between speculatively executed transactions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadSet {
+    /// Keys read directly
+    keys: HashSet<storage::Key>,
+    /// Prefixes iterated over
+    prefixes: HashSet<storage::Key>,
+}
+
+impl ReadSet {
+    /// Check if any of the given written keys would have altered the result
+    /// of the reads in this set
+    pub fn conflicts_with(&self, written: &BTreeSet<storage::Key>) -> bool {
+        written.iter().any(|key| {
+            self.keys.contains(key)
+                || self
+                    .prefixes
+                    .iter()
+                    .any(|prefix| key.split_prefix(prefix).is_some())
+        })
+    }
+}
+
+/// Optional tracking of the reads performed through the write log. Reads can
+/// happen concurrently from the validity predicates, hence the lock.
+#[derive(Debug, Default)]
+struct ReadLog(Option<Mutex<ReadSet>>);
+
+impl ReadLog {
+    fn tracking() -> Self {
+        Self(Some(Mutex::new(ReadSet::default())))
+    }
+
+    fn record_key(&self, key: &storage::Key) {
+        if let Some(set) = &self.0 {
+            set.lock()
+                .expect("Read log lock shouldn't be poisoned")
+                .keys
+                .insert(key.clone());
+        }
+    }
+
+    fn record_prefix(&self, prefix: &storage::Key) {
+        if let Some(set) = &self.0 {
+            set.lock()
+                .expect("Read log lock shouldn't be poisoned")
+                .prefixes
+                .insert(prefix.clone());
+        }
+    }
+
+    fn take(&mut self) -> Option<ReadSet> {
+        self.0.take().map(|set| {
+            set.into_inner()
+                .expect("Read log lock shouldn't be poisoned")
+        })
+    }
+}
+
+impl Clone for ReadLog {
+    fn clone(&self) -> Self {
+        Self(self.0.as_ref().map(|set| {
+            Mutex::new(
+                set.lock()
+                    .expect("Read log lock shouldn't be poisoned")
+                    .clone(),
+            )
+        }))
+    }
+}
+
+// The reads are not part of the state, they never make two logs different
+impl std::cmp::PartialEq for ReadLog {
+    fn eq(&self, _other: &ReadLog) -> bool {
+        true
+    }
+}
+
+impl std::cmp::Eq for ReadLog {}
+
+/// The batch and transaction write logs of a batch executed against a
+/// speculative copy of the write log, to be moved back into the main one
+#[derive(Debug, Default)]
+pub struct BatchWriteLog {
+    batch_write_log: Vec<BatchedTxWriteLog>,
+    tx_write_log: TxWriteLog,
+}
+
 /// The write log storage
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WriteLog {
     /// The generator of established addresses
     pub(crate) block_address_gen: Option<EstablishedAddressGen>,
     /// All the storage modification accepted by validity predicates are stored
-    /// in block write-log, before being committed to the storage
-    pub(crate) block_write_log: HashMap<storage::Key, StorageModification>,
+    /// in block write-log, before being committed to the storage. Shared
+    /// with the speculative copies of the log and only copied on write.
+    pub(crate) block_write_log: Arc<HashMap<storage::Key, StorageModification>>,
     /// The write log of the transactions of the current batch
     /// INVARIANT: this has to be sorted by the insertion
     /// order to correctly read values
@@ -149,8 +237,14 @@ pub struct WriteLog {
     pub(crate) tx_write_log: TxWriteLog,
     /// Storage modifications for the replay protection storage, cannot be
     /// managed in the normal write log because we need to commit them
-    /// sometimes even on batch failure
-    pub(crate) replay_protection: HashSet<Hash>,
+    /// sometimes even on batch failure. Shared with the speculative copies of
+    /// the log and only copied on write.
+    pub(crate) replay_protection: Arc<HashSet<Hash>>,
+    /// The reads performed through this log, only tracked on speculative
+    /// copies
+    read_log: ReadLog,
+    /// The gas cost schedule used to charge the accesses to the log
+    gas_costs: GasCostSchedule,
 }
 
 /// Write log prefix iterator
@@ -173,15 +267,48 @@ impl Default for WriteLog {
     fn default() -> Self {
         Self {
             block_address_gen: None,
-            block_write_log: HashMap::with_capacity(100_000),
+            block_write_log: Arc::new(HashMap::with_capacity(100_000)),
             batch_write_log: Vec::with_capacity(5),
             tx_write_log: Default::default(),
-            replay_protection: HashSet::with_capacity(1_000),
+            replay_protection: Arc::new(HashSet::with_capacity(1_000)),
+            read_log: ReadLog::default(),
+            gas_costs: GasCostSchedule::default(),
         }
     }
 }
 
 impl WriteLog {
+    /// Create a new write log charging the accesses with the given gas cost
+    /// schedule
+    pub(crate) fn with_gas_costs(gas_costs: GasCostSchedule) -> Self {
+        Self {
+            gas_costs,
+            ..Default::default()
+        }
+    }
+
+    /// Get the gas cost schedule used to charge the accesses to the log
+    pub fn gas_costs(&self) -> &GasCostSchedule {
+        &self.gas_costs
+    }
+
+    /// Set the gas cost schedule used to charge the accesses to the log
+    pub(crate) fn set_gas_costs(&mut self, gas_costs: GasCostSchedule) {
+        self.gas_costs = gas_costs;
+    }
+
+    /// Record a read of the given key that bypassed the log, i.e. straight
+    /// from the DB
+    pub(crate) fn record_db_read(&self, key: &storage::Key) {
+        self.read_log.record_key(key);
+    }
+
+    /// Record an iteration over the given prefix that bypassed the log, i.e.
+    /// straight from the DB
+    pub(crate) fn record_db_iter(&self, prefix: &storage::Key) {
+        self.read_log.record_prefix(prefix);
+    }
+
     /// Read a non-temp value at the given key and return the value and the gas
     /// cost, returns [`None`] if the key is not present in the write log
     pub fn read(
@@ -189,6 +316,7 @@ impl WriteLog {
         key: &storage::Key,
     ) -> std::result::Result<(Option<&StorageModification>, Gas), arith::Error>
     {
+        self.read_log.record_key(key);
         // try to read from tx write log first
         match self
             .tx_write_log
@@ -218,12 +346,17 @@ impl WriteLog {
                 } as u64;
                 Ok((
                     Some(v),
-                    checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
+                    checked!(gas * self.gas_costs.memory_access_per_byte)?
+                        .into(),
                 ))
             }
             None => {
                 let gas = key.len() as u64;
-                Ok((None, checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into()))
+                Ok((
+                    None,
+                    checked!(gas * self.gas_costs.memory_access_per_byte)?
+                        .into(),
+                ))
             }
         }
     }
@@ -236,6 +369,7 @@ impl WriteLog {
         key: &storage::Key,
     ) -> std::result::Result<(Option<&StorageModification>, Gas), arith::Error>
     {
+        self.read_log.record_key(key);
         for bucket in self
             .batch_write_log
             .iter()
@@ -255,12 +389,16 @@ impl WriteLog {
                 } as u64;
                 return Ok((
                     Some(v),
-                    checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
+                    checked!(gas * self.gas_costs.memory_access_per_byte)?
+                        .into(),
                 ));
             }
         }
         let gas = key.len() as u64;
-        Ok((None, checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into()))
+        Ok((
+            None,
+            checked!(gas * self.gas_costs.memory_access_per_byte)?.into(),
+        ))
     }
 
     /// Read a temp value at the given key and return the value and the gas
@@ -277,12 +415,17 @@ impl WriteLog {
 
                 Ok((
                     Some(value),
-                    checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
+                    checked!(gas * self.gas_costs.memory_access_per_byte)?
+                        .into(),
                 ))
             }
             None => {
                 let gas = key.len() as u64;
-                Ok((None, checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into()))
+                Ok((
+                    None,
+                    checked!(gas * self.gas_costs.memory_access_per_byte)?
+                        .into(),
+                ))
             }
         }
     }
@@ -332,7 +475,7 @@ impl WriteLog {
 
         let gas = checked!(key.len() + len)? as u64;
         Ok((
-            checked!(gas * STORAGE_WRITE_GAS_PER_BYTE)?.into(),
+            checked!(gas * self.gas_costs.storage_write_per_byte)?.into(),
             size_diff,
         ))
     }
@@ -350,8 +493,7 @@ impl WriteLog {
         if self.tx_write_log.tx_temp_log.contains_key(key) {
             return Err(Error::UpdateTemporaryValue);
         }
-        if let Some(prev) = self
-            .block_write_log
+        if let Some(prev) = Arc::make_mut(&mut self.block_write_log)
             .insert(key.clone(), StorageModification::Write { value })
         {
             match prev {
@@ -412,7 +554,7 @@ impl WriteLog {
         // accessing storage
         let gas = checked!(key.len() + len)? as u64;
         Ok((
-            checked!(gas * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
+            checked!(gas * self.gas_costs.memory_access_per_byte)?.into(),
             size_diff,
         ))
     }
@@ -447,7 +589,7 @@ impl WriteLog {
             .and_then(i64::checked_neg)
             .ok_or(Error::SizeDiffOverflow)?;
         Ok((
-            checked!(gas * STORAGE_DELETE_GAS_PER_BYTE)?.into(),
+            checked!(gas * self.gas_costs.storage_delete_per_byte)?.into(),
             size_diff,
         ))
     }
@@ -459,8 +601,7 @@ impl WriteLog {
         if key.is_validity_predicate().is_some() {
             return Err(Error::DeleteVp);
         }
-        if let Some(prev) = self
-            .block_write_log
+        if let Some(prev) = Arc::make_mut(&mut self.block_write_log)
             .insert(key.clone(), StorageModification::Delete)
         {
             match prev {
@@ -493,7 +634,7 @@ impl WriteLog {
             .len()
             .checked_add(vp_code_hash.len())
             .expect("Cannot overflow")) as u64)
-            .checked_mul(STORAGE_WRITE_GAS_PER_BYTE)
+            .checked_mul(self.gas_costs.storage_write_per_byte)
             .expect("Canno overflow");
         self.tx_write_log
             .write_log
@@ -505,7 +646,8 @@ impl WriteLog {
     /// overflow.
     pub fn emit_event<E: EventToEmit>(&mut self, event: E) -> Option<Gas> {
         let event = event.into();
-        let gas_cost = event.emission_gas_cost(MEMORY_ACCESS_GAS_PER_BYTE);
+        let gas_cost =
+            event.emission_gas_cost(self.gas_costs.memory_access_per_byte);
         if gas_cost.as_ref().is_some() {
             let event_type = event.kind().to_string();
             if !self.tx_write_log.events.tree.contains_key(&event_type) {
@@ -535,6 +677,17 @@ impl WriteLog {
             .collect()
     }
 
+    /// Get the non-temporary storage keys changed by the current batch,
+    /// including the ones of the current transaction.
+    pub fn get_batch_keys(&self) -> BTreeSet<storage::Key> {
+        self.batch_write_log
+            .iter()
+            .flat_map(|log| log.write_log.keys())
+            .chain(self.tx_write_log.write_log.keys())
+            .cloned()
+            .collect()
+    }
+
     /// Get the storage keys changed in the current transaction (left) and
     /// the addresses of accounts initialized in the current transaction
     /// (right). The first vector excludes keys of validity predicates of
@@ -646,8 +799,9 @@ impl WriteLog {
     /// log which might still contain some data and needs to be handled
     /// separately.
     pub fn commit_batch_only(&mut self) {
+        let block_write_log = Arc::make_mut(&mut self.block_write_log);
         for log in std::mem::take(&mut self.batch_write_log) {
-            self.block_write_log.extend(log.write_log);
+            block_write_log.extend(log.write_log);
             self.block_address_gen = log.address_gen;
         }
     }
@@ -658,6 +812,43 @@ impl WriteLog {
         self.batch_write_log = Default::default();
     }
 
+    /// Copy the block-level state of this log into a new log that tracks all
+    /// the reads performed through it. The block-level state is shared with
+    /// this log until either of them writes to it. The batch and transaction
+    /// logs of the copy start empty.
+    pub fn speculative_copy(&self) -> Self {
+        Self {
+            block_address_gen: self.block_address_gen.clone(),
+            block_write_log: Arc::clone(&self.block_write_log),
+            batch_write_log: Vec::with_capacity(5),
+            tx_write_log: Default::default(),
+            replay_protection: Arc::clone(&self.replay_protection),
+            read_log: ReadLog::tracking(),
+            gas_costs: self.gas_costs,
+        }
+    }
+
+    /// Take the set of reads tracked by a speculative copy of the log.
+    /// Returns [`None`] if reads are not being tracked.
+    pub fn take_read_set(&mut self) -> Option<ReadSet> {
+        self.read_log.take()
+    }
+
+    /// Take the batch and transaction logs, leaving them empty.
+    pub fn take_batch(&mut self) -> BatchWriteLog {
+        BatchWriteLog {
+            batch_write_log: std::mem::take(&mut self.batch_write_log),
+            tx_write_log: std::mem::take(&mut self.tx_write_log),
+        }
+    }
+
+    /// Replace the batch and transaction logs with the ones of a batch
+    /// executed against a speculative copy of this log.
+    pub fn replace_batch(&mut self, batch: BatchWriteLog) {
+        self.batch_write_log = batch.batch_write_log;
+        self.tx_write_log = batch.tx_write_log;
+    }
+
     /// Get the verifiers set whose validity predicates should validate the
     /// current transaction changes and the storage keys that have been
     /// modified created, updated and deleted via the write log.
@@ -694,6 +885,7 @@ impl WriteLog {
     /// Iterate modifications prior to the current transaction, whose storage
     /// key matches the given prefix, sorted by their storage key.
     pub fn iter_prefix_pre(&self, prefix: &storage::Key) -> PrefixIter {
+        self.read_log.record_prefix(prefix);
         let mut matches = BTreeMap::new();
 
         for (key, modification) in self.block_write_log.iter().chain(
@@ -714,6 +906,7 @@ impl WriteLog {
     /// Iterate modifications posterior of the current tx, whose storage key
     /// matches the given prefix, sorted by their storage key.
     pub fn iter_prefix_post(&self, prefix: &storage::Key) -> PrefixIter {
+        self.read_log.record_prefix(prefix);
         let mut matches = BTreeMap::new();
 
         for (key, modification) in self.block_write_log.iter().chain(
@@ -739,7 +932,7 @@ impl WriteLog {
 
     /// Write the transaction hash
     pub fn write_tx_hash(&mut self, hash: Hash) -> Result<()> {
-        if !self.replay_protection.insert(hash) {
+        if !Arc::make_mut(&mut self.replay_protection).insert(hash) {
             // Cannot write an hash if it's already present in the set
             return Err(Error::ReplayProtection(format!(
                 "Requested a write of hash {hash} which has already been \
@@ -752,7 +945,7 @@ impl WriteLog {
 
     /// Remove the transaction hash because redundant
     pub(crate) fn redundant_tx_hash(&mut self, hash: &Hash) -> Result<()> {
-        if !self.replay_protection.swap_remove(hash) {
+        if !Arc::make_mut(&mut self.replay_protection).swap_remove(hash) {
             return Err(Error::ReplayProtection(format!(
                 "Requested a redundant modification on hash {hash} which is \
                  unknown"
@@ -767,6 +960,10 @@ impl WriteLog {
 mod tests {
     use assert_matches::assert_matches;
     use namada_core::address;
+    use namada_gas::{
+        MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_DELETE_GAS_PER_BYTE,
+        STORAGE_WRITE_GAS_PER_BYTE,
+    };
     use pretty_assertions::assert_eq;
     use proptest::prelude::*;
 
@@ -1142,6 +1339,101 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_speculative_copy() {
+        let mut write_log = WriteLog::default();
+        let key1 =
+            storage::Key::parse("key1").expect("cannot parse the key string");
+        let key2 =
+            storage::Key::parse("key2").expect("cannot parse the key string");
+        let prefix =
+            storage::Key::parse("prefix").expect("cannot parse the key string");
+        let val1 = "val1".as_bytes().to_vec();
+        let _ = write_log.write(&key1, val1.clone()).unwrap();
+        write_log.commit_batch_and_current_tx();
+
+        // reads are not tracked on the original log
+        assert!(write_log.take_read_set().is_none());
+
+        // the copy sees the block log and tracks the reads
+        let mut copy = write_log.speculative_copy();
+        assert_eq!(copy, write_log);
+        let (value, _) = copy.read(&key1).unwrap();
+        assert!(value.is_some());
+        let _ = copy.iter_prefix_post(&prefix);
+        let _ = copy.write(&key2, val1.clone()).unwrap();
+        copy.commit_tx_to_batch();
+        let _ = copy.write(&key1, val1).unwrap();
+        assert_eq!(
+            copy.get_batch_keys(),
+            BTreeSet::from([key1.clone(), key2.clone()])
+        );
+
+        let read_set = copy.take_read_set().unwrap();
+        assert!(read_set.conflicts_with(&BTreeSet::from([key1.clone()])));
+        assert!(!read_set.conflicts_with(&BTreeSet::from([key2.clone()])));
+        let prefixed = prefix.push(&"key".to_string()).unwrap();
+        assert!(read_set.conflicts_with(&BTreeSet::from([prefixed])));
+
+        // move the speculative batch back into the original log
+        write_log.replace_batch(copy.take_batch());
+        assert!(copy.get_batch_keys().is_empty());
+        assert_eq!(write_log.get_batch_keys(), BTreeSet::from([key1, key2]));
+    }
+
+    #[test]
+    fn test_speculative_copy_on_write() {
+        let mut write_log = WriteLog::default();
+        let key1 =
+            storage::Key::parse("key1").expect("cannot parse the key string");
+        let key2 =
+            storage::Key::parse("key2").expect("cannot parse the key string");
+        let val1 = "val1".as_bytes().to_vec();
+        write_log.protocol_write(&key1, val1.clone()).unwrap();
+
+        // the copy shares the block log with the original one
+        let mut copy = write_log.speculative_copy();
+        assert!(Arc::ptr_eq(
+            &copy.block_write_log,
+            &write_log.block_write_log
+        ));
+
+        // writing to either of the logs doesn't affect the other
+        write_log.protocol_write(&key2, val1.clone()).unwrap();
+        copy.protocol_delete(&key1).unwrap();
+        assert!(!Arc::ptr_eq(
+            &copy.block_write_log,
+            &write_log.block_write_log
+        ));
+        assert_matches!(
+            write_log.read(&key1).unwrap().0,
+            Some(StorageModification::Write { .. })
+        );
+        assert_matches!(
+            copy.read(&key1).unwrap().0,
+            Some(StorageModification::Delete)
+        );
+        assert!(copy.read(&key2).unwrap().0.is_none());
+    }
+
+    #[test]
+    fn test_speculative_db_reads() {
+        let state = crate::testing::TestState::default();
+        let key =
+            storage::Key::parse("key").expect("cannot parse the key string");
+        let prefix =
+            storage::Key::parse("prefix").expect("cannot parse the key string");
+
+        // the reads that bypass the write log are tracked too
+        let mut temp_state = state.with_speculative_write_log();
+        let _ = temp_state.db_read(&key).unwrap();
+        let _ = temp_state.db_iter_prefix(&prefix).unwrap();
+        let read_set = temp_state.write_log_mut().take_read_set().unwrap();
+        assert!(read_set.conflicts_with(&BTreeSet::from([key])));
+        let prefixed = prefix.push(&"key".to_string()).unwrap();
+        assert!(read_set.conflicts_with(&BTreeSet::from([prefixed])));
+    }
+
     prop_compose! {
         fn arb_verifiers_changed_key_tx_all_key()
             (verifiers_from_tx in testing::arb_verifiers_from_tx())
@@ -203,6 +203,27 @@ pub trait DB: Debug {
         is_old: bool,
     ) -> Result<Option<Vec<u8>>>;
 
+    /// Switch the DB to archive mode, in which the values of all the account
+    /// subspace keys are kept for every block committed from then on,
+    /// regardless of whether their diffs are persisted. Archive mode cannot
+    /// be turned off once it has been enabled.
+    fn enable_archive_mode(&mut self) -> Result<()>;
+
+    /// The first block height from which the full history of the account
+    /// subspace is kept, if the DB is in archive mode.
+    fn archive_start_height(&self) -> Option<BlockHeight>;
+
+    /// Read all the key-vals of the account subspace under the given `prefix`,
+    /// including the `prefix` key itself, as they were at the given height,
+    /// ordered by their keys. Only
+    /// supported in archive mode, for heights starting from
+    /// [`DB::archive_start_height`].
+    fn read_subspace_prefix_with_height(
+        &self,
+        prefix: &Key,
+        height: BlockHeight,
+    ) -> Result<Vec<(String, Vec<u8>)>>;
+
     /// Write the value with the given height and account subspace key to the
     /// DB. Returns the size difference from previous value, if any, or the
     /// size of the value otherwise.
@@ -390,6 +390,26 @@ impl DB for MockDB {
         self.read_subspace_val(key)
     }
 
+    fn enable_archive_mode(&mut self) -> Result<()> {
+        Err(Error::DBError(
+            "Archive mode is not supported by the mock DB".to_string(),
+        ))
+    }
+
+    fn archive_start_height(&self) -> Option<BlockHeight> {
+        None
+    }
+
+    fn read_subspace_prefix_with_height(
+        &self,
+        _prefix: &Key,
+        _height: BlockHeight,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        Err(Error::DBError(
+            "Archive mode is not supported by the mock DB".to_string(),
+        ))
+    }
+
     fn write_subspace_val(
         &mut self,
         height: BlockHeight,
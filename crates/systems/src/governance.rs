@@ -1,7 +1,27 @@
 //! Governance abstract interfaces
 
+use namada_core::address::Address;
+use namada_core::storage;
 pub use namada_storage::Result;
 
+/// Abstract governance keys interface
+pub trait Keys {
+    /// Key for the vote of an account on a proposal
+    fn account_vote_key(proposal_id: u64, voter: &Address) -> storage::Key;
+
+    /// Key for the vote of a delegator on a proposal, applied to its bonds to
+    /// the given validator
+    fn delegation_vote_key(
+        proposal_id: u64,
+        voter: &Address,
+        validator: &Address,
+    ) -> storage::Key;
+
+    /// Check if the given storage key is any of the vote keys of a proposal.
+    /// If it is, return the proposal ID and the voter address.
+    fn is_any_vote_key(key: &storage::Key) -> Option<(u64, &Address)>;
+}
+
 /// Abstract governance storage read interface
 pub trait Read<S> {
     /// Check if an accepted proposal is being executed
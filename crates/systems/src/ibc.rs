@@ -1,14 +1,16 @@
 //! IBC abstract interfaces
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
 
 use masp_primitives::transaction::components::ValueSum;
 use masp_primitives::transaction::TransparentAddress;
 use namada_core::address::Address;
-use namada_core::borsh::BorshDeserialize;
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::masp::TAddrData;
 use namada_core::{masp_primitives, storage, token};
 pub use namada_storage::Result;
+use serde::{Deserialize, Serialize};
 
 /// Abstract IBC storage read interface
 pub trait Read<S> {
@@ -26,6 +28,161 @@ pub trait Read<S> {
     ) -> Result<ChangedBalances>;
 }
 
+/// Abstract IBC storage write interface
+pub trait Write<S>: Read<S> {
+    /// Apply a change of a single IBC parameter
+    fn apply_param_change(storage: &mut S, change: &ParamChange) -> Result<()>;
+}
+
+/// A change of a single IBC parameter that can be applied by a governance
+/// proposal
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum ParamChange {
+    /// Set the default supply limit of each token
+    DefaultMintLimit(token::Amount),
+    /// Set the default per-epoch throughput limit of each token
+    DefaultPerEpochThroughputLimit(token::Amount),
+    /// Set the rolling-window rate limits of a token
+    RateLimit(RateLimitChange),
+}
+
+impl ParamChange {
+    /// Check that the new value is in its valid range. Any amount is a valid
+    /// limit.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::DefaultMintLimit(_)
+            | Self::DefaultPerEpochThroughputLimit(_) => true,
+            Self::RateLimit(change) => change
+                .channel_id
+                .as_ref()
+                .map_or(true, |channel_id| !channel_id.is_empty()),
+        }
+    }
+
+    /// Check if both of the changes apply to the same parameter. The rate
+    /// limits of different tokens or channels are different parameters.
+    pub fn is_same_parameter(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::RateLimit(a), Self::RateLimit(b)) => {
+                a.token == b.token && a.channel_id == b.channel_id
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+/// Rolling-window quotas of a token. `None` means no limit.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RateLimits {
+    /// Inbound quota in the last hour
+    pub hourly_inbound: Option<token::Amount>,
+    /// Outbound quota in the last hour
+    pub hourly_outbound: Option<token::Amount>,
+    /// Inbound quota in the last day
+    pub daily_inbound: Option<token::Amount>,
+    /// Outbound quota in the last day
+    pub daily_outbound: Option<token::Amount>,
+}
+
+impl Display for RateLimits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = |limit: &Option<token::Amount>| match limit {
+            Some(amount) => amount.to_string(),
+            None => "unlimited".to_string(),
+        };
+        write!(
+            f,
+            "hourly inbound {}, hourly outbound {}, daily inbound {}, daily \
+             outbound {}",
+            fmt(&self.hourly_inbound),
+            fmt(&self.hourly_outbound),
+            fmt(&self.daily_inbound),
+            fmt(&self.daily_outbound),
+        )
+    }
+}
+
+/// A change of the rate limits of a token over all the channels, or over a
+/// channel when the channel ID is given
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RateLimitChange {
+    /// The token
+    pub token: Address,
+    /// The channel ID on this chain of the per-channel override
+    pub channel_id: Option<String>,
+    /// The new limits
+    pub limits: RateLimits,
+}
+
+impl Display for ParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DefaultMintLimit(value) => {
+                write!(f, "default_mint_limit = {value}")
+            }
+            Self::DefaultPerEpochThroughputLimit(value) => {
+                write!(f, "default_per_epoch_throughput_limit = {value}")
+            }
+            Self::RateLimit(change) => match &change.channel_id {
+                Some(channel_id) => write!(
+                    f,
+                    "rate_limit[{}/{channel_id}] = {}",
+                    change.token, change.limits
+                ),
+                None => write!(
+                    f,
+                    "rate_limit[{}] = {}",
+                    change.token, change.limits
+                ),
+            },
+        }
+    }
+}
+
 /// Balances changed by a transaction
 #[derive(Default, Debug, Clone)]
 pub struct ChangedBalances {
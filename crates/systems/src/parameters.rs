@@ -1,10 +1,17 @@
 //! Parameters abstract interfaces
 
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::address::Address;
 use namada_core::chain::BlockHeight;
 pub use namada_core::parameters::*;
-use namada_core::storage;
 use namada_core::time::DurationSecs;
+use namada_core::{storage, token};
+pub use namada_gas::GasCostSchedule;
 pub use namada_storage::Result;
+use serde::{Deserialize, Serialize};
 
 /// Abstract parameters storage keys interface
 pub trait Keys {
@@ -29,6 +36,9 @@ pub trait Read<S> {
     /// Read the number of epochs per year parameter
     fn epochs_per_year(storage: &S) -> Result<u64>;
 
+    /// Read the active gas cost schedule
+    fn gas_cost_schedule(storage: &S) -> Result<GasCostSchedule>;
+
     /// Return an estimate of the maximum time taken to decide a block,
     /// by sourcing block headers from up to `num_blocks_to_read`, and
     /// from chain parameters.
@@ -43,4 +53,141 @@ pub trait Read<S> {
 pub trait Write<S>: Read<S> {
     /// Write all parameters
     fn write(storage: &mut S, parameters: &Parameters) -> Result<()>;
+
+    /// Write a gas cost schedule to be activated at the end of the block
+    fn write_pending_gas_cost_schedule(
+        storage: &mut S,
+        schedule: &GasCostSchedule,
+    ) -> Result<()>;
+
+    /// Apply a change of a single parameter
+    fn apply_change(storage: &mut S, change: &ParamChange) -> Result<()> {
+        if let ParamChange::GasCostSchedule(schedule) = change {
+            return Self::write_pending_gas_cost_schedule(storage, schedule);
+        }
+        let mut parameters = Self::read(storage)?;
+        change.apply(&mut parameters);
+        Self::write(storage, &parameters)
+    }
+}
+
+/// A change of a single protocol parameter that can be applied by a
+/// governance proposal. The parameters that are read-only or that must be kept
+/// in sync with CometBFT are not included.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum ParamChange {
+    /// Set the max payload size, in bytes, for a mempool tx
+    MaxTxBytes(u32),
+    /// Set the max gas for block
+    MaxBlockGas(u64),
+    /// Set the allowed validity predicate hashes
+    VpAllowlist(Vec<String>),
+    /// Set the allowed tx hashes
+    TxAllowlist(Vec<String>),
+    /// Set the gas limit for a masp transaction paying fees
+    MaspFeePaymentGasLimit(u64),
+    /// Set the gas scale
+    GasScale(u64),
+    /// Set the cost per gas unit for every token allowed for fee payment
+    MinimumGasPrice(BTreeMap<Address, token::Amount>),
+    /// Enable or disable the native token transfers
+    IsNativeTokenTransferable(bool),
+    /// Enable or disable the fee priority ordering check of block proposals
+    EnforceFeePriorityOrdering(bool),
+    /// Set the gas cost schedule, from the next block
+    GasCostSchedule(GasCostSchedule),
+}
+
+impl ParamChange {
+    /// Check that the new value is in its valid range. The tx size, the block
+    /// gas, the gas scale and the gas costs must not be zero.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::MaxTxBytes(value) => *value > 0,
+            Self::MaxBlockGas(value) | Self::GasScale(value) => *value > 0,
+            Self::GasCostSchedule(value) => value.is_valid(),
+            Self::VpAllowlist(_)
+            | Self::TxAllowlist(_)
+            | Self::MaspFeePaymentGasLimit(_)
+            | Self::MinimumGasPrice(_)
+            | Self::IsNativeTokenTransferable(_)
+            | Self::EnforceFeePriorityOrdering(_) => true,
+        }
+    }
+
+    /// Apply the change to the given parameters. The gas cost schedule isn't
+    /// part of the parameters, it's stored separately.
+    pub fn apply(&self, parameters: &mut Parameters) {
+        match self {
+            Self::MaxTxBytes(value) => parameters.max_tx_bytes = *value,
+            Self::MaxBlockGas(value) => parameters.max_block_gas = *value,
+            Self::VpAllowlist(value) => parameters.vp_allowlist = value.clone(),
+            Self::TxAllowlist(value) => parameters.tx_allowlist = value.clone(),
+            Self::MaspFeePaymentGasLimit(value) => {
+                parameters.masp_fee_payment_gas_limit = *value
+            }
+            Self::GasScale(value) => parameters.gas_scale = *value,
+            Self::MinimumGasPrice(value) => {
+                parameters.minimum_gas_price = value.clone()
+            }
+            Self::IsNativeTokenTransferable(value) => {
+                parameters.is_native_token_transferable = *value
+            }
+            Self::EnforceFeePriorityOrdering(value) => {
+                parameters.enforce_fee_priority_ordering = *value
+            }
+            Self::GasCostSchedule(_) => {}
+        }
+    }
+}
+
+impl Display for ParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MaxTxBytes(value) => write!(f, "max_tx_bytes = {value}"),
+            Self::MaxBlockGas(value) => write!(f, "max_block_gas = {value}"),
+            Self::VpAllowlist(value) => {
+                write!(f, "vp_allowlist = [{}]", value.join(", "))
+            }
+            Self::TxAllowlist(value) => {
+                write!(f, "tx_allowlist = [{}]", value.join(", "))
+            }
+            Self::MaspFeePaymentGasLimit(value) => {
+                write!(f, "masp_fee_payment_gas_limit = {value}")
+            }
+            Self::GasScale(value) => write!(f, "gas_scale = {value}"),
+            Self::MinimumGasPrice(value) => write!(
+                f,
+                "minimum_gas_price = [{}]",
+                value
+                    .iter()
+                    .map(|(token, amount)| format!("{token}: {amount}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::IsNativeTokenTransferable(value) => {
+                write!(f, "is_native_token_transferable = {value}")
+            }
+            Self::EnforceFeePriorityOrdering(value) => {
+                write!(f, "enforce_fee_priority_ordering = {value}")
+            }
+            Self::GasCostSchedule(value) => {
+                write!(f, "gas_cost_schedule = {value:?}")
+            }
+        }
+    }
 }
@@ -1,9 +1,15 @@
 //! Proof-of-Stake abstract interfaces
 
+use std::fmt::Display;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::address::Address;
 use namada_core::chain::Epoch;
+use namada_core::collections::HashSet;
+use namada_core::dec::Dec;
 use namada_core::token;
 pub use namada_storage::Result;
+use serde::{Deserialize, Serialize};
 
 use crate::governance;
 
@@ -63,4 +69,123 @@ pub trait Read<S> {
     ) -> Result<token::Amount>
     where
         Gov: governance::Read<S>;
+
+    /// Find all the validators to which the delegator has a delegation at the
+    /// given epoch
+    fn delegation_validators(
+        storage: &S,
+        delegator: &Address,
+        epoch: Epoch,
+    ) -> Result<HashSet<Address>>;
+}
+
+/// Abstract PoS storage write interface
+pub trait Write<S>: Read<S> {
+    /// Apply a change of a single PoS-owned parameter
+    fn apply_param_change(storage: &mut S, change: &ParamChange) -> Result<()>;
+}
+
+/// A change of a single PoS-owned parameter that can be applied by a
+/// governance proposal. The parameters that determine the layout of the PoS
+/// data (e.g. the pipeline and unbonding lengths or the number of validator
+/// slots) are not included, because changing them requires a migration of
+/// the existing data.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum ParamChange {
+    /// Set the amount of tokens rewarded to a validator for proposing a block
+    BlockProposerReward(Dec),
+    /// Set the amount of tokens rewarded to each validator that voted on a
+    /// block proposal
+    BlockVoteReward(Dec),
+    /// Set the maximum staking rewards rate per annum
+    MaxInflationRate(Dec),
+    /// Set the target ratio of staked NAM tokens to total NAM tokens
+    TargetStakedRatio(Dec),
+    /// Set the fraction of validator's stake that should be slashed on a
+    /// duplicate vote
+    DuplicateVoteMinSlashRate(Dec),
+    /// Set the fraction of validator's stake that should be slashed on a
+    /// light client attack
+    LightClientAttackMinSlashRate(Dec),
+    /// Set the minimum amount of bonded tokens that a validator needs to be in
+    /// either the `consensus` or `below_capacity` validator sets
+    ValidatorStakeThreshold(token::Amount),
+    /// Set the minimum required activity of consensus validators over the
+    /// liveness window
+    LivenessThreshold(Dec),
+    /// Set the PoS gain p
+    RewardsGainP(Dec),
+    /// Set the PoS gain d
+    RewardsGainD(Dec),
+}
+
+impl ParamChange {
+    /// Check that the new value is in its valid range. The rewards gains must
+    /// not be negative and all the other rates must be between `0` and `1`.
+    pub fn is_valid(&self) -> bool {
+        let is_valid_rate =
+            |rate: &Dec| !rate.is_negative() && *rate <= Dec::one();
+        match self {
+            Self::BlockProposerReward(rate)
+            | Self::BlockVoteReward(rate)
+            | Self::MaxInflationRate(rate)
+            | Self::TargetStakedRatio(rate)
+            | Self::DuplicateVoteMinSlashRate(rate)
+            | Self::LightClientAttackMinSlashRate(rate)
+            | Self::LivenessThreshold(rate) => is_valid_rate(rate),
+            Self::RewardsGainP(gain) | Self::RewardsGainD(gain) => {
+                !gain.is_negative()
+            }
+            Self::ValidatorStakeThreshold(_) => true,
+        }
+    }
+}
+
+impl Display for ParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlockProposerReward(value) => {
+                write!(f, "block_proposer_reward = {value}")
+            }
+            Self::BlockVoteReward(value) => {
+                write!(f, "block_vote_reward = {value}")
+            }
+            Self::MaxInflationRate(value) => {
+                write!(f, "max_inflation_rate = {value}")
+            }
+            Self::TargetStakedRatio(value) => {
+                write!(f, "target_staked_ratio = {value}")
+            }
+            Self::DuplicateVoteMinSlashRate(value) => {
+                write!(f, "duplicate_vote_min_slash_rate = {value}")
+            }
+            Self::LightClientAttackMinSlashRate(value) => {
+                write!(f, "light_client_attack_min_slash_rate = {value}")
+            }
+            Self::ValidatorStakeThreshold(value) => write!(
+                f,
+                "validator_stake_threshold = {}",
+                value.to_string_native()
+            ),
+            Self::LivenessThreshold(value) => {
+                write!(f, "liveness_threshold = {value}")
+            }
+            Self::RewardsGainP(value) => write!(f, "rewards_gain_p = {value}"),
+            Self::RewardsGainD(value) => write!(f, "rewards_gain_d = {value}"),
+        }
+    }
 }
@@ -84,6 +84,15 @@ pub trait Write<S>: Read<S> {
         amount: token::Amount,
     ) -> Result<()>;
 
+    /// Mint `amount` of `token` as `minter` to `dest`.
+    fn mint_tokens(
+        storage: &mut S,
+        minter: &Address,
+        token: &Address,
+        dest: &Address,
+        amount: token::Amount,
+    ) -> Result<()>;
+
     /// Credit tokens to an account, to be used only by protocol. In
     /// transactions, this would get rejected by the default `vp_token`.
     fn credit_tokens(
@@ -24,9 +24,14 @@ use namada_node::shell::SnapshotSync;
 use namada_node::storage::DbSnapshot;
 use namada_sdk::account::AccountPublicKeysMap;
 use namada_sdk::collections::HashMap;
+use namada_sdk::events::extend::Height as HeightAttr;
+use namada_sdk::events::subscription::{
+    ClientTransport, EventFilter, EventSubscription, TypedEvent,
+};
 use namada_sdk::migrations;
 use namada_sdk::queries::RPC;
 use namada_sdk::token::{self, DenominatedAmount};
+use namada_sdk::tx::event::types::APPLIED as APPLIED_TX;
 use namada_sdk::tx::{self, Tx, TX_TRANSFER_WASM, VP_USER_WASM};
 use namada_test_utils::TestWasms;
 use test_log::test;
@@ -2305,6 +2310,66 @@ fn make_migration_json() -> (Hash, tempfile::NamedTempFile) {
     (hash, file)
 }
 
+/// Test that an event subscription driven by a mock node only delivers the
+/// events of the blocks finalized after it was created, and that each block
+/// is only visited once.
+#[test]
+fn event_subscription() -> Result<()> {
+    // This address doesn't matter for tests. But an argument is required.
+    let validator_one_rpc = "http://127.0.0.1:26567";
+
+    let (node, _services) = setup::setup()?;
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let start_height = node.block_height().next_height();
+    let filter = EventFilter::new()
+        .of_event_type(APPLIED_TX)
+        .from_height(start_height);
+    let mut subscription =
+        EventSubscription::new(ClientTransport(&node), filter);
+
+    // 1. No blocks were finalized since the subscription was created
+    let events = rt.block_on(subscription.poll()).expect("Test failed");
+    assert!(events.is_empty());
+
+    // 2. Submit a token transfer tx
+    let tx_args = apply_use_device(vec![
+        "transparent-transfer",
+        "--source",
+        BERTHA,
+        "--target",
+        ALBERT,
+        "--token",
+        NAM,
+        "--amount",
+        "10.1",
+        "--signing-keys",
+        BERTHA_KEY,
+        "--node",
+        &validator_one_rpc,
+    ]);
+    let captured = CapturedOutput::of(|| run(&node, Bin::Client, tx_args));
+    assert_matches!(captured.result, Ok(_));
+    assert!(captured.contains(TX_APPLIED_SUCCESS));
+
+    // 3. The subscription delivers the applied tx event
+    let events = rt.block_on(subscription.poll()).expect("Test failed");
+    assert_eq!(events.len(), 1);
+    let TypedEvent::TxApplied(event) = &events[0] else {
+        panic!("Unexpected event: {:?}", events[0]);
+    };
+    let height = event.read_attribute::<HeightAttr>().expect("Test failed");
+    assert!(height >= start_height && height <= node.block_height());
+
+    // 4. Finalizing an empty block yields no new events, and the block of
+    // the transfer is not visited again
+    node.finalize_and_commit(None);
+    let events = rt.block_on(subscription.poll()).expect("Test failed");
+    assert!(events.is_empty());
+
+    Ok(())
+}
+
 pub fn find_file_with_ext(
     dir: &Path,
     extension: &str,
@@ -1,5 +1,6 @@
 use core::time::Duration;
 use std::cell::RefCell;
+use std::str::FromStr;
 
 use ibc_testkit::testapp::ibc::clients::mock::client_state::{
     client_type, MockClientState,
@@ -52,6 +53,7 @@ use namada_sdk::ibc::core::connection::types::{
 pub use namada_sdk::ibc::core::host::types::identifiers::{
     ChannelId, ClientId, ConnectionId, PortId, Sequence,
 };
+use namada_sdk::ibc::ica::{self, HostMsg, InterchainAccountPacketData};
 use namada_sdk::ibc::parameters::IbcParameters;
 use namada_sdk::ibc::primitives::proto::{Any, Protobuf};
 use namada_sdk::ibc::primitives::Timestamp;
@@ -382,6 +384,46 @@ pub fn prepare_opened_channel(
     (port_id, channel_id, writes)
 }
 
+pub fn prepare_opened_ica_host_channel(
+    conn_id: &ConnectionId,
+    owner: &Address,
+) -> (PortId, ChannelId, Address, HashMap<storage::Key, Vec<u8>>) {
+    let mut writes = HashMap::new();
+
+    // port
+    let port_id = PortId::from_str(ica::HOST_PORT_ID_STR).unwrap();
+    let key = port_key(&port_id);
+    writes.insert(key, 0_u64.to_be_bytes().to_vec());
+    // channel
+    let channel_id = ChannelId::new(0);
+    let key = channel_key(&port_id, &channel_id);
+    let controller_port_id = ica::controller_port_id(owner);
+    let counterparty = ChanCounterparty::new(
+        controller_port_id.clone(),
+        Some(channel_id.clone()),
+    );
+    let channel = ChannelEnd::new(
+        ChanState::Open,
+        Order::Ordered,
+        counterparty,
+        vec![conn_id.clone()],
+        ChanVersion::new(ica::VERSION.to_string()),
+    )
+    .expect("invalid channel");
+    writes.insert(key, channel.encode_vec());
+    // interchain account
+    let key = ibc::storage::ica_host_channel_key(conn_id, &controller_port_id);
+    writes.insert(key, channel_id.to_string().serialize_to_vec());
+    let account = address::interchain_account(
+        conn_id.as_str(),
+        controller_port_id.as_str(),
+    );
+    let key = ibc::storage::ica_host_account_key(conn_id, &controller_port_id);
+    writes.insert(key, account.serialize_to_vec());
+
+    (port_id, channel_id, account, writes)
+}
+
 pub fn msg_create_client() -> MsgCreateClient {
     let (client_state, consensus_state) = dummy_client();
     MsgCreateClient {
@@ -614,6 +656,7 @@ pub fn msg_transfer(
     MsgTransfer {
         message,
         transfer: None,
+        fee: None,
     }
 }
 
@@ -674,6 +717,30 @@ pub fn received_packet(
     }
 }
 
+pub fn received_ica_packet(
+    port_id: PortId,
+    channel_id: ChannelId,
+    sequence: Sequence,
+    owner: &Address,
+    msgs: Vec<HostMsg>,
+) -> Packet {
+    let timestamp = (Timestamp::now() + Duration::from_secs(100)).unwrap();
+    let data = InterchainAccountPacketData::execute_tx(
+        msgs.into_iter().map(Any::from).collect(),
+        "memo".to_string(),
+    );
+    Packet {
+        seq_on_a: sequence,
+        port_id_on_a: ica::controller_port_id(owner),
+        chan_id_on_a: ChannelId::new(0),
+        port_id_on_b: port_id,
+        chan_id_on_b: channel_id,
+        data: serde_json::to_vec(&data).unwrap(),
+        timeout_height_on_b: TimeoutHeight::Never,
+        timeout_timestamp_on_b: TimeoutTimestamp::At(timestamp),
+    }
+}
+
 pub fn msg_timeout(packet: Packet, next_sequence_recv: Sequence) -> MsgTimeout {
     MsgTimeout {
         packet,
@@ -740,3 +807,9 @@ pub fn transfer_ack_with_error() -> AcknowledgementStatus {
         .expect("Empty message"),
     )
 }
+
+pub fn ica_ack_with_error() -> AcknowledgementStatus {
+    AcknowledgementStatus::error(
+        StatusValue::new(ica::HOST_MSGS_FAILURE).expect("Empty message"),
+    )
+}
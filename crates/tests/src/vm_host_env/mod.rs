@@ -29,6 +29,7 @@ mod tests {
     use namada_sdk::hash::Hash;
     use namada_sdk::ibc::context::nft_transfer_mod::testing::DummyNftTransferModule;
     use namada_sdk::ibc::context::transfer_mod::testing::DummyTransferModule;
+    use namada_sdk::ibc::ica::{HostMsg, MsgHostTransfer};
     use namada_sdk::ibc::primitives::ToProto;
     use namada_sdk::ibc::{
         storage as ibc_storage, trace as ibc_trace, Error as IbcActionError,
@@ -1555,6 +1556,116 @@ mod tests {
         assert_eq!(changed_keys, expected_changed_keys);
     }
 
+    #[test]
+    fn test_ibc_ica_receive_failed_host_msgs() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        let keypair = key::testing::keypair_1();
+        let keypairs = vec![keypair.clone()];
+        let pks_map = AccountPublicKeysMap::from_iter([
+            key::testing::keypair_1().ref_to(),
+        ]);
+
+        // Set the initial state before starting transactions
+        let (token, receiver) = ibc::init_storage();
+        let (client_id, _client_state, mut writes) = ibc::prepare_client();
+        let (conn_id, conn_writes) = ibc::prepare_opened_connection(&client_id);
+        writes.extend(conn_writes);
+        let owner = address::testing::gen_established_address();
+        let (port_id, channel_id, account, channel_writes) =
+            ibc::prepare_opened_ica_host_channel(&conn_id, &owner);
+        writes.extend(channel_writes);
+
+        writes.into_iter().for_each(|(key, val)| {
+            tx_host_env::with(|env| {
+                env.state.write_bytes(&key, &val).expect("write error");
+            });
+        });
+        let account_balance_key =
+            token::storage_key::balance_key(&token, &account);
+        let receiver_balance_key =
+            token::storage_key::balance_key(&token, &receiver);
+        let account_balance = Amount::native_whole(50);
+        let receiver_balance: Option<Amount> = tx_host_env::with(|env| {
+            env.state
+                .write(&account_balance_key, account_balance)
+                .expect("write error");
+            env.state.read(&receiver_balance_key).expect("read error")
+        });
+
+        // packet whose second transfer exceeds the balance of the account
+        let transfer = HostMsg::Transfer(MsgHostTransfer {
+            target: receiver.clone(),
+            token: token.clone(),
+            amount: Amount::native_whole(50),
+        });
+        let sequence = ibc::Sequence::from(1);
+        let packet = ibc::received_ica_packet(
+            port_id.clone(),
+            channel_id.clone(),
+            sequence,
+            &owner,
+            vec![transfer.clone(), transfer],
+        );
+
+        // Start a transaction to receive a packet
+        let msg = ibc::msg_packet_recv(packet);
+        let mut tx_data = vec![];
+        msg.to_any().encode(&mut tx_data).expect("encoding failed");
+
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.add_code(vec![], None)
+            .add_serialized_data(tx_data.clone())
+            .sign_raw(keypairs, pks_map, None)
+            .sign_wrapper(keypair);
+        // Receive the packet, but the host messages fail
+        tx_host_env::ibc::ibc_actions(tx::ctx())
+            .execute::<token::Transfer>(&tx_data)
+            .expect("receiving the packet failed");
+
+        // Check if the transaction is valid
+        let env = tx_host_env::take();
+        let result = ibc::validate_ibc_vp_from_tx(
+            &env,
+            &tx.batch_ref_first_tx().unwrap(),
+        );
+        assert!(
+            result.is_ok(),
+            "Expected VP to accept the tx, got {result:?}"
+        );
+        // Check if the ack has an error due to the failed host messages
+        tx_host_env::set(env);
+        let ack_key = ibc_storage::ack_key(&port_id, &channel_id, sequence);
+        let ack = tx_host_env::with(|env| {
+            env.state.read_bytes(&ack_key).expect("read error").unwrap()
+        });
+        let expected_ack =
+            Hash::sha256(Vec::<u8>::from(ibc::ica_ack_with_error())).to_vec();
+        assert_eq!(ack, expected_ack);
+        // Check if the first transfer was reverted
+        let balances: (Option<Amount>, Option<Amount>) =
+            tx_host_env::with(|env| {
+                (
+                    env.state.read(&account_balance_key).expect("read error"),
+                    env.state.read(&receiver_balance_key).expect("read error"),
+                )
+            });
+        assert_eq!(balances, (Some(account_balance), receiver_balance));
+        // Check if only the ack and the next sequence are added
+        let next_seq_recv_key =
+            ibc_storage::next_sequence_recv_key(&port_id, &channel_id);
+        let changed_keys = tx_host_env::with(|env| {
+            env.state
+                .write_log()
+                .verifiers_and_changed_keys(&BTreeSet::new())
+                .1
+        });
+        let expected_changed_keys =
+            BTreeSet::from([ack_key, next_seq_recv_key]);
+        assert_eq!(changed_keys, expected_changed_keys);
+    }
+
     #[test]
     fn test_ibc_unescrow_token() {
         // The environment must be initialized first
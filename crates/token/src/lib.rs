@@ -18,10 +18,12 @@
     clippy::print_stderr
 )]
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use namada_core::address::Address;
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::chain::Epoch;
+use namada_core::hash::Hash;
 use namada_events::EmitEvents;
 use namada_macros::BorshDeserializer;
 #[cfg(feature = "migrations")]
@@ -109,10 +111,11 @@ where
     Ok(())
 }
 
-/// Apply token logic for finalizing block (i.e. shielded token rewards)
+/// Apply token logic for finalizing block (i.e. shielded token rewards and
+/// scheduled transfers)
 pub fn finalize_block<S, Params>(
     storage: &mut S,
-    _events: &mut impl EmitEvents,
+    events: &mut impl EmitEvents,
     is_new_masp_epoch: bool,
 ) -> Result<()>
 where
@@ -122,7 +125,7 @@ where
     if is_new_masp_epoch {
         conversion::update_allowed_conversions::<S, Params, Store<S>>(storage)?;
     }
-    Ok(())
+    namada_trans_token::execute_scheduled_transfers(storage, events)
 }
 
 /// Accounts can send or receive funds in a transparent token transfer
@@ -175,6 +178,85 @@ pub struct Transfer {
     pub shielded_section_hash: Option<MaspTxId>,
 }
 
+/// Arguments for pre-authorizing a budget to pay the fees of wrapper txs
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct SponsorFees {
+    /// The sponsor paying the fees
+    pub sponsor: Address,
+    /// The token in which the fees are paid
+    pub token: Address,
+    /// The total budget. A zero budget revokes the sponsorship.
+    pub budget: Amount,
+    /// The maximum amount of fees paid in a single epoch
+    pub epoch_cap: Amount,
+    /// The hashes of the tx codes whose fees are paid
+    pub code_hashes: BTreeSet<Hash>,
+    /// The fee payers whose fees are paid. Any fee payer if empty.
+    pub targets: BTreeSet<Address>,
+}
+
+/// Arguments for scheduling a transfer executed by the protocol at a regular
+/// interval of epochs
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ScheduleTransfer {
+    /// The source of the transfer
+    pub source: Address,
+    /// The target of the transfer
+    pub target: Address,
+    /// The transferred token
+    pub token: Address,
+    /// The amount transferred on each execution
+    pub amount: Amount,
+    /// The epoch of the first execution
+    pub start_epoch: Epoch,
+    /// The number of epochs between two executions
+    pub interval: u64,
+    /// The number of executions
+    pub count: u64,
+}
+
+/// Arguments for cancelling a scheduled transfer
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct CancelScheduledTransfer {
+    /// The source of the scheduled transfer
+    pub source: Address,
+    /// The id of the scheduled transfer
+    pub id: u64,
+}
+
 /// References to the transparent sections of a [`Transfer`].
 #[derive(Debug, Clone)]
 pub struct TransparentTransfersRef<'a> {
@@ -123,6 +123,16 @@ where
         storage::burn_tokens(storage, token, source, amount)
     }
 
+    fn mint_tokens(
+        storage: &mut S,
+        minter: &Address,
+        token: &Address,
+        dest: &Address,
+        amount: Amount,
+    ) -> Result<()> {
+        storage::mint_tokens(storage, minter, token, dest, amount)
+    }
+
     fn credit_tokens(
         storage: &mut S,
         token: &Address,
@@ -233,3 +243,94 @@ where
         Ok(())
     }
 }
+
+/// Maximum number of scheduled transfers executed in a single block. This
+/// bounds the extra work done in block finalization, the remaining due
+/// transfers are executed in the following blocks.
+pub const MAX_SCHEDULED_TRANSFERS_PER_BLOCK: usize = 256;
+
+/// Execute the scheduled transfers that are due in the current epoch, the
+/// earliest first. At most [`MAX_SCHEDULED_TRANSFERS_PER_BLOCK`] transfers are
+/// executed, the rest is left for the following blocks. A transfer that
+/// fails, e.g. on an insufficient balance, is skipped until its next
+/// execution.
+pub fn execute_scheduled_transfers<S>(
+    storage: &mut S,
+    events: &mut impl EmitEvents,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let current_epoch = storage.get_block_epoch()?;
+    let prefix = storage_key::scheduled_transfer_due_prefix();
+    let mut due = Vec::new();
+    for entry in namada_state::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _) = entry?;
+        let Some((epoch, source, id)) =
+            storage_key::is_scheduled_transfer_due_key(&key)
+        else {
+            continue;
+        };
+        if epoch > current_epoch
+            || due.len() >= MAX_SCHEDULED_TRANSFERS_PER_BLOCK
+        {
+            break;
+        }
+        due.push((key.clone(), source.clone(), id));
+    }
+
+    for (due_key, source, id) in due {
+        let Some(order) = read_scheduled_transfer(storage, &source, id)? else {
+            // The index entry of a deleted transfer
+            storage.delete(&due_key)?;
+            continue;
+        };
+        let token::ScheduledTransfer {
+            token,
+            amount,
+            target,
+            ..
+        } = &order;
+        let balance = read_balance(storage, token, &source)?;
+        let locked = read_locked_amount(storage, token, &source)?;
+        let result = if balance
+            .checked_sub(*amount)
+            .is_some_and(|unlocked| unlocked >= locked)
+        {
+            transfer(storage, token, &source, target, *amount)
+        } else {
+            Err(Error::new_alloc(format!(
+                "{source} has insufficient unlocked balance"
+            )))
+        };
+        match result {
+            Ok(()) => {
+                let source_balance = read_balance(storage, token, &source)?;
+                let target_balance = read_balance(storage, token, target)?;
+                events.emit(TokenEvent {
+                    descriptor: "scheduled-transfer".into(),
+                    level: EventLevel::Block,
+                    operation: TokenOperation::transfer(
+                        UserAccount::Internal(source.clone()),
+                        UserAccount::Internal(target.clone()),
+                        token.clone(),
+                        (*amount).into(),
+                        source_balance.into(),
+                        Some(target_balance.into()),
+                    ),
+                });
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to execute the scheduled transfer {id} of \
+                     {source}: {err}"
+                );
+            }
+        }
+        match order.next() {
+            Some(next) => write_scheduled_transfer(storage, &source, id, next)?,
+            None => delete_scheduled_transfer(storage, &source, id)?,
+        }
+    }
+    Ok(())
+}
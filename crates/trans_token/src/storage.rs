@@ -1,7 +1,13 @@
+use std::collections::BTreeMap;
+
 use namada_core::address::{Address, InternalAddress};
 use namada_core::hints;
 pub use namada_core::storage::Key;
-use namada_core::token::{self, Amount, AmountError, DenominatedAmount};
+use namada_core::storage::{DbKeySeg, KeySeg};
+use namada_core::token::{
+    self, Amount, AmountError, DenominatedAmount, FeeSponsorship,
+    ScheduledTransfer, VestingSchedule,
+};
 
 use crate::storage_key::*;
 use crate::{Error, Result, ResultExt, StorageRead, StorageWrite};
@@ -185,6 +191,10 @@ where
         Address::Internal(InternalAddress::IbcToken(_)) => {
             return Ok(Some(0u8.into()));
         }
+        Address::Internal(InternalAddress::LiquidShare(_)) => {
+            // Liquid staking shares use the denomination of the native token
+            return Ok(Some(token::NATIVE_MAX_DECIMAL_PLACES.into()));
+        }
         token => (denom_key(token), false),
     };
     storage.read(&key).map(|opt_denom| {
@@ -216,6 +226,210 @@ where
     storage.write(&key, denom)
 }
 
+/// Read the vesting schedule of a given token and owner, if any.
+pub fn read_vesting_schedule<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+) -> Result<Option<VestingSchedule>>
+where
+    S: StorageRead,
+{
+    storage.read(&vesting_key(token, owner))
+}
+
+/// Write the vesting schedule of a given token and owner. This is only
+/// allowed in genesis or by governance, the multitoken VP rejects any other
+/// changes to vesting schedules.
+pub fn write_vesting_schedule<S>(
+    storage: &mut S,
+    token: &Address,
+    owner: &Address,
+    schedule: VestingSchedule,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !schedule.is_valid() {
+        return Err(Error::new_alloc(format!(
+            "Invalid vesting schedule of {owner} for token {token}"
+        )));
+    }
+    storage.write(&vesting_key(token, owner), schedule)
+}
+
+/// Read the amount of a given token and owner that is still locked by a
+/// vesting schedule at the current epoch.
+pub fn read_locked_amount<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let Some(schedule) = read_vesting_schedule(storage, token, owner)? else {
+        return Ok(token::Amount::zero());
+    };
+    let epoch = storage.get_block_epoch()?;
+    schedule
+        .locked_at(epoch)
+        .ok_or(AmountError::Overflow)
+        .into_storage_result()
+}
+
+/// Read the amount of a given token and owner that has vested at the current
+/// epoch. Returns `None` if the balance is not subject to a vesting schedule.
+pub fn read_vested_amount<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+) -> Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    let Some(schedule) = read_vesting_schedule(storage, token, owner)? else {
+        return Ok(None);
+    };
+    let epoch = storage.get_block_epoch()?;
+    schedule
+        .vested_at(epoch)
+        .ok_or(AmountError::Overflow)
+        .into_storage_result()
+        .map(Some)
+}
+
+/// Read the fee sponsorship of a given sponsor, if any.
+pub fn read_fee_sponsorship<S>(
+    storage: &S,
+    sponsor: &Address,
+) -> Result<Option<FeeSponsorship>>
+where
+    S: StorageRead,
+{
+    storage.read(&fee_sponsorship_key(sponsor))
+}
+
+/// Write the fee sponsorship of a given sponsor. Writing a new sponsorship
+/// replaces the previous one, together with its spent amounts.
+pub fn write_fee_sponsorship<S>(
+    storage: &mut S,
+    sponsor: &Address,
+    sponsorship: FeeSponsorship,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !sponsorship.is_valid() {
+        return Err(Error::new_alloc(format!(
+            "Invalid fee sponsorship of {sponsor}"
+        )));
+    }
+    storage.write(&fee_sponsorship_key(sponsor), sponsorship)
+}
+
+/// Read a scheduled transfer of the source, if any.
+pub fn read_scheduled_transfer<S>(
+    storage: &S,
+    source: &Address,
+    id: u64,
+) -> Result<Option<ScheduledTransfer>>
+where
+    S: StorageRead,
+{
+    storage.read(&scheduled_transfer_key(source, id))
+}
+
+/// Read all the scheduled transfers of the source, by their ids.
+pub fn read_scheduled_transfers<S>(
+    storage: &S,
+    source: &Address,
+) -> Result<BTreeMap<u64, ScheduledTransfer>>
+where
+    S: StorageRead,
+{
+    let prefix = scheduled_transfer_prefix(source);
+    let mut transfers = BTreeMap::new();
+    for entry in namada_state::iter_prefix(storage, &prefix)? {
+        let (key, transfer) = entry?;
+        if let Some(DbKeySeg::StringSeg(id)) = key.last() {
+            let id = u64::parse(id.clone()).into_storage_result()?;
+            transfers.insert(id, transfer);
+        }
+    }
+    Ok(transfers)
+}
+
+/// Write a scheduled transfer of the source, indexed by the epoch of its next
+/// execution.
+pub fn write_scheduled_transfer<S>(
+    storage: &mut S,
+    source: &Address,
+    id: u64,
+    transfer: ScheduledTransfer,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !transfer.is_valid() {
+        return Err(Error::new_alloc(format!(
+            "Invalid scheduled transfer {id} of {source}"
+        )));
+    }
+    if let Some(prev) = read_scheduled_transfer(storage, source, id)? {
+        storage.delete(&scheduled_transfer_due_key(
+            prev.next_epoch,
+            source,
+            id,
+        ))?;
+    }
+    storage.write(
+        &scheduled_transfer_due_key(transfer.next_epoch, source, id),
+        (),
+    )?;
+    storage.write(&scheduled_transfer_key(source, id), transfer)
+}
+
+/// Schedule a new transfer of the source. Returns the id of the scheduled
+/// transfer.
+pub fn schedule_transfer<S>(
+    storage: &mut S,
+    source: &Address,
+    transfer: ScheduledTransfer,
+) -> Result<u64>
+where
+    S: StorageRead + StorageWrite,
+{
+    let id_key = next_scheduled_transfer_id_key(source);
+    let id: u64 = storage.read(&id_key)?.unwrap_or_default();
+    let next_id = id.checked_add(1).ok_or_else(|| {
+        Error::new_const("The scheduled transfer id overflowed")
+    })?;
+    storage.write(&id_key, next_id)?;
+    write_scheduled_transfer(storage, source, id, transfer)?;
+    Ok(id)
+}
+
+/// Delete a scheduled transfer of the source together with its index.
+pub fn delete_scheduled_transfer<S>(
+    storage: &mut S,
+    source: &Address,
+    id: u64,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if let Some(transfer) = read_scheduled_transfer(storage, source, id)? {
+        storage.delete(&scheduled_transfer_due_key(
+            transfer.next_epoch,
+            source,
+            id,
+        ))?;
+        storage.delete(&scheduled_transfer_key(source, id))?;
+    }
+    Ok(())
+}
+
 /// Apply transfer of a `token` from `src` to `dest` in storage.
 ///
 /// Returns an `Err` if `src` has insufficient balance or if the transfer the
@@ -365,12 +579,18 @@ pub fn denom_to_amount(
 
 #[cfg(test)]
 mod testing {
+    use namada_core::chain::Epoch;
     use namada_core::{address, token};
-    use namada_state::testing::TestStorage;
+    use namada_events::Event;
+    use namada_state::testing::{TestState, TestStorage};
 
     use super::{
-        burn_tokens, credit_tokens, read_balance, read_total_supply, transfer,
+        burn_tokens, credit_tokens, read_balance, read_scheduled_transfer,
+        read_scheduled_transfers, read_total_supply, schedule_transfer,
+        transfer,
     };
+    use crate::execute_scheduled_transfers;
+    use crate::storage_key::scheduled_transfer_due_prefix;
 
     #[test]
     fn test_credit() {
@@ -515,4 +735,97 @@ mod testing {
         let total_supply = read_total_supply(&storage, &native_token).unwrap();
         assert_eq!(total_supply, balance3);
     }
+
+    #[test]
+    fn test_execute_scheduled_transfers() {
+        let mut state = TestState::default();
+        let native_token = address::testing::nam();
+        let mut events: Vec<Event> = vec![];
+
+        let source = address::testing::gen_implicit_address();
+        let target = address::testing::gen_implicit_address();
+        credit_tokens(
+            &mut state,
+            &native_token,
+            &source,
+            token::Amount::native_whole(10),
+        )
+        .unwrap();
+
+        let order = token::ScheduledTransfer {
+            token: native_token.clone(),
+            amount: token::Amount::native_whole(3),
+            target: target.clone(),
+            next_epoch: Epoch(1),
+            interval: 2,
+            remaining: 2,
+        };
+        let id = schedule_transfer(&mut state, &source, order).unwrap();
+        // An order that cannot be covered by the balance of the source
+        let unfunded = token::ScheduledTransfer {
+            token: native_token.clone(),
+            amount: token::Amount::native_whole(100),
+            target: target.clone(),
+            next_epoch: Epoch(1),
+            interval: 1,
+            remaining: 1,
+        };
+        let unfunded_id =
+            schedule_transfer(&mut state, &source, unfunded).unwrap();
+        assert_ne!(id, unfunded_id);
+        assert_eq!(read_scheduled_transfers(&state, &source).unwrap().len(), 2);
+
+        // Nothing is due before the start epoch
+        execute_scheduled_transfers(&mut state, &mut events).unwrap();
+        assert!(events.is_empty());
+        assert!(
+            read_balance(&state, &native_token, &target)
+                .unwrap()
+                .is_zero()
+        );
+
+        // The first execution
+        state.in_mem_mut().block.epoch = Epoch(1);
+        execute_scheduled_transfers(&mut state, &mut events).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            read_balance(&state, &native_token, &target).unwrap(),
+            token::Amount::native_whole(3)
+        );
+        let order = read_scheduled_transfer(&state, &source, id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(order.next_epoch, Epoch(3));
+        assert_eq!(order.remaining, 1);
+        // The unfunded order has been skipped on its last execution
+        assert!(
+            read_scheduled_transfer(&state, &source, unfunded_id)
+                .unwrap()
+                .is_none()
+        );
+
+        // Nothing more is due in the same epoch
+        execute_scheduled_transfers(&mut state, &mut events).unwrap();
+        assert_eq!(events.len(), 1);
+
+        // The last execution
+        state.in_mem_mut().block.epoch = Epoch(3);
+        execute_scheduled_transfers(&mut state, &mut events).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            read_balance(&state, &native_token, &target).unwrap(),
+            token::Amount::native_whole(6)
+        );
+        assert!(
+            read_scheduled_transfers(&state, &source)
+                .unwrap()
+                .is_empty()
+        );
+        let mut due = namada_state::iter_prefix_bytes(
+            &state,
+            &scheduled_transfer_due_prefix(),
+        )
+        .unwrap();
+        assert!(due.next().is_none());
+    }
 }
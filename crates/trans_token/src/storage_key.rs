@@ -1,6 +1,7 @@
 //! Transparent token storage keys
 
 use namada_core::address::{Address, InternalAddress};
+use namada_core::chain::Epoch;
 use namada_core::storage::{self, DbKeySeg, KeySeg};
 
 /// Key segment for a balance key
@@ -13,6 +14,14 @@ pub const MINTER_STORAGE_KEY: &str = "minter";
 pub const MINTED_STORAGE_KEY: &str = "minted";
 /// Key segment for token parameters
 pub const PARAMETERS_STORAGE_KEY: &str = "parameters";
+/// Key segment for vesting schedules
+pub const VESTING_STORAGE_KEY: &str = "vesting";
+/// Key segment for fee sponsorships
+pub const FEE_SPONSORSHIP_STORAGE_KEY: &str = "fee_sponsorship";
+/// Key segment for scheduled transfers
+pub const SCHEDULED_TRANSFER_STORAGE_KEY: &str = "scheduled_transfer";
+/// Key segment for the next id of a scheduled transfer
+pub const SCHEDULED_TRANSFER_ID_STORAGE_KEY: &str = "scheduled_transfer_id";
 
 /// Gets the key for the given token address, error with the given
 /// message to expect if the key is not in the address
@@ -55,6 +64,76 @@ pub fn parameter_prefix(token_addr: &Address) -> storage::Key {
     .expect("Cannot obtain a storage key")
 }
 
+/// Obtain a storage key for the vesting schedule of the owner's balance. It's
+/// stored in the owner's sub-space, where the account's VP only allows
+/// governance to change it.
+pub fn vesting_key(token_addr: &Address, owner: &Address) -> storage::Key {
+    storage::Key::from(owner.to_db_key())
+        .push(&VESTING_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&token_addr.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the fee sponsorship of the sponsor. It's stored
+/// in the sponsor's sub-space so that only the sponsor can authorize it.
+pub fn fee_sponsorship_key(sponsor: &Address) -> storage::Key {
+    storage::Key::from(sponsor.to_db_key())
+        .push(&FEE_SPONSORSHIP_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for the scheduled transfers of the source. They
+/// are stored in the source's sub-space so that only the source can authorize
+/// them.
+pub fn scheduled_transfer_prefix(source: &Address) -> storage::Key {
+    storage::Key::from(source.to_db_key())
+        .push(&SCHEDULED_TRANSFER_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for a scheduled transfer of the source.
+pub fn scheduled_transfer_key(source: &Address, id: u64) -> storage::Key {
+    scheduled_transfer_prefix(source)
+        .push(&id)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the id of the next scheduled transfer of the
+/// source.
+pub fn next_scheduled_transfer_id_key(source: &Address) -> storage::Key {
+    storage::Key::from(source.to_db_key())
+        .push(&SCHEDULED_TRANSFER_ID_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for the index of the scheduled transfers by
+/// the epoch of their next execution.
+pub fn scheduled_transfer_due_prefix() -> storage::Key {
+    storage::Key::from(
+        Address::Internal(InternalAddress::Multitoken).to_db_key(),
+    )
+    .push(&SCHEDULED_TRANSFER_STORAGE_KEY.to_owned())
+    .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the index of a scheduled transfer of the source
+/// by the epoch of its next execution. The epoch segments are ordered, such
+/// that the earliest transfers are iterated first.
+pub fn scheduled_transfer_due_key(
+    epoch: Epoch,
+    source: &Address,
+    id: u64,
+) -> storage::Key {
+    scheduled_transfer_due_prefix()
+        .push(&epoch)
+        .expect("Cannot obtain a storage key")
+        .push(&source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&id)
+        .expect("Cannot obtain a storage key")
+}
+
 /// Obtain a storage key for the multitoken minter.
 pub fn minter_key(token_addr: &Address) -> storage::Key {
     storage::Key::from(
@@ -192,3 +271,39 @@ pub fn is_any_minted_balance_key(key: &storage::Key) -> Option<&Address> {
         _ => None,
     }
 }
+
+/// Check if the given storage key is a vesting schedule key for an unspecified
+/// token. If it is, return the token and owner address.
+pub fn is_any_vesting_key(key: &storage::Key) -> Option<[&Address; 2]> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(vesting),
+            DbKeySeg::AddressSeg(token),
+        ] if vesting == VESTING_STORAGE_KEY => Some([token, owner]),
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is an index key of a scheduled transfer. If
+/// it is, return the epoch of its next execution, its source and its id.
+pub fn is_scheduled_transfer_due_key(
+    key: &storage::Key,
+) -> Option<(Epoch, &Address, u64)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(epoch),
+            DbKeySeg::AddressSeg(source),
+            DbKeySeg::StringSeg(id),
+        ] if *addr == Address::Internal(InternalAddress::Multitoken)
+            && prefix == SCHEDULED_TRANSFER_STORAGE_KEY =>
+        {
+            let epoch = Epoch::parse(epoch.clone()).ok()?;
+            let id = u64::parse(id.clone()).ok()?;
+            Some((epoch, source, id))
+        }
+        _ => None,
+    }
+}
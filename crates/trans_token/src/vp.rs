@@ -5,9 +5,10 @@ use std::marker::PhantomData;
 
 use namada_core::address::{Address, InternalAddress, GOV, POS};
 use namada_core::booleans::BoolResultUnitExt;
+use namada_core::chain::Epoch;
 use namada_core::collections::HashMap;
 use namada_core::storage::{Key, KeySeg};
-use namada_core::token::Amount;
+use namada_core::token::{Amount, ScheduledTransfer, VestingSchedule};
 use namada_systems::{governance, parameters};
 use namada_tx::action::{
     Action, Bond, ClaimRewards, GovAction, PosAction, Withdraw,
@@ -16,8 +17,10 @@ use namada_tx::BatchedTxRef;
 use namada_vp_env::{Error, Result, VpEnv};
 
 use crate::storage_key::{
-    is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
-    is_any_token_parameter_key, minter_key,
+    balance_key, is_any_minted_balance_key, is_any_minter_key,
+    is_any_token_balance_key, is_any_token_parameter_key, is_any_vesting_key,
+    is_scheduled_transfer_due_key, minter_key, scheduled_transfer_key,
+    vesting_key,
 };
 use crate::StorageRead;
 
@@ -133,6 +136,14 @@ where
                                 "Native token deposit isn't allowed",
                             ));
                         }
+                        Self::is_valid_vesting_debit(
+                            ctx,
+                            token,
+                            owner,
+                            post,
+                            &native_token,
+                            &actions,
+                        )?;
                         let diff = pre
                             .checked_sub(post)
                             .expect("Underflow shouldn't happen here");
@@ -179,6 +190,24 @@ where
                 Self::is_valid_minter(ctx, token, verifiers)?;
             } else if is_any_token_parameter_key(key).is_some() {
                 return Self::is_valid_parameter(ctx, tx_data);
+            } else if is_any_vesting_key(key).is_some() {
+                // Vesting schedules can only be set in genesis or by a
+                // governance proposal, which has been handled above
+                return Err(Error::new_alloc(format!(
+                    "Vesting schedules cannot be changed by a transaction: \
+                     {key}"
+                )));
+            } else if let Some((epoch, source, id)) =
+                is_scheduled_transfer_due_key(key)
+            {
+                Self::is_valid_scheduled_transfer_index(
+                    ctx,
+                    key,
+                    keys_changed,
+                    epoch,
+                    source,
+                    id,
+                )?;
             } else if key.segments.first()
                 == Some(
                     &Address::Internal(InternalAddress::Multitoken).to_db_key(),
@@ -264,12 +293,124 @@ where
                     )),
                 }
             }
+            Address::Internal(InternalAddress::LiquidShare(_)) => {
+                // Liquid staking shares can only be minted by PoS
+                let minter_key = minter_key(token);
+                match ctx.read_post::<Address>(&minter_key)? {
+                    Some(minter) if minter == POS => {
+                        verifiers.contains(&minter).ok_or_else(|| {
+                            Error::new_const("The PoS VP was not triggered")
+                        })
+                    }
+                    _ => Err(Error::new_const(
+                        "Only the PoS account is able to mint liquid staking \
+                         shares",
+                    )),
+                }
+            }
             _ => Err(Error::new_alloc(format!(
                 "Attempted to mint non-IBC token {token}"
             ))),
         }
     }
 
+    /// Check that a change to the index of the scheduled transfers is
+    /// consistent with the scheduled transfer of the source. The transfer must
+    /// be changed in the same tx, such that the source's VP authorizes it.
+    pub fn is_valid_scheduled_transfer_index(
+        ctx: &'ctx CTX,
+        key: &Key,
+        keys_changed: &BTreeSet<Key>,
+        epoch: Epoch,
+        source: &Address,
+        id: u64,
+    ) -> Result<()> {
+        let transfer_key = scheduled_transfer_key(source, id);
+        if !keys_changed.contains(&transfer_key) {
+            return Err(Error::new_alloc(format!(
+                "The scheduled transfer {id} of {source} must be changed \
+                 together with its index"
+            )));
+        }
+        let is_indexed = ctx.has_key_post(key)?;
+        let is_scheduled = ctx
+            .read_post::<ScheduledTransfer>(&transfer_key)?
+            .is_some_and(|transfer| {
+                transfer.is_valid() && transfer.next_epoch == epoch
+            });
+        if is_indexed != is_scheduled {
+            return Err(Error::new_alloc(format!(
+                "The index of the scheduled transfer {id} of {source} is \
+                 inconsistent: {key}"
+            )));
+        }
+        if is_indexed && epoch < ctx.get_block_epoch()? {
+            return Err(Error::new_alloc(format!(
+                "The scheduled transfer {id} of {source} cannot be scheduled \
+                 in the past epoch {epoch}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that a debit from the owner's balance does not spend the amount
+    /// still locked by its vesting schedule, if any. The locked amount may
+    /// only leave the balance by being bonded, up to the amount actually
+    /// credited to PoS in the tx.
+    pub fn is_valid_vesting_debit(
+        ctx: &'ctx CTX,
+        token: &Address,
+        owner: &Address,
+        post: Amount,
+        native_token: &Address,
+        actions: &[Action],
+    ) -> Result<()> {
+        let Some(schedule) =
+            ctx.read_pre::<VestingSchedule>(&vesting_key(token, owner))?
+        else {
+            return Ok(());
+        };
+        let epoch = ctx.get_block_epoch()?;
+        let locked = schedule.locked_at(epoch).ok_or_else(|| {
+            Error::new_const("Overflowed in vesting schedule check")
+        })?;
+        let bonded = if token == native_token {
+            let bonded =
+                Amount::sum(actions.iter().filter_map(|action| match action {
+                    Action::Pos(PosAction::Bond(Bond {
+                        validator,
+                        source,
+                        amount,
+                    })) if source.as_ref().unwrap_or(validator) == owner => {
+                        Some(*amount)
+                    }
+                    _ => None,
+                }))
+                .ok_or_else(|| {
+                    Error::new_const("Overflowed in vesting schedule check")
+                })?;
+            // The bond actions are only declared by the tx, so they cannot
+            // exceed the tokens actually transferred to PoS
+            let pos_balance_key = balance_key(token, &POS);
+            let pre_pos: Amount =
+                ctx.read_pre(&pos_balance_key)?.unwrap_or_default();
+            let post_pos: Amount =
+                ctx.read_post(&pos_balance_key)?.unwrap_or_default();
+            bonded.min(post_pos.checked_sub(pre_pos).unwrap_or_default())
+        } else {
+            Amount::zero()
+        };
+        let spendable_post = post.checked_add(bonded).ok_or_else(|| {
+            Error::new_const("Overflowed in vesting schedule check")
+        })?;
+        (spendable_post >= locked).ok_or_else(|| {
+            Error::new_alloc(format!(
+                "The debit from {owner} exceeds its vested balance of token \
+                 {token}, {locked} is still locked",
+            ))
+        })
+    }
+
     /// Return if the parameter change was done via a governance proposal
     pub fn is_valid_parameter(
         ctx: &'ctx CTX,
@@ -306,11 +447,18 @@ fn has_bal_inc_protocol_action(action: &Action, owner: Owner<'_>) -> bool {
             // NB: pos or gov's balance can increase
             Owner::Protocol => true,
         },
+        Action::Gov(GovAction::UnlockVotingTokens { owner: source }) => {
+            match owner {
+                Owner::Account(owner) => source == owner,
+                // NB: pos or gov's balance can increase
+                Owner::Protocol => true,
+            }
+        }
         // NB: only pos or gov balances can decrease with these actions
         Action::Pos(PosAction::Bond(Bond { .. }))
-        | Action::Gov(GovAction::InitProposal { .. }) => {
-            owner == Owner::Protocol
-        }
+        | Action::Gov(
+            GovAction::InitProposal { .. } | GovAction::LockVotingTokens { .. },
+        ) => owner == Owner::Protocol,
         // NB: every other case is invalid
         _ => false,
     }
@@ -327,7 +475,10 @@ fn has_bal_dec_protocol_action(action: &Action, owner: Owner<'_>) -> bool {
             // NB: pos or gov's balance can decrease
             Owner::Protocol => true,
         },
-        Action::Gov(GovAction::InitProposal { author: source }) => {
+        Action::Gov(
+            GovAction::InitProposal { author: source }
+            | GovAction::LockVotingTokens { owner: source },
+        ) => {
             match owner {
                 Owner::Account(owner) => source == owner,
                 // NB: pos or gov's balance can decrease
@@ -338,7 +489,10 @@ fn has_bal_dec_protocol_action(action: &Action, owner: Owner<'_>) -> bool {
         Action::Pos(
             PosAction::ClaimRewards(ClaimRewards { .. })
             | PosAction::Withdraw(Withdraw { .. }),
-        ) => owner == Owner::Protocol,
+        )
+        | Action::Gov(GovAction::UnlockVotingTokens { .. }) => {
+            owner == Owner::Protocol
+        }
         // NB: every other case is invalid
         _ => false,
     }
@@ -350,7 +504,8 @@ mod tests {
 
     use assert_matches::assert_matches;
     use namada_core::address::testing::{
-        established_address_1, established_address_2, nam,
+        established_address_1, established_address_2, established_address_3,
+        nam,
     };
     use namada_core::borsh::BorshSerializeExt;
     use namada_core::key::testing::keypair_1;
@@ -369,7 +524,7 @@ mod tests {
     use namada_vp::native_vp::{self, CtxPreStorageRead};
 
     use super::*;
-    use crate::storage_key::{balance_key, minted_balance_key};
+    use crate::storage_key::minted_balance_key;
 
     const ADDRESS: Address = Address::Internal(InternalAddress::Multitoken);
 
@@ -1114,6 +1269,167 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vesting_locked_transfer() {
+        let mut state = init_state();
+        let src = established_address_1();
+        let dest = established_address_2();
+        let keys_changed = transfer(&mut state, &src, &dest);
+
+        // 95 of the initial 100 are locked until epoch 10
+        let schedule = VestingSchedule::Cliff {
+            amount: Amount::native_whole(95),
+            cliff: 10.into(),
+        };
+        state
+            .db_write(&vesting_key(&nam(), &src), schedule.serialize_to_vec())
+            .expect("write failed");
+
+        let tx_index = TxIndex::default();
+        let BatchedTx { tx, cmt } = dummy_tx(&state);
+        let gas_meter = RefCell::new(VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new(u64::MAX),
+        ));
+        let (vp_vp_cache, _vp_cache_dir) = vp_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(src);
+        verifiers.insert(dest);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            &tx,
+            &cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_vp_cache,
+        );
+
+        assert!(
+            MultitokenVp::validate_tx(
+                &ctx,
+                &tx.batch_ref_tx(&cmt),
+                &keys_changed,
+                &verifiers
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_vesting_bond_unvested() {
+        let mut state = init_state();
+        let src = established_address_1();
+        let dest = POS;
+        let keys_changed = transfer(&mut state, &src, &dest);
+        state
+            .push_action(Action::Pos(PosAction::Bond(Bond {
+                validator: established_address_2(),
+                source: Some(src.clone()),
+                amount: Amount::native_whole(10),
+            })))
+            .unwrap();
+
+        // the whole balance is locked, but bonding it is allowed
+        let schedule = VestingSchedule::Linear {
+            amount: Amount::native_whole(100),
+            start: 5.into(),
+            end: 10.into(),
+        };
+        state
+            .db_write(&vesting_key(&nam(), &src), schedule.serialize_to_vec())
+            .expect("write failed");
+
+        let tx_index = TxIndex::default();
+        let BatchedTx { tx, cmt } = dummy_tx(&state);
+        let gas_meter = RefCell::new(VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new(u64::MAX),
+        ));
+        let (vp_vp_cache, _vp_cache_dir) = vp_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(src);
+        verifiers.insert(dest);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            &tx,
+            &cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_vp_cache,
+        );
+
+        assert_matches!(
+            MultitokenVp::validate_tx(
+                &ctx,
+                &tx.batch_ref_tx(&cmt),
+                &keys_changed,
+                &verifiers
+            ),
+            Ok(_)
+        );
+    }
+
+    #[test]
+    fn test_vesting_fake_bond() {
+        let mut state = init_state();
+        let src = established_address_1();
+        let dest = established_address_2();
+        let keys_changed = transfer(&mut state, &src, &dest);
+        // The bond action is declared, but the tokens aren't transferred to
+        // PoS
+        state
+            .push_action(Action::Pos(PosAction::Bond(Bond {
+                validator: established_address_3(),
+                source: Some(src.clone()),
+                amount: Amount::native_whole(10),
+            })))
+            .unwrap();
+
+        let schedule = VestingSchedule::Linear {
+            amount: Amount::native_whole(100),
+            start: 5.into(),
+            end: 10.into(),
+        };
+        state
+            .db_write(&vesting_key(&nam(), &src), schedule.serialize_to_vec())
+            .expect("write failed");
+
+        let tx_index = TxIndex::default();
+        let BatchedTx { tx, cmt } = dummy_tx(&state);
+        let gas_meter = RefCell::new(VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new(u64::MAX),
+        ));
+        let (vp_vp_cache, _vp_cache_dir) = vp_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(src);
+        verifiers.insert(dest);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &state,
+            &tx,
+            &cmt,
+            &tx_index,
+            &gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_vp_cache,
+        );
+
+        assert!(
+            MultitokenVp::validate_tx(
+                &ctx,
+                &tx.batch_ref_tx(&cmt),
+                &keys_changed,
+                &verifiers
+            )
+            .is_err()
+        );
+    }
+
     // The multitoken vps ensures that all the involved parties have their vp
     // triggered
     #[test]
@@ -10,12 +10,14 @@ use std::fmt;
 
 use namada_core::address::Address;
 use namada_core::borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::hash::Hash;
 use namada_core::masp::MaspTxId;
 use namada_core::storage::KeySeg;
 use namada_core::{address, storage};
 
 pub use crate::data::pos::{
-    Bond, ClaimRewards, Redelegation, Unbond, Withdraw,
+    AutoCompound, Bond, ClaimRewards, RedeemLiquidShares, Redelegation,
+    TokenizeBond, Unbond, Withdraw,
 };
 
 /// Actions applied from txs.
@@ -30,6 +32,7 @@ pub enum Action {
     Pgf(PgfAction),
     Masp(MaspAction),
     IbcShielding,
+    IbcHook(IbcHook),
 }
 
 /// PoS tx actions.
@@ -45,6 +48,9 @@ pub enum PosAction {
     Withdraw(Withdraw),
     Redelegation(Redelegation),
     ClaimRewards(ClaimRewards),
+    AutoCompound(AutoCompound),
+    TokenizeBond(TokenizeBond),
+    RedeemLiquidShares(RedeemLiquidShares),
     CommissionChange(Address),
     MetadataChange(Address),
     ConsensusKeyChange(Address),
@@ -56,6 +62,9 @@ pub enum PosAction {
 pub enum GovAction {
     InitProposal { author: Address },
     VoteProposal { id: u64, voter: Address },
+    DelegateVote { delegator: Address },
+    LockVotingTokens { owner: Address },
+    UnlockVotingTokens { owner: Address },
 }
 
 /// PGF tx actions.
@@ -75,6 +84,20 @@ pub enum MaspAction {
     MaspAuthorizer(Address),
 }
 
+/// A tx triggered by the memo of a received IBC packet. The hook is executed
+/// by the protocol right after the IBC tx.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct IbcHook {
+    /// The receiver of the packet whose VP validates the hook
+    pub receiver: Address,
+    /// The hash of the allow-listed tx code
+    pub code_hash: Hash,
+    /// The tx data
+    pub data: Vec<u8>,
+    /// The gas limit of the hook in whole gas units
+    pub gas_limit: u64,
+}
+
 /// Read actions from temporary storage
 pub trait Read {
     /// Storage access errors
@@ -157,3 +180,11 @@ pub fn is_ibc_shielding_transfer<T: Read>(
         .iter()
         .any(|action| matches!(action, Action::IbcShielding)))
 }
+
+/// Helper function to get the IBC hook from the [`Actions`]
+pub fn get_ibc_hook(actions: &Actions) -> Option<IbcHook> {
+    actions.iter().find_map(|action| match action {
+        Action::IbcHook(hook) => Some(hook.clone()),
+        _ => None,
+    })
+}
@@ -130,6 +130,31 @@ pub struct ClaimRewards {
     pub source: Option<Address>,
 }
 
+/// A change of the automatic compounding of a bond's rewards.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct AutoCompound {
+    /// Validator address
+    pub validator: Address,
+    /// Source address of the bond. For self-bonds, the validator is also the
+    /// source
+    pub source: Option<Address>,
+    /// Whether the rewards of the bond should be automatically compounded
+    pub enabled: bool,
+}
+
 /// A redelegation of bonded tokens from one validator to another.
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(
@@ -156,6 +181,55 @@ pub struct Redelegation {
     pub amount: token::Amount,
 }
 
+/// A tokenization of bonded tokens into liquid staking shares of the
+/// validator.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct TokenizeBond {
+    /// Validator address
+    pub validator: Address,
+    /// Owner (delegator) of the bonds to be tokenized
+    pub owner: Address,
+    /// The amount of bonded tokens
+    pub amount: token::Amount,
+}
+
+/// A redemption of liquid staking shares back into bonded tokens.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshDeserializer,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct RedeemLiquidShares {
+    /// Liquid staking share token address
+    pub share_token: Address,
+    /// Owner of the shares to be redeemed
+    pub owner: Address,
+    /// The amount of shares
+    pub amount: token::Amount,
+}
+
 /// A change to the validator commission rate.
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(
@@ -160,6 +160,9 @@ pub struct WrapperTx {
     pub pk: common::PublicKey,
     /// Max amount of gas that can be used when executing the inner tx
     pub gas_limit: GasLimit,
+    /// The sponsor paying the fees from its pre-authorized budget instead of
+    /// the fee payer
+    pub fee_sponsor: Option<Address>,
 }
 
 impl WrapperTx {
@@ -172,7 +175,18 @@ impl WrapperTx {
         pk: common::PublicKey,
         gas_limit: GasLimit,
     ) -> WrapperTx {
-        Self { fee, pk, gas_limit }
+        Self {
+            fee,
+            pk,
+            gas_limit,
+            fee_sponsor: None,
+        }
+    }
+
+    /// Have the fees paid by the given sponsor
+    pub fn with_fee_sponsor(mut self, sponsor: Address) -> Self {
+        self.fee_sponsor = Some(sponsor);
+        self
     }
 
     /// Get the address of the implicit account associated
@@ -184,6 +198,12 @@ impl WrapperTx {
         Address::from(&self.pk)
     }
 
+    /// Get the address of the account whose balance is debited for the
+    /// fees. This is the fee sponsor, if any, otherwise the fee payer.
+    pub fn fee_source(&self) -> Address {
+        self.fee_sponsor.clone().unwrap_or_else(|| self.fee_payer())
+    }
+
     /// Produce a SHA-256 hash of this section
     pub fn hash<'a>(&self, hasher: &'a mut Sha256) -> &'a mut Sha256 {
         hasher.update(self.serialize_to_vec());
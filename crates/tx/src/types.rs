@@ -413,8 +413,8 @@ impl Tx {
             .map_err(DecodeError::InvalidEncoding)
     }
 
-    /// Verify that the section with the given hash has been signed by the given
-    /// public key
+    /// Verify that the section with the given hash has been signed by enough
+    /// of the given public keys for their total weight to meet the threshold
     pub fn verify_signatures<F>(
         &self,
         hashes: &[namada_core::hash::Hash],
@@ -469,7 +469,9 @@ impl Tx {
                         witnesses.push(signatures);
                     }
                     // Short-circuit these checks if the threshold is exceeded
-                    if verified_pks.len() >= threshold.into() {
+                    if public_keys_index_map.total_weight(&verified_pks)
+                        >= threshold.into()
+                    {
                         return Ok(witnesses);
                     }
                 }
@@ -477,7 +479,7 @@ impl Tx {
         }
         Err(VerifySigError::InvalidSectionSignature(format!(
             "signature threshold not met: ({} < {})",
-            verified_pks.len(),
+            public_keys_index_map.total_weight(&verified_pks),
             threshold
         )))
     }
@@ -698,6 +700,15 @@ impl Tx {
         self
     }
 
+    /// Have the fees of the wrapper paid by the given sponsor. Does nothing
+    /// if the tx is not a wrapper.
+    pub fn add_fee_sponsor(&mut self, sponsor: Address) -> &mut Self {
+        if let TxType::Wrapper(wrapper) = &mut self.header.tx_type {
+            wrapper.fee_sponsor = Some(sponsor);
+        }
+        self
+    }
+
     /// Add fee payer keypair to the tx builder
     pub fn sign_wrapper(&mut self, keypair: common::SecretKey) -> &mut Self {
         self.protocol_filter();
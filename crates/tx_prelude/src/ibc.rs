@@ -1,25 +1,34 @@
 //! IBC lower-level functions for transactions.
 
 use std::cell::RefCell;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 
 use namada_core::address::Address;
+use namada_core::chain::{BlockHeader, BlockHeight, ChainId, Epoch, Epochs};
+use namada_core::storage::{self, TxIndex};
 use namada_core::token::Amount;
 pub use namada_ibc::event::{IbcEvent, IbcEventType};
+use namada_ibc::ica::{HostMsg, VoteOption};
+use namada_ibc::storage::hook_failure_key;
 pub use namada_ibc::storage::{
-    burn_tokens, client_state_key, is_ibc_key, mint_limit_key, mint_tokens,
-    throughput_limit_key, upgraded_client_state_key,
-    upgraded_consensus_state_key,
+    burn_tokens, client_state_key, hook_allowlist_key, is_ibc_key,
+    mint_limit_key, mint_tokens, store_wasm_client_code, throughput_limit_key,
+    upgraded_client_state_key, upgraded_consensus_state_key,
+    wasm_client_allowlist_key,
 };
 pub use namada_ibc::trace::ibc_token;
 pub use namada_ibc::{
-    IbcActions, IbcCommonContext, IbcStorageContext, NftTransferModule,
-    ProofSpec, TransferModule,
+    FeeModule, IbcActions, IbcCommonContext, IbcHooksModule, IbcStorageContext,
+    IcaModule, NftTransferModule, PacketForwardModule, ProofSpec,
+    TransferModule,
 };
+use namada_proof_of_stake::bond_tokens;
+use namada_tx::action::{Action, GovAction, IbcHook, Write};
 use namada_tx_env::TxEnv;
 
-use crate::{token, Ctx, Result};
+use crate::proof_of_stake::{find_delegation_validators, is_validator};
+use crate::{governance, token, Ctx, Result, StorageRead, StorageWrite};
 
 /// IBC actions to handle an IBC message. The `verifiers` inserted into the set
 /// must be inserted into the tx context with `Ctx::insert_verifier` after tx
@@ -27,13 +36,28 @@ use crate::{token, Ctx, Result};
 pub fn ibc_actions(
     ctx: &mut Ctx,
 ) -> IbcActions<'_, Ctx, crate::parameters::Store<Ctx>, token::Store<Ctx>> {
+    // The protocol writes the failure of the IBC hook to acknowledge the
+    // packet with it
+    let hook_failure = TxEnv::read_temp(ctx, &hook_failure_key())
+        .expect("Reading the IBC hook failure shouldn't fail");
     let ctx = Rc::new(RefCell::new(ctx.clone()));
     let verifiers = Rc::new(RefCell::new(BTreeSet::<Address>::new()));
     let mut actions = IbcActions::new(ctx.clone(), verifiers.clone());
-    let module = TransferModule::new(ctx.clone(), verifiers);
-    actions.add_transfer_module(module);
-    let module = NftTransferModule::<Ctx, token::Store<Ctx>>::new(ctx);
+    let module = FeeModule::new(
+        ctx.clone(),
+        PacketForwardModule::new(
+            ctx.clone(),
+            IbcHooksModule::new(
+                ctx.clone(),
+                TransferModule::new(ctx.clone(), verifiers),
+                hook_failure,
+            ),
+        ),
+    );
+    actions.add_fee_module(module);
+    let module = NftTransferModule::<Ctx, token::Store<Ctx>>::new(ctx.clone());
     actions.add_transfer_module(module);
+    actions.add_ica_module(IcaModule::new(ctx));
     actions
 }
 
@@ -84,9 +108,213 @@ impl IbcStorageContext for Ctx {
         burn_tokens::<_, token::Store<_>>(self, target, token, amount)
     }
 
+    fn execute_host_msgs(
+        &mut self,
+        account: &Address,
+        msgs: &[HostMsg],
+    ) -> Result<()> {
+        // Try the messages first not to write anything when any of them fails
+        let mut dry_run = DryRunStorage {
+            ctx: self,
+            writes: BTreeMap::new(),
+        };
+        for msg in msgs {
+            match msg {
+                HostMsg::Transfer(msg) => namada_token::transfer(
+                    &mut dry_run,
+                    &msg.token,
+                    account,
+                    &msg.target,
+                    msg.amount,
+                )?,
+                HostMsg::Bond(msg) => {
+                    let current_epoch = dry_run.get_block_epoch()?;
+                    bond_tokens::<_, governance::Store<_>, token::Store<_>>(
+                        &mut dry_run,
+                        Some(account),
+                        &msg.validator,
+                        msg.amount,
+                        current_epoch,
+                        None,
+                    )?
+                }
+                HostMsg::Vote(msg) => vote_proposal(
+                    &mut dry_run,
+                    account,
+                    msg.proposal_id,
+                    msg.vote,
+                )?,
+            }
+        }
+
+        for msg in msgs {
+            match msg {
+                HostMsg::Transfer(msg) => token::transfer(
+                    self,
+                    account,
+                    &msg.target,
+                    &msg.token,
+                    msg.amount,
+                )?,
+                HostMsg::Bond(msg) => Ctx::bond_tokens(
+                    self,
+                    Some(account),
+                    &msg.validator,
+                    msg.amount,
+                )?,
+                HostMsg::Vote(msg) => {
+                    TxEnv::insert_verifier(self, account)?;
+                    self.push_action(Action::Gov(GovAction::VoteProposal {
+                        id: msg.proposal_id,
+                        voter: account.clone(),
+                    }))?;
+                    vote_proposal(self, account, msg.proposal_id, msg.vote)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn schedule_hook(&mut self, hook: IbcHook) -> Result<()> {
+        self.push_action(Action::IbcHook(hook))
+    }
+
     fn insert_verifier(&mut self, addr: &Address) -> Result<()> {
         TxEnv::insert_verifier(self, addr)
     }
 }
 
 impl IbcCommonContext for Ctx {}
+
+/// Vote on the governance proposal for the interchain account
+fn vote_proposal<S>(
+    storage: &mut S,
+    voter: &Address,
+    proposal_id: u64,
+    vote: VoteOption,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let current_epoch = storage.get_block_epoch()?;
+    let delegation_targets = if is_validator(storage, voter)? {
+        [voter.clone()].into()
+    } else {
+        find_delegation_validators(storage, voter, &current_epoch)?
+    };
+    let vote = match vote {
+        VoteOption::Yay => governance::ProposalVote::Yay,
+        VoteOption::Nay => governance::ProposalVote::Nay,
+        VoteOption::Abstain => governance::ProposalVote::Abstain,
+    };
+    let data = governance::VoteProposalData {
+        id: proposal_id,
+        vote,
+        voter: voter.clone(),
+    };
+    governance::vote_proposal(storage, data, delegation_targets)
+}
+
+/// Storage to try the host messages of an interchain account on top of the
+/// transaction storage without writing to it
+struct DryRunStorage<'a> {
+    ctx: &'a Ctx,
+    /// Written values, `None` for deleted keys
+    writes: BTreeMap<storage::Key, Option<Vec<u8>>>,
+}
+
+impl StorageRead for DryRunStorage<'_> {
+    type PrefixIter<'iter>
+        = std::vec::IntoIter<(String, Vec<u8>)>
+    where
+        Self: 'iter;
+
+    fn read_bytes(&self, key: &storage::Key) -> Result<Option<Vec<u8>>> {
+        match self.writes.get(key) {
+            Some(val) => Ok(val.clone()),
+            None => self.ctx.read_bytes(key),
+        }
+    }
+
+    fn has_key(&self, key: &storage::Key) -> Result<bool> {
+        match self.writes.get(key) {
+            Some(val) => Ok(val.is_some()),
+            None => self.ctx.has_key(key),
+        }
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+    ) -> Result<Self::PrefixIter<'iter>> {
+        let mut items = BTreeMap::new();
+        let mut iter = self.ctx.iter_prefix(prefix)?;
+        while let Some((key, val)) = self.ctx.iter_next(&mut iter)? {
+            items.insert(key, Some(val));
+        }
+        for (key, val) in &self.writes {
+            if key.split_prefix(prefix).is_some() {
+                items.insert(key.to_string(), val.clone());
+            }
+        }
+        let items: Vec<_> = items
+            .into_iter()
+            .filter_map(|(key, val)| val.map(|val| (key, val)))
+            .collect();
+        Ok(items.into_iter())
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> Result<Option<(String, Vec<u8>)>> {
+        Ok(iter.next())
+    }
+
+    fn get_chain_id(&self) -> Result<ChainId> {
+        self.ctx.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> Result<BlockHeight> {
+        self.ctx.get_block_height()
+    }
+
+    fn get_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<BlockHeader>> {
+        self.ctx.get_block_header(height)
+    }
+
+    fn get_block_epoch(&self) -> Result<Epoch> {
+        self.ctx.get_block_epoch()
+    }
+
+    fn get_pred_epochs(&self) -> Result<Epochs> {
+        self.ctx.get_pred_epochs()
+    }
+
+    fn get_tx_index(&self) -> Result<TxIndex> {
+        self.ctx.get_tx_index()
+    }
+
+    fn get_native_token(&self) -> Result<Address> {
+        self.ctx.get_native_token()
+    }
+}
+
+impl StorageWrite for DryRunStorage<'_> {
+    fn write_bytes(
+        &mut self,
+        key: &storage::Key,
+        val: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        self.writes.insert(key.clone(), Some(val.as_ref().to_vec()));
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &storage::Key) -> Result<()> {
+        self.writes.insert(key.clone(), None);
+        Ok(())
+    }
+}
@@ -2,6 +2,9 @@
 
 use namada_core::dec::Dec;
 use namada_core::key;
+use namada_proof_of_stake::liquid_shares::{
+    redeem_liquid_shares, tokenize_bond,
+};
 pub use namada_proof_of_stake::parameters::PosParams;
 pub use namada_proof_of_stake::queries::find_delegation_validators;
 use namada_proof_of_stake::storage::read_pos_params;
@@ -10,13 +13,15 @@ use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
     change_validator_commission_rate, change_validator_metadata,
     claim_reward_tokens, deactivate_validator, reactivate_validator,
-    redelegate_tokens, unbond_tokens, unjail_validator, withdraw_tokens,
+    redelegate_tokens, set_auto_compound, unbond_tokens, unjail_validator,
+    withdraw_tokens,
 };
 pub use namada_proof_of_stake::{
     is_validator, parameters, storage, storage_key, types,
 };
 use namada_tx::action::{
-    Action, ClaimRewards, PosAction, Redelegation, Unbond, Withdraw, Write,
+    Action, AutoCompound, ClaimRewards, PosAction, RedeemLiquidShares,
+    Redelegation, TokenizeBond, Unbond, Withdraw, Write,
 };
 use namada_tx::data::pos::{BecomeValidator, Bond};
 
@@ -198,6 +203,89 @@ impl Ctx {
         )
     }
 
+    /// Enable or disable the automatic compounding of the rewards of a bond
+    pub fn set_auto_compound(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        enabled: bool,
+    ) -> TxResult {
+        // The tx must be authorized by the source address
+        let verifier = source.as_ref().unwrap_or(&validator);
+        self.insert_verifier(verifier)?;
+
+        self.push_action(Action::Pos(PosAction::AutoCompound(AutoCompound {
+            validator: validator.clone(),
+            source: source.cloned(),
+            enabled,
+        })))?;
+
+        let current_epoch = self.get_block_epoch()?;
+        set_auto_compound::<_, governance::Store<_>>(
+            self,
+            source,
+            validator,
+            enabled,
+            current_epoch,
+        )
+    }
+
+    /// Tokenize bonded tokens into liquid staking shares of the validator.
+    /// Returns the amount of minted shares.
+    pub fn tokenize_bond(
+        &mut self,
+        owner: &Address,
+        validator: &Address,
+        amount: token::Amount,
+    ) -> Result<token::Amount> {
+        // The tx must be authorized by the owner address
+        self.insert_verifier(owner)?;
+
+        self.push_action(Action::Pos(PosAction::TokenizeBond(TokenizeBond {
+            validator: validator.clone(),
+            owner: owner.clone(),
+            amount,
+        })))?;
+
+        let current_epoch = self.get_block_epoch()?;
+        tokenize_bond::<_, governance::Store<_>, token::Store<_>>(
+            self,
+            owner,
+            validator,
+            amount,
+            current_epoch,
+        )
+    }
+
+    /// Redeem liquid staking shares back into bonded tokens. Returns the
+    /// amount of redeemed bonded tokens.
+    pub fn redeem_liquid_shares(
+        &mut self,
+        owner: &Address,
+        share_token: &Address,
+        amount: token::Amount,
+    ) -> Result<token::Amount> {
+        // The tx must be authorized by the owner address
+        self.insert_verifier(owner)?;
+
+        self.push_action(Action::Pos(PosAction::RedeemLiquidShares(
+            RedeemLiquidShares {
+                share_token: share_token.clone(),
+                owner: owner.clone(),
+                amount,
+            },
+        )))?;
+
+        let current_epoch = self.get_block_epoch()?;
+        redeem_liquid_shares::<_, governance::Store<_>, token::Store<_>>(
+            self,
+            owner,
+            share_token,
+            amount,
+            current_epoch,
+        )
+    }
+
     /// Claim available reward tokens
     pub fn claim_reward_tokens(
         &mut self,
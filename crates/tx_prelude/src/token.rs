@@ -6,12 +6,14 @@ pub use namada_token::testing;
 pub use namada_token::tx::apply_shielded_transfer;
 use namada_token::TransparentTransfersRef;
 pub use namada_token::{
-    storage_key, utils, Amount, DenominatedAmount, Store, Transfer,
+    storage_key, utils, Amount, CancelScheduledTransfer, DenominatedAmount,
+    FeeSponsorship, ScheduleTransfer, ScheduledTransfer, SponsorFees, Store,
+    Transfer,
 };
 use namada_tx::BatchedTx;
 use namada_tx_env::Address;
 
-use crate::{Ctx, Result, TxResult};
+use crate::{Ctx, Error, Result, StorageRead, TxResult};
 
 const EVENT_DESC: &str = "transfer-from-wasm";
 
@@ -51,3 +53,83 @@ pub fn apply_transparent_transfers(
         EVENT_DESC.into(),
     )
 }
+
+/// Pre-authorize a budget of the sponsor to pay the fees of wrapper txs. The
+/// fees already paid in the current epoch are carried over, such that
+/// updating the sponsorship doesn't reset its epoch cap.
+pub fn sponsor_fees(ctx: &mut Ctx, data: SponsorFees) -> TxResult {
+    let SponsorFees {
+        sponsor,
+        token,
+        budget,
+        epoch_cap,
+        code_hashes,
+        targets,
+    } = data;
+    let epoch = ctx.get_block_epoch()?;
+    let epoch_spent = namada_token::read_fee_sponsorship(ctx, &sponsor)?
+        .filter(|sponsorship| sponsorship.epoch == epoch)
+        .map(|sponsorship| sponsorship.epoch_spent)
+        .unwrap_or_default();
+    namada_token::write_fee_sponsorship(
+        ctx,
+        &sponsor,
+        FeeSponsorship {
+            token,
+            budget,
+            epoch_cap,
+            code_hashes,
+            targets,
+            epoch,
+            epoch_spent,
+        },
+    )
+}
+
+/// Schedule a transfer of the source, executed by the protocol from the start
+/// epoch at a regular interval of epochs. Returns the id of the scheduled
+/// transfer.
+pub fn schedule_transfer(ctx: &mut Ctx, data: ScheduleTransfer) -> Result<u64> {
+    let ScheduleTransfer {
+        source,
+        target,
+        token,
+        amount,
+        start_epoch,
+        interval,
+        count,
+    } = data;
+    let epoch = ctx.get_block_epoch()?;
+    if start_epoch < epoch {
+        return Err(Error::new_alloc(format!(
+            "The start epoch {start_epoch} of the scheduled transfer is in \
+             the past, the current epoch is {epoch}"
+        )));
+    }
+    namada_token::schedule_transfer(
+        ctx,
+        &source,
+        ScheduledTransfer {
+            token,
+            amount,
+            target,
+            next_epoch: start_epoch,
+            interval,
+            remaining: count,
+        },
+    )
+}
+
+/// Cancel a scheduled transfer of the source.
+pub fn cancel_scheduled_transfer(
+    ctx: &mut Ctx,
+    data: CancelScheduledTransfer,
+) -> TxResult {
+    let CancelScheduledTransfer { source, id } = data;
+    if namada_token::read_scheduled_transfer(ctx, &source, id)?.is_none() {
+        return Err(Error::new_alloc(format!(
+            "No scheduled transfer {id} of {source}"
+        )));
+    }
+    namada_token::delete_scheduled_transfer(ctx, &source, id)
+}
@@ -16,10 +16,7 @@ use namada_core::hash::Hash;
 use namada_core::internal::{HostEnvResult, KeyVal};
 use namada_core::storage::{Key, TxIndex, TX_INDEX_LENGTH};
 use namada_events::{Event, EventTypeBuilder};
-use namada_gas::{
-    self as gas, Gas, GasMetering, TxGasMeter, VpGasMeter,
-    MEMORY_ACCESS_GAS_PER_BYTE,
-};
+use namada_gas::{self as gas, Gas, GasMetering, TxGasMeter, VpGasMeter};
 use namada_state::prefix_iter::{PrefixIteratorId, PrefixIterators};
 use namada_state::write_log::{self, WriteLog};
 use namada_state::{
@@ -618,6 +615,18 @@ where
         .into_storage_result()
 }
 
+/// Get the gas cost per byte of memory access from the tx gas meter
+fn tx_memory_access_per_byte<MEM, D, H, CA>(env: &TxVmEnv<MEM, D, H, CA>) -> u64
+where
+    MEM: VmMemory,
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let (gas_meter, _sentinel) = env.ctx.gas_meter_and_sentinel();
+    gas_meter.borrow().gas_costs().memory_access_per_byte
+}
+
 /// Called from VP wasm to request to use the given gas amount
 pub fn vp_charge_gas<MEM, D, H, EVAL, CA>(
     env: &mut VpVmEnv<MEM, D, H, EVAL, CA>,
@@ -1453,10 +1462,8 @@ where
     let verifiers = unsafe { env.ctx.verifiers.get_mut() };
     // This is not a storage write, use the same multiplier used for a storage
     // read
-    consume_tx_gas::<MEM, D, H, CA>(
-        env,
-        checked!(addr_len * MEMORY_ACCESS_GAS_PER_BYTE)?.into(),
-    )?;
+    let cost = tx_memory_access_per_byte(env);
+    consume_tx_gas::<MEM, D, H, CA>(env, checked!(addr_len * cost)?.into())?;
     verifiers.insert(addr);
 
     Ok(())
@@ -1617,13 +1624,9 @@ where
     H: 'static + StorageHasher,
     CA: WasmCacheAccess,
 {
-    consume_tx_gas::<MEM, D, H, CA>(
-        env,
-        (TX_INDEX_LENGTH as u64)
-            .checked_mul(MEMORY_ACCESS_GAS_PER_BYTE)
-            .expect("Consts mul that cannot overflow")
-            .into(),
-    )?;
+    let len = TX_INDEX_LENGTH as u64;
+    let cost = tx_memory_access_per_byte(env);
+    consume_tx_gas::<MEM, D, H, CA>(env, checked!(len * cost)?.into())?;
     let tx_index = unsafe { env.ctx.tx_index.get() };
     Ok(tx_index.0)
 }
@@ -1683,10 +1686,8 @@ where
         .try_into()
         .map_err(TxRuntimeError::NumConversionError)?;
     let len_u64 = u64::try_from(len)?;
-    consume_tx_gas::<MEM, D, H, CA>(
-        env,
-        checked!(MEMORY_ACCESS_GAS_PER_BYTE * len_u64)?.into(),
-    )?;
+    let cost = tx_memory_access_per_byte(env);
+    consume_tx_gas::<MEM, D, H, CA>(env, checked!(cost * len_u64)?.into())?;
     let result_buffer = unsafe { env.ctx.result_buffer.get_mut() };
     result_buffer.replace(bytes);
     Ok(len)
@@ -1704,13 +1705,9 @@ where
     CA: WasmCacheAccess,
 {
     // Gas for getting the native token address from storage
-    consume_tx_gas::<MEM, D, H, CA>(
-        env,
-        (ESTABLISHED_ADDRESS_BYTES_LEN as u64)
-            .checked_mul(MEMORY_ACCESS_GAS_PER_BYTE)
-            .expect("Consts mul that cannot overflow")
-            .into(),
-    )?;
+    let len = ESTABLISHED_ADDRESS_BYTES_LEN as u64;
+    let cost = tx_memory_access_per_byte(env);
+    consume_tx_gas::<MEM, D, H, CA>(env, checked!(len * cost)?.into())?;
     let state = env.state();
     let native_token = state.in_mem().native_token.clone();
     let native_token_string = native_token.encode();
@@ -1967,16 +1964,13 @@ where
 
     let tx = unsafe { env.ctx.tx.get() };
 
+    let verify_sig_gas = gas_meter.borrow().gas_costs().verify_tx_sig;
     match tx.verify_signatures(
         &hashes,
         public_keys_map,
         &Some(signer),
         threshold,
-        || {
-            gas_meter
-                .borrow_mut()
-                .consume(gas::VERIFY_TX_SIG_GAS.into())
-        },
+        || gas_meter.borrow_mut().consume(verify_sig_gas.into()),
     ) {
         Ok(_) => Ok(()),
         Err(err) => match err {
@@ -2123,16 +2117,13 @@ where
     let tx = unsafe { env.ctx.tx.get() };
 
     let (gas_meter, sentinel) = env.ctx.gas_meter_and_sentinel();
+    let verify_sig_gas = gas_meter.borrow().gas_costs().verify_tx_sig;
     match tx.verify_signatures(
         &hashes,
         public_keys_map,
         &None,
         threshold,
-        || {
-            gas_meter
-                .borrow_mut()
-                .consume(gas::VERIFY_TX_SIG_GAS.into())
-        },
+        || gas_meter.borrow_mut().consume(verify_sig_gas.into()),
     ) {
         Ok(_) => Ok(HostEnvResult::Success.to_i64()),
         Err(err) => match err {
@@ -2406,7 +2397,10 @@ pub mod testing {
 
         let (write_log, in_mem, db) = state.split_borrow();
         let mut env = TxVmEnv::new(
-            WasmMemory::new(Rc::downgrade(&store)),
+            WasmMemory::new(
+                Rc::downgrade(&store),
+                gas_meter.borrow().gas_costs().memory_access_per_byte,
+            ),
             write_log,
             in_mem,
             db,
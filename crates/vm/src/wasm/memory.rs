@@ -8,7 +8,7 @@ use std::str::Utf8Error;
 
 use namada_core::arith::{self, checked};
 use namada_core::borsh::BorshSerializeExt;
-use namada_gas::{Gas, MEMORY_ACCESS_GAS_PER_BYTE};
+use namada_gas::Gas;
 use namada_tx::BatchedTxRef;
 use thiserror::Error;
 use wasmer::sys::BaseTunables;
@@ -305,6 +305,8 @@ fn write_memory_bytes(
 pub struct WasmMemory {
     store: rc::Weak<RefCell<Store>>,
     memory: Rc<RefCell<Option<wasmer::Memory>>>,
+    /// The gas cost of accessing the memory per byte
+    access_gas_per_byte: u64,
 }
 
 // TODO(namada#3313): Wasm memory is neither `Send` nor `Sync`, but we must
@@ -313,11 +315,16 @@ unsafe impl Send for WasmMemory {}
 unsafe impl Sync for WasmMemory {}
 
 impl WasmMemory {
-    /// Build a new wasm memory.
-    pub fn new(store: rc::Weak<RefCell<Store>>) -> Self {
+    /// Build a new wasm memory, charging the given gas cost per byte of
+    /// access.
+    pub fn new(
+        store: rc::Weak<RefCell<Store>>,
+        access_gas_per_byte: u64,
+    ) -> Self {
         Self {
             store,
             memory: Rc::new(RefCell::new(None)),
+            access_gas_per_byte,
         }
     }
 
@@ -360,7 +367,7 @@ impl VmMemory for WasmMemory {
             let mut store = store.borrow_mut();
             let bytes = read_memory_bytes(&mut *store, memory, offset, len)?;
             let len = bytes.len() as u64;
-            let gas = checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?;
+            let gas = checked!(len * self.access_gas_per_byte)?;
             Ok((bytes, gas.into()))
         })
     }
@@ -376,7 +383,7 @@ impl VmMemory for WasmMemory {
             // only writing to memory and we already charge gas for
             // every memory page allocated
             let len = bytes.as_ref().len() as u64;
-            let gas = checked!(len * MEMORY_ACCESS_GAS_PER_BYTE)?;
+            let gas = checked!(len * self.access_gas_per_byte)?;
             let store = self
                 .store
                 .upgrade()
@@ -203,7 +203,10 @@ where
     let sentinel = RefCell::new(TxSentinel::default());
     let (write_log, in_mem, db) = state.split_borrow();
     let mut env = TxVmEnv::new(
-        WasmMemory::new(Rc::downgrade(&store)),
+        WasmMemory::new(
+            Rc::downgrade(&store),
+            gas_meter.borrow().gas_costs().memory_access_per_byte,
+        ),
         write_log,
         in_mem,
         db,
@@ -343,7 +346,10 @@ where
         };
     let BatchedTxRef { tx, cmt } = batched_tx;
     let mut env = VpVmEnv::new(
-        WasmMemory::new(Rc::downgrade(&store)),
+        WasmMemory::new(
+            Rc::downgrade(&store),
+            gas_meter.borrow().gas_costs().memory_access_per_byte,
+        ),
         address,
         state.write_log(),
         state.in_mem(),
@@ -530,7 +536,7 @@ where
 impl<'a, S, CA> namada_vp::native_vp::VpEvaluator<'a, S, VpCache<CA>, Self>
     for VpEvalWasm<<S as StateRead>::D, <S as StateRead>::H, CA>
 where
-    S: 'static + StateRead,
+    S: 'a + StateRead,
     CA: WasmCacheAccess,
 {
     fn eval(
@@ -635,7 +641,10 @@ where
         let store = Rc::new(RefCell::new(store));
 
         let mut env = VpVmEnv {
-            memory: WasmMemory::new(Rc::downgrade(&store)),
+            memory: WasmMemory::new(
+                Rc::downgrade(&store),
+                gas_meter.borrow().gas_costs().memory_access_per_byte,
+            ),
             ctx,
         };
         let yielded_value_borrow = env.ctx.yielded_value;
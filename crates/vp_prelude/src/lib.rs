@@ -81,15 +81,17 @@ pub fn is_proposal_accepted(ctx: &Ctx, proposal_id: u64) -> VpEnvResult<bool> {
     ctx.has_key_pre(&proposal_execution_key).into_vp_error()
 }
 
-/// Verify section signatures
+/// Verify section signatures against the given threshold
 #[cold]
 #[inline(never)]
-fn verify_signatures(ctx: &Ctx, tx: &Tx, owner: &Address) -> VpResult {
+fn verify_signatures(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    threshold: u8,
+) -> VpResult {
     let public_keys_index_map =
         account::public_keys_index_map(&ctx.pre(), owner).into_vp_error()?;
-    let threshold = account::threshold(&ctx.pre(), owner)
-        .into_vp_error()?
-        .unwrap_or(1);
 
     // Serialize parameters
     let public_keys_map = public_keys_index_map.serialize_to_vec();
@@ -114,18 +116,19 @@ fn verify_signatures(ctx: &Ctx, tx: &Tx, owner: &Address) -> VpResult {
 #[derive(Default)]
 #[repr(transparent)]
 pub struct VerifySigGadget {
-    has_validated_sig: bool,
+    validated_threshold: Option<u8>,
 }
 
 impl VerifySigGadget {
     /// Create a new [`VerifySigGadget`].
     pub const fn new() -> Self {
         Self {
-            has_validated_sig: false,
+            validated_threshold: None,
         }
     }
 
-    /// Verify a tx signature, only paying the cost of this operation once.
+    /// Verify a tx signature against the account's default threshold, only
+    /// paying the cost of this operation once.
     #[inline(always)]
     pub fn verify_signatures(
         &mut self,
@@ -133,11 +136,27 @@ impl VerifySigGadget {
         tx_data: &Tx,
         owner: &Address,
     ) -> VpResult {
-        if !self.has_validated_sig {
-            verify_signatures(ctx, tx_data, owner)?;
-            self.has_validated_sig = true;
-        }
-        Ok(())
+        let threshold = account::threshold(&ctx.pre(), owner)
+            .into_vp_error()?
+            .unwrap_or(1);
+        self.verify_threshold(ctx, tx_data, owner, threshold)
+    }
+
+    /// Verify a tx signature against the account's threshold for the given
+    /// class of actions. Signatures are only verified again if the threshold
+    /// is higher than the ones already validated.
+    #[inline(always)]
+    pub fn verify_signatures_for(
+        &mut self,
+        class: account::ActionClass,
+        ctx: &Ctx,
+        tx_data: &Tx,
+        owner: &Address,
+    ) -> VpResult {
+        let threshold = account::threshold_for_action(&ctx.pre(), owner, class)
+            .into_vp_error()?
+            .unwrap_or(1);
+        self.verify_threshold(ctx, tx_data, owner, threshold)
     }
 
     /// Identical to [`Self::verify_signatures`], but execute a predicate before
@@ -156,6 +175,42 @@ impl VerifySigGadget {
         }
         Ok(())
     }
+
+    /// Identical to [`Self::verify_signatures_for`], but execute a predicate
+    /// before validating a sig. If the predicate returns false, we do not
+    /// check tx signatures.
+    #[inline(always)]
+    pub fn verify_signatures_for_when<F: FnOnce() -> bool>(
+        &mut self,
+        predicate: F,
+        class: account::ActionClass,
+        ctx: &Ctx,
+        tx_data: &Tx,
+        owner: &Address,
+    ) -> VpResult {
+        if predicate() {
+            self.verify_signatures_for(class, ctx, tx_data, owner)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn verify_threshold(
+        &mut self,
+        ctx: &Ctx,
+        tx_data: &Tx,
+        owner: &Address,
+        threshold: u8,
+    ) -> VpResult {
+        if self
+            .validated_threshold
+            .map_or(true, |validated| validated < threshold)
+        {
+            verify_signatures(ctx, tx_data, owner, threshold)?;
+            self.validated_threshold = Some(threshold);
+        }
+        Ok(())
+    }
 }
 
 /// Format and log a string in a debug build.
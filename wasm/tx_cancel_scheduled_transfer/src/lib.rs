@@ -0,0 +1,18 @@
+//! A tx to cancel a scheduled transfer.
+//! This tx uses `token::CancelScheduledTransfer` as its input.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let cancel = token::CancelScheduledTransfer::try_from_slice(&data[..])
+        .wrap_err("Failed to decode a CancelScheduledTransfer tx data")?;
+    debug_log!(
+        "apply_tx called to cancel a scheduled transfer: {:#?}",
+        cancel
+    );
+
+    token::cancel_scheduled_transfer(ctx, cancel)
+        .wrap_err("Failed to cancel the scheduled transfer")
+}
@@ -0,0 +1,28 @@
+//! A tx to delegate the governance vote of an account to another address, or
+//! to revoke the delegation.
+//! This tx uses `governance::DelegateVoteData` as its input.
+
+use namada_tx_prelude::action::{Action, GovAction, Write};
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let governance::DelegateVoteData {
+        delegator,
+        delegate,
+    } = governance::DelegateVoteData::try_from_slice(&data[..])
+        .wrap_err("Failed to decode DelegateVoteData value")?;
+
+    // The tx must be authorized by the delegator address
+    ctx.insert_verifier(&delegator)?;
+
+    ctx.push_action(Action::Gov(GovAction::DelegateVote {
+        delegator: delegator.clone(),
+    }))?;
+
+    debug_log!("apply_tx called to delegate the vote of {delegator}");
+
+    governance::delegate_vote(ctx, &delegator, delegate)
+        .wrap_err("Failed to delegate the governance vote")
+}
@@ -0,0 +1,38 @@
+//! A tx to lock native tokens in the governance account for lock-weighted
+//! voting.
+//! This tx uses `governance::LockVotingTokensData` as its input.
+
+use namada_tx_prelude::action::{Action, GovAction, Write};
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let governance::LockVotingTokensData {
+        owner,
+        amount,
+        lock_epochs,
+    } = governance::LockVotingTokensData::try_from_slice(&data[..])
+        .wrap_err("Failed to decode LockVotingTokensData value")?;
+
+    // The tx must be authorized by the owner address
+    ctx.insert_verifier(&owner)?;
+
+    ctx.push_action(Action::Gov(GovAction::LockVotingTokens {
+        owner: owner.clone(),
+    }))?;
+
+    debug_log!(
+        "apply_tx called to lock {} voting tokens of {owner} for \
+         {lock_epochs} epochs",
+        amount.to_string_native()
+    );
+
+    governance::lock_voting_tokens::<_, token::Store<_>>(
+        ctx,
+        &owner,
+        amount,
+        lock_epochs,
+    )
+    .wrap_err("Failed to lock the voting tokens")
+}
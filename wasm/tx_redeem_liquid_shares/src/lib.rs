@@ -0,0 +1,17 @@
+//! A tx to redeem liquid staking shares back into bonded tokens of the owner.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let transaction::pos::RedeemLiquidShares {
+        share_token,
+        owner,
+        amount,
+    } = transaction::pos::RedeemLiquidShares::try_from_slice(&data[..])
+        .wrap_err("Failed to decode a RedeemLiquidShares tx data")?;
+    ctx.redeem_liquid_shares(&owner, &share_token, amount)
+        .wrap_err("Failed to redeem liquid staking shares")?;
+    Ok(())
+}
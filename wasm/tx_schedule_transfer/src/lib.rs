@@ -0,0 +1,18 @@
+//! A tx to schedule a transfer executed by the protocol at a regular interval
+//! of epochs.
+//! This tx uses `token::ScheduleTransfer` as its input.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let transfer = token::ScheduleTransfer::try_from_slice(&data[..])
+        .wrap_err("Failed to decode a ScheduleTransfer tx data")?;
+    debug_log!("apply_tx called with scheduled transfer: {:#?}", transfer);
+
+    let id = token::schedule_transfer(ctx, transfer)
+        .wrap_err("Failed to schedule the transfer")?;
+    debug_log!("Scheduled transfer id: {}", id);
+    Ok(())
+}
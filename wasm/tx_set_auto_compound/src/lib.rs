@@ -0,0 +1,17 @@
+//! A tx to enable or disable the automatic compounding of PoS rewards of a
+//! bond.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let transaction::pos::AutoCompound {
+        validator,
+        source,
+        enabled,
+    } = transaction::pos::AutoCompound::try_from_slice(&data[..])
+        .wrap_err("Failed to decode an AutoCompound tx data")?;
+    ctx.set_auto_compound(source.as_ref(), &validator, enabled)
+        .wrap_err("Failed to set auto-compounding of rewards")
+}
@@ -0,0 +1,16 @@
+//! A tx to pre-authorize a budget of the sponsor to pay the fees of wrapper
+//! txs.
+//! This tx uses `token::SponsorFees` as its input.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let sponsorship = token::SponsorFees::try_from_slice(&data[..])
+        .wrap_err("Failed to decode a SponsorFees tx data")?;
+    debug_log!("apply_tx called with fee sponsorship: {:#?}", sponsorship);
+
+    token::sponsor_fees(ctx, sponsorship)
+        .wrap_err("Failed to write the fee sponsorship")
+}
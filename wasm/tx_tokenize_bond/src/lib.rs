@@ -0,0 +1,18 @@
+//! A tx for a delegator (non-validator bond owner) to tokenize bonded tokens
+//! into liquid staking shares of the validator.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let transaction::pos::TokenizeBond {
+        validator,
+        owner,
+        amount,
+    } = transaction::pos::TokenizeBond::try_from_slice(&data[..])
+        .wrap_err("Failed to decode a TokenizeBond tx data")?;
+    ctx.tokenize_bond(&owner, &validator, amount)
+        .wrap_err("Failed to tokenize bond")?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! A tx to return the expired locked voting tokens to their owner.
+//! This tx uses `Address` of the owner as its input.
+
+use namada_tx_prelude::action::{Action, GovAction, Write};
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: BatchedTx) -> TxResult {
+    let data = ctx.get_tx_data(&tx_data)?;
+    let owner = Address::try_from_slice(&data[..]).wrap_err(
+        "Failed to decode the address of the owner of the voting tokens",
+    )?;
+
+    // The tx must be authorized by the owner address
+    ctx.insert_verifier(&owner)?;
+
+    ctx.push_action(Action::Gov(GovAction::UnlockVotingTokens {
+        owner: owner.clone(),
+    }))?;
+
+    let amount =
+        governance::unlock_voting_tokens::<_, token::Store<_>>(ctx, &owner)
+            .wrap_err("Failed to unlock the voting tokens")?;
+    debug_log!(
+        "Unlocked {} voting tokens of {owner}",
+        amount.to_string_native()
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! A tx for updating an account's validity predicate.
 //! This tx uses `account::UpdateAccount` as its input.
 
+use booleans::ResultBoolExt;
 use namada_tx_prelude::*;
 
 #[transaction]
@@ -54,5 +55,44 @@ fn apply_tx(ctx: &mut Ctx, batched_tx: BatchedTx) -> TxResult {
         }
     }
 
+    // The weights are indexed like the public keys, so the old ones must not
+    // carry over to a new set of keys
+    if !tx_data.public_keys.is_empty() || !tx_data.weights.is_empty() {
+        account::clear_weights(ctx, owner)
+            .wrap_err("Failed to reset the account's public key weights")?;
+        for (index, weight) in tx_data.weights.iter().enumerate() {
+            let index = index as u8;
+            account::set_public_key_weight(ctx, owner, index, *weight)
+                .wrap_err(
+                    "Failed to update the account's public key weights",
+                )?;
+        }
+    }
+
+    if !tx_data.action_thresholds.is_empty() {
+        let mut action_thresholds = account::action_thresholds(ctx, owner)
+            .wrap_err("Failed to read the account's action thresholds")?;
+        for (class, threshold) in tx_data.action_thresholds {
+            if threshold == 0 {
+                action_thresholds.remove(&class);
+            } else {
+                action_thresholds.insert(class, threshold);
+            }
+        }
+        account::write_action_thresholds(ctx, owner, &action_thresholds)
+            .wrap_err("Failed to update the account's action thresholds")?;
+    }
+
+    // The updated keys must still be able to authorize the account's actions
+    account::is_auth_valid(ctx, owner)
+        .wrap_err("Failed to read the account's public keys and thresholds")
+        .true_or_else(|| {
+            const ERR_MSG: &str = "The account's public key weights must be \
+                                   nonzero, one per key, and add up to each \
+                                   of its thresholds";
+            debug_log!("{ERR_MSG}");
+            Error::new_const(ERR_MSG)
+        })?;
+
     Ok(())
 }
@@ -65,6 +65,13 @@ fn validate_tx(
                 | PosAction::ConsensusKeyChange(source)
                 | PosAction::Redelegation(Redelegation {
                     owner: source, ..
+                })
+                | PosAction::TokenizeBond(TokenizeBond {
+                    owner: source, ..
+                })
+                | PosAction::RedeemLiquidShares(RedeemLiquidShares {
+                    owner: source,
+                    ..
                 }) => gadget.verify_signatures_when(
                     || source == addr,
                     ctx,
@@ -78,6 +85,11 @@ fn validate_tx(
                     source, validator, ..
                 })
                 | PosAction::Withdraw(Withdraw { source, validator })
+                | PosAction::AutoCompound(AutoCompound {
+                    source,
+                    validator,
+                    ..
+                })
                 | PosAction::ClaimRewards(ClaimRewards { validator, source }) =>
                 {
                     let source = source.unwrap_or(validator);
@@ -91,7 +103,10 @@ fn validate_tx(
             },
             Action::Gov(
                 GovAction::InitProposal { author: source }
-                | GovAction::VoteProposal { voter: source, .. },
+                | GovAction::VoteProposal { voter: source, .. }
+                | GovAction::DelegateVote { delegator: source }
+                | GovAction::LockVotingTokens { owner: source }
+                | GovAction::UnlockVotingTokens { owner: source },
             )
             | Action::Pgf(
                 PgfAction::ResignSteward(source)
@@ -105,7 +120,7 @@ fn validate_tx(
             Action::Masp(MaspAction::MaspAuthorizer(source)) => gadget
                 .verify_signatures_when(|| source == addr, ctx, &tx, &addr)?,
             Action::Masp(MaspAction::MaspSectionRef(_)) => (),
-            Action::IbcShielding => (),
+            Action::IbcShielding | Action::IbcHook(_) => (),
         }
     }
 
@@ -195,6 +210,15 @@ fn validate_tx(
                 &tx,
                 &addr,
             ),
+            // The vesting schedule can only be changed by governance, which
+            // has been allowed above
+            KeyType::Vesting(owner) => (owner != &addr).ok_or_else(|| {
+                VpError::Erased(
+                    "The vesting schedule of this account cannot be changed \
+                     by a transaction"
+                        .into(),
+                )
+            }),
             KeyType::Masp | KeyType::Ibc => Ok(()),
             KeyType::Unknown => {
                 // Unknown changes require a valid signature
@@ -217,6 +241,7 @@ enum KeyType<'a> {
     },
     TokenMinted,
     TokenMinter(&'a Address),
+    Vesting(&'a Address),
     Masp,
     Ibc,
     Unknown,
@@ -235,6 +260,10 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
         } else if let Some(minter) = token::storage_key::is_any_minter_key(key)
         {
             Self::TokenMinter(minter)
+        } else if let Some([_, owner]) =
+            token::storage_key::is_any_vesting_key(key)
+        {
+            Self::Vesting(owner)
         } else if token::storage_key::is_masp_key(key) {
             Self::Masp
         } else if ibc::is_ibc_key(key) {
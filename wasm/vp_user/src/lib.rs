@@ -10,8 +10,12 @@
 //! requires a valid signature(s) only from the validator.
 //!
 //! Any other storage key changes are allowed only with a valid signature.
+//!
+//! The signature threshold depends on the class of the action being authorized
+//! (see [`ActionClass`]), falling back to the account's default threshold.
 
 use booleans::BoolResultUnitExt;
+use namada_vp_prelude::account::ActionClass;
 use namada_vp_prelude::tx::action::*;
 use namada_vp_prelude::*;
 
@@ -51,7 +55,7 @@ fn validate_tx(
     let actions = ctx.read_actions().into_vp_error()?;
 
     // Require authorization by signature when the source of an action is this
-    // VP's address
+    // VP's address, using the threshold of the action's class
     for action in actions {
         match action {
             Action::Pos(pos_action) => match pos_action {
@@ -61,11 +65,26 @@ fn validate_tx(
                 | PosAction::Unjail(source)
                 | PosAction::CommissionChange(source)
                 | PosAction::MetadataChange(source)
-                | PosAction::ConsensusKeyChange(source)
-                | PosAction::Redelegation(Redelegation {
+                | PosAction::ConsensusKeyChange(source) => gadget
+                    .verify_signatures_for_when(
+                        || source == addr,
+                        ActionClass::Validator,
+                        ctx,
+                        &tx,
+                        &addr,
+                    )?,
+                PosAction::Redelegation(Redelegation {
+                    owner: source, ..
+                })
+                | PosAction::TokenizeBond(TokenizeBond {
                     owner: source, ..
-                }) => gadget.verify_signatures_when(
+                })
+                | PosAction::RedeemLiquidShares(RedeemLiquidShares {
+                    owner: source,
+                    ..
+                }) => gadget.verify_signatures_for_when(
                     || source == addr,
+                    ActionClass::Staking,
                     ctx,
                     &tx,
                     &addr,
@@ -77,11 +96,25 @@ fn validate_tx(
                     source, validator, ..
                 })
                 | PosAction::Withdraw(Withdraw { source, validator })
-                | PosAction::ClaimRewards(ClaimRewards { validator, source }) =>
-                {
+                | PosAction::AutoCompound(AutoCompound {
+                    source,
+                    validator,
+                    ..
+                }) => {
                     let source = source.unwrap_or(validator);
-                    gadget.verify_signatures_when(
+                    gadget.verify_signatures_for_when(
                         || source == addr,
+                        ActionClass::Staking,
+                        ctx,
+                        &tx,
+                        &addr,
+                    )?
+                }
+                PosAction::ClaimRewards(ClaimRewards { validator, source }) => {
+                    let source = source.unwrap_or(validator);
+                    gadget.verify_signatures_for_when(
+                        || source == addr,
+                        ActionClass::ClaimRewards,
                         ctx,
                         &tx,
                         &addr,
@@ -90,21 +123,31 @@ fn validate_tx(
             },
             Action::Gov(
                 GovAction::InitProposal { author: source }
-                | GovAction::VoteProposal { voter: source, .. },
+                | GovAction::VoteProposal { voter: source, .. }
+                | GovAction::DelegateVote { delegator: source }
+                | GovAction::LockVotingTokens { owner: source }
+                | GovAction::UnlockVotingTokens { owner: source },
             )
             | Action::Pgf(
                 PgfAction::ResignSteward(source)
                 | PgfAction::UpdateStewardCommission(source),
-            ) => gadget.verify_signatures_when(
+            ) => gadget.verify_signatures_for_when(
                 || source == addr,
+                ActionClass::Governance,
                 ctx,
                 &tx,
                 &addr,
             )?,
             Action::Masp(MaspAction::MaspAuthorizer(source)) => gadget
-                .verify_signatures_when(|| source == addr, ctx, &tx, &addr)?,
+                .verify_signatures_for_when(
+                    || source == addr,
+                    ActionClass::Transfer,
+                    ctx,
+                    &tx,
+                    &addr,
+                )?,
             Action::Masp(MaspAction::MaspSectionRef(_)) => (),
-            Action::IbcShielding => (),
+            Action::IbcShielding | Action::IbcHook(_) => (),
         }
     }
 
@@ -119,9 +162,10 @@ fn validate_tx(
                         ctx.read_post(key).into_vp_error()?.unwrap_or_default();
                     let change =
                         post.change().checked_sub(pre.change()).unwrap();
-                    gadget.verify_signatures_when(
+                    gadget.verify_signatures_for_when(
                         // NB: debit has to signed, credit doesn't
                         || change.is_negative(),
+                        ActionClass::Transfer,
                         ctx,
                         &tx,
                         &addr,
@@ -158,14 +202,64 @@ fn validate_tx(
             KeyType::Vp(owner) => {
                 let vp_overwritten: bool =
                     ctx.has_key_post(key).into_vp_error()?;
-                gadget.verify_signatures_when(
+                gadget.verify_signatures_for_when(
                     || owner == &addr && vp_overwritten,
+                    ActionClass::UpdateAccount,
                     ctx,
                     &tx,
                     &addr,
                 )
             }
+            KeyType::AccountAuth(owner) => {
+                if owner == &addr {
+                    gadget.verify_signatures_for(
+                        ActionClass::UpdateAccount,
+                        ctx,
+                        &tx,
+                        &addr,
+                    )?;
+                    // The updated keys must still be able to authorize the
+                    // account's actions
+                    account::is_auth_valid(&ctx.post(), &addr)
+                        .into_vp_error()?
+                        .ok_or_else(|| {
+                            VpError::Erased(
+                                "The account's public key weights must be \
+                                 nonzero, one per key, and add up to each of \
+                                 its thresholds"
+                                    .into(),
+                            )
+                        })
+                } else {
+                    Ok(())
+                }
+            }
+            // The vesting schedule can only be changed by governance, which
+            // has been allowed above
+            KeyType::Vesting(owner) => (owner != &addr).ok_or_else(|| {
+                VpError::Erased(
+                    "The vesting schedule of this account cannot be changed \
+                     by a transaction"
+                        .into(),
+                )
+            }),
             KeyType::Masp | KeyType::Ibc => Ok(()),
+            // Changes to the PoS and governance storage of this address must
+            // be authorized with the threshold of their class of actions
+            KeyType::PoS => gadget.verify_signatures_for_when(
+                || key.iter_addresses().any(|owner| owner == &addr),
+                ActionClass::Staking,
+                ctx,
+                &tx,
+                &addr,
+            ),
+            KeyType::Governance => gadget.verify_signatures_for_when(
+                || key.iter_addresses().any(|owner| owner == &addr),
+                ActionClass::Governance,
+                ctx,
+                &tx,
+                &addr,
+            ),
             KeyType::Unknown => {
                 // Unknown changes require a valid signature
                 gadget.verify_signatures(ctx, &tx, &addr)
@@ -183,9 +277,13 @@ enum KeyType<'a> {
     TokenBalance { owner: &'a Address },
     TokenMinted,
     TokenMinter(&'a Address),
+    Vesting(&'a Address),
     Vp(&'a Address),
+    AccountAuth(&'a Address),
     Masp,
     Ibc,
+    PoS,
+    Governance,
     Unknown,
 }
 
@@ -200,12 +298,24 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
         } else if let Some(minter) = token::storage_key::is_any_minter_key(key)
         {
             Self::TokenMinter(minter)
+        } else if let Some([_, owner]) =
+            token::storage_key::is_any_vesting_key(key)
+        {
+            Self::Vesting(owner)
         } else if let Some(address) = key.is_validity_predicate() {
             Self::Vp(address)
+        } else if let Some(address) = account::is_account_auth_key(key) {
+            Self::AccountAuth(address)
         } else if token::storage_key::is_masp_key(key) {
             Self::Masp
         } else if ibc::is_ibc_key(key) {
             Self::Ibc
+        } else if proof_of_stake::storage_key::is_pos_key(key) {
+            Self::PoS
+        } else if gov_storage::keys::is_governance_key(key)
+            || pgf_storage::keys::is_pgf_key(key)
+        {
+            Self::Governance
         } else {
             Self::Unknown
         }
@@ -214,6 +324,7 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::panic;
 
     use address::testing::arb_non_internal_address;
@@ -469,6 +580,222 @@ mod tests {
         );
     }
 
+    /// Run a debit transfer from a 2-of-2 account whose first key has the given
+    /// weight, signed with the first key only.
+    fn validate_weighted_debit_transfer(
+        signer_weight: u8,
+        action_thresholds: BTreeMap<ActionClass, u8>,
+    ) -> std::thread::Result<VpResult> {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let other_public_key = key::testing::keypair_2().ref_to();
+        let target = address::testing::established_address_2();
+        let token = address::testing::nam();
+        let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(
+            &vp_owner,
+            vec![public_key.clone(), other_public_key.clone()],
+            2,
+        );
+        account::set_public_key_weight(
+            &mut tx_env.state,
+            &vp_owner,
+            0,
+            signer_weight,
+        )
+        .unwrap();
+        account::write_action_thresholds(
+            &mut tx_env.state,
+            &vp_owner,
+            &action_thresholds,
+        )
+        .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, amount);
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.state,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                amount,
+            )
+            .unwrap();
+        });
+
+        let pks_map =
+            AccountPublicKeysMap::from_iter(vec![public_key, other_public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.batched_tx.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Authorization(Authorization::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.batch_first_tx();
+        vp_env.batched_tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        panic::catch_unwind(|| {
+            validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
+        })
+    }
+
+    /// Test that a debit transfer signed by a single key whose weight meets
+    /// the threshold is accepted.
+    #[test]
+    fn test_weighted_signed_debit_transfer_accepted() {
+        let result = validate_weighted_debit_transfer(2, BTreeMap::new());
+        assert!(result.unwrap().is_ok());
+    }
+
+    /// Test that a debit transfer is rejected when the signatures' weight is
+    /// below the default threshold.
+    #[test]
+    fn test_under_weighted_signed_debit_transfer_rejected() {
+        let result = validate_weighted_debit_transfer(1, BTreeMap::new());
+        assert!(
+            result
+                .err()
+                .map(|a| a.downcast_ref::<String>().cloned().unwrap())
+                .unwrap()
+                .contains("InvalidSectionSignature")
+        );
+    }
+
+    /// Test that a debit transfer is rejected when the signatures' weight
+    /// meets the default threshold, but not the transfer threshold.
+    #[test]
+    fn test_weighted_signed_debit_transfer_below_action_threshold_rejected() {
+        let result = validate_weighted_debit_transfer(
+            2,
+            BTreeMap::from([(ActionClass::Transfer, 3)]),
+        );
+        assert!(
+            result
+                .err()
+                .map(|a| a.downcast_ref::<String>().cloned().unwrap())
+                .unwrap()
+                .contains("InvalidSectionSignature")
+        );
+    }
+
+    /// Test that a signed update of the account's public key weights that
+    /// leaves it unable to reach its threshold is rejected.
+    #[test]
+    fn test_signed_zero_weight_update_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Zero the weight of the account's only key
+            account::set_public_key_weight(tx::ctx(), address, 0, 0).unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.batched_tx.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Authorization(Authorization::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.batch_first_tx();
+        vp_env.batched_tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
+                .is_err()
+        );
+    }
+
+    /// Test that a write to the governance storage of the account without a
+    /// valid signature is rejected.
+    #[test]
+    fn test_unsigned_gov_key_write_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let public_key = key::testing::keypair_1().ref_to();
+        let delegate = address::testing::established_address_2();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &delegate]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key], 1);
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Delegate the account's vote by writing directly into the
+            // governance storage
+            let delegate_key =
+                gov_storage::keys::get_vote_delegate_key(address);
+            tx::ctx().write(&delegate_key, &delegate).unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        let mut tx_data = Tx::from_type(TxType::Raw);
+        tx_data.set_data(Data::new(vec![]));
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            panic::catch_unwind(|| {
+                validate_tx(
+                    &CTX,
+                    tx_data.batch_first_tx(),
+                    vp_owner,
+                    keys_changed,
+                    verifiers,
+                )
+            })
+            .err()
+            .map(|a| a.downcast_ref::<String>().cloned().unwrap())
+            .unwrap()
+            .contains("InvalidSectionSignature")
+        );
+    }
+
     /// Test that a non-validator PoS action that must be authorized is rejected
     /// without a valid signature.
     #[test]